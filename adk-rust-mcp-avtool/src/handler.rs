@@ -6,13 +6,19 @@
 use adk_rust_mcp_common::auth::AuthProvider;
 use adk_rust_mcp_common::config::Config;
 use adk_rust_mcp_common::error::Error;
+use adk_rust_mcp_common::ffmpeg::{record_exit_code, run_ffmpeg_with_stdin};
 use adk_rust_mcp_common::gcs::{GcsClient, GcsUri};
+use adk_rust_mcp_common::retry::{RetryPolicy, retry_with_backoff};
+use adk_rust_mcp_common::tracing::SpanTimer;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::process::Command;
-use tracing::{debug, info, instrument};
+use tracing::{debug, field, info, instrument, warn};
 use uuid::Uuid;
 
 // =============================================================================
@@ -28,10 +34,183 @@ pub const DEFAULT_GIF_FPS: u8 = 10;
 /// Default volume multiplier.
 pub const DEFAULT_VOLUME: f32 = 1.0;
 
+/// Maximum silence between clips when concatenating with gaps (seconds).
+pub const MAX_GAP_SECONDS: f64 = 60.0;
+
+/// Video encoders accepted by the transcode tool.
+pub const SUPPORTED_VIDEO_CODECS: &[&str] = &[
+    "libx264", "libx265", "libvpx", "libvpx-vp9", "libaom-av1", "mpeg4", "prores_ks", "gif", "copy",
+];
+
+/// Audio encoders accepted by the transcode tool.
+pub const SUPPORTED_AUDIO_CODECS: &[&str] = &[
+    "aac", "libmp3lame", "libopus", "libvorbis", "flac", "pcm_s16le", "copy",
+];
+
+/// Input extensions ffmpeg can read from a pipe without seeking. GCS inputs
+/// in these containers are streamed into ffmpeg's stdin by the transcode tool
+/// instead of being downloaded first; MP4/MOV are absent because their index
+/// may sit at the end of the file.
+pub const STREAMABLE_INPUT_EXTENSIONS: &[&str] = &[
+    "mp3", "wav", "flac", "ogg", "opus", "aac", "webm", "mkv", "ts", "mpg", "mpeg",
+];
+
+/// Encoder presets accepted by the transcode tool (x264/x265 naming).
+pub const SUPPORTED_PRESETS: &[&str] = &[
+    "ultrafast", "superfast", "veryfast", "faster", "fast", "medium", "slow", "slower", "veryslow",
+];
+
+/// Default number of contact sheet columns.
+pub const DEFAULT_CONTACT_SHEET_COLUMNS: u32 = 4;
+
+/// Default number of contact sheet rows.
+pub const DEFAULT_CONTACT_SHEET_ROWS: u32 = 4;
+
+/// Default width of each contact sheet thumbnail in pixels.
+pub const DEFAULT_CONTACT_SHEET_WIDTH: u32 = 320;
+
+/// Maximum number of contact sheet columns or rows.
+pub const MAX_CONTACT_SHEET_GRID: u32 = 20;
+
+/// Valid picture-in-picture overlay positions.
+pub const VALID_PIP_POSITIONS: &[&str] = &["top_left", "top_right", "bottom_left", "bottom_right", "center"];
+
+/// Valid picture-in-picture audio modes.
+pub const VALID_PIP_AUDIO_MODES: &[&str] = &["main", "mix"];
+
+/// Default picture-in-picture overlay width as a fraction of the main video width.
+pub const DEFAULT_PIP_SCALE: f32 = 0.25;
+
+/// Default picture-in-picture overlay position.
+pub const DEFAULT_PIP_POSITION: &str = "bottom_right";
+
+/// Default picture-in-picture margin from the frame edges in pixels.
+pub const DEFAULT_PIP_MARGIN: u32 = 20;
+
+/// Default picture-in-picture audio mode.
+pub const DEFAULT_PIP_AUDIO_MODE: &str = "main";
+
+/// Default maximum input duration in seconds for reversing.
+///
+/// The `reverse`/`areverse` filters buffer the entire stream in memory,
+/// so long inputs are rejected up front.
+pub const DEFAULT_REVERSE_MAX_DURATION: f64 = 30.0;
+
+/// Environment variable overriding [`DEFAULT_REVERSE_MAX_DURATION`].
+pub use adk_rust_mcp_common::config::REVERSE_MAX_DURATION_ENV;
+
+/// Valid scene detection modes.
+pub const VALID_DETECT_MODES: &[&str] = &["scene", "black"];
+
+/// Default scene detection mode.
+pub const DEFAULT_DETECT_MODE: &str = "scene";
+
+/// Default minimum scene change score (0-1) for `scene` mode.
+pub const DEFAULT_SCENE_THRESHOLD: f64 = 0.4;
+
+/// Default minimum fraction of black pixels (0-1) for a frame to count as black.
+pub const DEFAULT_BLACK_PICTURE_THRESHOLD: f64 = 0.98;
+
+/// Default minimum black segment duration in seconds.
+pub const DEFAULT_BLACK_MIN_DURATION: f64 = 0.5;
+
+/// Default color of the waveform drawn by `image_to_video`.
+pub const DEFAULT_WAVEFORM_COLOR: &str = "white";
+
+/// Default foreground level (0-1) above which the background is ducked.
+pub const DEFAULT_DUCK_THRESHOLD: f32 = 0.05;
+
+/// Default compression ratio applied to the background while ducked.
+pub const DEFAULT_DUCK_RATIO: f32 = 8.0;
+
+/// Default time in milliseconds for the background to duck once the foreground starts.
+pub const DEFAULT_DUCK_ATTACK_MS: f32 = 20.0;
+
+/// Default time in milliseconds for the background to recover once the foreground stops.
+pub const DEFAULT_DUCK_RELEASE_MS: f32 = 300.0;
+
+/// Threshold range accepted by ffmpeg's `sidechaincompress` (-60 dB to 0 dB).
+pub const DUCK_THRESHOLD_RANGE: (f32, f32) = (0.001, 1.0);
+
+/// Ratio range accepted by ffmpeg's `sidechaincompress`.
+pub const DUCK_RATIO_RANGE: (f32, f32) = (1.0, 20.0);
+
+/// Attack range in milliseconds accepted by ffmpeg's `sidechaincompress`.
+pub const DUCK_ATTACK_RANGE_MS: (f32, f32) = (0.01, 2000.0);
+
+/// Release range in milliseconds accepted by ffmpeg's `sidechaincompress`.
+pub const DUCK_RELEASE_RANGE_MS: (f32, f32) = (0.01, 9000.0);
+
+/// Options `ffmpeg_run` accepts that take a value, each checked by kind.
+pub const FFMPEG_RUN_VALUE_OPTIONS: &[&str] = &[
+    "-vf", "-af", "-filter:v", "-filter:a", "-filter_complex", "-c", "-c:v", "-c:a", "-codec:v",
+    "-codec:a", "-b:v", "-b:a", "-maxrate", "-bufsize", "-crf", "-q:v", "-q:a", "-r", "-ar", "-ac",
+    "-frames:v", "-aspect", "-s", "-ss", "-t", "-to", "-map", "-preset", "-tune", "-profile:v",
+    "-pix_fmt", "-movflags",
+];
+
+/// Options `ffmpeg_run` accepts that take no value.
+pub const FFMPEG_RUN_FLAG_OPTIONS: &[&str] = &["-an", "-vn", "-sn", "-shortest"];
+
+/// Filters `ffmpeg_run` accepts in `-vf`, `-af` and `-filter_complex`.
+///
+/// Filters that read or write files (`movie`, `subtitles`, `drawtext`,
+/// `lut3d`, ...), load plugins (`frei0r`, `ladspa`) or take commands at
+/// runtime (`sendcmd`, `zmq`) are deliberately absent, as are source
+/// filters (`color`, `sine`, `anullsrc`, ...) that generate endless output
+/// and `reverse`/`areverse`, which buffer the whole input in memory (the
+/// `reverse` tool applies them under a duration cap). Padding filters such as `apad` stay, bounded by the `-t` cap from
+/// [`FfmpegRunParams::build_args`].
+pub const FFMPEG_RUN_FILTERS: &[&str] = &[
+    // Video
+    "scale", "crop", "pad", "fps", "format", "setsar", "setdar", "setpts", "transpose", "hflip",
+    "vflip", "rotate", "eq", "hue", "negate", "colorbalance", "colorchannelmixer", "lutrgb",
+    "lutyuv", "unsharp", "boxblur", "gblur", "hqdn3d", "nlmeans", "deband", "deflicker", "noise",
+    "vignette", "edgedetect", "chromakey", "colorkey", "fade", "xfade", "overlay", "hstack",
+    "vstack", "xstack", "tile", "thumbnail", "select", "trim", "tpad", "zoompan", "minterpolate",
+    "tblend", "drawbox", "drawgrid", "palettegen", "paletteuse", "split", "null",
+    // Audio
+    "volume", "atempo", "asetrate", "aresample", "aformat", "loudnorm", "dynaudnorm", "highpass",
+    "lowpass", "bandpass", "equalizer", "bass", "treble", "acompressor", "sidechaincompress",
+    "agate", "alimiter", "silenceremove", "afade", "acrossfade", "adelay", "apad", "atrim",
+    "asetpts", "aecho", "chorus", "flanger", "tremolo", "vibrato", "pan", "amix",
+    "amerge", "asplit", "aselect", "anull", "showwaves", "showspectrum",
+    // Both
+    "concat",
+];
+
+/// Longest output `ffmpeg_run` writes when `MCP_MAX_OUTPUT_DURATION` is unset.
+pub const FFMPEG_RUN_DEFAULT_MAX_DURATION_SECS: f64 = 4.0 * 3600.0;
+
+/// Filters in a `ffmpeg_run` `-vf` chain that can leave an odd frame size, so
+/// H.264/H.265 outputs get [`EVEN_DIMENSIONS_FILTER`] appended.
+pub const FFMPEG_RUN_RESIZING_FILTERS: &[&str] = &["scale", "crop", "pad", "rotate", "zoompan"];
+
 // =============================================================================
 // Output Types
 // =============================================================================
 
+/// Result of a tool that writes a media file.
+///
+/// Displays as the output path, so callers that only need the path can keep
+/// formatting it directly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputResult {
+    /// Final output location (local path or GCS URI).
+    pub output: String,
+    /// Size of the written file in bytes.
+    pub size_bytes: u64,
+    /// Media duration in seconds, when ffprobe reports one (absent for still images).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<f64>,
+}
+
+impl std::fmt::Display for OutputResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.output)
+    }
+}
+
 /// Media file information returned by ffprobe.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MediaInfo {
@@ -41,10 +220,86 @@ pub struct MediaInfo {
     pub format: String,
     /// List of streams in the file.
     pub streams: Vec<StreamInfo>,
+    /// Container tags (e.g., title, artist, creation_time).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, String>,
 }
 
-/// Information about a single stream in a media file.
+impl MediaInfo {
+    /// Parse `ffprobe -show_format -show_streams` JSON.
+    ///
+    /// # Errors
+    /// Returns `Error::Ffmpeg` if the output has no `format` object.
+    pub fn from_ffprobe(json: &serde_json::Value) -> Result<Self, Error> {
+        let format = json.get("format").ok_or_else(|| {
+            Error::ffmpeg("ffprobe output missing 'format' field")
+        })?;
+
+        let duration: f64 = format
+            .get("duration")
+            .and_then(|d| d.as_str())
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0.0);
+
+        let format_name = format
+            .get("format_name")
+            .and_then(|f| f.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let streams = json
+            .get("streams")
+            .and_then(|s| s.as_array())
+            .map(|arr| arr.iter().map(StreamInfo::from_ffprobe).collect())
+            .unwrap_or_default();
+
+        Ok(Self {
+            duration,
+            format: format_name,
+            streams,
+            tags: ffprobe_tags(format),
+        })
+    }
+}
+
+/// Collect the string values of an ffprobe `tags` object.
+fn ffprobe_tags(section: &serde_json::Value) -> HashMap<String, String> {
+    section
+        .get("tags")
+        .and_then(|t| t.as_object())
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Scene-change or black-frame detection result.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneDetectionResult {
+    /// Detection mode that produced the result (`scene` or `black`).
+    pub mode: String,
+    /// Detected timestamps in seconds: scene cuts, or the start of each black segment.
+    pub timestamps: Vec<f64>,
+    /// Black segments (only populated in `black` mode).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub black_segments: Vec<BlackSegment>,
+}
+
+/// A run of black frames reported by `blackdetect`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlackSegment {
+    /// Segment start in seconds.
+    pub start: f64,
+    /// Segment end in seconds.
+    pub end: f64,
+    /// Segment duration in seconds.
+    pub duration: f64,
+}
+
+/// Information about a single stream in a media file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StreamInfo {
     /// Stream index.
     pub index: u32,
@@ -64,6 +319,56 @@ pub struct StreamInfo {
     /// Number of audio channels (if audio stream).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub channels: Option<u32>,
+    /// Stream bitrate in bits per second, when the container records it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bit_rate: Option<u64>,
+    /// Codec profile (e.g., "High", "Main 10", "LC").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Pixel format (if video stream, e.g., "yuv420p").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pix_fmt: Option<String>,
+    /// Frame rate as a fraction (if video stream, e.g., "30000/1001").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r_frame_rate: Option<String>,
+    /// Stream duration in seconds, which can differ from the container's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+    /// Stream tags (e.g., language, title, handler_name).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, String>,
+}
+
+impl StreamInfo {
+    /// Parse one entry of the `streams` array from `ffprobe -show_streams` JSON.
+    ///
+    /// ffprobe reports numbers such as `bit_rate` as strings and uses
+    /// `unknown`, `N/A` or `0/0` when a value does not apply; those become `None`.
+    pub fn from_ffprobe(stream: &serde_json::Value) -> Self {
+        let str_field = |key: &str| {
+            stream
+                .get(key)
+                .and_then(|v| v.as_str())
+                .filter(|v| !matches!(*v, "" | "unknown" | "N/A" | "0/0"))
+        };
+        Self {
+            index: stream.get("index").and_then(|i| i.as_u64()).unwrap_or(0) as u32,
+            codec_type: str_field("codec_type").unwrap_or("unknown").to_string(),
+            codec_name: str_field("codec_name").unwrap_or("unknown").to_string(),
+            width: stream.get("width").and_then(|w| w.as_u64()).map(|w| w as u32),
+            height: stream.get("height").and_then(|h| h.as_u64()).map(|h| h as u32),
+            sample_rate: str_field("sample_rate").and_then(|s| s.parse().ok()),
+            channels: stream.get("channels").and_then(|c| c.as_u64()).map(|c| c as u32),
+            bit_rate: str_field("bit_rate").and_then(|s| s.parse().ok()),
+            profile: str_field("profile").map(str::to_string),
+            pix_fmt: str_field("pix_fmt").map(str::to_string),
+            r_frame_rate: str_field("r_frame_rate").map(str::to_string),
+            duration: str_field("duration")
+                .and_then(|s| s.parse::<f64>().ok())
+                .filter(|d| d.is_finite() && *d >= 0.0),
+            tags: ffprobe_tags(stream),
+        }
+    }
 }
 
 // =============================================================================
@@ -84,6 +389,10 @@ pub struct ConvertAudioParams {
     pub input: String,
     /// Output MP3 file path (local path or GCS URI).
     pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
     /// Audio bitrate (e.g., "128k", "192k", "320k"). Default: "192k".
     #[serde(default = "default_bitrate")]
     pub bitrate: String,
@@ -100,6 +409,10 @@ pub struct VideoToGifParams {
     pub input: String,
     /// Output GIF file path (local path or GCS URI).
     pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
     /// Frames per second for the GIF. Default: 10.
     #[serde(default = "default_fps")]
     pub fps: u8,
@@ -112,6 +425,12 @@ pub struct VideoToGifParams {
     /// Duration in seconds.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub duration: Option<f64>,
+    /// Number of times the GIF repeats: 0 loops forever (GIF default), -1 plays once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loop_count: Option<i32>,
+    /// Fade in from and out to black over this many seconds at the clip boundaries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fade_duration: Option<f64>,
 }
 
 fn default_fps() -> u8 {
@@ -127,6 +446,109 @@ pub struct CombineAvParams {
     pub audio_input: String,
     /// Output file path (local path or GCS URI).
     pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
+    /// Loop the video until the audio ends instead of cutting the audio at
+    /// the end of the video, e.g. a short clip under a long narration.
+    #[serde(default)]
+    pub loop_video: bool,
+    /// Which video stream of `video_input` to use, counting video streams
+    /// only (0 = first). Default: 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub video_stream_index: Option<u32>,
+    /// Which audio stream of `audio_input` to use, counting audio streams
+    /// only (0 = first). Default: 0.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_stream_index: Option<u32>,
+    /// Mix the new audio with the first audio stream of `video_input`
+    /// instead of replacing it, e.g. to add commentary. Default: false.
+    #[serde(default)]
+    pub mix_audio: bool,
+}
+
+impl CombineAvParams {
+    /// Check the requested streams against the stream counts ffprobe found:
+    /// video streams in `video_input`, audio streams in `video_input`, and
+    /// audio streams in `audio_input`.
+    pub fn check_streams(&self, video_streams: usize, video_audio_streams: usize, audio_streams: usize) -> Result<(), Error> {
+        let mut errors = Vec::new();
+
+        let video_index = self.video_stream_index.unwrap_or(0) as usize;
+        if video_index >= video_streams {
+            errors.push(format!(
+                "video_stream_index {} is out of range; video_input '{}' has {} video stream(s)",
+                video_index, self.video_input, video_streams
+            ));
+        }
+
+        let audio_index = self.audio_stream_index.unwrap_or(0) as usize;
+        if audio_index >= audio_streams {
+            errors.push(format!(
+                "audio_stream_index {} is out of range; audio_input '{}' has {} audio stream(s)",
+                audio_index, self.audio_input, audio_streams
+            ));
+        }
+
+        if self.mix_audio && video_audio_streams == 0 {
+            errors.push(format!(
+                "mix_audio requires an audio stream in video_input '{}', which has none",
+                self.video_input
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::validation(errors.join("; ")))
+        }
+    }
+
+    /// Build the ffmpeg arguments for muxing `audio` under `video` into `output`.
+    ///
+    /// The output ends with the shorter stream. With `loop_video` the video
+    /// input repeats indefinitely, so the audio always sets the length.
+    pub fn build_args(&self, video: &str, audio: &str, output: &str) -> Vec<String> {
+        let video_map = format!("0:v:{}", self.video_stream_index.unwrap_or(0));
+        let audio_index = self.audio_stream_index.unwrap_or(0);
+
+        let mut args: Vec<String> = Vec::new();
+        if self.loop_video {
+            args.extend(["-stream_loop", "-1"].map(String::from));
+        }
+        args.extend(["-i", video, "-i", audio].map(String::from));
+        args.extend(["-c:v", "copy", "-c:a", "aac", "-map", &video_map].map(String::from));
+        if self.mix_audio {
+            // duration=shortest keeps a looped video's own audio from running forever
+            let filter = format!(
+                "[0:a:0][1:a:{}]amix=inputs=2:duration=shortest:dropout_transition=0[aout]",
+                audio_index
+            );
+            args.extend(["-filter_complex".to_string(), filter, "-map".to_string(), "[aout]".to_string()]);
+        } else {
+            args.extend(["-map".to_string(), format!("1:a:{}", audio_index)]);
+        }
+        args.extend(["-shortest", output].map(String::from));
+        args
+    }
+
+    /// Estimate the output: the video's frame size, ending with the shorter
+    /// stream (or the audio when `loop_video` is set).
+    pub fn estimate_output(&self, video: &ProbedInput, audio: &ProbedInput) -> OutputEstimate {
+        let duration_seconds = if self.loop_video {
+            audio.duration_seconds
+        } else {
+            match (video.duration_seconds, audio.duration_seconds) {
+                (Some(v), Some(a)) => Some(v.min(a)),
+                (v, a) => v.or(a),
+            }
+        };
+        OutputEstimate {
+            dimensions: video.dimensions,
+            duration_seconds,
+        }
+    }
 }
 
 /// Parameters for overlaying an image on video.
@@ -138,6 +560,10 @@ pub struct OverlayImageParams {
     pub image_input: String,
     /// Output file path (local path or GCS URI).
     pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
     /// X position of the overlay (from left). Default: 0.
     #[serde(default)]
     pub x: i32,
@@ -153,6 +579,9 @@ pub struct OverlayImageParams {
     /// Duration in seconds for the overlay.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub duration: Option<f64>,
+    /// Opacity of the overlay from 0.0 (transparent) to 1.0 (opaque). Default: fully opaque.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opacity: Option<f32>,
 }
 
 /// Parameters for concatenating media files.
@@ -162,6 +591,22 @@ pub struct ConcatenateParams {
     pub inputs: Vec<String>,
     /// Output file path (local path or GCS URI).
     pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
+    /// Add a chapter marker at the start of each input. Default: false.
+    /// Chapters are kept by containers that support them (MP4, MOV, MKV, MP3, OGG).
+    #[serde(default)]
+    pub add_chapters: bool,
+    /// Chapter titles, one per input. Defaults to the input file names
+    /// without extension. Requires `add_chapters`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chapter_titles: Option<Vec<String>>,    /// Join the clips as audio with this many seconds of silence between
+    /// them (0-60). The audio is re-encoded for the output format and any
+    /// video is dropped. When omitted, streams are copied without gaps.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gap_seconds: Option<f64>,
 }
 
 /// Parameters for adjusting audio volume.
@@ -171,6 +616,10 @@ pub struct AdjustVolumeParams {
     pub input: String,
     /// Output audio file path (local path or GCS URI).
     pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
     /// Volume adjustment: numeric multiplier (e.g., "0.5", "2.0") or dB string (e.g., "-3dB", "+6dB").
     pub volume: String,
 }
@@ -182,6 +631,10 @@ pub struct LayerAudioParams {
     pub inputs: Vec<AudioLayer>,
     /// Output file path (local path or GCS URI).
     pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
 }
 
 /// A single audio layer for mixing.
@@ -201,6 +654,315 @@ fn default_volume() -> f32 {
     DEFAULT_VOLUME
 }
 
+/// Parameters for ducking a background track under a foreground track.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DuckAudioParams {
+    /// Background audio (e.g. music) that is lowered while the foreground plays
+    /// (local path or GCS URI).
+    pub background: String,
+    /// Foreground audio (e.g. narration) that triggers the ducking
+    /// (local path or GCS URI).
+    pub foreground: String,
+    /// Output audio file path (local path or GCS URI).
+    pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
+    /// Foreground level (0.001-1.0) above which the background is ducked. Default: 0.05.
+    #[serde(default = "default_duck_threshold")]
+    pub threshold: f32,
+    /// How strongly the background is compressed while ducked (1-20). Default: 8.
+    #[serde(default = "default_duck_ratio")]
+    pub ratio: f32,
+    /// Milliseconds for the background to duck once the foreground starts (0.01-2000). Default: 20.
+    #[serde(default = "default_duck_attack_ms")]
+    pub attack_ms: f32,
+    /// Milliseconds for the background to recover once the foreground stops (0.01-9000). Default: 300.
+    #[serde(default = "default_duck_release_ms")]
+    pub release_ms: f32,
+}
+
+fn default_duck_threshold() -> f32 {
+    DEFAULT_DUCK_THRESHOLD
+}
+
+fn default_duck_ratio() -> f32 {
+    DEFAULT_DUCK_RATIO
+}
+
+fn default_duck_attack_ms() -> f32 {
+    DEFAULT_DUCK_ATTACK_MS
+}
+
+fn default_duck_release_ms() -> f32 {
+    DEFAULT_DUCK_RELEASE_MS
+}
+
+fn default_overwrite() -> bool {
+    true
+}
+
+/// Parameters for transcoding a media file with explicit codec selection.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct TranscodeParams {
+    /// Input file path (local path or GCS URI).
+    pub input: String,
+    /// Output file path (local path or GCS URI). The extension selects the container.
+    pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
+    /// Video codec (e.g., "libx264", "libvpx-vp9", "copy"). Default: inferred from output extension.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub video_codec: Option<String>,
+    /// Audio codec (e.g., "aac", "libopus", "copy"). Default: inferred from output extension.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_codec: Option<String>,
+    /// Video bitrate (e.g., "2M", "800k").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub video_bitrate: Option<String>,
+    /// Audio bitrate (e.g., "128k", "192k").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_bitrate: Option<String>,
+    /// Constant rate factor for quality-based encoding (0-51 for x264/x265, 0-63 for VP9/AV1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crf: Option<u8>,
+    /// Encoder preset trading speed for compression (e.g., "fast", "medium", "slow").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+}
+
+/// Parameters for changing the container of a media file without re-encoding.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct RemuxParams {
+    /// Input file path (local path or GCS URI).
+    pub input: String,
+    /// Output file path (local path or GCS URI). The extension selects the container.
+    pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
+}
+
+/// Parameters for setting or stripping container metadata tags.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct SetMetadataParams {
+    /// Input file path (local path or GCS URI).
+    pub input: String,
+    /// Output file path (local path or GCS URI). Use the input's extension;
+    /// streams are copied, not re-encoded.
+    pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
+    /// Container tags to set (e.g., {"title": "Intro", "artist": "Studio"}).
+    /// An empty value removes that tag.
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+    /// Remove all existing container and stream metadata before applying
+    /// `tags`. Default: false.
+    #[serde(default)]
+    pub strip_all: bool,
+}
+
+/// Parameters for running ffmpeg with allowlisted options.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct FfmpegRunParams {
+    /// Input file paths (local paths or GCS URIs), passed as `-i` in order.
+    pub inputs: Vec<String>,
+    /// Output file path (local path or GCS URI). The extension selects the container.
+    pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
+    /// Options placed between the inputs and the output, e.g.
+    /// `["-vf", "hflip,eq=contrast=1.2", "-c:v", "libx264"]`. Only allowlisted
+    /// options and filters are accepted.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// Parameters for picture-in-picture composition of two videos.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct PipParams {
+    /// Main (background) video file path (local path or GCS URI).
+    pub main_input: String,
+    /// Overlay video file path (local path or GCS URI).
+    pub overlay_input: String,
+    /// Output file path (local path or GCS URI).
+    pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
+    /// Overlay width as a fraction of the main video width (0.0-1.0]. Default: 0.25.
+    #[serde(default = "default_pip_scale")]
+    pub scale: f32,
+    /// Overlay position: "top_left", "top_right", "bottom_left", "bottom_right", or "center".
+    /// Default: "bottom_right".
+    #[serde(default = "default_pip_position")]
+    pub position: String,
+    /// Margin from the frame edges in pixels (ignored for "center"). Default: 20.
+    #[serde(default = "default_pip_margin")]
+    pub margin: u32,
+    /// Audio handling: "main" keeps only the main video's audio (overlay muted),
+    /// "mix" mixes both audio tracks. Default: "main".
+    #[serde(default = "default_pip_audio_mode")]
+    pub audio_mode: String,
+}
+
+fn default_pip_scale() -> f32 {
+    DEFAULT_PIP_SCALE
+}
+
+fn default_pip_position() -> String {
+    DEFAULT_PIP_POSITION.to_string()
+}
+
+fn default_pip_margin() -> u32 {
+    DEFAULT_PIP_MARGIN
+}
+
+fn default_pip_audio_mode() -> String {
+    DEFAULT_PIP_AUDIO_MODE.to_string()
+}
+
+/// Parameters for reversing a video and/or audio file.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ReverseParams {
+    /// Input media file path (local path or GCS URI).
+    pub input: String,
+    /// Output file path (local path or GCS URI).
+    pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
+    /// Maximum input duration in seconds for this call. Longer inputs are
+    /// rejected because reversing buffers the whole stream in memory. Can
+    /// only lower the server limit (`MCP_REVERSE_MAX_DURATION`, default 30).
+    #[serde(default)]
+    pub max_duration: Option<f64>,
+}
+
+/// Parameters for detecting scene changes or black frames in a video.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct DetectScenesParams {
+    /// Input video file path (local path or GCS URI).
+    pub input: String,
+    /// Detection mode: `scene` (content changes) or `black` (black frames). Default: scene.
+    #[serde(default = "default_detect_mode")]
+    pub mode: String,
+    /// Detection threshold (0-1). For `scene`, the minimum scene change score
+    /// (default 0.4); for `black`, the minimum fraction of black pixels in a frame (default 0.98).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+    /// Minimum black segment duration in seconds (`black` mode only). Default: 0.5.
+    #[serde(default = "default_black_min_duration")]
+    pub min_black_duration: f64,
+}
+
+fn default_detect_mode() -> String {
+    DEFAULT_DETECT_MODE.to_string()
+}
+
+fn default_black_min_duration() -> f64 {
+    DEFAULT_BLACK_MIN_DURATION
+}
+
+/// Parameters for generating a contact sheet (thumbnail grid) from a video.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ContactSheetParams {
+    /// Input video file path (local path or GCS URI).
+    pub input: String,
+    /// Output image file path (local path or GCS URI), e.g. a PNG or JPEG.
+    pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
+    /// Number of thumbnail columns. Default: 4.
+    #[serde(default = "default_contact_sheet_columns")]
+    pub columns: u32,
+    /// Number of thumbnail rows. Default: 4.
+    #[serde(default = "default_contact_sheet_rows")]
+    pub rows: u32,
+    /// Width of each thumbnail in pixels (height keeps the aspect ratio). Default: 320.
+    #[serde(default = "default_contact_sheet_width")]
+    pub width: u32,
+}
+
+fn default_contact_sheet_columns() -> u32 {
+    DEFAULT_CONTACT_SHEET_COLUMNS
+}
+
+fn default_contact_sheet_rows() -> u32 {
+    DEFAULT_CONTACT_SHEET_ROWS
+}
+
+fn default_contact_sheet_width() -> u32 {
+    DEFAULT_CONTACT_SHEET_WIDTH
+}
+
+/// Parameters for turning a still image and an audio track into a video.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ImageToVideoParams {
+    /// Still image file path (local path or GCS URI): PNG, JPEG, WebP or BMP.
+    pub image_input: String,
+    /// Audio file path (local path or GCS URI). The video lasts as long as the audio.
+    pub audio_input: String,
+    /// Output video file path (local path or GCS URI), e.g. an MP4.
+    pub output: String,
+    /// Replace an existing local output file. Default: true. When false, the
+    /// call fails if the output already exists.
+    #[serde(default = "default_overwrite")]
+    pub overwrite: bool,
+    /// Draw the audio waveform across the bottom quarter of the image. Default: false.
+    #[serde(default)]
+    pub waveform: bool,
+    /// Waveform color as a name or hex value (e.g. "white", "0x1db954"). Default: "white".
+    #[serde(default = "default_waveform_color")]
+    pub waveform_color: String,
+}
+
+fn default_waveform_color() -> String {
+    DEFAULT_WAVEFORM_COLOR.to_string()
+}
+
+/// Parameters for downloading a GCS object to local disk.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct StorageDownloadParams {
+    /// GCS URI of the object to download (gs://bucket/path).
+    pub uri: String,
+    /// Local file path to write, inside the server's download directory
+    /// (`MCP_DOWNLOAD_DIR`); relative paths are placed in it. Missing parent
+    /// directories are created.
+    pub output_path: String,
+    /// Replace an existing local file. Default: false, so the call fails if
+    /// the file already exists.
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+/// Parameters for uploading a local file to GCS.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct StorageUploadParams {
+    /// Local file path to upload, inside the server's download directory
+    /// (`MCP_DOWNLOAD_DIR`); relative paths are looked up in it.
+    pub input_path: String,
+    /// Destination GCS URI (gs://bucket/path).
+    pub uri: String,
+    /// MIME type of the object (e.g. "video/mp4"). Default: inferred from the
+    /// file extension.
+    #[serde(default)]
+    pub content_type: Option<String>,
+}
+
 // =============================================================================
 // Validation
 // =============================================================================
@@ -279,1169 +1041,6281 @@ impl VolumeValue {
     }
 }
 
-impl AdjustVolumeParams {
-    /// Validate the volume parameter.
-    pub fn validate(&self) -> Result<VolumeValue, Vec<ValidationError>> {
+impl ConcatenateParams {
+    /// Validate the inputs and chapter options.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
         let mut errors = Vec::new();
-        
-        if self.input.trim().is_empty() {
+
+        if self.inputs.is_empty() {
             errors.push(ValidationError {
-                field: "input".to_string(),
-                message: "Input path cannot be empty".to_string(),
+                field: "inputs".to_string(),
+                message: "At least one input file is required".to_string(),
             });
         }
-        
-        if self.output.trim().is_empty() {
-            errors.push(ValidationError {
-                field: "output".to_string(),
-                message: "Output path cannot be empty".to_string(),
-            });
+
+        // NaN is rejected too
+        if let Some(gap) = self.gap_seconds {
+            if !(0.0..=MAX_GAP_SECONDS).contains(&gap) {
+                errors.push(ValidationError {
+                    field: "gap_seconds".to_string(),
+                    message: format!(
+                        "gap_seconds must be between 0 and {}, got {}",
+                        MAX_GAP_SECONDS, gap
+                    ),
+                });
+            }
         }
-        
-        let volume = match VolumeValue::parse(&self.volume) {
-            Ok(v) => Some(v),
-            Err(e) => {
+
+        if let Some(titles) = &self.chapter_titles {
+            if !self.add_chapters {
                 errors.push(ValidationError {
-                    field: "volume".to_string(),
-                    message: e,
+                    field: "chapter_titles".to_string(),
+                    message: "chapter_titles requires add_chapters".to_string(),
                 });
-                None
             }
-        };
-        
+            if titles.len() != self.inputs.len() {
+                errors.push(ValidationError {
+                    field: "chapter_titles".to_string(),
+                    message: format!(
+                        "Expected one title per input ({}), got {}",
+                        self.inputs.len(),
+                        titles.len()
+                    ),
+                });
+            }
+        }
+
         if errors.is_empty() {
-            Ok(volume.unwrap())
+            Ok(())
         } else {
             Err(errors)
         }
     }
-}
-
 
-// =============================================================================
-// AVToolHandler
-// =============================================================================
+    /// Chapter titles: the given titles, or each input's file name without
+    /// extension.
+    pub fn chapter_titles(&self) -> Vec<String> {
+        if let Some(titles) = &self.chapter_titles {
+            return titles.clone();
+        }
+        self.inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| {
+                Path::new(input)
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .unwrap_or_else(|| format!("Chapter {}", i + 1))
+            })
+            .collect()
+    }
 
-/// AVTool handler for FFmpeg-based media processing.
-pub struct AVToolHandler {
-    /// Application configuration.
-    pub config: Config,
-    /// GCS client for storage operations.
-    pub gcs: GcsClient,
-    /// Temporary directory for downloaded files.
-    temp_dir: PathBuf,
-}
+    /// Estimate the output: the inputs played back to back, sized like the
+    /// largest frame. Gap mode adds the silences and drops video.
+    pub fn estimate_output(&self, inputs: &[ProbedInput]) -> OutputEstimate {
+        let gaps = self.gap_seconds.unwrap_or(0.0) * inputs.len().saturating_sub(1) as f64;
+        OutputEstimate {
+            dimensions: inputs
+                .iter()
+                .filter_map(|i| i.dimensions)
+                .max_by_key(|&(w, h)| u64::from(w) * u64::from(h))
+                .filter(|_| self.gap_seconds.is_none()),
+            duration_seconds: inputs
+                .iter()
+                .filter_map(|i| i.duration_seconds)
+                .reduce(|a, b| a + b)
+                .map(|total| total + gaps),
+        }
+    }
 
-impl AVToolHandler {
-    /// Create a new AVToolHandler with the given configuration.
-    ///
-    /// # Errors
-    /// Returns an error if GCS client initialization fails.
-    #[instrument(level = "debug", name = "avtool_handler_new", skip_all)]
-    pub async fn new(config: Config) -> Result<Self, Error> {
-        debug!("Initializing AVToolHandler");
+    /// Build the concat filtergraph for gap mode: every clip but the last is
+    /// padded with `gap_seconds` of silence, then all are joined into `[out]`.
+    pub fn gap_filter(&self, count: usize) -> String {
+        let gap = self.gap_seconds.unwrap_or(0.0);
+        let mut filter = String::new();
+        for i in 0..count {
+            if i + 1 < count && gap > 0.0 {
+                filter.push_str(&format!("[{i}:a:0]apad=pad_dur={gap}[a{i}];"));
+            } else {
+                filter.push_str(&format!("[{i}:a:0]anull[a{i}];"));
+            }
+        }
+        for i in 0..count {
+            filter.push_str(&format!("[a{i}]"));
+        }
+        filter.push_str(&format!("concat=n={count}:v=0:a=1[out]"));
+        filter
+    }
 
-        let auth = AuthProvider::new().await?;
-        let gcs = GcsClient::with_auth(auth);
-        
-        // Create temp directory for downloaded files
-        let temp_dir = std::env::temp_dir().join("adk-rust-mcp-avtool");
-        tokio::fs::create_dir_all(&temp_dir).await?;
+    /// Build the ffmpeg arguments for gap mode. The chapters file, if any,
+    /// is added after the clips.
+    pub fn build_gap_args(&self, inputs: &[String], chapters: Option<&str>, output: &str) -> Vec<String> {
+        let mut args = Vec::new();
+        for input in inputs {
+            args.push("-i".to_string());
+            args.push(input.clone());
+        }
+        if let Some(chapters) = chapters {
+            args.extend(["-i".to_string(), chapters.to_string()]);
+        }
+        args.extend([
+            "-filter_complex".to_string(),
+            self.gap_filter(inputs.len()),
+            "-map".to_string(),
+            "[out]".to_string(),
+        ]);
+        if chapters.is_some() {
+            args.extend(["-map_chapters".to_string(), inputs.len().to_string()]);
+        }
+        args.push(output.to_string());
+        args
+    }
+}
 
-        Ok(Self {
-            config,
-            gcs,
-            temp_dir,
-        })
+impl LayerAudioParams {
+    /// Estimate the output: the mix lasts until the latest layer ends.
+    pub fn estimate_output(&self, inputs: &[ProbedInput]) -> OutputEstimate {
+        OutputEstimate {
+            dimensions: None,
+            duration_seconds: self
+                .inputs
+                .iter()
+                .zip(inputs)
+                .filter_map(|(layer, probed)| {
+                    probed.duration_seconds.map(|d| layer.offset_seconds.max(0.0) + d)
+                })
+                .reduce(f64::max),
+        }
     }
+}
 
-    /// Create a new AVToolHandler with provided dependencies (for testing).
-    #[cfg(test)]
-    pub fn with_deps(config: Config, gcs: GcsClient, temp_dir: PathBuf) -> Self {
-        Self {
-            config,
-            gcs,
-            temp_dir,
+impl DuckAudioParams {
+    /// Validate the ducking parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        for (field, path) in [("background", &self.background), ("foreground", &self.foreground)] {
+            if path.trim().is_empty() {
+                errors.push(ValidationError {
+                    field: field.to_string(),
+                    message: format!("{} path cannot be empty", field),
+                });
+            }
+        }
+
+        if self.output.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "output".to_string(),
+                message: "Output path cannot be empty".to_string(),
+            });
+        }
+
+        let knobs = [
+            ("threshold", self.threshold, DUCK_THRESHOLD_RANGE),
+            ("ratio", self.ratio, DUCK_RATIO_RANGE),
+            ("attack_ms", self.attack_ms, DUCK_ATTACK_RANGE_MS),
+            ("release_ms", self.release_ms, DUCK_RELEASE_RANGE_MS),
+        ];
+        for (field, value, (min, max)) in knobs {
+            if !(min..=max).contains(&value) {
+                errors.push(ValidationError {
+                    field: field.to_string(),
+                    message: format!("{} must be between {} and {}, got {}", field, min, max, value),
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
         }
     }
 
-    // =========================================================================
-    // Path Resolution Helpers
-    // =========================================================================
+    /// The `-filter_complex` graph: the foreground is split into a sidechain
+    /// that compresses the background and a copy mixed back over the result.
+    pub fn filter_complex(&self) -> String {
+        format!(
+            "[1:a]asplit=2[sc][fg];\
+             [0:a][sc]sidechaincompress=threshold={}:ratio={}:attack={}:release={}[ducked];\
+             [ducked][fg]amix=inputs=2:duration=longest",
+            self.threshold, self.ratio, self.attack_ms, self.release_ms
+        )
+    }
 
-    /// Check if a path is a GCS URI.
-    pub fn is_gcs_uri(path: &str) -> bool {
-        path.starts_with("gs://")
+    /// Build the ffmpeg arguments for ducking `background` under `foreground`.
+    pub fn build_args(&self, background: &str, foreground: &str, output: &str) -> Vec<String> {
+        vec![
+            "-i".to_string(),
+            background.to_string(),
+            "-i".to_string(),
+            foreground.to_string(),
+            "-filter_complex".to_string(),
+            self.filter_complex(),
+            output.to_string(),
+        ]
     }
 
-    /// Resolve an input path, downloading from GCS if necessary.
-    ///
-    /// Returns the local path to use for FFmpeg operations.
-    #[instrument(level = "debug", skip(self))]
-    pub async fn resolve_input(&self, path: &str) -> Result<PathBuf, Error> {
-        if Self::is_gcs_uri(path) {
-            // Download from GCS to temp file
-            let gcs_uri = GcsUri::parse(path)?;
-            let filename = Path::new(&gcs_uri.object)
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("input");
-            
-            let local_path = self.temp_dir.join(format!("{}_{}", Uuid::new_v4(), filename));
-            
-            debug!(gcs_uri = %path, local_path = %local_path.display(), "Downloading from GCS");
-            let data = self.gcs.download(&gcs_uri).await?;
-            tokio::fs::write(&local_path, &data).await?;
-            
-            Ok(local_path)
-        } else {
-            // Local path, use as-is
-            Ok(PathBuf::from(path))
+    /// Estimate the output: the mix lasts as long as the longer track.
+    pub fn estimate_output(&self, inputs: &[ProbedInput]) -> OutputEstimate {
+        OutputEstimate {
+            dimensions: None,
+            duration_seconds: inputs.iter().filter_map(|i| i.duration_seconds).reduce(f64::max),
         }
     }
+}
 
-    /// Handle output, uploading to GCS if the output path is a GCS URI.
-    ///
-    /// Returns the final output path (GCS URI or local path).
-    #[instrument(level = "debug", skip(self))]
-    pub async fn handle_output(&self, local_path: &Path, output: &str) -> Result<String, Error> {
-        if Self::is_gcs_uri(output) {
-            // Upload to GCS
-            let gcs_uri = GcsUri::parse(output)?;
-            let data = tokio::fs::read(local_path).await?;
-            
-            // Determine content type from extension
-            let content_type = Self::content_type_from_extension(local_path);
-            
-            debug!(local_path = %local_path.display(), gcs_uri = %output, "Uploading to GCS");
-            self.gcs.upload(&gcs_uri, &data, content_type).await?;
-            
-            Ok(output.to_string())
-        } else {
-            // Local path - if different from local_path, copy the file
-            if local_path != Path::new(output) {
-                tokio::fs::copy(local_path, output).await?;
+impl AdjustVolumeParams {
+    /// Validate the volume parameter.
+    pub fn validate(&self) -> Result<VolumeValue, Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        
+        if self.input.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "input".to_string(),
+                message: "Input path cannot be empty".to_string(),
+            });
+        }
+        
+        if self.output.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "output".to_string(),
+                message: "Output path cannot be empty".to_string(),
+            });
+        }
+        
+        let volume = match VolumeValue::parse(&self.volume) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                errors.push(ValidationError {
+                    field: "volume".to_string(),
+                    message: e,
+                });
+                None
             }
-            Ok(output.to_string())
+        };
+        
+        if errors.is_empty() {
+            Ok(volume.unwrap())
+        } else {
+            Err(errors)
         }
     }
+}
 
-    /// Get content type from file extension.
-    fn content_type_from_extension(path: &Path) -> &'static str {
-        match path.extension().and_then(|e| e.to_str()) {
-            Some("mp3") => "audio/mpeg",
-            Some("wav") => "audio/wav",
-            Some("mp4") => "video/mp4",
-            Some("webm") => "video/webm",
-            Some("gif") => "image/gif",
-            Some("png") => "image/png",
-            Some("jpg") | Some("jpeg") => "image/jpeg",
-            Some("mkv") => "video/x-matroska",
-            Some("avi") => "video/x-msvideo",
-            Some("mov") => "video/quicktime",
-            Some("ogg") => "audio/ogg",
-            Some("flac") => "audio/flac",
-            _ => "application/octet-stream",
+
+impl OverlayImageParams {
+    /// Validate the overlay parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if let Some(opacity) = self.opacity {
+            if !(0.0..=1.0).contains(&opacity) {
+                errors.push(ValidationError {
+                    field: "opacity".to_string(),
+                    message: format!("Opacity must be between 0.0 and 1.0, got {}", opacity),
+                });
+            }
         }
-    }
 
-    /// Generate a temporary output path.
-    fn temp_output_path(&self, extension: &str) -> PathBuf {
-        self.temp_dir.join(format!("{}.{}", Uuid::new_v4(), extension))
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
 
-    // =========================================================================
-    // FFmpeg/FFprobe Execution
-    // =========================================================================
-
-    /// Execute ffprobe and return parsed JSON output.
-    async fn run_ffprobe(&self, input: &Path) -> Result<serde_json::Value, Error> {
-        let output = Command::new("ffprobe")
-            .args([
-                "-v", "quiet",
-                "-print_format", "json",
-                "-show_format",
-                "-show_streams",
-            ])
-            .arg(input)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await?;
+    /// Build the `-filter_complex` graph for compositing the image onto the video.
+    pub fn filter_complex(&self) -> String {
+        let mut filter_parts = Vec::new();
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(Error::ffmpeg(format!(
-                "ffprobe failed for '{}': {}",
-                input.display(),
-                stderr
-            )));
+        // Pre-process the image input (scale and/or alpha) into a labelled stream
+        let mut image_filters = Vec::new();
+        if let Some(scale) = self.scale {
+            image_filters.push(format!("scale=iw*{}:ih*{}", scale, scale));
+        }
+        if let Some(opacity) = self.opacity {
+            image_filters.push(format!("format=rgba,colorchannelmixer=aa={}", opacity));
         }
 
-        let json: serde_json::Value = serde_json::from_slice(&output.stdout).map_err(|e| {
-            Error::ffmpeg(format!("Failed to parse ffprobe output: {}", e))
-        })?;
+        let img_ref = if image_filters.is_empty() {
+            "[1:v]"
+        } else {
+            filter_parts.push(format!("[1:v]{}[img]", image_filters.join(",")));
+            "[img]"
+        };
 
-        Ok(json)
+        // Build overlay filter with position and timing
+        let mut overlay = format!("[0:v]{}overlay={}:{}", img_ref, self.x, self.y);
+
+        // Add enable expression for timing
+        if self.start_time.is_some() || self.duration.is_some() {
+            let start = self.start_time.unwrap_or(0.0);
+            let enable = if let Some(dur) = self.duration {
+                format!(":enable='between(t,{},{})'", start, start + dur)
+            } else {
+                format!(":enable='gte(t,{})'", start)
+            };
+            overlay.push_str(&enable);
+        }
+
+        filter_parts.push(overlay);
+        filter_parts.join(";")
     }
+}
 
-    /// Execute ffmpeg with the given arguments.
-    async fn run_ffmpeg(&self, args: &[&str]) -> Result<(), Error> {
-        debug!(args = ?args, "Running ffmpeg");
-        
-        let output = Command::new("ffmpeg")
-            .args(["-y"]) // Overwrite output files
-            .args(args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await?;
+/// Check whether a bitrate string looks like an FFmpeg bitrate (e.g., "128k", "2M", "1.5M", "64000").
+fn is_valid_bitrate(bitrate: &str) -> bool {
+    let number = bitrate
+        .strip_suffix(['k', 'K', 'm', 'M'])
+        .unwrap_or(bitrate);
+    !number.is_empty() && number.parse::<f64>().map(|n| n > 0.0).unwrap_or(false)
+}
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(Error::ffmpeg(format!("ffmpeg failed: {}", stderr)));
-        }
+/// Filter that rounds the frame size down to even numbers.
+pub const EVEN_DIMENSIONS_FILTER: &str = "scale=trunc(iw/2)*2:trunc(ih/2)*2";
 
-        Ok(())
+/// Whether `codec` is an H.264/H.265 encoder, software or hardware. These
+/// reject odd frame widths and heights ("width not divisible by 2").
+pub fn requires_even_dimensions(codec: &str) -> bool {
+    matches!(codec, "libx264" | "libx265") || codec.starts_with("h264_") || codec.starts_with("hevc_")
+}
+
+/// Default (video, audio) codecs for an output container extension.
+///
+/// `None` means the stream type is not carried by the container (e.g., audio in GIF),
+/// in which case the corresponding stream is dropped.
+pub fn default_codecs_for_extension(ext: &str) -> (Option<&'static str>, Option<&'static str>) {
+    match ext.to_lowercase().as_str() {
+        "mp4" | "m4v" | "mov" | "mkv" => (Some("libx264"), Some("aac")),
+        "webm" => (Some("libvpx-vp9"), Some("libopus")),
+        "avi" => (Some("mpeg4"), Some("libmp3lame")),
+        "gif" => (Some("gif"), None),
+        "mp3" => (None, Some("libmp3lame")),
+        "wav" => (None, Some("pcm_s16le")),
+        "ogg" => (None, Some("libvorbis")),
+        "opus" => (None, Some("libopus")),
+        "flac" => (None, Some("flac")),
+        "m4a" | "aac" => (None, Some("aac")),
+        _ => (Some("libx264"), Some("aac")),
     }
+}
 
-    // =========================================================================
-    // Tool Implementations
-    // =========================================================================
+impl TranscodeParams {
+    /// Validate the transcode parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
 
-    /// Get media file information using ffprobe.
-    #[instrument(level = "info", skip(self))]
-    pub async fn get_media_info(&self, params: GetMediaInfoParams) -> Result<MediaInfo, Error> {
-        let local_input = self.resolve_input(&params.input).await?;
-        
-        let json = self.run_ffprobe(&local_input).await?;
-        
-        // Parse format info
-        let format = json.get("format").ok_or_else(|| {
-            Error::ffmpeg("ffprobe output missing 'format' field")
-        })?;
-        
-        let duration: f64 = format
-            .get("duration")
-            .and_then(|d| d.as_str())
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(0.0);
-        
-        let format_name = format
-            .get("format_name")
-            .and_then(|f| f.as_str())
-            .unwrap_or("unknown")
-            .to_string();
-        
-        // Parse streams
-        let streams_json = json.get("streams").and_then(|s| s.as_array());
-        let streams: Vec<StreamInfo> = streams_json
-            .map(|arr| {
-                arr.iter()
-                    .map(|s| StreamInfo {
-                        index: s.get("index").and_then(|i| i.as_u64()).unwrap_or(0) as u32,
-                        codec_type: s.get("codec_type").and_then(|c| c.as_str()).unwrap_or("unknown").to_string(),
-                        codec_name: s.get("codec_name").and_then(|c| c.as_str()).unwrap_or("unknown").to_string(),
-                        width: s.get("width").and_then(|w| w.as_u64()).map(|w| w as u32),
-                        height: s.get("height").and_then(|h| h.as_u64()).map(|h| h as u32),
-                        sample_rate: s.get("sample_rate").and_then(|r| r.as_str()).and_then(|s| s.parse().ok()),
-                        channels: s.get("channels").and_then(|c| c.as_u64()).map(|c| c as u32),
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
-        
-        // Clean up temp file if we downloaded from GCS
-        if Self::is_gcs_uri(&params.input) {
-            let _ = tokio::fs::remove_file(&local_input).await;
+        if self.input.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "input".to_string(),
+                message: "Input path cannot be empty".to_string(),
+            });
         }
-        
-        info!(duration, format = %format_name, streams = streams.len(), "Got media info");
-        
-        Ok(MediaInfo {
-            duration,
-            format: format_name,
-            streams,
-        })
-    }
 
-    /// Convert WAV to MP3.
-    #[instrument(level = "info", skip(self))]
-    pub async fn convert_wav_to_mp3(&self, params: ConvertAudioParams) -> Result<String, Error> {
-        let local_input = self.resolve_input(&params.input).await?;
-        let temp_output = self.temp_output_path("mp3");
-        
-        let input_str = local_input.to_string_lossy();
-        let output_str = temp_output.to_string_lossy();
-        
-        self.run_ffmpeg(&[
-            "-i", &input_str,
-            "-codec:a", "libmp3lame",
-            "-b:a", &params.bitrate,
-            &output_str,
-        ]).await?;
-        
-        let result = self.handle_output(&temp_output, &params.output).await?;
-        
-        // Clean up temp files
-        if Self::is_gcs_uri(&params.input) {
-            let _ = tokio::fs::remove_file(&local_input).await;
+        if self.output.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "output".to_string(),
+                message: "Output path cannot be empty".to_string(),
+            });
         }
-        let _ = tokio::fs::remove_file(&temp_output).await;
-        
-        info!(output = %result, "Converted WAV to MP3");
-        Ok(result)
-    }
 
-    /// Convert video to GIF.
-    #[instrument(level = "info", skip(self))]
-    pub async fn video_to_gif(&self, params: VideoToGifParams) -> Result<String, Error> {
-        let local_input = self.resolve_input(&params.input).await?;
-        let temp_output = self.temp_output_path("gif");
-        
-        let input_str = local_input.to_string_lossy();
-        let output_str = temp_output.to_string_lossy();
-        
-        // Build filter string
-        let mut filters = vec![format!("fps={}", params.fps)];
-        if let Some(width) = params.width {
-            filters.push(format!("scale={}:-1:flags=lanczos", width));
+        if let Some(ref codec) = self.video_codec {
+            if !SUPPORTED_VIDEO_CODECS.contains(&codec.as_str()) {
+                errors.push(ValidationError {
+                    field: "video_codec".to_string(),
+                    message: format!(
+                        "Unsupported video codec '{}'. Supported: {}",
+                        codec,
+                        SUPPORTED_VIDEO_CODECS.join(", ")
+                    ),
+                });
+            }
         }
-        let filter_str = filters.join(",");
-        
-        let mut args: Vec<String> = Vec::new();
-        
-        // Add start time if specified
-        if let Some(start) = params.start_time {
-            args.push("-ss".to_string());
-            args.push(format!("{}", start));
+
+        if let Some(ref codec) = self.audio_codec {
+            if !SUPPORTED_AUDIO_CODECS.contains(&codec.as_str()) {
+                errors.push(ValidationError {
+                    field: "audio_codec".to_string(),
+                    message: format!(
+                        "Unsupported audio codec '{}'. Supported: {}",
+                        codec,
+                        SUPPORTED_AUDIO_CODECS.join(", ")
+                    ),
+                });
+            }
         }
-        
-        args.push("-i".to_string());
-        args.push(input_str.to_string());
-        
-        // Add duration if specified
-        if let Some(duration) = params.duration {
-            args.push("-t".to_string());
-            args.push(format!("{}", duration));
+
+        for (field, bitrate) in [
+            ("video_bitrate", &self.video_bitrate),
+            ("audio_bitrate", &self.audio_bitrate),
+        ] {
+            if let Some(bitrate) = bitrate {
+                if !is_valid_bitrate(bitrate) {
+                    errors.push(ValidationError {
+                        field: field.to_string(),
+                        message: format!(
+                            "Invalid bitrate '{}'. Expected a value like '128k' or '2M'",
+                            bitrate
+                        ),
+                    });
+                }
+            }
         }
-        
-        args.push("-vf".to_string());
-        args.push(filter_str);
-        args.push(output_str.to_string());
-        
-        let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
-        self.run_ffmpeg(&args_refs).await?;
-        
-        let result = self.handle_output(&temp_output, &params.output).await?;
-        
-        // Clean up temp files
-        if Self::is_gcs_uri(&params.input) {
-            let _ = tokio::fs::remove_file(&local_input).await;
+
+        if let Some(crf) = self.crf {
+            let max_crf = match self.resolved_codecs().0 {
+                Some("libvpx") | Some("libvpx-vp9") | Some("libaom-av1") => 63,
+                _ => 51,
+            };
+            if crf > max_crf {
+                errors.push(ValidationError {
+                    field: "crf".to_string(),
+                    message: format!("CRF must be between 0 and {}, got {}", max_crf, crf),
+                });
+            }
         }
-        let _ = tokio::fs::remove_file(&temp_output).await;
-        
-        info!(output = %result, "Converted video to GIF");
-        Ok(result)
-    }
 
-    /// Combine audio and video.
-    #[instrument(level = "info", skip(self))]
-    pub async fn combine_audio_video(&self, params: CombineAvParams) -> Result<String, Error> {
-        let local_video = self.resolve_input(&params.video_input).await?;
-        let local_audio = self.resolve_input(&params.audio_input).await?;
-        
-        // Determine output extension from output path
-        let ext = Path::new(&params.output)
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("mp4");
-        let temp_output = self.temp_output_path(ext);
-        
-        let video_str = local_video.to_string_lossy();
-        let audio_str = local_audio.to_string_lossy();
-        let output_str = temp_output.to_string_lossy();
-        
-        self.run_ffmpeg(&[
-            "-i", &video_str,
-            "-i", &audio_str,
-            "-c:v", "copy",
-            "-c:a", "aac",
-            "-map", "0:v:0",
-            "-map", "1:a:0",
-            "-shortest",
-            &output_str,
-        ]).await?;
-        
-        let result = self.handle_output(&temp_output, &params.output).await?;
-        
-        // Clean up temp files
-        if Self::is_gcs_uri(&params.video_input) {
-            let _ = tokio::fs::remove_file(&local_video).await;
+        if let Some(ref preset) = self.preset {
+            if !SUPPORTED_PRESETS.contains(&preset.as_str()) {
+                errors.push(ValidationError {
+                    field: "preset".to_string(),
+                    message: format!(
+                        "Unsupported preset '{}'. Supported: {}",
+                        preset,
+                        SUPPORTED_PRESETS.join(", ")
+                    ),
+                });
+            }
         }
-        if Self::is_gcs_uri(&params.audio_input) {
-            let _ = tokio::fs::remove_file(&local_audio).await;
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
         }
-        let _ = tokio::fs::remove_file(&temp_output).await;
-        
-        info!(output = %result, "Combined audio and video");
-        Ok(result)
     }
 
-    /// Overlay image on video.
-    #[instrument(level = "info", skip(self))]
-    pub async fn overlay_image(&self, params: OverlayImageParams) -> Result<String, Error> {
-        let local_video = self.resolve_input(&params.video_input).await?;
-        let local_image = self.resolve_input(&params.image_input).await?;
-        
-        let ext = Path::new(&params.output)
+    /// Resolve the (video, audio) codecs, filling in defaults from the output extension.
+    pub fn resolved_codecs(&self) -> (Option<&str>, Option<&str>) {
+        let ext = Path::new(&self.output)
             .extension()
             .and_then(|e| e.to_str())
             .unwrap_or("mp4");
-        let temp_output = self.temp_output_path(ext);
-        
-        let video_str = local_video.to_string_lossy();
-        let image_str = local_image.to_string_lossy();
-        let output_str = temp_output.to_string_lossy();
-        
-        // Build filter complex
-        let mut filter_parts = Vec::new();
-        
-        // Scale image if specified
-        if let Some(scale) = params.scale {
-            filter_parts.push(format!("[1:v]scale=iw*{}:ih*{}[img]", scale, scale));
-        }
-        
-        // Build overlay filter with position and timing
-        let img_ref = if params.scale.is_some() { "[img]" } else { "[1:v]" };
-        let mut overlay = format!("[0:v]{}overlay={}:{}", img_ref, params.x, params.y);
-        
-        // Add enable expression for timing
-        if params.start_time.is_some() || params.duration.is_some() {
-            let start = params.start_time.unwrap_or(0.0);
-            let enable = if let Some(dur) = params.duration {
-                format!(":enable='between(t,{},{})'", start, start + dur)
-            } else {
-                format!(":enable='gte(t,{})'", start)
-            };
-            overlay.push_str(&enable);
+        let (default_video, default_audio) = default_codecs_for_extension(ext);
+
+        (
+            self.video_codec.as_deref().or(default_video),
+            self.audio_codec.as_deref().or(default_audio),
+        )
+    }
+
+    /// Build the ffmpeg arguments for transcoding `input` into `output`.
+    pub fn build_args(&self, input: &str, output: &str) -> Vec<String> {
+        self.build_args_with_hwaccel(input, output, HwAccel::None)
+    }
+
+    /// [`build_args`](Self::build_args) with hardware decoding and, for
+    /// H.264/H.265, the matching hardware encoder.
+    ///
+    /// Hardware encoders take no x264 `preset`; `crf` maps to the encoder's
+    /// constant-quality option where one exists. H.264/H.265 encodes round the
+    /// frame size down to even numbers so odd-sized sources such as GIFs encode.
+    pub fn build_args_with_hwaccel(&self, input: &str, output: &str, hwaccel: HwAccel) -> Vec<String> {
+        let (video_codec, audio_codec) = self.resolved_codecs();
+        let mut args = vec!["-i".to_string(), input.to_string()];
+
+        match video_codec {
+            Some(codec) => match hwaccel.encoder_for(codec) {
+                Some(encoder) => {
+                    if hwaccel == HwAccel::Vaapi {
+                        // VAAPI encoders only accept frames in GPU memory
+                        args.extend([
+                            "-vf".to_string(),
+                            format!("{},format=nv12,hwupload", EVEN_DIMENSIONS_FILTER),
+                        ]);
+                    } else {
+                        args.extend(["-vf".to_string(), EVEN_DIMENSIONS_FILTER.to_string()]);
+                    }
+                    args.extend(["-c:v".to_string(), encoder.to_string()]);
+                    if let Some(ref bitrate) = self.video_bitrate {
+                        args.extend(["-b:v".to_string(), bitrate.clone()]);
+                    }
+                    let quality_flag = match hwaccel {
+                        HwAccel::Cuda => Some("-cq"),
+                        HwAccel::Vaapi => Some("-qp"),
+                        HwAccel::VideoToolbox | HwAccel::None => None,
+                    };
+                    if let (Some(flag), Some(crf)) = (quality_flag, self.crf) {
+                        args.extend([flag.to_string(), crf.to_string()]);
+                    }
+                }
+                None => {
+                    if requires_even_dimensions(codec) {
+                        args.extend(["-vf".to_string(), EVEN_DIMENSIONS_FILTER.to_string()]);
+                    }
+                    args.extend(["-c:v".to_string(), codec.to_string()]);
+                    if codec != "copy" {
+                        if let Some(ref bitrate) = self.video_bitrate {
+                            args.extend(["-b:v".to_string(), bitrate.clone()]);
+                        }
+                        if let Some(crf) = self.crf {
+                            args.extend(["-crf".to_string(), crf.to_string()]);
+                        }
+                        if let Some(ref preset) = self.preset {
+                            args.extend(["-preset".to_string(), preset.clone()]);
+                        }
+                    }
+                }
+            },
+            None => args.push("-vn".to_string()),
         }
-        
-        filter_parts.push(overlay);
-        let filter_complex = filter_parts.join(";");
-        
-        self.run_ffmpeg(&[
-            "-i", &video_str,
-            "-i", &image_str,
-            "-filter_complex", &filter_complex,
-            "-c:a", "copy",
-            &output_str,
-        ]).await?;
-        
-        let result = self.handle_output(&temp_output, &params.output).await?;
-        
-        // Clean up temp files
-        if Self::is_gcs_uri(&params.video_input) {
-            let _ = tokio::fs::remove_file(&local_video).await;
+
+        match audio_codec {
+            Some(codec) => {
+                args.extend(["-c:a".to_string(), codec.to_string()]);
+                if codec != "copy" {
+                    if let Some(ref bitrate) = self.audio_bitrate {
+                        args.extend(["-b:a".to_string(), bitrate.clone()]);
+                    }
+                }
+            }
+            None => args.push("-an".to_string()),
         }
-        if Self::is_gcs_uri(&params.image_input) {
-            let _ = tokio::fs::remove_file(&local_image).await;
+
+        args.push(output.to_string());
+        // Stream copies and audio-only outputs skip decoding entirely
+        if video_codec.is_some_and(|codec| codec != "copy") {
+            args = hwaccel.with_decode_args(args);
         }
-        let _ = tokio::fs::remove_file(&temp_output).await;
-        
-        info!(output = %result, "Overlaid image on video");
-        Ok(result)
+        args
     }
+}
 
-    /// Concatenate media files.
-    #[instrument(level = "info", skip(self))]
-    pub async fn concatenate(&self, params: ConcatenateParams) -> Result<String, Error> {
-        if params.inputs.is_empty() {
-            return Err(Error::validation("At least one input file is required"));
-        }
-        
-        // Resolve all inputs
-        let mut local_inputs = Vec::new();
-        for input in &params.inputs {
-            local_inputs.push(self.resolve_input(input).await?);
-        }
-        
-        let ext = Path::new(&params.output)
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("mp4");
-        let temp_output = self.temp_output_path(ext);
-        
-        // Create concat file list
-        let concat_file = self.temp_dir.join(format!("{}_concat.txt", Uuid::new_v4()));
-        let concat_content: String = local_inputs
-            .iter()
-            .map(|p| format!("file '{}'\n", p.display()))
-            .collect();
-        tokio::fs::write(&concat_file, &concat_content).await?;
-        
-        let concat_str = concat_file.to_string_lossy();
-        let output_str = temp_output.to_string_lossy();
-        
-        self.run_ffmpeg(&[
-            "-f", "concat",
-            "-safe", "0",
-            "-i", &concat_str,
-            "-c", "copy",
-            &output_str,
-        ]).await?;
-        
-        let result = self.handle_output(&temp_output, &params.output).await?;
-        
-        // Clean up temp files
-        for (i, input) in params.inputs.iter().enumerate() {
-            if Self::is_gcs_uri(input) {
-                let _ = tokio::fs::remove_file(&local_inputs[i]).await;
+/// Codecs (ffprobe `codec_name`) a container can hold without re-encoding.
+///
+/// An empty list means the container carries no streams of that type. Entries
+/// ending in `_` match by prefix (e.g. `pcm_` covers every PCM format).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContainerCodecs {
+    /// Accepted video codecs, including cover art in audio containers.
+    pub video: &'static [&'static str],
+    /// Accepted audio codecs.
+    pub audio: &'static [&'static str],
+    /// Accepted subtitle codecs.
+    pub subtitle: &'static [&'static str],
+}
+
+impl ContainerCodecs {
+    /// Whether a stream of `codec_type` encoded with `codec_name` can be copied.
+    ///
+    /// Stream types other than video, audio and subtitle are not remuxed and
+    /// always pass.
+    pub fn accepts(&self, codec_type: &str, codec_name: &str) -> bool {
+        let allowed = match codec_type {
+            "video" => self.video,
+            "audio" => self.audio,
+            "subtitle" => self.subtitle,
+            _ => return true,
+        };
+        allowed.iter().any(|c| {
+            if c.ends_with('_') {
+                codec_name.starts_with(c)
+            } else {
+                *c == codec_name
             }
-        }
-        let _ = tokio::fs::remove_file(&concat_file).await;
-        let _ = tokio::fs::remove_file(&temp_output).await;
-        
-        info!(output = %result, count = params.inputs.len(), "Concatenated media files");
-        Ok(result)
+        })
     }
+}
 
-    /// Adjust audio volume.
-    #[instrument(level = "info", skip(self))]
-    pub async fn adjust_volume(&self, params: AdjustVolumeParams) -> Result<String, Error> {
-        // Validate and parse volume
-        let volume = params.validate().map_err(|errors| {
-            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
-            Error::validation(messages.join("; "))
-        })?;
-        
-        let local_input = self.resolve_input(&params.input).await?;
-        
-        let ext = Path::new(&params.output)
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("wav");
-        let temp_output = self.temp_output_path(ext);
-        
-        let input_str = local_input.to_string_lossy();
-        let output_str = temp_output.to_string_lossy();
-        let volume_filter = format!("volume={}", volume.to_ffmpeg_value());
-        
-        self.run_ffmpeg(&[
-            "-i", &input_str,
-            "-af", &volume_filter,
-            &output_str,
-        ]).await?;
-        
-        let result = self.handle_output(&temp_output, &params.output).await?;
-        
-        // Clean up temp files
-        if Self::is_gcs_uri(&params.input) {
-            let _ = tokio::fs::remove_file(&local_input).await;
+/// Codecs an output container extension accepts for stream copy.
+///
+/// `None` for containers that take nearly any codec (e.g. Matroska) or are not
+/// known; ffmpeg then decides.
+pub fn remux_codecs_for_extension(ext: &str) -> Option<ContainerCodecs> {
+    const COVER_ART: &[&str] = &["mjpeg", "png"];
+    let codecs = match ext.to_lowercase().as_str() {
+        "mp4" | "m4v" => ContainerCodecs {
+            video: &["h264", "hevc", "av1", "vp9", "mpeg4", "mpeg2video", "mjpeg"],
+            audio: &["aac", "mp3", "ac3", "eac3", "opus", "flac", "alac"],
+            subtitle: &["mov_text"],
+        },
+        "mov" => ContainerCodecs {
+            video: &["h264", "hevc", "prores", "mpeg4", "mjpeg", "dnxhd", "av1"],
+            audio: &["aac", "mp3", "alac", "ac3", "pcm_"],
+            subtitle: &["mov_text"],
+        },
+        "webm" => ContainerCodecs {
+            video: &["vp8", "vp9", "av1"],
+            audio: &["vorbis", "opus"],
+            subtitle: &["webvtt"],
+        },
+        "avi" => ContainerCodecs {
+            video: &["h264", "mpeg4", "msmpeg4v2", "msmpeg4v3", "mjpeg", "mpeg2video", "rawvideo"],
+            audio: &["mp3", "mp2", "ac3", "aac", "pcm_"],
+            subtitle: &[],
+        },
+        "ts" | "m2ts" => ContainerCodecs {
+            video: &["h264", "hevc", "mpeg2video", "mpeg1video"],
+            audio: &["aac", "mp3", "mp2", "ac3", "eac3", "opus"],
+            subtitle: &["dvb_subtitle"],
+        },
+        "mp3" => ContainerCodecs { video: COVER_ART, audio: &["mp3"], subtitle: &[] },
+        "m4a" => ContainerCodecs { video: COVER_ART, audio: &["aac", "alac"], subtitle: &[] },
+        "flac" => ContainerCodecs { video: COVER_ART, audio: &["flac"], subtitle: &[] },
+        "wav" => ContainerCodecs { video: &[], audio: &["pcm_"], subtitle: &[] },
+        "ogg" => ContainerCodecs { video: &[], audio: &["vorbis", "opus", "flac"], subtitle: &[] },
+        "opus" => ContainerCodecs { video: &[], audio: &["opus"], subtitle: &[] },
+        _ => return None,
+    };
+    Some(codecs)
+}
+
+impl RemuxParams {
+    /// Validate the remux parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if self.input.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "input".to_string(),
+                message: "Input path cannot be empty".to_string(),
+            });
+        }
+
+        if self.output.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "output".to_string(),
+                message: "Output path cannot be empty".to_string(),
+            });
+        } else if self.output_extension().is_none() {
+            errors.push(ValidationError {
+                field: "output".to_string(),
+                message: format!(
+                    "Output '{}' has no file extension; the extension selects the container",
+                    self.output
+                ),
+            });
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
         }
-        let _ = tokio::fs::remove_file(&temp_output).await;
-        
-        info!(output = %result, volume = ?volume, "Adjusted audio volume");
-        Ok(result)
     }
 
-    /// Layer multiple audio files.
-    #[instrument(level = "info", skip(self))]
-    pub async fn layer_audio(&self, params: LayerAudioParams) -> Result<String, Error> {
-        if params.inputs.is_empty() {
-            return Err(Error::validation("At least one audio layer is required"));
+    /// Extension of the output path, which selects the container.
+    pub fn output_extension(&self) -> Option<&str> {
+        Path::new(&self.output).extension().and_then(|e| e.to_str())
+    }
+
+    /// Describe the probed streams the output container cannot hold, e.g.
+    /// `audio stream 1 (opus)`. Empty when every stream can be copied.
+    pub fn incompatible_streams(&self, probe: &serde_json::Value) -> Vec<String> {
+        let Some(codecs) = self.output_extension().and_then(remux_codecs_for_extension) else {
+            return Vec::new();
+        };
+        probe
+            .get("streams")
+            .and_then(|s| s.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|stream| {
+                let codec_type = stream.get("codec_type").and_then(|c| c.as_str())?;
+                let codec_name = stream.get("codec_name").and_then(|c| c.as_str()).unwrap_or("unknown");
+                if codecs.accepts(codec_type, codec_name) {
+                    return None;
+                }
+                let index = stream.get("index").and_then(|i| i.as_u64()).unwrap_or(0);
+                Some(format!("{} stream {} ({})", codec_type, index, codec_name))
+            })
+            .collect()
+    }
+
+    /// Build the ffmpeg arguments, copying every video, audio and subtitle stream.
+    pub fn build_args(&self, input: &str, output: &str) -> Vec<String> {
+        [
+            "-i", input,
+            "-map", "0:v?", "-map", "0:a?", "-map", "0:s?",
+            "-c", "copy",
+            output,
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+}
+
+impl SetMetadataParams {
+    /// Validate the metadata parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if self.input.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "input".to_string(),
+                message: "Input path cannot be empty".to_string(),
+            });
         }
-        
-        // Resolve all inputs
-        let mut local_inputs = Vec::new();
-        for layer in &params.inputs {
-            local_inputs.push(self.resolve_input(&layer.path).await?);
+
+        if self.output.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "output".to_string(),
+                message: "Output path cannot be empty".to_string(),
+            });
+        } else if Path::new(&self.output).extension().is_none() {
+            errors.push(ValidationError {
+                field: "output".to_string(),
+                message: format!(
+                    "Output '{}' has no file extension; the extension selects the container",
+                    self.output
+                ),
+            });
         }
-        
-        let ext = Path::new(&params.output)
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("wav");
-        let temp_output = self.temp_output_path(ext);
-        
-        // Build ffmpeg command with amix filter
-        let mut args = Vec::new();
-        
-        // Add all inputs
-        for local_input in &local_inputs {
-            args.push("-i".to_string());
-            args.push(local_input.to_string_lossy().to_string());
+
+        if self.tags.is_empty() && !self.strip_all {
+            errors.push(ValidationError {
+                field: "tags".to_string(),
+                message: "Provide at least one tag or set strip_all".to_string(),
+            });
         }
-        
-        // Build filter complex for mixing with delays and volumes
-        let mut filter_parts = Vec::new();
-        let mut mix_inputs = Vec::new();
-        
-        for (i, layer) in params.inputs.iter().enumerate() {
-            let label = format!("a{}", i);
-            let mut filter = format!("[{}:a]", i);
-            
-            // Add delay if offset > 0
-            if layer.offset_seconds > 0.0 {
-                let delay_ms = (layer.offset_seconds * 1000.0) as i64;
-                filter.push_str(&format!("adelay={}|{}", delay_ms, delay_ms));
-                if layer.volume != 1.0 {
-                    filter.push_str(&format!(",volume={}", layer.volume));
+
+        // ffmpeg splits `-metadata key=value` at the first `=`, so only keys are restricted
+        for (key, value) in self.sorted_tags() {
+            if key.trim().is_empty() || key.contains('=') || key.chars().any(char::is_control) {
+                errors.push(ValidationError {
+                    field: format!("tags.{}", key),
+                    message: "Tag names must be non-empty and contain no '=' or control characters"
+                        .to_string(),
+                });
+            }
+            if value.contains('\0') {
+                errors.push(ValidationError {
+                    field: format!("tags.{}", key),
+                    message: "Tag values cannot contain NUL characters".to_string(),
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// The tags sorted by name, so the ffmpeg arguments are reproducible.
+    pub fn sorted_tags(&self) -> Vec<(&str, &str)> {
+        let mut tags: Vec<(&str, &str)> = self
+            .tags
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        tags.sort_unstable();
+        tags
+    }
+
+    /// Build the ffmpeg arguments, copying every stream.
+    ///
+    /// Each tag is passed as a single `key=value` argument, so values need no
+    /// quoting; ffmpeg is run without a shell.
+    pub fn build_args(&self, input: &str, output: &str) -> Vec<String> {
+        let mut args: Vec<String> = ["-i", input, "-map", "0", "-c", "copy"]
+            .map(String::from)
+            .to_vec();
+        if self.strip_all {
+            args.extend(["-map_metadata", "-1"].map(String::from));
+        }
+        for (key, value) in self.sorted_tags() {
+            args.push("-metadata".to_string());
+            args.push(format!("{}={}", key, value));
+        }
+        args.push(output.to_string());
+        args
+    }
+}
+
+impl FfmpegRunParams {
+    /// Validate the inputs, output and every option against the allowlists.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if self.inputs.is_empty() {
+            errors.push(ValidationError {
+                field: "inputs".to_string(),
+                message: "At least one input is required".to_string(),
+            });
+        }
+        for (i, input) in self.inputs.iter().enumerate() {
+            if let Err(message) = check_run_path(input) {
+                errors.push(ValidationError {
+                    field: format!("inputs[{}]", i),
+                    message,
+                });
+            }
+        }
+
+        if let Err(message) = check_run_path(&self.output) {
+            errors.push(ValidationError {
+                field: "output".to_string(),
+                message,
+            });
+        } else if Path::new(&self.output).extension().is_none() {
+            errors.push(ValidationError {
+                field: "output".to_string(),
+                message: format!(
+                    "Output '{}' has no file extension; the extension selects the container",
+                    self.output
+                ),
+            });
+        }
+
+        // Errors point at the value when the option itself is allowed
+        let mut args = self.args.iter().enumerate();
+        while let Some((i, option)) = args.next() {
+            let result = if FFMPEG_RUN_FLAG_OPTIONS.contains(&option.as_str()) {
+                Ok(())
+            } else if FFMPEG_RUN_VALUE_OPTIONS.contains(&option.as_str()) {
+                match args.next() {
+                    Some((j, value)) => check_run_option_value(option, value).map_err(|e| (j, e)),
+                    None => Err((i, format!("{} requires a value", option))),
                 }
-            } else if layer.volume != 1.0 {
-                filter.push_str(&format!("volume={}", layer.volume));
             } else {
-                filter.push_str("anull");
+                Err((
+                    i,
+                    format!(
+                        "'{}' is not an allowed option. Allowed: {}, {}",
+                        option,
+                        FFMPEG_RUN_VALUE_OPTIONS.join(", "),
+                        FFMPEG_RUN_FLAG_OPTIONS.join(", ")
+                    ),
+                ))
+            };
+            if let Err((index, message)) = result {
+                errors.push(ValidationError {
+                    field: format!("args[{}]", index),
+                    message,
+                });
             }
-            
-            filter.push_str(&format!("[{}]", label));
-            filter_parts.push(filter);
-            mix_inputs.push(format!("[{}]", label));
         }
-        
-        // Add amix filter
-        let mix_filter = format!(
-            "{}amix=inputs={}:duration=longest",
-            mix_inputs.join(""),
-            params.inputs.len()
-        );
-        filter_parts.push(mix_filter);
-        
-        let filter_complex = filter_parts.join(";");
-        
-        args.extend([
-            "-filter_complex".to_string(),
-            filter_complex,
-            temp_output.to_string_lossy().to_string(),
-        ]);
-        
-        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-        self.run_ffmpeg(&args_refs).await?;
-        
-        let result = self.handle_output(&temp_output, &params.output).await?;
-        
-        // Clean up temp files
-        for (i, layer) in params.inputs.iter().enumerate() {
-            if Self::is_gcs_uri(&layer.path) {
-                let _ = tokio::fs::remove_file(&local_inputs[i]).await;
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// The video encoder the output will use: the last `-c:v`, `-codec:v` or
+    /// `-c` in `args`, else the default for the output extension. `None` when
+    /// `-vn` drops video.
+    pub fn video_codec(&self) -> Option<&str> {
+        if self.args.iter().any(|a| a == "-vn") {
+            return None;
+        }
+        let explicit = self
+            .args
+            .windows(2)
+            .rev()
+            .find(|pair| matches!(pair[0].as_str(), "-c:v" | "-codec:v" | "-c"))
+            .map(|pair| pair[1].as_str());
+        explicit.or_else(|| {
+            let ext = Path::new(&self.output)
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("mp4");
+            default_codecs_for_extension(ext).0
+        })
+    }
+
+    /// The output length requested with `-t` (or, without it, `-to`), in seconds.
+    ///
+    /// Accepts ffmpeg's `SS[.m]`, `MM:SS[.m]` and `HH:MM:SS[.m]` forms.
+    pub fn requested_duration(&self) -> Option<f64> {
+        let last = |name: &str| {
+            self.args
+                .windows(2)
+                .rev()
+                .find(|pair| pair[0] == name)
+                .map(|pair| pair[1].as_str())
+        };
+        last("-t").or_else(|| last("-to")).and_then(parse_ffmpeg_time)
+    }
+
+    /// Check that a requested `-t`/`-to` does not exceed `max_duration` seconds.
+    pub fn check_duration(&self, max_duration: f64) -> Result<(), String> {
+        match self.requested_duration() {
+            Some(requested) if requested > max_duration => Err(format!(
+                "Requested output length {}s exceeds the {}s limit ({})",
+                requested, max_duration, MAX_OUTPUT_DURATION_ENV
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Build the ffmpeg arguments: each input, then `args`, then the output.
+    ///
+    /// Each input is opened with `-protocol_whitelist file`, so a local
+    /// playlist or concat script (`.m3u8`, `.ffconcat`) cannot pull in other
+    /// files or URLs.
+    ///
+    /// When the output is H.264/H.265 and a `-vf` chain resizes the video,
+    /// [`EVEN_DIMENSIONS_FILTER`] is appended to the chain so odd sizes from
+    /// e.g. `scale=iw/3:-1` still encode. `-filter_complex` graphs are passed
+    /// through unchanged.
+    ///
+    /// Unless `args` already bound the length with `-t` or `-to` (checked by
+    /// [`FfmpegRunParams::check_duration`]), `-t max_duration` is added before
+    /// the output so filters such as `apad` or `tpad` cannot run forever.
+    pub fn build_args(&self, inputs: &[String], output: &str, max_duration: f64) -> Vec<String> {
+        let even = self.video_codec().is_some_and(requires_even_dimensions);
+        let mut args = Vec::with_capacity(inputs.len() * 4 + self.args.len() + 1);
+        for input in inputs {
+            args.extend(["-protocol_whitelist", "file", "-i"].map(String::from));
+            args.push(input.clone());
+        }
+        let mut options = self.args.iter();
+        while let Some(option) = options.next() {
+            args.push(option.clone());
+            if FFMPEG_RUN_VALUE_OPTIONS.contains(&option.as_str()) {
+                let Some(value) = options.next() else { break };
+                let resizes = || {
+                    filtergraph_names(value).is_ok_and(|names| {
+                        names.iter().any(|n| FFMPEG_RUN_RESIZING_FILTERS.contains(&n.as_str()))
+                    })
+                };
+                if even && matches!(option.as_str(), "-vf" | "-filter:v") && resizes() {
+                    args.push(format!("{},{}", value, EVEN_DIMENSIONS_FILTER));
+                } else {
+                    args.push(value.clone());
+                }
             }
         }
-        let _ = tokio::fs::remove_file(&temp_output).await;
-        
-        info!(output = %result, layers = params.inputs.len(), "Layered audio files");
-        Ok(result)
+        if self.requested_duration().is_none() {
+            args.push("-t".to_string());
+            args.push(max_duration.to_string());
+        }
+        args.push(output.to_string());
+        args
+    }
+
+    /// Estimate the output from the largest and longest input, cut to a
+    /// requested `-t`/`-to`. Filters can change both, so this is only a first
+    /// check against the output limits; the `-t` cap bounds the rest.
+    pub fn estimate_output(&self, inputs: &[ProbedInput]) -> OutputEstimate {
+        let longest = inputs
+            .iter()
+            .filter_map(|i| i.duration_seconds)
+            .reduce(f64::max);
+        OutputEstimate {
+            dimensions: inputs
+                .iter()
+                .filter_map(|i| i.dimensions)
+                .max_by_key(|&(w, h)| u64::from(w) * u64::from(h)),
+            duration_seconds: match (longest, self.requested_duration()) {
+                (Some(longest), Some(requested)) => Some(longest.min(requested)),
+                (longest, _) => longest,
+            },
+        }
+    }
+}
+
+/// Escape a value for an ffmetadata file, where `=`, `;`, `#`, `\` and
+/// newlines are special.
+fn escape_ffmetadata(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Build an ffmetadata file with one chapter per clip, placed back to back.
+///
+/// Times are written in milliseconds; each chapter ends where the next
+/// begins so rounding never leaves gaps.
+pub fn chapters_ffmetadata(titles: &[String], durations: &[f64]) -> String {
+    let mut content = String::from(";FFMETADATA1\n");
+    let mut start_ms = 0u64;
+    let mut elapsed = 0.0;
+    for (title, duration) in titles.iter().zip(durations) {
+        elapsed += duration;
+        let end_ms = (elapsed * 1000.0).round() as u64;
+        content.push_str(&format!(
+            "[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            start_ms,
+            end_ms,
+            escape_ffmetadata(title)
+        ));
+        start_ms = end_ms;
+    }
+    content
+}
+
+/// Reject empty paths and protocol URLs (`file:`, `http:`, `concat:`, ...)
+/// so `ffmpeg_run` only reads and writes plain files and GCS objects.
+fn check_run_path(path: &str) -> Result<(), String> {
+    if path.trim().is_empty() {
+        return Err("Path cannot be empty".to_string());
+    }
+    if path.starts_with('-') {
+        return Err(format!("Path '{}' must not start with '-'", path));
+    }
+    let first_component = path.split('/').next().unwrap_or_default();
+    if !AVToolHandler::is_gcs_uri(path) && first_component.contains(':') {
+        return Err(format!(
+            "'{}' looks like a protocol URL; only local paths and gs:// URIs are allowed",
+            path
+        ));
+    }
+    Ok(())
+}
+
+/// Check the value of an allowlisted `ffmpeg_run` option.
+fn check_run_option_value(option: &str, value: &str) -> Result<(), String> {
+    // Non-empty and made only of ASCII letters, digits and `extra`
+    let word = |extra: &str| {
+        !value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || extra.contains(c))
+    };
+    let number = |extra: &str| {
+        !value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_ascii_digit() || extra.contains(c))
+    };
+    let valid = match option {
+        "-vf" | "-af" | "-filter:v" | "-filter:a" | "-filter_complex" => {
+            return check_filtergraph(value).map_err(|e| format!("{}: {}", option, e));
+        }
+        "-c" | "-c:v" | "-codec:v" | "-c:a" | "-codec:a" => {
+            SUPPORTED_VIDEO_CODECS.contains(&value) || SUPPORTED_AUDIO_CODECS.contains(&value)
+        }
+        "-preset" => SUPPORTED_PRESETS.contains(&value),
+        "-b:v" | "-b:a" | "-maxrate" | "-bufsize" => {
+            let digits = value.trim_end_matches(['k', 'K', 'M']);
+            !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+        }
+        "-crf" | "-q:v" | "-q:a" | "-r" | "-ar" | "-ac" | "-frames:v" | "-aspect" => number("./:"),
+        "-ss" | "-t" | "-to" => number(".:"),
+        "-s" => value.split_once('x').is_some_and(|(w, h)| {
+            [w, h]
+                .iter()
+                .all(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        }),
+        "-map" => word(":?[]_"),
+        "-movflags" => word("+-_"),
+        // -tune, -profile:v, -pix_fmt
+        _ => word("_"),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid value '{}' for {}", value, option))
+    }
+}
+
+/// Parse an ffmpeg time duration (`SS[.m]`, `MM:SS[.m]` or `HH:MM:SS[.m]`) into seconds.
+fn parse_ffmpeg_time(value: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in value.split(':') {
+        let n: f64 = part.parse().ok()?;
+        seconds = seconds * 60.0 + n;
+    }
+    (value.split(':').count() <= 3 && seconds.is_finite()).then_some(seconds)
+}
+
+/// Check that every filter in a filtergraph is allowlisted and that no
+/// option reads its value from a file (ffmpeg 7's `/option=path` syntax).
+fn check_filtergraph(graph: &str) -> Result<(), String> {
+    for (name, options) in parse_filtergraph(graph)? {
+        if !FFMPEG_RUN_FILTERS.contains(&name.as_str()) {
+            return Err(format!("filter '{}' is not allowed", name));
+        }
+        if options.split(':').any(|option| option.trim_start().starts_with('/')) {
+            return Err(format!(
+                "filter '{}' loads an option from a file ('/option=path'), which is not allowed",
+                name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// List the filter names in a filtergraph, in order.
+fn filtergraph_names(graph: &str) -> Result<Vec<String>, String> {
+    Ok(parse_filtergraph(graph)?.into_iter().map(|(name, _)| name).collect())
+}
+
+/// Split a filtergraph into each filter's name and option string, in order.
+///
+/// Splits on top-level `,` and `;` the way ffmpeg does, honoring `'...'`
+/// quoting and `\` escapes, then strips `[label]` pads to find each filter
+/// name. The option string is whatever follows the name's `=`.
+fn parse_filtergraph(graph: &str) -> Result<Vec<(String, String)>, String> {
+    let mut filters = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = graph.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                current.extend(chars.next());
+            }
+            '\'' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            ',' | ';' if !quoted => filters.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quote in filtergraph".to_string());
+    }
+    filters.push(current);
+
+    let mut parsed = Vec::with_capacity(filters.len());
+    for filter in &filters {
+        let mut rest = filter.trim();
+        while let Some(label) = rest.strip_prefix('[') {
+            let end = label
+                .find(']')
+                .ok_or_else(|| format!("unterminated label in '{}'", filter.trim()))?;
+            rest = label[end + 1..].trim_start();
+        }
+        let name_len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let (name, after) = rest.split_at(name_len);
+        if name.is_empty() || !(after.is_empty() || after.starts_with(['=', '@', '['])) {
+            return Err(format!(
+                "could not read a filter name from '{}'",
+                filter.trim()
+            ));
+        }
+        let options = after.split_once('=').map_or("", |(_, options)| options);
+        parsed.push((name.to_string(), options.to_string()));
+    }
+    Ok(parsed)
+}
+
+impl PipParams {
+    /// Validate the picture-in-picture parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if !(self.scale > 0.0 && self.scale <= 1.0) {
+            errors.push(ValidationError {
+                field: "scale".to_string(),
+                message: format!("scale must be greater than 0.0 and at most 1.0, got {}", self.scale),
+            });
+        }
+
+        if !VALID_PIP_POSITIONS.contains(&self.position.as_str()) {
+            errors.push(ValidationError {
+                field: "position".to_string(),
+                message: format!(
+                    "Invalid position '{}'. Valid options: {}",
+                    self.position,
+                    VALID_PIP_POSITIONS.join(", ")
+                ),
+            });
+        }
+
+        if !VALID_PIP_AUDIO_MODES.contains(&self.audio_mode.as_str()) {
+            errors.push(ValidationError {
+                field: "audio_mode".to_string(),
+                message: format!(
+                    "Invalid audio_mode '{}'. Valid options: {}",
+                    self.audio_mode,
+                    VALID_PIP_AUDIO_MODES.join(", ")
+                ),
+            });
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Overlay width in pixels for a main video of the given width, rounded to an even number.
+    pub fn overlay_width(&self, main_width: u32) -> u32 {
+        let width = (main_width as f32 * self.scale).round() as u32;
+        (width / 2 * 2).max(2)
+    }
+
+    /// Overlay x/y expressions for the configured position and margin.
+    pub fn overlay_position(&self) -> (String, String) {
+        let m = self.margin;
+        match self.position.as_str() {
+            "top_left" => (format!("{}", m), format!("{}", m)),
+            "top_right" => (format!("W-w-{}", m), format!("{}", m)),
+            "bottom_left" => (format!("{}", m), format!("H-h-{}", m)),
+            "center" => ("(W-w)/2".to_string(), "(H-h)/2".to_string()),
+            _ => (format!("W-w-{}", m), format!("H-h-{}", m)),
+        }
+    }
+
+    /// Whether both audio tracks should be mixed.
+    fn mixes_audio(&self, main_has_audio: bool, overlay_has_audio: bool) -> bool {
+        self.audio_mode == "mix" && main_has_audio && overlay_has_audio
+    }
+
+    /// Whether the overlay's audio stands in for a silent main video.
+    fn uses_overlay_audio(&self, main_has_audio: bool, overlay_has_audio: bool) -> bool {
+        self.audio_mode == "mix" && !main_has_audio && overlay_has_audio
+    }
+
+    /// Build the filter graph compositing the overlay onto the main video.
+    ///
+    /// Once the overlay ends it is removed and the main video shows through
+    /// (`eof_action=pass`), so the output always runs for the main video's length.
+    /// Overlay audio used on its own is padded with silence (`apad`) so it
+    /// never cuts the output short.
+    pub fn filter_complex(&self, main_width: u32, main_has_audio: bool, overlay_has_audio: bool) -> String {
+        let (x, y) = self.overlay_position();
+        let mut filter = format!(
+            "[1:v]scale={}:-2[pip];[0:v][pip]overlay=x={}:y={}:eof_action=pass[vout]",
+            self.overlay_width(main_width),
+            x,
+            y
+        );
+
+        if self.mixes_audio(main_has_audio, overlay_has_audio) {
+            filter.push_str(";[0:a][1:a]amix=inputs=2:duration=first:dropout_transition=0[aout]");
+        } else if self.uses_overlay_audio(main_has_audio, overlay_has_audio) {
+            filter.push_str(";[1:a]apad[aout]");
+        }
+
+        filter
+    }
+
+    /// Build the ffmpeg arguments for compositing `overlay` onto `main` into `output`.
+    pub fn build_args(
+        &self,
+        main: &str,
+        overlay: &str,
+        output: &str,
+        main_width: u32,
+        main_has_audio: bool,
+        overlay_has_audio: bool,
+    ) -> Vec<String> {
+        let mut args: Vec<String> = vec![
+            "-i".to_string(), main.to_string(),
+            "-i".to_string(), overlay.to_string(),
+            "-filter_complex".to_string(),
+            self.filter_complex(main_width, main_has_audio, overlay_has_audio),
+            "-map".to_string(), "[vout]".to_string(),
+        ];
+
+        if self.mixes_audio(main_has_audio, overlay_has_audio) {
+            args.extend(["-map", "[aout]", "-c:a", "aac"].map(String::from));
+        } else if main_has_audio {
+            args.extend(["-map", "0:a", "-c:a", "copy"].map(String::from));
+        } else if self.uses_overlay_audio(main_has_audio, overlay_has_audio) {
+            // The padded audio never ends, so -shortest stops at the main video's end
+            args.extend(["-map", "[aout]", "-c:a", "aac", "-shortest"].map(String::from));
+        }
+
+        args.push(output.to_string());
+        args
+    }
+}
+
+impl VideoToGifParams {
+    /// Validate the GIF parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if let Some(loop_count) = self.loop_count {
+            if loop_count < -1 {
+                errors.push(ValidationError {
+                    field: "loop_count".to_string(),
+                    message: format!(
+                        "loop_count must be -1 (play once), 0 (loop forever), or a positive repeat count, got {}",
+                        loop_count
+                    ),
+                });
+            }
+        }
+
+        if let Some(fade) = self.fade_duration {
+            if !fade.is_finite() || fade <= 0.0 {
+                errors.push(ValidationError {
+                    field: "fade_duration".to_string(),
+                    message: format!("fade_duration must be a finite number greater than 0, got {}", fade),
+                });
+            } else if let Some(duration) = self.duration {
+                if fade * 2.0 > duration {
+                    errors.push(ValidationError {
+                        field: "fade_duration".to_string(),
+                        message: format!(
+                            "fade_duration {} is too long for a {}s clip (fade in and out must fit)",
+                            fade, duration
+                        ),
+                    });
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Build the video filter chain.
+    ///
+    /// `clip_duration` is the length of the converted segment; the fade-out is
+    /// skipped when it is unknown.
+    pub fn filter(&self, clip_duration: Option<f64>) -> String {
+        let mut filters = vec![format!("fps={}", self.fps)];
+        if let Some(width) = self.width {
+            filters.push(format!("scale={}:-1:flags=lanczos", width));
+        }
+        if let Some(fade) = self.fade_duration {
+            filters.push(format!("fade=t=in:st=0:d={}", fade));
+            if let Some(clip) = clip_duration.filter(|c| *c > fade) {
+                filters.push(format!("fade=t=out:st={:.3}:d={}", clip - fade, fade));
+            }
+        }
+        filters.join(",")
+    }
+
+    /// Build the ffmpeg arguments for converting `input` into a GIF at `output`.
+    pub fn build_args(&self, input: &str, output: &str, clip_duration: Option<f64>) -> Vec<String> {
+        let mut args: Vec<String> = Vec::new();
+
+        // Seek before the input for fast trimming
+        if let Some(start) = self.start_time {
+            args.extend(["-ss".to_string(), format!("{}", start)]);
+        }
+
+        args.extend(["-i".to_string(), input.to_string()]);
+
+        if let Some(duration) = self.duration {
+            args.extend(["-t".to_string(), format!("{}", duration)]);
+        }
+
+        args.extend(["-vf".to_string(), self.filter(clip_duration)]);
+
+        // Loop count is a gif muxer option, so it goes right before the output
+        if let Some(loop_count) = self.loop_count {
+            args.extend(["-loop".to_string(), loop_count.to_string()]);
+        }
+
+        args.push(output.to_string());
+        args
+    }
+
+    /// Estimate the output: scaled to `width` if set, trimmed by `start_time`
+    /// and `duration`.
+    pub fn estimate_output(&self, input: &ProbedInput) -> OutputEstimate {
+        let dimensions = match self.width {
+            Some(width) => input.scaled_to_width(width),
+            None => input.dimensions,
+        };
+        let duration_seconds = self.duration.or_else(|| {
+            input
+                .duration_seconds
+                .map(|total| (total - self.start_time.unwrap_or(0.0)).max(0.0))
+        });
+        OutputEstimate {
+            dimensions,
+            duration_seconds,
+        }
+    }
+}
+
+impl ReverseParams {
+    /// Validate the reverse parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if let Some(max) = self.max_duration {
+            if !max.is_finite() || max <= 0.0 {
+                errors.push(ValidationError {
+                    field: "max_duration".to_string(),
+                    message: format!("max_duration must be a finite number greater than 0, got {}", max),
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// The input duration limit: `server_max`, lowered by `max_duration`.
+    pub fn effective_max_duration(&self, server_max: f64) -> f64 {
+        self.max_duration.map_or(server_max, |max| max.min(server_max))
+    }
+
+    /// Check the probed input duration against the limit from
+    /// [`ReverseParams::effective_max_duration`].
+    ///
+    /// An input whose duration cannot be probed is rejected, since the
+    /// memory bound cannot be enforced without it.
+    pub fn check_duration(&self, duration: Option<f64>, server_max: f64) -> Result<(), Error> {
+        let Some(duration) = duration else {
+            return Err(Error::validation(
+                "Cannot determine the input duration, which is needed to bound the in-memory reverse",
+            ));
+        };
+        let max = self.effective_max_duration(server_max);
+        if duration > max {
+            return Err(Error::validation(format!(
+                "Input is {:.1}s long, which exceeds the {:.1}s limit ({}). \
+                 Reversing buffers the whole stream in memory; trim the input first.",
+                duration, max, REVERSE_MAX_DURATION_ENV
+            )));
+        }
+        Ok(())
+    }
+
+    /// Build the ffmpeg arguments, reversing whichever streams are present.
+    pub fn build_args(&self, input: &str, output: &str, has_video: bool, has_audio: bool) -> Vec<String> {
+        let mut args = vec!["-i".to_string(), input.to_string()];
+
+        if has_video {
+            args.extend(["-vf", "reverse"].map(String::from));
+        }
+        if has_audio {
+            args.extend(["-af", "areverse"].map(String::from));
+        }
+
+        args.push(output.to_string());
+        args
+    }
+}
+
+impl DetectScenesParams {
+    /// Validate the detection parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if !VALID_DETECT_MODES.contains(&self.mode.as_str()) {
+            errors.push(ValidationError {
+                field: "mode".to_string(),
+                message: format!(
+                    "Invalid mode '{}'. Valid options: {}",
+                    self.mode,
+                    VALID_DETECT_MODES.join(", ")
+                ),
+            });
+        }
+
+        if let Some(threshold) = self.threshold {
+            if !(0.0..=1.0).contains(&threshold) {
+                errors.push(ValidationError {
+                    field: "threshold".to_string(),
+                    message: format!("threshold must be between 0 and 1, got {}", threshold),
+                });
+            }
+        }
+
+        if self.min_black_duration.is_nan() || self.min_black_duration <= 0.0 {
+            errors.push(ValidationError {
+                field: "min_black_duration".to_string(),
+                message: format!(
+                    "min_black_duration must be greater than 0, got {}",
+                    self.min_black_duration
+                ),
+            });
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Build the detection filter for the configured mode.
+    pub fn filter(&self) -> String {
+        if self.mode == "black" {
+            format!(
+                "blackdetect=d={}:pic_th={}",
+                self.min_black_duration,
+                self.threshold.unwrap_or(DEFAULT_BLACK_PICTURE_THRESHOLD)
+            )
+        } else {
+            format!(
+                "select='gt(scene,{})',showinfo",
+                self.threshold.unwrap_or(DEFAULT_SCENE_THRESHOLD)
+            )
+        }
+    }
+
+    /// Build the ffmpeg arguments. Frames are decoded and discarded; the
+    /// detections are read back from stderr.
+    pub fn build_args(&self, input: &str) -> Vec<String> {
+        vec![
+            "-i".to_string(),
+            input.to_string(),
+            "-vf".to_string(),
+            self.filter(),
+            "-an".to_string(),
+            "-f".to_string(),
+            "null".to_string(),
+            "-".to_string(),
+        ]
+    }
+
+    /// Parse ffmpeg's stderr into a detection result.
+    pub fn parse_output(&self, stderr: &str) -> SceneDetectionResult {
+        if self.mode == "black" {
+            let black_segments = parse_blackdetect_segments(stderr);
+            SceneDetectionResult {
+                mode: self.mode.clone(),
+                timestamps: black_segments.iter().map(|s| s.start).collect(),
+                black_segments,
+            }
+        } else {
+            SceneDetectionResult {
+                mode: self.mode.clone(),
+                timestamps: parse_showinfo_timestamps(stderr),
+                black_segments: Vec::new(),
+            }
+        }
+    }
+}
+
+/// Extract a numeric `key:value` field from an ffmpeg log line.
+fn log_field(line: &str, key: &str) -> Option<f64> {
+    let start = line.find(key)? + key.len();
+    line[start..]
+        .split_whitespace()
+        .next()
+        .and_then(|v| v.parse().ok())
+}
+
+/// Parse frame timestamps (`pts_time`) from `showinfo` filter output.
+pub fn parse_showinfo_timestamps(stderr: &str) -> Vec<f64> {
+    stderr
+        .lines()
+        .filter(|line| line.contains("Parsed_showinfo"))
+        .filter_map(|line| log_field(line, "pts_time:"))
+        .collect()
+}
+
+/// Parse black segments from `blackdetect` filter output.
+pub fn parse_blackdetect_segments(stderr: &str) -> Vec<BlackSegment> {
+    stderr
+        .lines()
+        .filter(|line| line.contains("black_start:"))
+        .filter_map(|line| {
+            let start = log_field(line, "black_start:")?;
+            let end = log_field(line, "black_end:")?;
+            let duration = log_field(line, "black_duration:").unwrap_or(end - start);
+            Some(BlackSegment { start, end, duration })
+        })
+        .collect()
+}
+
+impl ContactSheetParams {
+    /// Validate the contact sheet parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if self.columns == 0 || self.columns > MAX_CONTACT_SHEET_GRID {
+            errors.push(ValidationError {
+                field: "columns".to_string(),
+                message: format!(
+                    "columns must be between 1 and {}, got {}",
+                    MAX_CONTACT_SHEET_GRID, self.columns
+                ),
+            });
+        }
+
+        if self.rows == 0 || self.rows > MAX_CONTACT_SHEET_GRID {
+            errors.push(ValidationError {
+                field: "rows".to_string(),
+                message: format!(
+                    "rows must be between 1 and {}, got {}",
+                    MAX_CONTACT_SHEET_GRID, self.rows
+                ),
+            });
+        }
+
+        if self.width == 0 {
+            errors.push(ValidationError {
+                field: "width".to_string(),
+                message: "width must be greater than 0".to_string(),
+            });
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Build the video filter chain for a clip of the given duration.
+    ///
+    /// The sample rate is written as the fraction `tiles/duration` rather than
+    /// `1/interval`, so short clips are not rounded to a zero interval.
+    pub fn filter(&self, duration: f64) -> String {
+        let tiles = (self.columns * self.rows).max(1);
+        let fps = if duration > 0.0 {
+            format!("{}/{}", tiles, duration)
+        } else {
+            "1".to_string()
+        };
+        format!(
+            "fps={},scale={}:-2,tile={}x{}",
+            fps,
+            self.width,
+            self.columns,
+            self.rows
+        )
+    }
+
+    /// Estimate the output: a single image of `columns` x `rows` tiles, each
+    /// `width` pixels wide.
+    pub fn estimate_output(&self, input: &ProbedInput) -> OutputEstimate {
+        OutputEstimate {
+            dimensions: input
+                .scaled_to_width(self.width)
+                .map(|(w, h)| (w.saturating_mul(self.columns), h.saturating_mul(self.rows))),
+            duration_seconds: None,
+        }
+    }
+}
+
+impl ImageToVideoParams {
+    /// Validate the image-to-video parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if !AVToolHandler::is_still_image(Path::new(&self.image_input)) {
+            errors.push(ValidationError {
+                field: "image_input".to_string(),
+                message: format!(
+                    "image_input must be a still image (png, jpg, jpeg, webp or bmp), got '{}'",
+                    self.image_input
+                ),
+            });
+        }
+
+        // The color is spliced into the filter graph, so only allow names and hex values
+        let color = self.waveform_color.strip_prefix('#').or_else(|| self.waveform_color.strip_prefix("0x"));
+        let valid_color = match color {
+            Some(hex) => matches!(hex.len(), 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit()),
+            None => !self.waveform_color.is_empty() && self.waveform_color.chars().all(|c| c.is_ascii_alphabetic()),
+        };
+        if !valid_color {
+            errors.push(ValidationError {
+                field: "waveform_color".to_string(),
+                message: format!(
+                    "waveform_color must be a color name or a hex value like 0x1db954, got '{}'",
+                    self.waveform_color
+                ),
+            });
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Build the ffmpeg arguments for `image` shown for the length of `audio`.
+    ///
+    /// The image is looped and the output stops with the audio. Its dimensions
+    /// are rounded down to even numbers, which H.264 requires. `image_width` is
+    /// only used to size the waveform.
+    pub fn build_args(&self, image: &str, audio: &str, output: &str, image_width: u32) -> Vec<String> {
+        let mut args: Vec<String> = vec![
+            "-loop".to_string(), "1".to_string(),
+            "-i".to_string(), image.to_string(),
+            "-i".to_string(), audio.to_string(),
+        ];
+
+        if self.waveform {
+            let width = (image_width / 2 * 2).max(2);
+            let height = (width / 8 * 2).max(2);
+            args.extend([
+                "-filter_complex".to_string(),
+                format!(
+                    "[0:v]{}[bg];[1:a]showwaves=s={}x{}:mode=cline:colors={}[wave];\
+                     [bg][wave]overlay=0:H-h:shortest=1,format=yuv420p[vout]",
+                    EVEN_DIMENSIONS_FILTER, width, height, self.waveform_color
+                ),
+                "-map".to_string(), "[vout]".to_string(),
+                "-c:v".to_string(), "libx264".to_string(),
+            ]);
+        } else {
+            args.extend([
+                "-vf".to_string(), format!("{},format=yuv420p", EVEN_DIMENSIONS_FILTER),
+                "-map".to_string(), "0:v:0".to_string(),
+                "-c:v".to_string(), "libx264".to_string(),
+                "-tune".to_string(), "stillimage".to_string(),
+            ]);
+        }
+
+        args.extend(["-map", "1:a:0", "-c:a", "aac", "-shortest", output].map(String::from));
+        args
+    }
+
+    /// Estimate the output: the image rounded down to even dimensions, as
+    /// long as the audio.
+    pub fn estimate_output(&self, image: &ProbedInput, audio: &ProbedInput) -> OutputEstimate {
+        OutputEstimate {
+            dimensions: image.dimensions.map(|(w, h)| (w / 2 * 2, h / 2 * 2)),
+            duration_seconds: audio.duration_seconds,
+        }
+    }
+}
+
+impl StorageDownloadParams {
+    /// Validate the download parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if let Err(e) = GcsUri::parse(&self.uri) {
+            errors.push(ValidationError {
+                field: "uri".to_string(),
+                message: e.to_string(),
+            });
+        }
+
+        if self.output_path.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "output_path".to_string(),
+                message: "output_path cannot be empty".to_string(),
+            });
+        } else if AVToolHandler::is_gcs_uri(&self.output_path) {
+            errors.push(ValidationError {
+                field: "output_path".to_string(),
+                message: format!("output_path must be a local path, got '{}'", self.output_path),
+            });
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl StorageUploadParams {
+    /// Validate the upload parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if self.input_path.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "input_path".to_string(),
+                message: "input_path cannot be empty".to_string(),
+            });
+        } else if AVToolHandler::is_gcs_uri(&self.input_path) {
+            errors.push(ValidationError {
+                field: "input_path".to_string(),
+                message: format!("input_path must be a local path, got '{}'", self.input_path),
+            });
+        }
+
+        if let Err(e) = GcsUri::parse(&self.uri) {
+            errors.push(ValidationError {
+                field: "uri".to_string(),
+                message: e.to_string(),
+            });
+        }
+
+        if let Some(content_type) = &self.content_type {
+            let valid = content_type
+                .split_once('/')
+                .is_some_and(|(kind, subtype)| !kind.is_empty() && !subtype.is_empty() && !subtype.contains('/'));
+            if !valid {
+                errors.push(ValidationError {
+                    field: "content_type".to_string(),
+                    message: format!("content_type must look like 'type/subtype', got '{}'", content_type),
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+// =============================================================================
+// Temp File Naming
+// =============================================================================
+
+/// Source of unique stems for temporary file names.
+///
+/// Production uses [`UuidTempNamer`]; tests can inject [`SequentialTempNamer`]
+/// so temp paths, and therefore ffmpeg argv, are predictable.
+pub trait TempNamer: Send + Sync {
+    /// Return a stem that is unique within the handler's temp directory.
+    fn next_name(&self) -> String;
+}
+
+/// Names temp files with random v4 UUIDs.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct UuidTempNamer;
+
+impl TempNamer for UuidTempNamer {
+    fn next_name(&self) -> String {
+        Uuid::new_v4().to_string()
+    }
+}
+
+/// Names temp files `<prefix>-0`, `<prefix>-1`, ... in call order.
+#[derive(Debug)]
+pub struct SequentialTempNamer {
+    prefix: String,
+    counter: AtomicU64,
+}
+
+impl SequentialTempNamer {
+    /// Create a namer whose stems start with `prefix`.
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            counter: AtomicU64::new(0),
+        }
+    }
+}
+
+impl TempNamer for SequentialTempNamer {
+    fn next_name(&self) -> String {
+        let n = self.counter.fetch_add(1, Ordering::Relaxed);
+        format!("{}-{}", self.prefix, n)
+    }
+}
+
+// =============================================================================
+// FFmpeg Availability
+// =============================================================================
+
+pub use adk_rust_mcp_common::ffmpeg::{
+    FFMPEG_PATH_ENV, FfmpegVersion, MIN_FFMPEG_VERSION, check_ffmpeg, check_ffmpeg_at, ffmpeg_binary,
+    parse_ffmpeg_version,
+};
+
+// =============================================================================
+// Hardware Acceleration
+// =============================================================================
+
+/// Environment variable selecting hardware acceleration (`none`, `cuda`,
+/// `vaapi` or `videotoolbox`; default `none`).
+pub use adk_rust_mcp_common::config::FFMPEG_HWACCEL_ENV;
+
+/// DRM render node used for VAAPI decoding and encoding.
+pub const VAAPI_DEVICE: &str = "/dev/dri/renderD128";
+
+/// Fragments of ffmpeg errors raised when a hardware decoder or encoder
+/// cannot be initialized, which trigger a retry in software.
+const HWACCEL_FAILURE_MARKERS: &[&str] = &[
+    "Unknown encoder 'h264_",
+    "Unknown encoder 'hevc_",
+    "Device creation failed",
+    "Device setup failed",
+    "Failed setup for format",
+    "hwaccel initialisation returned error",
+    "No device available for decoder",
+    "Cannot load libcuda",
+    "Cannot load libnvidia-encode",
+    "No capable devices found",
+    "OpenEncodeSessionEx failed",
+    "Failed to initialise VAAPI connection",
+    "No usable encoding profile found",
+    "Error creating a VideoToolbox",
+    "Error while opening encoder",
+];
+
+/// Hardware acceleration used for decoding and H.264/H.265 encoding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HwAccel {
+    /// Software decoding and encoding.
+    #[default]
+    None,
+    /// NVIDIA NVDEC/NVENC.
+    Cuda,
+    /// VAAPI on Linux (Intel/AMD), using [`VAAPI_DEVICE`].
+    Vaapi,
+    /// Apple VideoToolbox.
+    VideoToolbox,
+}
+
+impl HwAccel {
+    /// Parse a `FFMPEG_HWACCEL` value (case-insensitive).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "none" => Some(Self::None),
+            "cuda" => Some(Self::Cuda),
+            "vaapi" => Some(Self::Vaapi),
+            "videotoolbox" => Some(Self::VideoToolbox),
+            _ => None,
+        }
+    }
+
+    /// Read `FFMPEG_HWACCEL` (or `[ffmpeg] hwaccel`) from `config`, falling
+    /// back to software with a warning for unknown values.
+    pub fn from_config(config: &Config) -> Self {
+        let Some(value) = config.ffmpeg_hwaccel.as_deref() else {
+            return Self::None;
+        };
+        Self::parse(value).unwrap_or_else(|| {
+            warn!(
+                value = %value,
+                "Unknown {} value; expected none, cuda, vaapi or videotoolbox. Using software encoding",
+                FFMPEG_HWACCEL_ENV
+            );
+            Self::None
+        })
+    }
+
+    /// Input options enabling hardware decoding; they go before `-i`.
+    pub fn decode_args(self) -> &'static [&'static str] {
+        match self {
+            Self::None => &[],
+            Self::Cuda => &["-hwaccel", "cuda"],
+            Self::Vaapi => &["-hwaccel", "vaapi", "-vaapi_device", VAAPI_DEVICE],
+            Self::VideoToolbox => &["-hwaccel", "videotoolbox"],
+        }
+    }
+
+    /// Hardware encoder replacing a software `codec`, if there is one.
+    pub fn encoder_for(self, codec: &str) -> Option<&'static str> {
+        match (self, codec) {
+            (Self::Cuda, "libx264") => Some("h264_nvenc"),
+            (Self::Cuda, "libx265") => Some("hevc_nvenc"),
+            (Self::Vaapi, "libx264") => Some("h264_vaapi"),
+            (Self::Vaapi, "libx265") => Some("hevc_vaapi"),
+            (Self::VideoToolbox, "libx264") => Some("h264_videotoolbox"),
+            (Self::VideoToolbox, "libx265") => Some("hevc_videotoolbox"),
+            _ => None,
+        }
+    }
+
+    /// Insert [`decode_args`](Self::decode_args) before the first `-i`.
+    pub fn with_decode_args(self, mut args: Vec<String>) -> Vec<String> {
+        let at = args.iter().position(|a| a == "-i").unwrap_or(0);
+        args.splice(at..at, self.decode_args().iter().map(|a| a.to_string()));
+        args
+    }
+}
+
+impl std::fmt::Display for HwAccel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::None => "none",
+            Self::Cuda => "cuda",
+            Self::Vaapi => "vaapi",
+            Self::VideoToolbox => "videotoolbox",
+        })
+    }
+}
+
+/// Whether an ffmpeg error means the hardware path could not be used, as
+/// opposed to a problem with the input or arguments.
+pub fn is_hwaccel_failure(message: &str) -> bool {
+    HWACCEL_FAILURE_MARKERS.iter().any(|marker| message.contains(marker))
+}
+
+// =============================================================================
+// Output Limits
+// =============================================================================
+
+/// Environment variables capping output frame size in pixels (width x height)
+/// and output duration in seconds.
+pub use adk_rust_mcp_common::config::{MAX_OUTPUT_DURATION_ENV, MAX_OUTPUT_PIXELS_ENV};
+
+/// Upper bounds on what a single tool call may produce. Unset limits are not enforced.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OutputLimits {
+    /// Maximum output width x height.
+    pub max_pixels: Option<u64>,
+    /// Maximum output duration in seconds.
+    pub max_duration: Option<f64>,
+}
+
+impl OutputLimits {
+    /// The limits set in `config` by [`MAX_OUTPUT_PIXELS_ENV`] and
+    /// [`MAX_OUTPUT_DURATION_ENV`] or the `[avtool]` file section.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_pixels: config.max_output_pixels,
+            max_duration: config.max_output_duration,
+        }
+    }
+
+    /// Whether no limit is set, so inputs need not be probed.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_pixels.is_none() && self.max_duration.is_none()
+    }
+
+    /// Check an estimated output against the limits.
+    ///
+    /// # Errors
+    /// Returns `Error::Validation` naming the exceeded limit.
+    pub fn check(&self, estimate: &OutputEstimate) -> Result<(), Error> {
+        if let (Some(max), Some((width, height))) = (self.max_pixels, estimate.dimensions) {
+            let pixels = u64::from(width) * u64::from(height);
+            if pixels > max {
+                return Err(Error::validation(format!(
+                    "Output would be {}x{} ({} pixels), exceeding {} ({} pixels)",
+                    width, height, pixels, MAX_OUTPUT_PIXELS_ENV, max
+                )));
+            }
+        }
+        if let (Some(max), Some(duration)) = (self.max_duration, estimate.duration_seconds) {
+            if duration > max {
+                return Err(Error::validation(format!(
+                    "Output would be {:.1}s long, exceeding {} ({}s)",
+                    duration, MAX_OUTPUT_DURATION_ENV, max
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Frame size and duration of an input, as reported by ffprobe.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProbedInput {
+    /// Width and height of the first video stream.
+    pub dimensions: Option<(u32, u32)>,
+    /// Container duration in seconds.
+    pub duration_seconds: Option<f64>,
+}
+
+impl ProbedInput {
+    /// Extract the first video stream's size and the container duration
+    /// from `ffprobe -show_format -show_streams` JSON.
+    pub fn from_ffprobe(json: &serde_json::Value) -> Self {
+        let dimensions = json
+            .get("streams")
+            .and_then(|s| s.as_array())
+            .and_then(|arr| arr.iter().find(|s| s.get("codec_type").and_then(|c| c.as_str()) == Some("video")))
+            .and_then(|s| {
+                let width = s.get("width")?.as_u64()?;
+                let height = s.get("height")?.as_u64()?;
+                Some((width as u32, height as u32))
+            });
+        let duration_seconds = json
+            .get("format")
+            .and_then(|f| f.get("duration"))
+            .and_then(|d| d.as_str())
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|d| d.is_finite() && *d >= 0.0);
+        Self {
+            dimensions,
+            duration_seconds,
+        }
+    }
+
+    /// Frame size after scaling to `width` with the aspect ratio kept.
+    pub fn scaled_to_width(&self, width: u32) -> Option<(u32, u32)> {
+        self.dimensions
+            .filter(|&(w, _)| w > 0)
+            .map(|(w, h)| (width, (u64::from(h) * u64::from(width)).div_ceil(u64::from(w)) as u32))
+    }
+}
+
+/// Frame size and duration a tool call is expected to produce.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OutputEstimate {
+    /// Output width and height (`None` for audio or when unknown).
+    pub dimensions: Option<(u32, u32)>,
+    /// Output duration in seconds (`None` for still images or when unknown).
+    pub duration_seconds: Option<f64>,
+}
+
+impl From<&ProbedInput> for OutputEstimate {
+    /// An output with the same frame size and length as the input.
+    fn from(input: &ProbedInput) -> Self {
+        Self {
+            dimensions: input.dimensions,
+            duration_seconds: input.duration_seconds,
+        }
+    }
+}
+
+// =============================================================================
+// AVToolHandler
+// =============================================================================
+
+/// AVTool handler for FFmpeg-based media processing.
+pub struct AVToolHandler {
+    /// Application configuration.
+    pub config: Config,
+    /// GCS client for storage operations.
+    pub gcs: GcsClient,
+    /// Temporary directory for downloaded files.
+    temp_dir: PathBuf,
+    /// Naming scheme for files created in `temp_dir`.
+    temp_namer: Arc<dyn TempNamer>,
+    /// Hardware acceleration for transcodes and GIF conversion.
+    hwaccel: HwAccel,
+    /// Caps on output frame size and duration.
+    output_limits: OutputLimits,
+    /// Longest input `reverse` accepts, in seconds.
+    reverse_max_duration: f64,
+}
+
+impl AVToolHandler {
+    /// Create a new AVToolHandler with the given configuration.
+    ///
+    /// # Errors
+    /// Returns an error if GCS client initialization fails.
+    #[instrument(level = "debug", name = "avtool_handler_new", skip_all)]
+    pub async fn new(config: Config) -> Result<Self, Error> {
+        debug!("Initializing AVToolHandler");
+
+        let auth = Arc::new(AuthProvider::new().await?);
+        let gcs = GcsClient::with_auth(auth);
+        
+        // Create temp directory for downloaded files
+        let temp_dir = std::env::temp_dir().join("adk-rust-mcp-avtool");
+        tokio::fs::create_dir_all(&temp_dir).await?;
+
+        let hwaccel = HwAccel::from_config(&config);
+        if hwaccel != HwAccel::None {
+            info!(hwaccel = %hwaccel, "Using hardware acceleration");
+        }
+        let output_limits = OutputLimits::from_config(&config);
+        let reverse_max_duration = config.reverse_max_duration.unwrap_or(DEFAULT_REVERSE_MAX_DURATION);
+
+        Ok(Self {
+            config,
+            gcs,
+            temp_dir,
+            temp_namer: Arc::new(UuidTempNamer),
+            hwaccel,
+            output_limits,
+            reverse_max_duration,
+        })
+    }
+
+    /// Create a new AVToolHandler with provided dependencies (for testing).
+    #[cfg(test)]
+    pub fn with_deps(config: Config, gcs: GcsClient, temp_dir: PathBuf) -> Self {
+        Self {
+            config,
+            gcs,
+            temp_dir,
+            temp_namer: Arc::new(UuidTempNamer),
+            hwaccel: HwAccel::None,
+            output_limits: OutputLimits::default(),
+            reverse_max_duration: DEFAULT_REVERSE_MAX_DURATION,
+        }
+    }
+
+    /// Replace the temp file naming scheme (defaults to UUIDs).
+    pub fn with_temp_namer(mut self, namer: Arc<dyn TempNamer>) -> Self {
+        self.temp_namer = namer;
+        self
+    }
+
+    /// Replace the hardware acceleration (defaults to `FFMPEG_HWACCEL`).
+    pub fn with_hwaccel(mut self, hwaccel: HwAccel) -> Self {
+        self.hwaccel = hwaccel;
+        self
+    }
+
+    /// Replace the output limits (defaults to `MCP_MAX_OUTPUT_PIXELS` and
+    /// `MCP_MAX_OUTPUT_DURATION`).
+    pub fn with_output_limits(mut self, limits: OutputLimits) -> Self {
+        self.output_limits = limits;
+        self
+    }
+
+    /// Replace the longest input `reverse` accepts (defaults to
+    /// `MCP_REVERSE_MAX_DURATION`, or [`DEFAULT_REVERSE_MAX_DURATION`]).
+    pub fn with_reverse_max_duration(mut self, seconds: f64) -> Self {
+        self.reverse_max_duration = seconds;
+        self
+    }
+
+    // =========================================================================
+    // Path Resolution Helpers
+    // =========================================================================
+
+    /// Check if a path is a GCS URI.
+    pub fn is_gcs_uri(path: &str) -> bool {
+        path.starts_with("gs://")
+    }
+
+    /// Resolve an input path, downloading from GCS if necessary.
+    ///
+    /// Returns the local path to use for FFmpeg operations.
+    ///
+    /// # Errors
+    /// Returns `Error::Validation` if a local input does not exist, so tools
+    /// fail before spawning ffmpeg.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn resolve_input(&self, path: &str) -> Result<PathBuf, Error> {
+        if Self::is_gcs_uri(path) {
+            // Download from GCS to temp file
+            let gcs_uri = GcsUri::parse(path)?;
+            let filename = Path::new(&gcs_uri.object)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("input");
+            
+            let local_path = self.temp_dir.join(format!("{}_{}", self.temp_namer.next_name(), filename));
+            
+            debug!(gcs_uri = %path, local_path = %local_path.display(), "Downloading from GCS");
+            let data = self.gcs.download(&gcs_uri).await?;
+            tokio::fs::write(&local_path, &data).await?;
+            
+            Ok(local_path)
+        } else {
+            // Local path, use as-is
+            let local_path = PathBuf::from(path);
+            if !tokio::fs::try_exists(&local_path).await.unwrap_or(false) {
+                return Err(Error::validation(format!("Input file not found: {}", path)));
+            }
+            Ok(local_path)
+        }
+    }
+
+    /// Handle output, uploading to GCS if the output path is a GCS URI.
+    ///
+    /// Returns the final output path (GCS URI or local path). Without
+    /// `overwrite`, an existing local output is left untouched and an error
+    /// is returned.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn handle_output(&self, local_path: &Path, output: &str, overwrite: bool) -> Result<String, Error> {
+        if Self::is_gcs_uri(output) {
+            // Upload to GCS
+            let gcs_uri = GcsUri::parse(output)?;
+            let data = tokio::fs::read(local_path).await?;
+            
+            // Determine content type from extension
+            let content_type = Self::content_type_from_extension(local_path);
+            
+            debug!(local_path = %local_path.display(), gcs_uri = %output, "Uploading to GCS");
+            self.gcs.upload(&gcs_uri, &data, content_type).await?;
+            
+            Ok(output.to_string())
+        } else {
+            // Local path - if different from local_path, copy the file
+            if local_path == Path::new(output) {
+                return Ok(output.to_string());
+            }
+            if overwrite {
+                tokio::fs::copy(local_path, output).await?;
+            } else {
+                // create_new makes the existence check and the write atomic
+                let mut target = tokio::fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(output)
+                    .await
+                    .map_err(|e| match e.kind() {
+                        std::io::ErrorKind::AlreadyExists => Self::output_exists_error(output),
+                        _ => Error::from(e),
+                    })?;
+                let mut source = tokio::fs::File::open(local_path).await?;
+                tokio::io::copy(&mut source, &mut target).await?;
+            }
+            Ok(output.to_string())
+        }
+    }
+
+    /// Measure a finished temp output, then deliver it to `output`.
+    ///
+    /// The size comes from the local file; the duration from a best-effort
+    /// ffprobe that is skipped for still images and ignored on failure.
+    async fn finish_output(&self, local_path: &Path, output: &str, overwrite: bool) -> Result<OutputResult, Error> {
+        let size_bytes = tokio::fs::metadata(local_path).await?.len();
+        let duration_seconds = if Self::is_still_image(local_path) {
+            None
+        } else {
+            self.probe_duration(local_path).await
+        };
+
+        let output = self.handle_output(local_path, output, overwrite).await?;
+        Ok(OutputResult {
+            output,
+            size_bytes,
+            duration_seconds,
+        })
+    }
+
+    /// Fail early when `overwrite` is off and a local `output` already exists,
+    /// before any input is downloaded or processed. GCS outputs are not checked.
+    async fn check_overwrite(output: &str, overwrite: bool) -> Result<(), Error> {
+        if !overwrite && !Self::is_gcs_uri(output) && tokio::fs::try_exists(output).await? {
+            return Err(Self::output_exists_error(output));
+        }
+        Ok(())
+    }
+
+    /// Number of streams of `codec_type` (e.g. `audio`) in ffprobe output.
+    fn stream_count(json: &serde_json::Value, codec_type: &str) -> usize {
+        json.get("streams")
+            .and_then(|s| s.as_array())
+            .map_or(0, |arr| {
+                arr.iter()
+                    .filter(|s| s.get("codec_type").and_then(|c| c.as_str()) == Some(codec_type))
+                    .count()
+            })
+    }
+
+    /// Error for a local output that would be overwritten.
+    fn output_exists_error(output: &str) -> Error {
+        Error::validation(format!(
+            "Output '{}' already exists; set overwrite to true to replace it",
+            output
+        ))
+    }
+
+    /// Whether the path has a still image extension (no meaningful duration).
+    fn is_still_image(path: &Path) -> bool {
+        matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("png") | Some("jpg") | Some("jpeg") | Some("webp") | Some("bmp")
+        )
+    }
+
+    /// Container duration in seconds, or `None` if ffprobe fails or reports none.
+    async fn probe_duration(&self, path: &Path) -> Option<f64> {
+        let json = self.run_ffprobe(path).await.ok()?;
+        json.get("format")
+            .and_then(|f| f.get("duration"))
+            .and_then(|d| d.as_str())
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|d| d.is_finite() && *d > 0.0)
+    }
+
+    /// Get content type from file extension.
+    fn content_type_from_extension(path: &Path) -> &'static str {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("mp3") => "audio/mpeg",
+            Some("wav") => "audio/wav",
+            Some("mp4") => "video/mp4",
+            Some("webm") => "video/webm",
+            Some("gif") => "image/gif",
+            Some("png") => "image/png",
+            Some("jpg") | Some("jpeg") => "image/jpeg",
+            Some("mkv") => "video/x-matroska",
+            Some("avi") => "video/x-msvideo",
+            Some("mov") => "video/quicktime",
+            Some("ogg") => "audio/ogg",
+            Some("flac") => "audio/flac",
+            _ => "application/octet-stream",
+        }
+    }
+
+    /// Generate a temporary output path.
+    fn temp_output_path(&self, extension: &str) -> PathBuf {
+        self.temp_dir.join(format!("{}.{}", self.temp_namer.next_name(), extension))
+    }
+
+    // =========================================================================
+    // FFmpeg/FFprobe Execution
+    // =========================================================================
+
+    /// Probe `inputs` and reject the call if the estimated output exceeds
+    /// the configured limits.
+    ///
+    /// Each input is a `(requested path, local path)` pair. Inputs downloaded
+    /// from GCS are removed when the check fails. Nothing is probed when no
+    /// limit is set.
+    async fn enforce_output_limits(
+        &self,
+        inputs: &[(&str, &Path)],
+        estimate: impl FnOnce(&[ProbedInput]) -> OutputEstimate,
+    ) -> Result<(), Error> {
+        if self.output_limits.is_unlimited() {
+            return Ok(());
+        }
+
+        let mut probed = Vec::with_capacity(inputs.len());
+        let mut outcome = Ok(());
+        for (_, local) in inputs {
+            match self.run_ffprobe(local).await {
+                Ok(json) => probed.push(ProbedInput::from_ffprobe(&json)),
+                Err(e) => {
+                    outcome = Err(e);
+                    break;
+                }
+            }
+        }
+        if outcome.is_ok() {
+            outcome = self.output_limits.check(&estimate(&probed));
+        }
+
+        if outcome.is_err() {
+            for (requested, local) in inputs {
+                if Self::is_gcs_uri(requested) {
+                    let _ = tokio::fs::remove_file(local).await;
+                }
+            }
+        }
+        outcome
+    }
+
+    /// Execute ffprobe and return parsed JSON output.
+    #[instrument(
+        level = "info",
+        name = "ffprobe",
+        skip_all,
+        fields(input = %input.display(), exit_code = field::Empty, duration_ms = field::Empty)
+    )]
+    async fn run_ffprobe(&self, input: &Path) -> Result<serde_json::Value, Error> {
+        let _timer = SpanTimer::start();
+        let binary = self.config.ffprobe_binary();
+        let output = Command::new(&binary)
+            .args([
+                "-v", "quiet",
+                "-print_format", "json",
+                "-show_format",
+                "-show_streams",
+            ])
+            .arg(input)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .map_err(|e| {
+                Error::ffmpeg(format!(
+                    "ffprobe not found at '{}' ({}). It is expected next to the ffmpeg in {}",
+                    binary, e, FFMPEG_PATH_ENV
+                ))
+            })?;
+        record_exit_code(&output.status);
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::ffmpeg(format!(
+                "ffprobe failed for '{}': {}",
+                input.display(),
+                stderr
+            )));
+        }
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).map_err(|e| {
+            Error::ffmpeg(format!("Failed to parse ffprobe output: {}", e))
+        })?;
+
+        Ok(json)
+    }
+
+    /// Execute ffmpeg with the given arguments. Without `overwrite`, ffmpeg
+    /// refuses to replace existing files instead of running with `-y`.
+    async fn run_ffmpeg(&self, args: &[&str], overwrite: bool) -> Result<(), Error> {
+        self.run_ffmpeg_capture(args, overwrite).await.map(|_| ())
+    }
+
+    /// Run ffmpeg with the arguments `build` produces for the configured
+    /// hardware acceleration, retrying in software if the hardware decoder or
+    /// encoder cannot be initialized.
+    ///
+    /// With `stdin`, the GCS object is streamed into ffmpeg's `pipe:0` on
+    /// each attempt.
+    async fn run_ffmpeg_accelerated(
+        &self,
+        overwrite: bool,
+        stdin: Option<&GcsUri>,
+        build: impl Fn(HwAccel) -> Vec<String>,
+    ) -> Result<(), Error> {
+        let args = build(self.hwaccel);
+        let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        match self.run_ffmpeg_from(&args_refs, overwrite, stdin).await {
+            Err(Error::Ffmpeg(message)) if self.hwaccel != HwAccel::None && is_hwaccel_failure(&message) => {
+                warn!(hwaccel = %self.hwaccel, error = %message, "Hardware acceleration failed; retrying in software");
+                let args = build(HwAccel::None);
+                let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+                self.run_ffmpeg_from(&args_refs, overwrite, stdin).await
+            }
+            result => result,
+        }
+    }
+
+    /// Run ffmpeg, streaming `stdin` from GCS into `pipe:0` when given.
+    async fn run_ffmpeg_from(&self, args: &[&str], overwrite: bool, stdin: Option<&GcsUri>) -> Result<(), Error> {
+        let Some(uri) = stdin else {
+            return self.run_ffmpeg(args, overwrite).await;
+        };
+        debug!(gcs_uri = %uri, "Streaming GCS object into ffmpeg stdin");
+        run_ffmpeg_with_stdin(&self.config.ffmpeg_binary(), args, overwrite, |mut pipe| async move {
+            self.gcs.download_to_writer(uri, &mut pipe).await?;
+            Ok(())
+        })
+        .await
+        .map(|_| ())
+    }
+
+    /// Whether `input` can be streamed into ffmpeg instead of downloaded: a
+    /// GCS object with a [`STREAMABLE_INPUT_EXTENSIONS`] extension. Output
+    /// limits probe the input file, so they force a download.
+    fn can_stream_input(&self, input: &str) -> bool {
+        Self::is_gcs_uri(input)
+            && self.output_limits.is_unlimited()
+            && Path::new(input)
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|ext| STREAMABLE_INPUT_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+    }
+
+    /// Run ffmpeg and return its stderr, where filters such as `showinfo`
+    /// and `blackdetect` write their results.
+    async fn run_ffmpeg_capture(&self, args: &[&str], overwrite: bool) -> Result<String, Error> {
+        adk_rust_mcp_common::ffmpeg::run_ffmpeg(&self.config.ffmpeg_binary(), args, overwrite).await
+    }
+
+    // =========================================================================
+    // Tool Implementations
+    // =========================================================================
+
+    /// Get media file information using ffprobe.
+    #[instrument(level = "info", skip(self))]
+    pub async fn get_media_info(&self, params: GetMediaInfoParams) -> Result<MediaInfo, Error> {
+        let local_input = self.resolve_input(&params.input).await?;
+        
+        let json = self.run_ffprobe(&local_input).await;
+        
+        // Clean up temp file if we downloaded from GCS
+        if Self::is_gcs_uri(&params.input) {
+            let _ = tokio::fs::remove_file(&local_input).await;
+        }
+        
+        let info = MediaInfo::from_ffprobe(&json?)?;
+        info!(duration = info.duration, format = %info.format, streams = info.streams.len(), "Got media info");
+        Ok(info)
+    }
+
+    /// Convert WAV to MP3.
+    #[instrument(level = "info", skip(self))]
+    pub async fn convert_wav_to_mp3(&self, params: ConvertAudioParams) -> Result<OutputResult, Error> {
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+        let local_input = self.resolve_input(&params.input).await?;
+        self.enforce_output_limits(&[(&params.input, &local_input)], |p| OutputEstimate::from(&p[0]))
+            .await?;
+        let temp_output = self.temp_output_path("mp3");
+        
+        let input_str = local_input.to_string_lossy();
+        let output_str = temp_output.to_string_lossy();
+        
+        self.run_ffmpeg(&[
+            "-i", &input_str,
+            "-codec:a", "libmp3lame",
+            "-b:a", &params.bitrate,
+            &output_str,
+        ], params.overwrite).await?;
+        
+        let result = self.finish_output(&temp_output, &params.output, params.overwrite).await?;
+        
+        // Clean up temp files
+        if Self::is_gcs_uri(&params.input) {
+            let _ = tokio::fs::remove_file(&local_input).await;
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+        
+        info!(output = %result, "Converted WAV to MP3");
+        Ok(result)
+    }
+
+    /// Convert video to GIF.
+    #[instrument(level = "info", skip(self))]
+    pub async fn video_to_gif(&self, params: VideoToGifParams) -> Result<OutputResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+        let local_input = self.resolve_input(&params.input).await?;
+        self.enforce_output_limits(&[(&params.input, &local_input)], |p| params.estimate_output(&p[0]))
+            .await?;
+        let temp_output = self.temp_output_path("gif");
+        
+        let input_str = local_input.to_string_lossy();
+        let output_str = temp_output.to_string_lossy();
+        
+        // The fade-out needs the clip length; probe it when no duration was given
+        let clip_duration = match (params.fade_duration, params.duration) {
+            (Some(_), None) => {
+                let json = self.run_ffprobe(&local_input).await?;
+                json.get("format")
+                    .and_then(|f| f.get("duration"))
+                    .and_then(|d| d.as_str())
+                    .and_then(|s| s.parse::<f64>().ok())
+                    .map(|total| total - params.start_time.unwrap_or(0.0))
+            }
+            (_, duration) => duration,
+        };
+        
+        self.run_ffmpeg_accelerated(params.overwrite, None, |hwaccel| {
+            hwaccel.with_decode_args(params.build_args(&input_str, &output_str, clip_duration))
+        })
+        .await?;
+        
+        let result = self.finish_output(&temp_output, &params.output, params.overwrite).await?;
+        
+        // Clean up temp files
+        if Self::is_gcs_uri(&params.input) {
+            let _ = tokio::fs::remove_file(&local_input).await;
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+        
+        info!(output = %result, "Converted video to GIF");
+        Ok(result)
+    }
+
+    /// Combine audio and video.
+    #[instrument(level = "info", skip(self))]
+    pub async fn combine_audio_video(&self, params: CombineAvParams) -> Result<OutputResult, Error> {
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+        let local_video = self.resolve_input(&params.video_input).await?;
+        let local_audio = self.resolve_input(&params.audio_input).await?;
+        self.enforce_output_limits(
+            &[(&params.video_input, &local_video), (&params.audio_input, &local_audio)],
+            |p| params.estimate_output(&p[0], &p[1]),
+        )
+        .await?;
+        
+        // Determine output extension from output path
+        let ext = Path::new(&params.output)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("mp4");
+        // Make sure the selected streams exist before ffmpeg reports a bare mapping error
+        let video_json = self.run_ffprobe(&local_video).await?;
+        let audio_json = self.run_ffprobe(&local_audio).await?;
+        if let Err(e) = params.check_streams(
+            Self::stream_count(&video_json, "video"),
+            Self::stream_count(&video_json, "audio"),
+            Self::stream_count(&audio_json, "audio"),
+        ) {
+            if Self::is_gcs_uri(&params.video_input) {
+                let _ = tokio::fs::remove_file(&local_video).await;
+            }
+            if Self::is_gcs_uri(&params.audio_input) {
+                let _ = tokio::fs::remove_file(&local_audio).await;
+            }
+            return Err(e);
+        }
+
+        let temp_output = self.temp_output_path(ext);
+        
+        let args = params.build_args(
+            &local_video.to_string_lossy(),
+            &local_audio.to_string_lossy(),
+            &temp_output.to_string_lossy(),
+        );
+        let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.run_ffmpeg(&args_refs, params.overwrite).await?;
+        
+        let result = self.finish_output(&temp_output, &params.output, params.overwrite).await?;
+        
+        // Clean up temp files
+        if Self::is_gcs_uri(&params.video_input) {
+            let _ = tokio::fs::remove_file(&local_video).await;
+        }
+        if Self::is_gcs_uri(&params.audio_input) {
+            let _ = tokio::fs::remove_file(&local_audio).await;
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+        
+        info!(output = %result, "Combined audio and video");
+        Ok(result)
+    }
+
+    /// Overlay image on video.
+    #[instrument(level = "info", skip(self))]
+    pub async fn overlay_image(&self, params: OverlayImageParams) -> Result<OutputResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+        let local_video = self.resolve_input(&params.video_input).await?;
+        let local_image = self.resolve_input(&params.image_input).await?;
+        self.enforce_output_limits(
+            &[(&params.video_input, &local_video), (&params.image_input, &local_image)],
+            |p| OutputEstimate::from(&p[0]),
+        )
+        .await?;
+        
+        let ext = Path::new(&params.output)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("mp4");
+        let temp_output = self.temp_output_path(ext);
+        
+        let video_str = local_video.to_string_lossy();
+        let image_str = local_image.to_string_lossy();
+        let output_str = temp_output.to_string_lossy();
+        
+        let filter_complex = params.filter_complex();
+        
+        self.run_ffmpeg(&[
+            "-i", &video_str,
+            "-i", &image_str,
+            "-filter_complex", &filter_complex,
+            "-c:a", "copy",
+            &output_str,
+        ], params.overwrite).await?;
+        
+        let result = self.finish_output(&temp_output, &params.output, params.overwrite).await?;
+        
+        // Clean up temp files
+        if Self::is_gcs_uri(&params.video_input) {
+            let _ = tokio::fs::remove_file(&local_video).await;
+        }
+        if Self::is_gcs_uri(&params.image_input) {
+            let _ = tokio::fs::remove_file(&local_image).await;
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+        
+        info!(output = %result, "Overlaid image on video");
+        Ok(result)
+    }
+
+    /// Concatenate media files.
+    #[instrument(level = "info", skip(self))]
+    pub async fn concatenate(&self, params: ConcatenateParams) -> Result<OutputResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+        
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+
+        // Resolve all inputs
+        let mut local_inputs = Vec::new();
+        for input in &params.inputs {
+            local_inputs.push(self.resolve_input(input).await?);
+        }
+        let limit_inputs: Vec<(&str, &Path)> = params
+            .inputs
+            .iter()
+            .map(String::as_str)
+            .zip(local_inputs.iter().map(PathBuf::as_path))
+            .collect();
+        self.enforce_output_limits(&limit_inputs, |p| params.estimate_output(p)).await?;
+        
+        let ext = Path::new(&params.output)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("mp4");
+        let temp_output = self.temp_output_path(ext);
+        
+        // Chapter boundaries fall at the cumulative input durations, plus any gaps
+        let chapters_file = if params.add_chapters {
+            let gap = params.gap_seconds.unwrap_or(0.0);
+            let mut durations = Vec::with_capacity(local_inputs.len());
+            for (i, (input, local)) in params.inputs.iter().zip(&local_inputs).enumerate() {
+                let duration = self.probe_duration(local).await.ok_or_else(|| {
+                    Error::validation(format!(
+                        "Cannot determine the duration of '{}', which is needed to place chapters",
+                        input
+                    ))
+                })?;
+                let is_last = i + 1 == local_inputs.len();
+                durations.push(if is_last { duration } else { duration + gap });
+            }
+            let path = self.temp_dir.join(format!("{}_chapters.txt", self.temp_namer.next_name()));
+            tokio::fs::write(&path, chapters_ffmetadata(&params.chapter_titles(), &durations)).await?;
+            Some(path)
+        } else {
+            None
+        };
+        let chapters_str = chapters_file.as_ref().map(|p| p.to_string_lossy().to_string());
+        let output_str = temp_output.to_string_lossy().to_string();
+        
+        // Audio mode decodes every clip and joins them with the concat filter;
+        // otherwise the concat demuxer copies streams
+        let mut concat_file = None;
+        let args = if params.gap_seconds.is_some() {
+            let local_strs: Vec<String> = local_inputs
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect();
+            params.build_gap_args(&local_strs, chapters_str.as_deref(), &output_str)
+        } else {
+            // Create concat file list
+            let list = self.temp_dir.join(format!("{}_concat.txt", self.temp_namer.next_name()));
+            let concat_content: String = local_inputs
+                .iter()
+                .map(|p| format!("file '{}'\n", p.display()))
+                .collect();
+            tokio::fs::write(&list, &concat_content).await?;
+            
+            let mut args: Vec<String> = ["-f", "concat", "-safe", "0", "-i"].map(String::from).to_vec();
+            args.push(list.to_string_lossy().to_string());
+            if let Some(chapters) = &chapters_str {
+                args.extend(["-i", chapters, "-map_chapters", "1"].map(String::from));
+            }
+            args.extend(["-c".to_string(), "copy".to_string(), output_str.clone()]);
+            concat_file = Some(list);
+            args
+        };
+        let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        
+        self.run_ffmpeg(&args_refs, params.overwrite).await?;
+        
+        let result = self.finish_output(&temp_output, &params.output, params.overwrite).await?;
+        
+        // Clean up temp files
+        for (i, input) in params.inputs.iter().enumerate() {
+            if Self::is_gcs_uri(input) {
+                let _ = tokio::fs::remove_file(&local_inputs[i]).await;
+            }
+        }
+        for path in concat_file.iter().chain(&chapters_file) {
+            let _ = tokio::fs::remove_file(path).await;
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+        
+        info!(
+            output = %result,
+            count = params.inputs.len(),
+            chapters = params.add_chapters,
+            gap_seconds = ?params.gap_seconds,
+            "Concatenated media files"
+        );
+        Ok(result)
+    }
+
+    /// Adjust audio volume.
+    #[instrument(level = "info", skip(self))]
+    pub async fn adjust_volume(&self, params: AdjustVolumeParams) -> Result<OutputResult, Error> {
+        // Validate and parse volume
+        let volume = params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+        
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+        let local_input = self.resolve_input(&params.input).await?;
+        self.enforce_output_limits(&[(&params.input, &local_input)], |p| OutputEstimate::from(&p[0]))
+            .await?;
+        
+        let ext = Path::new(&params.output)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("wav");
+        let temp_output = self.temp_output_path(ext);
+        
+        let input_str = local_input.to_string_lossy();
+        let output_str = temp_output.to_string_lossy();
+        let volume_filter = format!("volume={}", volume.to_ffmpeg_value());
+        
+        self.run_ffmpeg(&[
+            "-i", &input_str,
+            "-af", &volume_filter,
+            &output_str,
+        ], params.overwrite).await?;
+        
+        let result = self.finish_output(&temp_output, &params.output, params.overwrite).await?;
+        
+        // Clean up temp files
+        if Self::is_gcs_uri(&params.input) {
+            let _ = tokio::fs::remove_file(&local_input).await;
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+        
+        info!(output = %result, volume = ?volume, "Adjusted audio volume");
+        Ok(result)
+    }
+
+    /// Layer multiple audio files.
+    #[instrument(level = "info", skip(self))]
+    pub async fn layer_audio(&self, params: LayerAudioParams) -> Result<OutputResult, Error> {
+        if params.inputs.is_empty() {
+            return Err(Error::validation("At least one audio layer is required"));
+        }
+        
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+
+        // Resolve all inputs
+        let mut local_inputs = Vec::new();
+        for layer in &params.inputs {
+            local_inputs.push(self.resolve_input(&layer.path).await?);
+        }
+        let limit_inputs: Vec<(&str, &Path)> = params
+            .inputs
+            .iter()
+            .map(|layer| layer.path.as_str())
+            .zip(local_inputs.iter().map(PathBuf::as_path))
+            .collect();
+        self.enforce_output_limits(&limit_inputs, |p| params.estimate_output(p)).await?;
+        
+        let ext = Path::new(&params.output)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("wav");
+        let temp_output = self.temp_output_path(ext);
+        
+        // Build ffmpeg command with amix filter
+        let mut args = Vec::new();
+        
+        // Add all inputs
+        for local_input in &local_inputs {
+            args.push("-i".to_string());
+            args.push(local_input.to_string_lossy().to_string());
+        }
+        
+        // Build filter complex for mixing with delays and volumes
+        let mut filter_parts = Vec::new();
+        let mut mix_inputs = Vec::new();
+        
+        for (i, layer) in params.inputs.iter().enumerate() {
+            let label = format!("a{}", i);
+            let mut filter = format!("[{}:a]", i);
+            
+            // Add delay if offset > 0
+            if layer.offset_seconds > 0.0 {
+                let delay_ms = (layer.offset_seconds * 1000.0) as i64;
+                filter.push_str(&format!("adelay={}|{}", delay_ms, delay_ms));
+                if layer.volume != 1.0 {
+                    filter.push_str(&format!(",volume={}", layer.volume));
+                }
+            } else if layer.volume != 1.0 {
+                filter.push_str(&format!("volume={}", layer.volume));
+            } else {
+                filter.push_str("anull");
+            }
+            
+            filter.push_str(&format!("[{}]", label));
+            filter_parts.push(filter);
+            mix_inputs.push(format!("[{}]", label));
+        }
+        
+        // Add amix filter
+        let mix_filter = format!(
+            "{}amix=inputs={}:duration=longest",
+            mix_inputs.join(""),
+            params.inputs.len()
+        );
+        filter_parts.push(mix_filter);
+        
+        let filter_complex = filter_parts.join(";");
+        
+        args.extend([
+            "-filter_complex".to_string(),
+            filter_complex,
+            temp_output.to_string_lossy().to_string(),
+        ]);
+        
+        let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        self.run_ffmpeg(&args_refs, params.overwrite).await?;
+        
+        let result = self.finish_output(&temp_output, &params.output, params.overwrite).await?;
+        
+        // Clean up temp files
+        for (i, layer) in params.inputs.iter().enumerate() {
+            if Self::is_gcs_uri(&layer.path) {
+                let _ = tokio::fs::remove_file(&local_inputs[i]).await;
+            }
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+        
+        info!(output = %result, layers = params.inputs.len(), "Layered audio files");
+        Ok(result)
+    }
+
+    /// Mix a foreground track over a background track, lowering the
+    /// background whenever the foreground is audible.
+    #[instrument(level = "info", skip(self))]
+    pub async fn duck_audio(&self, params: DuckAudioParams) -> Result<OutputResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+        let local_background = self.resolve_input(&params.background).await?;
+        let local_foreground = self.resolve_input(&params.foreground).await?;
+        self.enforce_output_limits(
+            &[(&params.background, &local_background), (&params.foreground, &local_foreground)],
+            |p| params.estimate_output(p),
+        )
+        .await?;
+
+        let ext = Path::new(&params.output)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("wav");
+        let temp_output = self.temp_output_path(ext);
+
+        let args = params.build_args(
+            &local_background.to_string_lossy(),
+            &local_foreground.to_string_lossy(),
+            &temp_output.to_string_lossy(),
+        );
+        let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.run_ffmpeg(&args_refs, params.overwrite).await?;
+
+        let result = self.finish_output(&temp_output, &params.output, params.overwrite).await?;
+
+        // Clean up temp files
+        for (path, local) in [(&params.background, &local_background), (&params.foreground, &local_foreground)] {
+            if Self::is_gcs_uri(path) {
+                let _ = tokio::fs::remove_file(local).await;
+            }
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+
+        info!(output = %result, "Ducked background audio under foreground");
+        Ok(result)
+    }
+
+    /// Transcode a media file with explicit codec selection.
+    #[instrument(level = "info", skip(self))]
+    pub async fn transcode(&self, params: TranscodeParams) -> Result<OutputResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+
+        // Pipe-friendly GCS inputs are streamed into ffmpeg rather than downloaded
+        let stream = if self.can_stream_input(&params.input) {
+            Some(GcsUri::parse(&params.input)?)
+        } else {
+            None
+        };
+        let local_input = match stream {
+            Some(_) => None,
+            None => {
+                let local_input = self.resolve_input(&params.input).await?;
+                self.enforce_output_limits(&[(&params.input, &local_input)], |p| OutputEstimate::from(&p[0]))
+                    .await?;
+                Some(local_input)
+            }
+        };
+
+        let ext = Path::new(&params.output)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("mp4");
+        let temp_output = self.temp_output_path(ext);
+
+        let input_str = local_input
+            .as_ref()
+            .map_or_else(|| "pipe:0".into(), |p| p.to_string_lossy());
+        let output_str = temp_output.to_string_lossy();
+
+        let (video_codec, audio_codec) = params.resolved_codecs();
+        self.run_ffmpeg_accelerated(params.overwrite, stream.as_ref(), |hwaccel| {
+            params.build_args_with_hwaccel(&input_str, &output_str, hwaccel)
+        })
+        .await
+        .map_err(|e| match e {
+            Error::Ffmpeg(message) if message.contains("Unknown encoder") => Error::ffmpeg(format!(
+                "Encoder not available in this FFmpeg build (video: {}, audio: {}). \
+                 Install an FFmpeg build with the required encoder or choose another codec. {}",
+                video_codec.unwrap_or("none"),
+                audio_codec.unwrap_or("none"),
+                message
+            )),
+            other => other,
+        })?;
+
+        let result = self.finish_output(&temp_output, &params.output, params.overwrite).await?;
+
+        // Clean up temp files
+        if let Some(local_input) = local_input.filter(|_| Self::is_gcs_uri(&params.input)) {
+            let _ = tokio::fs::remove_file(&local_input).await;
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+
+        info!(output = %result, "Transcoded media file");
+        Ok(result)
+    }
+
+    /// Change the container of a media file, copying streams without re-encoding.
+    #[instrument(level = "info", skip(self))]
+    pub async fn remux(&self, params: RemuxParams) -> Result<OutputResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+        let local_input = self.resolve_input(&params.input).await?;
+        self.enforce_output_limits(&[(&params.input, &local_input)], |p| OutputEstimate::from(&p[0]))
+            .await?;
+
+        // Reject codecs the target container cannot hold before ffmpeg writes anything
+        let json = self.run_ffprobe(&local_input).await?;
+        let incompatible = params.incompatible_streams(&json);
+        if !incompatible.is_empty() {
+            if Self::is_gcs_uri(&params.input) {
+                let _ = tokio::fs::remove_file(&local_input).await;
+            }
+            return Err(Error::validation(format!(
+                "Cannot remux into .{} without re-encoding; the container does not support {}. \
+                 Use ffmpeg_transcode to convert the streams instead.",
+                params.output_extension().unwrap_or_default(),
+                incompatible.join(", ")
+            )));
+        }
+
+        let ext = params.output_extension().unwrap_or("mp4");
+        let temp_output = self.temp_output_path(ext);
+
+        let args = params.build_args(&local_input.to_string_lossy(), &temp_output.to_string_lossy());
+        let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.run_ffmpeg(&args_refs, params.overwrite).await?;
+
+        let result = self.finish_output(&temp_output, &params.output, params.overwrite).await?;
+
+        // Clean up temp files
+        if Self::is_gcs_uri(&params.input) {
+            let _ = tokio::fs::remove_file(&local_input).await;
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+
+        info!(output = %result, "Remuxed media file");
+        Ok(result)
+    }
+
+    /// Set or strip metadata tags, copying streams without re-encoding.
+    #[instrument(level = "info", skip(self))]
+    pub async fn set_metadata(&self, params: SetMetadataParams) -> Result<OutputResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+        let local_input = self.resolve_input(&params.input).await?;
+
+        let ext = Path::new(&params.output)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("mp4");
+        let temp_output = self.temp_output_path(ext);
+
+        let args = params.build_args(&local_input.to_string_lossy(), &temp_output.to_string_lossy());
+        let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.run_ffmpeg(&args_refs, params.overwrite).await?;
+
+        let result = self.finish_output(&temp_output, &params.output, params.overwrite).await?;
+
+        // Clean up temp files
+        if Self::is_gcs_uri(&params.input) {
+            let _ = tokio::fs::remove_file(&local_input).await;
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+
+        info!(output = %result, tags = params.tags.len(), strip_all = params.strip_all, "Set metadata");
+        Ok(result)
+    }
+
+    /// Run ffmpeg with caller-supplied options after checking them against
+    /// the `ffmpeg_run` allowlists.
+    #[instrument(level = "info", skip(self))]
+    pub async fn ffmpeg_run(&self, params: FfmpegRunParams) -> Result<OutputResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+        let max_duration = self
+            .output_limits
+            .max_duration
+            .unwrap_or(FFMPEG_RUN_DEFAULT_MAX_DURATION_SECS);
+        params.check_duration(max_duration).map_err(Error::validation)?;
+
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+        let mut local_inputs = Vec::with_capacity(params.inputs.len());
+        for input in &params.inputs {
+            local_inputs.push(self.resolve_input(input).await?);
+        }
+        let limit_inputs: Vec<(&str, &Path)> = params
+            .inputs
+            .iter()
+            .map(String::as_str)
+            .zip(local_inputs.iter().map(PathBuf::as_path))
+            .collect();
+        self.enforce_output_limits(&limit_inputs, |p| params.estimate_output(p))
+            .await?;
+
+        let ext = Path::new(&params.output)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("mp4");
+        let temp_output = self.temp_output_path(ext);
+
+        let inputs: Vec<String> = local_inputs
+            .iter()
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect();
+        let args = params.build_args(&inputs, &temp_output.to_string_lossy(), max_duration);
+        let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.run_ffmpeg(&args_refs, params.overwrite).await?;
+
+        let result = self
+            .finish_output(&temp_output, &params.output, params.overwrite)
+            .await?;
+
+        // Clean up temp files
+        for (input, local) in params.inputs.iter().zip(&local_inputs) {
+            if Self::is_gcs_uri(input) {
+                let _ = tokio::fs::remove_file(local).await;
+            }
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+
+        info!(output = %result, "Ran ffmpeg with allowlisted options");
+        Ok(result)
+    }
+
+    /// Composite an overlay video onto a main video (picture-in-picture).
+    #[instrument(level = "info", skip(self))]
+    pub async fn picture_in_picture(&self, params: PipParams) -> Result<OutputResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+        let local_main = self.resolve_input(&params.main_input).await?;
+        let local_overlay = self.resolve_input(&params.overlay_input).await?;
+        self.enforce_output_limits(
+            &[(&params.main_input, &local_main), (&params.overlay_input, &local_overlay)],
+            |p| OutputEstimate::from(&p[0]),
+        )
+        .await?;
+
+        // Probe both inputs for the main width and which ones carry audio
+        let has_audio = |json: &serde_json::Value| {
+            json.get("streams")
+                .and_then(|s| s.as_array())
+                .is_some_and(|arr| arr.iter().any(|s| s.get("codec_type").and_then(|c| c.as_str()) == Some("audio")))
+        };
+        let main_json = self.run_ffprobe(&local_main).await?;
+        let overlay_json = self.run_ffprobe(&local_overlay).await?;
+
+        let main_width = main_json
+            .get("streams")
+            .and_then(|s| s.as_array())
+            .and_then(|arr| {
+                arr.iter()
+                    .find(|s| s.get("codec_type").and_then(|c| c.as_str()) == Some("video"))
+                    .and_then(|s| s.get("width"))
+                    .and_then(|w| w.as_u64())
+            })
+            .ok_or_else(|| Error::validation(format!("main_input '{}' has no video stream", params.main_input)))?
+            as u32;
+
+        let ext = Path::new(&params.output)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("mp4");
+        let temp_output = self.temp_output_path(ext);
+
+        let args = params.build_args(
+            &local_main.to_string_lossy(),
+            &local_overlay.to_string_lossy(),
+            &temp_output.to_string_lossy(),
+            main_width,
+            has_audio(&main_json),
+            has_audio(&overlay_json),
+        );
+        let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.run_ffmpeg(&args_refs, params.overwrite).await?;
+
+        let result = self.finish_output(&temp_output, &params.output, params.overwrite).await?;
+
+        // Clean up temp files
+        if Self::is_gcs_uri(&params.main_input) {
+            let _ = tokio::fs::remove_file(&local_main).await;
+        }
+        if Self::is_gcs_uri(&params.overlay_input) {
+            let _ = tokio::fs::remove_file(&local_overlay).await;
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+
+        info!(output = %result, "Composited picture-in-picture video");
+        Ok(result)
+    }
+
+    /// Reverse a video and/or audio file.
+    #[instrument(level = "info", skip(self))]
+    pub async fn reverse(&self, params: ReverseParams) -> Result<OutputResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+        let local_input = self.resolve_input(&params.input).await?;
+        self.enforce_output_limits(&[(&params.input, &local_input)], |p| OutputEstimate::from(&p[0]))
+            .await?;
+
+        // Probe the duration and streams before committing to an in-memory reverse
+        let json = self.run_ffprobe(&local_input).await?;
+        let duration: Option<f64> = json
+            .get("format")
+            .and_then(|f| f.get("duration"))
+            .and_then(|d| d.as_str())
+            .and_then(|s| s.parse().ok());
+
+        if let Err(e) = params.check_duration(duration, self.reverse_max_duration) {
+            if Self::is_gcs_uri(&params.input) {
+                let _ = tokio::fs::remove_file(&local_input).await;
+            }
+            return Err(e);
+        }
+
+        let has_stream = |codec_type: &str| {
+            json.get("streams")
+                .and_then(|s| s.as_array())
+                .is_some_and(|arr| arr.iter().any(|s| s.get("codec_type").and_then(|c| c.as_str()) == Some(codec_type)))
+        };
+
+        let ext = Path::new(&params.output)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("mp4");
+        let temp_output = self.temp_output_path(ext);
+
+        let args = params.build_args(
+            &local_input.to_string_lossy(),
+            &temp_output.to_string_lossy(),
+            has_stream("video"),
+            has_stream("audio"),
+        );
+        let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.run_ffmpeg(&args_refs, params.overwrite).await?;
+
+        let result = self.finish_output(&temp_output, &params.output, params.overwrite).await?;
+
+        // Clean up temp files
+        if Self::is_gcs_uri(&params.input) {
+            let _ = tokio::fs::remove_file(&local_input).await;
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+
+        info!(output = %result, duration, "Reversed media file");
+        Ok(result)
+    }
+
+    /// Generate a contact sheet image summarizing a video.
+    #[instrument(level = "info", skip(self))]
+    pub async fn contact_sheet(&self, params: ContactSheetParams) -> Result<OutputResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+        let local_input = self.resolve_input(&params.input).await?;
+        self.enforce_output_limits(&[(&params.input, &local_input)], |p| params.estimate_output(&p[0]))
+            .await?;
+
+        // Probe the duration so the sampled frames cover the whole clip
+        let json = self.run_ffprobe(&local_input).await?;
+        let duration: f64 = json
+            .get("format")
+            .and_then(|f| f.get("duration"))
+            .and_then(|d| d.as_str())
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0.0);
+
+        let ext = Path::new(&params.output)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("png");
+        let temp_output = self.temp_output_path(ext);
+
+        let input_str = local_input.to_string_lossy();
+        let output_str = temp_output.to_string_lossy();
+        let filter = params.filter(duration);
+
+        self.run_ffmpeg(&[
+            "-i", &input_str,
+            "-vf", &filter,
+            "-frames:v", "1",
+            &output_str,
+        ], params.overwrite).await?;
+
+        let result = self.finish_output(&temp_output, &params.output, params.overwrite).await?;
+
+        // Clean up temp files
+        if Self::is_gcs_uri(&params.input) {
+            let _ = tokio::fs::remove_file(&local_input).await;
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+
+        info!(output = %result, duration, "Generated contact sheet");
+        Ok(result)
+    }
+
+    /// Detect scene changes or black frames in a video.
+    #[instrument(level = "info", skip(self))]
+    pub async fn detect_scenes(&self, params: DetectScenesParams) -> Result<SceneDetectionResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        let local_input = self.resolve_input(&params.input).await?;
+
+        let args = params.build_args(&local_input.to_string_lossy());
+        let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        let stderr = self.run_ffmpeg_capture(&args_refs, true).await;
+
+        // Clean up temp files
+        if Self::is_gcs_uri(&params.input) {
+            let _ = tokio::fs::remove_file(&local_input).await;
+        }
+
+        let result = params.parse_output(&stderr?);
+
+        info!(mode = %result.mode, count = result.timestamps.len(), "Detected scenes");
+        Ok(result)
+    }
+
+    /// Turn a still image and an audio track into a video (an audiogram).
+    #[instrument(level = "info", skip(self))]
+    pub async fn image_to_video(&self, params: ImageToVideoParams) -> Result<OutputResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        Self::check_overwrite(&params.output, params.overwrite).await?;
+        let local_image = self.resolve_input(&params.image_input).await?;
+        let local_audio = self.resolve_input(&params.audio_input).await?;
+        self.enforce_output_limits(
+            &[(&params.image_input, &local_image), (&params.audio_input, &local_audio)],
+            |p| params.estimate_output(&p[0], &p[1]),
+        )
+        .await?;
+
+        // The waveform spans the image width
+        let image_width = if params.waveform {
+            self.run_ffprobe(&local_image)
+                .await?
+                .get("streams")
+                .and_then(|s| s.as_array())
+                .and_then(|arr| {
+                    arr.iter()
+                        .find(|s| s.get("codec_type").and_then(|c| c.as_str()) == Some("video"))
+                        .and_then(|s| s.get("width"))
+                        .and_then(|w| w.as_u64())
+                })
+                .ok_or_else(|| Error::validation(format!("image_input '{}' has no image stream", params.image_input)))?
+                as u32
+        } else {
+            0
+        };
+
+        let ext = Path::new(&params.output)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("mp4");
+        let temp_output = self.temp_output_path(ext);
+
+        let args = params.build_args(
+            &local_image.to_string_lossy(),
+            &local_audio.to_string_lossy(),
+            &temp_output.to_string_lossy(),
+            image_width,
+        );
+        let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.run_ffmpeg(&args_refs, params.overwrite).await?;
+
+        let result = self.finish_output(&temp_output, &params.output, params.overwrite).await?;
+
+        // Clean up temp files
+        if Self::is_gcs_uri(&params.image_input) {
+            let _ = tokio::fs::remove_file(&local_image).await;
+        }
+        if Self::is_gcs_uri(&params.audio_input) {
+            let _ = tokio::fs::remove_file(&local_audio).await;
+        }
+        let _ = tokio::fs::remove_file(&temp_output).await;
+
+        info!(output = %result, waveform = params.waveform, "Created video from image and audio");
+        Ok(result)
+    }
+
+    // =========================================================================
+    // Storage Operations
+    // =========================================================================
+
+    /// Download a GCS object to a local file.
+    ///
+    /// The object is streamed to a temp file and transient failures are
+    /// retried with the configured API backoff. Only a complete download is
+    /// moved to `output_path`, so a failure never touches an existing file.
+    /// `output_path` must lie inside the download directory.
+    #[instrument(level = "info", skip(self, params), fields(uri = %params.uri, output_path = %params.output_path))]
+    pub async fn storage_download(&self, params: StorageDownloadParams) -> Result<OutputResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        let uri = GcsUri::parse(&params.uri)?;
+        let path = self.config.confine_local_path(&params.output_path)?;
+        let output = path.to_string_lossy().to_string();
+        if path.is_dir() {
+            return Err(Error::validation(format!(
+                "output_path '{}' is a directory; provide a file path",
+                params.output_path
+            )));
+        }
+        Self::check_overwrite(&output, params.overwrite).await?;
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("bin");
+        let temp_output = self.temp_output_path(ext);
+        let policy = RetryPolicy::from_config(&self.config);
+        let result = retry_with_backoff(
+            &policy,
+            "storage download",
+            |_| async {
+                let mut file = tokio::fs::File::create(&temp_output).await?;
+                Ok(self.gcs.download_to_writer(&uri, &mut file).await?)
+            },
+            |e: &Error| matches!(e, Error::Gcs(gcs) if gcs.is_transient()),
+        )
+        .await;
+
+        // A rename is atomic but only works within one filesystem; fall back
+        // to copying, which also keeps the no-overwrite check race-free
+        let delivered = match result {
+            Ok(bytes) => {
+                let moved = params.overwrite && tokio::fs::rename(&temp_output, &path).await.is_ok();
+                if moved {
+                    Ok(bytes)
+                } else {
+                    self.handle_output(&temp_output, &output, params.overwrite)
+                        .await
+                        .map(|_| bytes)
+                }
+            }
+            Err(e) => Err(e),
+        };
+        let _ = tokio::fs::remove_file(&temp_output).await;
+        let size_bytes = delivered?;
+
+        info!(size_bytes, "Downloaded GCS object");
+        Ok(OutputResult {
+            output,
+            size_bytes,
+            duration_seconds: None,
+        })
+    }
+
+    /// Upload a local file to GCS, streaming it from disk.
+    ///
+    /// `input_path` must lie inside the download directory, so arbitrary
+    /// files readable by the server cannot be exfiltrated. The content type
+    /// defaults to one inferred from the file extension.
+    #[instrument(level = "info", skip(self, params), fields(input_path = %params.input_path, uri = %params.uri))]
+    pub async fn storage_upload(&self, params: StorageUploadParams) -> Result<OutputResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        let uri = GcsUri::parse(&params.uri)?;
+        let path = self.config.confine_local_path(&params.input_path)?;
+        if !tokio::fs::metadata(&path).await.is_ok_and(|m| m.is_file()) {
+            return Err(Error::validation(format!("Input file not found: {}", params.input_path)));
+        }
+
+        let content_type = params
+            .content_type
+            .as_deref()
+            .unwrap_or_else(|| Self::content_type_from_extension(&path));
+        let size_bytes = self.gcs.upload_file(&uri, &path, content_type).await?;
+
+        info!(size_bytes, content_type, "Uploaded file to GCS");
+        Ok(OutputResult {
+            output: params.uri,
+            size_bytes,
+            duration_seconds: None,
+        })
+    }
+}
+
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_result_displays_path_and_serializes_extras() {
+        let result = OutputResult {
+            output: "gs://bucket/out.mp3".to_string(),
+            size_bytes: 48_000,
+            duration_seconds: Some(3.0),
+        };
+        assert_eq!(result.to_string(), "gs://bucket/out.mp3");
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["output"], "gs://bucket/out.mp3");
+        assert_eq!(json["size_bytes"], 48_000);
+        assert_eq!(json["duration_seconds"], 3.0);
+
+        let still = OutputResult {
+            duration_seconds: None,
+            ..result
+        };
+        assert!(serde_json::to_value(&still).unwrap().get("duration_seconds").is_none());
+    }
+
+    #[test]
+    fn test_is_still_image() {
+        assert!(AVToolHandler::is_still_image(Path::new("/tmp/sheet.png")));
+        assert!(AVToolHandler::is_still_image(Path::new("/tmp/frame.jpeg")));
+        assert!(!AVToolHandler::is_still_image(Path::new("/tmp/clip.gif")));
+        assert!(!AVToolHandler::is_still_image(Path::new("/tmp/clip.mp4")));
+    }
+
+    // =========================================================================
+    // FFmpeg Error Handling Tests (Requirements 9.19, 9.20)
+    // =========================================================================
+
+    #[test]
+    fn test_ffmpeg_error_contains_stderr_output() {
+        // Verify that FFmpeg errors include the stderr output for debugging
+        let stderr_output = "Invalid input file: file not found";
+        let err = Error::ffmpeg(format!("ffmpeg failed: {}", stderr_output));
+        let msg = err.to_string();
+        
+        assert!(msg.contains("FFmpeg"), "Error should mention FFmpeg");
+        assert!(msg.contains("Invalid input file"), "Error should contain stderr output");
+    }
+
+    #[test]
+    fn test_ffprobe_error_contains_file_path() {
+        // Verify that FFprobe errors include the file path for context
+        let file_path = "/path/to/nonexistent.mp4";
+        let err = Error::ffmpeg(format!("ffprobe failed for '{}': No such file or directory", file_path));
+        let msg = err.to_string();
+        
+        assert!(msg.contains("ffprobe"), "Error should mention ffprobe");
+        assert!(msg.contains(file_path), "Error should contain file path");
+    }
+
+    #[test]
+    fn test_ffmpeg_error_preserves_codec_errors() {
+        // Verify that codec-related errors are preserved
+        let codec_error = "Unknown encoder 'libx265'";
+        let err = Error::ffmpeg(format!("ffmpeg failed: {}", codec_error));
+        let msg = err.to_string();
+        
+        assert!(msg.contains("libx265"), "Error should preserve codec name");
+        assert!(msg.contains("Unknown encoder"), "Error should preserve error type");
+    }
+
+    #[test]
+    fn test_ffmpeg_error_preserves_format_errors() {
+        // Verify that format-related errors are preserved
+        let format_error = "Invalid data found when processing input";
+        let err = Error::ffmpeg(format!("ffmpeg failed: {}", format_error));
+        let msg = err.to_string();
+        
+        assert!(msg.contains("Invalid data"), "Error should preserve format error");
+    }
+
+    // =========================================================================
+    // Media Info Extraction Tests (Requirement 9.11)
+    // =========================================================================
+
+    #[test]
+    fn test_media_info_parsing_video_stream() {
+        // Test parsing of video stream information
+        let stream = StreamInfo {
+            index: 0,
+            codec_type: "video".to_string(),
+            codec_name: "h264".to_string(),
+            width: Some(1920),
+            height: Some(1080),
+            sample_rate: None,
+            channels: None,
+            ..Default::default()
+        };
+        
+        assert_eq!(stream.codec_type, "video");
+        assert_eq!(stream.codec_name, "h264");
+        assert_eq!(stream.width, Some(1920));
+        assert_eq!(stream.height, Some(1080));
+        assert!(stream.sample_rate.is_none());
+        assert!(stream.channels.is_none());
+    }
+
+    #[test]
+    fn test_media_info_parsing_audio_stream() {
+        // Test parsing of audio stream information
+        let stream = StreamInfo {
+            index: 1,
+            codec_type: "audio".to_string(),
+            codec_name: "aac".to_string(),
+            width: None,
+            height: None,
+            sample_rate: Some(48000),
+            channels: Some(2),
+            ..Default::default()
+        };
+        
+        assert_eq!(stream.codec_type, "audio");
+        assert_eq!(stream.codec_name, "aac");
+        assert!(stream.width.is_none());
+        assert!(stream.height.is_none());
+        assert_eq!(stream.sample_rate, Some(48000));
+        assert_eq!(stream.channels, Some(2));
+    }
+
+    #[test]
+    fn test_stream_info_from_ffprobe_codec_details() {
+        let video = StreamInfo::from_ffprobe(&serde_json::json!({
+            "index": 0,
+            "codec_name": "h264",
+            "profile": "High",
+            "codec_type": "video",
+            "width": 1920,
+            "height": 1080,
+            "pix_fmt": "yuv420p",
+            "r_frame_rate": "30000/1001",
+            "duration": "10.010000",
+            "bit_rate": "4980123"
+        }));
+        assert_eq!(video.codec_name, "h264");
+        assert_eq!(video.profile.as_deref(), Some("High"));
+        assert_eq!(video.pix_fmt.as_deref(), Some("yuv420p"));
+        assert_eq!(video.r_frame_rate.as_deref(), Some("30000/1001"));
+        assert_eq!(video.duration, Some(10.01));
+        assert_eq!(video.bit_rate, Some(4_980_123));
+
+        // Placeholders ffprobe uses for values that do not apply
+        let audio = StreamInfo::from_ffprobe(&serde_json::json!({
+            "index": 1,
+            "codec_name": "aac",
+            "profile": "LC",
+            "codec_type": "audio",
+            "sample_rate": "48000",
+            "channels": 2,
+            "r_frame_rate": "0/0",
+            "bit_rate": "N/A"
+        }));
+        assert_eq!(audio.sample_rate, Some(48000));
+        assert_eq!(audio.profile.as_deref(), Some("LC"));
+        assert!(audio.r_frame_rate.is_none());
+        assert!(audio.bit_rate.is_none());
+        assert!(audio.pix_fmt.is_none());
+        assert!(audio.duration.is_none());
+
+        // Absent details are left out of the JSON
+        let json = serde_json::to_value(&audio).unwrap();
+        for key in ["bit_rate", "pix_fmt", "r_frame_rate", "duration"] {
+            assert!(json.get(key).is_none(), "{} should be omitted", key);
+        }
+    }
+
+    #[test]
+    fn test_media_info_from_ffprobe_tags() {
+        let json = serde_json::json!({
+            "streams": [
+                {
+                    "index": 0,
+                    "codec_name": "h264",
+                    "codec_type": "video",
+                    "width": 1280,
+                    "height": 720,
+                    "tags": { "language": "und", "handler_name": "VideoHandler" }
+                },
+                {
+                    "index": 1,
+                    "codec_name": "aac",
+                    "codec_type": "audio",
+                    "tags": { "language": "eng", "title": "Commentary" }
+                },
+                {
+                    "index": 2,
+                    "codec_name": "mov_text",
+                    "codec_type": "subtitle",
+                    "tags": { "language": "fra" }
+                }
+            ],
+            "format": {
+                "format_name": "mov,mp4,m4a,3gp,3g2,mj2",
+                "duration": "12.500000",
+                "tags": {
+                    "title": "Launch teaser",
+                    "artist": "Studio",
+                    "creation_time": "2024-05-01T12:00:00.000000Z"
+                }
+            }
+        });
+
+        let info = MediaInfo::from_ffprobe(&json).unwrap();
+        assert_eq!(info.duration, 12.5);
+        assert_eq!(info.tags.get("title").map(String::as_str), Some("Launch teaser"));
+        assert_eq!(info.tags.get("artist").map(String::as_str), Some("Studio"));
+        assert_eq!(
+            info.tags.get("creation_time").map(String::as_str),
+            Some("2024-05-01T12:00:00.000000Z")
+        );
+
+        let languages: Vec<_> = info
+            .streams
+            .iter()
+            .map(|s| s.tags.get("language").map(String::as_str))
+            .collect();
+        assert_eq!(languages, [Some("und"), Some("eng"), Some("fra")]);
+        assert_eq!(info.streams[1].tags.get("title").map(String::as_str), Some("Commentary"));
+
+        let out = serde_json::to_value(&info).unwrap();
+        assert_eq!(out["tags"]["artist"], "Studio");
+        assert_eq!(out["streams"][2]["tags"]["language"], "fra");
+    }
+
+    #[test]
+    fn test_media_info_from_ffprobe_without_tags() {
+        let json = serde_json::json!({
+            "streams": [{ "index": 0, "codec_name": "pcm_s16le", "codec_type": "audio" }],
+            "format": { "format_name": "wav", "duration": "1.000000" }
+        });
+        let info = MediaInfo::from_ffprobe(&json).unwrap();
+        assert!(info.tags.is_empty());
+        assert!(info.streams[0].tags.is_empty());
+
+        // Empty tag maps are left out of the JSON
+        let out = serde_json::to_value(&info).unwrap();
+        assert!(out.get("tags").is_none());
+        assert!(out["streams"][0].get("tags").is_none());
+
+        let err = MediaInfo::from_ffprobe(&serde_json::json!({ "streams": [] })).unwrap_err();
+        assert!(err.to_string().contains("missing 'format'"), "{}", err);
+    }
+
+    #[test]
+    fn test_media_info_complete_structure() {
+        // Test complete MediaInfo structure with multiple streams
+        let info = MediaInfo {
+            duration: 120.5,
+            format: "matroska,webm".to_string(),
+            tags: HashMap::new(),
+            streams: vec![
+                StreamInfo {
+                    index: 0,
+                    codec_type: "video".to_string(),
+                    codec_name: "vp9".to_string(),
+                    width: Some(3840),
+                    height: Some(2160),
+                    sample_rate: None,
+                    channels: None,
+                    ..Default::default()
+                },
+                StreamInfo {
+                    index: 1,
+                    codec_type: "audio".to_string(),
+                    codec_name: "opus".to_string(),
+                    width: None,
+                    height: None,
+                    sample_rate: Some(48000),
+                    channels: Some(6),
+                    ..Default::default()
+                },
+                StreamInfo {
+                    index: 2,
+                    codec_type: "subtitle".to_string(),
+                    codec_name: "subrip".to_string(),
+                    width: None,
+                    height: None,
+                    sample_rate: None,
+                    channels: None,
+                    ..Default::default()
+                },
+            ],
+        };
+        
+        assert_eq!(info.duration, 120.5);
+        assert_eq!(info.format, "matroska,webm");
+        assert_eq!(info.streams.len(), 3);
+        
+        // Verify video stream
+        assert_eq!(info.streams[0].codec_type, "video");
+        assert_eq!(info.streams[0].width, Some(3840));
+        
+        // Verify audio stream
+        assert_eq!(info.streams[1].codec_type, "audio");
+        assert_eq!(info.streams[1].channels, Some(6));
+        
+        // Verify subtitle stream
+        assert_eq!(info.streams[2].codec_type, "subtitle");
+    }
+
+    #[test]
+    fn test_media_info_json_output_format() {
+        // Test that MediaInfo serializes to proper JSON format
+        let info = MediaInfo {
+            duration: 60.0,
+            format: "mp4".to_string(),
+            tags: HashMap::new(),
+            streams: vec![
+                StreamInfo {
+                    index: 0,
+                    codec_type: "video".to_string(),
+                    codec_name: "h264".to_string(),
+                    width: Some(1280),
+                    height: Some(720),
+                    sample_rate: None,
+                    channels: None,
+                    ..Default::default()
+                },
+            ],
+        };
+        
+        let json = serde_json::to_value(&info).unwrap();
+        
+        // Verify JSON structure
+        assert!(json.is_object());
+        assert!(json["duration"].is_f64());
+        assert!(json["format"].is_string());
+        assert!(json["streams"].is_array());
+        
+        // Verify values
+        assert_eq!(json["duration"].as_f64().unwrap(), 60.0);
+        assert_eq!(json["format"].as_str().unwrap(), "mp4");
+        assert_eq!(json["streams"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_media_info_empty_streams() {
+        // Test MediaInfo with no streams (edge case)
+        let info = MediaInfo {
+            duration: 0.0,
+            format: "unknown".to_string(),
+            tags: HashMap::new(),
+            streams: vec![],
+        };
+        
+        let json = serde_json::to_string(&info).unwrap();
+        let parsed: MediaInfo = serde_json::from_str(&json).unwrap();
+        
+        assert_eq!(parsed.duration, 0.0);
+        assert_eq!(parsed.format, "unknown");
+        assert!(parsed.streams.is_empty());
+    }
+
+    // =========================================================================
+    // VolumeValue Tests
+    // =========================================================================
+
+    #[test]
+    fn test_volume_parse_multiplier() {
+        assert_eq!(VolumeValue::parse("0.5").unwrap(), VolumeValue::Multiplier(0.5));
+        assert_eq!(VolumeValue::parse("1.0").unwrap(), VolumeValue::Multiplier(1.0));
+        assert_eq!(VolumeValue::parse("2.0").unwrap(), VolumeValue::Multiplier(2.0));
+        assert_eq!(VolumeValue::parse("1").unwrap(), VolumeValue::Multiplier(1.0));
+        assert_eq!(VolumeValue::parse("0").unwrap(), VolumeValue::Multiplier(0.0));
+    }
+
+    #[test]
+    fn test_volume_parse_decibels() {
+        assert_eq!(VolumeValue::parse("-3dB").unwrap(), VolumeValue::Decibels(-3.0));
+        assert_eq!(VolumeValue::parse("+6dB").unwrap(), VolumeValue::Decibels(6.0));
+        assert_eq!(VolumeValue::parse("0dB").unwrap(), VolumeValue::Decibels(0.0));
+        assert_eq!(VolumeValue::parse("-10.5dB").unwrap(), VolumeValue::Decibels(-10.5));
+        // Case insensitive
+        assert_eq!(VolumeValue::parse("-3DB").unwrap(), VolumeValue::Decibels(-3.0));
+        assert_eq!(VolumeValue::parse("-3db").unwrap(), VolumeValue::Decibels(-3.0));
+    }
+
+    #[test]
+    fn test_volume_parse_with_whitespace() {
+        assert_eq!(VolumeValue::parse("  0.5  ").unwrap(), VolumeValue::Multiplier(0.5));
+        assert_eq!(VolumeValue::parse("  -3dB  ").unwrap(), VolumeValue::Decibels(-3.0));
+    }
+
+    #[test]
+    fn test_volume_parse_invalid() {
+        assert!(VolumeValue::parse("").is_err());
+        assert!(VolumeValue::parse("abc").is_err());
+        assert!(VolumeValue::parse("dB").is_err());
+        assert!(VolumeValue::parse("-3").is_err()); // Negative multiplier not allowed
+    }
+
+    #[test]
+    fn test_volume_to_ffmpeg_value() {
+        assert_eq!(VolumeValue::Multiplier(0.5).to_ffmpeg_value(), "0.5");
+        assert_eq!(VolumeValue::Multiplier(2.0).to_ffmpeg_value(), "2");
+        assert_eq!(VolumeValue::Decibels(-3.0).to_ffmpeg_value(), "-3dB");
+        assert_eq!(VolumeValue::Decibels(6.0).to_ffmpeg_value(), "6dB");
+    }
+
+    // =========================================================================
+    // Parameter Validation Tests
+    // =========================================================================
+
+    #[test]
+    fn test_adjust_volume_params_valid() {
+        let params = AdjustVolumeParams {
+            input: "input.wav".to_string(),
+            output: "output.wav".to_string(),
+            overwrite: true,
+            volume: "0.5".to_string(),
+        };
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_adjust_volume_params_invalid_volume() {
+        let params = AdjustVolumeParams {
+            input: "input.wav".to_string(),
+            output: "output.wav".to_string(),
+            overwrite: true,
+            volume: "invalid".to_string(),
+        };
+        let result = params.validate();
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "volume"));
+    }
+
+    #[test]
+    fn test_adjust_volume_params_empty_input() {
+        let params = AdjustVolumeParams {
+            input: "".to_string(),
+            output: "output.wav".to_string(),
+            overwrite: true,
+            volume: "0.5".to_string(),
+        };
+        let result = params.validate();
+        assert!(result.is_err());
+        let errors = result.unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "input"));
+    }
+
+    // =========================================================================
+    // Temp File Naming Tests
+    // =========================================================================
+
+    fn test_handler(temp_dir: PathBuf) -> AVToolHandler {
+        let config = Config {
+            project_id: "test-project".to_string(),
+            ..Default::default()
+        };
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        AVToolHandler::with_deps(config, GcsClient::with_auth(auth), temp_dir)
+    }
+
+    #[test]
+    fn test_sequential_temp_namer_counts_up() {
+        let namer = SequentialTempNamer::new("tmp");
+        assert_eq!(namer.next_name(), "tmp-0");
+        assert_eq!(namer.next_name(), "tmp-1");
+        assert_eq!(namer.next_name(), "tmp-2");
+    }
+
+    #[test]
+    fn test_uuid_temp_namer_is_unique() {
+        let namer = UuidTempNamer;
+        let a = namer.next_name();
+        let b = namer.next_name();
+        assert_ne!(a, b);
+        assert!(Uuid::parse_str(&a).is_ok());
+    }
+
+    #[test]
+    fn test_temp_output_path_uses_injected_namer() {
+        let handler = test_handler(PathBuf::from("/tmp/avtool-test"))
+            .with_temp_namer(Arc::new(SequentialTempNamer::new("out")));
+
+        assert_eq!(handler.temp_output_path("gif"), PathBuf::from("/tmp/avtool-test/out-0.gif"));
+        assert_eq!(handler.temp_output_path("mp3"), PathBuf::from("/tmp/avtool-test/out-1.mp3"));
+    }
+
+    #[test]
+    fn test_can_stream_input() {
+        let handler = test_handler(PathBuf::from("/tmp/avtool-test"));
+        for input in ["gs://bucket/a.mp3", "gs://bucket/dir/b.WEBM", "gs://bucket/c.ts"] {
+            assert!(handler.can_stream_input(input), "{}", input);
+        }
+        // MP4/MOV may need seeking, local files are read directly
+        for input in ["gs://bucket/a.mp4", "gs://bucket/b.mov", "gs://bucket/noext", "/tmp/a.mp3"] {
+            assert!(!handler.can_stream_input(input), "{}", input);
+        }
+
+        // Output limits probe a local copy of the input
+        let handler = handler.with_output_limits(OutputLimits {
+            max_pixels: Some(1920 * 1080),
+            max_duration: None,
+        });
+        assert!(!handler.can_stream_input("gs://bucket/a.mp3"));
+    }
+
+    #[test]
+    fn test_temp_output_path_defaults_to_uuid() {
+        let handler = test_handler(PathBuf::from("/tmp/avtool-test"));
+        let path = handler.temp_output_path("mp4");
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap();
+        assert!(Uuid::parse_str(stem).is_ok(), "Expected a UUID stem, got {}", stem);
+    }
+
+    #[test]
+    fn test_gif_argv_is_reproducible_with_sequential_namer() {
+        let argv = || {
+            let handler = test_handler(PathBuf::from("/tmp/avtool-test"))
+                .with_temp_namer(Arc::new(SequentialTempNamer::new("golden")));
+            let params = VideoToGifParams {
+                input: "in.mp4".to_string(),
+                output: "out.gif".to_string(),
+                overwrite: true,
+                fps: 10,
+                width: Some(320),
+                start_time: None,
+                duration: None,
+                loop_count: None,
+                fade_duration: None,
+            };
+            let output = handler.temp_output_path("gif");
+            params.build_args("in.mp4", &output.to_string_lossy(), None)
+        };
+
+        let first = argv();
+        assert_eq!(first, argv());
+        assert_eq!(first.last().map(String::as_str), Some("/tmp/avtool-test/golden-0.gif"));
+    }
+
+    // =========================================================================
+    // Input Resolution Tests
+    // =========================================================================
+
+    #[tokio::test]
+    async fn test_resolve_input_missing_local_file() {
+        let handler = test_handler(PathBuf::from("/tmp/avtool-test"));
+        let err = handler.resolve_input("/nonexistent/input.wav").await.unwrap_err();
+        assert!(matches!(err, Error::Validation(_)), "Expected validation error, got {:?}", err);
+        assert!(err.to_string().contains("Input file not found: /nonexistent/input.wav"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_input_existing_local_file() {
+        let path = std::env::temp_dir().join(format!("avtool-resolve-{}.wav", Uuid::new_v4()));
+        std::fs::write(&path, b"RIFF").unwrap();
+
+        let handler = test_handler(PathBuf::from("/tmp/avtool-test"));
+        let resolved = handler.resolve_input(&path.to_string_lossy()).await.unwrap();
+        assert_eq!(resolved, path);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_tool_reports_missing_input_before_ffmpeg() {
+        let handler = test_handler(PathBuf::from("/tmp/avtool-test"));
+        let params = CombineAvParams {
+            video_input: "/nonexistent/video.mp4".to_string(),
+            audio_input: "/nonexistent/audio.wav".to_string(),
+            output: "/tmp/avtool-test/out.mp4".to_string(),
+            overwrite: true,
+            loop_video: false,
+            video_stream_index: None,
+            audio_stream_index: None,
+            mix_audio: false,
+        };
+
+        let err = handler.combine_audio_video(params).await.unwrap_err();
+        assert!(matches!(err, Error::Validation(_)), "Expected validation error, got {:?}", err);
+        assert!(err.to_string().contains("/nonexistent/video.mp4"));
+    }
+
+    // =========================================================================
+    // Overwrite Protection Tests
+    // =========================================================================
+
+    #[test]
+    fn test_overwrite_defaults_to_true() {
+        let params: ConvertAudioParams =
+            serde_json::from_str(r#"{"input": "in.wav", "output": "out.mp3"}"#).unwrap();
+        assert!(params.overwrite);
+    }
+
+    #[tokio::test]
+    async fn test_check_overwrite_rejects_existing_local_output() {
+        let path = std::env::temp_dir().join(format!("avtool-overwrite-{}.mp4", Uuid::new_v4()));
+        std::fs::write(&path, b"existing").unwrap();
+        let output = path.to_string_lossy();
+
+        let err = AVToolHandler::check_overwrite(&output, false).await.unwrap_err();
+        assert!(matches!(err, Error::Validation(_)), "Expected validation error, got {:?}", err);
+        assert!(err.to_string().contains("already exists"));
+        assert!(AVToolHandler::check_overwrite(&output, true).await.is_ok());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_check_overwrite_allows_missing_and_gcs_outputs() {
+        assert!(AVToolHandler::check_overwrite("/nonexistent/out.mp4", false).await.is_ok());
+        assert!(AVToolHandler::check_overwrite("gs://bucket/out.mp4", false).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_handle_output_without_overwrite_keeps_existing_file() {
+        let dir = std::env::temp_dir();
+        let source = dir.join(format!("avtool-src-{}.mp4", Uuid::new_v4()));
+        let target = dir.join(format!("avtool-dst-{}.mp4", Uuid::new_v4()));
+        std::fs::write(&source, b"new").unwrap();
+        std::fs::write(&target, b"old").unwrap();
+
+        let handler = test_handler(PathBuf::from("/tmp/avtool-test"));
+        let output = target.to_string_lossy();
+        let err = handler.handle_output(&source, &output, false).await.unwrap_err();
+        assert!(matches!(err, Error::Validation(_)), "Expected validation error, got {:?}", err);
+        assert_eq!(std::fs::read(&target).unwrap(), b"old");
+
+        handler.handle_output(&source, &output, true).await.unwrap();
+        assert_eq!(std::fs::read(&target).unwrap(), b"new");
+
+        let _ = std::fs::remove_file(&source);
+        let _ = std::fs::remove_file(&target);
+    }
+
+    // =========================================================================
+    // GCS URI Detection Tests
+    // =========================================================================
+
+    #[test]
+    fn test_is_gcs_uri() {
+        assert!(AVToolHandler::is_gcs_uri("gs://bucket/path/file.mp4"));
+        assert!(AVToolHandler::is_gcs_uri("gs://my-bucket/file.wav"));
+        assert!(!AVToolHandler::is_gcs_uri("/local/path/file.mp4"));
+        assert!(!AVToolHandler::is_gcs_uri("./relative/path.wav"));
+        assert!(!AVToolHandler::is_gcs_uri("file.mp3"));
+        assert!(!AVToolHandler::is_gcs_uri("s3://bucket/file.mp4"));
+    }
+
+    // =========================================================================
+    // Content Type Tests
+    // =========================================================================
+
+    #[test]
+    fn test_content_type_from_extension() {
+        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file.mp3")), "audio/mpeg");
+        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file.wav")), "audio/wav");
+        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file.mp4")), "video/mp4");
+        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file.gif")), "image/gif");
+        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file.png")), "image/png");
+        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file.jpg")), "image/jpeg");
+        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file.unknown")), "application/octet-stream");
+        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file")), "application/octet-stream");
+    }
+
+    // =========================================================================
+    // Serialization Tests
+    // =========================================================================
+
+    #[test]
+    fn test_media_info_serialization() {
+        let info = MediaInfo {
+            duration: 10.5,
+            format: "mp4".to_string(),
+            tags: HashMap::new(),
+            streams: vec![
+                StreamInfo {
+                    index: 0,
+                    codec_type: "video".to_string(),
+                    codec_name: "h264".to_string(),
+                    width: Some(1920),
+                    height: Some(1080),
+                    sample_rate: None,
+                    channels: None,
+                    ..Default::default()
+                },
+                StreamInfo {
+                    index: 1,
+                    codec_type: "audio".to_string(),
+                    codec_name: "aac".to_string(),
+                    width: None,
+                    height: None,
+                    sample_rate: Some(44100),
+                    channels: Some(2),
+                    ..Default::default()
+                },
+            ],
+        };
+
+        let json = serde_json::to_string(&info).unwrap();
+        let deserialized: MediaInfo = serde_json::from_str(&json).unwrap();
+        
+        assert_eq!(deserialized.duration, 10.5);
+        assert_eq!(deserialized.format, "mp4");
+        assert_eq!(deserialized.streams.len(), 2);
+    }
+
+    #[test]
+    fn test_convert_audio_params_defaults() {
+        let params: ConvertAudioParams = serde_json::from_str(r#"{
+            "input": "input.wav",
+            "output": "output.mp3"
+        }"#).unwrap();
+        
+        assert_eq!(params.bitrate, DEFAULT_BITRATE);
+    }
+
+    #[test]
+    fn test_video_to_gif_params_defaults() {
+        let params: VideoToGifParams = serde_json::from_str(r#"{
+            "input": "input.mp4",
+            "output": "output.gif"
+        }"#).unwrap();
+        
+        assert_eq!(params.fps, DEFAULT_GIF_FPS);
+        assert!(params.width.is_none());
+        assert!(params.start_time.is_none());
+        assert!(params.duration.is_none());
+        assert!(params.loop_count.is_none());
+        assert!(params.fade_duration.is_none());
+    }
+
+    fn gif_params(json: &str) -> VideoToGifParams {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_video_to_gif_args_include_loop_flag() {
+        let params = gif_params(r#"{"input": "in.mp4", "output": "out.gif", "loop_count": -1}"#);
+        let args = params.build_args("in.mp4", "out.gif", None);
+
+        let pos = args.iter().position(|a| a == "-loop").expect("argv should contain -loop");
+        assert_eq!(args[pos + 1], "-1");
+        assert_eq!(args.last().unwrap(), "out.gif");
+        assert!(pos > args.iter().position(|a| a == "-i").unwrap(), "-loop must be an output option");
+    }
+
+    #[test]
+    fn test_video_to_gif_args_omit_loop_by_default() {
+        let params = gif_params(r#"{"input": "in.mp4", "output": "out.gif"}"#);
+        let args = params.build_args("in.mp4", "out.gif", None);
+        assert!(!args.iter().any(|a| a == "-loop"));
+    }
+
+    #[test]
+    fn test_video_to_gif_fade_filter() {
+        let params = gif_params(r#"{"input": "in.mp4", "output": "out.gif", "width": 320, "duration": 4.0, "fade_duration": 0.5}"#);
+        assert_eq!(
+            params.filter(Some(4.0)),
+            "fps=10,scale=320:-1:flags=lanczos,fade=t=in:st=0:d=0.5,fade=t=out:st=3.500:d=0.5"
+        );
+        // Without a known clip length only the fade-in is applied
+        assert_eq!(params.filter(None), "fps=10,scale=320:-1:flags=lanczos,fade=t=in:st=0:d=0.5");
+    }
+
+    #[test]
+    fn test_video_to_gif_validation() {
+        assert!(gif_params(r#"{"input": "a", "output": "b", "loop_count": 3, "fade_duration": 0.5}"#).validate().is_ok());
+
+        let errors = gif_params(r#"{"input": "a", "output": "b", "loop_count": -2}"#).validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "loop_count"));
+
+        let errors = gif_params(r#"{"input": "a", "output": "b", "fade_duration": 0}"#).validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "fade_duration"));
+
+        let errors = gif_params(r#"{"input": "a", "output": "b", "duration": 1.0, "fade_duration": 0.6}"#).validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "fade_duration"));
+
+        // Without a duration there is no clip length to catch an infinite fade
+        let mut params = gif_params(r#"{"input": "a", "output": "b"}"#);
+        params.fade_duration = Some(f64::INFINITY);
+        let errors = params.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "fade_duration"));
+    }
+
+    #[test]
+    fn test_audio_layer_defaults() {
+        let layer: AudioLayer = serde_json::from_str(r#"{
+            "path": "audio.wav"
+        }"#).unwrap();
+        
+        assert_eq!(layer.offset_seconds, 0.0);
+        assert_eq!(layer.volume, DEFAULT_VOLUME);
+    }
+
+    // =========================================================================
+    // Concatenate Validation Tests
+    // =========================================================================
+
+    #[test]
+    fn test_concatenate_params_valid() {
+        let params = ConcatenateParams {
+            inputs: vec!["file1.mp4".to_string(), "file2.mp4".to_string()],
+            output: "output.mp4".to_string(),
+            overwrite: true,
+            add_chapters: false,
+            chapter_titles: None,
+            gap_seconds: None,
+        };
+        
+        assert!(!params.inputs.is_empty());
+        assert_eq!(params.inputs.len(), 2);
+    }
+
+    #[test]
+    fn test_concatenate_params_single_input() {
+        let params = ConcatenateParams {
+            inputs: vec!["file1.mp4".to_string()],
+            output: "output.mp4".to_string(),
+            overwrite: true,
+            add_chapters: false,
+            chapter_titles: None,
+            gap_seconds: None,
+        };
+        
+        // Single input is valid (though not very useful)
+        assert_eq!(params.inputs.len(), 1);
+    }
+
+    #[test]
+    fn test_concatenate_chapter_validation() {
+        let params = |titles: Option<Vec<&str>>, add_chapters: bool| ConcatenateParams {
+            inputs: vec!["intro.mp4".to_string(), "gs://bucket/clips/main.mp4".to_string()],
+            output: "out.mp4".to_string(),
+            overwrite: true,
+            add_chapters,
+            chapter_titles: titles.map(|t| t.into_iter().map(String::from).collect()),
+            gap_seconds: None,
+        };
+
+        assert!(params(None, true).validate().is_ok());
+        assert!(params(Some(vec!["Intro", "Main"]), true).validate().is_ok());
+
+        let errors = params(Some(vec!["Intro"]), true).validate().unwrap_err();
+        assert_eq!(errors[0].field, "chapter_titles");
+        assert!(errors[0].message.contains("one title per input (2), got 1"));
+
+        let errors = params(Some(vec!["Intro", "Main"]), false).validate().unwrap_err();
+        assert!(errors[0].message.contains("requires add_chapters"));
+
+        let mut empty = params(None, false);
+        empty.inputs.clear();
+        assert_eq!(empty.validate().unwrap_err()[0].field, "inputs");
+    }
+
+    #[test]
+    fn test_concatenate_chapter_titles_default_to_file_names() {
+        let params: ConcatenateParams = serde_json::from_str(
+            r#"{"inputs": ["/tmp/01 Intro.mp4", "gs://bucket/clips/main.take2.mov"], "output": "out.mp4", "add_chapters": true}"#,
+        ).unwrap();
+        assert_eq!(params.chapter_titles(), vec!["01 Intro", "main.take2"]);
+
+        let params = ConcatenateParams {
+            chapter_titles: Some(vec!["Opening".to_string(), "Feature".to_string()]),
+            gap_seconds: None,
+            ..params
+        };
+        assert_eq!(params.chapter_titles(), vec!["Opening", "Feature"]);
+    }
+
+    #[test]
+    fn test_concatenate_gap_validation() {
+        let params = |gap: f64| ConcatenateParams {
+            inputs: vec!["a.wav".to_string(), "b.wav".to_string()],
+            output: "out.wav".to_string(),
+            overwrite: true,
+            add_chapters: false,
+            chapter_titles: None,
+            gap_seconds: Some(gap),
+        };
+
+        assert!(params(0.0).validate().is_ok());
+        assert!(params(0.75).validate().is_ok());
+        assert!(params(MAX_GAP_SECONDS).validate().is_ok());
+        for gap in [-0.5, MAX_GAP_SECONDS + 1.0, f64::NAN] {
+            let errors = params(gap).validate().unwrap_err();
+            assert_eq!(errors[0].field, "gap_seconds");
+        }
+    }
+
+    #[test]
+    fn test_concatenate_gap_args() {
+        let params: ConcatenateParams = serde_json::from_str(
+            r#"{"inputs": ["a.wav", "b.mp3", "c.wav"], "output": "out.mp3", "gap_seconds": 0.5}"#,
+        ).unwrap();
+        let inputs = vec!["/tmp/a.wav".to_string(), "/tmp/b.mp3".to_string(), "/tmp/c.wav".to_string()];
+
+        assert_eq!(
+            params.gap_filter(3),
+            "[0:a:0]apad=pad_dur=0.5[a0];[1:a:0]apad=pad_dur=0.5[a1];[2:a:0]anull[a2];\
+             [a0][a1][a2]concat=n=3:v=0:a=1[out]"
+        );
+        assert_eq!(
+            params.build_gap_args(&inputs, None, "tmp.mp3"),
+            vec![
+                "-i", "/tmp/a.wav", "-i", "/tmp/b.mp3", "-i", "/tmp/c.wav",
+                "-filter_complex", &params.gap_filter(3),
+                "-map", "[out]",
+                "tmp.mp3",
+            ]
+        );
+
+        // The chapters file follows the clips, so it is input 3
+        let args = params.build_gap_args(&inputs, Some("/tmp/chapters.txt"), "tmp.mp3");
+        assert_eq!(&args[6..8], ["-i", "/tmp/chapters.txt"]);
+        assert_eq!(&args[12..14], ["-map_chapters", "3"]);
+
+        // A zero gap joins the clips back to back
+        let params = ConcatenateParams { gap_seconds: Some(0.0), ..params };
+        assert_eq!(params.gap_filter(2), "[0:a:0]anull[a0];[1:a:0]anull[a1];[a0][a1]concat=n=2:v=0:a=1[out]");
+    }
+
+    #[test]
+    fn test_concatenate_gap_estimate_output() {
+        let params: ConcatenateParams = serde_json::from_str(
+            r#"{"inputs": ["a.mp4", "b.mp4", "c.mp4"], "output": "out.wav", "gap_seconds": 1.5}"#,
+        ).unwrap();
+        let estimate = params.estimate_output(&[
+            probed(Some((1280, 720)), Some(10.0)),
+            probed(None, Some(5.0)),
+            probed(None, Some(2.0)),
+        ]);
+        assert_eq!(estimate, OutputEstimate {
+            dimensions: None,
+            duration_seconds: Some(20.0),
+        });
+    }
+
+    #[test]
+    fn test_chapters_ffmetadata() {
+        let titles = vec!["Intro".to_string(), "Q&A; part=2 #1\\x\nnext".to_string()];
+        let content = chapters_ffmetadata(&titles, &[2.0005, 3.5]);
+        assert_eq!(
+            content,
+            ";FFMETADATA1\n\
+             [CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=2001\ntitle=Intro\n\
+             [CHAPTER]\nTIMEBASE=1/1000\nSTART=2001\nEND=5501\ntitle=Q&A\\; part\\=2 \\#1\\\\x\\\nnext\n"
+        );
+    }
+
+    // =========================================================================
+    // Layer Audio Validation Tests
+    // =========================================================================
+
+    #[test]
+    fn test_layer_audio_params_valid() {
+        let params = LayerAudioParams {
+            inputs: vec![
+                AudioLayer {
+                    path: "audio1.wav".to_string(),
+                    offset_seconds: 0.0,
+                    volume: 1.0,
+                },
+                AudioLayer {
+                    path: "audio2.wav".to_string(),
+                    offset_seconds: 2.5,
+                    volume: 0.8,
+                },
+            ],
+            output: "mixed.wav".to_string(),
+            overwrite: true,
+        };
+        
+        assert_eq!(params.inputs.len(), 2);
+        assert_eq!(params.inputs[1].offset_seconds, 2.5);
+        assert_eq!(params.inputs[1].volume, 0.8);
+    }
+
+    #[test]
+    fn test_layer_audio_with_negative_offset() {
+        // Negative offset should be allowed (for pre-delay effects)
+        let layer = AudioLayer {
+            path: "audio.wav".to_string(),
+            offset_seconds: -1.0,
+            volume: 1.0,
+        };
+        
+        // The struct allows negative values, validation happens at runtime
+        assert_eq!(layer.offset_seconds, -1.0);
+    }
+
+    // =========================================================================
+    // Duck Audio Params Tests
+    // =========================================================================
+
+    fn duck_params() -> DuckAudioParams {
+        serde_json::from_str(
+            r#"{"background": "music.wav", "foreground": "voice.wav", "output": "ducked.wav"}"#,
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_duck_audio_params_defaults() {
+        let params = duck_params();
+        assert!(params.overwrite);
+        assert_eq!(params.threshold, DEFAULT_DUCK_THRESHOLD);
+        assert_eq!(params.ratio, DEFAULT_DUCK_RATIO);
+        assert_eq!(params.attack_ms, DEFAULT_DUCK_ATTACK_MS);
+        assert_eq!(params.release_ms, DEFAULT_DUCK_RELEASE_MS);
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_duck_audio_build_args() {
+        let params = DuckAudioParams { ratio: 12.0, ..duck_params() };
+        let args = params.build_args("bg.wav", "fg.wav", "tmp.wav");
+        assert_eq!(
+            args,
+            vec![
+                "-i", "bg.wav", "-i", "fg.wav", "-filter_complex",
+                "[1:a]asplit=2[sc][fg];\
+                 [0:a][sc]sidechaincompress=threshold=0.05:ratio=12:attack=20:release=300[ducked];\
+                 [ducked][fg]amix=inputs=2:duration=longest",
+                "tmp.wav",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duck_audio_validation() {
+        let params = DuckAudioParams {
+            background: " ".to_string(),
+            output: String::new(),
+            threshold: 0.0,
+            ratio: 25.0,
+            attack_ms: 0.0,
+            release_ms: 10_000.0,
+            ..duck_params()
+        };
+        let errors = params.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["background", "output", "threshold", "ratio", "attack_ms", "release_ms"]);
+        assert_eq!(errors[3].message, "ratio must be between 1 and 20, got 25");
+
+        // Range bounds are inclusive
+        let params = DuckAudioParams {
+            threshold: DUCK_THRESHOLD_RANGE.1,
+            ratio: DUCK_RATIO_RANGE.0,
+            attack_ms: DUCK_ATTACK_RANGE_MS.1,
+            release_ms: DUCK_RELEASE_RANGE_MS.0,
+            ..duck_params()
+        };
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_duck_audio_estimate_output() {
+        let estimate = duck_params().estimate_output(&[probed(None, Some(30.0)), probed(None, Some(42.5))]);
+        assert_eq!(estimate, OutputEstimate {
+            dimensions: None,
+            duration_seconds: Some(42.5),
+        });
+    }
+
+    // =========================================================================
+    // Overlay Image Params Tests
+    // =========================================================================
+
+    #[test]
+    fn test_overlay_image_params_defaults() {
+        let params: OverlayImageParams = serde_json::from_str(r#"{
+            "video_input": "video.mp4",
+            "image_input": "overlay.png",
+            "output": "output.mp4"
+        }"#).unwrap();
+        
+        assert_eq!(params.x, 0);
+        assert_eq!(params.y, 0);
+        assert!(params.scale.is_none());
+        assert!(params.start_time.is_none());
+        assert!(params.duration.is_none());
+    }
+
+    #[test]
+    fn test_overlay_image_params_with_position() {
+        let params: OverlayImageParams = serde_json::from_str(r#"{
+            "video_input": "video.mp4",
+            "image_input": "overlay.png",
+            "output": "output.mp4",
+            "x": 100,
+            "y": 50,
+            "scale": 0.5
+        }"#).unwrap();
+        
+        assert_eq!(params.x, 100);
+        assert_eq!(params.y, 50);
+        assert_eq!(params.scale, Some(0.5));
+    }
+
+    #[test]
+    fn test_overlay_image_opacity_validation() {
+        let mut params: OverlayImageParams = serde_json::from_str(r#"{
+            "video_input": "video.mp4",
+            "image_input": "overlay.png",
+            "output": "output.mp4"
+        }"#).unwrap();
+        assert!(params.opacity.is_none());
+        assert!(params.validate().is_ok());
+
+        for valid in [0.0, 0.5, 1.0] {
+            params.opacity = Some(valid);
+            assert!(params.validate().is_ok(), "opacity {} should be valid", valid);
+        }
+
+        for invalid in [-0.1, 1.5] {
+            params.opacity = Some(invalid);
+            let errors = params.validate().unwrap_err();
+            assert!(errors.iter().any(|e| e.field == "opacity"));
+        }
+    }
+
+    #[test]
+    fn test_overlay_image_filter_complex() {
+        let mut params: OverlayImageParams = serde_json::from_str(r#"{
+            "video_input": "video.mp4",
+            "image_input": "overlay.png",
+            "output": "output.mp4",
+            "x": 10,
+            "y": 20
+        }"#).unwrap();
+        assert_eq!(params.filter_complex(), "[0:v][1:v]overlay=10:20");
+
+        params.opacity = Some(0.5);
+        assert_eq!(
+            params.filter_complex(),
+            "[1:v]format=rgba,colorchannelmixer=aa=0.5[img];[0:v][img]overlay=10:20"
+        );
+
+        params.scale = Some(0.25);
+        assert_eq!(
+            params.filter_complex(),
+            "[1:v]scale=iw*0.25:ih*0.25,format=rgba,colorchannelmixer=aa=0.5[img];[0:v][img]overlay=10:20"
+        );
+    }
+
+    // =========================================================================
+    // Combine AV Params Tests
+    // =========================================================================
+
+    #[test]
+    fn test_combine_av_params_valid() {
+        let params: CombineAvParams = serde_json::from_str(r#"{
+            "video_input": "video.mp4",
+            "audio_input": "audio.wav",
+            "output": "combined.mp4"
+        }"#).unwrap();
+        
+        assert_eq!(params.video_input, "video.mp4");
+        assert_eq!(params.audio_input, "audio.wav");
+        assert_eq!(params.output, "combined.mp4");
+        assert!(!params.loop_video);
+    }
+
+    #[test]
+    fn test_combine_av_args_stop_at_shortest() {
+        let params: CombineAvParams = serde_json::from_str(r#"{
+            "video_input": "video.mp4",
+            "audio_input": "audio.wav",
+            "output": "combined.mp4"
+        }"#).unwrap();
+
+        let args = params.build_args("in.mp4", "in.wav", "out.mp4");
+        assert_eq!(args[..4], ["-i", "in.mp4", "-i", "in.wav"]);
+        assert!(args.contains(&"-shortest".to_string()));
+        assert!(!args.contains(&"-stream_loop".to_string()));
+    }
+
+    #[test]
+    fn test_combine_av_args_loop_video() {
+        let params: CombineAvParams = serde_json::from_str(r#"{
+            "video_input": "video.mp4",
+            "audio_input": "audio.wav",
+            "output": "combined.mp4",
+            "loop_video": true
+        }"#).unwrap();
+
+        let args = params.build_args("in.mp4", "in.wav", "out.mp4");
+        // The loop option applies to the input that follows it: the video
+        assert_eq!(args[..6], ["-stream_loop", "-1", "-i", "in.mp4", "-i", "in.wav"]);
+        assert!(args.contains(&"-shortest".to_string()));
+        assert_eq!(args.last().map(String::as_str), Some("out.mp4"));
+    }
+
+    // =========================================================================
+    // Transcode Tests
+    // =========================================================================
+
+    fn transcode_params(output: &str) -> TranscodeParams {
+        TranscodeParams {
+            input: "input.mov".to_string(),
+            output: output.to_string(),
+            overwrite: true,
+            video_codec: None,
+            audio_codec: None,
+            video_bitrate: None,
+            audio_bitrate: None,
+            crf: None,
+            preset: None,
+        }
+    }
+
+    #[test]
+    fn test_default_codecs_for_extension() {
+        assert_eq!(default_codecs_for_extension("mp4"), (Some("libx264"), Some("aac")));
+        assert_eq!(default_codecs_for_extension("WEBM"), (Some("libvpx-vp9"), Some("libopus")));
+        assert_eq!(default_codecs_for_extension("gif"), (Some("gif"), None));
+        assert_eq!(default_codecs_for_extension("mp3"), (None, Some("libmp3lame")));
+        assert_eq!(default_codecs_for_extension("flac"), (None, Some("flac")));
+    }
+
+    #[test]
+    fn test_transcode_build_args_defaults_from_extension() {
+        let args = transcode_params("out.webm").build_args("in.mov", "tmp.webm");
+        assert_eq!(args, vec!["-i", "in.mov", "-c:v", "libvpx-vp9", "-c:a", "libopus", "tmp.webm"]);
+
+        let args = transcode_params("out.mp3").build_args("in.mov", "tmp.mp3");
+        assert_eq!(args, vec!["-i", "in.mov", "-vn", "-c:a", "libmp3lame", "tmp.mp3"]);
+    }
+
+    #[test]
+    fn test_transcode_build_args_explicit_options() {
+        let mut params = transcode_params("out.mp4");
+        params.video_codec = Some("libx265".to_string());
+        params.video_bitrate = Some("2M".to_string());
+        params.crf = Some(23);
+        params.preset = Some("slow".to_string());
+        params.audio_bitrate = Some("128k".to_string());
+
+        let args = params.build_args("in.mov", "tmp.mp4");
+        assert_eq!(
+            args,
+            vec![
+                "-i", "in.mov",
+                "-vf", EVEN_DIMENSIONS_FILTER,
+                "-c:v", "libx265", "-b:v", "2M", "-crf", "23", "-preset", "slow",
+                "-c:a", "aac", "-b:a", "128k",
+                "tmp.mp4",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transcode_build_args_copy_skips_encoder_options() {
+        let mut params = transcode_params("out.mkv");
+        params.video_codec = Some("copy".to_string());
+        params.crf = Some(20);
+
+        let args = params.build_args("in.mov", "tmp.mkv");
+        assert!(!args.contains(&"-crf".to_string()));
+        assert!(args.windows(2).any(|w| w == ["-c:v", "copy"]));
+    }
+
+    #[test]
+    fn test_transcode_validation() {
+        assert!(transcode_params("out.mp4").validate().is_ok());
+
+        let mut params = transcode_params("out.mp4");
+        params.video_codec = Some("h264_magic".to_string());
+        params.audio_codec = Some("mp3".to_string());
+        params.video_bitrate = Some("fast".to_string());
+        params.crf = Some(60);
+        params.preset = Some("ludicrous".to_string());
+
+        let errors = params.validate().unwrap_err();
+        for field in ["video_codec", "audio_codec", "video_bitrate", "crf", "preset"] {
+            assert!(errors.iter().any(|e| e.field == field), "expected error for {}", field);
+        }
+
+        // VP9 accepts a wider CRF range
+        let mut params = transcode_params("out.webm");
+        params.crf = Some(60);
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_is_valid_bitrate() {
+        assert!(is_valid_bitrate("128k"));
+        assert!(is_valid_bitrate("2M"));
+        assert!(is_valid_bitrate("1.5M"));
+        assert!(is_valid_bitrate("64000"));
+        assert!(!is_valid_bitrate(""));
+        assert!(!is_valid_bitrate("k"));
+        assert!(!is_valid_bitrate("-128k"));
+        assert!(!is_valid_bitrate("fast"));
+    }
+
+    // =========================================================================
+    // Remux Tests
+    // =========================================================================
+
+    fn remux_params(output: &str) -> RemuxParams {
+        RemuxParams {
+            input: "in.mkv".to_string(),
+            output: output.to_string(),
+            overwrite: true,
+        }
+    }
+
+    fn probe_streams(streams: &[(&str, &str)]) -> serde_json::Value {
+        let streams: Vec<serde_json::Value> = streams
+            .iter()
+            .enumerate()
+            .map(|(i, (codec_type, codec_name))| {
+                serde_json::json!({ "index": i, "codec_type": codec_type, "codec_name": codec_name })
+            })
+            .collect();
+        serde_json::json!({ "streams": streams })
+    }
+
+    #[test]
+    fn test_remux_build_args_copy_all_streams() {
+        let args = remux_params("out.mp4").build_args("in.mkv", "tmp.mp4");
+        assert_eq!(
+            args,
+            vec!["-i", "in.mkv", "-map", "0:v?", "-map", "0:a?", "-map", "0:s?", "-c", "copy", "tmp.mp4"]
+        );
+    }
+
+    #[test]
+    fn test_remux_validation() {
+        assert!(remux_params("out.mp4").validate().is_ok());
+
+        let errors = remux_params("out").validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "output" && e.message.contains("extension")));
+
+        let mut params = remux_params("");
+        params.input = " ".to_string();
+        let errors = params.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "input"));
+        assert!(errors.iter().any(|e| e.field == "output"));
+    }
+
+    fn metadata_params(tags: &[(&str, &str)], strip_all: bool) -> SetMetadataParams {
+        SetMetadataParams {
+            input: "in.mp3".to_string(),
+            output: "out.mp3".to_string(),
+            overwrite: true,
+            tags: tags.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            strip_all,
+        }
+    }
+
+    #[test]
+    fn test_set_metadata_build_args() {
+        let params = metadata_params(&[("title", "Intro; rm -rf / \"$(x)\""), ("artist", "A=B")], false);
+        assert_eq!(
+            params.build_args("in.mp3", "tmp.mp3"),
+            vec![
+                "-i", "in.mp3", "-map", "0", "-c", "copy",
+                "-metadata", "artist=A=B",
+                "-metadata", "title=Intro; rm -rf / \"$(x)\"",
+                "tmp.mp3",
+            ]
+        );
+
+        let params = metadata_params(&[("comment", "")], true);
+        assert_eq!(
+            params.build_args("in.mp4", "tmp.mp4"),
+            vec![
+                "-i", "in.mp4", "-map", "0", "-c", "copy",
+                "-map_metadata", "-1",
+                "-metadata", "comment=",
+                "tmp.mp4",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_metadata_validation() {
+        assert!(metadata_params(&[("title", "Intro")], false).validate().is_ok());
+        assert!(metadata_params(&[], true).validate().is_ok());
+
+        let errors = metadata_params(&[], false).validate().unwrap_err();
+        assert_eq!(errors[0].field, "tags");
+
+        let errors = metadata_params(&[("a=b", "x"), ("", "x"), ("line\nbreak", "x"), ("ok", "nul\0")], false)
+            .validate()
+            .unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["tags.", "tags.a=b", "tags.line\nbreak", "tags.ok"]);
+
+        let mut params = metadata_params(&[("title", "x")], false);
+        params.output = "out".to_string();
+        let errors = params.validate().unwrap_err();
+        assert!(errors[0].message.contains("no file extension"));
+    }
+
+    #[test]
+    fn test_container_codecs_accepts() {
+        let mp4 = remux_codecs_for_extension("MP4").unwrap();
+        assert!(mp4.accepts("video", "h264"));
+        assert!(mp4.accepts("audio", "aac"));
+        assert!(!mp4.accepts("subtitle", "subrip"));
+        assert!(mp4.accepts("data", "bin_data"));
+
+        // Prefix entries match every PCM variant
+        let wav = remux_codecs_for_extension("wav").unwrap();
+        assert!(wav.accepts("audio", "pcm_s24le"));
+        assert!(!wav.accepts("audio", "mp3"));
+        assert!(!wav.accepts("video", "mjpeg"));
+
+        assert!(remux_codecs_for_extension("mkv").is_none());
+    }
+
+    #[test]
+    fn test_remux_incompatible_streams() {
+        let probe = probe_streams(&[("video", "h264"), ("audio", "opus")]);
+        assert_eq!(remux_params("out.avi").incompatible_streams(&probe), vec!["audio stream 1 (opus)"]);
+        assert!(remux_params("out.mp4").incompatible_streams(&probe).is_empty());
+        assert!(remux_params("out.mkv").incompatible_streams(&probe).is_empty());
+
+        let probe = probe_streams(&[("video", "h264"), ("audio", "aac"), ("subtitle", "subrip")]);
+        assert_eq!(
+            remux_params("out.webm").incompatible_streams(&probe),
+            vec!["video stream 0 (h264)", "audio stream 1 (aac)", "subtitle stream 2 (subrip)"]
+        );
+    }
+
+    // =========================================================================
+    // ffmpeg_run Tests
+    // =========================================================================
+
+    fn run_params(args: &[&str]) -> FfmpegRunParams {
+        FfmpegRunParams {
+            inputs: vec!["/tmp/in.mp4".to_string()],
+            output: "/tmp/out.mp4".to_string(),
+            overwrite: true,
+            args: args.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    fn run_errors(params: &FfmpegRunParams) -> Vec<String> {
+        params
+            .validate()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_ffmpeg_run_accepts_allowlisted_options() {
+        let params = run_params(&[
+            "-vf",
+            "scale=w=iw/2:h=-2,eq=contrast=1.2",
+            "-af",
+            "volume@v=0.5,afade=t=in:d=1",
+            "-c:v",
+            "libx264",
+            "-preset",
+            "slow",
+            "-crf",
+            "23",
+            "-c:a",
+            "aac",
+            "-b:a",
+            "192k",
+            "-ar",
+            "48000",
+            "-ss",
+            "00:00:01.5",
+            "-t",
+            "10",
+            "-r",
+            "30000/1001",
+            "-s",
+            "1280x720",
+            "-pix_fmt",
+            "yuv420p",
+            "-movflags",
+            "+faststart",
+            "-map",
+            "0:a?",
+            "-shortest",
+        ]);
+        assert!(params.validate().is_ok(), "{:?}", params.validate().err());
+
+        let params = run_params(&[
+            "-filter_complex",
+            "[0:v]split=2[a][b];[a]hflip[l];[b]select='between(t\\,1\\,2)'[r];[l][r]hstack",
+        ]);
+        assert!(params.validate().is_ok(), "{:?}", params.validate().err());
+    }
+
+    #[test]
+    fn test_ffmpeg_run_rejects_unlisted_options() {
+        for option in [
+            "-f",
+            "-i",
+            "-y",
+            "-filter_script:v",
+            "-attach",
+            "-protocol_whitelist",
+        ] {
+            let errors = run_errors(&run_params(&[option, "x"]));
+            assert!(
+                errors[0].contains("is not an allowed option"),
+                "{}: {:?}",
+                option,
+                errors
+            );
+        }
+
+        let errors = run_errors(&run_params(&["-vf"]));
+        assert_eq!(errors, ["args[0]: -vf requires a value"]);
+    }
+
+    #[test]
+    fn test_ffmpeg_run_rejects_unsafe_filters() {
+        for graph in [
+            "movie=/etc/passwd",
+            "scale=640:-2,subtitles=/tmp/subs.srt",
+            "drawtext=textfile=/etc/hostname",
+            "[0:v]hflip[a];[a]sendcmd=f=cmds.txt",
+            "amovie=http\\://example.com/a.mp3",
+            "reverse",
+            "[0:a]areverse[a]",
+        ] {
+            let errors = run_errors(&run_params(&["-filter_complex", graph]));
+            assert!(
+                errors[0].contains("is not allowed"),
+                "{}: {:?}",
+                graph,
+                errors
+            );
+        }
+
+        // Separators inside quotes do not start a new filter
+        assert!(
+            run_params(&["-vf", "select='1,movie=x'"])
+                .validate()
+                .is_ok()
+        );
+
+        let errors = run_errors(&run_params(&["-vf", "hflip,'unterminated"]));
+        assert!(errors[0].contains("unterminated quote"), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_ffmpeg_run_rejects_bad_values() {
+        for (option, value) in [
+            ("-c:v", "libfoo"),
+            ("-b:v", "1M; rm -rf /"),
+            ("-ss", "file:/etc/passwd"),
+            ("-s", "1280x"),
+            ("-map", "-0:s"),
+            ("-pix_fmt", "../yuv"),
+            ("-preset", "insane"),
+        ] {
+            let errors = run_errors(&run_params(&[option, value]));
+            assert_eq!(
+                errors,
+                [format!("args[1]: Invalid value '{}' for {}", value, option)]
+            );
+        }
+    }
+
+    #[test]
+    fn test_ffmpeg_run_rejects_protocol_paths() {
+        let mut params = run_params(&[]);
+        params.inputs = vec![
+            "file:/etc/passwd".to_string(),
+            "concat:a.mp4|b.mp4".to_string(),
+            "https://example.com/a.mp4".to_string(),
+            "-i".to_string(),
+            "gs://bucket/in.mp4".to_string(),
+            "clips/in.mp4".to_string(),
+        ];
+        params.output = "pipe:1".to_string();
+        let errors = params.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            ["inputs[0]", "inputs[1]", "inputs[2]", "inputs[3]", "output"]
+        );
+
+        let mut params = run_params(&[]);
+        params.inputs.clear();
+        params.output = "/tmp/out".to_string();
+        let errors = run_errors(&params);
+        assert!(errors[0].contains("At least one input"));
+        assert!(errors[1].contains("no file extension"));
+    }
+
+    #[test]
+    fn test_ffmpeg_run_build_args() {
+        let params = run_params(&["-vf", "hflip", "-an"]);
+        let args = params.build_args(&["a.mp4".to_string(), "b.wav".to_string()], "tmp.mp4", 600.0);
+        assert_eq!(
+            args,
+            [
+                "-protocol_whitelist", "file", "-i", "a.mp4", "-protocol_whitelist", "file", "-i", "b.wav",
+                "-vf", "hflip", "-an", "-t", "600", "tmp.mp4"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ffmpeg_run_caps_output_duration() {
+        let inputs = ["in.wav".to_string()];
+
+        // Padding without a length is cut at the cap
+        let args = run_params(&["-af", "apad"]).build_args(&inputs, "tmp.wav", 120.5);
+        assert_eq!(args[args.len() - 3..], ["-t", "120.5", "tmp.wav"]);
+
+        // A caller-supplied -t or -to already bounds the output
+        for options in [&["-af", "apad", "-t", "1:30"][..], &["-ss", "5", "-to", "00:00:20"]] {
+            let params = run_params(options);
+            let args = params.build_args(&inputs, "tmp.wav", 120.0);
+            assert_eq!(args[4..args.len() - 1], params.args[..], "{:?}", options);
+            assert!(params.check_duration(120.0).is_ok());
+        }
+        assert_eq!(run_params(&["-t", "1:30"]).requested_duration(), Some(90.0));
+        assert_eq!(run_params(&["-t", "01:00:00.5"]).requested_duration(), Some(3600.5));
+
+        // ...but only up to the limit
+        let err = run_params(&["-af", "apad", "-t", "600"]).check_duration(120.0).unwrap_err();
+        assert!(err.contains(MAX_OUTPUT_DURATION_ENV), "{}", err);
+        assert!(run_params(&["-to", "2:01"]).check_duration(120.0).is_err());
+
+        // The estimate honors the requested length
+        let input = ProbedInput {
+            dimensions: None,
+            duration_seconds: Some(3000.0),
+        };
+        let estimate = run_params(&["-t", "60"]).estimate_output(&[input]);
+        assert_eq!(estimate.duration_seconds, Some(60.0));
+        assert_eq!(run_params(&[]).estimate_output(&[input]).duration_seconds, Some(3000.0));
+    }
+
+    #[test]
+    fn test_ffmpeg_run_rejects_source_and_file_option_filters() {
+        for graph in ["sine=frequency=440", "anullsrc", "color=c=red:s=320x240", "[0:a]anull[a];sine[b]"] {
+            let errors = run_errors(&run_params(&["-filter_complex", graph]));
+            assert!(errors[0].contains("is not allowed"), "{}: {:?}", graph, errors);
+        }
+
+        for graph in ["eq=/contrast=/tmp/c.txt", "scale=w=640:/h=/etc/h", "[0:v]hflip,fade@f=/d=/tmp/d[v]"] {
+            let errors = run_errors(&run_params(&["-vf", graph]));
+            assert!(errors[0].contains("loads an option from a file"), "{}: {:?}", graph, errors);
+        }
+
+        // Division inside an option value is not the file syntax
+        assert!(run_params(&["-vf", "crop=iw/3:ih/3,setpts=PTS/2"]).validate().is_ok());
+    }
+
+    #[test]
+    fn test_ffmpeg_run_evens_resized_h264_output() {
+        let inputs = ["in.gif".to_string()];
+
+        // Default mp4 encoder is libx264
+        let args = run_params(&["-vf", "crop=iw/3:ih/3", "-t", "5"]).build_args(&inputs, "tmp.mp4", 600.0);
+        assert_eq!(
+            args,
+            [
+                "-protocol_whitelist", "file", "-i", "in.gif", "-vf", "crop=iw/3:ih/3,scale=trunc(iw/2)*2:trunc(ih/2)*2",
+                "-t", "5", "tmp.mp4"
+            ]
+        );
+
+        let args = run_params(&["-filter:v", "hflip,scale=321:-1", "-c:v", "libx265"]).build_args(&inputs, "tmp.mp4", 600.0);
+        assert_eq!(args[5], "hflip,scale=321:-1,scale=trunc(iw/2)*2:trunc(ih/2)*2");
+
+        // No resizing filter, another encoder, no video or a complex graph: unchanged
+        for options in [
+            &["-vf", "hflip"][..],
+            &["-vf", "scale=321:-1", "-c:v", "libvpx-vp9"],
+            &["-vf", "scale=321:-1", "-vn"],
+            &["-filter_complex", "[0:v]scale=321:-1"],
+        ] {
+            let params = run_params(options);
+            let args = params.build_args(&inputs, "tmp.mp4", 600.0);
+            assert_eq!(args[4..args.len() - 3], params.args[..], "{:?}", options);
+        }
+    }
+
+    #[test]
+    fn test_requires_even_dimensions() {
+        for codec in ["libx264", "libx265", "h264_nvenc", "hevc_vaapi", "h264_videotoolbox"] {
+            assert!(requires_even_dimensions(codec), "{}", codec);
+        }
+        for codec in ["libvpx-vp9", "libaom-av1", "gif", "mpeg4", "copy"] {
+            assert!(!requires_even_dimensions(codec), "{}", codec);
+        }
+    }
+
+    // =========================================================================
+    // Hardware Acceleration Tests
+    // =========================================================================
+
+    #[test]
+    fn test_hwaccel_parse() {
+        assert_eq!(HwAccel::parse("none"), Some(HwAccel::None));
+        assert_eq!(HwAccel::parse(""), Some(HwAccel::None));
+        assert_eq!(HwAccel::parse("CUDA"), Some(HwAccel::Cuda));
+        assert_eq!(HwAccel::parse(" vaapi "), Some(HwAccel::Vaapi));
+        assert_eq!(HwAccel::parse("videotoolbox"), Some(HwAccel::VideoToolbox));
+        assert_eq!(HwAccel::parse("qsv"), None);
+
+        for hwaccel in [HwAccel::None, HwAccel::Cuda, HwAccel::Vaapi, HwAccel::VideoToolbox] {
+            assert_eq!(HwAccel::parse(&hwaccel.to_string()), Some(hwaccel));
+        }
+    }
+
+    #[test]
+    fn test_hwaccel_encoders() {
+        assert_eq!(HwAccel::Cuda.encoder_for("libx264"), Some("h264_nvenc"));
+        assert_eq!(HwAccel::Vaapi.encoder_for("libx265"), Some("hevc_vaapi"));
+        assert_eq!(HwAccel::VideoToolbox.encoder_for("libx264"), Some("h264_videotoolbox"));
+        assert_eq!(HwAccel::Cuda.encoder_for("libvpx-vp9"), None);
+        assert_eq!(HwAccel::None.encoder_for("libx264"), None);
+    }
+
+    #[test]
+    fn test_hwaccel_decode_args_precede_first_input() {
+        let args: Vec<String> = ["-ss", "2", "-i", "in.mp4", "-i", "logo.png", "out.mp4"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        assert_eq!(
+            HwAccel::Cuda.with_decode_args(args.clone()),
+            vec!["-ss", "2", "-hwaccel", "cuda", "-i", "in.mp4", "-i", "logo.png", "out.mp4"]
+        );
+        assert_eq!(HwAccel::None.with_decode_args(args.clone()), args);
+    }
+
+    #[test]
+    fn test_transcode_build_args_cuda() {
+        let mut params = transcode_params("out.mp4");
+        params.crf = Some(23);
+        params.preset = Some("slow".to_string());
+
+        let args = params.build_args_with_hwaccel("in.mov", "tmp.mp4", HwAccel::Cuda);
+        assert_eq!(
+            args,
+            vec![
+                "-hwaccel", "cuda", "-i", "in.mov",
+                "-vf", EVEN_DIMENSIONS_FILTER,
+                "-c:v", "h264_nvenc", "-cq", "23",
+                "-c:a", "aac",
+                "tmp.mp4",
+            ]
+        );
+
+        // Software arguments are unchanged
+        assert_eq!(params.build_args("in.mov", "tmp.mp4"), params.build_args_with_hwaccel("in.mov", "tmp.mp4", HwAccel::None));
     }
-}
 
+    #[test]
+    fn test_transcode_build_args_vaapi_uploads_frames() {
+        let mut params = transcode_params("out.mkv");
+        params.video_codec = Some("libx265".to_string());
+        params.video_bitrate = Some("4M".to_string());
+
+        let args = params.build_args_with_hwaccel("in.mov", "tmp.mkv", HwAccel::Vaapi);
+        assert_eq!(
+            args,
+            vec![
+                "-hwaccel", "vaapi", "-vaapi_device", VAAPI_DEVICE, "-i", "in.mov",
+                "-vf", "scale=trunc(iw/2)*2:trunc(ih/2)*2,format=nv12,hwupload",
+                "-c:v", "hevc_vaapi", "-b:v", "4M",
+                "-c:a", "aac",
+                "tmp.mkv",
+            ]
+        );
+    }
 
-// =============================================================================
-// Unit Tests
-// =============================================================================
+    #[test]
+    fn test_transcode_build_args_hwaccel_software_codecs() {
+        // No hardware encoder for VP9: decode on the GPU, encode in software
+        let args = transcode_params("out.webm").build_args_with_hwaccel("in.mov", "tmp.webm", HwAccel::VideoToolbox);
+        assert_eq!(
+            args,
+            vec!["-hwaccel", "videotoolbox", "-i", "in.mov", "-c:v", "libvpx-vp9", "-c:a", "libopus", "tmp.webm"]
+        );
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // Stream copies and audio-only outputs never touch the decoder
+        let mut params = transcode_params("out.mkv");
+        params.video_codec = Some("copy".to_string());
+        assert!(!params.build_args_with_hwaccel("in.mov", "tmp.mkv", HwAccel::Cuda).contains(&"-hwaccel".to_string()));
+        let args = transcode_params("out.mp3").build_args_with_hwaccel("in.mov", "tmp.mp3", HwAccel::Cuda);
+        assert_eq!(args, vec!["-i", "in.mov", "-vn", "-c:a", "libmp3lame", "tmp.mp3"]);
+    }
+
+    #[test]
+    fn test_is_hwaccel_failure() {
+        assert!(is_hwaccel_failure("ffmpeg failed: Unknown encoder 'h264_nvenc'"));
+        assert!(is_hwaccel_failure("[AVHWDeviceContext @ 0x1] Cannot load libcuda.so.1\nDevice creation failed: -1."));
+        assert!(is_hwaccel_failure("[h264_vaapi @ 0x2] No usable encoding profile found."));
+        assert!(!is_hwaccel_failure("in.mov: No such file or directory"));
+        assert!(!is_hwaccel_failure("Unknown encoder 'libfdk_aac'"));
+    }
 
     // =========================================================================
-    // FFmpeg Error Handling Tests (Requirements 9.19, 9.20)
+    // Output Limit Tests
     // =========================================================================
 
+    fn probed(dimensions: Option<(u32, u32)>, duration_seconds: Option<f64>) -> ProbedInput {
+        ProbedInput {
+            dimensions,
+            duration_seconds,
+        }
+    }
+
     #[test]
-    fn test_ffmpeg_error_contains_stderr_output() {
-        // Verify that FFmpeg errors include the stderr output for debugging
-        let stderr_output = "Invalid input file: file not found";
-        let err = Error::ffmpeg(format!("ffmpeg failed: {}", stderr_output));
-        let msg = err.to_string();
-        
-        assert!(msg.contains("FFmpeg"), "Error should mention FFmpeg");
-        assert!(msg.contains("Invalid input file"), "Error should contain stderr output");
+    fn test_output_limits_default_is_unlimited() {
+        let limits = OutputLimits::default();
+        assert!(limits.is_unlimited());
+        let huge = OutputEstimate {
+            dimensions: Some((100_000, 100_000)),
+            duration_seconds: Some(1e9),
+        };
+        assert!(limits.check(&huge).is_ok());
     }
 
     #[test]
-    fn test_ffprobe_error_contains_file_path() {
-        // Verify that FFprobe errors include the file path for context
-        let file_path = "/path/to/nonexistent.mp4";
-        let err = Error::ffmpeg(format!("ffprobe failed for '{}': No such file or directory", file_path));
-        let msg = err.to_string();
-        
-        assert!(msg.contains("ffprobe"), "Error should mention ffprobe");
-        assert!(msg.contains(file_path), "Error should contain file path");
+    fn test_output_limits_pixels_error_names_limit() {
+        let limits = OutputLimits {
+            max_pixels: Some(1920 * 1080),
+            max_duration: None,
+        };
+        assert!(!limits.is_unlimited());
+        assert!(limits.check(&OutputEstimate { dimensions: Some((1920, 1080)), duration_seconds: None }).is_ok());
+
+        let err = limits
+            .check(&OutputEstimate { dimensions: Some((3840, 2160)), duration_seconds: Some(5.0) })
+            .unwrap_err();
+        assert!(matches!(err, Error::Validation(_)));
+        let message = err.to_string();
+        assert!(message.contains(MAX_OUTPUT_PIXELS_ENV), "{}", message);
+        assert!(message.contains("3840x2160"), "{}", message);
     }
 
     #[test]
-    fn test_ffmpeg_error_preserves_codec_errors() {
-        // Verify that codec-related errors are preserved
-        let codec_error = "Unknown encoder 'libx265'";
-        let err = Error::ffmpeg(format!("ffmpeg failed: {}", codec_error));
-        let msg = err.to_string();
-        
-        assert!(msg.contains("libx265"), "Error should preserve codec name");
-        assert!(msg.contains("Unknown encoder"), "Error should preserve error type");
+    fn test_output_limits_duration_error_names_limit() {
+        let limits = OutputLimits {
+            max_pixels: None,
+            max_duration: Some(60.0),
+        };
+        assert!(limits.check(&OutputEstimate { dimensions: None, duration_seconds: Some(60.0) }).is_ok());
+        // Unknown duration is not rejected
+        assert!(limits.check(&OutputEstimate::default()).is_ok());
+
+        let message = limits
+            .check(&OutputEstimate { dimensions: None, duration_seconds: Some(90.5) })
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains(MAX_OUTPUT_DURATION_ENV), "{}", message);
+        assert!(message.contains("90.5s"), "{}", message);
     }
 
     #[test]
-    fn test_ffmpeg_error_preserves_format_errors() {
-        // Verify that format-related errors are preserved
-        let format_error = "Invalid data found when processing input";
-        let err = Error::ffmpeg(format!("ffmpeg failed: {}", format_error));
-        let msg = err.to_string();
-        
-        assert!(msg.contains("Invalid data"), "Error should preserve format error");
+    fn test_probed_input_from_ffprobe() {
+        let json = serde_json::json!({
+            "format": {"duration": "12.480000"},
+            "streams": [
+                {"codec_type": "audio", "codec_name": "aac"},
+                {"codec_type": "video", "codec_name": "h264", "width": 1280, "height": 720}
+            ]
+        });
+        assert_eq!(ProbedInput::from_ffprobe(&json), probed(Some((1280, 720)), Some(12.48)));
+
+        let audio_only = serde_json::json!({
+            "format": {"duration": "3.0"},
+            "streams": [{"codec_type": "audio"}]
+        });
+        assert_eq!(ProbedInput::from_ffprobe(&audio_only), probed(None, Some(3.0)));
+        assert_eq!(ProbedInput::from_ffprobe(&serde_json::json!({})), ProbedInput::default());
     }
 
-    // =========================================================================
-    // Media Info Extraction Tests (Requirement 9.11)
-    // =========================================================================
+    #[test]
+    fn test_probed_input_scaled_to_width() {
+        assert_eq!(probed(Some((1920, 1080)), None).scaled_to_width(480), Some((480, 270)));
+        assert_eq!(probed(Some((1000, 333)), None).scaled_to_width(500), Some((500, 167)));
+        assert_eq!(probed(None, None).scaled_to_width(480), None);
+        assert_eq!(probed(Some((0, 0)), None).scaled_to_width(480), None);
+    }
 
     #[test]
-    fn test_media_info_parsing_video_stream() {
-        // Test parsing of video stream information
-        let stream = StreamInfo {
-            index: 0,
-            codec_type: "video".to_string(),
-            codec_name: "h264".to_string(),
-            width: Some(1920),
-            height: Some(1080),
-            sample_rate: None,
-            channels: None,
-        };
-        
-        assert_eq!(stream.codec_type, "video");
-        assert_eq!(stream.codec_name, "h264");
-        assert_eq!(stream.width, Some(1920));
-        assert_eq!(stream.height, Some(1080));
-        assert!(stream.sample_rate.is_none());
-        assert!(stream.channels.is_none());
+    fn test_gif_estimate_output() {
+        let mut params: VideoToGifParams =
+            serde_json::from_str(r#"{"input": "in.mp4", "output": "out.gif"}"#).unwrap();
+        let input = probed(Some((1920, 1080)), Some(30.0));
+        assert_eq!(params.estimate_output(&input), OutputEstimate::from(&input));
+
+        params.width = Some(640);
+        params.start_time = Some(10.0);
+        assert_eq!(params.estimate_output(&input), OutputEstimate {
+            dimensions: Some((640, 360)),
+            duration_seconds: Some(20.0),
+        });
+
+        params.duration = Some(4.0);
+        assert_eq!(params.estimate_output(&input).duration_seconds, Some(4.0));
     }
 
     #[test]
-    fn test_media_info_parsing_audio_stream() {
-        // Test parsing of audio stream information
-        let stream = StreamInfo {
-            index: 1,
-            codec_type: "audio".to_string(),
-            codec_name: "aac".to_string(),
-            width: None,
-            height: None,
-            sample_rate: Some(48000),
-            channels: Some(2),
+    fn test_contact_sheet_estimate_output() {
+        let params = ContactSheetParams {
+            input: "in.mp4".to_string(),
+            output: "sheet.png".to_string(),
+            overwrite: true,
+            columns: 4,
+            rows: 3,
+            width: 320,
         };
-        
-        assert_eq!(stream.codec_type, "audio");
-        assert_eq!(stream.codec_name, "aac");
-        assert!(stream.width.is_none());
-        assert!(stream.height.is_none());
-        assert_eq!(stream.sample_rate, Some(48000));
-        assert_eq!(stream.channels, Some(2));
+        assert_eq!(params.estimate_output(&probed(Some((1280, 720)), Some(60.0))), OutputEstimate {
+            dimensions: Some((1280, 540)),
+            duration_seconds: None,
+        });
     }
 
     #[test]
-    fn test_media_info_complete_structure() {
-        // Test complete MediaInfo structure with multiple streams
-        let info = MediaInfo {
-            duration: 120.5,
-            format: "matroska,webm".to_string(),
-            streams: vec![
-                StreamInfo {
-                    index: 0,
-                    codec_type: "video".to_string(),
-                    codec_name: "vp9".to_string(),
-                    width: Some(3840),
-                    height: Some(2160),
-                    sample_rate: None,
-                    channels: None,
-                },
-                StreamInfo {
-                    index: 1,
-                    codec_type: "audio".to_string(),
-                    codec_name: "opus".to_string(),
-                    width: None,
-                    height: None,
-                    sample_rate: Some(48000),
-                    channels: Some(6),
-                },
-                StreamInfo {
-                    index: 2,
-                    codec_type: "subtitle".to_string(),
-                    codec_name: "subrip".to_string(),
-                    width: None,
-                    height: None,
-                    sample_rate: None,
-                    channels: None,
-                },
-            ],
-        };
-        
-        assert_eq!(info.duration, 120.5);
-        assert_eq!(info.format, "matroska,webm");
-        assert_eq!(info.streams.len(), 3);
-        
-        // Verify video stream
-        assert_eq!(info.streams[0].codec_type, "video");
-        assert_eq!(info.streams[0].width, Some(3840));
-        
-        // Verify audio stream
-        assert_eq!(info.streams[1].codec_type, "audio");
-        assert_eq!(info.streams[1].channels, Some(6));
-        
-        // Verify subtitle stream
-        assert_eq!(info.streams[2].codec_type, "subtitle");
+    fn test_combine_av_estimate_output() {
+        let mut params: CombineAvParams = serde_json::from_str(
+            r#"{"video_input": "v.mp4", "audio_input": "a.wav", "output": "out.mp4"}"#,
+        ).unwrap();
+        let video = probed(Some((1280, 720)), Some(10.0));
+        let audio = probed(None, Some(45.0));
+        assert_eq!(params.estimate_output(&video, &audio), OutputEstimate {
+            dimensions: Some((1280, 720)),
+            duration_seconds: Some(10.0),
+        });
+
+        params.loop_video = true;
+        assert_eq!(params.estimate_output(&video, &audio).duration_seconds, Some(45.0));
     }
 
     #[test]
-    fn test_media_info_json_output_format() {
-        // Test that MediaInfo serializes to proper JSON format
-        let info = MediaInfo {
-            duration: 60.0,
-            format: "mp4".to_string(),
-            streams: vec![
-                StreamInfo {
-                    index: 0,
-                    codec_type: "video".to_string(),
-                    codec_name: "h264".to_string(),
-                    width: Some(1280),
-                    height: Some(720),
-                    sample_rate: None,
-                    channels: None,
-                },
-            ],
-        };
-        
-        let json = serde_json::to_value(&info).unwrap();
-        
-        // Verify JSON structure
-        assert!(json.is_object());
-        assert!(json["duration"].is_f64());
-        assert!(json["format"].is_string());
-        assert!(json["streams"].is_array());
-        
-        // Verify values
-        assert_eq!(json["duration"].as_f64().unwrap(), 60.0);
-        assert_eq!(json["format"].as_str().unwrap(), "mp4");
-        assert_eq!(json["streams"].as_array().unwrap().len(), 1);
+    fn test_combine_av_args_select_streams() {
+        let mut params: CombineAvParams = serde_json::from_str(
+            r#"{"video_input": "v.mkv", "audio_input": "a.mka", "output": "out.mp4",
+                "video_stream_index": 1, "audio_stream_index": 2}"#,
+        ).unwrap();
+        let args = params.build_args("in.mkv", "in.mka", "out.mp4");
+        assert!(args.windows(2).any(|w| w == ["-map", "0:v:1"]));
+        assert!(args.windows(2).any(|w| w == ["-map", "1:a:2"]));
+        assert!(!args.contains(&"-filter_complex".to_string()));
+
+        params.mix_audio = true;
+        let args = params.build_args("in.mkv", "in.mka", "out.mp4");
+        assert!(args.windows(2).any(|w| {
+            w == ["-filter_complex", "[0:a:0][1:a:2]amix=inputs=2:duration=shortest:dropout_transition=0[aout]"]
+        }));
+        assert!(args.windows(2).any(|w| w == ["-map", "[aout]"]));
+        assert!(!args.iter().any(|a| a.starts_with("1:a:")));
     }
 
     #[test]
-    fn test_media_info_empty_streams() {
-        // Test MediaInfo with no streams (edge case)
-        let info = MediaInfo {
-            duration: 0.0,
-            format: "unknown".to_string(),
-            streams: vec![],
+    fn test_combine_av_check_streams() {
+        let mut params: CombineAvParams = serde_json::from_str(
+            r#"{"video_input": "v.mp4", "audio_input": "a.wav", "output": "out.mp4"}"#,
+        ).unwrap();
+        assert!(params.check_streams(1, 0, 1).is_ok());
+
+        params.video_stream_index = Some(1);
+        params.audio_stream_index = Some(3);
+        let err = params.check_streams(1, 0, 2).unwrap_err().to_string();
+        assert!(err.contains("video_stream_index 1 is out of range; video_input 'v.mp4' has 1 video stream(s)"), "{}", err);
+        assert!(err.contains("audio_stream_index 3 is out of range; audio_input 'a.wav' has 2 audio stream(s)"), "{}", err);
+        assert!(params.check_streams(2, 0, 4).is_ok());
+
+        params.mix_audio = true;
+        let err = params.check_streams(2, 0, 4).unwrap_err().to_string();
+        assert!(err.contains("mix_audio requires an audio stream"), "{}", err);
+        assert!(params.check_streams(2, 1, 4).is_ok());
+    }
+
+    #[test]
+    fn test_stream_count() {
+        let json = serde_json::json!({"streams": [
+            {"codec_type": "video"}, {"codec_type": "audio"}, {"codec_type": "audio"}
+        ]});
+        assert_eq!(AVToolHandler::stream_count(&json, "audio"), 2);
+        assert_eq!(AVToolHandler::stream_count(&json, "video"), 1);
+        assert_eq!(AVToolHandler::stream_count(&serde_json::json!({}), "video"), 0);
+    }
+
+    #[test]
+    fn test_image_to_video_estimate_output() {
+        let params: ImageToVideoParams = serde_json::from_str(
+            r#"{"image_input": "cover.png", "audio_input": "a.mp3", "output": "out.mp4"}"#,
+        ).unwrap();
+        let estimate = params.estimate_output(&probed(Some((1081, 1351)), None), &probed(None, Some(180.0)));
+        assert_eq!(estimate, OutputEstimate {
+            dimensions: Some((1080, 1350)),
+            duration_seconds: Some(180.0),
+        });
+    }
+
+    #[test]
+    fn test_concatenate_estimate_output() {
+        let params = ConcatenateParams {
+            inputs: vec!["a.mp4".to_string(), "b.mp4".to_string()],
+            output: "out.mp4".to_string(),
+            overwrite: true,
+            add_chapters: false,
+            chapter_titles: None,
+            gap_seconds: None,
         };
-        
-        let json = serde_json::to_string(&info).unwrap();
-        let parsed: MediaInfo = serde_json::from_str(&json).unwrap();
-        
-        assert_eq!(parsed.duration, 0.0);
-        assert_eq!(parsed.format, "unknown");
-        assert!(parsed.streams.is_empty());
+        let estimate = params.estimate_output(&[
+            probed(Some((1280, 720)), Some(10.0)),
+            probed(Some((1920, 1080)), Some(5.5)),
+        ]);
+        assert_eq!(estimate, OutputEstimate {
+            dimensions: Some((1920, 1080)),
+            duration_seconds: Some(15.5),
+        });
+    }
+
+    #[test]
+    fn test_layer_audio_estimate_output() {
+        let params: LayerAudioParams = serde_json::from_str(
+            r#"{"inputs": [{"path": "bed.wav"}, {"path": "voice.wav", "offset_seconds": 25.0}], "output": "mix.wav"}"#,
+        ).unwrap();
+        let estimate = params.estimate_output(&[probed(None, Some(30.0)), probed(None, Some(10.0))]);
+        assert_eq!(estimate, OutputEstimate {
+            dimensions: None,
+            duration_seconds: Some(35.0),
+        });
     }
 
     // =========================================================================
-    // VolumeValue Tests
+    // Picture-in-Picture Tests
     // =========================================================================
 
+    fn pip_params() -> PipParams {
+        serde_json::from_str(
+            r#"{"main_input": "/tmp/main.mp4", "overlay_input": "/tmp/cam.mp4", "output": "/tmp/out.mp4"}"#,
+        ).unwrap()
+    }
+
     #[test]
-    fn test_volume_parse_multiplier() {
-        assert_eq!(VolumeValue::parse("0.5").unwrap(), VolumeValue::Multiplier(0.5));
-        assert_eq!(VolumeValue::parse("1.0").unwrap(), VolumeValue::Multiplier(1.0));
-        assert_eq!(VolumeValue::parse("2.0").unwrap(), VolumeValue::Multiplier(2.0));
-        assert_eq!(VolumeValue::parse("1").unwrap(), VolumeValue::Multiplier(1.0));
-        assert_eq!(VolumeValue::parse("0").unwrap(), VolumeValue::Multiplier(0.0));
+    fn test_pip_params_defaults() {
+        let params = pip_params();
+        assert_eq!(params.scale, DEFAULT_PIP_SCALE);
+        assert_eq!(params.position, DEFAULT_PIP_POSITION);
+        assert_eq!(params.margin, DEFAULT_PIP_MARGIN);
+        assert_eq!(params.audio_mode, DEFAULT_PIP_AUDIO_MODE);
+        assert!(params.validate().is_ok());
     }
 
     #[test]
-    fn test_volume_parse_decibels() {
-        assert_eq!(VolumeValue::parse("-3dB").unwrap(), VolumeValue::Decibels(-3.0));
-        assert_eq!(VolumeValue::parse("+6dB").unwrap(), VolumeValue::Decibels(6.0));
-        assert_eq!(VolumeValue::parse("0dB").unwrap(), VolumeValue::Decibels(0.0));
-        assert_eq!(VolumeValue::parse("-10.5dB").unwrap(), VolumeValue::Decibels(-10.5));
-        // Case insensitive
-        assert_eq!(VolumeValue::parse("-3DB").unwrap(), VolumeValue::Decibels(-3.0));
-        assert_eq!(VolumeValue::parse("-3db").unwrap(), VolumeValue::Decibels(-3.0));
+    fn test_pip_validation() {
+        let mut params = pip_params();
+        params.scale = 1.5;
+        params.position = "middle".to_string();
+        params.audio_mode = "overlay".to_string();
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().any(|e| e.field == "scale"));
+        assert!(errors.iter().any(|e| e.field == "position"));
+        assert!(errors.iter().any(|e| e.field == "audio_mode"));
+
+        params = pip_params();
+        params.scale = 0.0;
+        assert!(params.validate().is_err());
     }
 
     #[test]
-    fn test_volume_parse_with_whitespace() {
-        assert_eq!(VolumeValue::parse("  0.5  ").unwrap(), VolumeValue::Multiplier(0.5));
-        assert_eq!(VolumeValue::parse("  -3dB  ").unwrap(), VolumeValue::Decibels(-3.0));
+    fn test_pip_overlay_width_is_even() {
+        let mut params = pip_params();
+        assert_eq!(params.overlay_width(1920), 480);
+        params.scale = 0.3;
+        assert_eq!(params.overlay_width(1278), 382);
+        params.scale = 0.001;
+        assert_eq!(params.overlay_width(640), 2);
     }
 
     #[test]
-    fn test_volume_parse_invalid() {
-        assert!(VolumeValue::parse("").is_err());
-        assert!(VolumeValue::parse("abc").is_err());
-        assert!(VolumeValue::parse("dB").is_err());
-        assert!(VolumeValue::parse("-3").is_err()); // Negative multiplier not allowed
+    fn test_pip_overlay_positions() {
+        let mut params = pip_params();
+        params.margin = 10;
+        let cases = [
+            ("top_left", "10", "10"),
+            ("top_right", "W-w-10", "10"),
+            ("bottom_left", "10", "H-h-10"),
+            ("bottom_right", "W-w-10", "H-h-10"),
+            ("center", "(W-w)/2", "(H-h)/2"),
+        ];
+        for (position, x, y) in cases {
+            params.position = position.to_string();
+            assert_eq!(params.overlay_position(), (x.to_string(), y.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_pip_filter_complex_main_audio() {
+        let params = pip_params();
+        assert_eq!(
+            params.filter_complex(1280, true, true),
+            "[1:v]scale=320:-2[pip];[0:v][pip]overlay=x=W-w-20:y=H-h-20:eof_action=pass[vout]"
+        );
+        let args = params.build_args("main.mp4", "cam.mp4", "out.mp4", 1280, true, true);
+        assert!(args.windows(2).any(|w| w == ["-map", "0:a"]));
+        assert!(!args.iter().any(|a| a.contains("1:a")));
+    }
+
+    #[test]
+    fn test_pip_filter_complex_mix_audio() {
+        let mut params = pip_params();
+        params.audio_mode = "mix".to_string();
+        let filter = params.filter_complex(1280, true, true);
+        assert!(filter.ends_with(";[0:a][1:a]amix=inputs=2:duration=first:dropout_transition=0[aout]"));
+        let args = params.build_args("main.mp4", "cam.mp4", "out.mp4", 1280, true, true);
+        assert!(args.windows(2).any(|w| w == ["-map", "[aout]"]));
+    }
+
+    #[test]
+    fn test_pip_mix_falls_back_when_overlay_silent() {
+        let mut params = pip_params();
+        params.audio_mode = "mix".to_string();
+        assert!(!params.filter_complex(1280, true, false).contains("amix"));
+        let args = params.build_args("main.mp4", "cam.mp4", "out.mp4", 1280, true, false);
+        assert!(args.windows(2).any(|w| w == ["-map", "0:a"]));
+
+        // A silent main video takes the overlay audio, padded to the main video's length
+        assert!(params.filter_complex(1280, false, true).ends_with(";[1:a]apad[aout]"));
+        let args = params.build_args("main.mp4", "cam.mp4", "out.mp4", 1280, false, true);
+        assert!(args.windows(2).any(|w| w == ["-map", "[aout]"]));
+        assert!(args.contains(&"-shortest".to_string()));
+    }
+
+    #[test]
+    fn test_pip_no_audio() {
+        let params = pip_params();
+        let args = params.build_args("main.mp4", "cam.mp4", "out.mp4", 1280, false, true);
+        assert_eq!(args.last().map(String::as_str), Some("out.mp4"));
+        assert!(!args.iter().any(|a| a == "-c:a"));
+    }
+
+    // =========================================================================
+    // Reverse Tests
+    // =========================================================================
+
+    #[test]
+    fn test_reverse_params_defaults() {
+        let params: ReverseParams = serde_json::from_str(
+            r#"{"input": "/tmp/in.mp4", "output": "/tmp/out.mp4"}"#,
+        ).unwrap();
+        assert_eq!(params.max_duration, None);
+        assert_eq!(params.effective_max_duration(DEFAULT_REVERSE_MAX_DURATION), DEFAULT_REVERSE_MAX_DURATION);
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_reverse_max_duration_validation() {
+        let mut params = ReverseParams {
+            input: "/tmp/in.mp4".to_string(),
+            output: "/tmp/out.mp4".to_string(),
+            overwrite: true,
+            max_duration: Some(0.0),
+        };
+        for max in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            params.max_duration = Some(max);
+            assert!(params.validate().is_err(), "{}", max);
+        }
+        params.max_duration = Some(10.0);
+        assert!(params.validate().is_ok());
     }
 
     #[test]
-    fn test_volume_to_ffmpeg_value() {
-        assert_eq!(VolumeValue::Multiplier(0.5).to_ffmpeg_value(), "0.5");
-        assert_eq!(VolumeValue::Multiplier(2.0).to_ffmpeg_value(), "2");
-        assert_eq!(VolumeValue::Decibels(-3.0).to_ffmpeg_value(), "-3dB");
-        assert_eq!(VolumeValue::Decibels(6.0).to_ffmpeg_value(), "6dB");
+    fn test_reverse_duration_guard() {
+        let params = ReverseParams {
+            input: "/tmp/in.mp4".to_string(),
+            output: "/tmp/out.mp4".to_string(),
+            overwrite: true,
+            max_duration: None,
+        };
+        assert!(params.check_duration(Some(12.5), 30.0).is_ok());
+        assert!(params.check_duration(Some(30.0), 30.0).is_ok());
+
+        let err = params.check_duration(Some(95.0), 30.0).unwrap_err();
+        assert!(matches!(err, Error::Validation(_)));
+        assert!(err.to_string().contains("95.0s"));
+        assert!(err.to_string().contains(REVERSE_MAX_DURATION_ENV));
+
+        // A request can lower the server limit but not raise it
+        let lowered = ReverseParams { max_duration: Some(10.0), ..params.clone() };
+        assert!(lowered.check_duration(Some(12.5), 30.0).is_err());
+        let raised = ReverseParams { max_duration: Some(600.0), ..params.clone() };
+        assert_eq!(raised.effective_max_duration(30.0), 30.0);
+        assert!(raised.check_duration(Some(95.0), 30.0).is_err());
+
+        // An unprobeable duration must not slip past the limit
+        let err = params.check_duration(None, 30.0).unwrap_err();
+        assert!(matches!(err, Error::Validation(_)));
+        assert!(err.to_string().contains("Cannot determine"));
+    }
+
+    #[test]
+    fn test_reverse_build_args() {
+        let params = ReverseParams {
+            input: "/tmp/in.mp4".to_string(),
+            output: "/tmp/out.mp4".to_string(),
+            overwrite: true,
+            max_duration: None,
+        };
+        assert_eq!(
+            params.build_args("in.mp4", "out.mp4", true, true),
+            vec!["-i", "in.mp4", "-vf", "reverse", "-af", "areverse", "out.mp4"]
+        );
+        assert_eq!(
+            params.build_args("in.wav", "out.wav", false, true),
+            vec!["-i", "in.wav", "-af", "areverse", "out.wav"]
+        );
+        assert_eq!(
+            params.build_args("in.mp4", "out.mp4", true, false),
+            vec!["-i", "in.mp4", "-vf", "reverse", "out.mp4"]
+        );
     }
 
     // =========================================================================
-    // Parameter Validation Tests
+    // Contact Sheet Tests
     // =========================================================================
 
     #[test]
-    fn test_adjust_volume_params_valid() {
-        let params = AdjustVolumeParams {
-            input: "input.wav".to_string(),
-            output: "output.wav".to_string(),
-            volume: "0.5".to_string(),
-        };
+    fn test_contact_sheet_params_defaults() {
+        let params: ContactSheetParams = serde_json::from_str(
+            r#"{"input": "/tmp/in.mp4", "output": "/tmp/sheet.png"}"#,
+        ).unwrap();
+        assert_eq!(params.columns, DEFAULT_CONTACT_SHEET_COLUMNS);
+        assert_eq!(params.rows, DEFAULT_CONTACT_SHEET_ROWS);
+        assert_eq!(params.width, DEFAULT_CONTACT_SHEET_WIDTH);
         assert!(params.validate().is_ok());
     }
 
     #[test]
-    fn test_adjust_volume_params_invalid_volume() {
-        let params = AdjustVolumeParams {
-            input: "input.wav".to_string(),
-            output: "output.wav".to_string(),
-            volume: "invalid".to_string(),
+    fn test_contact_sheet_validation() {
+        let params = ContactSheetParams {
+            input: "/tmp/in.mp4".to_string(),
+            output: "/tmp/sheet.png".to_string(),
+            overwrite: true,
+            columns: 0,
+            rows: MAX_CONTACT_SHEET_GRID + 1,
+            width: 0,
         };
-        let result = params.validate();
-        assert!(result.is_err());
-        let errors = result.unwrap_err();
-        assert!(errors.iter().any(|e| e.field == "volume"));
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().any(|e| e.field == "columns"));
+        assert!(errors.iter().any(|e| e.field == "rows"));
+        assert!(errors.iter().any(|e| e.field == "width"));
     }
 
     #[test]
-    fn test_adjust_volume_params_empty_input() {
-        let params = AdjustVolumeParams {
-            input: "".to_string(),
-            output: "output.wav".to_string(),
-            volume: "0.5".to_string(),
+    fn test_contact_sheet_filter_spans_duration() {
+        let params = ContactSheetParams {
+            input: "/tmp/in.mp4".to_string(),
+            output: "/tmp/sheet.png".to_string(),
+            overwrite: true,
+            columns: 4,
+            rows: 3,
+            width: 240,
         };
-        let result = params.validate();
-        assert!(result.is_err());
-        let errors = result.unwrap_err();
-        assert!(errors.iter().any(|e| e.field == "input"));
+        assert_eq!(params.filter(24.0), "fps=12/24,scale=240:-2,tile=4x3");
+
+        // Sub-millisecond intervals keep their precision
+        assert_eq!(params.filter(0.006), "fps=12/0.006,scale=240:-2,tile=4x3");
+    }
+
+    #[test]
+    fn test_contact_sheet_filter_unknown_duration() {
+        let params = ContactSheetParams {
+            input: "/tmp/in.mp4".to_string(),
+            output: "/tmp/sheet.png".to_string(),
+            overwrite: true,
+            columns: 2,
+            rows: 2,
+            width: 160,
+        };
+        assert_eq!(params.filter(0.0), "fps=1,scale=160:-2,tile=2x2");
     }
 
     // =========================================================================
-    // GCS URI Detection Tests
+    // Image To Video Tests
     // =========================================================================
 
+    fn image_to_video_params(json: &str) -> ImageToVideoParams {
+        serde_json::from_str(json).unwrap()
+    }
+
     #[test]
-    fn test_is_gcs_uri() {
-        assert!(AVToolHandler::is_gcs_uri("gs://bucket/path/file.mp4"));
-        assert!(AVToolHandler::is_gcs_uri("gs://my-bucket/file.wav"));
-        assert!(!AVToolHandler::is_gcs_uri("/local/path/file.mp4"));
-        assert!(!AVToolHandler::is_gcs_uri("./relative/path.wav"));
-        assert!(!AVToolHandler::is_gcs_uri("file.mp3"));
-        assert!(!AVToolHandler::is_gcs_uri("s3://bucket/file.mp4"));
+    fn test_image_to_video_params_defaults() {
+        let params = image_to_video_params(
+            r#"{"image_input": "/tmp/cover.png", "audio_input": "/tmp/talk.wav", "output": "/tmp/out.mp4"}"#,
+        );
+        assert!(!params.waveform);
+        assert_eq!(params.waveform_color, DEFAULT_WAVEFORM_COLOR);
+        assert!(params.validate().is_ok());
     }
 
-    // =========================================================================
-    // Content Type Tests
-    // =========================================================================
+    #[test]
+    fn test_image_to_video_validation() {
+        let mut params = image_to_video_params(
+            r#"{"image_input": "/tmp/clip.mp4", "audio_input": "/tmp/talk.wav", "output": "/tmp/out.mp4",
+                "waveform_color": "white;drawtext"}"#,
+        );
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|e| e.field == "image_input"));
+        assert!(errors.iter().any(|e| e.field == "waveform_color"));
+
+        params.image_input = "gs://bucket/cover.jpg".to_string();
+        for color in ["red", "0x1db954", "#1DB954", "0x1db954cc"] {
+            params.waveform_color = color.to_string();
+            assert!(params.validate().is_ok(), "{color}");
+        }
+        for color in ["", "0x1db95", "#zzzzzz", "red@0.5"] {
+            params.waveform_color = color.to_string();
+            assert!(params.validate().is_err(), "{color}");
+        }
+    }
 
     #[test]
-    fn test_content_type_from_extension() {
-        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file.mp3")), "audio/mpeg");
-        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file.wav")), "audio/wav");
-        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file.mp4")), "video/mp4");
-        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file.gif")), "image/gif");
-        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file.png")), "image/png");
-        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file.jpg")), "image/jpeg");
-        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file.unknown")), "application/octet-stream");
-        assert_eq!(AVToolHandler::content_type_from_extension(Path::new("file")), "application/octet-stream");
+    fn test_image_to_video_args_loop_image_until_audio_ends() {
+        let params = image_to_video_params(
+            r#"{"image_input": "cover.png", "audio_input": "talk.wav", "output": "out.mp4"}"#,
+        );
+        let args = params.build_args("in.png", "in.wav", "out.mp4", 0);
+        assert_eq!(
+            args,
+            [
+                "-loop", "1", "-i", "in.png", "-i", "in.wav",
+                "-vf", "scale=trunc(iw/2)*2:trunc(ih/2)*2,format=yuv420p",
+                "-map", "0:v:0", "-c:v", "libx264", "-tune", "stillimage",
+                "-map", "1:a:0", "-c:a", "aac", "-shortest", "out.mp4",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_image_to_video_args_with_waveform() {
+        let params = image_to_video_params(
+            r#"{"image_input": "cover.png", "audio_input": "talk.wav", "output": "out.mp4",
+                "waveform": true, "waveform_color": "0x1db954"}"#,
+        );
+        let args = params.build_args("in.png", "in.wav", "out.mp4", 1081);
+
+        let filter = &args[args.iter().position(|a| a == "-filter_complex").unwrap() + 1];
+        assert_eq!(
+            filter,
+            "[0:v]scale=trunc(iw/2)*2:trunc(ih/2)*2[bg];\
+             [1:a]showwaves=s=1080x270:mode=cline:colors=0x1db954[wave];\
+             [bg][wave]overlay=0:H-h:shortest=1,format=yuv420p[vout]"
+        );
+        assert!(args.windows(2).any(|w| w == ["-map", "[vout]"]));
+        assert!(!args.contains(&"-vf".to_string()));
+        assert!(args.ends_with(&["-shortest".to_string(), "out.mp4".to_string()]));
     }
 
     // =========================================================================
-    // Serialization Tests
+    // Scene Detection Tests
     // =========================================================================
 
+    fn detect_params(json: &str) -> DetectScenesParams {
+        serde_json::from_str(json).unwrap()
+    }
+
     #[test]
-    fn test_media_info_serialization() {
-        let info = MediaInfo {
-            duration: 10.5,
-            format: "mp4".to_string(),
-            streams: vec![
-                StreamInfo {
-                    index: 0,
-                    codec_type: "video".to_string(),
-                    codec_name: "h264".to_string(),
-                    width: Some(1920),
-                    height: Some(1080),
-                    sample_rate: None,
-                    channels: None,
-                },
-                StreamInfo {
-                    index: 1,
-                    codec_type: "audio".to_string(),
-                    codec_name: "aac".to_string(),
-                    width: None,
-                    height: None,
-                    sample_rate: Some(44100),
-                    channels: Some(2),
-                },
-            ],
-        };
+    fn test_detect_scenes_params_defaults() {
+        let params = detect_params(r#"{"input": "/tmp/in.mp4"}"#);
+        assert_eq!(params.mode, DEFAULT_DETECT_MODE);
+        assert!(params.threshold.is_none());
+        assert_eq!(params.min_black_duration, DEFAULT_BLACK_MIN_DURATION);
+        assert!(params.validate().is_ok());
+    }
 
-        let json = serde_json::to_string(&info).unwrap();
-        let deserialized: MediaInfo = serde_json::from_str(&json).unwrap();
-        
-        assert_eq!(deserialized.duration, 10.5);
-        assert_eq!(deserialized.format, "mp4");
-        assert_eq!(deserialized.streams.len(), 2);
+    #[test]
+    fn test_detect_scenes_validation() {
+        let params = detect_params(
+            r#"{"input": "/tmp/in.mp4", "mode": "faces", "threshold": 1.5, "min_black_duration": 0}"#,
+        );
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().any(|e| e.field == "mode"));
+        assert!(errors.iter().any(|e| e.field == "threshold"));
+        assert!(errors.iter().any(|e| e.field == "min_black_duration"));
     }
 
     #[test]
-    fn test_convert_audio_params_defaults() {
-        let params: ConvertAudioParams = serde_json::from_str(r#"{
-            "input": "input.wav",
-            "output": "output.mp3"
-        }"#).unwrap();
-        
-        assert_eq!(params.bitrate, DEFAULT_BITRATE);
+    fn test_detect_scenes_filters() {
+        let scene = detect_params(r#"{"input": "/tmp/in.mp4", "threshold": 0.3}"#);
+        assert_eq!(scene.filter(), "select='gt(scene,0.3)',showinfo");
+
+        let black = detect_params(r#"{"input": "/tmp/in.mp4", "mode": "black", "min_black_duration": 1.0}"#);
+        assert_eq!(black.filter(), "blackdetect=d=1:pic_th=0.98");
+        assert_eq!(
+            black.build_args("in.mp4"),
+            vec!["-i", "in.mp4", "-vf", "blackdetect=d=1:pic_th=0.98", "-an", "-f", "null", "-"]
+        );
     }
 
     #[test]
-    fn test_video_to_gif_params_defaults() {
-        let params: VideoToGifParams = serde_json::from_str(r#"{
-            "input": "input.mp4",
-            "output": "output.gif"
-        }"#).unwrap();
-        
-        assert_eq!(params.fps, DEFAULT_GIF_FPS);
-        assert!(params.width.is_none());
-        assert!(params.start_time.is_none());
-        assert!(params.duration.is_none());
+    fn test_parse_showinfo_timestamps() {
+        let stderr = "\
+[Parsed_showinfo_1 @ 0x7f8] config in time_base: 1/12800, frame_rate: 25/1
+[Parsed_showinfo_1 @ 0x7f8] n:   0 pts:  52736 pts_time:4.12    duration:    512 pos:   123456 fmt:yuv420p
+[Parsed_showinfo_1 @ 0x7f8] n:   1 pts: 128000 pts_time:10      duration:    512 pos:   234567 fmt:yuv420p
+frame=  250 fps=0.0 q=-0.0 Lsize=N/A time=00:00:10.00 bitrate=N/A speed=  30x";
+        assert_eq!(parse_showinfo_timestamps(stderr), vec![4.12, 10.0]);
     }
 
     #[test]
-    fn test_audio_layer_defaults() {
-        let layer: AudioLayer = serde_json::from_str(r#"{
-            "path": "audio.wav"
-        }"#).unwrap();
-        
-        assert_eq!(layer.offset_seconds, 0.0);
-        assert_eq!(layer.volume, DEFAULT_VOLUME);
+    fn test_parse_blackdetect_segments() {
+        let stderr = "\
+[blackdetect @ 0x7f8] black_start:0 black_end:1.5 black_duration:1.5
+[blackdetect @ 0x7f8] black_start:12.04 black_end:13.2 black_duration:1.16
+frame=  330 fps=0.0 q=-0.0 Lsize=N/A time=00:00:13.20 bitrate=N/A";
+        let params = detect_params(r#"{"input": "/tmp/in.mp4", "mode": "black"}"#);
+        let result = params.parse_output(stderr);
+
+        assert_eq!(result.timestamps, vec![0.0, 12.04]);
+        assert_eq!(result.black_segments[1], BlackSegment { start: 12.04, end: 13.2, duration: 1.16 });
     }
 
     // =========================================================================
-    // Concatenate Validation Tests
+    // Storage Tests
     // =========================================================================
 
+    /// A handler whose download directory is `temp_dir`.
+    fn storage_handler(server: &wiremock::MockServer, temp_dir: PathBuf) -> AVToolHandler {
+        let mut handler = test_handler(temp_dir.clone());
+        handler.config.download_dir = Some(temp_dir.to_string_lossy().to_string());
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        handler.gcs = GcsClient::with_base_url(auth, server.uri());
+        handler
+    }
+
     #[test]
-    fn test_concatenate_params_valid() {
-        let params = ConcatenateParams {
-            inputs: vec!["file1.mp4".to_string(), "file2.mp4".to_string()],
-            output: "output.mp4".to_string(),
+    fn test_storage_params_validation() {
+        let download = StorageDownloadParams {
+            uri: "https://bucket/video.mp4".to_string(),
+            output_path: "gs://bucket/copy.mp4".to_string(),
+            overwrite: true,
         };
-        
-        assert!(!params.inputs.is_empty());
-        assert_eq!(params.inputs.len(), 2);
+        let errors = download.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "uri"));
+        assert!(errors.iter().any(|e| e.field == "output_path"));
+
+        let upload: StorageUploadParams = serde_json::from_str(
+            r#"{"input_path": "", "uri": "gs://bucket/out.mp4", "content_type": "mp4"}"#,
+        ).unwrap();
+        let errors = upload.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "input_path"));
+        assert!(errors.iter().any(|e| e.field == "content_type"));
+        assert!(!errors.iter().any(|e| e.field == "uri"));
     }
 
-    #[test]
-    fn test_concatenate_params_single_input() {
-        let params = ConcatenateParams {
-            inputs: vec!["file1.mp4".to_string()],
-            output: "output.mp4".to_string(),
+    #[tokio::test]
+    async fn test_storage_download_creates_parent_dirs() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/storage/v1/b/bucket/o/videos%2Fclip.mp4"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"video bytes".to_vec()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("a").join("b").join("clip.mp4");
+        let params = StorageDownloadParams {
+            uri: "gs://bucket/videos/clip.mp4".to_string(),
+            output_path: output.to_string_lossy().to_string(),
+            overwrite: true,
         };
-        
-        // Single input is valid (though not very useful)
-        assert_eq!(params.inputs.len(), 1);
+
+        let result = storage_handler(&server, dir.path().to_path_buf())
+            .storage_download(params)
+            .await
+            .unwrap();
+
+        assert_eq!(result.size_bytes, 11);
+        assert_eq!(std::fs::read(&output).unwrap(), b"video bytes");
     }
 
-    // =========================================================================
-    // Layer Audio Validation Tests
-    // =========================================================================
+    #[tokio::test]
+    async fn test_storage_download_failure_removes_partial_file() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("No such object"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("missing.mp4");
+        let params = StorageDownloadParams {
+            uri: "gs://bucket/missing.mp4".to_string(),
+            output_path: output.to_string_lossy().to_string(),
+            overwrite: true,
+        };
 
-    #[test]
-    fn test_layer_audio_params_valid() {
-        let params = LayerAudioParams {
-            inputs: vec![
-                AudioLayer {
-                    path: "audio1.wav".to_string(),
-                    offset_seconds: 0.0,
-                    volume: 1.0,
-                },
-                AudioLayer {
-                    path: "audio2.wav".to_string(),
-                    offset_seconds: 2.5,
-                    volume: 0.8,
-                },
-            ],
-            output: "mixed.wav".to_string(),
+        let err = storage_handler(&server, dir.path().to_path_buf())
+            .storage_download(params)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("404"), "{}", err);
+        assert!(!output.exists());
+    }
+
+    #[tokio::test]
+    async fn test_storage_download_failure_keeps_existing_file() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("No such object"))
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("existing.mp4");
+        std::fs::write(&output, b"keep me").unwrap();
+        let params = StorageDownloadParams {
+            uri: "gs://bucket/missing.mp4".to_string(),
+            output_path: output.to_string_lossy().to_string(),
+            overwrite: true,
         };
-        
-        assert_eq!(params.inputs.len(), 2);
-        assert_eq!(params.inputs[1].offset_seconds, 2.5);
-        assert_eq!(params.inputs[1].volume, 0.8);
+
+        storage_handler(&server, dir.path().to_path_buf())
+            .storage_download(params)
+            .await
+            .unwrap_err();
+
+        assert_eq!(std::fs::read(&output).unwrap(), b"keep me");
     }
 
-    #[test]
-    fn test_layer_audio_with_negative_offset() {
-        // Negative offset should be allowed (for pre-delay effects)
-        let layer = AudioLayer {
-            path: "audio.wav".to_string(),
-            offset_seconds: -1.0,
-            volume: 1.0,
+    #[tokio::test]
+    async fn test_storage_download_respects_overwrite() {
+        let server = wiremock::MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("existing.mp4");
+        std::fs::write(&output, b"keep me").unwrap();
+        let params = StorageDownloadParams {
+            uri: "gs://bucket/clip.mp4".to_string(),
+            output_path: output.to_string_lossy().to_string(),
+            overwrite: false,
         };
-        
-        // The struct allows negative values, validation happens at runtime
-        assert_eq!(layer.offset_seconds, -1.0);
+
+        let err = storage_handler(&server, dir.path().to_path_buf())
+            .storage_download(params)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("already exists"), "{}", err);
+        assert_eq!(std::fs::read(&output).unwrap(), b"keep me");
     }
 
-    // =========================================================================
-    // Overlay Image Params Tests
-    // =========================================================================
+    #[tokio::test]
+    async fn test_storage_upload_infers_content_type() {
+        use wiremock::matchers::{header, method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/upload/storage/v1/b/bucket/o"))
+            .and(query_param("name", "audio/track.mp3"))
+            .and(header("Content-Type", "audio/mpeg"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("track.mp3");
+        std::fs::write(&input, b"mp3 data").unwrap();
+        let params = StorageUploadParams {
+            input_path: input.to_string_lossy().to_string(),
+            uri: "gs://bucket/audio/track.mp3".to_string(),
+            content_type: None,
+        };
 
-    #[test]
-    fn test_overlay_image_params_defaults() {
-        let params: OverlayImageParams = serde_json::from_str(r#"{
-            "video_input": "video.mp4",
-            "image_input": "overlay.png",
-            "output": "output.mp4"
-        }"#).unwrap();
-        
-        assert_eq!(params.x, 0);
-        assert_eq!(params.y, 0);
-        assert!(params.scale.is_none());
-        assert!(params.start_time.is_none());
-        assert!(params.duration.is_none());
+        let result = storage_handler(&server, dir.path().to_path_buf())
+            .storage_upload(params)
+            .await
+            .unwrap();
+
+        assert_eq!(result.output, "gs://bucket/audio/track.mp3");
+        assert_eq!(result.size_bytes, 8);
     }
 
-    #[test]
-    fn test_overlay_image_params_with_position() {
-        let params: OverlayImageParams = serde_json::from_str(r#"{
-            "video_input": "video.mp4",
-            "image_input": "overlay.png",
-            "output": "output.mp4",
-            "x": 100,
-            "y": 50,
-            "scale": 0.5
-        }"#).unwrap();
-        
-        assert_eq!(params.x, 100);
-        assert_eq!(params.y, 50);
-        assert_eq!(params.scale, Some(0.5));
+    #[tokio::test]
+    async fn test_storage_upload_missing_input() {
+        let server = wiremock::MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let params = StorageUploadParams {
+            input_path: dir.path().join("nope.wav").to_string_lossy().to_string(),
+            uri: "gs://bucket/nope.wav".to_string(),
+            content_type: None,
+        };
+
+        let err = storage_handler(&server, dir.path().to_path_buf())
+            .storage_upload(params)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("Input file not found"), "{}", err);
     }
 
-    // =========================================================================
-    // Combine AV Params Tests
-    // =========================================================================
+    #[tokio::test]
+    async fn test_storage_paths_confined_to_download_dir() {
+        let server = wiremock::MockServer::start().await;
+        let root = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let secret = outside.path().join("credentials.json");
+        std::fs::write(&secret, b"{}").unwrap();
+        let handler = storage_handler(&server, root.path().join("media"));
+
+        let upload = StorageUploadParams {
+            input_path: secret.to_string_lossy().to_string(),
+            uri: "gs://bucket/stolen.json".to_string(),
+            content_type: None,
+        };
+        let err = handler.storage_upload(upload).await.unwrap_err();
+        assert!(err.to_string().contains("outside the download directory"), "{}", err);
+
+        let download = StorageDownloadParams {
+            uri: "gs://bucket/clip.mp4".to_string(),
+            output_path: "../clip.mp4".to_string(),
+            overwrite: true,
+        };
+        let err = handler.storage_download(download).await.unwrap_err();
+        assert!(err.to_string().contains(".."), "{}", err);
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
 
     #[test]
-    fn test_combine_av_params_valid() {
-        let params: CombineAvParams = serde_json::from_str(r#"{
-            "video_input": "video.mp4",
-            "audio_input": "audio.wav",
-            "output": "combined.mp4"
-        }"#).unwrap();
-        
-        assert_eq!(params.video_input, "video.mp4");
-        assert_eq!(params.audio_input, "audio.wav");
-        assert_eq!(params.output, "combined.mp4");
+    fn test_storage_download_overwrite_defaults_off() {
+        let params: StorageDownloadParams =
+            serde_json::from_str(r#"{"uri": "gs://bucket/clip.mp4", "output_path": "clip.mp4"}"#).unwrap();
+        assert!(!params.overwrite);
     }
 }
 
@@ -1465,12 +7339,12 @@ mod property_tests {
 
     /// Strategy to generate valid numeric multipliers (non-negative floats)
     fn valid_multiplier_strategy() -> impl Strategy<Value = f64> {
-        (0.0f64..=10.0f64)
+        0.0f64..=10.0f64
     }
 
     /// Strategy to generate valid dB values (can be negative or positive)
     fn valid_db_strategy() -> impl Strategy<Value = f64> {
-        (-60.0f64..=60.0f64)
+        -60.0f64..=60.0f64
     }
 
     proptest! {
@@ -1738,12 +7612,14 @@ mod property_tests {
                     height: if i % 2 == 0 { Some(1080) } else { None },
                     sample_rate: if i % 2 == 1 { Some(44100) } else { None },
                     channels: if i % 2 == 1 { Some(2) } else { None },
+                    ..Default::default()
                 })
                 .collect();
             
             let info = MediaInfo {
                 duration,
                 format: format.clone(),
+                tags: HashMap::new(),
                 streams,
             };
             
@@ -1795,6 +7671,7 @@ mod property_tests {
             let original = MediaInfo {
                 duration,
                 format: format.clone(),
+                tags: HashMap::new(),
                 streams: vec![
                     StreamInfo {
                         index: 0,
@@ -1804,6 +7681,7 @@ mod property_tests {
                         height: Some(1080),
                         sample_rate: None,
                         channels: None,
+                        ..Default::default()
                     },
                 ],
             };
@@ -1835,6 +7713,7 @@ mod property_tests {
                 height: if has_height { Some(1080) } else { None },
                 sample_rate: if has_sample_rate { Some(44100) } else { None },
                 channels: if has_channels { Some(2) } else { None },
+                ..Default::default()
             };
             
             let json_str = serde_json::to_string(&stream).expect("Should serialize");
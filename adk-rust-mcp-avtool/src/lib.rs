@@ -11,6 +11,18 @@
 //! - `ffmpeg_concatenate_media_files` - Concatenate media files
 //! - `ffmpeg_adjust_volume` - Adjust audio volume
 //! - `ffmpeg_layer_audio_files` - Layer/mix multiple audio files
+//! - `ffmpeg_duck_audio` - Lower background audio under a foreground track
+//! - `ffmpeg_transcode` - Transcode with explicit codec selection
+//! - `ffmpeg_remux` - Change the container without re-encoding
+//! - `ffmpeg_set_metadata` - Set or strip metadata tags
+//! - `ffmpeg_run` - Run ffmpeg with allowlisted options and filters
+//! - `ffmpeg_contact_sheet` - Generate a thumbnail contact sheet from a video
+//! - `ffmpeg_picture_in_picture` - Composite one video over another
+//! - `ffmpeg_reverse` - Reverse video and/or audio
+//! - `ffmpeg_detect_scenes` - Detect scene changes or black frames
+//! - `ffmpeg_image_to_video` - Turn a still image and audio into a video
+//! - `storage_download` - Download a GCS object to local disk
+//! - `storage_upload` - Upload a local file to GCS
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
@@ -21,15 +33,42 @@ pub use handler::{
     AVToolHandler,
     AdjustVolumeParams,
     AudioLayer,
+    BlackSegment,
     CombineAvParams,
     ConcatenateParams,
+    ContactSheetParams,
+    ContainerCodecs,
     ConvertAudioParams,
+    DetectScenesParams,
+    DuckAudioParams,
+    FfmpegRunParams,
+    FfmpegVersion,
     GetMediaInfoParams,
+    HwAccel,
+    ImageToVideoParams,
     LayerAudioParams,
     MediaInfo,
+    OutputEstimate,
+    OutputLimits,
+    OutputResult,
     OverlayImageParams,
+    PipParams,
+    ProbedInput,
+    RemuxParams,
+    ReverseParams,
+    SceneDetectionResult,
+    SequentialTempNamer,
+    SetMetadataParams,
+    StorageDownloadParams,
+    StorageUploadParams,
     StreamInfo,
+    TempNamer,
+    TranscodeParams,
+    UuidTempNamer,
     VideoToGifParams,
     VolumeValue,
+    check_ffmpeg,
+    check_ffmpeg_at,
+    remux_codecs_for_extension,
 };
 pub use server::AVToolServer;
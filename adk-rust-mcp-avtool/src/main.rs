@@ -12,6 +12,16 @@
 //! - `ffmpeg_concatenate_media_files` - Concatenate media files
 //! - `ffmpeg_adjust_volume` - Adjust audio volume
 //! - `ffmpeg_layer_audio_files` - Layer/mix multiple audio files
+//! - `ffmpeg_duck_audio` - Lower background audio under a foreground track
+//! - `ffmpeg_transcode` - Transcode with explicit codec selection
+//! - `ffmpeg_remux` - Change the container without re-encoding
+//! - `ffmpeg_set_metadata` - Set or strip metadata tags
+//! - `ffmpeg_run` - Run ffmpeg with allowlisted options and filters
+//! - `ffmpeg_contact_sheet` - Generate a thumbnail contact sheet from a video
+//! - `ffmpeg_picture_in_picture` - Composite one video over another
+//! - `ffmpeg_reverse` - Reverse video and/or audio
+//! - `ffmpeg_detect_scenes` - Detect scene changes or black frames
+//! - `ffmpeg_image_to_video` - Turn a still image and audio into a video
 //!
 //! # Usage
 //!
@@ -26,8 +36,9 @@
 //! adk-rust-mcp-avtool --transport sse --port 8080
 //! ```
 
-use adk_rust_mcp_avtool::AVToolServer;
-use adk_rust_mcp_common::{Config, McpServerBuilder, TransportArgs};
+use adk_rust_mcp_avtool::{check_ffmpeg_at, AVToolServer};
+use adk_rust_mcp_common::tracing::with_startup_logging;
+use adk_rust_mcp_common::{ConfigArgs, McpServerBuilder, ServerIdentity, TransportArgs};
 use anyhow::Result;
 use clap::Parser;
 
@@ -39,47 +50,64 @@ use adk_rust_mcp_common::otel::{init_tracing_with_optional_otel, OtelConfig};
 #[command(about = "MCP server for audio/video processing using FFmpeg")]
 #[command(version)]
 struct Args {
+    /// Configuration file
+    #[command(flatten)]
+    config: ConfigArgs,
+
     #[command(flatten)]
     transport: TransportArgs,
 }
 
+/// Prefix of environment variables that override the shared ones for this server.
+const ENV_PREFIX: &str = "AVTOOL_";
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Load configuration first, since it decides how logs are redacted
+    let args = Args::parse();
+    let config = with_startup_logging(|| args.config.load_with_prefix(ENV_PREFIX))?;
+
     // Initialize tracing with optional OpenTelemetry support
     #[cfg(feature = "otel")]
     let _otel_guard = {
-        let config = OtelConfig::from_env()
+        let otel = OtelConfig::from_env()
             .unwrap_or_default()
-            .with_service_name("adk-rust-mcp-avtool");
-        init_tracing_with_optional_otel(config).await
+            .with_service_name("adk-rust-mcp-avtool")
+            .with_redaction(config.log_redaction);
+        init_tracing_with_optional_otel(otel).await
     };
 
     #[cfg(not(feature = "otel"))]
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .fmt_fields(config.log_redaction)
         .init();
 
-    // Parse command-line arguments
-    let args = Args::parse();
-    
-    // Load configuration
-    let config = Config::from_env()?;
-    
     tracing::info!(
         project_id = %config.project_id,
         location = %config.location,
         "Starting adk-rust-mcp-avtool server"
     );
 
+    // Fail fast if ffmpeg is missing rather than on the first tool call
+    let ffmpeg = check_ffmpeg_at(&config.ffmpeg_binary()).await?;
+
     // Create server
+    let port = config.port;
+    let identity = ServerIdentity::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+        .with_config(&config)
+        .with_extra("ffmpeg_version", ffmpeg.raw);
     let server = AVToolServer::new(config);
     
     // Get transport configuration
-    let transport = args.transport.into_transport();
+    let sse_keep_alive = args.transport.sse_keep_alive();
+    let transport = args.transport.into_transport_with_port(port);
 
     // Run server
     McpServerBuilder::new(server)
+        .with_server_identity(identity)
         .with_transport(transport)
+        .with_sse_keep_alive(sse_keep_alive)
         .run()
         .await?;
 
@@ -5,11 +5,13 @@
 
 use crate::handler::{
     AVToolHandler, AdjustVolumeParams, CombineAvParams, ConcatenateParams,
-    ConvertAudioParams, GetMediaInfoParams, LayerAudioParams,
-    OverlayImageParams, VideoToGifParams,
+    ContactSheetParams, ConvertAudioParams, DetectScenesParams, DuckAudioParams, FfmpegRunParams,
+    GetMediaInfoParams, ImageToVideoParams, LayerAudioParams,
+    OutputResult, OverlayImageParams, PipParams, RemuxParams, ReverseParams, SetMetadataParams,
+    StorageDownloadParams, StorageUploadParams, TranscodeParams, VideoToGifParams,
 };
 use adk_rust_mcp_common::config::Config;
-use adk_rust_mcp_common::error::Error;
+use adk_rust_mcp_common::error::{Error, ErrorCode};
 use rmcp::{
     model::{
         CallToolResult, Content, ListResourcesResult, ReadResourceResult,
@@ -56,7 +58,7 @@ impl AVToolServer {
         info!(input = %params.input, "Getting media info");
 
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -65,7 +67,7 @@ impl AVToolServer {
         })?;
 
         let info = handler.get_media_info(params).await.map_err(|e| {
-            McpError::internal_error(format!("Failed to get media info: {}", e), None)
+            e.to_mcp_error("Failed to get media info")
         })?;
 
         let json = serde_json::to_string_pretty(&info).map_err(|e| {
@@ -80,7 +82,7 @@ impl AVToolServer {
         info!(input = %params.input, output = %params.output, "Converting WAV to MP3");
 
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -89,10 +91,10 @@ impl AVToolServer {
         })?;
 
         let output = handler.convert_wav_to_mp3(params).await.map_err(|e| {
-            McpError::internal_error(format!("Conversion failed: {}", e), None)
+            e.to_mcp_error("Conversion failed")
         })?;
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Converted to: {}", output))]))
+        Ok(format_output("Converted to:", &output))
     }
 
     /// Convert video to GIF.
@@ -100,7 +102,7 @@ impl AVToolServer {
         info!(input = %params.input, output = %params.output, "Converting video to GIF");
 
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -109,10 +111,10 @@ impl AVToolServer {
         })?;
 
         let output = handler.video_to_gif(params).await.map_err(|e| {
-            McpError::internal_error(format!("Conversion failed: {}", e), None)
+            e.to_mcp_error("Conversion failed")
         })?;
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Created GIF: {}", output))]))
+        Ok(format_output("Created GIF:", &output))
     }
 
     /// Combine audio and video.
@@ -120,7 +122,7 @@ impl AVToolServer {
         info!(video = %params.video_input, audio = %params.audio_input, "Combining audio and video");
 
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -129,10 +131,10 @@ impl AVToolServer {
         })?;
 
         let output = handler.combine_audio_video(params).await.map_err(|e| {
-            McpError::internal_error(format!("Combine failed: {}", e), None)
+            e.to_mcp_error("Combine failed")
         })?;
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Combined to: {}", output))]))
+        Ok(format_output("Combined to:", &output))
     }
 
     /// Overlay image on video.
@@ -140,7 +142,7 @@ impl AVToolServer {
         info!(video = %params.video_input, image = %params.image_input, "Overlaying image on video");
 
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -149,10 +151,10 @@ impl AVToolServer {
         })?;
 
         let output = handler.overlay_image(params).await.map_err(|e| {
-            McpError::internal_error(format!("Overlay failed: {}", e), None)
+            e.to_mcp_error("Overlay failed")
         })?;
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Created: {}", output))]))
+        Ok(format_output("Created:", &output))
     }
 
     /// Concatenate media files.
@@ -160,7 +162,7 @@ impl AVToolServer {
         info!(count = params.inputs.len(), output = %params.output, "Concatenating media files");
 
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -169,10 +171,10 @@ impl AVToolServer {
         })?;
 
         let output = handler.concatenate(params).await.map_err(|e| {
-            McpError::internal_error(format!("Concatenation failed: {}", e), None)
+            e.to_mcp_error("Concatenation failed")
         })?;
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Concatenated to: {}", output))]))
+        Ok(format_output("Concatenated to:", &output))
     }
 
     /// Adjust audio volume.
@@ -180,7 +182,7 @@ impl AVToolServer {
         info!(input = %params.input, volume = %params.volume, "Adjusting audio volume");
 
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -189,10 +191,10 @@ impl AVToolServer {
         })?;
 
         let output = handler.adjust_volume(params).await.map_err(|e| {
-            McpError::internal_error(format!("Volume adjustment failed: {}", e), None)
+            e.to_mcp_error("Volume adjustment failed")
         })?;
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Adjusted volume: {}", output))]))
+        Ok(format_output("Adjusted volume:", &output))
     }
 
     /// Layer multiple audio files.
@@ -200,7 +202,7 @@ impl AVToolServer {
         info!(layers = params.inputs.len(), output = %params.output, "Layering audio files");
 
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -209,19 +211,267 @@ impl AVToolServer {
         })?;
 
         let output = handler.layer_audio(params).await.map_err(|e| {
-            McpError::internal_error(format!("Audio layering failed: {}", e), None)
+            e.to_mcp_error("Audio layering failed")
         })?;
 
-        Ok(CallToolResult::success(vec![Content::text(format!("Layered audio: {}", output))]))
+        Ok(format_output("Layered audio:", &output))
+    }
+
+    /// Duck a background track under a foreground track.
+    pub async fn duck_audio(&self, params: DuckAudioParams) -> Result<CallToolResult, McpError> {
+        info!(background = %params.background, foreground = %params.foreground, output = %params.output, "Ducking audio");
+
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard.as_ref().ok_or_else(|| {
+            McpError::internal_error("Handler not initialized", None)
+        })?;
+
+        let output = handler.duck_audio(params).await.map_err(|e| {
+            e.to_mcp_error("Audio ducking failed")
+        })?;
+
+        Ok(format_output("Ducked audio:", &output))
+    }
+
+    /// Transcode a media file.
+    pub async fn transcode(&self, params: TranscodeParams) -> Result<CallToolResult, McpError> {
+        info!(input = %params.input, output = %params.output, "Transcoding media file");
+
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard.as_ref().ok_or_else(|| {
+            McpError::internal_error("Handler not initialized", None)
+        })?;
+
+        let output = handler.transcode(params).await.map_err(|e| {
+            e.to_mcp_error("Transcode failed")
+        })?;
+
+        Ok(format_output("Transcoded to:", &output))
+    }
+
+    /// Change the container of a media file without re-encoding.
+    pub async fn remux(&self, params: RemuxParams) -> Result<CallToolResult, McpError> {
+        info!(input = %params.input, output = %params.output, "Remuxing media file");
+
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard.as_ref().ok_or_else(|| {
+            McpError::internal_error("Handler not initialized", None)
+        })?;
+
+        let output = handler.remux(params).await.map_err(|e| {
+            e.to_mcp_error("Remux failed")
+        })?;
+
+        Ok(format_output("Remuxed to:", &output))
+    }
+
+    /// Set or strip metadata tags on a media file.
+    pub async fn set_metadata(&self, params: SetMetadataParams) -> Result<CallToolResult, McpError> {
+        info!(input = %params.input, output = %params.output, tags = params.tags.len(), strip_all = params.strip_all, "Setting metadata");
+
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard.as_ref().ok_or_else(|| {
+            McpError::internal_error("Handler not initialized", None)
+        })?;
+
+        let output = handler.set_metadata(params).await.map_err(|e| {
+            e.to_mcp_error("Set metadata failed")
+        })?;
+
+        Ok(format_output("Metadata written to:", &output))
+    }
+
+    /// Run ffmpeg with allowlisted options.
+    pub async fn ffmpeg_run(&self, params: FfmpegRunParams) -> Result<CallToolResult, McpError> {
+        info!(inputs = params.inputs.len(), output = %params.output, args = ?params.args, "Running ffmpeg");
+
+        self.ensure_handler()
+            .await
+            .map_err(|e| e.to_mcp_error("Failed to initialize handler"))?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard
+            .as_ref()
+            .ok_or_else(|| McpError::internal_error("Handler not initialized", None))?;
+
+        let output = handler
+            .ffmpeg_run(params)
+            .await
+            .map_err(|e| e.to_mcp_error("ffmpeg run failed"))?;
+
+        Ok(format_output("Wrote:", &output))
+    }
+
+    /// Composite an overlay video onto a main video.
+    pub async fn picture_in_picture(&self, params: PipParams) -> Result<CallToolResult, McpError> {
+        info!(main = %params.main_input, overlay = %params.overlay_input, "Compositing picture-in-picture");
+
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard.as_ref().ok_or_else(|| {
+            McpError::internal_error("Handler not initialized", None)
+        })?;
+
+        let output = handler.picture_in_picture(params).await.map_err(|e| {
+            e.to_mcp_error("Picture-in-picture failed")
+        })?;
+
+        Ok(format_output("Picture-in-picture saved to:", &output))
+    }
+
+    /// Reverse a media file.
+    pub async fn reverse(&self, params: ReverseParams) -> Result<CallToolResult, McpError> {
+        info!(input = %params.input, output = %params.output, "Reversing media file");
+
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard.as_ref().ok_or_else(|| {
+            McpError::internal_error("Handler not initialized", None)
+        })?;
+
+        let output = handler.reverse(params).await.map_err(|e| {
+            e.to_mcp_error("Reverse failed")
+        })?;
+
+        Ok(format_output("Reversed to:", &output))
+    }
+
+    /// Generate a contact sheet from a video.
+    pub async fn contact_sheet(&self, params: ContactSheetParams) -> Result<CallToolResult, McpError> {
+        info!(input = %params.input, output = %params.output, "Generating contact sheet");
+
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard.as_ref().ok_or_else(|| {
+            McpError::internal_error("Handler not initialized", None)
+        })?;
+
+        let output = handler.contact_sheet(params).await.map_err(|e| {
+            e.to_mcp_error("Contact sheet generation failed")
+        })?;
+
+        Ok(format_output("Contact sheet saved to:", &output))
+    }
+
+    /// Detect scene changes or black frames in a video.
+    pub async fn detect_scenes(&self, params: DetectScenesParams) -> Result<CallToolResult, McpError> {
+        info!(input = %params.input, mode = %params.mode, "Detecting scenes");
+
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard.as_ref().ok_or_else(|| {
+            McpError::internal_error("Handler not initialized", None)
+        })?;
+
+        let result = handler.detect_scenes(params).await.map_err(|e| {
+            e.to_mcp_error("Scene detection failed")
+        })?;
+
+        let json = serde_json::to_string_pretty(&result).map_err(|e| {
+            McpError::internal_error(format!("Failed to serialize result: {}", e), None)
+        })?;
+
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    /// Turn a still image and an audio track into a video.
+    pub async fn image_to_video(&self, params: ImageToVideoParams) -> Result<CallToolResult, McpError> {
+        info!(image = %params.image_input, audio = %params.audio_input, "Creating video from image and audio");
+
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard.as_ref().ok_or_else(|| {
+            McpError::internal_error("Handler not initialized", None)
+        })?;
+
+        let output = handler.image_to_video(params).await.map_err(|e| {
+            e.to_mcp_error("Image to video failed")
+        })?;
+
+        Ok(format_output("Created video:", &output))
+    }
+
+    /// Download a GCS object to local disk.
+    pub async fn storage_download(&self, params: StorageDownloadParams) -> Result<CallToolResult, McpError> {
+        info!(uri = %params.uri, output_path = %params.output_path, "Downloading GCS object");
+
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard.as_ref().ok_or_else(|| {
+            McpError::internal_error("Handler not initialized", None)
+        })?;
+
+        let output = handler.storage_download(params).await.map_err(|e| {
+            e.to_mcp_error("Download failed")
+        })?;
+
+        Ok(format_output("Downloaded to:", &output))
+    }
+
+    /// Upload a local file to GCS.
+    pub async fn storage_upload(&self, params: StorageUploadParams) -> Result<CallToolResult, McpError> {
+        info!(input_path = %params.input_path, uri = %params.uri, "Uploading file to GCS");
+
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard.as_ref().ok_or_else(|| {
+            McpError::internal_error("Handler not initialized", None)
+        })?;
+
+        let output = handler.storage_upload(params).await.map_err(|e| {
+            e.to_mcp_error("Upload failed")
+        })?;
+
+        Ok(format_output("Uploaded to:", &output))
     }
 }
 
+// Method signatures mirror the rmcp trait, which returns `impl Future`.
+#[allow(clippy::manual_async_fn)]
 impl ServerHandler for AVToolServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             instructions: Some(
                 "Audio/video processing server using FFmpeg. \
-                 Provides tools for media conversion, combining, and manipulation."
+                 Provides tools for media conversion, combining, and manipulation, \
+                 plus storage_download and storage_upload to move files between GCS and local disk."
                     .to_string(),
             ),
             capabilities: ServerCapabilities::builder()
@@ -258,11 +508,13 @@ impl ServerHandler for AVToolServer {
                 ),
                 create_tool::<OverlayImageParams>(
                     "ffmpeg_overlay_image_on_video",
-                    "Overlay an image on a video at a specified position with optional timing.",
+                    "Overlay an image on a video at a specified position with optional timing and opacity.",
                 ),
                 create_tool::<ConcatenateParams>(
                     "ffmpeg_concatenate_media_files",
-                    "Concatenate multiple media files into a single file.",
+                    "Concatenate multiple media files into a single file. Set gap_seconds to join \
+                     audio clips (e.g. narration lines) with silence between them, and add_chapters \
+                     to mark each clip as a chapter.",
                 ),
                 create_tool::<AdjustVolumeParams>(
                     "ffmpeg_adjust_volume",
@@ -272,6 +524,71 @@ impl ServerHandler for AVToolServer {
                     "ffmpeg_layer_audio_files",
                     "Layer/mix multiple audio files with optional offset and volume control.",
                 ),
+                create_tool::<DuckAudioParams>(
+                    "ffmpeg_duck_audio",
+                    "Mix a foreground track (e.g. narration) over a background track (e.g. music), \
+                     automatically lowering the background whenever the foreground is audible. \
+                     Threshold, ratio, attack, and release control the ducking.",
+                ),
+                create_tool::<TranscodeParams>(
+                    "ffmpeg_transcode",
+                    "Transcode a media file with explicit video/audio codec, bitrate, CRF, and preset. \
+                     Codecs default to sensible choices for the output file extension.",
+                ),
+                create_tool::<RemuxParams>(
+                    "ffmpeg_remux",
+                    "Change a media file's container (e.g. .mkv to .mp4) by copying streams without \
+                     re-encoding. Fails if the target container cannot hold the source codecs; use \
+                     ffmpeg_transcode in that case.",
+                ),
+                create_tool::<SetMetadataParams>(
+                    "ffmpeg_set_metadata",
+                    "Set container metadata tags (title, artist, comment, ...) on a media file, or strip \
+                     all existing metadata with strip_all. Streams are copied without re-encoding.",
+                ),
+                create_tool::<FfmpegRunParams>(
+                    "ffmpeg_run",
+                    "Run ffmpeg on the given inputs with custom options for cases the other tools \
+                     don't cover. Only allowlisted options (filters, codecs, bitrates, timing, -map) \
+                     and filters are accepted; -f, -i, protocols, source and file-reading filters are \
+                     rejected. Output without -t/-to is capped at MCP_MAX_OUTPUT_DURATION (default 4 hours).",
+                ),
+                create_tool::<PipParams>(
+                    "ffmpeg_picture_in_picture",
+                    "Composite a scaled overlay video onto a main video (picture-in-picture), \
+                     with corner/center positioning and optional mixing of the overlay's audio.",
+                ),
+                create_tool::<ReverseParams>(
+                    "ffmpeg_reverse",
+                    "Play a video and/or audio file backwards. Inputs longer than the server limit \
+                     (default 30s; max_duration can lower it) are rejected because reversing buffers \
+                     the whole stream in memory.",
+                ),
+                create_tool::<ContactSheetParams>(
+                    "ffmpeg_contact_sheet",
+                    "Generate a single contact sheet image (grid of thumbnails) sampled evenly across a video.",
+                ),
+                create_tool::<DetectScenesParams>(
+                    "ffmpeg_detect_scenes",
+                    "Detect scene changes (mode 'scene') or black frames (mode 'black') in a video and \
+                     return their timestamps in seconds. Useful for finding natural cut points to trim or concatenate.",
+                ),
+                create_tool::<ImageToVideoParams>(
+                    "ffmpeg_image_to_video",
+                    "Turn a still image and an audio track into an MP4 that shows the image for the length \
+                     of the audio (an audiogram), optionally with the audio waveform drawn over the image.",
+                ),
+                create_tool::<StorageDownloadParams>(
+                    "storage_download",
+                    "Download a GCS object (gs://bucket/path) to a local file inside the server's download \
+                     directory, creating parent directories as needed. Use this to fetch generated artifacts \
+                     without running a media operation.",
+                ),
+                create_tool::<StorageUploadParams>(
+                    "storage_upload",
+                    "Upload a local file from the server's download directory to a GCS URI. The content \
+                     type is inferred from the file extension unless content_type is given.",
+                ),
             ];
 
             Ok(ListToolsResult {
@@ -321,6 +638,54 @@ impl ServerHandler for AVToolServer {
                     let tool_params: LayerAudioParams = parse_params(params.arguments)?;
                     self.layer_audio(tool_params).await
                 }
+                "ffmpeg_duck_audio" => {
+                    let tool_params: DuckAudioParams = parse_params(params.arguments)?;
+                    self.duck_audio(tool_params).await
+                }
+                "ffmpeg_transcode" => {
+                    let tool_params: TranscodeParams = parse_params(params.arguments)?;
+                    self.transcode(tool_params).await
+                }
+                "ffmpeg_remux" => {
+                    let tool_params: RemuxParams = parse_params(params.arguments)?;
+                    self.remux(tool_params).await
+                }
+                "ffmpeg_set_metadata" => {
+                    let tool_params: SetMetadataParams = parse_params(params.arguments)?;
+                    self.set_metadata(tool_params).await
+                }
+                "ffmpeg_run" => {
+                    let tool_params: FfmpegRunParams = parse_params(params.arguments)?;
+                    self.ffmpeg_run(tool_params).await
+                }
+                "ffmpeg_picture_in_picture" => {
+                    let tool_params: PipParams = parse_params(params.arguments)?;
+                    self.picture_in_picture(tool_params).await
+                }
+                "ffmpeg_reverse" => {
+                    let tool_params: ReverseParams = parse_params(params.arguments)?;
+                    self.reverse(tool_params).await
+                }
+                "ffmpeg_contact_sheet" => {
+                    let tool_params: ContactSheetParams = parse_params(params.arguments)?;
+                    self.contact_sheet(tool_params).await
+                }
+                "ffmpeg_detect_scenes" => {
+                    let tool_params: DetectScenesParams = parse_params(params.arguments)?;
+                    self.detect_scenes(tool_params).await
+                }
+                "ffmpeg_image_to_video" => {
+                    let tool_params: ImageToVideoParams = parse_params(params.arguments)?;
+                    self.image_to_video(tool_params).await
+                }
+                "storage_download" => {
+                    let tool_params: StorageDownloadParams = parse_params(params.arguments)?;
+                    self.storage_download(tool_params).await
+                }
+                "storage_upload" => {
+                    let tool_params: StorageUploadParams = parse_params(params.arguments)?;
+                    self.storage_upload(tool_params).await
+                }
                 _ => Err(McpError::invalid_params(format!("Unknown tool: {}", params.name), None)),
             }
         }
@@ -390,8 +755,22 @@ fn parse_params<T: for<'de> Deserialize<'de>>(
     arguments
         .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
         .transpose()
-        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), None))?
-        .ok_or_else(|| McpError::invalid_params("Missing parameters", None))
+        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data())))?
+        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))
+}
+
+/// Format a file-producing tool result as text plus structured output.
+///
+/// The first line keeps the `<label> <path>` form; size and duration follow.
+fn format_output(label: &str, result: &OutputResult) -> CallToolResult {
+    let mut message = format!("{} {}\nSize: {} bytes", label, result.output, result.size_bytes);
+    if let Some(duration) = result.duration_seconds {
+        message.push_str(&format!(", duration: {:.2}s", duration));
+    }
+
+    let mut tool_result = CallToolResult::success(vec![Content::text(message)]);
+    tool_result.structured_content = serde_json::to_value(result).ok();
+    tool_result
 }
 
 // =============================================================================
@@ -405,9 +784,7 @@ mod tests {
     fn test_config() -> Config {
         Config {
             project_id: "test-project".to_string(),
-            location: "us-central1".to_string(),
-            gcs_bucket: None,
-            port: 8080,
+            ..Default::default()
         }
     }
 
@@ -439,6 +816,37 @@ mod tests {
         assert_eq!(result.unwrap().input, "test.mp4");
     }
 
+    #[test]
+    fn test_format_output_includes_size_and_duration() {
+        let result = OutputResult {
+            output: "/tmp/out.mp3".to_string(),
+            size_bytes: 1024,
+            duration_seconds: Some(2.5),
+        };
+
+        let tool_result = format_output("Converted to:", &result);
+        let text = tool_result.content[0].as_text().expect("text content");
+        assert_eq!(text.text, "Converted to: /tmp/out.mp3\nSize: 1024 bytes, duration: 2.50s");
+
+        let structured = tool_result.structured_content.expect("structured content");
+        assert_eq!(structured["output"], "/tmp/out.mp3");
+        assert_eq!(structured["size_bytes"], 1024);
+        assert_eq!(structured["duration_seconds"], 2.5);
+    }
+
+    #[test]
+    fn test_format_output_omits_missing_duration() {
+        let result = OutputResult {
+            output: "/tmp/sheet.png".to_string(),
+            size_bytes: 2048,
+            duration_seconds: None,
+        };
+
+        let tool_result = format_output("Contact sheet saved to:", &result);
+        let text = tool_result.content[0].as_text().expect("text content");
+        assert_eq!(text.text, "Contact sheet saved to: /tmp/sheet.png\nSize: 2048 bytes");
+    }
+
     #[test]
     fn test_parse_params_missing() {
         let result: Result<GetMediaInfoParams, _> = parse_params(None);
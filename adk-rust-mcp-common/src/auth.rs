@@ -19,7 +19,7 @@ enum TokenSource {
     /// Production token provider from gcp_auth
     Provider(Arc<dyn TokenProvider>),
     /// Mock token for testing
-    #[cfg(test)]
+    #[cfg(any(test, feature = "test-utils"))]
     Mock(String),
 }
 
@@ -61,9 +61,10 @@ impl AuthProvider {
 
     /// Create a mock auth provider for testing.
     ///
-    /// This method is only available in test builds and returns a provider
-    /// that always returns the specified token without making any network calls.
-    #[cfg(test)]
+    /// This method is only available in test builds (or with the `test-utils`
+    /// feature) and returns a provider that always returns the specified token
+    /// without making any network calls.
+    #[cfg(any(test, feature = "test-utils"))]
     pub fn mock(token: &str) -> Self {
         Self {
             source: TokenSource::Mock(token.to_string()),
@@ -98,7 +99,7 @@ impl AuthProvider {
                 debug!("Token obtained successfully");
                 Ok(token.as_str().to_string())
             }
-            #[cfg(test)]
+            #[cfg(any(test, feature = "test-utils"))]
             TokenSource::Mock(token) => {
                 debug!("Returning mock token");
                 Ok(token.clone())
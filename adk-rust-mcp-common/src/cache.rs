@@ -0,0 +1,238 @@
+//! Content-addressed caching of generation results.
+//!
+//! Deterministic requests (same prompt, seed, model and output target) always
+//! produce the same media, so servers can remember where a previous result was
+//! written and return it instead of calling the generation API again.
+//!
+//! Entries are keyed by [`cache_key`], a SHA-256 over a canonical JSON encoding
+//! of the request parameters. Two backends are provided:
+//! - [`InMemoryResultCache`] - per-process cache (the default)
+//! - [`GcsResultCache`] - shared cache stored as JSON objects under a GCS prefix
+
+use crate::auth::AuthProvider;
+use crate::config::Config;
+use crate::error::Error;
+use crate::gcs::{GcsClient, GcsUri};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+/// Environment variable selecting the GCS-backed cache (`gs://bucket/prefix`).
+pub const RESULT_CACHE_URI_ENV: &str = "RESULT_CACHE_URI";
+
+/// A cached generation result: where the outputs were written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedResult {
+    /// Output locations (local paths or `gs://` URIs), in generation order.
+    pub uris: Vec<String>,
+}
+
+impl CachedResult {
+    /// Whether the cached local outputs are still on disk.
+    ///
+    /// `gs://` URIs are trusted without a round trip; a cache hit whose local
+    /// files were removed should be treated as a miss.
+    pub fn local_outputs_exist(&self) -> bool {
+        self.uris
+            .iter()
+            .filter(|uri| !uri.starts_with("gs://"))
+            .all(|path| std::path::Path::new(path).exists())
+    }
+}
+
+/// Storage for generation results keyed by [`cache_key`].
+#[async_trait]
+pub trait ResultCache: Send + Sync {
+    /// Look up a cached result.
+    async fn get(&self, key: &str) -> Result<Option<CachedResult>, Error>;
+
+    /// Store a result, replacing any existing entry for the key.
+    async fn put(&self, key: &str, result: &CachedResult) -> Result<(), Error>;
+}
+
+/// Compute the cache key for a request.
+///
+/// `namespace` separates tools (e.g. `"image_generate"`) so identical
+/// parameter sets for different operations never collide. Object keys are
+/// sorted before hashing, so field order does not affect the key.
+///
+/// # Errors
+/// Returns `Error::Validation` if the parameters cannot be serialized to JSON.
+pub fn cache_key<T: Serialize>(namespace: &str, params: &T) -> Result<String, Error> {
+    let value = serde_json::to_value(params)
+        .map_err(|e| Error::validation(format!("Failed to serialize cache key params: {}", e)))?;
+
+    let mut canonical = String::new();
+    write_canonical(&value, &mut canonical);
+
+    let mut hasher = Sha256::new();
+    hasher.update(namespace.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(canonical.as_bytes());
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// Write `value` as compact JSON with object keys in sorted order.
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// In-memory result cache, scoped to the lifetime of the process.
+#[derive(Debug, Default)]
+pub struct InMemoryResultCache {
+    entries: Mutex<HashMap<String, CachedResult>>,
+}
+
+impl InMemoryResultCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ResultCache for InMemoryResultCache {
+    async fn get(&self, key: &str) -> Result<Option<CachedResult>, Error> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(entries.get(key).cloned())
+    }
+
+    async fn put(&self, key: &str, result: &CachedResult) -> Result<(), Error> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.insert(key.to_string(), result.clone());
+        Ok(())
+    }
+}
+
+/// GCS-backed result cache, shared across processes.
+///
+/// Each entry is stored as `<prefix>/<key>.json`.
+pub struct GcsResultCache {
+    gcs: GcsClient,
+    bucket: String,
+    prefix: String,
+}
+
+impl GcsResultCache {
+    /// Create a cache storing entries under `location` (`gs://bucket/prefix`).
+    ///
+    /// # Errors
+    /// Returns `Error::Gcs` if `location` is not a valid GCS URI.
+    pub fn new(gcs: GcsClient, location: &str) -> Result<Self, Error> {
+        // Allow a bare bucket (`gs://bucket` or `gs://bucket/`) as the location.
+        let with_object = if location.trim_start_matches("gs://").contains('/') {
+            location.to_string()
+        } else {
+            format!("{}/", location)
+        };
+        let uri = GcsUri::parse(&with_object)?;
+
+        Ok(Self {
+            gcs,
+            bucket: uri.bucket,
+            prefix: uri.object.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// The GCS object holding the entry for `key`.
+    pub fn entry_uri(&self, key: &str) -> GcsUri {
+        let object = if self.prefix.is_empty() {
+            format!("{}.json", key)
+        } else {
+            format!("{}/{}.json", self.prefix, key)
+        };
+        GcsUri {
+            bucket: self.bucket.clone(),
+            object,
+            generation: None,
+        }
+    }
+}
+
+#[async_trait]
+impl ResultCache for GcsResultCache {
+    async fn get(&self, key: &str) -> Result<Option<CachedResult>, Error> {
+        let uri = self.entry_uri(key);
+        if !self.gcs.exists(&uri).await? {
+            return Ok(None);
+        }
+
+        let data = self.gcs.download(&uri).await?;
+        let result = serde_json::from_slice(&data).map_err(|e| {
+            Error::validation(format!("Invalid cache entry {}: {}", uri, e))
+        })?;
+        Ok(Some(result))
+    }
+
+    async fn put(&self, key: &str, result: &CachedResult) -> Result<(), Error> {
+        let uri = self.entry_uri(key);
+        let data = serde_json::to_vec(result)
+            .map_err(|e| Error::validation(format!("Failed to serialize cache entry: {}", e)))?;
+        self.gcs.upload(&uri, &data, "application/json").await?;
+        Ok(())
+    }
+}
+
+/// Build the result cache selected by the environment.
+///
+/// Uses [`GcsResultCache`] when `RESULT_CACHE_URI` is set, otherwise an
+/// [`InMemoryResultCache`].
+///
+/// # Errors
+/// Returns `Error::Gcs` if `RESULT_CACHE_URI` is not a valid GCS URI.
+pub fn result_cache_from_env(auth: &Arc<AuthProvider>) -> Result<Arc<dyn ResultCache>, Error> {
+    result_cache_at(auth, std::env::var(RESULT_CACHE_URI_ENV).ok().as_deref())
+}
+
+/// Build the result cache selected by `config.result_cache_uri`, which comes
+/// from `RESULT_CACHE_URI` or `[cache] result_uri` in the configuration file.
+///
+/// # Errors
+/// Returns `Error::Gcs` if the location is not a valid GCS URI.
+pub fn result_cache_from_config(auth: &Arc<AuthProvider>, config: &Config) -> Result<Arc<dyn ResultCache>, Error> {
+    result_cache_at(auth, config.result_cache_uri.as_deref())
+}
+
+fn result_cache_at(auth: &Arc<AuthProvider>, location: Option<&str>) -> Result<Arc<dyn ResultCache>, Error> {
+    match location.map(str::trim).filter(|l| !l.is_empty()) {
+        Some(location) => {
+            info!(location = %location, "Using GCS-backed result cache");
+            let gcs = GcsClient::with_auth(Arc::clone(auth));
+            Ok(Arc::new(GcsResultCache::new(gcs, location)?))
+        }
+        None => Ok(Arc::new(InMemoryResultCache::new())),
+    }
+}
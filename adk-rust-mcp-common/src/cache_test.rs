@@ -0,0 +1,160 @@
+//! Unit tests for the result cache module.
+
+use crate::cache::{CachedResult, InMemoryResultCache, ResultCache, cache_key};
+use serde_json::json;
+
+#[test]
+fn cache_key_is_stable_and_hex() {
+    let params = json!({"prompt": "a cat", "seed": 42});
+    let a = cache_key("image_generate", &params).unwrap();
+    let b = cache_key("image_generate", &params).unwrap();
+    assert_eq!(a, b);
+    assert_eq!(a.len(), 64);
+    assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+}
+
+#[test]
+fn cache_key_ignores_field_order() {
+    let a = cache_key("ns", &json!({"prompt": "a cat", "seed": 42, "nested": {"x": 1, "y": 2}})).unwrap();
+    let b = cache_key("ns", &json!({"nested": {"y": 2, "x": 1}, "seed": 42, "prompt": "a cat"})).unwrap();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn cache_key_changes_with_params_and_namespace() {
+    let base = cache_key("image_generate", &json!({"prompt": "a cat", "seed": 42})).unwrap();
+    let other_seed = cache_key("image_generate", &json!({"prompt": "a cat", "seed": 43})).unwrap();
+    let other_ns = cache_key("music_generate", &json!({"prompt": "a cat", "seed": 42})).unwrap();
+    assert_ne!(base, other_seed);
+    assert_ne!(base, other_ns);
+}
+
+#[tokio::test]
+async fn in_memory_cache_round_trip() {
+    let cache = InMemoryResultCache::new();
+    assert_eq!(cache.get("k").await.unwrap(), None);
+
+    let result = CachedResult {
+        uris: vec!["gs://bucket/a.png".to_string()],
+    };
+    cache.put("k", &result).await.unwrap();
+    assert_eq!(cache.get("k").await.unwrap(), Some(result));
+}
+
+#[test]
+fn local_outputs_exist_checks_only_local_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let present = dir.path().join("a.png");
+    std::fs::write(&present, b"png").unwrap();
+
+    let hit = CachedResult {
+        uris: vec![present.display().to_string(), "gs://bucket/b.png".to_string()],
+    };
+    assert!(hit.local_outputs_exist());
+
+    let stale = CachedResult {
+        uris: vec![dir.path().join("missing.png").display().to_string()],
+    };
+    assert!(!stale.local_outputs_exist());
+}
+
+mod gcs_cache_tests {
+    use std::sync::Arc;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use crate::auth::AuthProvider;
+    use crate::cache::{CachedResult, GcsResultCache, ResultCache};
+    use crate::gcs::GcsClient;
+
+    fn cache_for(server: &MockServer, location: &str) -> GcsResultCache {
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        GcsResultCache::new(GcsClient::with_base_url(auth, server.uri()), location).unwrap()
+    }
+
+    #[test]
+    fn entry_uri_uses_prefix() {
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        let cache = GcsResultCache::new(
+            GcsClient::with_base_url(Arc::clone(&auth), "http://localhost".to_string()),
+            "gs://cache-bucket/results/",
+        )
+        .unwrap();
+        assert_eq!(cache.entry_uri("abc").to_string(), "gs://cache-bucket/results/abc.json");
+
+        let bare = GcsResultCache::new(
+            GcsClient::with_base_url(auth, "http://localhost".to_string()),
+            "gs://cache-bucket",
+        )
+        .unwrap();
+        assert_eq!(bare.entry_uri("abc").to_string(), "gs://cache-bucket/abc.json");
+    }
+
+    #[test]
+    fn rejects_invalid_location() {
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        let result = GcsResultCache::new(
+            GcsClient::with_base_url(auth, "http://localhost".to_string()),
+            "s3://bucket/prefix",
+        );
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_missing_entry_returns_none() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/storage/v1/b/cache-bucket/o/results%2Fabc.json"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let cache = cache_for(&server, "gs://cache-bucket/results");
+        assert_eq!(cache.get("abc").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn get_existing_entry_downloads_json() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/storage/v1/b/cache-bucket/o/results%2Fabc.json"))
+            .and(query_param("alt", "media"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(r#"{"uris":["gs://out/image.png"]}"#),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/storage/v1/b/cache-bucket/o/results%2Fabc.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .mount(&server)
+            .await;
+
+        let cache = cache_for(&server, "gs://cache-bucket/results");
+        let entry = cache.get("abc").await.unwrap();
+        assert_eq!(
+            entry,
+            Some(CachedResult {
+                uris: vec!["gs://out/image.png".to_string()]
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn put_uploads_entry() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/upload/storage/v1/b/cache-bucket/o"))
+            .and(query_param("name", "results/abc.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let cache = cache_for(&server, "gs://cache-bucket/results");
+        let result = CachedResult {
+            uris: vec!["gs://out/image.png".to_string()],
+        };
+        cache.put("abc", &result).await.unwrap();
+    }
+}
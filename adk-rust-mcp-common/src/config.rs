@@ -1,8 +1,409 @@
 //! Configuration module for loading environment variables and settings.
+//!
+//! Settings come from environment variables (and a `.env` file) and,
+//! optionally, a TOML file named by `--config` or `GENMEDIA_CONFIG`, so one
+//! file can configure all servers. Environment variables override the file:
+//!
+//! ```toml
+//! project_id = "my-project"
+//! location = "us-central1"
+//! gcs_bucket = "my-bucket"
+//! port = 8080
+//! api_max_attempts = 3
+//! download_dir = "/srv/media"
+//!
+//! [image]
+//! safety_filter_level = "block_medium_and_above"
+//! person_generation = "allow_adult"
+//!
+//! [video]
+//! lro_timeout_secs = 1800
+//!
+//! [avtool]
+//! max_output_pixels = 8294400
+//! max_output_duration = 3600
+//! reverse_max_duration = 30
+//!
+//! [ffmpeg]
+//! path = "/usr/local/bin/ffmpeg"
+//! hwaccel = "cuda"
+//!
+//! [http]
+//! proxy = "http://proxy.internal:3128"
+//! connect_timeout_secs = 30
+//! read_timeout_secs = 300
+//! ca_bundle = "/etc/ssl/corp-root.pem"
+//!
+//! [idempotency]
+//! ttl_secs = 600
+//! capacity = 1000
+//! max_bytes = 67108864
+//!
+//! [cache]
+//! result_uri = "gs://my-bucket/cache"
+//!
+//! [logging]
+//! redact = 80
+//! ```
+//!
+//! Each key stands in for an environment variable (`[video] lro_timeout_secs`
+//! for `VIDEO_LRO_TIMEOUT_SECS`, `[http] proxy` for `HTTPS_PROXY`, ...) and
+//! follows the same lookup order. Unknown keys are logged as warnings and
+//! otherwise ignored.
+//!
+//! # Lookup order
+//!
+//! Each server binary passes its own prefix (`IMAGE_`, `VIDEO_`, `MUSIC_`,
+//! `SPEECH_`, `AVTOOL_`, `MULTIMODAL_`) so servers sharing a host can be
+//! configured apart. For every setting the first of these that is present wins:
+//!
+//! 1. The prefixed variable, e.g. `VIDEO_LOCATION` (ignored when empty)
+//! 2. The unprefixed variable, e.g. `LOCATION`
+//! 3. The configuration file
+//! 4. The built-in default
+//!
+//! `--port` on the command line overrides all of these for the port.
 
-use crate::error::ConfigError;
+use crate::cache::RESULT_CACHE_URI_ENV;
+use crate::error::{ConfigError, Error};
+use crate::ffmpeg::{FFMPEG_PATH_ENV, ffprobe_for};
+use crate::http::{
+    HTTP_CA_BUNDLE_ENV, HTTP_CONNECT_TIMEOUT_ENV, HTTP_READ_TIMEOUT_ENV, HTTPS_PROXY_ENV, HttpClientConfig,
+};
+use crate::idempotency::{IDEMPOTENCY_CAPACITY_ENV, IDEMPOTENCY_MAX_BYTES_ENV, IDEMPOTENCY_TTL_ENV};
+use crate::retry::{API_MAX_ATTEMPTS_ENV, DEFAULT_MAX_ATTEMPTS};
+use crate::tracing::{DEFAULT_REDACT_MAX_LEN, LOG_REDACT_ENV, RedactingFields};
+use crate::transport::DEFAULT_PORT;
+use serde::Deserialize;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use tracing::warn;
 
-/// Application configuration loaded from environment variables.
+/// Environment variable naming a TOML configuration file.
+pub const CONFIG_FILE_ENV: &str = "GENMEDIA_CONFIG";
+
+/// Environment variable naming the directory for local output files.
+pub const DOWNLOAD_DIR_ENV: &str = "MCP_DOWNLOAD_DIR";
+
+/// Subdirectory of the system temp directory used when `MCP_DOWNLOAD_DIR` is unset.
+pub const DEFAULT_DOWNLOAD_SUBDIR: &str = "adk-rust-mcp";
+
+/// Environment variable for the overall video generation timeout in seconds.
+pub const VIDEO_LRO_TIMEOUT_ENV: &str = "VIDEO_LRO_TIMEOUT_SECS";
+
+/// Environment variable selecting ffmpeg hardware acceleration.
+pub const FFMPEG_HWACCEL_ENV: &str = "FFMPEG_HWACCEL";
+
+/// Environment variable capping output frame size in pixels (width x height).
+pub const MAX_OUTPUT_PIXELS_ENV: &str = "MCP_MAX_OUTPUT_PIXELS";
+
+/// Environment variable capping output duration in seconds.
+pub const MAX_OUTPUT_DURATION_ENV: &str = "MCP_MAX_OUTPUT_DURATION";
+
+/// Environment variable capping the input length of `ffmpeg_reverse` in seconds.
+pub const REVERSE_MAX_DURATION_ENV: &str = "MCP_REVERSE_MAX_DURATION";
+
+/// Valid Imagen safety filter levels (`safetySetting`), for `IMAGE_SAFETY_FILTER_LEVEL`.
+pub const IMAGE_SAFETY_FILTER_LEVELS: &[&str] = &[
+    "block_low_and_above",
+    "block_medium_and_above",
+    "block_only_high",
+    "block_none",
+];
+
+/// Valid Imagen person generation policies (`personGeneration`), for `IMAGE_PERSON_GENERATION`.
+pub const IMAGE_PERSON_GENERATION_POLICIES: &[&str] = &["dont_allow", "allow_adult", "allow_all"];
+
+/// Location used when neither `LOCATION` nor the config file sets one.
+pub const DEFAULT_LOCATION: &str = "us-central1";
+
+/// Vertex AI regions accepted as a per-request `location` override.
+pub const VERTEX_AI_LOCATIONS: &[&str] = &[
+    "us-central1",
+    "us-east1",
+    "us-east4",
+    "us-east5",
+    "us-south1",
+    "us-west1",
+    "us-west4",
+    "northamerica-northeast1",
+    "southamerica-east1",
+    "europe-central2",
+    "europe-north1",
+    "europe-southwest1",
+    "europe-west1",
+    "europe-west2",
+    "europe-west3",
+    "europe-west4",
+    "europe-west6",
+    "europe-west8",
+    "europe-west9",
+    "asia-east1",
+    "asia-east2",
+    "asia-northeast1",
+    "asia-northeast3",
+    "asia-south1",
+    "asia-southeast1",
+    "australia-southeast1",
+    "me-central1",
+    "me-central2",
+    "me-west1",
+];
+
+/// Check a per-request `location` override against [`VERTEX_AI_LOCATIONS`].
+///
+/// # Errors
+/// Returns a message listing the valid regions if `location` is not one of them.
+pub fn validate_location(location: &str) -> Result<(), String> {
+    if VERTEX_AI_LOCATIONS.contains(&location) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unknown location '{}'. Valid locations: {}",
+            location,
+            VERTEX_AI_LOCATIONS.join(", ")
+        ))
+    }
+}
+
+/// Values read from a TOML configuration file.
+///
+/// Every key is optional. [`Config::load`] fills in whatever the environment
+/// does not set from these values.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConfigFile {
+    /// Google Cloud project ID
+    pub project_id: Option<String>,
+    /// Google Cloud location/region
+    pub location: Option<String>,
+    /// GCS bucket for media output
+    pub gcs_bucket: Option<String>,
+    /// HTTP server port
+    pub port: Option<u16>,
+    /// Attempts per upstream API request
+    pub api_max_attempts: Option<u32>,
+    /// Directory for local output files
+    pub download_dir: Option<String>,
+    /// Image server settings (`[image]`)
+    #[serde(default)]
+    pub image: ImageConfigSection,
+    /// Video server settings (`[video]`)
+    #[serde(default)]
+    pub video: VideoConfigSection,
+    /// AVTool output limits (`[avtool]`)
+    #[serde(default)]
+    pub avtool: AvtoolConfigSection,
+    /// ffmpeg binary and acceleration (`[ffmpeg]`)
+    #[serde(default)]
+    pub ffmpeg: FfmpegConfigSection,
+    /// Outbound HTTP client settings (`[http]`)
+    #[serde(default)]
+    pub http: HttpConfigSection,
+    /// Idempotency key store limits (`[idempotency]`)
+    #[serde(default)]
+    pub idempotency: IdempotencyConfigSection,
+    /// Result cache location (`[cache]`)
+    #[serde(default)]
+    pub cache: CacheConfigSection,
+    /// Log output settings (`[logging]`)
+    #[serde(default)]
+    pub logging: LoggingConfigSection,
+    /// Path the file was read from, for error messages
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    #[serde(flatten)]
+    unknown: toml::Table,
+}
+
+/// The `[image]` section of a configuration file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ImageConfigSection {
+    /// Default Imagen safety filter level
+    pub safety_filter_level: Option<String>,
+    /// Default Imagen person generation policy
+    pub person_generation: Option<String>,
+    #[serde(flatten)]
+    unknown: toml::Table,
+}
+
+/// The `[video]` section of a configuration file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct VideoConfigSection {
+    /// Overall time allowed for a video generation operation, in seconds
+    pub lro_timeout_secs: Option<u64>,
+    #[serde(flatten)]
+    unknown: toml::Table,
+}
+
+/// The `[avtool]` section of a configuration file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AvtoolConfigSection {
+    /// Maximum output frame size in pixels (width x height)
+    pub max_output_pixels: Option<u64>,
+    /// Maximum output duration in seconds
+    pub max_output_duration: Option<f64>,
+    /// Longest input `ffmpeg_reverse` accepts, in seconds
+    pub reverse_max_duration: Option<f64>,
+    #[serde(flatten)]
+    unknown: toml::Table,
+}
+
+/// The `[ffmpeg]` section of a configuration file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FfmpegConfigSection {
+    /// ffmpeg binary; `ffprobe` is looked up next to it
+    pub path: Option<String>,
+    /// Hardware acceleration: `none`, `cuda`, `vaapi` or `videotoolbox`
+    pub hwaccel: Option<String>,
+    #[serde(flatten)]
+    unknown: toml::Table,
+}
+
+/// The `[http]` section of a configuration file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HttpConfigSection {
+    /// Proxy URL for all outbound requests
+    pub proxy: Option<String>,
+    /// Connection timeout in seconds
+    pub connect_timeout_secs: Option<u64>,
+    /// Timeout between reads of a response body, in seconds
+    pub read_timeout_secs: Option<u64>,
+    /// PEM file with extra root certificates
+    pub ca_bundle: Option<String>,
+    #[serde(flatten)]
+    unknown: toml::Table,
+}
+
+/// The `[idempotency]` section of a configuration file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct IdempotencyConfigSection {
+    /// How long a recorded result is kept, in seconds
+    pub ttl_secs: Option<u64>,
+    /// Most keys held at once
+    pub capacity: Option<u64>,
+    /// Most bytes of recorded results held at once
+    pub max_bytes: Option<u64>,
+    #[serde(flatten)]
+    unknown: toml::Table,
+}
+
+/// The `[cache]` section of a configuration file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CacheConfigSection {
+    /// GCS location for cached generation results
+    pub result_uri: Option<String>,
+    #[serde(flatten)]
+    unknown: toml::Table,
+}
+
+/// The `[logging]` section of a configuration file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LoggingConfigSection {
+    /// Log redaction: `true`/`false` or a maximum value length
+    pub redact: Option<RedactSetting>,
+    #[serde(flatten)]
+    unknown: toml::Table,
+}
+
+/// A `[logging] redact` value, written like `MCP_LOG_REDACT`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum RedactSetting {
+    /// `true` redacts with the default limit, `false` disables redaction
+    Enabled(bool),
+    /// Redact, truncating values longer than this many characters
+    MaxLen(u64),
+    /// Any `MCP_LOG_REDACT` value, e.g. `"off"`
+    Setting(String),
+}
+
+impl RedactSetting {
+    /// The equivalent `MCP_LOG_REDACT` value.
+    pub fn as_env_value(&self) -> String {
+        match self {
+            Self::Enabled(true) => "on".to_string(),
+            Self::Enabled(false) => "off".to_string(),
+            Self::MaxLen(n) => n.to_string(),
+            Self::Setting(s) => s.clone(),
+        }
+    }
+}
+
+impl ConfigFile {
+    /// Parse configuration from TOML text.
+    ///
+    /// # Errors
+    /// Returns `ConfigError::InvalidFile` if the text is not valid TOML or a
+    /// known key has the wrong type.
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        toml::from_str(text).map_err(|e| ConfigError::invalid_file("<config>", e.message()))
+    }
+
+    /// Read and parse a configuration file, warning about unknown keys.
+    ///
+    /// # Errors
+    /// Returns `ConfigError::InvalidFile` if the file cannot be read or parsed.
+    pub fn read(path: &Path) -> Result<Self, ConfigError> {
+        let invalid = |reason: String| ConfigError::invalid_file(path.display().to_string(), reason);
+        let text = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+        let mut file: Self = toml::from_str(&text).map_err(|e| invalid(e.message().to_string()))?;
+        file.path = Some(path.to_path_buf());
+
+        for key in file.unknown_keys() {
+            warn!(path = %path.display(), key = %key, "Ignoring unknown configuration key");
+        }
+        Ok(file)
+    }
+
+    /// Keys the file sets that no server reads, e.g. `prject_id` or `image.sed`.
+    pub fn unknown_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.unknown.keys().cloned().collect();
+        let sections = [
+            ("image", &self.image.unknown),
+            ("video", &self.video.unknown),
+            ("avtool", &self.avtool.unknown),
+            ("ffmpeg", &self.ffmpeg.unknown),
+            ("http", &self.http.unknown),
+            ("idempotency", &self.idempotency.unknown),
+            ("cache", &self.cache.unknown),
+            ("logging", &self.logging.unknown),
+        ];
+        for (section, unknown) in sections {
+            keys.extend(unknown.keys().map(|k| format!("{}.{}", section, k)));
+        }
+        keys.sort();
+        keys
+    }
+
+    /// The value this file gives the setting read from environment variable
+    /// `name`, as text, for settings in the sections above.
+    fn lookup(&self, name: &str) -> Option<String> {
+        let text = |value: &Option<String>| value.clone();
+        let number = |value: Option<u64>| value.map(|n| n.to_string());
+        let seconds = |value: Option<f64>| value.map(|n| n.to_string());
+        match name {
+            VIDEO_LRO_TIMEOUT_ENV => number(self.video.lro_timeout_secs),
+            MAX_OUTPUT_PIXELS_ENV => number(self.avtool.max_output_pixels),
+            MAX_OUTPUT_DURATION_ENV => seconds(self.avtool.max_output_duration),
+            REVERSE_MAX_DURATION_ENV => seconds(self.avtool.reverse_max_duration),
+            FFMPEG_PATH_ENV => text(&self.ffmpeg.path),
+            FFMPEG_HWACCEL_ENV => text(&self.ffmpeg.hwaccel),
+            HTTPS_PROXY_ENV => text(&self.http.proxy),
+            HTTP_CONNECT_TIMEOUT_ENV => number(self.http.connect_timeout_secs),
+            HTTP_READ_TIMEOUT_ENV => number(self.http.read_timeout_secs),
+            HTTP_CA_BUNDLE_ENV => text(&self.http.ca_bundle),
+            IDEMPOTENCY_TTL_ENV => number(self.idempotency.ttl_secs),
+            IDEMPOTENCY_CAPACITY_ENV => number(self.idempotency.capacity),
+            IDEMPOTENCY_MAX_BYTES_ENV => number(self.idempotency.max_bytes),
+            RESULT_CACHE_URI_ENV => text(&self.cache.result_uri),
+            LOG_REDACT_ENV => self.logging.redact.as_ref().map(RedactSetting::as_env_value),
+            _ => None,
+        }
+    }
+}
+
+/// Application configuration loaded from environment variables and an
+/// optional configuration file.
 #[derive(Debug, Clone)]
 pub struct Config {
     /// Google Cloud project ID (required)
@@ -13,6 +414,68 @@ pub struct Config {
     pub gcs_bucket: Option<String>,
     /// HTTP server port
     pub port: u16,
+    /// Default Imagen safety filter level (e.g. `block_medium_and_above`)
+    pub image_safety_filter_level: Option<String>,
+    /// Default Imagen person generation policy (e.g. `dont_allow`)
+    pub image_person_generation: Option<String>,
+    /// Attempts per upstream API request, including the first (retries on 429/5xx)
+    pub api_max_attempts: u32,
+    /// Directory for local output files (`MCP_DOWNLOAD_DIR`)
+    pub download_dir: Option<String>,
+    /// Outbound HTTP client settings (`HTTPS_PROXY`, `HTTP_*`)
+    pub http: HttpClientConfig,
+    /// ffmpeg binary (`FFMPEG_PATH`)
+    pub ffmpeg_path: Option<String>,
+    /// ffmpeg hardware acceleration, unparsed (`FFMPEG_HWACCEL`)
+    pub ffmpeg_hwaccel: Option<String>,
+    /// Cap on AVTool output frame size in pixels (`MCP_MAX_OUTPUT_PIXELS`)
+    pub max_output_pixels: Option<u64>,
+    /// Cap on AVTool output duration in seconds (`MCP_MAX_OUTPUT_DURATION`)
+    pub max_output_duration: Option<f64>,
+    /// Longest input `ffmpeg_reverse` accepts, in seconds (`MCP_REVERSE_MAX_DURATION`)
+    pub reverse_max_duration: Option<f64>,
+    /// Overall video generation timeout (`VIDEO_LRO_TIMEOUT_SECS`)
+    pub video_lro_timeout: Option<Duration>,
+    /// How long idempotency keys are kept (`IDEMPOTENCY_TTL_SECS`)
+    pub idempotency_ttl: Option<Duration>,
+    /// Most idempotency keys kept (`IDEMPOTENCY_CAPACITY`)
+    pub idempotency_capacity: Option<usize>,
+    /// Most bytes of recorded idempotent results (`IDEMPOTENCY_MAX_BYTES`)
+    pub idempotency_max_bytes: Option<usize>,
+    /// GCS location of a shared result cache (`RESULT_CACHE_URI`)
+    pub result_cache_uri: Option<String>,
+    /// Log redaction (`MCP_LOG_REDACT`)
+    pub log_redaction: RedactingFields,
+}
+
+/// The values used when nothing is configured, with an empty `project_id`.
+///
+/// Mainly useful for building a `Config` in tests with struct update syntax.
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            project_id: String::new(),
+            location: DEFAULT_LOCATION.to_string(),
+            gcs_bucket: None,
+            port: DEFAULT_PORT,
+            image_safety_filter_level: None,
+            image_person_generation: None,
+            api_max_attempts: DEFAULT_MAX_ATTEMPTS,
+            download_dir: None,
+            http: HttpClientConfig::default(),
+            ffmpeg_path: None,
+            ffmpeg_hwaccel: None,
+            max_output_pixels: None,
+            max_output_duration: None,
+            reverse_max_duration: None,
+            video_lro_timeout: None,
+            idempotency_ttl: None,
+            idempotency_capacity: None,
+            idempotency_max_bytes: None,
+            result_cache_uri: None,
+            log_redaction: RedactingFields::new(DEFAULT_REDACT_MAX_LEN),
+        }
+    }
 }
 
 impl Config {
@@ -24,26 +487,325 @@ impl Config {
         // Load .env file if present (ignore errors if not found)
         let _ = dotenvy::dotenv();
 
-        let project_id = std::env::var("PROJECT_ID")
-            .map_err(|_| ConfigError::MissingEnvVar("PROJECT_ID".to_string()))?;
+        Self::from_sources(None, |name| std::env::var(name).ok())
+    }
+
+    /// Load configuration from environment variables, letting `prefix`ed
+    /// variables override unprefixed ones.
+    ///
+    /// With prefix `VIDEO_`, `VIDEO_LOCATION` is used if set and `LOCATION`
+    /// otherwise; see the [module docs](self) for the full lookup order.
+    ///
+    /// # Errors
+    /// Returns `ConfigError::MissingEnvVar` if neither `{prefix}PROJECT_ID`
+    /// nor PROJECT_ID is set.
+    pub fn from_env_with_prefix(prefix: &str) -> Result<Self, ConfigError> {
+        let _ = dotenvy::dotenv();
+
+        Self::from_sources(None, prefixed(prefix, |name| std::env::var(name).ok()))
+    }
+
+    /// Load configuration from a TOML file alone, without environment overrides.
+    ///
+    /// # Errors
+    /// Returns `ConfigError::InvalidFile` if the file cannot be read or parsed,
+    /// and `ConfigError::MissingEnvVar` if it does not set `project_id`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let file = ConfigFile::read(path.as_ref())?;
+        Self::from_sources(Some(&file), |_| None)
+    }
 
-        let location = std::env::var("LOCATION").unwrap_or_else(|_| "us-central1".to_string());
+    /// Load configuration from an optional TOML file and the environment.
+    ///
+    /// The file is `cli_path` if given, otherwise the one named by
+    /// `GENMEDIA_CONFIG`; without either this behaves like [`Config::from_env`].
+    /// Environment variables take precedence over values from the file.
+    ///
+    /// # Errors
+    /// Returns `ConfigError::InvalidFile` if the file cannot be read or parsed,
+    /// and `ConfigError::MissingEnvVar` if neither the file nor the
+    /// environment sets the project ID.
+    pub fn load(cli_path: Option<&Path>) -> Result<Self, ConfigError> {
+        Self::load_with_prefix(cli_path, "")
+    }
 
-        let gcs_bucket = std::env::var("GCS_BUCKET").ok();
+    /// Like [`Config::load`], with `prefix`ed environment variables
+    /// overriding unprefixed ones as in [`Config::from_env_with_prefix`].
+    ///
+    /// # Errors
+    /// See [`Config::load`].
+    pub fn load_with_prefix(cli_path: Option<&Path>, prefix: &str) -> Result<Self, ConfigError> {
+        let _ = dotenvy::dotenv();
 
-        let port = std::env::var("PORT")
+        let env_path = std::env::var(CONFIG_FILE_ENV)
             .ok()
+            .filter(|p| !p.trim().is_empty())
+            .map(PathBuf::from);
+        let file = match cli_path.map(Path::to_path_buf).or(env_path) {
+            Some(path) => Some(ConfigFile::read(&path)?),
+            None => None,
+        };
+
+        Self::from_sources(file.as_ref(), prefixed(prefix, |name| std::env::var(name).ok()))
+    }
+
+    /// Build a configuration from file values and an environment lookup,
+    /// with the environment taking precedence.
+    pub(crate) fn from_sources(
+        file: Option<&ConfigFile>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ConfigError> {
+        let defaults = ConfigFile::default();
+        let file_values = file.unwrap_or(&defaults);
+
+        let project_id = env("PROJECT_ID")
+            .or_else(|| file_values.project_id.clone())
+            .ok_or_else(|| match file.and_then(|f| f.path.as_deref()) {
+                Some(path) => ConfigError::missing_env_var(format!(
+                    "PROJECT_ID (or project_id in {})",
+                    path.display()
+                )),
+                None => ConfigError::missing_env_var("PROJECT_ID"),
+            })?;
+
+        let location = env("LOCATION")
+            .or_else(|| file_values.location.clone())
+            .unwrap_or_else(|| DEFAULT_LOCATION.to_string());
+
+        let gcs_bucket = env("GCS_BUCKET").or_else(|| file_values.gcs_bucket.clone());
+
+        let port = env("PORT")
             .and_then(|p| p.parse().ok())
-            .unwrap_or(8080);
+            .or(file_values.port)
+            .unwrap_or(DEFAULT_PORT);
+
+        // Checked here so a typo stops the server at startup instead of
+        // failing every image request that relies on the default
+        let one_of = |name: &str, value: Option<String>, valid: &[&str]| match value {
+            Some(v) if !valid.contains(&v.as_str()) => Err(ConfigError::invalid_value(
+                name,
+                format!("'{}' is not one of {}", v, valid.join(", ")),
+            )),
+            value => Ok(value),
+        };
+        let image_safety_filter_level = one_of(
+            "IMAGE_SAFETY_FILTER_LEVEL",
+            env("IMAGE_SAFETY_FILTER_LEVEL").or_else(|| file_values.image.safety_filter_level.clone()),
+            IMAGE_SAFETY_FILTER_LEVELS,
+        )?;
+        let image_person_generation = one_of(
+            "IMAGE_PERSON_GENERATION",
+            env("IMAGE_PERSON_GENERATION").or_else(|| file_values.image.person_generation.clone()),
+            IMAGE_PERSON_GENERATION_POLICIES,
+        )?;
+
+        let api_max_attempts = env(API_MAX_ATTEMPTS_ENV)
+            .and_then(|n| n.parse().ok())
+            .filter(|&n| n > 0)
+            .or(file_values.api_max_attempts.filter(|&n| n > 0))
+            .unwrap_or(DEFAULT_MAX_ATTEMPTS);
+
+        let download_dir = env(DOWNLOAD_DIR_ENV)
+            .filter(|dir| !dir.trim().is_empty())
+            .or_else(|| file_values.download_dir.clone().filter(|dir| !dir.trim().is_empty()));
+
+        // Service settings: each key in a file section stands in for its
+        // environment variable and is parsed the same way. As above, a blank
+        // or invalid env value does not hide the file's.
+        let text = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let setting = |name: &str| text(env(name)).or_else(|| text(file_values.lookup(name)));
+        let positive = |name: &str| {
+            let parse = |value| text(value).and_then(|v| v.parse::<u64>().ok()).filter(|&n| n > 0);
+            parse(env(name)).or_else(|| parse(file_values.lookup(name)))
+        };
+        let seconds = |name: &str| {
+            let parse = |value| {
+                text(value)
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .filter(|&d| d.is_finite() && d > 0.0)
+            };
+            parse(env(name)).or_else(|| parse(file_values.lookup(name)))
+        };
+
+        let http = HttpClientConfig::from_lookup(|name| setting(name))?;
 
         Ok(Self {
             project_id,
             location,
             gcs_bucket,
             port,
+            image_safety_filter_level,
+            image_person_generation,
+            api_max_attempts,
+            download_dir,
+            http,
+            ffmpeg_path: setting(FFMPEG_PATH_ENV),
+            ffmpeg_hwaccel: setting(FFMPEG_HWACCEL_ENV),
+            max_output_pixels: positive(MAX_OUTPUT_PIXELS_ENV),
+            max_output_duration: seconds(MAX_OUTPUT_DURATION_ENV),
+            reverse_max_duration: seconds(REVERSE_MAX_DURATION_ENV),
+            video_lro_timeout: positive(VIDEO_LRO_TIMEOUT_ENV).map(Duration::from_secs),
+            idempotency_ttl: positive(IDEMPOTENCY_TTL_ENV).map(Duration::from_secs),
+            idempotency_capacity: positive(IDEMPOTENCY_CAPACITY_ENV).map(|n| n as usize),
+            idempotency_max_bytes: positive(IDEMPOTENCY_MAX_BYTES_ENV).map(|n| n as usize),
+            result_cache_uri: setting(RESULT_CACHE_URI_ENV),
+            log_redaction: RedactingFields::from_setting(setting(LOG_REDACT_ENV).as_deref()),
         })
     }
 
+    /// Directory for local files whose name the server picks, such as videos
+    /// downloaded without a `local_path`.
+    ///
+    /// This is `MCP_DOWNLOAD_DIR` when set, otherwise `adk-rust-mcp` under the
+    /// system temp directory, so downloads never land in the process's working
+    /// directory by accident. The result is absolute.
+    pub fn download_dir(&self) -> PathBuf {
+        let dir = match self.download_dir.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => std::env::temp_dir().join(DEFAULT_DOWNLOAD_SUBDIR),
+        };
+        absolute(&dir)
+    }
+
+    /// Resolve a caller-supplied local output path to an absolute path.
+    ///
+    /// Absolute paths are returned unchanged. Relative paths are placed in
+    /// `MCP_DOWNLOAD_DIR` when it is set and otherwise resolved against the
+    /// working directory, as before the setting existed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use adk_rust_mcp_common::config::Config;
+    /// use std::path::Path;
+    ///
+    /// let config = Config {
+    ///     project_id: "p".to_string(),
+    ///     download_dir: Some("/srv/media".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(config.resolve_local_path("renders/out.png"), Path::new("/srv/media/renders/out.png"));
+    /// assert_eq!(config.resolve_local_path("/tmp/out.png"), Path::new("/tmp/out.png"));
+    /// ```
+    pub fn resolve_local_path(&self, path: &str) -> PathBuf {
+        let path = Path::new(path);
+        if path.is_absolute() {
+            return path.to_path_buf();
+        }
+        match self.download_dir.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+            Some(_) => self.download_dir().join(path),
+            None => absolute(path),
+        }
+    }
+
+    /// Resolve a caller-supplied local path inside [`Config::download_dir`],
+    /// rejecting anything that would reach outside it.
+    ///
+    /// Relative paths are joined to the download directory; absolute paths
+    /// must already point into it. `..` components are refused, and symlinks
+    /// in the existing part of the path are resolved before the check, so a
+    /// link cannot lead out of the directory. The directory is created if
+    /// missing. The returned path is canonical up to its last existing
+    /// ancestor; the remaining components may not exist yet.
+    ///
+    /// # Errors
+    /// Returns `Error::Validation` if the path escapes the download directory,
+    /// and `Error::Io` if the directory cannot be created.
+    pub fn confine_local_path(&self, path: &str) -> Result<PathBuf, Error> {
+        let root = self.download_dir();
+        std::fs::create_dir_all(&root)?;
+        let root = root.canonicalize()?;
+        let outside = || {
+            Error::validation(format!(
+                "Path '{}' is outside the download directory '{}' ({})",
+                path,
+                root.display(),
+                DOWNLOAD_DIR_ENV
+            ))
+        };
+
+        let requested = Path::new(path);
+        if requested.components().any(|c| c == Component::ParentDir) {
+            return Err(Error::validation(format!("Path '{}' must not contain '..'", path)));
+        }
+        let candidate = if requested.is_absolute() {
+            requested.to_path_buf()
+        } else {
+            root.join(requested)
+        };
+
+        // Canonicalize the longest existing prefix; the rest is created later
+        let mut existing = candidate.as_path();
+        let mut missing = Vec::new();
+        let resolved = loop {
+            match existing.canonicalize() {
+                Ok(resolved) => break resolved,
+                // A dangling symlink would be followed on write
+                Err(_) if existing.symlink_metadata().is_ok() => return Err(outside()),
+                Err(_) => {
+                    let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+                        return Err(outside());
+                    };
+                    missing.push(name);
+                    existing = parent;
+                }
+            }
+        };
+
+        if !resolved.starts_with(&root) {
+            return Err(outside());
+        }
+        Ok(missing.into_iter().rev().fold(resolved, |path, name| path.join(name)))
+    }
+
+    /// Resolve an output location to a full GCS URI.
+    ///
+    /// Only a bare relative object name such as `renders/out.mp4` is placed
+    /// in the configured `gcs_bucket`. Anything else, including `gs://` URIs,
+    /// other schemes (`https:`, `file:`) and paths starting with `/`, is
+    /// returned unchanged, so the tool's own validation accepts a GCS URI
+    /// and rejects the rest with its usual error.
+    ///
+    /// # Errors
+    /// Returns `Error::Validation` if `output` is a bare object name and no
+    /// `GCS_BUCKET` is configured.
+    pub fn resolve_output_uri(&self, output: &str) -> Result<String, Error> {
+        if !is_bare_object_name(output) {
+            return Ok(output.to_string());
+        }
+
+        let bucket = self
+            .gcs_bucket
+            .as_deref()
+            .map(|b| b.trim().trim_start_matches("gs://").trim_end_matches('/'))
+            .filter(|b| !b.is_empty())
+            .ok_or_else(|| {
+                Error::validation(format!(
+                    "Output '{}' is not a gs:// URI and GCS_BUCKET is not configured; \
+                     pass a full gs://bucket/path URI or set GCS_BUCKET",
+                    output
+                ))
+            })?;
+
+        Ok(format!("gs://{}/{}", bucket, output))
+    }
+
+    /// The ffmpeg binary to execute: `FFMPEG_PATH` if set, otherwise `ffmpeg`.
+    pub fn ffmpeg_binary(&self) -> String {
+        self.ffmpeg_path.clone().unwrap_or_else(|| "ffmpeg".to_string())
+    }
+
+    /// The ffprobe binary next to [`Config::ffmpeg_binary`]; see [`ffprobe_for`].
+    pub fn ffprobe_binary(&self) -> String {
+        ffprobe_for(&self.ffmpeg_binary())
+    }
+
+    /// Region for a single request: the override if one is given, otherwise
+    /// the configured `location`.
+    pub fn location_or<'a>(&'a self, location: Option<&'a str>) -> &'a str {
+        location.unwrap_or(&self.location)
+    }
+
     /// Get the Vertex AI endpoint URL for a given API.
     pub fn vertex_ai_endpoint(&self, api: &str) -> String {
         format!(
@@ -52,3 +814,38 @@ impl Config {
         )
     }
 }
+
+/// Wrap an environment lookup so `{prefix}{name}` is tried before `name`.
+///
+/// Empty prefixed values are skipped, so `VIDEO_GCS_BUCKET=` does not hide
+/// `GCS_BUCKET`.
+pub(crate) fn prefixed(
+    prefix: &str,
+    env: impl Fn(&str) -> Option<String>,
+) -> impl Fn(&str) -> Option<String> {
+    let prefix = prefix.to_string();
+    move |name| {
+        if !prefix.is_empty() {
+            if let Some(value) = env(&format!("{}{}", prefix, name)).filter(|v| !v.is_empty()) {
+                return Some(value);
+            }
+        }
+        env(name)
+    }
+}
+
+/// Make `path` absolute against the working directory, leaving it as is if
+/// the working directory cannot be read.
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Whether `output` is a plain relative object name: not empty, not starting
+/// with `/`, and without a URI scheme such as `gs:` or `https:`.
+fn is_bare_object_name(output: &str) -> bool {
+    let has_scheme = output.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    });
+    !output.trim().is_empty() && !output.starts_with('/') && !has_scheme
+}
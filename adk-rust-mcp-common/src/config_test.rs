@@ -49,6 +49,7 @@ mod config_logic_tests {
             location: "us-central1".to_string(),
             gcs_bucket: Some("my-bucket".to_string()),
             port: 8080,
+            ..Default::default()
         };
 
         assert_eq!(config.project_id, "test-project");
@@ -65,6 +66,7 @@ mod config_logic_tests {
             location: "us-west1".to_string(),
             gcs_bucket: None,
             port: 8080,
+            ..Default::default()
         };
 
         let endpoint = config.vertex_ai_endpoint("imagen-3.0-generate-002");
@@ -86,6 +88,7 @@ mod config_logic_tests {
                 location: location.to_string(),
                 gcs_bucket: None,
                 port: 8080,
+                ..Default::default()
             };
 
             let endpoint = config.vertex_ai_endpoint("test-model");
@@ -106,6 +109,7 @@ mod config_logic_tests {
             location: "us-central1".to_string(),
             gcs_bucket: Some("bucket".to_string()),
             port: 9000,
+            ..Default::default()
         };
 
         let cloned = config.clone();
@@ -123,12 +127,564 @@ mod config_logic_tests {
             location: "us-central1".to_string(),
             gcs_bucket: None,
             port: 8080,
+            ..Default::default()
         };
 
         let debug_str = format!("{:?}", config);
         assert!(debug_str.contains("test-project"));
         assert!(debug_str.contains("us-central1"));
     }
+
+    fn config_with_bucket(gcs_bucket: Option<&str>) -> Config {
+        Config {
+            project_id: "test-project".to_string(),
+            location: "us-central1".to_string(),
+            gcs_bucket: gcs_bucket.map(str::to_string),
+            port: 8080,
+            ..Default::default()
+        }
+    }
+
+    /// Full GCS URIs pass through regardless of the configured bucket
+    #[test]
+    fn resolve_output_uri_keeps_gcs_uris() {
+        for bucket in [None, Some("media")] {
+            let config = config_with_bucket(bucket);
+            assert_eq!(
+                config.resolve_output_uri("gs://other/out.mp4").unwrap(),
+                "gs://other/out.mp4"
+            );
+        }
+    }
+
+    /// Bare object names land in the configured bucket
+    #[test]
+    fn resolve_output_uri_uses_configured_bucket() {
+        let config = config_with_bucket(Some("media"));
+        assert_eq!(
+            config.resolve_output_uri("renders/out.mp4").unwrap(),
+            "gs://media/renders/out.mp4"
+        );
+        // A colon later in the name is not a scheme
+        assert_eq!(
+            config.resolve_output_uri("renders/take:2.mp4").unwrap(),
+            "gs://media/renders/take:2.mp4"
+        );
+
+        let prefixed = config_with_bucket(Some("gs://media/"));
+        assert_eq!(
+            prefixed.resolve_output_uri("renders/out.mp4").unwrap(),
+            "gs://media/renders/out.mp4"
+        );
+    }
+
+    /// Other schemes and absolute paths are left for the tool to reject
+    #[test]
+    fn resolve_output_uri_leaves_non_object_names() {
+        let config = config_with_bucket(Some("media"));
+        for output in ["/out.png", "https://example.com/out.png", "file:out.png", "s3://bucket/out.png", "C:out.png", ""] {
+            assert_eq!(config.resolve_output_uri(output).unwrap(), output);
+        }
+    }
+
+    /// A per-request location overrides the configured one
+    #[test]
+    fn location_or_prefers_override() {
+        let config = config_with_bucket(None);
+        assert_eq!(config.location_or(None), "us-central1");
+        assert_eq!(config.location_or(Some("europe-west4")), "europe-west4");
+    }
+
+    /// Only known Vertex AI regions are accepted as overrides
+    #[test]
+    fn validate_location_accepts_known_regions() {
+        use crate::config::{VERTEX_AI_LOCATIONS, validate_location};
+
+        for location in VERTEX_AI_LOCATIONS {
+            assert!(validate_location(location).is_ok(), "{}", location);
+        }
+        for location in ["", "global", "europe-west99", "US-CENTRAL1", "us-central1 "] {
+            let msg = validate_location(location).unwrap_err();
+            assert!(msg.contains("Valid locations"), "{}", msg);
+        }
+    }
+
+    /// Bare object names without a configured bucket are rejected
+    #[test]
+    fn resolve_output_uri_requires_bucket_for_bare_names() {
+        for bucket in [None, Some(""), Some("gs://")] {
+            let err = config_with_bucket(bucket)
+                .resolve_output_uri("renders/out.mp4")
+                .unwrap_err();
+            let msg = err.to_string();
+            assert!(msg.contains("GCS_BUCKET"), "{}", msg);
+            assert!(msg.contains("renders/out.mp4"), "{}", msg);
+        }
+    }
+
+    /// Server-named files go to a temp subdirectory unless MCP_DOWNLOAD_DIR is set
+    #[test]
+    fn download_dir_defaults_to_temp_subdir() {
+        use crate::config::DEFAULT_DOWNLOAD_SUBDIR;
+
+        for dir in [None, Some(""), Some("  ")] {
+            let config = Config {
+                download_dir: dir.map(String::from),
+                ..config_with_bucket(None)
+            };
+            assert_eq!(config.download_dir(), std::env::temp_dir().join(DEFAULT_DOWNLOAD_SUBDIR));
+        }
+
+        let config = Config {
+            download_dir: Some("/srv/media".to_string()),
+            ..config_with_bucket(None)
+        };
+        assert_eq!(config.download_dir(), std::path::Path::new("/srv/media"));
+
+        let relative = Config {
+            download_dir: Some("media".to_string()),
+            ..config_with_bucket(None)
+        };
+        assert!(relative.download_dir().is_absolute());
+        assert!(relative.download_dir().ends_with("media"));
+    }
+
+    /// Relative output paths use MCP_DOWNLOAD_DIR when set, the working directory otherwise
+    #[test]
+    fn resolve_local_path_is_absolute() {
+        let cwd = std::env::current_dir().unwrap();
+        let config = config_with_bucket(None);
+        assert_eq!(config.resolve_local_path("out/a.png"), cwd.join("out/a.png"));
+        assert_eq!(config.resolve_local_path("/tmp/a.png"), std::path::Path::new("/tmp/a.png"));
+
+        let config = Config {
+            download_dir: Some("/srv/media".to_string()),
+            ..config_with_bucket(None)
+        };
+        assert_eq!(config.resolve_local_path("out/a.png"), std::path::Path::new("/srv/media/out/a.png"));
+        assert_eq!(config.resolve_local_path("/tmp/a.png"), std::path::Path::new("/tmp/a.png"));
+    }
+
+    /// Storage paths must stay inside MCP_DOWNLOAD_DIR
+    #[test]
+    fn confine_local_path_stays_in_download_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let config = Config {
+            download_dir: Some(root.join("media").to_string_lossy().to_string()),
+            ..config_with_bucket(None)
+        };
+        let media = root.join("media");
+
+        assert_eq!(config.confine_local_path("a/b/clip.mp4").unwrap(), media.join("a/b/clip.mp4"));
+        let absolute = media.join("clip.mp4");
+        assert_eq!(config.confine_local_path(&absolute.to_string_lossy()).unwrap(), absolute);
+
+        for escape in ["../secret", "a/../../secret", "/etc/passwd"] {
+            assert!(config.confine_local_path(escape).is_err(), "{}", escape);
+        }
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&root, media.join("up")).unwrap();
+            std::os::unix::fs::symlink(root.join("nowhere"), media.join("dangling")).unwrap();
+            let err = config.confine_local_path("up/secret").unwrap_err();
+            assert!(err.to_string().contains("outside the download directory"), "{}", err);
+            assert!(config.confine_local_path("dangling").is_err());
+        }
+    }
+}
+
+/// Tests for loading a TOML configuration file and merging it with the environment
+#[cfg(test)]
+mod file_tests {
+    use crate::config::{Config, ConfigFile};
+    use crate::error::ConfigError;
+    use crate::tracing::{DEFAULT_REDACT_MAX_LEN, RedactingFields};
+    use std::collections::HashMap;
+    use std::path::Path;
+    use std::time::Duration;
+
+    const FILE: &str = r#"
+        project_id = "file-project"
+        location = "europe-west4"
+        gcs_bucket = "file-bucket"
+        port = 9100
+        api_max_attempts = 5
+        download_dir = "/srv/media"
+
+        [image]
+        safety_filter_level = "block_only_high"
+        person_generation = "allow_adult"
+    "#;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> =
+            vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn default_matches_unconfigured_values() {
+        let loaded = Config::from_sources(None, env(&[("PROJECT_ID", "p")])).unwrap();
+        let default = Config {
+            project_id: "p".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(format!("{:?}", loaded), format!("{:?}", default));
+    }
+
+    #[test]
+    fn file_values_apply_without_env() {
+        let file = ConfigFile::parse(FILE).unwrap();
+        let config = Config::from_sources(Some(&file), env(&[])).unwrap();
+
+        assert_eq!(config.project_id, "file-project");
+        assert_eq!(config.location, "europe-west4");
+        assert_eq!(config.gcs_bucket.as_deref(), Some("file-bucket"));
+        assert_eq!(config.port, 9100);
+        assert_eq!(config.api_max_attempts, 5);
+        assert_eq!(config.download_dir.as_deref(), Some("/srv/media"));
+        assert_eq!(config.image_safety_filter_level.as_deref(), Some("block_only_high"));
+        assert_eq!(config.image_person_generation.as_deref(), Some("allow_adult"));
+    }
+
+    #[test]
+    fn env_overrides_file_values() {
+        let file = ConfigFile::parse(FILE).unwrap();
+        let config = Config::from_sources(
+            Some(&file),
+            env(&[
+                ("PROJECT_ID", "env-project"),
+                ("LOCATION", "us-east1"),
+                ("GCS_BUCKET", "env-bucket"),
+                ("PORT", "3000"),
+                ("API_MAX_ATTEMPTS", "2"),
+                ("MCP_DOWNLOAD_DIR", "/data"),
+                ("IMAGE_SAFETY_FILTER_LEVEL", "block_low_and_above"),
+                ("IMAGE_PERSON_GENERATION", "dont_allow"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(config.project_id, "env-project");
+        assert_eq!(config.location, "us-east1");
+        assert_eq!(config.gcs_bucket.as_deref(), Some("env-bucket"));
+        assert_eq!(config.port, 3000);
+        assert_eq!(config.api_max_attempts, 2);
+        assert_eq!(config.download_dir.as_deref(), Some("/data"));
+        assert_eq!(config.image_safety_filter_level.as_deref(), Some("block_low_and_above"));
+        assert_eq!(config.image_person_generation.as_deref(), Some("dont_allow"));
+    }
+
+    /// Env values that would be ignored on their own do not hide the file's
+    #[test]
+    fn invalid_or_blank_env_values_fall_back_to_file() {
+        let file = ConfigFile::parse(FILE).unwrap();
+        let config = Config::from_sources(
+            Some(&file),
+            env(&[("PORT", "not-a-port"), ("API_MAX_ATTEMPTS", "0"), ("MCP_DOWNLOAD_DIR", " ")]),
+        )
+        .unwrap();
+
+        assert_eq!(config.port, 9100);
+        assert_eq!(config.api_max_attempts, 5);
+        assert_eq!(config.download_dir.as_deref(), Some("/srv/media"));
+    }
+
+    #[test]
+    fn partial_file_uses_defaults_and_env() {
+        let file = ConfigFile::parse("gcs_bucket = \"file-bucket\"").unwrap();
+        let config = Config::from_sources(Some(&file), env(&[("PROJECT_ID", "env-project")])).unwrap();
+
+        assert_eq!(config.project_id, "env-project");
+        assert_eq!(config.location, "us-central1");
+        assert_eq!(config.gcs_bucket.as_deref(), Some("file-bucket"));
+        assert_eq!(config.port, 8080);
+        assert_eq!(config.image_safety_filter_level, None);
+    }
+
+    #[test]
+    fn missing_project_id_is_an_error() {
+        let file = ConfigFile::parse("location = \"us-east1\"").unwrap();
+        let err = Config::from_sources(Some(&file), env(&[])).unwrap_err();
+        assert!(matches!(err, ConfigError::MissingEnvVar(ref name) if name == "PROJECT_ID"), "{}", err);
+    }
+
+    #[test]
+    fn unknown_keys_are_reported_not_rejected() {
+        let file = ConfigFile::parse(
+            r#"
+            project_id = "p"
+            prject_id = "typo"
+
+            [image]
+            person_generation = "allow_all"
+            seed = 7
+
+            [video]
+            model = "veo-3"
+
+            [music]
+            seed = 1
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(file.unknown_keys(), vec!["image.seed", "music", "prject_id", "video.model"]);
+        let config = Config::from_sources(Some(&file), env(&[])).unwrap();
+        assert_eq!(config.project_id, "p");
+        assert_eq!(config.image_person_generation.as_deref(), Some("allow_all"));
+    }
+
+    const SERVICE_FILE: &str = r#"
+        project_id = "p"
+
+        [video]
+        lro_timeout_secs = 900
+
+        [avtool]
+        max_output_pixels = 2073600
+        max_output_duration = 600
+        reverse_max_duration = 12.5
+
+        [ffmpeg]
+        path = "/opt/ffmpeg/bin/ffmpeg"
+        hwaccel = "cuda"
+
+        [http]
+        proxy = "http://proxy.internal:3128"
+        connect_timeout_secs = 5
+        read_timeout_secs = 60
+        ca_bundle = "/etc/ssl/corp.pem"
+
+        [idempotency]
+        ttl_secs = 120
+        capacity = 10
+        max_bytes = 4096
+
+        [cache]
+        result_uri = "gs://cache-bucket/results"
+
+        [logging]
+        redact = false
+    "#;
+
+    #[test]
+    fn service_sections_apply_without_env() {
+        let file = ConfigFile::parse(SERVICE_FILE).unwrap();
+        assert!(file.unknown_keys().is_empty(), "{:?}", file.unknown_keys());
+        let config = Config::from_sources(Some(&file), env(&[])).unwrap();
+
+        assert_eq!(config.video_lro_timeout, Some(Duration::from_secs(900)));
+        assert_eq!(config.max_output_pixels, Some(2_073_600));
+        assert_eq!(config.max_output_duration, Some(600.0));
+        assert_eq!(config.reverse_max_duration, Some(12.5));
+        assert_eq!(config.ffmpeg_binary(), "/opt/ffmpeg/bin/ffmpeg");
+        assert_eq!(config.ffmpeg_hwaccel.as_deref(), Some("cuda"));
+        assert_eq!(config.http.proxy.as_deref(), Some("http://proxy.internal:3128"));
+        assert_eq!(config.http.connect_timeout, Duration::from_secs(5));
+        assert_eq!(config.http.read_timeout, Duration::from_secs(60));
+        assert_eq!(config.http.ca_bundle.as_deref(), Some(Path::new("/etc/ssl/corp.pem")));
+        assert_eq!(config.idempotency_ttl, Some(Duration::from_secs(120)));
+        assert_eq!(config.idempotency_capacity, Some(10));
+        assert_eq!(config.idempotency_max_bytes, Some(4096));
+        assert_eq!(config.result_cache_uri.as_deref(), Some("gs://cache-bucket/results"));
+        assert_eq!(config.log_redaction, RedactingFields::disabled());
+    }
+
+    #[test]
+    fn env_overrides_service_sections() {
+        let file = ConfigFile::parse(SERVICE_FILE).unwrap();
+        let config = Config::from_sources(
+            Some(&file),
+            env(&[
+                ("VIDEO_LRO_TIMEOUT_SECS", "60"),
+                ("MCP_MAX_OUTPUT_DURATION", "30"),
+                ("FFMPEG_PATH", "/usr/bin/ffmpeg"),
+                ("HTTP_READ_TIMEOUT_SECS", "10"),
+                ("IDEMPOTENCY_CAPACITY", "3"),
+                ("RESULT_CACHE_URI", "gs://other/cache"),
+                ("MCP_LOG_REDACT", "64"),
+                // Ignored on their own, so the file's values stay
+                ("MCP_REVERSE_MAX_DURATION", "-1"),
+                ("IDEMPOTENCY_TTL_SECS", "0"),
+                ("FFMPEG_HWACCEL", " "),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(config.video_lro_timeout, Some(Duration::from_secs(60)));
+        assert_eq!(config.max_output_duration, Some(30.0));
+        assert_eq!(config.ffmpeg_binary(), "/usr/bin/ffmpeg");
+        assert_eq!(config.http.read_timeout, Duration::from_secs(10));
+        assert_eq!(config.http.connect_timeout, Duration::from_secs(5));
+        assert_eq!(config.idempotency_capacity, Some(3));
+        assert_eq!(config.result_cache_uri.as_deref(), Some("gs://other/cache"));
+        assert_eq!(config.log_redaction, RedactingFields::new(64));
+        assert_eq!(config.reverse_max_duration, Some(12.5));
+        assert_eq!(config.idempotency_ttl, Some(Duration::from_secs(120)));
+        assert_eq!(config.ffmpeg_hwaccel.as_deref(), Some("cuda"));
+    }
+
+    #[test]
+    fn log_redaction_accepts_env_style_values() {
+        for (value, expected) in [
+            ("true", RedactingFields::new(DEFAULT_REDACT_MAX_LEN)),
+            ("false", RedactingFields::disabled()),
+            ("80", RedactingFields::new(80)),
+            ("\"off\"", RedactingFields::disabled()),
+        ] {
+            let file = ConfigFile::parse(&format!("project_id = \"p\"\n[logging]\nredact = {}", value)).unwrap();
+            let config = Config::from_sources(Some(&file), env(&[])).unwrap();
+            assert_eq!(config.log_redaction, expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn invalid_image_defaults_are_errors() {
+        let file = ConfigFile::parse("project_id = \"p\"\n[image]\nsafety_filter_level = \"block_most\"").unwrap();
+        let err = Config::from_sources(Some(&file), env(&[])).unwrap_err();
+        assert!(matches!(err, ConfigError::InvalidValue(ref name, _) if name == "IMAGE_SAFETY_FILTER_LEVEL"), "{}", err);
+        assert!(err.to_string().contains("block_most"), "{}", err);
+
+        let err = Config::from_sources(None, env(&[("PROJECT_ID", "p"), ("IMAGE_PERSON_GENERATION", "allow_kids")]))
+            .unwrap_err();
+        assert!(matches!(err, ConfigError::InvalidValue(ref name, _) if name == "IMAGE_PERSON_GENERATION"), "{}", err);
+
+        // A valid env value replaces an invalid file value
+        let config = Config::from_sources(Some(&file), env(&[("IMAGE_SAFETY_FILTER_LEVEL", "block_none")])).unwrap();
+        assert_eq!(config.image_safety_filter_level.as_deref(), Some("block_none"));
+    }
+
+    #[test]
+    fn invalid_http_timeout_in_file_is_an_error() {
+        let file = ConfigFile::parse("project_id = \"p\"\n[http]\nconnect_timeout_secs = 0").unwrap();
+        let err = Config::from_sources(Some(&file), env(&[])).unwrap_err();
+        assert!(err.to_string().contains("HTTP_CONNECT_TIMEOUT_SECS"), "{}", err);
+    }
+
+    #[test]
+    fn wrong_types_and_bad_toml_are_errors() {
+        for text in ["port = \"eighty\"", "port = 70000", "project_id = "] {
+            let err = ConfigFile::parse(text).unwrap_err();
+            assert!(matches!(err, ConfigError::InvalidFile(..)), "{}: {}", text, err);
+        }
+    }
+
+    #[test]
+    fn from_file_reads_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("genmedia.toml");
+        std::fs::write(&path, FILE).unwrap();
+
+        let config = Config::from_file(&path).unwrap();
+        assert_eq!(config.project_id, "file-project");
+        assert_eq!(config.port, 9100);
+
+        let missing = Config::from_file(dir.path().join("missing.toml")).unwrap_err();
+        assert!(matches!(missing, ConfigError::InvalidFile(..)), "{}", missing);
+        assert!(missing.to_string().contains("missing.toml"), "{}", missing);
+
+        std::fs::write(&path, "location = \"us-east1\"").unwrap();
+        let err = Config::from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("genmedia.toml"), "{}", err);
+    }
+}
+
+/// Tests for per-server environment variable prefixes
+#[cfg(test)]
+mod prefix_tests {
+    use crate::config::{Config, ConfigFile, prefixed};
+    use std::collections::HashMap;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> + Clone {
+        let vars: HashMap<String, String> =
+            vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn prefixed_location_applies_only_to_its_server() {
+        let env = env(&[
+            ("PROJECT_ID", "shared-project"),
+            ("LOCATION", "us-central1"),
+            ("VIDEO_LOCATION", "europe-west4"),
+        ]);
+
+        let video = Config::from_sources(None, prefixed("VIDEO_", env.clone())).unwrap();
+        assert_eq!(video.location, "europe-west4");
+
+        let image = Config::from_sources(None, prefixed("IMAGE_", env)).unwrap();
+        assert_eq!(image.location, "us-central1");
+    }
+
+    #[test]
+    fn prefixed_vars_override_every_setting() {
+        let env = env(&[
+            ("PROJECT_ID", "shared-project"),
+            ("GCS_BUCKET", "shared-bucket"),
+            ("PORT", "8080"),
+            ("API_MAX_ATTEMPTS", "5"),
+            ("MCP_DOWNLOAD_DIR", "/srv/shared"),
+            ("MUSIC_PROJECT_ID", "music-project"),
+            ("MUSIC_GCS_BUCKET", "music-bucket"),
+            ("MUSIC_PORT", "8082"),
+            ("MUSIC_API_MAX_ATTEMPTS", "2"),
+            ("MUSIC_MCP_DOWNLOAD_DIR", "/srv/music"),
+        ]);
+
+        let config = Config::from_sources(None, prefixed("MUSIC_", env)).unwrap();
+        assert_eq!(config.project_id, "music-project");
+        assert_eq!(config.gcs_bucket.as_deref(), Some("music-bucket"));
+        assert_eq!(config.port, 8082);
+        assert_eq!(config.api_max_attempts, 2);
+        assert_eq!(config.download_dir.as_deref(), Some("/srv/music"));
+    }
+
+    #[test]
+    fn empty_prefixed_var_falls_back_to_unprefixed() {
+        let env = env(&[("PROJECT_ID", "p"), ("GCS_BUCKET", "shared-bucket"), ("VIDEO_GCS_BUCKET", "")]);
+        let config = Config::from_sources(None, prefixed("VIDEO_", env)).unwrap();
+        assert_eq!(config.gcs_bucket.as_deref(), Some("shared-bucket"));
+    }
+
+    #[test]
+    fn prefixed_project_id_alone_is_enough() {
+        let env = env(&[("SPEECH_PROJECT_ID", "speech-project")]);
+        assert_eq!(Config::from_sources(None, prefixed("SPEECH_", env.clone())).unwrap().project_id, "speech-project");
+        assert!(Config::from_sources(None, prefixed("VIDEO_", env)).is_err());
+    }
+
+    #[test]
+    fn prefixed_vars_override_service_settings() {
+        let file = ConfigFile::parse("project_id = \"p\"\n[ffmpeg]\npath = \"/opt/ffmpeg\"").unwrap();
+        let env = env(&[("AVTOOL_FFMPEG_PATH", "/usr/local/bin/ffmpeg"), ("MUSIC_HTTPS_PROXY", "http://music:3128")]);
+
+        let avtool = Config::from_sources(Some(&file), prefixed("AVTOOL_", env.clone())).unwrap();
+        assert_eq!(avtool.ffmpeg_binary(), "/usr/local/bin/ffmpeg");
+        assert_eq!(avtool.http.proxy, None);
+
+        let music = Config::from_sources(Some(&file), prefixed("MUSIC_", env)).unwrap();
+        assert_eq!(music.ffmpeg_binary(), "/opt/ffmpeg");
+        assert_eq!(music.http.proxy.as_deref(), Some("http://music:3128"));
+    }
+
+    /// Prefixed and unprefixed variables both beat the configuration file
+    #[test]
+    fn lookup_order_is_prefixed_then_unprefixed_then_file() {
+        let file = ConfigFile::parse("project_id = \"p\"\nlocation = \"asia-east1\"\nport = 9000").unwrap();
+        let env = env(&[("LOCATION", "us-east1"), ("VIDEO_PORT", "9100")]);
+
+        let config = Config::from_sources(Some(&file), prefixed("VIDEO_", env)).unwrap();
+        assert_eq!(config.location, "us-east1");
+        assert_eq!(config.port, 9100);
+
+        let config = Config::from_sources(Some(&file), prefixed("VIDEO_", |_: &str| None)).unwrap();
+        assert_eq!(config.location, "asia-east1");
+        assert_eq!(config.port, 9000);
+    }
 }
 
 /// Property-based tests for configuration defaults
@@ -152,6 +708,7 @@ mod property_tests {
                 location: "us-central1".to_string(),
                 gcs_bucket: None,
                 port: 8080,
+                ..Default::default()
             };
             prop_assert_eq!(config.project_id, project_id);
         }
@@ -171,6 +728,7 @@ mod property_tests {
                 location: location.clone(),
                 gcs_bucket: None,
                 port: 8080,
+                ..Default::default()
             };
             prop_assert_eq!(config.location, location);
         }
@@ -190,6 +748,7 @@ mod property_tests {
                 location: "us-central1".to_string(),
                 gcs_bucket: Some(bucket.clone()),
                 port: 8080,
+                ..Default::default()
             };
             prop_assert_eq!(config.gcs_bucket, Some(bucket));
         }
@@ -209,6 +768,7 @@ mod property_tests {
                 location: "us-central1".to_string(),
                 gcs_bucket: None,
                 port,
+                ..Default::default()
             };
             prop_assert_eq!(config.port, port);
         }
@@ -229,6 +789,7 @@ mod property_tests {
                 location: location.clone(),
                 gcs_bucket: None,
                 port: 8080,
+                ..Default::default()
             };
 
             let endpoint = config.vertex_ai_endpoint("test-model");
@@ -256,6 +817,7 @@ mod property_tests {
                 location: "us-central1".to_string(),
                 gcs_bucket: None,
                 port: 8080,
+                ..Default::default()
             };
 
             let endpoint = config.vertex_ai_endpoint(&model);
@@ -286,10 +848,17 @@ mod integration_tests {
     fn document_required_env_vars() {
         // PROJECT_ID is the only required environment variable
         // This test documents that requirement
-        let required_vars = vec!["PROJECT_ID"];
-        let optional_vars = vec!["LOCATION", "GCS_BUCKET", "PORT"];
+        let required_vars = ["PROJECT_ID"];
+        let optional_vars = [
+            "LOCATION",
+            "GCS_BUCKET",
+            "PORT",
+            "IMAGE_SAFETY_FILTER_LEVEL",
+            "IMAGE_PERSON_GENERATION",
+            "API_MAX_ATTEMPTS",
+        ];
 
         assert_eq!(required_vars.len(), 1);
-        assert_eq!(optional_vars.len(), 3);
+        assert_eq!(optional_vars.len(), 6);
     }
 }
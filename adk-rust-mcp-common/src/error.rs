@@ -8,14 +8,72 @@
 //! - `ConfigError`: Missing or invalid configuration
 //! - `GcsError`: Google Cloud Storage operations
 //! - `AuthError`: Authentication failures
-//! - `Error::Api`: Google Cloud API errors (includes endpoint and status)
+//! - `Error::Api`: Google Cloud API errors (includes endpoint, HTTP status and,
+//!   for parsed error bodies, the Google status such as `RESOURCE_EXHAUSTED`)
 //! - `Error::Validation`: Input validation failures
 //! - `Error::Io`: File system operations
 //! - `Error::Ffmpeg`: FFmpeg/FFprobe execution errors
 //! - `Error::Timeout`: Long-running operation timeouts
+//! - `Error::SafetyBlocked`: Generation refused by the model's safety filters
+//!
+//! Every error also maps to a stable [`ErrorCode`], which servers attach to MCP
+//! error `data` so clients can branch on the failure kind without parsing messages.
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// Stable, machine-readable error code surfaced in MCP error `data`.
+///
+/// The serialized names (`VALIDATION`, `UPSTREAM_API`, ...) are part of the
+/// tool contract and must not change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// Invalid input or configuration
+    Validation,
+    /// A Google Cloud API call failed
+    UpstreamApi,
+    /// FFmpeg/FFprobe failed
+    Ffmpeg,
+    /// A long-running operation timed out
+    Timeout,
+    /// Credentials are missing or could not be refreshed
+    Auth,
+    /// Storage (GCS or local file system) operation failed
+    Storage,
+    /// A referenced file, object or API resource does not exist
+    NotFound,
+    /// The model refused the prompt or its output on safety grounds
+    SafetyBlocked,
+}
+
+impl ErrorCode {
+    /// The stable string form of the code.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::Validation => "VALIDATION",
+            ErrorCode::UpstreamApi => "UPSTREAM_API",
+            ErrorCode::Ffmpeg => "FFMPEG",
+            ErrorCode::Timeout => "TIMEOUT",
+            ErrorCode::Auth => "AUTH",
+            ErrorCode::Storage => "STORAGE",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::SafetyBlocked => "SAFETY_BLOCKED",
+        }
+    }
+
+    /// MCP error `data` payload carrying this code (`{"code": "..."}`).
+    pub fn to_data(&self) -> serde_json::Value {
+        serde_json::json!({ "code": self.as_str() })
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Unified error type for the common library.
 ///
 /// This enum provides a single error type that can represent all error conditions
@@ -37,8 +95,12 @@ pub enum Error {
     /// API errors with endpoint and HTTP status context
     ///
     /// Includes the API endpoint that failed, HTTP status code, and error message
-    /// for debugging and user feedback.
-    #[error("API error for {endpoint} (HTTP {status_code}): {message}")]
+    /// for debugging and user feedback. Errors built with [`Error::api_response`]
+    /// from a standard Google error body also carry its status and details.
+    #[error(
+        "API error for {endpoint} (HTTP {status_code}{}): {message}",
+        .status.as_deref().map(|s| format!(" {}", s)).unwrap_or_default()
+    )]
     Api {
         /// The API endpoint that was called
         endpoint: String,
@@ -46,6 +108,10 @@ pub enum Error {
         status_code: u16,
         /// Error message from the API or describing the failure
         message: String,
+        /// Google status enum from the error body (e.g. "RESOURCE_EXHAUSTED")
+        status: Option<String>,
+        /// `error.details` entries from the error body
+        details: Vec<serde_json::Value>,
     },
 
     /// Input validation errors
@@ -63,6 +129,46 @@ pub enum Error {
     /// Operation timeout errors
     #[error("Operation timed out after {0} seconds")]
     Timeout(u64),
+
+    /// Generation blocked by the model's safety filters
+    #[error("Content blocked by safety filters: {0}")]
+    SafetyBlocked(SafetyBlock),
+}
+
+/// Why a generation request was blocked on safety grounds.
+///
+/// Serialized into the MCP error `data` next to the error code so clients can
+/// adjust the prompt or the safety settings without parsing messages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SafetyBlock {
+    /// Block or finish reason reported by the API (e.g. "SAFETY", "PROHIBITED_CONTENT")
+    pub reason: String,
+    /// Harm category that triggered the block, when the API names one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Threshold the request set for that category; `None` means the API default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<String>,
+    /// Probability the API assigned to the harm (e.g. "HIGH")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub probability: Option<String>,
+}
+
+impl std::fmt::Display for SafetyBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "reason {}", self.reason)?;
+        if let Some(ref category) = self.category {
+            write!(f, ", category {}", category)?;
+        }
+        if let Some(ref probability) = self.probability {
+            write!(f, ", probability {}", probability)?;
+        }
+        match self.threshold {
+            Some(ref threshold) => write!(f, ", threshold {}", threshold),
+            None if self.category.is_some() => write!(f, ", default threshold"),
+            None => Ok(()),
+        }
+    }
 }
 
 impl Error {
@@ -92,6 +198,42 @@ impl Error {
             endpoint: endpoint.into(),
             status_code,
             message: message.into(),
+            status: None,
+            details: Vec::new(),
+        }
+    }
+
+    /// Create an API error from the body of a non-success response.
+    ///
+    /// Google APIs answer errors with an envelope like
+    /// `{"error": {"code": 429, "message": "...", "status": "RESOURCE_EXHAUSTED", "details": [...]}}`
+    /// (sometimes wrapped in a one-element array). Its message, status and
+    /// details become the error's fields; any other body is kept verbatim as
+    /// the message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use adk_rust_mcp_common::error::Error;
+    ///
+    /// let body = r#"{"error": {"code": 429, "message": "Quota exceeded", "status": "RESOURCE_EXHAUSTED"}}"#;
+    /// let err = Error::api_response("https://api.example.com/v1/generate", 429, body);
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "API error for https://api.example.com/v1/generate (HTTP 429 RESOURCE_EXHAUSTED): Quota exceeded"
+    /// );
+    /// ```
+    pub fn api_response(endpoint: impl Into<String>, status_code: u16, body: impl Into<String>) -> Self {
+        let body = body.into();
+        match GoogleErrorBody::parse(&body) {
+            Some(parsed) => Error::Api {
+                endpoint: endpoint.into(),
+                status_code,
+                message: parsed.message,
+                status: parsed.status,
+                details: parsed.details,
+            },
+            None => Error::api(endpoint, status_code, body),
         }
     }
 
@@ -136,6 +278,103 @@ impl Error {
     pub fn timeout(seconds: u64) -> Self {
         Error::Timeout(seconds)
     }
+
+    /// The stable code for this error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use adk_rust_mcp_common::error::{Error, ErrorCode};
+    ///
+    /// assert_eq!(Error::validation("bad").code(), ErrorCode::Validation);
+    /// assert_eq!(Error::api("https://example.com", 404, "gone").code(), ErrorCode::NotFound);
+    /// ```
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::Config(_) | Error::Validation(_) => ErrorCode::Validation,
+            Error::Gcs(GcsError::AuthError(_)) | Error::Auth(_) => ErrorCode::Auth,
+            Error::Gcs(_) => ErrorCode::Storage,
+            Error::Api { status_code: 404, .. } => ErrorCode::NotFound,
+            Error::Api { status_code: 401 | 403, .. } => ErrorCode::Auth,
+            Error::Api { .. } => ErrorCode::UpstreamApi,
+            Error::Io(e) if e.kind() == std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+            Error::Io(_) => ErrorCode::Storage,
+            Error::Ffmpeg(_) => ErrorCode::Ffmpeg,
+            Error::Timeout(_) => ErrorCode::Timeout,
+            Error::SafetyBlocked(_) => ErrorCode::SafetyBlocked,
+        }
+    }
+
+    /// MCP error `data` for this error: the code, plus the block details for
+    /// [`Error::SafetyBlocked`] and the Google status and details for a parsed
+    /// [`Error::Api`].
+    pub fn to_data(&self) -> serde_json::Value {
+        let mut data = self.code().to_data();
+        let Some(fields) = data.as_object_mut() else {
+            return data;
+        };
+        match self {
+            Error::SafetyBlocked(block) => {
+                if let Ok(serde_json::Value::Object(details)) = serde_json::to_value(block) {
+                    fields.extend(details);
+                }
+            }
+            Error::Api { status, details, .. } => {
+                if let Some(status) = status {
+                    fields.insert("status".to_string(), status.clone().into());
+                }
+                if !details.is_empty() {
+                    fields.insert("details".to_string(), details.clone().into());
+                }
+            }
+            _ => {}
+        }
+        data
+    }
+
+    /// Convert to an MCP error whose message is `"{context}: {self}"` and whose
+    /// `data` carries the error code.
+    pub fn to_mcp_error(&self, context: &str) -> rmcp::ErrorData {
+        rmcp::ErrorData::internal_error(format!("{}: {}", context, self), Some(self.to_data()))
+    }
+}
+
+/// The standard Google API error envelope, `{"error": {...}}`.
+#[derive(Debug, Deserialize)]
+struct GoogleErrorEnvelope {
+    error: GoogleErrorBody,
+}
+
+/// The `error` object of a Google API error response.
+#[derive(Debug, Deserialize)]
+struct GoogleErrorBody {
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    details: Vec<serde_json::Value>,
+}
+
+impl GoogleErrorBody {
+    /// Parse an error response body, or `None` if it is not a Google error
+    /// envelope with a message or status.
+    fn parse(body: &str) -> Option<Self> {
+        let envelope = match serde_json::from_str::<serde_json::Value>(body).ok()? {
+            serde_json::Value::Array(items) => items.into_iter().next()?,
+            value => value,
+        };
+        let envelope: GoogleErrorEnvelope = serde_json::from_value(envelope).ok()?;
+        let mut error = envelope.error;
+        match (error.message.is_empty(), error.status.as_deref()) {
+            (true, None) => None,
+            (true, Some(status)) => {
+                error.message = status.to_string();
+                Some(error)
+            }
+            _ => Some(error),
+        }
+    }
 }
 
 /// Configuration errors.
@@ -151,6 +390,10 @@ pub enum ConfigError {
     /// An environment variable has an invalid value
     #[error("Invalid value for {0}: {1}")]
     InvalidValue(String, String),
+
+    /// A configuration file cannot be read or parsed
+    #[error("Invalid configuration file {0}: {1}")]
+    InvalidFile(String, String),
 }
 
 impl ConfigError {
@@ -163,6 +406,11 @@ impl ConfigError {
     pub fn invalid_value(name: impl Into<String>, reason: impl Into<String>) -> Self {
         ConfigError::InvalidValue(name.into(), reason.into())
     }
+
+    /// Create a new invalid configuration file error.
+    pub fn invalid_file(path: impl Into<String>, reason: impl Into<String>) -> Self {
+        ConfigError::InvalidFile(path.into(), reason.into())
+    }
 }
 
 /// GCS operation type for error context.
@@ -174,6 +422,8 @@ pub enum GcsOperation {
     Download,
     /// Check existence operation
     Exists,
+    /// Read object metadata operation
+    Metadata,
     /// Delete operation
     Delete,
 }
@@ -184,6 +434,7 @@ impl std::fmt::Display for GcsOperation {
             GcsOperation::Upload => write!(f, "upload"),
             GcsOperation::Download => write!(f, "download"),
             GcsOperation::Exists => write!(f, "exists"),
+            GcsOperation::Metadata => write!(f, "metadata"),
             GcsOperation::Delete => write!(f, "delete"),
         }
     }
@@ -258,6 +509,28 @@ impl GcsError {
     pub fn auth_error(message: impl Into<String>) -> Self {
         GcsError::AuthError(message.into())
     }
+
+    /// Whether retrying the operation may succeed.
+    ///
+    /// Dropped connections, interrupted transfers and retryable statuses
+    /// (see [`DEFAULT_RETRY_STATUSES`](crate::retry::DEFAULT_RETRY_STATUSES))
+    /// are transient; missing objects, permission errors, invalid URIs and
+    /// local write failures are not.
+    pub fn is_transient(&self) -> bool {
+        let GcsError::OperationFailed { message, .. } = self else {
+            return false;
+        };
+        match message.strip_prefix("Failed with status ") {
+            Some(status) => status
+                .get(..3)
+                .and_then(|code| code.parse::<u16>().ok())
+                .is_some_and(|code| crate::retry::DEFAULT_RETRY_STATUSES.contains(&code)),
+            None => {
+                message.starts_with("Download request failed")
+                    || message.starts_with("Failed to read response body")
+            }
+        }
+    }
 }
 
 /// Authentication errors.
@@ -298,6 +571,62 @@ mod tests {
         assert!(msg.contains("Internal error"), "Should contain message");
     }
 
+    #[test]
+    fn test_api_response_parses_google_error_body() {
+        let body = r#"{
+            "error": {
+                "code": 429,
+                "message": "Quota exceeded for aiplatform.googleapis.com/generate_content_requests_per_minute.",
+                "status": "RESOURCE_EXHAUSTED",
+                "details": [{"@type": "type.googleapis.com/google.rpc.ErrorInfo", "reason": "RATE_LIMIT_EXCEEDED"}]
+            }
+        }"#;
+        let err = Error::api_response("https://vertex.googleapis.com/v1/generate", 429, body);
+        assert_eq!(
+            err.to_string(),
+            "API error for https://vertex.googleapis.com/v1/generate (HTTP 429 RESOURCE_EXHAUSTED): \
+             Quota exceeded for aiplatform.googleapis.com/generate_content_requests_per_minute."
+        );
+        assert_eq!(
+            err.to_data(),
+            serde_json::json!({
+                "code": "UPSTREAM_API",
+                "status": "RESOURCE_EXHAUSTED",
+                "details": [{"@type": "type.googleapis.com/google.rpc.ErrorInfo", "reason": "RATE_LIMIT_EXCEEDED"}],
+            })
+        );
+    }
+
+    #[test]
+    fn test_api_response_parses_array_wrapped_body() {
+        let body = r#"[{"error": {"code": 403, "message": "Permission denied on resource project p.", "status": "PERMISSION_DENIED"}}]"#;
+        let err = Error::api_response("e", 403, body);
+        assert!(matches!(
+            &err,
+            Error::Api { message, status: Some(status), details, .. }
+                if message == "Permission denied on resource project p."
+                    && status == "PERMISSION_DENIED"
+                    && details.is_empty()
+        ));
+        assert_eq!(err.code(), ErrorCode::Auth);
+        assert_eq!(err.to_data(), serde_json::json!({"code": "AUTH", "status": "PERMISSION_DENIED"}));
+    }
+
+    #[test]
+    fn test_api_response_keeps_unparsed_body() {
+        for body in ["<html>Bad Gateway</html>", r#"{"error": {}}"#, r#"{"message": "x"}"#, ""] {
+            let err = Error::api_response("e", 502, body);
+            assert!(
+                matches!(&err, Error::Api { message, status: None, .. } if message == body),
+                "{body}"
+            );
+            assert_eq!(err.to_data(), serde_json::json!({"code": "UPSTREAM_API"}));
+        }
+
+        let status_only = Error::api_response("e", 503, r#"{"error": {"status": "UNAVAILABLE"}}"#);
+        assert_eq!(status_only.to_string(), "API error for e (HTTP 503 UNAVAILABLE): UNAVAILABLE");
+    }
+
     #[test]
     fn test_gcs_error_includes_uri_and_operation() {
         let err = GcsError::operation_failed(
@@ -311,6 +640,23 @@ mod tests {
         assert!(msg.contains("Access denied"), "Should contain message");
     }
 
+    #[test]
+    fn test_gcs_error_transient_classification() {
+        let failed = |message: &str| {
+            GcsError::operation_failed("gs://b/v.mp4", GcsOperation::Download, message)
+        };
+        assert!(failed("Failed with status 503 Service Unavailable: busy").is_transient());
+        assert!(failed("Failed with status 429 Too Many Requests: ").is_transient());
+        assert!(failed("Download request failed: connection reset").is_transient());
+        assert!(failed("Failed to read response body: unexpected EOF").is_transient());
+
+        assert!(!failed("Failed with status 404 Not Found: No such object").is_transient());
+        assert!(!failed("Failed with status 403 Forbidden: denied").is_transient());
+        assert!(!failed("Failed to write downloaded data: disk full").is_transient());
+        assert!(!GcsError::auth_error("no credentials").is_transient());
+        assert!(!GcsError::invalid_uri("not-a-uri").is_transient());
+    }
+
     #[test]
     fn test_config_error_includes_var_name() {
         let err = ConfigError::missing_env_var("PROJECT_ID");
@@ -370,11 +716,85 @@ mod tests {
         assert!(msg.contains("prompt too long"), "Should contain message");
     }
 
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(Error::validation("x").code(), ErrorCode::Validation);
+        assert_eq!(Error::from(ConfigError::missing_env_var("X")).code(), ErrorCode::Validation);
+        assert_eq!(Error::api("e", 500, "x").code(), ErrorCode::UpstreamApi);
+        assert_eq!(Error::api("e", 404, "x").code(), ErrorCode::NotFound);
+        assert_eq!(Error::api("e", 403, "x").code(), ErrorCode::Auth);
+        assert_eq!(Error::ffmpeg("x").code(), ErrorCode::Ffmpeg);
+        assert_eq!(Error::timeout(1).code(), ErrorCode::Timeout);
+        assert_eq!(Error::from(AuthError::NotConfigured).code(), ErrorCode::Auth);
+        assert_eq!(Error::from(GcsError::auth_error("x")).code(), ErrorCode::Auth);
+        assert_eq!(Error::from(GcsError::invalid_uri("x")).code(), ErrorCode::Storage);
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "x");
+        assert_eq!(Error::from(missing).code(), ErrorCode::NotFound);
+        let denied = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "x");
+        assert_eq!(Error::from(denied).code(), ErrorCode::Storage);
+    }
+
+    #[test]
+    fn test_error_code_serializes_to_stable_name() {
+        assert_eq!(serde_json::to_value(ErrorCode::UpstreamApi).unwrap(), "UPSTREAM_API");
+        assert_eq!(ErrorCode::NotFound.to_string(), "NOT_FOUND");
+        assert_eq!(ErrorCode::Ffmpeg.to_data(), serde_json::json!({"code": "FFMPEG"}));
+    }
+
+    #[test]
+    fn test_to_mcp_error_keeps_message_and_adds_code() {
+        let err = Error::timeout(30).to_mcp_error("Video generation failed");
+        assert_eq!(err.message, "Video generation failed: Operation timed out after 30 seconds");
+        assert_eq!(err.data, Some(serde_json::json!({"code": "TIMEOUT"})));
+    }
+
+    #[test]
+    fn test_safety_blocked_error_carries_details() {
+        let err = Error::SafetyBlocked(SafetyBlock {
+            reason: "SAFETY".to_string(),
+            category: Some("HARM_CATEGORY_DANGEROUS_CONTENT".to_string()),
+            threshold: Some("BLOCK_LOW_AND_ABOVE".to_string()),
+            probability: Some("MEDIUM".to_string()),
+        });
+        assert_eq!(err.code(), ErrorCode::SafetyBlocked);
+        assert_eq!(
+            err.to_string(),
+            "Content blocked by safety filters: reason SAFETY, category HARM_CATEGORY_DANGEROUS_CONTENT, \
+             probability MEDIUM, threshold BLOCK_LOW_AND_ABOVE"
+        );
+        assert_eq!(
+            err.to_mcp_error("Image generation failed").data,
+            Some(serde_json::json!({
+                "code": "SAFETY_BLOCKED",
+                "reason": "SAFETY",
+                "category": "HARM_CATEGORY_DANGEROUS_CONTENT",
+                "threshold": "BLOCK_LOW_AND_ABOVE",
+                "probability": "MEDIUM",
+            }))
+        );
+    }
+
+    #[test]
+    fn test_safety_block_without_category() {
+        let block = SafetyBlock {
+            reason: "PROHIBITED_CONTENT".to_string(),
+            category: None,
+            threshold: None,
+            probability: None,
+        };
+        assert_eq!(block.to_string(), "reason PROHIBITED_CONTENT");
+        assert_eq!(
+            Error::SafetyBlocked(block).to_data(),
+            serde_json::json!({"code": "SAFETY_BLOCKED", "reason": "PROHIBITED_CONTENT"})
+        );
+    }
+
     #[test]
     fn test_gcs_operation_display() {
         assert_eq!(GcsOperation::Upload.to_string(), "upload");
         assert_eq!(GcsOperation::Download.to_string(), "download");
         assert_eq!(GcsOperation::Exists.to_string(), "exists");
+        assert_eq!(GcsOperation::Metadata.to_string(), "metadata");
         assert_eq!(GcsOperation::Delete.to_string(), "delete");
     }
 }
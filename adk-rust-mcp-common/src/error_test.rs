@@ -37,6 +37,7 @@ fn gcs_operation_strategy() -> impl Strategy<Value = GcsOperation> {
         Just(GcsOperation::Upload),
         Just(GcsOperation::Download),
         Just(GcsOperation::Exists),
+        Just(GcsOperation::Metadata),
         Just(GcsOperation::Delete),
     ]
 }
@@ -161,6 +162,7 @@ mod unit_tests {
             GcsOperation::Upload,
             GcsOperation::Download,
             GcsOperation::Exists,
+            GcsOperation::Metadata,
             GcsOperation::Delete,
         ];
 
@@ -0,0 +1,303 @@
+//! FFmpeg process helpers.
+//!
+//! Locates the ffmpeg binary (`FFMPEG_PATH` or `ffmpeg` on `PATH`), checks its
+//! version at startup and runs it. Shared by the AVTool server and by
+//! generation servers that post-process their output, such as MP3 encoding
+//! of generated music.
+
+use crate::error::Error;
+use crate::tracing::SpanTimer;
+use std::future::Future;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::{ChildStdin, Command};
+use tracing::{Span, debug, field, info, instrument, warn};
+
+/// Environment variable overriding the ffmpeg binary (defaults to `ffmpeg` on `PATH`).
+pub const FFMPEG_PATH_ENV: &str = "FFMPEG_PATH";
+
+/// Oldest ffmpeg release known to support every filter the tools use
+/// (`xfade` needs 4.3).
+pub const MIN_FFMPEG_VERSION: (u32, u32) = (4, 3);
+
+/// The ffmpeg binary to execute: `FFMPEG_PATH` if set, otherwise `ffmpeg`.
+pub fn ffmpeg_binary() -> String {
+    std::env::var(FFMPEG_PATH_ENV)
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "ffmpeg".to_string())
+}
+
+/// The ffprobe that ships with the ffmpeg at `ffmpeg`.
+///
+/// ffprobe is looked up in the same directory, with `ffmpeg` in the file name
+/// replaced (`/opt/ff/ffmpeg-6.exe` gives `/opt/ff/ffprobe-6.exe`); a file name
+/// without `ffmpeg` in it gives plain `ffprobe` in that directory. The default
+/// `ffmpeg` maps to `ffprobe` on `PATH`.
+pub fn ffprobe_for(ffmpeg: &str) -> String {
+    let path = Path::new(ffmpeg);
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let probe = if name.contains("ffmpeg") {
+        name.replacen("ffmpeg", "ffprobe", 1)
+    } else {
+        "ffprobe".to_string()
+    };
+    match path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        Some(dir) => dir.join(probe).to_string_lossy().into_owned(),
+        None => probe,
+    }
+}
+
+/// ffmpeg release reported by `ffmpeg -version`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FfmpegVersion {
+    /// Version string as printed, e.g. `6.1.1-3ubuntu5` or `N-112345-gabc`.
+    pub raw: String,
+    /// Major and minor release numbers, when the version is a numbered release.
+    pub release: Option<(u32, u32)>,
+}
+
+impl FfmpegVersion {
+    /// Whether this is a numbered release older than [`MIN_FFMPEG_VERSION`].
+    ///
+    /// Git snapshot builds (`N-...`) have no release number and are assumed current.
+    pub fn is_below_minimum(&self) -> bool {
+        self.release.is_some_and(|release| release < MIN_FFMPEG_VERSION)
+    }
+}
+
+/// Parse the first line of `ffmpeg -version` output.
+///
+/// Accepts `ffmpeg version 6.1.1-3ubuntu5 Copyright ...`, `ffmpeg version n7.0 ...`
+/// and snapshot builds such as `ffmpeg version N-112345-gabc ...`.
+pub fn parse_ffmpeg_version(output: &str) -> Option<FfmpegVersion> {
+    let line = output.lines().next()?;
+    let raw = line.trim().strip_prefix("ffmpeg version ")?.split_whitespace().next()?;
+
+    let numeric = raw.strip_prefix('n').unwrap_or(raw);
+    let mut parts = numeric.split(|c: char| !c.is_ascii_digit());
+    let major = parts.next().and_then(|p| p.parse().ok());
+    let minor = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+
+    Some(FfmpegVersion {
+        raw: raw.to_string(),
+        release: major.map(|major| (major, minor)),
+    })
+}
+
+/// Error for a binary that could not be started.
+fn not_found(binary: &str, e: std::io::Error) -> Error {
+    Error::ffmpeg(format!(
+        "ffmpeg not found at '{}' ({}). Install FFmpeg or set {} to the ffmpeg binary",
+        binary, e, FFMPEG_PATH_ENV
+    ))
+}
+
+/// Verify that ffmpeg can be executed and log its version.
+///
+/// Intended for server startup, so a missing binary fails immediately rather
+/// than on the first tool call. Releases older than [`MIN_FFMPEG_VERSION`]
+/// only produce a warning.
+///
+/// # Errors
+/// Returns `Error::Ffmpeg` if the binary from [`ffmpeg_binary`] cannot be run.
+pub async fn check_ffmpeg() -> Result<FfmpegVersion, Error> {
+    check_ffmpeg_at(&ffmpeg_binary()).await
+}
+
+/// [`check_ffmpeg`] against an explicit binary path.
+pub async fn check_ffmpeg_at(binary: &str) -> Result<FfmpegVersion, Error> {
+    let output = Command::new(binary)
+        .arg("-version")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| not_found(binary, e))?;
+
+    if !output.status.success() {
+        return Err(Error::ffmpeg(format!(
+            "'{} -version' failed ({}). Check the binary or set {} to a working ffmpeg",
+            binary, output.status, FFMPEG_PATH_ENV
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let version = parse_ffmpeg_version(&stdout).unwrap_or_else(|| FfmpegVersion {
+        raw: "unknown".to_string(),
+        release: None,
+    });
+
+    info!(binary = %binary, version = %version.raw, "Found ffmpeg");
+    if version.is_below_minimum() {
+        warn!(
+            version = %version.raw,
+            minimum = %format!("{}.{}", MIN_FFMPEG_VERSION.0, MIN_FFMPEG_VERSION.1),
+            "ffmpeg is older than the minimum supported version; some filters (e.g. xfade) may be unavailable"
+        );
+    }
+
+    Ok(version)
+}
+
+/// Run the ffmpeg at `binary` with the given arguments, returning its stderr,
+/// where filters such as `showinfo` and `blackdetect` write their results.
+///
+/// With `overwrite` ffmpeg runs with `-y` and replaces existing output files;
+/// otherwise it runs with `-n` and fails instead.
+///
+/// # Errors
+/// Returns `Error::Ffmpeg` if ffmpeg cannot be started or exits unsuccessfully.
+#[instrument(
+    level = "info",
+    name = "ffmpeg",
+    skip_all,
+    fields(exit_code = field::Empty, duration_ms = field::Empty)
+)]
+pub async fn run_ffmpeg(binary: &str, args: &[&str], overwrite: bool) -> Result<String, Error> {
+    let _timer = SpanTimer::start();
+    debug!(args = ?args, "Running ffmpeg");
+
+    let output = Command::new(binary)
+        .arg(if overwrite { "-y" } else { "-n" })
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| not_found(binary, e))?;
+    record_exit_code(&output.status);
+
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    if !output.status.success() {
+        return Err(Error::ffmpeg(format!("ffmpeg failed: {}", stderr)));
+    }
+
+    Ok(stderr)
+}
+
+/// Run ffmpeg like [`run_ffmpeg`], with `feed` writing its standard input.
+///
+/// `args` should read an input from `pipe:0`. `feed` runs alongside ffmpeg
+/// and stdin is closed when it returns, which ffmpeg sees as end of input.
+/// If ffmpeg succeeds but `feed` failed, the output may be truncated, so the
+/// `feed` error is returned.
+///
+/// # Errors
+/// Returns `Error::Ffmpeg` if ffmpeg cannot be started or exits unsuccessfully,
+/// or the error from `feed`.
+#[instrument(
+    level = "info",
+    name = "ffmpeg",
+    skip_all,
+    fields(exit_code = field::Empty, duration_ms = field::Empty)
+)]
+pub async fn run_ffmpeg_with_stdin<F, Fut>(
+    binary: &str,
+    args: &[&str],
+    overwrite: bool,
+    feed: F,
+) -> Result<String, Error>
+where
+    F: FnOnce(ChildStdin) -> Fut,
+    Fut: Future<Output = Result<(), Error>>,
+{
+    let _timer = SpanTimer::start();
+    debug!(args = ?args, "Running ffmpeg with piped input");
+
+    let mut child = Command::new(binary)
+        .arg(if overwrite { "-y" } else { "-n" })
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| not_found(binary, e))?;
+    let stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| Error::ffmpeg("ffmpeg stdin was not captured"))?;
+
+    let (fed, output) = tokio::join!(feed(stdin), child.wait_with_output());
+    let output = output?;
+    record_exit_code(&output.status);
+
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    if !output.status.success() {
+        // ffmpeg exiting early also breaks the pipe, so its own error comes first
+        return Err(match fed {
+            Ok(()) => Error::ffmpeg(format!("ffmpeg failed: {}", stderr)),
+            Err(e) => Error::ffmpeg(format!("ffmpeg failed: {} (input stream: {})", stderr, e)),
+        });
+    }
+    fed?;
+
+    Ok(stderr)
+}
+
+/// Encode WAV bytes as MP3 at `bitrate` (e.g. `192k`), piping through the
+/// ffmpeg at `binary` without temporary files.
+///
+/// # Errors
+/// Returns `Error::Ffmpeg` if ffmpeg is not installed, lacks `libmp3lame`, or
+/// cannot decode the input.
+#[instrument(
+    level = "info",
+    name = "ffmpeg_encode_mp3",
+    skip(wav),
+    fields(
+        input_bytes = wav.len(),
+        output_bytes = field::Empty,
+        exit_code = field::Empty,
+        duration_ms = field::Empty
+    )
+)]
+pub async fn encode_mp3(binary: &str, wav: &[u8], bitrate: &str) -> Result<Vec<u8>, Error> {
+    let _timer = SpanTimer::start();
+
+    let mut child = Command::new(binary)
+        .args(["-hide_banner", "-loglevel", "error"])
+        .args(["-f", "wav", "-i", "pipe:0"])
+        .args(["-codec:a", "libmp3lame", "-b:a", bitrate])
+        .args(["-f", "mp3", "pipe:1"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| not_found(binary, e))?;
+
+    // Feed stdin concurrently so a full stdout pipe cannot deadlock ffmpeg
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| Error::ffmpeg("ffmpeg stdin was not captured"))?;
+    let input = wav.to_vec();
+    let writer = tokio::spawn(async move {
+        let result = stdin.write_all(&input).await;
+        drop(stdin);
+        result
+    });
+
+    let output = child.wait_with_output().await?;
+    record_exit_code(&output.status);
+    let write_result = writer
+        .await
+        .map_err(|e| Error::ffmpeg(format!("ffmpeg input writer failed: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(Error::ffmpeg(format!(
+            "MP3 encoding failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    write_result?;
+
+    Span::current().record("output_bytes", output.stdout.len());
+    Ok(output.stdout)
+}
+
+/// Record a process exit code on the current span (`-1` if killed by a signal).
+pub fn record_exit_code(status: &std::process::ExitStatus) {
+    Span::current().record("exit_code", status.code().unwrap_or(-1));
+}
@@ -0,0 +1,78 @@
+//! Unit tests for the ffmpeg module.
+
+use crate::ffmpeg::{
+    FFMPEG_PATH_ENV, check_ffmpeg_at, encode_mp3, ffprobe_for, parse_ffmpeg_version, run_ffmpeg_with_stdin,
+};
+use tokio::io::AsyncWriteExt;
+
+#[test]
+fn test_parse_ffmpeg_version_distro_build() {
+    let output = "ffmpeg version 6.1.1-3ubuntu5 Copyright (c) 2000-2023 the FFmpeg developers\nbuilt with gcc 13";
+    let version = parse_ffmpeg_version(output).unwrap();
+    assert_eq!(version.raw, "6.1.1-3ubuntu5");
+    assert_eq!(version.release, Some((6, 1)));
+    assert!(!version.is_below_minimum());
+}
+
+#[test]
+fn test_parse_ffmpeg_version_tagged_and_old_builds() {
+    let tagged = parse_ffmpeg_version("ffmpeg version n7.0 Copyright (c) 2000-2024").unwrap();
+    assert_eq!(tagged.release, Some((7, 0)));
+
+    let old = parse_ffmpeg_version("ffmpeg version 4.2.7-0ubuntu0.1 Copyright").unwrap();
+    assert_eq!(old.release, Some((4, 2)));
+    assert!(old.is_below_minimum());
+
+    let minimum = parse_ffmpeg_version("ffmpeg version 4.3 Copyright").unwrap();
+    assert!(!minimum.is_below_minimum());
+}
+
+#[test]
+fn test_parse_ffmpeg_version_snapshot_build() {
+    let version = parse_ffmpeg_version("ffmpeg version N-112345-gabcdef0 Copyright").unwrap();
+    assert_eq!(version.raw, "N-112345-gabcdef0");
+    assert_eq!(version.release, None);
+    assert!(!version.is_below_minimum());
+}
+
+#[test]
+fn test_parse_ffmpeg_version_rejects_other_output() {
+    assert!(parse_ffmpeg_version("").is_none());
+    assert!(parse_ffmpeg_version("ffprobe version 6.0").is_none());
+}
+
+#[tokio::test]
+async fn test_check_ffmpeg_missing_binary_names_env_var() {
+    let err = check_ffmpeg_at("/nonexistent/bin/ffmpeg").await.unwrap_err();
+    let msg = err.to_string();
+    assert!(msg.contains("/nonexistent/bin/ffmpeg"), "Error should name the binary: {}", msg);
+    assert!(msg.contains(FFMPEG_PATH_ENV), "Error should mention FFMPEG_PATH: {}", msg);
+}
+
+#[test]
+fn test_ffprobe_for_sits_next_to_ffmpeg() {
+    assert_eq!(ffprobe_for("ffmpeg"), "ffprobe");
+    assert_eq!(ffprobe_for("/usr/local/bin/ffmpeg"), "/usr/local/bin/ffprobe");
+    assert_eq!(ffprobe_for("/opt/ff/ffmpeg-6.exe"), "/opt/ff/ffprobe-6.exe");
+    assert_eq!(ffprobe_for("/opt/media/transcoder"), "/opt/media/ffprobe");
+}
+
+#[tokio::test]
+async fn test_encode_mp3_rejects_non_wav_input() {
+    // Either ffmpeg is missing or it cannot decode the bytes; both must be errors
+    let err = encode_mp3("ffmpeg", b"not a wav file", "128k").await.unwrap_err();
+    assert!(matches!(err, crate::Error::Ffmpeg(_)), "Unexpected error: {:?}", err);
+}
+
+#[tokio::test]
+async fn test_run_ffmpeg_with_stdin_rejects_undecodable_input() {
+    // Either ffmpeg is missing or it cannot decode the piped bytes
+    let args = ["-i", "pipe:0", "-f", "null", "-"];
+    let err = run_ffmpeg_with_stdin("ffmpeg", &args, true, |mut stdin| async move {
+        stdin.write_all(b"not media").await?;
+        Ok(())
+    })
+    .await
+    .unwrap_err();
+    assert!(matches!(err, crate::Error::Ffmpeg(_)), "Unexpected error: {:?}", err);
+}
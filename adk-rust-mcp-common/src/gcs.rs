@@ -1,6 +1,11 @@
 //! Google Cloud Storage utilities.
 
 use crate::auth::AuthProvider;
+use crate::tracing::SpanTimer;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tracing::{Span, field, instrument};
 use crate::error::{GcsError, GcsOperation};
 
 /// Parsed GCS URI components.
@@ -10,13 +15,28 @@ pub struct GcsUri {
     pub bucket: String,
     /// Object path within the bucket
     pub object: String,
+    /// Object generation (`gs://bucket/object#1234`), if pinned to a specific version
+    pub generation: Option<u64>,
 }
 
+/// Minimum bucket name length.
+const MIN_BUCKET_LEN: usize = 3;
+
+/// Maximum length of a bucket name without dots, and of each dot-separated part.
+const MAX_BUCKET_LEN: usize = 63;
+
+/// Maximum length of a bucket name containing dots.
+const MAX_DOTTED_BUCKET_LEN: usize = 222;
+
 impl GcsUri {
-    /// Parse a `gs://bucket/path` URI into components.
+    /// Parse a `gs://bucket/path[#generation]` URI into components.
+    ///
+    /// A trailing `#<digits>` is treated as the object generation; any other
+    /// `#` is kept as part of the object name.
     ///
     /// # Errors
-    /// Returns `GcsError::InvalidUri` if the URI format is invalid.
+    /// Returns `GcsError::InvalidUri` if the URI format is invalid or the
+    /// bucket name breaks GCS naming rules.
     pub fn parse(uri: &str) -> Result<Self, GcsError> {
         let uri = uri
             .strip_prefix("gs://")
@@ -26,27 +46,100 @@ impl GcsUri {
             .split_once('/')
             .ok_or_else(|| GcsError::InvalidUri(format!("URI must contain bucket and path: {}", uri)))?;
 
-        if bucket.is_empty() {
-            return Err(GcsError::InvalidUri("Bucket name cannot be empty".to_string()));
-        }
+        Self::validate_bucket(bucket)?;
+
+        let (object, generation) = match object.rsplit_once('#') {
+            Some((name, generation))
+                if !generation.is_empty() && generation.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                let generation = generation.parse().map_err(|_| {
+                    GcsError::InvalidUri(format!("Generation number out of range: {}", generation))
+                })?;
+                (name, Some(generation))
+            }
+            _ => (object, None),
+        };
 
         Ok(Self {
             bucket: bucket.to_string(),
             object: object.to_string(),
+            generation,
         })
     }
+
+    /// Check a bucket name against GCS naming rules.
+    ///
+    /// Names must be 3-63 characters of lowercase letters, digits, `-`, `_`,
+    /// and `.`, and must start and end with a letter or digit. Names with dots
+    /// may be up to 222 characters, with at most 63 between dots.
+    ///
+    /// # Errors
+    /// Returns `GcsError::InvalidUri` describing the first rule that is broken.
+    pub fn validate_bucket(bucket: &str) -> Result<(), GcsError> {
+        if bucket.is_empty() {
+            return Err(GcsError::InvalidUri("Bucket name cannot be empty".to_string()));
+        }
+
+        let max_len = if bucket.contains('.') { MAX_DOTTED_BUCKET_LEN } else { MAX_BUCKET_LEN };
+        if bucket.len() < MIN_BUCKET_LEN || bucket.len() > max_len {
+            return Err(GcsError::InvalidUri(format!(
+                "Bucket name '{}' must be {}-{} characters long ({} with dots), got {}",
+                bucket, MIN_BUCKET_LEN, MAX_BUCKET_LEN, MAX_DOTTED_BUCKET_LEN, bucket.len()
+            )));
+        }
+
+        if let Some(part) = bucket.split('.').find(|part| part.len() > MAX_BUCKET_LEN) {
+            return Err(GcsError::InvalidUri(format!(
+                "Bucket name '{}' has a dot-separated part of {} characters; each part must be at most {}",
+                bucket, part.len(), MAX_BUCKET_LEN
+            )));
+        }
+
+        if let Some(c) = bucket
+            .chars()
+            .find(|c| !(c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.')))
+        {
+            return Err(GcsError::InvalidUri(format!(
+                "Bucket name '{}' contains invalid character '{}'; only lowercase letters, digits, '-', '_', and '.' are allowed",
+                bucket, c
+            )));
+        }
+
+        let is_alnum = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit();
+        if !bucket.starts_with(is_alnum) || !bucket.ends_with(is_alnum) {
+            return Err(GcsError::InvalidUri(format!(
+                "Bucket name '{}' must start and end with a letter or digit",
+                bucket
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 impl std::fmt::Display for GcsUri {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "gs://{}/{}", self.bucket, self.object)
+        write!(f, "gs://{}/{}", self.bucket, self.object)?;
+        if let Some(generation) = self.generation {
+            write!(f, "#{}", generation)?;
+        }
+        Ok(())
     }
 }
 
+/// Append the object generation, if any, as a query parameter.
+fn with_generation(mut url: String, uri: &GcsUri) -> String {
+    if let Some(generation) = uri.generation {
+        let separator = if url.contains('?') { '&' } else { '?' };
+        url.push_str(&format!("{}generation={}", separator, generation));
+    }
+    url
+}
+
 /// GCS operations client.
 pub struct GcsClient {
     client: reqwest::Client,
-    auth: AuthProvider,
+    auth: Arc<AuthProvider>,
     /// Base URL for GCS API (configurable for testing)
     base_url: String,
 }
@@ -63,13 +156,16 @@ impl GcsClient {
 
         Ok(Self {
             client: reqwest::Client::new(),
-            auth,
+            auth: Arc::new(auth),
             base_url: "https://storage.googleapis.com".to_string(),
         })
     }
 
     /// Create a new GCS client with a provided auth provider.
-    pub fn with_auth(auth: AuthProvider) -> Self {
+    ///
+    /// The provider is shared, so handlers can reuse the same instance (and its
+    /// token cache) for their own API calls.
+    pub fn with_auth(auth: Arc<AuthProvider>) -> Self {
         Self {
             client: reqwest::Client::new(),
             auth,
@@ -77,9 +173,26 @@ impl GcsClient {
         }
     }
 
+    /// Create a new GCS client that sends requests through `client`.
+    ///
+    /// Handlers pass their shared client (see [`crate::http`]) so storage
+    /// calls use the same proxy, timeout, and TLS settings as API calls.
+    pub fn with_client(auth: Arc<AuthProvider>, client: reqwest::Client) -> Self {
+        Self {
+            client,
+            auth,
+            base_url: "https://storage.googleapis.com".to_string(),
+        }
+    }
+
+    /// The auth provider used for storage requests.
+    pub fn auth(&self) -> &Arc<AuthProvider> {
+        &self.auth
+    }
+
     /// Create a new GCS client with custom base URL (for testing).
-    #[cfg(test)]
-    pub fn with_base_url(auth: AuthProvider, base_url: String) -> Self {
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn with_base_url(auth: Arc<AuthProvider>, base_url: String) -> Self {
         Self {
             client: reqwest::Client::new(),
             auth,
@@ -96,11 +209,67 @@ impl GcsClient {
     ///
     /// # Errors
     /// Returns `GcsError::OperationFailed` if the upload fails.
+    #[instrument(
+        level = "info",
+        name = "gcs_upload",
+        skip(self, data),
+        fields(uri = %uri, bytes = data.len(), duration_ms = field::Empty)
+    )]
     pub async fn upload(
         &self,
         uri: &GcsUri,
         data: &[u8],
         content_type: &str,
+    ) -> Result<(), GcsError> {
+        let _timer = SpanTimer::start();
+        self.send_upload(uri, data.to_vec().into(), data.len() as u64, content_type)
+            .await
+    }
+
+    /// Upload a local file to GCS, streaming it from disk.
+    ///
+    /// Unlike [`GcsClient::upload`], the file is never read into memory as a
+    /// whole, so large media can be uploaded without buffering it.
+    ///
+    /// # Returns
+    /// The number of bytes uploaded.
+    ///
+    /// # Errors
+    /// Returns `GcsError::OperationFailed` if the file cannot be opened or the
+    /// upload fails.
+    #[instrument(
+        level = "info",
+        name = "gcs_upload",
+        skip(self, path),
+        fields(uri = %uri, path = %path.display(), bytes = field::Empty, duration_ms = field::Empty)
+    )]
+    pub async fn upload_file(
+        &self,
+        uri: &GcsUri,
+        path: &Path,
+        content_type: &str,
+    ) -> Result<u64, GcsError> {
+        let _timer = SpanTimer::start();
+        let failed = |e: std::io::Error| GcsError::OperationFailed {
+            uri: uri.to_string(),
+            operation: GcsOperation::Upload,
+            message: format!("Failed to read '{}': {}", path.display(), e),
+        };
+        let file = tokio::fs::File::open(path).await.map_err(failed)?;
+        let len = file.metadata().await.map_err(failed)?.len();
+        Span::current().record("bytes", len);
+
+        self.send_upload(uri, file.into(), len, content_type).await?;
+        Ok(len)
+    }
+
+    /// Send a media upload request with `body` and check its status.
+    async fn send_upload(
+        &self,
+        uri: &GcsUri,
+        body: reqwest::Body,
+        len: u64,
+        content_type: &str,
     ) -> Result<(), GcsError> {
         let token = self
             .auth
@@ -120,7 +289,8 @@ impl GcsClient {
             .post(&url)
             .header("Authorization", format!("Bearer {}", token))
             .header("Content-Type", content_type)
-            .body(data.to_vec())
+            .header("Content-Length", len)
+            .body(body)
             .send()
             .await
             .map_err(|e| GcsError::OperationFailed {
@@ -149,18 +319,98 @@ impl GcsClient {
     ///
     /// # Errors
     /// Returns `GcsError::OperationFailed` if the download fails.
+    #[instrument(
+        level = "info",
+        name = "gcs_download",
+        skip(self),
+        fields(uri = %uri, bytes = field::Empty, duration_ms = field::Empty)
+    )]
     pub async fn download(&self, uri: &GcsUri) -> Result<Vec<u8>, GcsError> {
+        let _timer = SpanTimer::start();
+        let response = self.download_response(uri).await?;
+
+        let data = response.bytes().await.map(|b| b.to_vec()).map_err(|e| {
+            GcsError::OperationFailed {
+                uri: uri.to_string(),
+                operation: GcsOperation::Download,
+                message: format!("Failed to read response body: {}", e),
+            }
+        })?;
+        Span::current().record("bytes", data.len());
+        Ok(data)
+    }
+
+    /// Stream an object from GCS into `writer` chunk by chunk.
+    ///
+    /// Unlike [`GcsClient::download`], the object is never held in memory as a
+    /// whole, so large outputs (e.g. Veo videos) can be written straight to a
+    /// file. The writer is flushed before returning.
+    ///
+    /// # Arguments
+    /// * `uri` - The GCS URI to download from
+    /// * `writer` - Destination for the object bytes
+    ///
+    /// # Returns
+    /// The number of bytes written.
+    ///
+    /// # Errors
+    /// Returns `GcsError::OperationFailed` if the download or a write fails.
+    /// Bytes already written are left in `writer`.
+    #[instrument(
+        level = "info",
+        name = "gcs_download",
+        skip(self, writer),
+        fields(uri = %uri, bytes = field::Empty, duration_ms = field::Empty)
+    )]
+    pub async fn download_to_writer<W>(&self, uri: &GcsUri, writer: &mut W) -> Result<u64, GcsError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let _timer = SpanTimer::start();
+        let mut response = self.download_response(uri).await?;
+        let failed = |message: String| GcsError::OperationFailed {
+            uri: uri.to_string(),
+            operation: GcsOperation::Download,
+            message,
+        };
+
+        let mut written = 0u64;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| failed(format!("Failed to read response body: {}", e)))?
+        {
+            writer
+                .write_all(&chunk)
+                .await
+                .map_err(|e| failed(format!("Failed to write downloaded data: {}", e)))?;
+            written += chunk.len() as u64;
+        }
+        writer
+            .flush()
+            .await
+            .map_err(|e| failed(format!("Failed to write downloaded data: {}", e)))?;
+
+        Span::current().record("bytes", written);
+        Ok(written)
+    }
+
+    /// Send the media download request and check its status.
+    async fn download_response(&self, uri: &GcsUri) -> Result<reqwest::Response, GcsError> {
         let token = self
             .auth
             .get_token(&["https://www.googleapis.com/auth/devstorage.read_only"])
             .await
             .map_err(|e| GcsError::AuthError(e.to_string()))?;
 
-        let url = format!(
-            "{}/storage/v1/b/{}/o/{}?alt=media",
-            self.base_url,
-            uri.bucket,
-            urlencoding::encode(&uri.object)
+        let url = with_generation(
+            format!(
+                "{}/storage/v1/b/{}/o/{}?alt=media",
+                self.base_url,
+                uri.bucket,
+                urlencoding::encode(&uri.object)
+            ),
+            uri,
         );
 
         let response = self
@@ -185,13 +435,7 @@ impl GcsClient {
             });
         }
 
-        response.bytes().await.map(|b| b.to_vec()).map_err(|e| {
-            GcsError::OperationFailed {
-                uri: uri.to_string(),
-                operation: GcsOperation::Download,
-                message: format!("Failed to read response body: {}", e),
-            }
-        })
+        Ok(response)
     }
 
     /// Check if an object exists in GCS.
@@ -208,11 +452,14 @@ impl GcsClient {
             .await
             .map_err(|e| GcsError::AuthError(e.to_string()))?;
 
-        let url = format!(
-            "{}/storage/v1/b/{}/o/{}",
-            self.base_url,
-            uri.bucket,
-            urlencoding::encode(&uri.object)
+        let url = with_generation(
+            format!(
+                "{}/storage/v1/b/{}/o/{}",
+                self.base_url,
+                uri.bucket,
+                urlencoding::encode(&uri.object)
+            ),
+            uri,
         );
 
         let response = self
@@ -240,4 +487,111 @@ impl GcsClient {
             }
         }
     }
+
+    /// Get the current generation of an object.
+    ///
+    /// The generation changes whenever the object is overwritten, so callers
+    /// can use it to tell whether a cached copy is still current. A URI that
+    /// pins a generation reads that generation's metadata.
+    ///
+    /// # Errors
+    /// Returns `GcsError::OperationFailed` if the object does not exist or the
+    /// metadata cannot be read.
+    pub async fn generation(&self, uri: &GcsUri) -> Result<u64, GcsError> {
+        let token = self
+            .auth
+            .get_token(&["https://www.googleapis.com/auth/devstorage.read_only"])
+            .await
+            .map_err(|e| GcsError::AuthError(e.to_string()))?;
+
+        let url = with_generation(
+            format!(
+                "{}/storage/v1/b/{}/o/{}",
+                self.base_url,
+                uri.bucket,
+                urlencoding::encode(&uri.object)
+            ),
+            uri,
+        );
+
+        let failed = |message: String| GcsError::OperationFailed {
+            uri: uri.to_string(),
+            operation: GcsOperation::Metadata,
+            message,
+        };
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await
+            .map_err(|e| failed(format!("Metadata request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(failed(format!("Failed with status {}: {}", status, body)));
+        }
+
+        // The JSON API encodes int64 fields such as `generation` as strings
+        let metadata: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| failed(format!("Failed to parse metadata: {}", e)))?;
+        metadata
+            .get("generation")
+            .and_then(|g| g.as_str())
+            .and_then(|g| g.parse().ok())
+            .ok_or_else(|| failed("Metadata has no generation".to_string()))
+    }
+
+    /// Delete an object from GCS.
+    ///
+    /// # Arguments
+    /// * `uri` - The GCS URI of the object to delete
+    ///
+    /// # Errors
+    /// Returns `GcsError::OperationFailed` if the delete fails.
+    pub async fn delete(&self, uri: &GcsUri) -> Result<(), GcsError> {
+        let token = self
+            .auth
+            .get_token(&["https://www.googleapis.com/auth/devstorage.read_write"])
+            .await
+            .map_err(|e| GcsError::AuthError(e.to_string()))?;
+
+        let url = with_generation(
+            format!(
+                "{}/storage/v1/b/{}/o/{}",
+                self.base_url,
+                uri.bucket,
+                urlencoding::encode(&uri.object)
+            ),
+            uri,
+        );
+
+        let response = self
+            .client
+            .delete(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await
+            .map_err(|e| GcsError::OperationFailed {
+                uri: uri.to_string(),
+                operation: GcsOperation::Delete,
+                message: format!("Delete request failed: {}", e),
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(GcsError::OperationFailed {
+                uri: uri.to_string(),
+                operation: GcsOperation::Delete,
+                message: format!("Failed with status {}: {}", status, body),
+            });
+        }
+
+        Ok(())
+    }
 }
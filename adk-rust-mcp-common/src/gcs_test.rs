@@ -5,9 +5,10 @@ use proptest::prelude::*;
 use crate::gcs::GcsUri;
 
 /// Generate valid GCS bucket names.
-/// Bucket names must be 3-63 characters, lowercase letters, numbers, hyphens, underscores.
+/// Bucket names must be 3-63 characters, lowercase letters, numbers, hyphens, underscores,
+/// starting and ending with a letter or number.
 fn bucket_name_strategy() -> impl Strategy<Value = String> {
-    "[a-z][a-z0-9_-]{2,62}".prop_filter("bucket name must be valid", |s| {
+    "[a-z][a-z0-9_-]{1,61}[a-z0-9]".prop_filter("bucket name must be valid", |s| {
         !s.is_empty() && s.len() >= 3 && s.len() <= 63
     })
 }
@@ -49,6 +50,34 @@ proptest! {
         prop_assert_eq!(formatted, original_uri, "Round-trip should preserve URI");
     }
 
+    /// Round-trip parsing preserves a `#generation` suffix.
+    #[test]
+    fn gcs_uri_round_trip_with_generation(
+        bucket in bucket_name_strategy(),
+        object in object_path_strategy(),
+        generation in any::<u64>()
+    ) {
+        let original_uri = format!("gs://{}/{}#{}", bucket, object, generation);
+
+        let parsed = GcsUri::parse(&original_uri)
+            .expect("Valid URI should parse successfully");
+
+        prop_assert_eq!(&parsed.object, &object, "Generation should not leak into the object");
+        prop_assert_eq!(parsed.generation, Some(generation));
+        prop_assert_eq!(parsed.to_string(), original_uri);
+    }
+
+    /// Bucket names with characters outside the allowed set are rejected.
+    #[test]
+    fn bucket_with_invalid_char_rejected(
+        prefix in "[a-z]{2}",
+        bad in "[A-Z!@$%^&*()+=]",
+        suffix in "[a-z]{2}"
+    ) {
+        let uri = format!("gs://{}{}{}/object", prefix, bad, suffix);
+        prop_assert!(GcsUri::parse(&uri).is_err(), "Should reject {}", uri);
+    }
+
     /// Test that invalid URIs are rejected.
     #[test]
     fn invalid_uri_rejected(uri in "[^g].*|g[^s].*|gs[^:].*") {
@@ -95,11 +124,86 @@ mod unit_tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn parse_uri_with_generation() {
+        let uri = GcsUri::parse("gs://my-bucket/videos/out.mp4#1712345678901234").unwrap();
+        assert_eq!(uri.object, "videos/out.mp4");
+        assert_eq!(uri.generation, Some(1712345678901234));
+    }
+
+    #[test]
+    fn parse_uri_hash_in_object_name() {
+        // A non-numeric fragment is part of the object name
+        let uri = GcsUri::parse("gs://my-bucket/take#2-final.mp4").unwrap();
+        assert_eq!(uri.object, "take#2-final.mp4");
+        assert_eq!(uri.generation, None);
+    }
+
+    #[test]
+    fn parse_uri_generation_out_of_range() {
+        let err = GcsUri::parse("gs://my-bucket/a.mp4#99999999999999999999999").unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{}", err);
+    }
+
+    #[test]
+    fn parse_uri_bucket_too_short() {
+        let err = GcsUri::parse("gs://ab/object").unwrap_err();
+        assert!(err.to_string().contains("3-63 characters"), "{}", err);
+    }
+
+    #[test]
+    fn parse_uri_bucket_too_long() {
+        let bucket = "a".repeat(64);
+        let err = GcsUri::parse(&format!("gs://{}/object", bucket)).unwrap_err();
+        assert!(err.to_string().contains("got 64"), "{}", err);
+    }
+
+    #[test]
+    fn parse_uri_dotted_bucket_length() {
+        // Dotted names may exceed 63 characters as long as each part fits
+        let part = "a".repeat(63);
+        let bucket = [part.as_str(), part.as_str(), part.as_str()].join(".");
+        assert_eq!(GcsUri::parse(&format!("gs://{}/object", bucket)).unwrap().bucket, bucket);
+
+        let bucket = format!("{}.{}", "a".repeat(64), "com");
+        let err = GcsUri::parse(&format!("gs://{}/object", bucket)).unwrap_err();
+        assert!(err.to_string().contains("part of 64 characters"), "{}", err);
+
+        let bucket = vec!["a".repeat(60); 4].join(".");
+        let err = GcsUri::parse(&format!("gs://{}/object", bucket)).unwrap_err();
+        assert!(err.to_string().contains("got 243"), "{}", err);
+    }
+
+    #[test]
+    fn parse_uri_bucket_uppercase() {
+        let err = GcsUri::parse("gs://My-Bucket/object").unwrap_err();
+        assert!(err.to_string().contains("invalid character 'M'"), "{}", err);
+    }
+
+    #[test]
+    fn parse_uri_bucket_leading_or_trailing_dash() {
+        for bucket in ["-bucket", "bucket-", "_bucket", "bucket."] {
+            let err = GcsUri::parse(&format!("gs://{}/object", bucket)).unwrap_err();
+            assert!(err.to_string().contains("start and end with a letter or digit"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn to_string_includes_generation() {
+        let uri = GcsUri {
+            bucket: "test-bucket".to_string(),
+            object: "file.txt".to_string(),
+            generation: Some(42),
+        };
+        assert_eq!(uri.to_string(), "gs://test-bucket/file.txt#42");
+    }
+
     #[test]
     fn to_string_formats_correctly() {
         let uri = GcsUri {
             bucket: "test-bucket".to_string(),
             object: "folder/file.txt".to_string(),
+            generation: None,
         };
         assert_eq!(uri.to_string(), "gs://test-bucket/folder/file.txt");
     }
@@ -109,7 +213,10 @@ mod unit_tests {
 /// **Validates: Requirements 2.7, 2.8, 2.10**
 #[cfg(test)]
 mod gcs_client_tests {
-    use wiremock::matchers::{header, method, path_regex};
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll};
+    use wiremock::matchers::{header, method, path_regex, query_param};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     use crate::auth::AuthProvider;
@@ -131,18 +238,51 @@ mod gcs_client_tests {
             .mount(&mock_server)
             .await;
 
-        let auth = AuthProvider::mock(TEST_TOKEN);
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
         let client = GcsClient::with_base_url(auth, mock_server.uri());
 
         let uri = GcsUri {
             bucket: "test-bucket".to_string(),
             object: "test-object.txt".to_string(),
+            generation: None,
         };
 
         let result = client.upload(&uri, b"test data", "text/plain").await;
         assert!(result.is_ok(), "Upload should succeed: {:?}", result);
     }
 
+    #[tokio::test]
+    async fn upload_file_streams_file_contents() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path_regex(r"/upload/storage/v1/b/.*/o.*"))
+            .and(header("Content-Type", "video/mp4"))
+            .and(header("Content-Length", "10"))
+            .and(wiremock::matchers::body_bytes(b"video data".to_vec()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
+        let client = GcsClient::with_base_url(auth, mock_server.uri());
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("clip.mp4");
+        std::fs::write(&file, b"video data").unwrap();
+        let uri = GcsUri::parse("gs://test-bucket/clip.mp4").unwrap();
+
+        let bytes = client.upload_file(&uri, &file, "video/mp4").await.unwrap();
+        assert_eq!(bytes, 10);
+
+        let missing = client
+            .upload_file(&uri, &dir.path().join("missing.mp4"), "video/mp4")
+            .await
+            .unwrap_err();
+        assert!(missing.to_string().contains("missing.mp4"), "{}", missing);
+    }
+
     #[tokio::test]
     async fn upload_failure_returns_error() {
         let mock_server = MockServer::start().await;
@@ -153,12 +293,13 @@ mod gcs_client_tests {
             .mount(&mock_server)
             .await;
 
-        let auth = AuthProvider::mock(TEST_TOKEN);
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
         let client = GcsClient::with_base_url(auth, mock_server.uri());
 
         let uri = GcsUri {
             bucket: "test-bucket".to_string(),
             object: "test-object.txt".to_string(),
+            generation: None,
         };
 
         let result = client.upload(&uri, b"test data", "text/plain").await;
@@ -185,12 +326,13 @@ mod gcs_client_tests {
             .mount(&mock_server)
             .await;
 
-        let auth = AuthProvider::mock(TEST_TOKEN);
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
         let client = GcsClient::with_base_url(auth, mock_server.uri());
 
         let uri = GcsUri {
             bucket: "test-bucket".to_string(),
             object: "test-object.txt".to_string(),
+            generation: None,
         };
 
         let result = client.download(&uri).await;
@@ -208,12 +350,13 @@ mod gcs_client_tests {
             .mount(&mock_server)
             .await;
 
-        let auth = AuthProvider::mock(TEST_TOKEN);
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
         let client = GcsClient::with_base_url(auth, mock_server.uri());
 
         let uri = GcsUri {
             bucket: "test-bucket".to_string(),
             object: "nonexistent.txt".to_string(),
+            generation: None,
         };
 
         let result = client.download(&uri).await;
@@ -228,6 +371,116 @@ mod gcs_client_tests {
         );
     }
 
+    /// Writer that records how much data arrives per write instead of keeping it.
+    #[derive(Default)]
+    struct CountingWriter {
+        total: usize,
+        largest_write: usize,
+        writes: usize,
+        checksum: u64,
+    }
+
+    impl tokio::io::AsyncWrite for CountingWriter {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            self.total += buf.len();
+            self.largest_write = self.largest_write.max(buf.len());
+            self.writes += 1;
+            self.checksum = buf.iter().fold(self.checksum, |acc, b| acc.wrapping_mul(31).wrapping_add(u64::from(*b)));
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn download_to_writer_streams_large_object_in_chunks() {
+        let mock_server = MockServer::start().await;
+        let size = 8 * 1024 * 1024;
+        let test_data: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+        let expected_checksum = test_data
+            .iter()
+            .fold(0u64, |acc, b| acc.wrapping_mul(31).wrapping_add(u64::from(*b)));
+
+        Mock::given(method("GET"))
+            .and(path_regex(r"/storage/v1/b/.*/o/.*"))
+            .and(query_param("alt", "media"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(test_data))
+            .mount(&mock_server)
+            .await;
+
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
+        let client = GcsClient::with_base_url(auth, mock_server.uri());
+        let uri = GcsUri::parse("gs://test-bucket/large.mp4").unwrap();
+
+        let mut writer = CountingWriter::default();
+        let written = client.download_to_writer(&uri, &mut writer).await.unwrap();
+
+        assert_eq!(written, size as u64);
+        assert_eq!(writer.total, size);
+        assert_eq!(writer.checksum, expected_checksum);
+        assert!(writer.writes > 1, "object should arrive in several chunks");
+        assert!(
+            writer.largest_write < size / 4,
+            "no single write should hold a large share of the object (largest {} bytes)",
+            writer.largest_write
+        );
+    }
+
+    #[tokio::test]
+    async fn download_to_writer_writes_file() {
+        let mock_server = MockServer::start().await;
+        let test_data = vec![7u8; 3 * 1024 * 1024];
+
+        Mock::given(method("GET"))
+            .and(path_regex(r"/storage/v1/b/.*/o/.*"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(test_data.clone()))
+            .mount(&mock_server)
+            .await;
+
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
+        let client = GcsClient::with_base_url(auth, mock_server.uri());
+        let uri = GcsUri::parse("gs://test-bucket/video.mp4").unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("video.mp4");
+        let mut file = tokio::fs::File::create(&file_path).await.unwrap();
+        let written = client.download_to_writer(&uri, &mut file).await.unwrap();
+
+        assert_eq!(written, test_data.len() as u64);
+        assert_eq!(std::fs::read(&file_path).unwrap(), test_data);
+    }
+
+    #[tokio::test]
+    async fn download_to_writer_failure_writes_nothing() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex(r"/storage/v1/b/.*/o/.*"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Not found"))
+            .mount(&mock_server)
+            .await;
+
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
+        let client = GcsClient::with_base_url(auth, mock_server.uri());
+        let uri = GcsUri::parse("gs://test-bucket/missing.mp4").unwrap();
+
+        let mut writer = CountingWriter::default();
+        let err = client.download_to_writer(&uri, &mut writer).await.unwrap_err();
+
+        assert!(err.to_string().contains("404"), "{}", err);
+        assert_eq!(writer.total, 0);
+    }
+
     #[tokio::test]
     async fn exists_returns_true_when_object_exists() {
         let mock_server = MockServer::start().await;
@@ -242,12 +495,13 @@ mod gcs_client_tests {
             .mount(&mock_server)
             .await;
 
-        let auth = AuthProvider::mock(TEST_TOKEN);
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
         let client = GcsClient::with_base_url(auth, mock_server.uri());
 
         let uri = GcsUri {
             bucket: "test-bucket".to_string(),
             object: "test-object.txt".to_string(),
+            generation: None,
         };
 
         let result = client.exists(&uri).await;
@@ -265,12 +519,13 @@ mod gcs_client_tests {
             .mount(&mock_server)
             .await;
 
-        let auth = AuthProvider::mock(TEST_TOKEN);
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
         let client = GcsClient::with_base_url(auth, mock_server.uri());
 
         let uri = GcsUri {
             bucket: "test-bucket".to_string(),
             object: "nonexistent.txt".to_string(),
+            generation: None,
         };
 
         let result = client.exists(&uri).await;
@@ -288,15 +543,137 @@ mod gcs_client_tests {
             .mount(&mock_server)
             .await;
 
-        let auth = AuthProvider::mock(TEST_TOKEN);
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
         let client = GcsClient::with_base_url(auth, mock_server.uri());
 
         let uri = GcsUri {
             bucket: "test-bucket".to_string(),
             object: "test-object.txt".to_string(),
+            generation: None,
         };
 
         let result = client.exists(&uri).await;
         assert!(result.is_err(), "Exists check should fail on server error");
     }
+
+    #[tokio::test]
+    async fn generation_reads_object_metadata() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex(r"/storage/v1/b/test-bucket/o/[^?]+$"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "name": "lexicon.json",
+                "generation": "1712345678901234"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
+        let client = GcsClient::with_base_url(auth, mock_server.uri());
+
+        let uri = GcsUri::parse("gs://test-bucket/lexicon.json").unwrap();
+        assert_eq!(client.generation(&uri).await.unwrap(), 1712345678901234);
+    }
+
+    #[tokio::test]
+    async fn generation_fails_for_missing_object() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex(r"/storage/v1/b/test-bucket/o/.*"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("No such object"))
+            .mount(&mock_server)
+            .await;
+
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
+        let client = GcsClient::with_base_url(auth, mock_server.uri());
+
+        let uri = GcsUri::parse("gs://test-bucket/missing.json").unwrap();
+        let err = client.generation(&uri).await.unwrap_err();
+        assert!(err.to_string().contains("404"), "{}", err);
+        assert!(err.to_string().contains("metadata"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn download_pins_generation() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path_regex(r"/storage/v1/b/test-bucket/o/.*"))
+            .and(query_param("generation", "7"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"v7".to_vec()))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
+        let client = GcsClient::with_base_url(auth, mock_server.uri());
+
+        let uri = GcsUri::parse("gs://test-bucket/file.txt#7").unwrap();
+        let data = client.download(&uri).await.expect("Download should succeed");
+        assert_eq!(data, b"v7".to_vec());
+    }
+
+    #[tokio::test]
+    async fn delete_success() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("DELETE"))
+            .and(path_regex(r"/storage/v1/b/test-bucket/o/.*"))
+            .and(header("Authorization", format!("Bearer {}", TEST_TOKEN)))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
+        let client = GcsClient::with_base_url(auth, mock_server.uri());
+
+        let uri = GcsUri {
+            bucket: "test-bucket".to_string(),
+            object: "videos/output.mp4".to_string(),
+            generation: None,
+        };
+
+        let result = client.delete(&uri).await;
+        assert!(result.is_ok(), "Delete should succeed: {:?}", result);
+    }
+
+    #[tokio::test]
+    async fn delete_failure_returns_error() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("DELETE"))
+            .and(path_regex(r"/storage/v1/b/.*/o/.*"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("Access denied"))
+            .mount(&mock_server)
+            .await;
+
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
+        let client = GcsClient::with_base_url(auth, mock_server.uri());
+
+        let uri = GcsUri {
+            bucket: "test-bucket".to_string(),
+            object: "output.mp4".to_string(),
+            generation: None,
+        };
+
+        let result = client.delete(&uri).await;
+        assert!(result.is_err(), "Delete should fail");
+
+        let err_msg = result.unwrap_err().to_string();
+        assert!(
+            err_msg.contains("403") && err_msg.contains("delete") && err_msg.contains("gs://test-bucket/output.mp4"),
+            "Error should include status, operation and URI: {}",
+            err_msg
+        );
+    }
+
+    #[test]
+    fn with_auth_shares_provider() {
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
+        let _client = GcsClient::with_auth(Arc::clone(&auth));
+        assert_eq!(Arc::strong_count(&auth), 2, "GcsClient should hold the shared provider");
+    }
 }
@@ -0,0 +1,148 @@
+//! Shared HTTP client construction.
+//!
+//! Every server talks to Vertex AI and Cloud Storage through a
+//! `reqwest::Client` built here, so proxy, timeout, and TLS settings apply
+//! uniformly. Settings come from the environment:
+//!
+//! - `HTTPS_PROXY` (or `https_proxy`): proxy for all outbound requests;
+//!   hosts in `NO_PROXY` bypass it
+//! - `HTTP_CONNECT_TIMEOUT_SECS`: connection timeout (default 30)
+//! - `HTTP_READ_TIMEOUT_SECS`: timeout between reads of a response body
+//!   (default 300, long enough for slow generation responses)
+//! - `HTTP_CA_BUNDLE`: PEM file with extra root certificates, e.g. for a
+//!   corporate proxy that intercepts TLS
+
+use crate::error::{ConfigError, Error};
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::debug;
+
+/// Environment variable naming the outbound proxy.
+pub const HTTPS_PROXY_ENV: &str = "HTTPS_PROXY";
+
+/// Environment variable for the connection timeout in seconds.
+pub const HTTP_CONNECT_TIMEOUT_ENV: &str = "HTTP_CONNECT_TIMEOUT_SECS";
+
+/// Environment variable for the read timeout in seconds.
+pub const HTTP_READ_TIMEOUT_ENV: &str = "HTTP_READ_TIMEOUT_SECS";
+
+/// Environment variable naming a PEM bundle of extra root certificates.
+pub const HTTP_CA_BUNDLE_ENV: &str = "HTTP_CA_BUNDLE";
+
+/// Default connection timeout.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default read timeout.
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Settings for the shared HTTP client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpClientConfig {
+    /// Proxy URL for all requests, if any
+    pub proxy: Option<String>,
+    /// Time allowed to establish a connection
+    pub connect_timeout: Duration,
+    /// Time allowed between reads of a response
+    pub read_timeout: Duration,
+    /// PEM bundle with extra trusted root certificates
+    pub ca_bundle: Option<PathBuf>,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            ca_bundle: None,
+        }
+    }
+}
+
+impl HttpClientConfig {
+    /// Load settings from the environment.
+    ///
+    /// # Errors
+    /// Returns `ConfigError::InvalidValue` if a timeout is not a positive
+    /// number of seconds.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Load settings through `lookup`, which returns the value of a variable.
+    ///
+    /// # Errors
+    /// Returns `ConfigError::InvalidValue` if a timeout is not a positive
+    /// number of seconds.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let value = |name: &str| lookup(name).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+        let proxy = value(HTTPS_PROXY_ENV).or_else(|| value(&HTTPS_PROXY_ENV.to_ascii_lowercase()));
+
+        let seconds = |name: &str, default: Duration| match value(name) {
+            None => Ok(default),
+            Some(v) => v
+                .parse::<u64>()
+                .ok()
+                .filter(|&s| s > 0)
+                .map(Duration::from_secs)
+                .ok_or_else(|| ConfigError::invalid_value(name, format!("expected a positive number of seconds, got '{}'", v))),
+        };
+
+        Ok(Self {
+            proxy,
+            connect_timeout: seconds(HTTP_CONNECT_TIMEOUT_ENV, DEFAULT_CONNECT_TIMEOUT)?,
+            read_timeout: seconds(HTTP_READ_TIMEOUT_ENV, DEFAULT_READ_TIMEOUT)?,
+            ca_bundle: value(HTTP_CA_BUNDLE_ENV).map(PathBuf::from),
+        })
+    }
+
+    /// Build a client with these settings.
+    ///
+    /// # Errors
+    /// Returns `Error::Config` if the proxy URL is invalid or the CA bundle
+    /// cannot be read or contains no usable certificates.
+    pub fn build(&self) -> Result<reqwest::Client, Error> {
+        // Nothing the servers call needs a redirect, and following one would
+        // let a fetched image URL bounce into the private network.
+        let mut builder = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .connect_timeout(self.connect_timeout)
+            .read_timeout(self.read_timeout);
+
+        if let Some(proxy_url) = &self.proxy {
+            let proxy = reqwest::Proxy::all(proxy_url)
+                .map_err(|e| ConfigError::invalid_value(HTTPS_PROXY_ENV, e.to_string()))?
+                .no_proxy(reqwest::NoProxy::from_env());
+            debug!(proxy = %proxy_url, "Routing HTTP requests through proxy");
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(path) = &self.ca_bundle {
+            let invalid = |reason: String| ConfigError::invalid_value(HTTP_CA_BUNDLE_ENV, reason);
+            let pem = std::fs::read(path)
+                .map_err(|e| invalid(format!("cannot read {}: {}", path.display(), e)))?;
+            let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+                .map_err(|e| invalid(format!("{}: {}", path.display(), e)))?;
+            if certificates.is_empty() {
+                return Err(invalid(format!("{} contains no certificates", path.display())).into());
+            }
+            debug!(path = %path.display(), count = certificates.len(), "Trusting extra root certificates");
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        builder
+            .build()
+            .map_err(|e| Error::Config(ConfigError::invalid_value("HTTP client", e.to_string())))
+    }
+}
+
+/// Build the shared HTTP client from environment settings.
+///
+/// # Errors
+/// Returns `Error::Config` if the settings are invalid.
+pub fn client_from_env() -> Result<reqwest::Client, Error> {
+    HttpClientConfig::from_env()?.build()
+}
@@ -0,0 +1,113 @@
+//! Unit tests for the shared HTTP client module.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::error::{ConfigError, Error};
+use crate::http::{
+    DEFAULT_CONNECT_TIMEOUT, DEFAULT_READ_TIMEOUT, HTTP_CA_BUNDLE_ENV, HTTP_CONNECT_TIMEOUT_ENV,
+    HTTP_READ_TIMEOUT_ENV, HTTPS_PROXY_ENV, HttpClientConfig,
+};
+
+fn config_from(vars: &[(&str, &str)]) -> Result<HttpClientConfig, ConfigError> {
+    let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    HttpClientConfig::from_lookup(|name| vars.get(name).cloned())
+}
+
+#[test]
+fn defaults_without_environment() {
+    let config = config_from(&[]).unwrap();
+    assert_eq!(config, HttpClientConfig::default());
+    assert_eq!(config.connect_timeout, DEFAULT_CONNECT_TIMEOUT);
+    assert_eq!(config.read_timeout, DEFAULT_READ_TIMEOUT);
+    assert!(config.proxy.is_none());
+    assert!(config.ca_bundle.is_none());
+}
+
+#[test]
+fn reads_all_settings() {
+    let config = config_from(&[
+        (HTTPS_PROXY_ENV, "http://proxy.corp:3128"),
+        (HTTP_CONNECT_TIMEOUT_ENV, "5"),
+        (HTTP_READ_TIMEOUT_ENV, " 90 "),
+        (HTTP_CA_BUNDLE_ENV, "/etc/ssl/corp.pem"),
+    ])
+    .unwrap();
+
+    assert_eq!(config.proxy.as_deref(), Some("http://proxy.corp:3128"));
+    assert_eq!(config.connect_timeout, Duration::from_secs(5));
+    assert_eq!(config.read_timeout, Duration::from_secs(90));
+    assert_eq!(config.ca_bundle, Some(PathBuf::from("/etc/ssl/corp.pem")));
+}
+
+#[test]
+fn lowercase_proxy_variable_is_honored() {
+    let config = config_from(&[("https_proxy", "http://proxy.corp:3128")]).unwrap();
+    assert_eq!(config.proxy.as_deref(), Some("http://proxy.corp:3128"));
+
+    let config = config_from(&[(HTTPS_PROXY_ENV, "http://upper:1"), ("https_proxy", "http://lower:2")]).unwrap();
+    assert_eq!(config.proxy.as_deref(), Some("http://upper:1"));
+}
+
+#[test]
+fn rejects_invalid_timeouts() {
+    for value in ["0", "-1", "soon", "1.5"] {
+        let err = config_from(&[(HTTP_READ_TIMEOUT_ENV, value)]).unwrap_err();
+        assert!(
+            matches!(err, ConfigError::InvalidValue(ref name, _) if name == HTTP_READ_TIMEOUT_ENV),
+            "{value}: {err}"
+        );
+    }
+}
+
+#[test]
+fn builds_default_client() {
+    assert!(HttpClientConfig::default().build().is_ok());
+}
+
+#[test]
+fn rejects_missing_or_empty_ca_bundle() {
+    let missing = HttpClientConfig {
+        ca_bundle: Some(PathBuf::from("/nonexistent/corp.pem")),
+        ..HttpClientConfig::default()
+    };
+    let err = missing.build().unwrap_err();
+    assert!(matches!(err, Error::Config(_)), "{err}");
+    assert!(err.to_string().contains(HTTP_CA_BUNDLE_ENV), "{err}");
+
+    let dir = tempfile::tempdir().unwrap();
+    let empty = dir.path().join("empty.pem");
+    std::fs::write(&empty, "no certificates here\n").unwrap();
+    let config = HttpClientConfig {
+        ca_bundle: Some(empty),
+        ..HttpClientConfig::default()
+    };
+    let err = config.build().unwrap_err();
+    assert!(err.to_string().contains("no certificates"), "{err}");
+}
+
+#[tokio::test]
+async fn routes_requests_through_proxy() {
+    // A plain HTTP request to an unresolvable host only succeeds via the proxy
+    let proxy = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ping"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("via proxy"))
+        .expect(1)
+        .mount(&proxy)
+        .await;
+
+    let client = HttpClientConfig {
+        proxy: Some(proxy.uri()),
+        ..HttpClientConfig::default()
+    }
+    .build()
+    .unwrap();
+
+    let response = client.get("http://upstream.invalid/ping").send().await.unwrap();
+    assert_eq!(response.text().await.unwrap(), "via proxy");
+}
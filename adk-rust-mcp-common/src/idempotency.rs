@@ -0,0 +1,300 @@
+//! Idempotency keys for generation tool calls.
+//!
+//! MCP clients that deliver tool calls at least once may resend a generation
+//! request after a dropped connection, and each resend is a separately billed
+//! Vertex AI call. A client that sets `idempotency_key` on a generation tool
+//! gets the first call's result back for every repeat of that key.
+//!
+//! Results are held in memory per process, in an LRU with a time-to-live: a
+//! key is forgotten after [`IdempotencyStore::ttl`] (default one hour) or once
+//! newer keys push it out, and the next call with it runs again. Recorded
+//! results also share a byte budget, since generation results can carry inline
+//! media; a result larger than the whole budget is not recorded. Failed calls
+//! are not recorded, so they can be retried under the same key. A repeat that
+//! arrives while the first call is still running waits for its result.
+
+use crate::cache::cache_key;
+use crate::config::Config;
+use crate::error::{Error, ErrorCode};
+use rmcp::ErrorData as McpError;
+use rmcp::model::CallToolResult;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+use tracing::info;
+
+/// Tool argument carrying the idempotency key.
+pub const IDEMPOTENCY_KEY_ARG: &str = "idempotency_key";
+
+/// Environment variable overriding how long results are kept, in seconds.
+pub const IDEMPOTENCY_TTL_ENV: &str = "IDEMPOTENCY_TTL_SECS";
+
+/// Environment variable overriding how many keys are kept.
+pub const IDEMPOTENCY_CAPACITY_ENV: &str = "IDEMPOTENCY_CAPACITY";
+
+/// Default time a result is kept.
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(3600);
+
+/// Environment variable overriding the total size of recorded results, in bytes.
+pub const IDEMPOTENCY_MAX_BYTES_ENV: &str = "IDEMPOTENCY_MAX_BYTES";
+
+/// Default number of keys kept.
+pub const DEFAULT_IDEMPOTENCY_CAPACITY: usize = 256;
+
+/// Default total size of recorded results (64 MiB).
+pub const DEFAULT_IDEMPOTENCY_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+struct Entry {
+    /// Fingerprint of the arguments the key was first used with
+    fingerprint: String,
+    created: Instant,
+    last_used: Instant,
+    /// Serialized size of the recorded result; 0 while the call is running
+    bytes: usize,
+    result: Arc<OnceCell<CallToolResult>>,
+}
+
+/// In-memory record of tool results by idempotency key.
+pub struct IdempotencyStore {
+    ttl: Duration,
+    capacity: usize,
+    max_bytes: usize,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl std::fmt::Debug for IdempotencyStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdempotencyStore")
+            .field("ttl", &self.ttl)
+            .field("capacity", &self.capacity)
+            .field("max_bytes", &self.max_bytes)
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl Default for IdempotencyStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_IDEMPOTENCY_CAPACITY, DEFAULT_IDEMPOTENCY_TTL)
+    }
+}
+
+impl IdempotencyStore {
+    /// Create a store keeping up to `capacity` keys for `ttl` each, within
+    /// [`DEFAULT_IDEMPOTENCY_MAX_BYTES`].
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            ttl,
+            capacity: capacity.max(1),
+            max_bytes: DEFAULT_IDEMPOTENCY_MAX_BYTES,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Limit the total serialized size of recorded results.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Create a store configured by `IDEMPOTENCY_TTL_SECS`, `IDEMPOTENCY_CAPACITY`
+    /// and `IDEMPOTENCY_MAX_BYTES`.
+    ///
+    /// Missing or invalid values fall back to the defaults.
+    pub fn from_env() -> Self {
+        let positive = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .filter(|&n| n > 0)
+        };
+        Self::new(
+            positive(IDEMPOTENCY_CAPACITY_ENV).map_or(DEFAULT_IDEMPOTENCY_CAPACITY, |n| n as usize),
+            positive(IDEMPOTENCY_TTL_ENV).map_or(DEFAULT_IDEMPOTENCY_TTL, Duration::from_secs),
+        )
+        .with_max_bytes(positive(IDEMPOTENCY_MAX_BYTES_ENV).map_or(DEFAULT_IDEMPOTENCY_MAX_BYTES, |n| n as usize))
+    }
+
+    /// Create a store with the limits from `config`, which reads the same
+    /// variables as [`IdempotencyStore::from_env`] and the `[idempotency]`
+    /// section of the configuration file.
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            config.idempotency_capacity.unwrap_or(DEFAULT_IDEMPOTENCY_CAPACITY),
+            config.idempotency_ttl.unwrap_or(DEFAULT_IDEMPOTENCY_TTL),
+        )
+        .with_max_bytes(config.idempotency_max_bytes.unwrap_or(DEFAULT_IDEMPOTENCY_MAX_BYTES))
+    }
+
+    /// How long a result is kept after the first call.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Number of keys currently held, including expired ones not yet purged.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Whether no keys are held.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Total serialized size of the recorded results.
+    pub fn bytes(&self) -> usize {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.values().map(|entry| entry.bytes).sum()
+    }
+
+    /// Run a tool call at most once per idempotency key.
+    ///
+    /// Without an `idempotency_key` in `arguments`, `call` simply runs. With
+    /// one, the first successful result for `tool` and that key is recorded
+    /// and returned for repeats until it expires.
+    ///
+    /// # Errors
+    /// Returns an invalid-params error if the key was already used with
+    /// different arguments, and otherwise whatever `call` returns.
+    pub async fn run<F>(&self, tool: &str, arguments: &Map<String, Value>, call: F) -> Result<CallToolResult, McpError>
+    where
+        F: Future<Output = Result<CallToolResult, McpError>>,
+    {
+        let Some(key) = arguments.get(IDEMPOTENCY_KEY_ARG).and_then(Value::as_str) else {
+            return call.await;
+        };
+
+        let fingerprint = request_fingerprint(tool, arguments).map_err(|e| e.to_mcp_error("Invalid arguments"))?;
+        let slot = self.slot(tool, key, &fingerprint).map_err(|e| {
+            McpError::invalid_params(e.to_string(), Some(ErrorCode::Validation.to_data()))
+        })?;
+
+        if slot.initialized() {
+            info!(tool, idempotency_key = key, "Returning recorded result for idempotency key");
+            return slot.get_or_try_init(|| call).await.cloned();
+        }
+        let result = slot.get_or_try_init(|| call).await?.clone();
+        self.record_size(tool, key, &slot, serialized_len(&result));
+        Ok(result)
+    }
+
+    /// Charge a finished result against the byte budget.
+    ///
+    /// Least recently used results are dropped until the total fits; a result
+    /// that alone exceeds the budget is dropped instead, so repeats of its key
+    /// run again.
+    fn record_size(&self, tool: &str, key: &str, slot: &Arc<OnceCell<CallToolResult>>, bytes: usize) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let scoped = scoped_key(tool, key);
+        // The key may have expired or been replaced while the call ran
+        match entries.get_mut(&scoped) {
+            Some(entry) if Arc::ptr_eq(&entry.result, slot) => entry.bytes = bytes,
+            _ => return,
+        }
+
+        if bytes > self.max_bytes {
+            entries.remove(&scoped);
+            info!(tool, idempotency_key = key, bytes, "Result too large to record for idempotency key");
+            return;
+        }
+
+        let mut total: usize = entries.values().map(|entry| entry.bytes).sum();
+        while total > self.max_bytes {
+            let Some((oldest, freed)) = entries
+                .iter()
+                .filter(|(k, entry)| entry.bytes > 0 && **k != scoped)
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(k, entry)| (k.clone(), entry.bytes))
+            else {
+                break;
+            };
+            entries.remove(&oldest);
+            total -= freed;
+        }
+    }
+
+    /// The result cell for `key`, creating it if the key is new or expired.
+    ///
+    /// # Errors
+    /// Returns `Error::Validation` if the key is live but was first used with
+    /// a different fingerprint.
+    pub fn slot(&self, tool: &str, key: &str, fingerprint: &str) -> Result<Arc<OnceCell<CallToolResult>>, Error> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        entries.retain(|_, entry| now.duration_since(entry.created) < self.ttl);
+
+        let scoped = scoped_key(tool, key);
+        if let Some(entry) = entries.get_mut(&scoped) {
+            if entry.fingerprint != fingerprint {
+                return Err(Error::validation(format!(
+                    "idempotency_key '{}' was already used for a {} call with different arguments",
+                    key, tool
+                )));
+            }
+            entry.last_used = now;
+            return Ok(Arc::clone(&entry.result));
+        }
+
+        if entries.len() >= self.capacity {
+            if let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(k, _)| k.clone())
+            {
+                entries.remove(&oldest);
+            }
+        }
+
+        let result = Arc::new(OnceCell::new());
+        entries.insert(
+            scoped,
+            Entry {
+                fingerprint: fingerprint.to_string(),
+                created: now,
+                last_used: now,
+                bytes: 0,
+                result: Arc::clone(&result),
+            },
+        );
+        Ok(result)
+    }
+}
+
+/// Map key for `key` used with `tool`.
+fn scoped_key(tool: &str, key: &str) -> String {
+    format!("{}\n{}", tool, key)
+}
+
+/// Size of `result` as JSON, counted without buffering it.
+fn serialized_len(result: &CallToolResult) -> usize {
+    struct Counter(usize);
+
+    impl std::io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    // Serializing a CallToolResult cannot fail; a partial count is still an estimate
+    let _ = serde_json::to_writer(&mut counter, result);
+    counter.0
+}
+
+/// Fingerprint of a tool call's arguments, ignoring the idempotency key.
+///
+/// # Errors
+/// Returns `Error::Validation` if the arguments cannot be serialized.
+pub fn request_fingerprint(tool: &str, arguments: &Map<String, Value>) -> Result<String, Error> {
+    let mut arguments = arguments.clone();
+    arguments.remove(IDEMPOTENCY_KEY_ARG);
+    cache_key(tool, &arguments)
+}
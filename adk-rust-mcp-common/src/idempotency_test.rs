@@ -0,0 +1,211 @@
+//! Unit tests for the idempotency module.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use rmcp::ErrorData as McpError;
+use rmcp::model::{CallToolResult, Content};
+use serde_json::{Map, Value, json};
+
+use crate::idempotency::{IdempotencyStore, request_fingerprint};
+
+fn args(value: Value) -> Map<String, Value> {
+    value.as_object().cloned().unwrap()
+}
+
+/// A tool call that counts how often it runs and reports the run number.
+async fn counted_call(calls: &AtomicUsize) -> Result<CallToolResult, McpError> {
+    let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+    Ok(CallToolResult::success(vec![Content::text(format!("run {}", n))]))
+}
+
+fn text(result: &CallToolResult) -> String {
+    result.content[0].as_text().unwrap().text.clone()
+}
+
+#[tokio::test]
+async fn runs_every_call_without_key() {
+    let store = IdempotencyStore::default();
+    let calls = AtomicUsize::new(0);
+    let arguments = args(json!({"prompt": "A cat"}));
+
+    store.run("image_generate", &arguments, counted_call(&calls)).await.unwrap();
+    store.run("image_generate", &arguments, counted_call(&calls)).await.unwrap();
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert!(store.is_empty());
+}
+
+#[tokio::test]
+async fn repeated_key_returns_first_result() {
+    let store = IdempotencyStore::default();
+    let calls = AtomicUsize::new(0);
+    let arguments = args(json!({"prompt": "A cat", "idempotency_key": "req-1"}));
+
+    let first = store.run("image_generate", &arguments, counted_call(&calls)).await.unwrap();
+    let second = store.run("image_generate", &arguments, counted_call(&calls)).await.unwrap();
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(text(&first), "run 1");
+    assert_eq!(text(&second), "run 1");
+}
+
+#[tokio::test]
+async fn keys_are_scoped_per_tool() {
+    let store = IdempotencyStore::default();
+    let calls = AtomicUsize::new(0);
+    let arguments = args(json!({"prompt": "A cat", "idempotency_key": "req-1"}));
+
+    store.run("image_generate", &arguments, counted_call(&calls)).await.unwrap();
+    store.run("music_generate", &arguments, counted_call(&calls)).await.unwrap();
+    let other_key = args(json!({"prompt": "A cat", "idempotency_key": "req-2"}));
+    store.run("image_generate", &other_key, counted_call(&calls)).await.unwrap();
+
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn reused_key_with_different_arguments_is_rejected() {
+    let store = IdempotencyStore::default();
+    let calls = AtomicUsize::new(0);
+
+    let first = args(json!({"prompt": "A cat", "idempotency_key": "req-1"}));
+    store.run("image_generate", &first, counted_call(&calls)).await.unwrap();
+
+    let changed = args(json!({"prompt": "A dog", "idempotency_key": "req-1"}));
+    let err = store.run("image_generate", &changed, counted_call(&calls)).await.unwrap_err();
+
+    assert_eq!(err.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+    assert!(err.message.contains("req-1"), "{}", err.message);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn failed_call_is_not_recorded() {
+    let store = IdempotencyStore::default();
+    let calls = AtomicUsize::new(0);
+    let arguments = args(json!({"prompt": "A cat", "idempotency_key": "req-1"}));
+
+    let failing = async { Err(McpError::internal_error("quota exceeded", None)) };
+    assert!(store.run("image_generate", &arguments, failing).await.is_err());
+
+    let result = store.run("image_generate", &arguments, counted_call(&calls)).await.unwrap();
+    assert_eq!(text(&result), "run 1");
+}
+
+#[tokio::test]
+async fn expired_key_runs_again() {
+    let store = IdempotencyStore::new(16, Duration::from_millis(20));
+    let calls = AtomicUsize::new(0);
+    let arguments = args(json!({"prompt": "A cat", "idempotency_key": "req-1"}));
+
+    store.run("image_generate", &arguments, counted_call(&calls)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    let result = store.run("image_generate", &arguments, counted_call(&calls)).await.unwrap();
+
+    assert_eq!(text(&result), "run 2");
+}
+
+#[tokio::test]
+async fn least_recently_used_key_is_evicted() {
+    let store = IdempotencyStore::new(2, Duration::from_secs(60));
+    let calls = AtomicUsize::new(0);
+    let key = |k: &str| args(json!({"prompt": "A cat", "idempotency_key": k}));
+
+    store.run("image_generate", &key("a"), counted_call(&calls)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(2)).await;
+    store.run("image_generate", &key("b"), counted_call(&calls)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(2)).await;
+    // Touch "a" so "b" becomes the least recently used
+    store.run("image_generate", &key("a"), counted_call(&calls)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(2)).await;
+    store.run("image_generate", &key("c"), counted_call(&calls)).await.unwrap();
+    assert_eq!(store.len(), 2);
+
+    let a = store.run("image_generate", &key("a"), counted_call(&calls)).await.unwrap();
+    assert_eq!(text(&a), "run 1");
+    let b = store.run("image_generate", &key("b"), counted_call(&calls)).await.unwrap();
+    assert_eq!(text(&b), "run 4");
+}
+
+/// A tool call returning `len` bytes of text, counting how often it runs.
+async fn sized_call(calls: &AtomicUsize, len: usize) -> Result<CallToolResult, McpError> {
+    calls.fetch_add(1, Ordering::SeqCst);
+    Ok(CallToolResult::success(vec![Content::text("x".repeat(len))]))
+}
+
+#[tokio::test]
+async fn results_are_evicted_to_fit_byte_budget() {
+    let store = IdempotencyStore::new(16, Duration::from_secs(60)).with_max_bytes(2_500);
+    let calls = AtomicUsize::new(0);
+    let key = |k: &str| args(json!({"prompt": "A cat", "idempotency_key": k}));
+
+    store.run("image_generate", &key("a"), sized_call(&calls, 1_000)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(2)).await;
+    store.run("image_generate", &key("b"), sized_call(&calls, 1_000)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(2)).await;
+    // Recording "c" pushes the total past the budget, dropping "a"
+    store.run("image_generate", &key("c"), sized_call(&calls, 1_000)).await.unwrap();
+    assert_eq!(store.len(), 2);
+    assert!(store.bytes() <= 2_500);
+
+    store.run("image_generate", &key("b"), sized_call(&calls, 1_000)).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    store.run("image_generate", &key("a"), sized_call(&calls, 1_000)).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn result_larger_than_budget_is_not_recorded() {
+    let store = IdempotencyStore::new(16, Duration::from_secs(60)).with_max_bytes(500);
+    let calls = AtomicUsize::new(0);
+    let arguments = args(json!({"prompt": "A cat", "idempotency_key": "req-1"}));
+
+    let first = store.run("image_generate", &arguments, sized_call(&calls, 1_000)).await.unwrap();
+    assert_eq!(text(&first).len(), 1_000);
+    assert!(store.is_empty());
+
+    store.run("image_generate", &arguments, sized_call(&calls, 1_000)).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn concurrent_repeat_waits_for_first_call() {
+    let store = Arc::new(IdempotencyStore::default());
+    let calls = Arc::new(AtomicUsize::new(0));
+    let arguments = args(json!({"prompt": "A cat", "idempotency_key": "req-1"}));
+
+    let slow_call = |calls: Arc<AtomicUsize>| async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        counted_call(&calls).await
+    };
+
+    let (first, second) = tokio::join!(
+        store.run("image_generate", &arguments, slow_call(Arc::clone(&calls))),
+        store.run("image_generate", &arguments, slow_call(Arc::clone(&calls))),
+    );
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(text(&first.unwrap()), text(&second.unwrap()));
+}
+
+#[test]
+fn fingerprint_ignores_key_and_field_order() {
+    let a = args(json!({"prompt": "A cat", "seed": 1, "idempotency_key": "req-1"}));
+    let b = args(json!({"seed": 1, "prompt": "A cat", "idempotency_key": "req-2"}));
+    let c = args(json!({"seed": 2, "prompt": "A cat"}));
+
+    assert_eq!(
+        request_fingerprint("image_generate", &a).unwrap(),
+        request_fingerprint("image_generate", &b).unwrap()
+    );
+    assert_ne!(
+        request_fingerprint("image_generate", &a).unwrap(),
+        request_fingerprint("image_generate", &c).unwrap()
+    );
+    assert_ne!(
+        request_fingerprint("image_generate", &a).unwrap(),
+        request_fingerprint("music_generate", &a).unwrap()
+    );
+}
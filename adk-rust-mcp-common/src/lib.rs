@@ -1,39 +1,67 @@
 //! ADK Rust MCP Common Library
 //!
 //! Shared utilities for configuration, GCS operations, model definitions,
-//! authentication, error handling, and tracing across all MCP GenMedia servers.
+//! authentication, HTTP client setup, FFmpeg invocation, result caching, idempotency keys, request retries, the server_info handshake tool, media input resolution, generation metadata sidecars, cached TTS voice lists, error handling, and tracing across all MCP GenMedia servers.
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 pub mod auth;
+pub mod cache;
 pub mod config;
 pub mod error;
+pub mod ffmpeg;
 pub mod gcs;
+pub mod http;
+pub mod idempotency;
+pub mod media_input;
+pub mod metadata;
 pub mod models;
+pub mod retry;
 pub mod server;
+pub mod server_info;
 pub mod tracing;
 pub mod transport;
+pub mod voices;
 
 #[cfg(feature = "otel")]
 #[cfg_attr(docsrs, doc(cfg(feature = "otel")))]
 pub mod otel;
 
+#[cfg(test)]
+mod cache_test;
 #[cfg(test)]
 mod config_test;
 #[cfg(test)]
+mod ffmpeg_test;
+#[cfg(test)]
 mod gcs_test;
 #[cfg(test)]
+mod http_test;
+#[cfg(test)]
+mod idempotency_test;
+#[cfg(test)]
+mod media_input_test;
+#[cfg(test)]
+mod metadata_test;
+#[cfg(test)]
 mod auth_test;
 #[cfg(test)]
 mod error_test;
 #[cfg(test)]
 mod transport_test;
 #[cfg(test)]
+mod retry_test;
+#[cfg(test)]
 mod server_test;
+#[cfg(test)]
+mod server_info_test;
+#[cfg(test)]
+mod voices_test;
 #[cfg(all(test, feature = "otel"))]
 mod otel_test;
 
 pub use config::Config;
-pub use error::{AuthError, ConfigError, Error, GcsError, GcsOperation, Result};
-pub use server::{McpServerBuilder, ServerError, shutdown_channel};
-pub use transport::{Transport, TransportArgs, TransportMode};
+pub use error::{AuthError, ConfigError, Error, ErrorCode, GcsError, GcsOperation, Result};
+pub use server::{ContentSink, McpServerBuilder, ServerError, shutdown_channel};
+pub use server_info::ServerIdentity;
+pub use transport::{ConfigArgs, Transport, TransportArgs, TransportMode};
@@ -0,0 +1,380 @@
+//! Resolution of image inputs for generation tools.
+//!
+//! Tools accept an image as base64 data, a `data:` URI, a local file path, a
+//! GCS URI, or an HTTPS URL. [`resolve_image_input`] turns any of these into
+//! base64 data so the image, video, and multimodal handlers share one set of
+//! rules; [`resolve_image`] also reports the MIME type a data URI declares.
+//!
+//! URL downloads are bounded: the response must declare an `image/*` content
+//! type, must finish within [`UrlDownloadLimits::timeout`], and may not exceed
+//! [`UrlDownloadLimits::max_bytes`]. Only `https://` URLs whose host resolves
+//! to public addresses are fetched, and redirects are not followed, so a
+//! request cannot reach loopback, private-network, or link-local services
+//! such as the cloud metadata endpoint.
+
+use crate::error::Error;
+use crate::gcs::{GcsClient, GcsUri};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use reqwest::Url;
+use reqwest::header::CONTENT_TYPE;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+use std::time::Duration;
+use tracing::debug;
+
+/// Default size limit for images downloaded from a URL (20 MiB).
+pub const DEFAULT_MAX_DOWNLOAD_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Default timeout for downloading an image from a URL.
+pub const DEFAULT_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Limits applied when downloading an image from a URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UrlDownloadLimits {
+    /// Largest accepted response body in bytes
+    pub max_bytes: u64,
+    /// Time allowed for the whole download
+    pub timeout: Duration,
+    /// Also accept `http://` URLs and hosts on loopback, private, or
+    /// link-local addresses; off by default and meant for local test servers
+    pub allow_private_hosts: bool,
+}
+
+impl Default for UrlDownloadLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_DOWNLOAD_BYTES,
+            timeout: DEFAULT_DOWNLOAD_TIMEOUT,
+            allow_private_hosts: false,
+        }
+    }
+}
+
+/// An image resolved to base64 data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedImage {
+    /// Base64-encoded image bytes
+    pub data: String,
+    /// MIME type declared by a `data:` URI; `None` for other inputs
+    pub mime_type: Option<String>,
+}
+
+/// Whether the input is a `data:` URI.
+pub fn is_data_uri(input: &str) -> bool {
+    input.get(..5).is_some_and(|scheme| scheme.eq_ignore_ascii_case("data:"))
+}
+
+/// Parse a base64 `data:` URI such as `data:image/png;base64,iVBORw0...`.
+///
+/// The media type and any parameters (e.g. `charset`) before `;base64` are
+/// stripped from the payload; whitespace inside the payload is ignored. The
+/// media type is lowercased and must be an `image/*` type when present.
+///
+/// # Errors
+/// Returns `Error::Validation` if the input is not a data URI, has no comma,
+/// is not base64-encoded, declares a non-image media type, or carries an
+/// invalid base64 payload.
+pub fn parse_data_uri(input: &str) -> Result<ResolvedImage, Error> {
+    if !is_data_uri(input) {
+        return Err(Error::validation("Data URI must start with 'data:'"));
+    }
+    let (header, payload) = input[5..]
+        .split_once(',')
+        .ok_or_else(|| Error::validation("Data URI is missing the ',' before its data"))?;
+
+    let mut params = header.split(';').map(str::trim);
+    let media_type = params.next().unwrap_or_default().to_ascii_lowercase();
+    if !params.any(|param| param.eq_ignore_ascii_case("base64")) {
+        return Err(Error::validation("Data URI must be base64-encoded (data:image/png;base64,...)"));
+    }
+    if !media_type.is_empty() && !media_type.starts_with("image/") {
+        return Err(Error::validation(format!(
+            "Data URI has media type '{}', expected an image",
+            media_type
+        )));
+    }
+
+    let data: String = payload.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    if data.is_empty() {
+        return Err(Error::validation("Data URI has no data"));
+    }
+    BASE64
+        .decode(&data)
+        .map_err(|e| Error::validation(format!("Invalid base64 data in data URI: {}", e)))?;
+
+    Ok(ResolvedImage {
+        data,
+        mime_type: (!media_type.is_empty()).then_some(media_type),
+    })
+}
+
+/// Whether the input is an `http://` or `https://` URL.
+pub fn is_http_url(input: &str) -> bool {
+    input.starts_with("https://") || input.starts_with("http://")
+}
+
+/// Check if a string ends with a common image file extension.
+pub fn has_image_extension(s: &str) -> bool {
+    let lower = s.to_lowercase();
+    lower.ends_with(".png")
+        || lower.ends_with(".jpg")
+        || lower.ends_with(".jpeg")
+        || lower.ends_with(".gif")
+        || lower.ends_with(".webp")
+        || lower.ends_with(".bmp")
+        || lower.ends_with(".tiff")
+        || lower.ends_with(".tif")
+}
+
+/// Resolve image input to base64 data.
+///
+/// Same as [`resolve_image`], without the MIME type.
+///
+/// # Errors
+/// See [`resolve_image`].
+pub async fn resolve_image_input(image: &str, gcs: &GcsClient, http: &reqwest::Client) -> Result<String, Error> {
+    resolve_image(image, gcs, http).await.map(|resolved| resolved.data)
+}
+
+/// Resolve image input to base64 data and, for data URIs, its MIME type.
+///
+/// Handles five input formats:
+/// - Data URI (`data:image/png;base64,...`), decoded with [`parse_data_uri`]
+/// - GCS URI (`gs://...`), downloaded with `gcs`
+/// - HTTPS URL, downloaded with `http` under the default [`UrlDownloadLimits`]
+/// - Local file path
+/// - Base64 data (returned as is)
+///
+/// # Errors
+/// Returns `Error::Validation` if the input matches none of the formats, a
+/// data URI is malformed, a referenced file does not exist, or a URL does not
+/// serve an image within the limits. Download failures are returned as
+/// `Error::Gcs` or `Error::Api`.
+pub async fn resolve_image(image: &str, gcs: &GcsClient, http: &reqwest::Client) -> Result<ResolvedImage, Error> {
+    let untyped = |data: String| ResolvedImage { data, mime_type: None };
+
+    // Explicit protocols first
+    if is_data_uri(image) {
+        return parse_data_uri(image);
+    }
+
+    if image.starts_with("gs://") {
+        let uri = GcsUri::parse(image)?;
+        let data = gcs.download(&uri).await?;
+        return Ok(untyped(BASE64.encode(&data)));
+    }
+
+    if is_http_url(image) {
+        let data = download_image_url(http, image, UrlDownloadLimits::default()).await?;
+        return Ok(untyped(BASE64.encode(&data)));
+    }
+
+    // Check if it looks like a file path:
+    // - Starts with / (absolute path)
+    // - Starts with ./ or ../ (relative path)
+    // - Contains a path separator and ends with an image extension
+    // - Is short enough to be a reasonable path (base64 images are typically very long)
+    let looks_like_path = image.starts_with('/')
+        || image.starts_with("./")
+        || image.starts_with("../")
+        || image.starts_with("~/")
+        || (image.len() < 500 && image.contains('/') && has_image_extension(image));
+
+    if looks_like_path {
+        let path = Path::new(image);
+        if !path.exists() {
+            return Err(Error::validation(format!("Image file not found: {}", image)));
+        }
+        let data = tokio::fs::read(path).await?;
+        return Ok(untyped(BASE64.encode(&data)));
+    }
+
+    // Try to validate as base64 - if it decodes successfully, it's base64
+    // This handles the case where base64 contains '/' characters
+    if image.len() > 100 && BASE64.decode(image).is_ok() {
+        return Ok(untyped(image.to_string()));
+    }
+
+    // Last resort: try as file path (might be a relative path without ./)
+    let path = Path::new(image);
+    if path.exists() {
+        let data = tokio::fs::read(path).await?;
+        return Ok(untyped(BASE64.encode(&data)));
+    }
+
+    // If nothing worked and it's long, assume it's base64 (might be malformed)
+    if image.len() > 100 {
+        return Ok(untyped(image.to_string()));
+    }
+
+    Err(Error::validation(format!(
+        "Image input '{}' is not a valid file path, GCS URI, URL, data URI, or base64 data",
+        image.chars().take(50).collect::<String>()
+    )))
+}
+
+/// Download an image from an HTTPS URL.
+///
+/// The host is resolved first and every address it resolves to must be
+/// public (see [`is_public_ip`]). Redirects are refused: `http` should be
+/// built by [`crate::http::HttpClientConfig::build`], which does not follow
+/// them, and a response that arrives from another URL is rejected anyway.
+///
+/// The body is read in chunks so an oversized response is rejected without
+/// buffering it, whether or not the server sends `Content-Length`.
+///
+/// # Errors
+/// - `Error::Validation` if the URL is not `https://`, its host resolves to a
+///   non-public address, the server redirects, the content type is not
+///   `image/*`, or the body exceeds `max_bytes`
+/// - `Error::Api` if the host cannot be resolved, the request fails, or it
+///   returns a non-success status
+/// - `Error::Timeout` if the download does not finish within `timeout`
+pub async fn download_image_url(
+    http: &reqwest::Client,
+    url: &str,
+    limits: UrlDownloadLimits,
+) -> Result<Vec<u8>, Error> {
+    debug!(url = %url, "Downloading image input");
+
+    match tokio::time::timeout(limits.timeout, fetch_image(http, url, limits)).await {
+        Ok(result) => result,
+        Err(_) => Err(Error::timeout(limits.timeout.as_secs())),
+    }
+}
+
+/// Whether `ip` is a publicly routable address.
+///
+/// Loopback, private (RFC 1918, shared CGNAT, IPv6 unique local),
+/// link-local (including the `169.254.169.254` metadata endpoint),
+/// unspecified, multicast, broadcast, documentation, and reserved ranges are
+/// not public. IPv4-mapped and NAT64 IPv6 addresses are judged by the IPv4
+/// address they carry.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_public_ipv4(v4),
+        IpAddr::V6(v6) => is_public_ipv6(v6),
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_multicast()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || a == 0
+        || a >= 240
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 192 && b == 0 && c == 0)
+        || (a == 198 && (18..20).contains(&b)))
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return is_public_ipv4(v4);
+    }
+    let segments = ip.segments();
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let [.., a, b, c, d] = ip.octets();
+        return is_public_ipv4(Ipv4Addr::new(a, b, c, d));
+    }
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        || (segments[0] & 0xfe00) == 0xfc00
+        || (segments[0] & 0xffc0) == 0xfe80
+        || (segments[0] & 0xffc0) == 0xfec0
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8))
+}
+
+/// Check that `url` is HTTPS and its host resolves only to public addresses.
+async fn check_url_target(url: &str, limits: UrlDownloadLimits) -> Result<Url, Error> {
+    let parsed = Url::parse(url).map_err(|e| Error::validation(format!("Invalid URL '{}': {}", url, e)))?;
+    if parsed.scheme() != "https" && !(limits.allow_private_hosts && parsed.scheme() == "http") {
+        return Err(Error::validation(format!("Image URL must use https://, got '{}'", url)));
+    }
+    if limits.allow_private_hosts {
+        return Ok(parsed);
+    }
+
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| Error::validation(format!("Image URL '{}' has no host", url)))?;
+    let port = parsed.port_or_known_default().unwrap_or(443);
+    let addresses: Vec<IpAddr> = match host.trim_start_matches('[').trim_end_matches(']').parse() {
+        Ok(ip) => vec![ip],
+        Err(_) => tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|e| Error::api(url, 0, format!("Failed to resolve host '{}': {}", host, e)))?
+            .map(|addr| addr.ip())
+            .collect(),
+    };
+    if let Some(blocked) = addresses.iter().find(|ip| !is_public_ip(**ip)) {
+        return Err(Error::validation(format!(
+            "Image URL '{}' resolves to non-public address {}",
+            url, blocked
+        )));
+    }
+    Ok(parsed)
+}
+
+async fn fetch_image(http: &reqwest::Client, url: &str, limits: UrlDownloadLimits) -> Result<Vec<u8>, Error> {
+    let max_bytes = limits.max_bytes;
+    let target = check_url_target(url, limits).await?;
+    let mut response = http
+        .get(target.clone())
+        .send()
+        .await
+        .map_err(|e| Error::api(url, 0, format!("Request failed: {}", e)))?;
+
+    let status = response.status();
+    if status.is_redirection() || response.url() != &target {
+        return Err(Error::validation(format!("Image URL '{}' redirects; redirects are not followed", url)));
+    }
+    if !status.is_success() {
+        return Err(Error::api(url, status.as_u16(), "Failed to download image"));
+    }
+
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    if !content_type.trim_start().to_ascii_lowercase().starts_with("image/") {
+        return Err(Error::validation(format!(
+            "URL {} did not return an image (content type: {})",
+            url,
+            if content_type.is_empty() { "none" } else { &content_type }
+        )));
+    }
+
+    let too_large = || {
+        Error::validation(format!(
+            "Image at {} exceeds the maximum download size of {} bytes",
+            url, max_bytes
+        ))
+    };
+
+    if response.content_length().is_some_and(|len| len > max_bytes) {
+        return Err(too_large());
+    }
+
+    let mut data = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| Error::api(url, status.as_u16(), format!("Failed to read response: {}", e)))?
+    {
+        if (data.len() + chunk.len()) as u64 > max_bytes {
+            return Err(too_large());
+        }
+        data.extend_from_slice(&chunk);
+    }
+
+    Ok(data)
+}
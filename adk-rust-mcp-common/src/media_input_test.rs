@@ -0,0 +1,336 @@
+//! Unit tests for the media input module.
+
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::auth::AuthProvider;
+use crate::error::Error;
+use crate::gcs::GcsClient;
+use crate::http::HttpClientConfig;
+use crate::media_input::{
+    UrlDownloadLimits, download_image_url, has_image_extension, is_data_uri, is_http_url, is_public_ip,
+    parse_data_uri, resolve_image, resolve_image_input,
+};
+
+const PNG_BYTES: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+fn limits(max_bytes: u64) -> UrlDownloadLimits {
+    UrlDownloadLimits {
+        max_bytes,
+        timeout: Duration::from_secs(5),
+        allow_private_hosts: true,
+    }
+}
+
+fn gcs_client(base_url: String) -> GcsClient {
+    GcsClient::with_base_url(Arc::new(AuthProvider::mock("test-token")), base_url)
+}
+
+#[test]
+fn has_image_extension_png() {
+    assert!(has_image_extension("image.png"));
+    assert!(has_image_extension("path/to/image.PNG"));
+}
+
+#[test]
+fn has_image_extension_jpg() {
+    assert!(has_image_extension("photo.jpg"));
+    assert!(has_image_extension("photo.jpeg"));
+    assert!(has_image_extension("photo.JPEG"));
+}
+
+#[test]
+fn has_image_extension_other_formats() {
+    assert!(has_image_extension("image.gif"));
+    assert!(has_image_extension("image.webp"));
+    assert!(has_image_extension("image.bmp"));
+    assert!(has_image_extension("image.tiff"));
+    assert!(has_image_extension("image.tif"));
+}
+
+#[test]
+fn has_image_extension_no_extension() {
+    assert!(!has_image_extension("noextension"));
+    assert!(!has_image_extension("path/to/file"));
+}
+
+#[test]
+fn has_image_extension_wrong_extension() {
+    assert!(!has_image_extension("file.txt"));
+    assert!(!has_image_extension("file.mp4"));
+    assert!(!has_image_extension("file.pdf"));
+}
+
+#[test]
+fn detects_http_urls() {
+    assert!(is_http_url("https://example.com/a.png"));
+    assert!(is_http_url("http://example.com/a.png"));
+    assert!(!is_http_url("gs://bucket/a.png"));
+    assert!(!is_http_url("./https/a.png"));
+}
+
+#[tokio::test]
+async fn downloads_image_url() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/a.png"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Type", "image/png")
+                .set_body_bytes(PNG_BYTES),
+        )
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/a.png", server.uri());
+    let data = download_image_url(&reqwest::Client::new(), &url, limits(1024)).await.unwrap();
+    assert_eq!(data, PNG_BYTES);
+}
+
+#[tokio::test]
+async fn rejects_non_image_content_type() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw("<html></html>", "text/html"))
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/page", server.uri());
+    let err = download_image_url(&reqwest::Client::new(), &url, limits(1024)).await.unwrap_err();
+    assert!(matches!(err, Error::Validation(ref msg) if msg.contains("text/html")), "{err}");
+}
+
+#[tokio::test]
+async fn rejects_oversized_image() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/big.png"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Type", "image/png")
+                .set_body_bytes(vec![0u8; 2048]),
+        )
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/big.png", server.uri());
+    let err = download_image_url(&reqwest::Client::new(), &url, limits(1024)).await.unwrap_err();
+    assert!(matches!(err, Error::Validation(ref msg) if msg.contains("maximum download size")), "{err}");
+}
+
+#[tokio::test]
+async fn reports_http_status() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/missing.png"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/missing.png", server.uri());
+    let err = download_image_url(&reqwest::Client::new(), &url, limits(1024)).await.unwrap_err();
+    assert!(matches!(err, Error::Api { status_code: 404, .. }), "{err}");
+}
+
+#[tokio::test]
+async fn times_out_slow_download() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/slow.png"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Type", "image/png")
+                .set_delay(Duration::from_secs(2)),
+        )
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/slow.png", server.uri());
+    let limits = UrlDownloadLimits {
+        timeout: Duration::from_millis(100),
+        ..limits(1024)
+    };
+    let err = download_image_url(&reqwest::Client::new(), &url, limits).await.unwrap_err();
+    assert!(matches!(err, Error::Timeout(_)), "{err}");
+}
+
+#[tokio::test]
+async fn refuses_redirects() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/moved.png"))
+        .respond_with(ResponseTemplate::new(302).insert_header("Location", "http://169.254.169.254/"))
+        .mount(&server)
+        .await;
+
+    let url = format!("{}/moved.png", server.uri());
+    let http = HttpClientConfig::default().build().unwrap();
+    let err = download_image_url(&http, &url, limits(1024)).await.unwrap_err();
+    assert!(matches!(err, Error::Validation(ref msg) if msg.contains("redirect")), "{err}");
+}
+
+#[tokio::test]
+async fn rejects_plain_http_and_private_hosts() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Type", "image/png")
+                .set_body_bytes(PNG_BYTES),
+        )
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let gcs = gcs_client(server.uri());
+    let http = reqwest::Client::new();
+    let local = format!("{}/frame.png", server.uri());
+    let cases = [
+        local.clone(),
+        local.replacen("http://", "https://", 1),
+        "https://localhost/frame.png".to_string(),
+        "https://169.254.169.254/computeMetadata/v1/".to_string(),
+        "https://10.0.0.8/frame.png".to_string(),
+        "https://[::1]/frame.png".to_string(),
+        "https://[::ffff:127.0.0.1]/frame.png".to_string(),
+    ];
+    for url in cases {
+        let err = resolve_image_input(&url, &gcs, &http).await.unwrap_err();
+        assert!(matches!(err, Error::Validation(_)), "{url}: {err}");
+    }
+}
+
+#[test]
+fn classifies_public_addresses() {
+    let public = ["8.8.8.8", "142.250.72.14", "2607:f8b0:4004:c07::6a", "64:ff9b::808:808"];
+    for ip in public {
+        assert!(is_public_ip(ip.parse::<IpAddr>().unwrap()), "{ip}");
+    }
+
+    let private = [
+        "127.0.0.1",
+        "10.1.2.3",
+        "172.16.0.1",
+        "192.168.1.1",
+        "169.254.169.254",
+        "100.64.0.1",
+        "0.0.0.0",
+        "255.255.255.255",
+        "::1",
+        "::",
+        "fd00::1",
+        "fe80::1",
+        "::ffff:10.0.0.1",
+        "64:ff9b::a9fe:a9fe",
+    ];
+    for ip in private {
+        assert!(!is_public_ip(ip.parse::<IpAddr>().unwrap()), "{ip}");
+    }
+}
+
+#[tokio::test]
+async fn resolves_gcs_input_to_base64() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/storage/v1/b/bucket/o/frame.png"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(PNG_BYTES))
+        .mount(&server)
+        .await;
+
+    let gcs = gcs_client(server.uri());
+    let data = resolve_image_input("gs://bucket/frame.png", &gcs, &reqwest::Client::new())
+        .await
+        .unwrap();
+    assert_eq!(data, BASE64.encode(PNG_BYTES));
+}
+
+#[tokio::test]
+async fn resolves_local_file_and_base64() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("frame.png");
+    std::fs::write(&file, PNG_BYTES).unwrap();
+
+    let gcs = gcs_client("http://localhost".to_string());
+    let http = reqwest::Client::new();
+
+    let from_file = resolve_image_input(file.to_str().unwrap(), &gcs, &http).await.unwrap();
+    assert_eq!(from_file, BASE64.encode(PNG_BYTES));
+
+    let encoded = BASE64.encode([7u8; 120]);
+    assert_eq!(resolve_image_input(&encoded, &gcs, &http).await.unwrap(), encoded);
+
+    let missing = resolve_image_input("/nonexistent/frame.png", &gcs, &http).await;
+    assert!(matches!(missing, Err(Error::Validation(_))));
+
+    let invalid = resolve_image_input("not an image", &gcs, &http).await;
+    assert!(matches!(invalid, Err(Error::Validation(_))));
+}
+
+#[test]
+fn detects_data_uris() {
+    assert!(is_data_uri("data:image/png;base64,AAAA"));
+    assert!(is_data_uri("DATA:image/png;base64,AAAA"));
+    assert!(!is_data_uri("data"));
+    assert!(!is_data_uri("gs://bucket/data:image.png"));
+}
+
+#[test]
+fn parses_data_uri_with_media_type_and_charset() {
+    let encoded = BASE64.encode(PNG_BYTES);
+
+    let parsed = parse_data_uri(&format!("data:image/png;base64,{}", encoded)).unwrap();
+    assert_eq!(parsed.data, encoded);
+    assert_eq!(parsed.mime_type.as_deref(), Some("image/png"));
+
+    let parsed = parse_data_uri(&format!("data:Image/JPEG;charset=utf-8;base64,{}", encoded)).unwrap();
+    assert_eq!(parsed.mime_type.as_deref(), Some("image/jpeg"));
+    assert_eq!(parsed.data, encoded);
+
+    // Line-wrapped payloads are joined; a missing media type is left unknown
+    let (head, tail) = encoded.split_at(8);
+    let parsed = parse_data_uri(&format!("data:;base64,{}\n{}", head, tail)).unwrap();
+    assert_eq!(parsed.data, encoded);
+    assert!(parsed.mime_type.is_none());
+}
+
+#[test]
+fn rejects_malformed_data_uris() {
+    let encoded = BASE64.encode(PNG_BYTES);
+    let cases = [
+        format!("data:image/png;base64{}", encoded),
+        format!("data:image/png,{}", encoded),
+        format!("data:text/plain;base64,{}", encoded),
+        "data:image/png;base64,".to_string(),
+        "data:image/png;base64,not*base64".to_string(),
+        "image/png;base64,AAAA".to_string(),
+    ];
+    for case in cases {
+        assert!(matches!(parse_data_uri(&case), Err(Error::Validation(_))), "{case}");
+    }
+}
+
+#[tokio::test]
+async fn resolves_data_uri_input() {
+    let gcs = gcs_client("http://localhost".to_string());
+    let http = reqwest::Client::new();
+    let encoded = BASE64.encode(PNG_BYTES);
+    let uri = format!("data:image/webp;base64,{}", encoded);
+
+    let resolved = resolve_image(&uri, &gcs, &http).await.unwrap();
+    assert_eq!(resolved.data, encoded);
+    assert_eq!(resolved.mime_type.as_deref(), Some("image/webp"));
+    assert_eq!(resolve_image_input(&uri, &gcs, &http).await.unwrap(), encoded);
+
+    // Other inputs carry no declared type
+    let raw = BASE64.encode([7u8; 120]);
+    assert!(resolve_image(&raw, &gcs, &http).await.unwrap().mime_type.is_none());
+
+    let bad = resolve_image("data:image/png;base64,@@@", &gcs, &http).await;
+    assert!(matches!(bad, Err(Error::Validation(_))));
+}
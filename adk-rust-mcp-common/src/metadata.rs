@@ -0,0 +1,111 @@
+//! Generation metadata sidecars.
+//!
+//! Generation tools can write a `<name>.json` file next to each saved output
+//! recording the prompt, model, seed and other settings that produced it, so
+//! results can be reproduced without a separate tracking system. Sidecars for
+//! `gs://` outputs are uploaded to the same bucket as `application/json`.
+
+use crate::error::Error;
+use crate::gcs::{GcsClient, GcsUri};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Settings that produced a generated output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenerationMetadata {
+    /// Prompt as given by the caller
+    pub prompt: String,
+    /// Negative prompt, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub negative_prompt: Option<String>,
+    /// Canonical ID of the model used
+    pub model: String,
+    /// Seed passed to the API, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+    /// Requested aspect ratio, if the tool has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<String>,
+    /// Long-running operation that produced the output (video only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation_name: Option<String>,
+    /// When the output was generated, in RFC 3339 UTC
+    pub created_at: String,
+}
+
+impl GenerationMetadata {
+    /// Create metadata for `prompt` and `model`, timestamped now.
+    pub fn new(prompt: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            prompt: prompt.into(),
+            negative_prompt: None,
+            model: model.into(),
+            seed: None,
+            aspect_ratio: None,
+            operation_name: None,
+            created_at: format_rfc3339(SystemTime::now()),
+        }
+    }
+}
+
+/// Path or URI of the sidecar for `output`: its extension replaced by `.json`.
+///
+/// Works for both local paths and `gs://` URIs; an output without an
+/// extension gets `.json` appended.
+pub fn sidecar_path(output: &str) -> String {
+    let name_start = output.rfind('/').map_or(0, |i| i + 1);
+    match output[name_start..].rfind('.') {
+        Some(dot) if dot > 0 => format!("{}.json", &output[..name_start + dot]),
+        _ => format!("{}.json", output),
+    }
+}
+
+/// Write the sidecar for `output` and return where it was written.
+///
+/// # Errors
+/// Returns an error if the metadata cannot be serialized, the local file
+/// cannot be written, or the GCS upload fails.
+pub async fn write_sidecar(gcs: &GcsClient, output: &str, metadata: &GenerationMetadata) -> Result<String, Error> {
+    let location = sidecar_path(output);
+    let data = serde_json::to_vec_pretty(metadata)
+        .map_err(|e| Error::validation(format!("Failed to serialize generation metadata: {}", e)))?;
+
+    if location.starts_with("gs://") {
+        let uri = GcsUri::parse(&location)?;
+        gcs.upload(&uri, &data, "application/json").await?;
+    } else {
+        if let Some(parent) = Path::new(&location).parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&location, &data).await?;
+    }
+    Ok(location)
+}
+
+/// Format `time` as an RFC 3339 UTC timestamp with second precision.
+pub fn format_rfc3339(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()) as i64;
+    let (days, rem) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
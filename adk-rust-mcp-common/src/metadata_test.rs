@@ -0,0 +1,89 @@
+//! Unit tests for the generation metadata module.
+
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::auth::AuthProvider;
+use crate::gcs::GcsClient;
+use crate::metadata::{GenerationMetadata, format_rfc3339, sidecar_path, write_sidecar};
+
+fn metadata() -> GenerationMetadata {
+    GenerationMetadata {
+        negative_prompt: Some("blurry".to_string()),
+        seed: Some(42),
+        aspect_ratio: Some("16:9".to_string()),
+        ..GenerationMetadata::new("A cat", "imagen-4.0-generate-001")
+    }
+}
+
+#[test]
+fn sidecar_path_replaces_extension() {
+    assert_eq!(sidecar_path("out/cat.png"), "out/cat.json");
+    assert_eq!(sidecar_path("gs://bucket/renders/clip.mp4"), "gs://bucket/renders/clip.json");
+    assert_eq!(sidecar_path("gs://bucket/v1.2/track_0.wav"), "gs://bucket/v1.2/track_0.json");
+}
+
+#[test]
+fn sidecar_path_appends_without_extension() {
+    assert_eq!(sidecar_path("out/cat"), "out/cat.json");
+    assert_eq!(sidecar_path("gs://bucket/dir.v2/clip"), "gs://bucket/dir.v2/clip.json");
+    assert_eq!(sidecar_path(".hidden"), ".hidden.json");
+}
+
+#[test]
+fn formats_rfc3339_timestamps() {
+    assert_eq!(format_rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+    assert_eq!(
+        format_rfc3339(UNIX_EPOCH + Duration::from_secs(951_827_696)),
+        "2000-02-29T12:34:56Z"
+    );
+    assert_eq!(
+        format_rfc3339(UNIX_EPOCH + Duration::from_secs(1_767_225_599)),
+        "2025-12-31T23:59:59Z"
+    );
+}
+
+#[test]
+fn serializes_only_present_fields() {
+    let json = serde_json::to_value(GenerationMetadata::new("A cat", "lyria-002")).unwrap();
+    let keys: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+    assert_eq!(keys.len(), 3, "{keys:?}");
+    assert!(json["created_at"].as_str().unwrap().ends_with('Z'));
+}
+
+#[tokio::test]
+async fn writes_local_sidecar() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("nested/cat.png");
+    let gcs = GcsClient::with_base_url(Arc::new(AuthProvider::mock("test-token")), "http://localhost".to_string());
+
+    let written = write_sidecar(&gcs, output.to_str().unwrap(), &metadata()).await.unwrap();
+
+    assert_eq!(written, dir.path().join("nested/cat.json").to_str().unwrap());
+    let saved: GenerationMetadata = serde_json::from_slice(&std::fs::read(&written).unwrap()).unwrap();
+    assert_eq!(saved, metadata());
+}
+
+#[tokio::test]
+async fn uploads_gcs_sidecar_as_json() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/upload/storage/v1/b/bucket/o"))
+        .and(query_param("name", "renders/cat.json"))
+        .and(header("Content-Type", "application/json"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let gcs = GcsClient::with_base_url(Arc::new(AuthProvider::mock("test-token")), server.uri());
+
+    let written = write_sidecar(&gcs, "gs://bucket/renders/cat.png", &metadata()).await.unwrap();
+    assert_eq!(written, "gs://bucket/renders/cat.json");
+
+    let requests = server.received_requests().await.unwrap();
+    let saved: GenerationMetadata = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(saved, metadata());
+}
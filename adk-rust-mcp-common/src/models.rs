@@ -1,10 +1,27 @@
-//! Model definitions and registry for Imagen, Veo, and Gemini models.
+//! Model definitions and registry for Imagen, Veo, Gemini, and Lyria models.
 //!
 //! This module provides static model definitions and a registry for resolving
-//! model names and aliases to their full definitions.
+//! model names and aliases to their full definitions. All aliases live here, so
+//! every server accepts the same short names (e.g. `imagen-4`, `veo-3`).
 
 use serde::Serialize;
 
+/// Pixel size a model produces for one aspect ratio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct OutputDimensions {
+    /// Aspect ratio as passed to the API, e.g. "16:9"
+    pub aspect_ratio: &'static str,
+    /// Output width in pixels
+    pub width: u32,
+    /// Output height in pixels
+    pub height: u32,
+}
+
+/// Find the entry for `aspect_ratio` in a model's dimension table.
+fn find_dimensions(table: &[OutputDimensions], aspect_ratio: &str) -> Option<OutputDimensions> {
+    table.iter().find(|d| d.aspect_ratio == aspect_ratio).copied()
+}
+
 /// Imagen model definition.
 #[derive(Debug, Clone, Copy, Serialize)]
 pub struct ImagenModel {
@@ -19,6 +36,41 @@ pub struct ImagenModel {
     pub supported_aspect_ratios: &'static [&'static str],
     /// Maximum number of images per request
     pub max_images: u8,
+    /// Whether the model accepts a `guidanceScale` parameter
+    pub supports_guidance_scale: bool,
+    /// Prompt languages accepted as the `language` parameter
+    pub supported_languages: &'static [&'static str],
+    /// Output size for each supported aspect ratio
+    pub output_dimensions: &'static [OutputDimensions],
+}
+
+impl ImagenModel {
+    /// Pixel size of images generated at `aspect_ratio`, or `None` if the
+    /// model does not support it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use adk_rust_mcp_common::models::IMAGEN_3_0_GENERATE_002;
+    ///
+    /// let dims = IMAGEN_3_0_GENERATE_002.dimensions_for("16:9").unwrap();
+    /// assert_eq!((dims.width, dims.height), (1408, 768));
+    /// ```
+    pub fn dimensions_for(&self, aspect_ratio: &str) -> Option<OutputDimensions> {
+        find_dimensions(self.output_dimensions, aspect_ratio)
+    }
+}
+
+/// Imagen upscale model definition.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ImagenUpscaleModel {
+    /// Full model identifier
+    pub id: &'static str,
+    /// Model aliases for convenience
+    #[serde(skip)]
+    pub aliases: &'static [&'static str],
+    /// Supported upscale factors, e.g. "x2"
+    pub supported_factors: &'static [&'static str],
 }
 
 /// Veo model definition.
@@ -35,6 +87,27 @@ pub struct VeoModel {
     pub supported_durations: &'static [u8],
     /// Whether the model supports audio generation
     pub supports_audio: bool,
+    /// Whether the model supports camera motion controls
+    pub supports_camera_control: bool,
+    /// Output size for each supported aspect ratio
+    pub output_dimensions: &'static [OutputDimensions],
+}
+
+impl VeoModel {
+    /// Pixel size of videos generated at `aspect_ratio`, or `None` if the
+    /// model does not support it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use adk_rust_mcp_common::models::VEO_2_0_GENERATE_001;
+    ///
+    /// let dims = VEO_2_0_GENERATE_001.dimensions_for("9:16").unwrap();
+    /// assert_eq!((dims.width, dims.height), (720, 1280));
+    /// ```
+    pub fn dimensions_for(&self, aspect_ratio: &str) -> Option<OutputDimensions> {
+        find_dimensions(self.output_dimensions, aspect_ratio)
+    }
 }
 
 /// Gemini model definition for multimodal generation.
@@ -49,6 +122,8 @@ pub struct GeminiModel {
     pub supports_image_generation: bool,
     /// Whether the model supports TTS
     pub supports_tts: bool,
+    /// Whether the model accepts audio and video as input
+    pub supports_audio_input: bool,
 }
 
 /// Lyria model definition for music generation.
@@ -61,38 +136,101 @@ pub struct LyriaModel {
     pub aliases: &'static [&'static str],
     /// Maximum number of samples per request
     pub max_samples: u8,
+    /// Maximum prompt (and negative prompt) length in characters
+    pub max_prompt_length: usize,
+    /// Shortest audio the model can generate, in seconds
+    pub min_duration_seconds: u32,
+    /// Longest audio the model can generate, in seconds
+    pub max_duration_seconds: u32,
+    /// Whether tempo (`bpm`) and `key` are accepted as request parameters;
+    /// otherwise they are described in the prompt
+    pub supports_structured_controls: bool,
+    /// Whether the model can continue an existing clip
+    pub supports_continuation: bool,
 }
 
+/// Identity shared by all model definitions, used for alias resolution.
+pub trait ModelDefinition: 'static {
+    /// Canonical model identifier.
+    fn id(&self) -> &'static str;
+
+    /// Alternative names accepted for this model.
+    fn aliases(&self) -> &'static [&'static str];
+
+    /// Whether `name` is the canonical ID or one of the aliases.
+    fn matches(&self, name: &str) -> bool {
+        self.id() == name || self.aliases().contains(&name)
+    }
+}
+
+macro_rules! impl_model_definition {
+    ($($model:ty),*) => {
+        $(
+            impl ModelDefinition for $model {
+                fn id(&self) -> &'static str {
+                    self.id
+                }
+
+                fn aliases(&self) -> &'static [&'static str] {
+                    self.aliases
+                }
+            }
+        )*
+    };
+}
+
+impl_model_definition!(ImagenModel, ImagenUpscaleModel, VeoModel, GeminiModel, LyriaModel);
 
 // =============================================================================
 // Static Model Definitions
 // =============================================================================
 
+/// Prompt languages supported by Imagen 3 and 4 (`auto` detects the language).
+pub const IMAGEN_LANGUAGES: &[&str] = &["auto", "en", "es", "hi", "ja", "ko", "pt", "zh", "zh-CN", "zh-TW"];
+
+/// Output sizes of Imagen 3 and 4 (1K) per aspect ratio.
+pub const IMAGEN_OUTPUT_DIMENSIONS: &[OutputDimensions] = &[
+    OutputDimensions { aspect_ratio: "1:1", width: 1024, height: 1024 },
+    OutputDimensions { aspect_ratio: "3:4", width: 896, height: 1280 },
+    OutputDimensions { aspect_ratio: "4:3", width: 1280, height: 896 },
+    OutputDimensions { aspect_ratio: "9:16", width: 768, height: 1408 },
+    OutputDimensions { aspect_ratio: "16:9", width: 1408, height: 768 },
+];
+
 /// Imagen 3.0 Generate model (stable)
 pub const IMAGEN_3_0_GENERATE_002: ImagenModel = ImagenModel {
     id: "imagen-3.0-generate-002",
-    aliases: &["imagen-3", "imagen-3.0", "imagen3"],
+    aliases: &["imagen-3", "imagen-3.0", "imagen3", "imagen-3.0-generate"],
     max_prompt_length: 480,
     supported_aspect_ratios: &["1:1", "3:4", "4:3", "9:16", "16:9"],
     max_images: 4,
+    supports_guidance_scale: true,
+    supported_languages: IMAGEN_LANGUAGES,
+    output_dimensions: IMAGEN_OUTPUT_DIMENSIONS,
 };
 
 /// Imagen 3.0 Fast Generate model
 pub const IMAGEN_3_0_FAST_GENERATE_001: ImagenModel = ImagenModel {
     id: "imagen-3.0-fast-generate-001",
-    aliases: &["imagen-3-fast", "imagen-3.0-fast"],
+    aliases: &["imagen-3-fast", "imagen-3.0-fast", "imagen3-fast", "imagen-3.0-fast-generate"],
     max_prompt_length: 480,
     supported_aspect_ratios: &["1:1", "3:4", "4:3", "9:16", "16:9"],
     max_images: 4,
+    supports_guidance_scale: true,
+    supported_languages: IMAGEN_LANGUAGES,
+    output_dimensions: IMAGEN_OUTPUT_DIMENSIONS,
 };
 
 /// Imagen 4.0 Generate Preview model (June 2025)
 pub const IMAGEN_4_0_GENERATE_PREVIEW_06_06: ImagenModel = ImagenModel {
     id: "imagen-4.0-generate-preview-06-06",
-    aliases: &["imagen-4", "imagen-4.0", "imagen4", "imagen-4-preview"],
+    aliases: &["imagen-4", "imagen-4.0", "imagen4", "imagen-4-preview", "imagen-4.0-generate-preview"],
     max_prompt_length: 2000,
     supported_aspect_ratios: &["1:1", "3:4", "4:3", "9:16", "16:9"],
     max_images: 4,
+    supports_guidance_scale: false,
+    supported_languages: IMAGEN_LANGUAGES,
+    output_dimensions: IMAGEN_OUTPUT_DIMENSIONS,
 };
 
 /// All available Imagen models
@@ -102,26 +240,57 @@ pub const IMAGEN_MODELS: &[ImagenModel] = &[
     IMAGEN_4_0_GENERATE_PREVIEW_06_06,
 ];
 
+// =============================================================================
+// Imagen Upscale Model Definitions
+// =============================================================================
+
+/// Imagen 4.0 Upscale Preview model
+pub const IMAGEN_4_0_UPSCALE_PREVIEW: ImagenUpscaleModel = ImagenUpscaleModel {
+    id: "imagen-4.0-upscale-preview",
+    aliases: &["imagen-4-upscale", "imagen-4.0-upscale"],
+    supported_factors: &["x2"],
+};
+
+/// Imagen image generation 002 model, used for x4 upscaling
+pub const IMAGEGENERATION_002: ImagenUpscaleModel = ImagenUpscaleModel {
+    id: "imagegeneration@002",
+    aliases: &["imagegeneration-002", "imagen-upscale-x4"],
+    supported_factors: &["x2", "x4"],
+};
+
+/// All available Imagen upscale models, in order of preference
+pub const IMAGEN_UPSCALE_MODELS: &[ImagenUpscaleModel] = &[IMAGEN_4_0_UPSCALE_PREVIEW, IMAGEGENERATION_002];
+
 // =============================================================================
 // Veo Model Definitions
 // =============================================================================
 
+/// Output sizes of Veo at 720p per aspect ratio.
+pub const VEO_720P_OUTPUT_DIMENSIONS: &[OutputDimensions] = &[
+    OutputDimensions { aspect_ratio: "16:9", width: 1280, height: 720 },
+    OutputDimensions { aspect_ratio: "9:16", width: 720, height: 1280 },
+];
+
 /// Veo 2.0 Generate model (stable)
 pub const VEO_2_0_GENERATE_001: VeoModel = VeoModel {
     id: "veo-2.0-generate-001",
-    aliases: &["veo-2", "veo-2.0", "veo2"],
+    aliases: &["veo-2", "veo-2.0", "veo2", "veo-2.0-generate"],
     supported_aspect_ratios: &["16:9", "9:16"],
     supported_durations: &[4, 6, 8],
     supports_audio: false,
+    supports_camera_control: false,
+    output_dimensions: VEO_720P_OUTPUT_DIMENSIONS,
 };
 
 /// Veo 3.0 Generate Preview model
 pub const VEO_3_0_GENERATE_PREVIEW: VeoModel = VeoModel {
     id: "veo-3.0-generate-preview",
-    aliases: &["veo-3", "veo-3.0", "veo3", "veo-3-preview"],
+    aliases: &["veo-3", "veo-3.0", "veo3", "veo-3-preview", "veo-3.0-generate"],
     supported_aspect_ratios: &["16:9", "9:16"],
     supported_durations: &[4, 6, 8],
     supports_audio: true,
+    supports_camera_control: true,
+    output_dimensions: VEO_720P_OUTPUT_DIMENSIONS,
 };
 
 /// All available Veo models
@@ -131,24 +300,79 @@ pub const VEO_MODELS: &[VeoModel] = &[VEO_2_0_GENERATE_001, VEO_3_0_GENERATE_PRE
 // Gemini Model Definitions
 // =============================================================================
 
-/// Gemini 2.0 Flash model for multimodal generation
-pub const GEMINI_2_0_FLASH: GeminiModel = GeminiModel {
-    id: "gemini-2.0-flash",
-    aliases: &["gemini-flash", "gemini-2-flash"],
+/// Gemini 2.5 Flash model for image, audio and video understanding
+pub const GEMINI_2_5_FLASH: GeminiModel = GeminiModel {
+    id: "gemini-2.5-flash",
+    aliases: &["flash", "gemini-flash"],
+    supports_image_generation: false,
+    supports_tts: false,
+    supports_audio_input: true,
+};
+
+/// Gemini 2.5 Pro model for image, audio and video understanding
+pub const GEMINI_2_5_PRO: GeminiModel = GeminiModel {
+    id: "gemini-2.5-pro",
+    aliases: &["pro", "gemini-pro"],
+    supports_image_generation: false,
+    supports_tts: false,
+    supports_audio_input: true,
+};
+
+/// Gemini 2.5 Flash Image model for image generation and editing
+pub const GEMINI_2_5_FLASH_IMAGE: GeminiModel = GeminiModel {
+    id: "gemini-2.5-flash-image",
+    aliases: &["flash-image", "gemini-flash-image"],
     supports_image_generation: true,
+    supports_tts: false,
+    supports_audio_input: false,
+};
+
+/// Gemini 2.5 Flash TTS model
+pub const GEMINI_2_5_FLASH_PREVIEW_TTS: GeminiModel = GeminiModel {
+    id: "gemini-2.5-flash-preview-tts",
+    aliases: &["flash-tts", "gemini-flash-tts"],
+    supports_image_generation: false,
+    supports_tts: true,
+    supports_audio_input: false,
+};
+
+/// Gemini 2.5 Pro TTS model
+pub const GEMINI_2_5_PRO_PREVIEW_TTS: GeminiModel = GeminiModel {
+    id: "gemini-2.5-pro-preview-tts",
+    aliases: &["pro-tts", "gemini-pro-tts"],
+    supports_image_generation: false,
     supports_tts: true,
+    supports_audio_input: false,
+};
+
+/// Gemini 2.0 Flash model
+pub const GEMINI_2_0_FLASH: GeminiModel = GeminiModel {
+    id: "gemini-2.0-flash",
+    aliases: &["gemini-2-flash"],
+    supports_image_generation: false,
+    supports_tts: false,
+    supports_audio_input: true,
 };
 
 /// Gemini 2.0 Flash Lite model
 pub const GEMINI_2_0_FLASH_LITE: GeminiModel = GeminiModel {
     id: "gemini-2.0-flash-lite",
     aliases: &["gemini-flash-lite", "gemini-2-flash-lite"],
-    supports_image_generation: true,
-    supports_tts: true,
+    supports_image_generation: false,
+    supports_tts: false,
+    supports_audio_input: true,
 };
 
 /// All available Gemini models
-pub const GEMINI_MODELS: &[GeminiModel] = &[GEMINI_2_0_FLASH, GEMINI_2_0_FLASH_LITE];
+pub const GEMINI_MODELS: &[GeminiModel] = &[
+    GEMINI_2_5_FLASH,
+    GEMINI_2_5_PRO,
+    GEMINI_2_5_FLASH_IMAGE,
+    GEMINI_2_5_FLASH_PREVIEW_TTS,
+    GEMINI_2_5_PRO_PREVIEW_TTS,
+    GEMINI_2_0_FLASH,
+    GEMINI_2_0_FLASH_LITE,
+];
 
 // =============================================================================
 // Lyria Model Definitions
@@ -159,6 +383,11 @@ pub const LYRIA_1_0: LyriaModel = LyriaModel {
     id: "lyria-1.0",
     aliases: &["lyria", "lyria-1", "music-generation"],
     max_samples: 4,
+    max_prompt_length: 2000,
+    min_duration_seconds: 10,
+    max_duration_seconds: 180,
+    supports_structured_controls: false,
+    supports_continuation: false,
 };
 
 /// All available Lyria models
@@ -195,9 +424,37 @@ impl ModelRegistry {
     /// assert!(model.is_some());
     /// ```
     pub fn resolve_imagen(name: &str) -> Option<&'static ImagenModel> {
-        IMAGEN_MODELS
+        IMAGEN_MODELS.iter().find(|model| model.matches(name))
+    }
+
+    /// Resolve an Imagen upscale model name or alias to full model definition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use adk_rust_mcp_common::models::ModelRegistry;
+    ///
+    /// let model = ModelRegistry::resolve_imagen_upscale("imagen-4-upscale");
+    /// assert_eq!(model.map(|m| m.id), Some("imagen-4.0-upscale-preview"));
+    /// ```
+    pub fn resolve_imagen_upscale(name: &str) -> Option<&'static ImagenUpscaleModel> {
+        IMAGEN_UPSCALE_MODELS.iter().find(|model| model.matches(name))
+    }
+
+    /// The preferred Imagen upscale model supporting `factor`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use adk_rust_mcp_common::models::ModelRegistry;
+    ///
+    /// assert_eq!(ModelRegistry::imagen_upscale_for_factor("x2").map(|m| m.id), Some("imagen-4.0-upscale-preview"));
+    /// assert_eq!(ModelRegistry::imagen_upscale_for_factor("x4").map(|m| m.id), Some("imagegeneration@002"));
+    /// ```
+    pub fn imagen_upscale_for_factor(factor: &str) -> Option<&'static ImagenUpscaleModel> {
+        IMAGEN_UPSCALE_MODELS
             .iter()
-            .find(|model| model.id == name || model.aliases.contains(&name))
+            .find(|model| model.supported_factors.contains(&factor))
     }
 
     /// Resolve a Veo model name or alias to full model definition.
@@ -219,9 +476,7 @@ impl ModelRegistry {
     /// assert!(model.is_some());
     /// ```
     pub fn resolve_veo(name: &str) -> Option<&'static VeoModel> {
-        VEO_MODELS
-            .iter()
-            .find(|model| model.id == name || model.aliases.contains(&name))
+        VEO_MODELS.iter().find(|model| model.matches(name))
     }
 
     /// Resolve a Gemini model name or alias to full model definition.
@@ -235,17 +490,51 @@ impl ModelRegistry {
     /// use adk_rust_mcp_common::models::ModelRegistry;
     ///
     /// // Resolve by canonical ID
-    /// let model = ModelRegistry::resolve_gemini("gemini-2.0-flash");
-    /// assert!(model.is_some());
+    /// let model = ModelRegistry::resolve_gemini("gemini-2.5-flash-image");
+    /// assert!(model.is_some_and(|m| m.supports_image_generation));
     ///
     /// // Resolve by alias
-    /// let model = ModelRegistry::resolve_gemini("gemini-flash");
-    /// assert!(model.is_some());
+    /// let model = ModelRegistry::resolve_gemini("pro");
+    /// assert_eq!(model.map(|m| m.id), Some("gemini-2.5-pro"));
     /// ```
     pub fn resolve_gemini(name: &str) -> Option<&'static GeminiModel> {
-        GEMINI_MODELS
-            .iter()
-            .find(|model| model.id == name || model.aliases.contains(&name))
+        GEMINI_MODELS.iter().find(|model| model.matches(name))
+    }
+
+    /// Pixel size of images generated by an Imagen model (name or alias) at
+    /// `aspect_ratio`.
+    ///
+    /// Returns `None` if the model is unknown or does not support the ratio.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use adk_rust_mcp_common::models::ModelRegistry;
+    ///
+    /// let dims = ModelRegistry::imagen_dimensions("imagen-4", "9:16").unwrap();
+    /// assert_eq!((dims.width, dims.height), (768, 1408));
+    /// assert!(ModelRegistry::imagen_dimensions("imagen-4", "21:9").is_none());
+    /// ```
+    pub fn imagen_dimensions(model: &str, aspect_ratio: &str) -> Option<OutputDimensions> {
+        Self::resolve_imagen(model)?.dimensions_for(aspect_ratio)
+    }
+
+    /// Pixel size of videos generated by a Veo model (name or alias) at
+    /// `aspect_ratio`.
+    ///
+    /// Returns `None` if the model is unknown or does not support the ratio.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use adk_rust_mcp_common::models::ModelRegistry;
+    ///
+    /// let dims = ModelRegistry::veo_dimensions("veo-3", "16:9").unwrap();
+    /// assert_eq!((dims.width, dims.height), (1280, 720));
+    /// assert!(ModelRegistry::veo_dimensions("veo-3", "1:1").is_none());
+    /// ```
+    pub fn veo_dimensions(model: &str, aspect_ratio: &str) -> Option<OutputDimensions> {
+        Self::resolve_veo(model)?.dimensions_for(aspect_ratio)
     }
 
     /// List all available Imagen models.
@@ -309,9 +598,7 @@ impl ModelRegistry {
     /// assert!(model.is_some());
     /// ```
     pub fn resolve_lyria(name: &str) -> Option<&'static LyriaModel> {
-        LYRIA_MODELS
-            .iter()
-            .find(|model| model.id == name || model.aliases.contains(&name))
+        LYRIA_MODELS.iter().find(|model| model.matches(name))
     }
 
     /// List all available Lyria models.
@@ -327,6 +614,79 @@ impl ModelRegistry {
     pub fn list_lyria_models() -> &'static [LyriaModel] {
         LYRIA_MODELS
     }
+
+    /// Suggest the known model closest to an unrecognized name.
+    ///
+    /// Compares `name` case-insensitively against every canonical ID and alias
+    /// and returns the canonical ID of the nearest match, or `None` if nothing
+    /// is within a few edits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use adk_rust_mcp_common::models::{ModelRegistry, VEO_MODELS};
+    ///
+    /// assert_eq!(ModelRegistry::suggest(VEO_MODELS, "veo-3.1"), Some("veo-3.0-generate-preview"));
+    /// assert_eq!(ModelRegistry::suggest(VEO_MODELS, "dall-e-3"), None);
+    /// ```
+    pub fn suggest<M: ModelDefinition>(models: &[M], name: &str) -> Option<&'static str> {
+        let lowered = name.to_lowercase();
+        let name = lowered.as_str();
+        let max_distance = (name.chars().count() / 3).max(2);
+
+        models
+            .iter()
+            .flat_map(|model| {
+                std::iter::once(model.id())
+                    .chain(model.aliases().iter().copied())
+                    .map(move |candidate| (levenshtein(name, candidate), model.id()))
+            })
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, id)| id)
+    }
+
+    /// Build the error message for a model name that did not resolve.
+    ///
+    /// Includes the closest match when there is one, followed by the valid
+    /// canonical IDs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use adk_rust_mcp_common::models::{ModelRegistry, IMAGEN_MODELS};
+    ///
+    /// let message = ModelRegistry::unknown_model_message(IMAGEN_MODELS, "imagen-4.1");
+    /// assert!(message.contains("did you mean 'imagen-4.0-generate-preview-06-06'?"));
+    /// ```
+    pub fn unknown_model_message<M: ModelDefinition>(models: &[M], name: &str) -> String {
+        let valid = models.iter().map(|m| m.id()).collect::<Vec<_>>().join(", ");
+        match Self::suggest(models, name) {
+            Some(suggestion) => format!(
+                "Unknown model '{}'; did you mean '{}'? Valid models: {}",
+                name, suggestion, valid
+            ),
+            None => format!("Unknown model '{}'. Valid models: {}", name, valid),
+        }
+    }
+}
+
+/// Levenshtein edit distance between two strings, by character.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
 }
 
 
@@ -366,6 +726,22 @@ mod tests {
         assert!(model.is_none());
     }
 
+    #[test]
+    fn test_resolve_imagen_upscale() {
+        let model = ModelRegistry::resolve_imagen_upscale("imagegeneration@002").unwrap();
+        assert!(model.supported_factors.contains(&"x4"));
+        assert!(ModelRegistry::resolve_imagen_upscale("imagen-4").is_none());
+    }
+
+    #[test]
+    fn test_imagen_upscale_for_factor() {
+        for factor in ["x2", "x4"] {
+            let model = ModelRegistry::imagen_upscale_for_factor(factor).unwrap();
+            assert!(model.supported_factors.contains(&factor));
+        }
+        assert!(ModelRegistry::imagen_upscale_for_factor("x8").is_none());
+    }
+
     #[test]
     fn test_resolve_veo_by_id() {
         let model = ModelRegistry::resolve_veo("veo-2.0-generate-001");
@@ -392,20 +768,26 @@ mod tests {
 
     #[test]
     fn test_resolve_gemini_by_id() {
-        let model = ModelRegistry::resolve_gemini("gemini-2.0-flash");
+        let model = ModelRegistry::resolve_gemini("gemini-2.5-flash-image");
         assert!(model.is_some());
         let model = model.unwrap();
-        assert_eq!(model.id, "gemini-2.0-flash");
+        assert_eq!(model.id, "gemini-2.5-flash-image");
         assert!(model.supports_image_generation);
+        assert!(!model.supports_tts);
+
+        let model = ModelRegistry::resolve_gemini("gemini-2.5-flash-preview-tts").unwrap();
         assert!(model.supports_tts);
+        assert!(!model.supports_image_generation);
     }
 
     #[test]
     fn test_resolve_gemini_by_alias() {
-        let model = ModelRegistry::resolve_gemini("gemini-flash");
+        let model = ModelRegistry::resolve_gemini("flash");
         assert!(model.is_some());
         let model = model.unwrap();
-        assert_eq!(model.id, "gemini-2.0-flash");
+        assert_eq!(model.id, "gemini-2.5-flash");
+        assert!(model.supports_audio_input);
+        assert_eq!(ModelRegistry::resolve_gemini("pro").unwrap().id, "gemini-2.5-pro");
     }
 
     #[test]
@@ -423,7 +805,7 @@ mod tests {
     #[test]
     fn test_list_gemini_models() {
         let models = ModelRegistry::list_gemini_models();
-        assert_eq!(models.len(), 2);
+        assert_eq!(models.len(), 7);
     }
 
     #[test]
@@ -455,6 +837,14 @@ mod tests {
         assert_eq!(models.len(), 1);
     }
 
+    #[test]
+    fn test_lyria_duration_ranges() {
+        for model in LYRIA_MODELS {
+            assert!(model.min_duration_seconds > 0, "{}", model.id);
+            assert!(model.min_duration_seconds <= model.max_duration_seconds, "{}", model.id);
+        }
+    }
+
     #[test]
     fn test_imagen_model_aspect_ratios() {
         let model = ModelRegistry::resolve_imagen("imagen-3").unwrap();
@@ -463,6 +853,107 @@ mod tests {
         assert!(model.supported_aspect_ratios.contains(&"9:16"));
     }
 
+    #[test]
+    fn test_output_dimensions_cover_supported_aspect_ratios() {
+        let check = |id: &str, ratios: &[&str], dims: &[OutputDimensions]| {
+            let listed: Vec<&str> = dims.iter().map(|d| d.aspect_ratio).collect();
+            assert_eq!(listed, ratios, "{}", id);
+            for d in dims {
+                let (w, h) = d.aspect_ratio.split_once(':').unwrap();
+                let (w, h): (f64, f64) = (w.parse().unwrap(), h.parse().unwrap());
+                let actual = f64::from(d.width) / f64::from(d.height);
+                // Imagen sizes are rounded to multiples of 128, e.g. 3:4 is 896x1280
+                assert!((actual / (w / h) - 1.0).abs() < 0.1, "{} {}", id, d.aspect_ratio);
+            }
+        };
+        for model in IMAGEN_MODELS {
+            check(model.id, model.supported_aspect_ratios, model.output_dimensions);
+        }
+        for model in VEO_MODELS {
+            check(model.id, model.supported_aspect_ratios, model.output_dimensions);
+        }
+    }
+
+    #[test]
+    fn test_registry_dimensions() {
+        let dims = ModelRegistry::imagen_dimensions("imagen-3.0-generate-002", "1:1").unwrap();
+        assert_eq!((dims.width, dims.height), (1024, 1024));
+        assert_eq!(ModelRegistry::imagen_dimensions("imagen-3-fast", "4:3").unwrap().width, 1280);
+        assert!(ModelRegistry::imagen_dimensions("unknown-model", "1:1").is_none());
+
+        let dims = ModelRegistry::veo_dimensions("veo-2", "9:16").unwrap();
+        assert_eq!((dims.width, dims.height), (720, 1280));
+        assert!(ModelRegistry::veo_dimensions("veo-2", "4:3").is_none());
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("veo-3", "veo-3"), 0);
+        assert_eq!(levenshtein("veo-3", "veo3"), 1);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_suggest_nearest_model() {
+        assert_eq!(ModelRegistry::suggest(VEO_MODELS, "veo-3.1"), Some("veo-3.0-generate-preview"));
+        assert_eq!(ModelRegistry::suggest(VEO_MODELS, "VEO-2"), Some("veo-2.0-generate-001"));
+        assert_eq!(
+            ModelRegistry::suggest(IMAGEN_MODELS, "imagen-3.0-generate-001"),
+            Some("imagen-3.0-generate-002")
+        );
+        assert_eq!(ModelRegistry::suggest(IMAGEN_MODELS, "imagen-3-fst"), Some("imagen-3.0-fast-generate-001"));
+        assert_eq!(ModelRegistry::suggest(LYRIA_MODELS, "lyria-2"), Some("lyria-1.0"));
+        assert_eq!(ModelRegistry::suggest(VEO_MODELS, "sora"), None);
+        assert_eq!(ModelRegistry::suggest(IMAGEN_MODELS, "dall-e-3"), None);
+    }
+
+    #[test]
+    fn test_unknown_model_message() {
+        let message = ModelRegistry::unknown_model_message(VEO_MODELS, "veo-3.1");
+        assert_eq!(
+            message,
+            "Unknown model 'veo-3.1'; did you mean 'veo-3.0-generate-preview'? \
+             Valid models: veo-2.0-generate-001, veo-3.0-generate-preview"
+        );
+
+        let message = ModelRegistry::unknown_model_message(VEO_MODELS, "sora");
+        assert_eq!(message, "Unknown model 'sora'. Valid models: veo-2.0-generate-001, veo-3.0-generate-preview");
+    }
+
+    #[test]
+    fn test_common_aliases_resolve() {
+        for (alias, id) in [
+            ("imagen-3", "imagen-3.0-generate-002"),
+            ("imagen-3-fast", "imagen-3.0-fast-generate-001"),
+            ("imagen-4", "imagen-4.0-generate-preview-06-06"),
+            ("imagen-4.0-generate-preview", "imagen-4.0-generate-preview-06-06"),
+            ("veo-2", "veo-2.0-generate-001"),
+            ("veo-3", "veo-3.0-generate-preview"),
+            ("veo-3.0-generate", "veo-3.0-generate-preview"),
+        ] {
+            let resolved = ModelRegistry::resolve_imagen(alias)
+                .map(|m| m.id)
+                .or_else(|| ModelRegistry::resolve_veo(alias).map(|m| m.id));
+            assert_eq!(resolved, Some(id), "alias {alias}");
+        }
+    }
+
+    #[test]
+    fn test_aliases_are_unique_across_models() {
+        let mut names: Vec<&str> = Vec::new();
+        names.extend(IMAGEN_MODELS.iter().flat_map(|m| std::iter::once(m.id).chain(m.aliases.iter().copied())));
+        names.extend(IMAGEN_UPSCALE_MODELS.iter().flat_map(|m| std::iter::once(m.id).chain(m.aliases.iter().copied())));
+        names.extend(VEO_MODELS.iter().flat_map(|m| std::iter::once(m.id).chain(m.aliases.iter().copied())));
+        names.extend(GEMINI_MODELS.iter().flat_map(|m| std::iter::once(m.id).chain(m.aliases.iter().copied())));
+        names.extend(LYRIA_MODELS.iter().flat_map(|m| std::iter::once(m.id).chain(m.aliases.iter().copied())));
+        let total = names.len();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), total, "model IDs and aliases must be unique");
+    }
+
     #[test]
     fn test_veo_model_supported_durations() {
         let model = ModelRegistry::resolve_veo("veo-2").unwrap();
@@ -497,14 +988,18 @@ mod property_tests {
             Just("imagen-3"),
             Just("imagen-3.0"),
             Just("imagen3"),
+            Just("imagen-3.0-generate"),
             // Aliases for imagen-3.0-fast-generate-001
             Just("imagen-3-fast"),
             Just("imagen-3.0-fast"),
+            Just("imagen3-fast"),
+            Just("imagen-3.0-fast-generate"),
             // Aliases for imagen-4.0-generate-preview-06-06
             Just("imagen-4"),
             Just("imagen-4.0"),
             Just("imagen4"),
             Just("imagen-4-preview"),
+            Just("imagen-4.0-generate-preview"),
         ]
     }
 
@@ -518,11 +1013,13 @@ mod property_tests {
             Just("veo-2"),
             Just("veo-2.0"),
             Just("veo2"),
+            Just("veo-2.0-generate"),
             // Aliases for veo-3.0-generate-preview
             Just("veo-3"),
             Just("veo-3.0"),
             Just("veo3"),
             Just("veo-3-preview"),
+            Just("veo-3.0-generate"),
         ]
     }
 
@@ -530,10 +1027,21 @@ mod property_tests {
     fn gemini_model_identifier_strategy() -> impl Strategy<Value = &'static str> {
         prop_oneof![
             // Canonical IDs
+            Just("gemini-2.5-flash"),
+            Just("gemini-2.5-pro"),
+            Just("gemini-2.5-flash-image"),
+            Just("gemini-2.5-flash-preview-tts"),
+            Just("gemini-2.5-pro-preview-tts"),
             Just("gemini-2.0-flash"),
             Just("gemini-2.0-flash-lite"),
-            // Aliases for gemini-2.0-flash
+            // Aliases for the 2.5 models
+            Just("flash"),
             Just("gemini-flash"),
+            Just("pro"),
+            Just("flash-image"),
+            Just("flash-tts"),
+            Just("pro-tts"),
+            // Aliases for gemini-2.0-flash
             Just("gemini-2-flash"),
             // Aliases for gemini-2.0-flash-lite
             Just("gemini-flash-lite"),
@@ -38,8 +38,12 @@
 //! - `PROJECT_ID`: Google Cloud project ID for trace export
 //! - `OTEL_SERVICE_NAME`: Service name for traces (default: "adk-rust-mcp")
 //! - `RUST_LOG`: Controls log level filtering (same as standard tracing)
+//! - `MCP_LOG_REDACT`: Redaction of field values, applied to both the console
+//!   output and the exported spans (see [`RedactingExporter`])
 
+use std::borrow::Cow;
 use std::env;
+use std::time::Duration;
 use thiserror::Error;
 use tracing_subscriber::{
     fmt::{self, format::FmtSpan},
@@ -47,8 +51,15 @@ use tracing_subscriber::{
     EnvFilter,
 };
 
-// Re-export types from opentelemetry-gcloud-trace to ensure version compatibility
-use opentelemetry_gcloud_trace::GcpCloudTraceExporterBuilder;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::{KeyValue, Value};
+use opentelemetry_gcloud_trace::GcpCloudTraceExporter;
+use opentelemetry_sdk::error::OTelSdkResult;
+use opentelemetry_sdk::trace::span_processor_with_async_runtime::BatchSpanProcessor;
+use opentelemetry_sdk::trace::{SdkTracerProvider, SpanData, SpanExporter};
+use opentelemetry_sdk::{runtime, Resource};
+
+use crate::tracing::RedactingFields;
 
 /// Errors that can occur during OpenTelemetry initialization.
 #[derive(Debug, Error)]
@@ -85,6 +96,8 @@ pub struct OtelConfig {
     pub service_name: String,
     /// Default log level when RUST_LOG is not set.
     pub default_log_level: String,
+    /// Redaction applied to logged fields and exported spans.
+    pub redaction: RedactingFields,
 }
 
 impl Default for OtelConfig {
@@ -94,6 +107,7 @@ impl Default for OtelConfig {
             project_id: None,
             service_name: "adk-rust-mcp".to_string(),
             default_log_level: "info".to_string(),
+            redaction: RedactingFields::from_env(),
         }
     }
 }
@@ -129,6 +143,7 @@ impl OtelConfig {
             project_id,
             service_name,
             default_log_level: "info".to_string(),
+            redaction: RedactingFields::from_env(),
         })
     }
 
@@ -149,6 +164,12 @@ impl OtelConfig {
         self.enabled = enabled;
         self
     }
+
+    /// Set the redaction, e.g. the server's `Config::log_redaction`.
+    pub fn with_redaction(mut self, redaction: RedactingFields) -> Self {
+        self.redaction = redaction;
+        self
+    }
 }
 
 /// Guard that ensures proper shutdown of OpenTelemetry when dropped.
@@ -169,6 +190,67 @@ impl Drop for OtelGuard {
     }
 }
 
+/// Span exporter that redacts attribute values before export.
+///
+/// The `fmt` layer redacts through [`RedactingFields`], but the OpenTelemetry
+/// layer records span fields and events as raw attributes. This wrapper runs
+/// every string attribute, and each event name (the log message), through the
+/// same redaction so prompts and base64 payloads do not reach the trace
+/// backend.
+#[derive(Debug)]
+pub struct RedactingExporter<E> {
+    inner: E,
+    redaction: RedactingFields,
+}
+
+impl<E> RedactingExporter<E> {
+    /// Wrap `inner`, redacting with `redaction`.
+    pub fn new(inner: E, redaction: RedactingFields) -> Self {
+        Self { inner, redaction }
+    }
+
+    /// Redact the attributes and events of one span.
+    pub fn redact_span(&self, mut span: SpanData) -> SpanData {
+        self.redact_attributes(&mut span.attributes);
+        for event in span.events.events.iter_mut() {
+            if let Cow::Owned(name) = self.redaction.apply(&event.name) {
+                event.name = Cow::Owned(name);
+            }
+            self.redact_attributes(&mut event.attributes);
+        }
+        span
+    }
+
+    fn redact_attributes(&self, attributes: &mut [KeyValue]) {
+        for attribute in attributes {
+            if let Value::String(value) = &attribute.value {
+                if let Cow::Owned(redacted) = self.redaction.apply(value.as_str()) {
+                    attribute.value = Value::String(redacted.into());
+                }
+            }
+        }
+    }
+}
+
+impl<E: SpanExporter> SpanExporter for RedactingExporter<E> {
+    fn export(&self, batch: Vec<SpanData>) -> impl std::future::Future<Output = OTelSdkResult> + Send {
+        let batch = batch.into_iter().map(|span| self.redact_span(span)).collect();
+        self.inner.export(batch)
+    }
+
+    fn shutdown_with_timeout(&mut self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn force_flush(&mut self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
 /// Initialize OpenTelemetry tracing with Google Cloud Trace export.
 ///
 /// This function sets up the tracing subscriber with:
@@ -215,18 +297,19 @@ pub async fn init_otel_tracing(config: OtelConfig) -> Result<OtelGuard, OtelErro
 
     let project_id = config.project_id.ok_or(OtelError::MissingProjectId)?;
 
-    // Create the Google Cloud Trace exporter
-    let exporter = GcpCloudTraceExporterBuilder::new(project_id);
-
-    let provider = exporter
-        .create_provider()
+    // Create the Google Cloud Trace exporter, redacting spans on the way out
+    let redaction = config.redaction;
+    let exporter = GcpCloudTraceExporter::new(&project_id, Resource::builder_empty().build())
         .await
         .map_err(|e| OtelError::ExporterCreationFailed(e.to_string()))?;
 
-    let tracer = exporter
-        .install(&provider)
-        .await
-        .map_err(|e| OtelError::TracerInstallFailed(e.to_string()))?;
+    let provider = SdkTracerProvider::builder()
+        .with_span_processor(
+            BatchSpanProcessor::builder(RedactingExporter::new(exporter, redaction), runtime::Tokio).build(),
+        )
+        .build();
+
+    let tracer = provider.tracer(config.service_name.clone());
 
     // Set the global tracer provider
     opentelemetry::global::set_tracer_provider(provider.clone());
@@ -246,7 +329,8 @@ pub async fn init_otel_tracing(config: OtelConfig) -> Result<OtelGuard, OtelErro
         .with_thread_names(false)
         .with_file(false)
         .with_line_number(false)
-        .with_span_events(FmtSpan::NONE);
+        .with_span_events(FmtSpan::NONE)
+        .fmt_fields(redaction);
 
     // Build and set the subscriber
     tracing_subscriber::registry()
@@ -293,18 +377,19 @@ pub async fn init_otel_tracing(config: OtelConfig) -> Result<OtelGuard, OtelErro
 /// }
 /// ```
 pub async fn init_tracing_with_optional_otel(config: OtelConfig) -> Option<OtelGuard> {
+    let redaction = config.redaction;
     if config.enabled {
         match init_otel_tracing(config).await {
             Ok(guard) => Some(guard),
             Err(e) => {
                 // Fall back to standard tracing
-                init_fallback_tracing();
+                init_fallback_tracing(redaction);
                 tracing::warn!("Failed to initialize OpenTelemetry, using standard tracing: {}", e);
                 None
             }
         }
     } else {
-        init_fallback_tracing();
+        init_fallback_tracing(redaction);
         tracing::debug!("OpenTelemetry disabled, using standard tracing");
         None
     }
@@ -313,7 +398,7 @@ pub async fn init_tracing_with_optional_otel(config: OtelConfig) -> Option<OtelG
 /// Initialize standard tracing without OpenTelemetry.
 ///
 /// This is used as a fallback when OpenTelemetry is disabled or fails to initialize.
-fn init_fallback_tracing() {
+fn init_fallback_tracing(redaction: RedactingFields) {
     let env_filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new("info"));
 
@@ -324,7 +409,8 @@ fn init_fallback_tracing() {
         .with_thread_names(false)
         .with_file(false)
         .with_line_number(false)
-        .with_span_events(FmtSpan::NONE);
+        .with_span_events(FmtSpan::NONE)
+        .fmt_fields(redaction);
 
     let _ = tracing_subscriber::registry()
         .with(env_filter)
@@ -6,6 +6,7 @@
 //! - Error handling for missing configuration
 
 use super::otel::*;
+use crate::tracing::RedactingFields;
 use std::env;
 
 /// Helper to temporarily set environment variables for testing.
@@ -69,12 +70,14 @@ fn test_otel_config_builder_methods() {
     let config = OtelConfig::new("my-project")
         .with_service_name("custom-service")
         .with_default_log_level("debug")
-        .with_enabled(false);
+        .with_enabled(false)
+        .with_redaction(RedactingFields::new(64));
     
     assert!(!config.enabled);
     assert_eq!(config.project_id, Some("my-project".to_string()));
     assert_eq!(config.service_name, "custom-service");
     assert_eq!(config.default_log_level, "debug");
+    assert_eq!(config.redaction, RedactingFields::new(64));
 }
 
 #[test]
@@ -210,7 +213,7 @@ async fn test_init_otel_tracing_missing_project_id() {
         enabled: true,
         project_id: None,
         service_name: "test".to_string(),
-        default_log_level: "info".to_string(),
+        ..Default::default()
     };
     
     let result = init_otel_tracing(config).await;
@@ -237,3 +240,48 @@ async fn test_init_tracing_with_optional_otel_disabled() {
 // 1. It requires valid GCP credentials
 // 2. The global tracing subscriber can only be set once per process
 // 3. Integration tests with real GCP would be more appropriate for full initialization testing
+
+#[test]
+fn test_redacting_exporter_redacts_attributes_and_events() {
+    use opentelemetry::trace::{Event, SpanContext, SpanId, SpanKind, Status};
+    use opentelemetry::{InstrumentationScope, KeyValue};
+    use opentelemetry_sdk::trace::{SpanData, SpanEvents, SpanLinks};
+    use std::borrow::Cow;
+    use std::time::SystemTime;
+
+    let blob = "QUJD".repeat(64);
+    let mut events = SpanEvents::default();
+    events.events.push(Event::new(
+        format!("Response body: {}", blob),
+        SystemTime::now(),
+        vec![KeyValue::new("prompt", "a very long prompt indeed")],
+        0,
+    ));
+    let span = SpanData {
+        span_context: SpanContext::empty_context(),
+        parent_span_id: SpanId::INVALID,
+        span_kind: SpanKind::Internal,
+        name: Cow::Borrowed("generate_image"),
+        start_time: SystemTime::now(),
+        end_time: SystemTime::now(),
+        attributes: vec![
+            KeyValue::new("image", blob.clone()),
+            KeyValue::new("output", "gs://bucket/out.png"),
+            KeyValue::new("count", 2),
+        ],
+        dropped_attributes_count: 0,
+        events,
+        links: SpanLinks::default(),
+        status: Status::Unset,
+        instrumentation_scope: InstrumentationScope::default(),
+    };
+
+    let exporter = RedactingExporter::new((), RedactingFields::new(12));
+    let span = exporter.redact_span(span);
+
+    assert_eq!(span.attributes[0].value.as_str(), "<192 bytes>");
+    assert_eq!(span.attributes[1].value.as_str(), "gs://bucket/…[+7 chars]");
+    assert_eq!(span.attributes[2], KeyValue::new("count", 2));
+    assert_eq!(span.events[0].name, "Response bod…[+14 chars]");
+    assert_eq!(span.events[0].attributes[0].value.as_str(), "a very long …[+13 chars]");
+}
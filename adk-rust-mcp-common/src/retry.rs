@@ -0,0 +1,222 @@
+//! Retries for upstream API requests.
+//!
+//! Generation endpoints occasionally answer with 429 (quota) or 5xx while the
+//! service is busy, and connections can drop. [`send_with_retry`] resends such
+//! requests with exponential backoff and jitter, honoring `Retry-After` when the
+//! server provides one. Other client errors (e.g. 400 for a rejected prompt)
+//! are returned immediately. Quota-limited endpoints can narrow the retried
+//! statuses with [`RetryPolicy::with_retry_statuses`].
+//!
+//! This is for the initial request only; long-running operation polling has
+//! its own loop with separate limits. Other fallible steps, such as GCS
+//! downloads, can reuse the same backoff through [`retry_with_backoff`].
+
+use crate::config::Config;
+use crate::error::Error;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+use tracing::warn;
+
+/// Environment variable overriding the number of attempts per API request.
+pub const API_MAX_ATTEMPTS_ENV: &str = "API_MAX_ATTEMPTS";
+
+/// Default number of attempts per API request (including the first).
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Statuses retried by default: timeouts, rate limits and transient server errors.
+pub const DEFAULT_RETRY_STATUSES: &[u16] = &[408, 429, 500, 502, 503, 504];
+
+/// Statuses retried for quota-limited endpoints such as Imagen predict.
+pub const QUOTA_RETRY_STATUSES: &[u16] = &[429, 503];
+
+/// Longest `Retry-After` delay that will be honored.
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// How often and how patiently to retry a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first (minimum 1)
+    pub max_attempts: u32,
+    /// Backoff before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound for the exponential backoff
+    pub max_backoff: Duration,
+    /// HTTP statuses that trigger a retry
+    pub retry_statuses: &'static [u16],
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            retry_statuses: DEFAULT_RETRY_STATUSES,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Default backoff with the configured number of attempts.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_attempts: config.api_max_attempts.max(1),
+            ..Self::default()
+        }
+    }
+
+    /// Retry only on the given statuses (e.g. [`QUOTA_RETRY_STATUSES`]).
+    pub fn with_retry_statuses(mut self, statuses: &'static [u16]) -> Self {
+        self.retry_statuses = statuses;
+        self
+    }
+
+    /// Whether a response status should be retried under this policy.
+    pub fn should_retry(&self, status: StatusCode) -> bool {
+        self.retry_statuses.contains(&status.as_u16())
+    }
+
+    /// Delay before retry number `retry` (1-based), with jitter.
+    ///
+    /// The delay doubles per retry up to `max_backoff`; jitter picks a point in
+    /// the upper half so concurrent clients spread out.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(16);
+        let base = self
+            .initial_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+        let half = base / 2;
+        half + half.mul_f64(jitter_fraction())
+    }
+}
+
+/// Whether a response status is retried by the default policy.
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    DEFAULT_RETRY_STATUSES.contains(&status.as_u16())
+}
+
+/// Parse a `Retry-After` header given in seconds.
+///
+/// HTTP-date values are ignored and fall back to the normal backoff.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let seconds: u64 = headers.get(RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(seconds).min(MAX_RETRY_AFTER))
+}
+
+/// Send a request, retrying transient failures according to `policy`.
+///
+/// `build` is called once per attempt to produce a fresh request. The final
+/// response is returned whatever its status, so callers keep their own error
+/// handling for non-success responses.
+///
+/// Requests that never reached the server (connection failures) are always
+/// retried. A timeout may mean the server already acted on the request, so it
+/// is retried only for idempotent methods such as GET; a timed-out POST is
+/// returned as an error rather than resent.
+///
+/// # Errors
+/// Returns `Error::Api` with status 0 if the request could not be built, or
+/// could not be sent on the last attempt.
+pub async fn send_with_retry<F>(policy: &RetryPolicy, endpoint: &str, build: F) -> Result<Response, Error>
+where
+    F: Fn() -> RequestBuilder,
+{
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+
+    loop {
+        let (client, request) = build().build_split();
+        let request = request.map_err(|e| Error::api(endpoint, 0, format!("Invalid request: {}", e)))?;
+        let idempotent = request.method().is_idempotent();
+
+        let (delay, reason) = match client.execute(request).await {
+            Ok(response) => {
+                let status = response.status();
+                if !policy.should_retry(status) || attempt >= max_attempts {
+                    return Ok(response);
+                }
+                let delay = retry_after(response.headers()).unwrap_or_else(|| policy.backoff(attempt));
+                (delay, format!("HTTP {}", status.as_u16()))
+            }
+            Err(e) => {
+                if !is_retryable_error(&e, idempotent) || attempt >= max_attempts {
+                    return Err(Error::api(endpoint, 0, format!("Request failed: {}", e)));
+                }
+                (policy.backoff(attempt), e.to_string())
+            }
+        };
+
+        warn!(
+            endpoint = %endpoint,
+            attempt,
+            max_attempts,
+            delay_ms = delay.as_millis() as u64,
+            reason = %reason,
+            "Retrying API request"
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Run a fallible operation, retrying transient failures according to `policy`.
+///
+/// `operation` is called once per attempt with the 1-based attempt number, so
+/// it can reset any partial state (e.g. truncate a file) before trying again.
+/// Errors for which `is_transient` returns false end the loop immediately.
+///
+/// # Errors
+/// Returns the error of the last attempt.
+pub async fn retry_with_backoff<T, E, F, Fut>(
+    policy: &RetryPolicy,
+    name: &str,
+    mut operation: F,
+    is_transient: impl Fn(&E) -> bool,
+) -> Result<T, E>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Display,
+{
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+
+    loop {
+        match operation(attempt).await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt >= max_attempts || !is_transient(&e) => return Err(e),
+            Err(e) => {
+                let delay = policy.backoff(attempt);
+                warn!(
+                    operation = %name,
+                    attempt,
+                    max_attempts,
+                    delay_ms = delay.as_millis() as u64,
+                    reason = %e,
+                    "Retrying operation"
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Whether a transport error is safe to retry: the connection could not be
+/// made, or an idempotent request timed out.
+fn is_retryable_error(error: &reqwest::Error, idempotent: bool) -> bool {
+    error.is_connect() || (idempotent && error.is_timeout())
+}
+
+/// Pseudo-random fraction in [0, 1) for jitter; does not need to be unpredictable.
+fn jitter_fraction() -> f64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    f64::from(nanos % 1_000) / 1_000.0
+}
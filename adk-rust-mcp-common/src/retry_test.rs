@@ -0,0 +1,282 @@
+//! Unit tests for the retry module.
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::retry::{
+    QUOTA_RETRY_STATUSES, RetryPolicy, is_retryable_status, retry_after, retry_with_backoff, send_with_retry,
+};
+
+fn fast_policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(5),
+        ..RetryPolicy::default()
+    }
+}
+
+#[test]
+fn retryable_statuses() {
+    for code in [408, 429, 500, 502, 503, 504] {
+        assert!(is_retryable_status(StatusCode::from_u16(code).unwrap()), "{code}");
+    }
+    for code in [200, 400, 401, 403, 404, 501] {
+        assert!(!is_retryable_status(StatusCode::from_u16(code).unwrap()), "{code}");
+    }
+}
+
+#[test]
+fn retry_after_parses_seconds_and_caps() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(retry_after(&headers), None);
+
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("3"));
+    assert_eq!(retry_after(&headers), Some(Duration::from_secs(3)));
+
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("86400"));
+    assert_eq!(retry_after(&headers), Some(Duration::from_secs(60)));
+
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+    assert_eq!(retry_after(&headers), None);
+}
+
+#[test]
+fn backoff_grows_and_is_bounded() {
+    let policy = RetryPolicy {
+        max_attempts: 5,
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(300),
+        ..RetryPolicy::default()
+    };
+    let first = policy.backoff(1);
+    assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+    let second = policy.backoff(2);
+    assert!(second >= Duration::from_millis(100) && second <= Duration::from_millis(200));
+    for retry in 3..10 {
+        assert!(policy.backoff(retry) <= Duration::from_millis(300));
+    }
+}
+
+#[tokio::test]
+async fn retries_503_then_succeeds() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/predict"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/predict"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = reqwest::Client::new();
+    let endpoint = format!("{}/predict", server.uri());
+    let response = send_with_retry(&fast_policy(3), &endpoint, || client.post(&endpoint))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text().await.unwrap(), "ok");
+}
+
+#[tokio::test]
+async fn quota_policy_retries_429_twice_then_succeeds() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/predict"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/predict"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = reqwest::Client::new();
+    let endpoint = format!("{}/predict", server.uri());
+    let policy = fast_policy(5).with_retry_statuses(QUOTA_RETRY_STATUSES);
+    let response = send_with_retry(&policy, &endpoint, || client.post(&endpoint))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn quota_policy_does_not_retry_500() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/predict"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = reqwest::Client::new();
+    let endpoint = format!("{}/predict", server.uri());
+    let policy = fast_policy(5).with_retry_statuses(QUOTA_RETRY_STATUSES);
+    let response = send_with_retry(&policy, &endpoint, || client.post(&endpoint))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn bad_request_is_not_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/predict"))
+        .respond_with(ResponseTemplate::new(400).set_body_string("bad prompt"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = reqwest::Client::new();
+    let endpoint = format!("{}/predict", server.uri());
+    let response = send_with_retry(&fast_policy(3), &endpoint, || client.post(&endpoint))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn timed_out_post_is_not_resent() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/predict"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(500)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = reqwest::Client::builder().timeout(Duration::from_millis(50)).build().unwrap();
+    let endpoint = format!("{}/predict", server.uri());
+    let err = send_with_retry(&fast_policy(3), &endpoint, || client.post(&endpoint))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, crate::error::Error::Api { status_code: 0, .. }), "{err}");
+}
+
+#[tokio::test]
+async fn timed_out_get_is_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/operation"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(500)))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let client = reqwest::Client::builder().timeout(Duration::from_millis(50)).build().unwrap();
+    let endpoint = format!("{}/operation", server.uri());
+    let result = send_with_retry(&fast_policy(3), &endpoint, || client.get(&endpoint)).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn connection_failure_is_retried_for_post() {
+    // Bind and drop a listener so the port refuses connections
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let endpoint = format!("http://127.0.0.1:{}/predict", port);
+    let client = reqwest::Client::new();
+    let attempts = std::sync::atomic::AtomicU32::new(0);
+
+    let result = send_with_retry(&fast_policy(3), &endpoint, || {
+        attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        client.post(&endpoint)
+    })
+    .await;
+    assert!(result.is_err());
+    assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/predict"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = reqwest::Client::new();
+    let endpoint = format!("{}/predict", server.uri());
+    let response = send_with_retry(&fast_policy(2), &endpoint, || client.post(&endpoint))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn operation_retries_transient_errors_until_success() {
+    let mut attempts = Vec::new();
+    let result: Result<&str, String> = retry_with_backoff(
+        &fast_policy(3),
+        "download",
+        |attempt| {
+            attempts.push(attempt);
+            async move { if attempt < 3 { Err("busy".to_string()) } else { Ok("done") } }
+        },
+        |_| true,
+    )
+    .await;
+    assert_eq!(result.unwrap(), "done");
+    assert_eq!(attempts, vec![1, 2, 3]);
+}
+
+#[tokio::test]
+async fn operation_stops_on_permanent_error_and_after_max_attempts() {
+    let mut calls = 0;
+    let result: Result<(), String> = retry_with_backoff(
+        &fast_policy(5),
+        "download",
+        |_| {
+            calls += 1;
+            async { Err("not found".to_string()) }
+        },
+        |e| e != "not found",
+    )
+    .await;
+    assert_eq!(result.unwrap_err(), "not found");
+    assert_eq!(calls, 1);
+
+    let mut calls = 0;
+    let result: Result<(), String> = retry_with_backoff(
+        &fast_policy(2),
+        "download",
+        |_| {
+            calls += 1;
+            async { Err("busy".to_string()) }
+        },
+        |_| true,
+    )
+    .await;
+    assert_eq!(result.unwrap_err(), "busy");
+    assert_eq!(calls, 2);
+}
+
+#[test]
+fn policy_from_config_uses_max_attempts() {
+    let mut config = crate::config::Config {
+        project_id: "test-project".to_string(),
+        api_max_attempts: 5,
+        ..Default::default()
+    };
+    assert_eq!(RetryPolicy::from_config(&config).max_attempts, 5);
+
+    config.api_max_attempts = 0;
+    assert_eq!(RetryPolicy::from_config(&config).max_attempts, 1);
+}
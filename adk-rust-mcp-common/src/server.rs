@@ -1,23 +1,33 @@
 //! MCP Server builder utilities.
 //!
 //! This module provides a consistent pattern for building and running MCP servers
-//! with support for multiple transport modes and graceful shutdown.
+//! with support for multiple transport modes and graceful shutdown. Every
+//! server also gets the `server_info` tool from [`crate::server_info`].
+//!
+//! Long-running tools can push intermediate content to the client while the
+//! call is in flight with [`ContentSink`], on transports that support it.
 //!
 //! # Example
 //!
 //! ```ignore
 //! use adk_rust_mcp_common::server::McpServerBuilder;
+//! use adk_rust_mcp_common::server_info::ServerIdentity;
 //! use adk_rust_mcp_common::transport::Transport;
 //!
 //! let handler = MyHandler::new();
 //! McpServerBuilder::new(handler)
+//!     .with_server_identity(ServerIdentity::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")))
 //!     .with_transport(Transport::stdio())
 //!     .run()
 //!     .await?;
 //! ```
 
-use crate::transport::Transport;
+use crate::server_info::{ServerIdentity, WithServerInfo};
+use crate::transport::{DEFAULT_SSE_KEEP_ALIVE_SECS, Transport};
+use rmcp::model::{Extensions, ProgressNotificationParam, ProgressToken};
+use rmcp::service::{Peer, RequestContext, RoleServer};
 use rmcp::{ServerHandler, ServiceExt};
+use std::time::Duration;
 use thiserror::Error;
 use tokio::sync::oneshot;
 
@@ -49,6 +59,8 @@ pub struct McpServerBuilder<H> {
     handler: H,
     transport: Transport,
     shutdown_rx: Option<oneshot::Receiver<()>>,
+    sse_keep_alive: Option<Duration>,
+    identity: Option<ServerIdentity>,
 }
 
 impl<H> McpServerBuilder<H>
@@ -61,15 +73,35 @@ where
             handler,
             transport: Transport::default(),
             shutdown_rx: None,
+            sse_keep_alive: Some(Duration::from_secs(DEFAULT_SSE_KEEP_ALIVE_SECS)),
+            identity: None,
         }
     }
 
+    /// Set the name, version, configuration and extras reported by the
+    /// `server_info` tool.
+    pub fn with_server_identity(mut self, identity: ServerIdentity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
     /// Set the transport mode for the server.
     pub fn with_transport(mut self, transport: Transport) -> Self {
         self.transport = transport;
         self
     }
 
+    /// Set the interval between keep-alive pings on SSE streams (HTTP and SSE
+    /// transports), or `None` to disable them.
+    ///
+    /// Pings are SSE comment frames that clients ignore; they stop reverse
+    /// proxies from closing a stream that is idle while a long operation, such
+    /// as video generation, is still running.
+    pub fn with_sse_keep_alive(mut self, interval: Option<Duration>) -> Self {
+        self.sse_keep_alive = interval;
+        self
+    }
+
     /// Set a shutdown signal receiver for graceful shutdown.
     ///
     /// When the sender is dropped or a message is sent, the server
@@ -109,8 +141,7 @@ where
         };
 
         // Run the server
-        let service = self
-            .handler
+        let service = WithServerInfo::new(self.handler, self.identity)
             .serve(transport)
             .await
             .map_err(|e| ServerError::Transport(e.to_string()))?;
@@ -130,14 +161,17 @@ where
     /// Run the server with HTTP streamable transport.
     async fn run_http(self, port: u16) -> Result<(), ServerError> {
         use rmcp::transport::streamable_http_server::{
-            session::local::LocalSessionManager, StreamableHttpService,
+            session::local::LocalSessionManager, StreamableHttpServerConfig, StreamableHttpService,
         };
 
-        let handler = self.handler.clone();
+        let handler = WithServerInfo::new(self.handler, self.identity);
         let service = StreamableHttpService::new(
             move || Ok(handler.clone()),
             LocalSessionManager::default().into(),
-            Default::default(),
+            StreamableHttpServerConfig {
+                sse_keep_alive: self.sse_keep_alive,
+                ..Default::default()
+            },
         );
 
         let router = axum::Router::new().nest_service("/mcp", service);
@@ -150,7 +184,7 @@ where
                 message: e.to_string(),
             })?;
 
-        tracing::info!(port, "HTTP server listening");
+        tracing::info!(port, sse_keep_alive = ?self.sse_keep_alive, "HTTP server listening");
 
         // Set up graceful shutdown
         let shutdown_future = async {
@@ -181,6 +215,83 @@ where
     }
 }
 
+/// Pushes intermediate content for a tool call that is still running.
+///
+/// Each piece is sent as an MCP progress notification for the request's
+/// `progressToken`: `progress` counts the pieces sent so far, `total` is the
+/// expected number of pieces (when known) and `message` carries the content.
+/// Clients that ignore the messages still see ordinary progress.
+///
+/// Only the streamable HTTP transport (also used for SSE) delivers
+/// notifications while a call is in flight. On stdio, or when the client sent
+/// no progress token, [`ContentSink::for_request`] returns `None` and the tool
+/// should return its result in one piece.
+#[derive(Clone)]
+pub struct ContentSink {
+    peer: Peer<RoleServer>,
+    progress_token: ProgressToken,
+    sent: u32,
+}
+
+impl ContentSink {
+    /// Sink for the request described by `context`, if it can stream.
+    pub fn for_request(context: &RequestContext<RoleServer>) -> Option<Self> {
+        if !supports_partial_content(&context.extensions) {
+            return None;
+        }
+        let progress_token = context.meta.get_progress_token()?;
+        Some(Self {
+            peer: context.peer.clone(),
+            progress_token,
+            sent: 0,
+        })
+    }
+
+    /// Number of pieces sent so far.
+    pub fn sent(&self) -> u32 {
+        self.sent
+    }
+
+    /// Send the next piece of content.
+    ///
+    /// # Errors
+    /// Returns `ServerError::Transport` if the notification cannot be sent,
+    /// e.g. because the client disconnected.
+    pub async fn push(&mut self, content: impl Into<String>, total: Option<u32>) -> Result<(), ServerError> {
+        self.sent += 1;
+        let notification =
+            partial_content_notification(self.progress_token.clone(), self.sent, total, content.into());
+        self.peer
+            .notify_progress(notification)
+            .await
+            .map_err(|e| ServerError::Transport(e.to_string()))
+    }
+}
+
+/// Whether a request arrived over a transport that delivers notifications
+/// during a tool call.
+///
+/// The streamable HTTP transport attaches the HTTP request parts to every
+/// request it dispatches; stdio requests carry none.
+pub fn supports_partial_content(extensions: &Extensions) -> bool {
+    extensions.get::<axum::http::request::Parts>().is_some()
+}
+
+/// Progress notification carrying the `index`-th (1-based) piece of content.
+pub fn partial_content_notification(
+    progress_token: ProgressToken,
+    index: u32,
+    total: Option<u32>,
+    content: String,
+) -> ProgressNotificationParam {
+    ProgressNotificationParam {
+        progress_token,
+        progress: f64::from(index),
+        total: total.map(f64::from),
+        message: Some(content),
+    }
+}
+
 /// Wait for a shutdown signal (SIGTERM or SIGINT).
 async fn wait_for_shutdown_signal() {
     #[cfg(unix)]
@@ -1,6 +1,9 @@
 //! Unit tests for server builder utilities.
 
-use super::server::{ServerError, shutdown_channel};
+use super::server::{
+    ServerError, partial_content_notification, shutdown_channel, supports_partial_content,
+};
+use rmcp::model::{Extensions, NumberOrString, ProgressToken};
 
 #[test]
 fn test_server_error_bind_failed_display() {
@@ -66,3 +69,30 @@ async fn test_shutdown_channel_async() {
     let result = rx.await;
     assert!(result.is_ok(), "Should receive shutdown signal");
 }
+
+#[test]
+fn test_partial_content_only_over_http() {
+    let mut extensions = Extensions::new();
+    assert!(!supports_partial_content(&extensions));
+
+    let (parts, _) = axum::http::Request::builder()
+        .uri("/mcp")
+        .body(())
+        .unwrap()
+        .into_parts();
+    extensions.insert(parts);
+    assert!(supports_partial_content(&extensions));
+}
+
+#[test]
+fn test_partial_content_notification() {
+    let token = ProgressToken(NumberOrString::String("tts-1".into()));
+    let notification = partial_content_notification(token.clone(), 2, Some(5), "chunk".to_string());
+    assert_eq!(notification.progress_token, token);
+    assert_eq!(notification.progress, 2.0);
+    assert_eq!(notification.total, Some(5.0));
+    assert_eq!(notification.message.as_deref(), Some("chunk"));
+
+    let open_ended = partial_content_notification(token, 1, None, String::new());
+    assert_eq!(open_ended.total, None);
+}
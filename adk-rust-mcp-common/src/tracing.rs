@@ -23,6 +23,10 @@
 //!   - `RUST_LOG=debug` - Enable debug logging for all modules
 //!   - `RUST_LOG=adk_rust_mcp_image=debug` - Enable debug for specific crate
 //!   - `RUST_LOG=warn,adk_rust_mcp_common=debug` - Warn by default, debug for common
+//! - `MCP_LOG_REDACT`: Controls redaction of logged field values (see [`RedactingFields`]):
+//!   - unset, `true` or `on` - Redact, truncating values over 256 characters
+//!   - a number, e.g. `MCP_LOG_REDACT=80` - Redact, truncating values over that length
+//!   - `false`, `off` or `0` - Log values unchanged
 //!
 //! # Log Format
 //!
@@ -30,14 +34,201 @@
 //! - Timestamp (ISO 8601 format)
 //! - Log level (ERROR, WARN, INFO, DEBUG, TRACE)
 //! - Target module
-//! - Message and structured fields
+//! - Message and structured fields, with long values truncated and base64
+//!   blobs replaced by `<N bytes>` placeholders
 
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::time::Instant;
+use tracing::Span;
+use tracing::field::{Field, Visit};
 use tracing_subscriber::{
-    fmt::{self, format::FmtSpan},
+    field::RecordFields,
+    fmt::{self, format::{FmtSpan, Writer}, FormatFields},
     prelude::*,
     EnvFilter,
 };
 
+/// Environment variable controlling log redaction.
+pub const LOG_REDACT_ENV: &str = "MCP_LOG_REDACT";
+
+/// Default maximum length of a logged value before it is truncated.
+pub const DEFAULT_REDACT_MAX_LEN: usize = 256;
+
+/// Shortest run of base64 characters treated as an encoded blob.
+pub const MIN_BASE64_BLOB_LEN: usize = 128;
+
+/// Field formatter that keeps prompts and media payloads out of the logs.
+///
+/// Used with the `fmt` layer in place of the default field formatter. Runs of
+/// base64 characters (e.g. image data in a raw API response) become
+/// `<N bytes>`, and values longer than the limit are cut short with a note of
+/// how much was dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RedactingFields {
+    /// Maximum value length, or `None` to log values unchanged
+    max_len: Option<usize>,
+}
+
+impl RedactingFields {
+    /// Redact values longer than `max_len` characters.
+    pub fn new(max_len: usize) -> Self {
+        Self { max_len: Some(max_len) }
+    }
+
+    /// Log values unchanged.
+    pub fn disabled() -> Self {
+        Self { max_len: None }
+    }
+
+    /// Configure redaction from `MCP_LOG_REDACT`.
+    pub fn from_env() -> Self {
+        Self::from_setting(std::env::var(LOG_REDACT_ENV).ok().as_deref())
+    }
+
+    /// Configure redaction from an `MCP_LOG_REDACT` value.
+    pub fn from_setting(setting: Option<&str>) -> Self {
+        let Some(setting) = setting.map(str::trim).filter(|s| !s.is_empty()) else {
+            return Self::new(DEFAULT_REDACT_MAX_LEN);
+        };
+        match setting.to_ascii_lowercase().as_str() {
+            "false" | "off" | "no" | "0" => Self::disabled(),
+            other => other
+                .parse()
+                .map(Self::new)
+                .unwrap_or_else(|_| Self::new(DEFAULT_REDACT_MAX_LEN)),
+        }
+    }
+
+    /// Apply the configured redaction to a value.
+    pub fn apply<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        match self.max_len {
+            Some(max_len) => redact(value, max_len),
+            None => Cow::Borrowed(value),
+        }
+    }
+}
+
+impl Default for RedactingFields {
+    fn default() -> Self {
+        Self::new(DEFAULT_REDACT_MAX_LEN)
+    }
+}
+
+impl<'writer> FormatFields<'writer> for RedactingFields {
+    fn format_fields<R: RecordFields>(&self, writer: Writer<'writer>, fields: R) -> std::fmt::Result {
+        let mut visitor = RedactingVisitor {
+            writer,
+            redaction: *self,
+            is_empty: true,
+            result: Ok(()),
+        };
+        fields.record(&mut visitor);
+        visitor.result
+    }
+}
+
+/// Writes fields like the default formatter, passing each value through redaction.
+struct RedactingVisitor<'writer> {
+    writer: Writer<'writer>,
+    redaction: RedactingFields,
+    is_empty: bool,
+    result: std::fmt::Result,
+}
+
+impl RedactingVisitor<'_> {
+    fn write_value(&mut self, field: &Field, value: &str, quoted: bool) {
+        if self.result.is_err() {
+            return;
+        }
+        let value = self.redaction.apply(value);
+        let separator = if self.is_empty { "" } else { " " };
+        self.is_empty = false;
+        self.result = match (field.name(), quoted) {
+            ("message", _) => write!(self.writer, "{}{}", separator, value),
+            (name, true) => write!(self.writer, "{}{}={:?}", separator, name, value),
+            (name, false) => write!(self.writer, "{}{}={}", separator, name, value),
+        };
+    }
+}
+
+impl Visit for RedactingVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.write_value(field, value, true);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        let mut formatted = String::new();
+        let _ = write!(formatted, "{:?}", value);
+        self.write_value(field, &formatted, false);
+    }
+}
+
+/// Replace base64 blobs with `<N bytes>` and truncate to `max_len` characters.
+///
+/// # Example
+///
+/// ```
+/// use adk_rust_mcp_common::tracing::redact;
+///
+/// let blob = "A".repeat(400);
+/// assert_eq!(redact(&format!("data: {}", blob), 1000), "data: <300 bytes>");
+/// assert_eq!(redact("a short prompt", 1000), "a short prompt");
+/// ```
+pub fn redact(value: &str, max_len: usize) -> Cow<'_, str> {
+    let without_blobs = replace_base64_blobs(value);
+    let char_count = without_blobs.chars().count();
+    if char_count <= max_len {
+        return without_blobs;
+    }
+
+    let kept: String = without_blobs.chars().take(max_len).collect();
+    Cow::Owned(format!("{}…[+{} chars]", kept, char_count - max_len))
+}
+
+/// Replace runs of at least [`MIN_BASE64_BLOB_LEN`] base64 characters with `<N bytes>`.
+///
+/// A run that starts a URI path (right after `scheme:`, e.g. a long
+/// `gs://bucket/renders/...` object name) is kept, since it names a location
+/// rather than carrying data.
+fn replace_base64_blobs(value: &str) -> Cow<'_, str> {
+    let is_base64 = |b: u8| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'-' | b'_');
+    if value.len() < MIN_BASE64_BLOB_LEN {
+        return Cow::Borrowed(value);
+    }
+
+    let bytes = value.as_bytes();
+    let mut out = String::new();
+    let mut copied = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if !is_base64(bytes[i]) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < bytes.len() && is_base64(bytes[i]) {
+            i += 1;
+        }
+        let data_len = i - start;
+        // Runs are ASCII, so `start` and `i` are always char boundaries.
+        let padding = bytes[i..].iter().take(2).take_while(|&&b| b == b'=').count();
+        let is_uri_path = value[..start].ends_with(':') && value[start..].starts_with("//");
+        if data_len >= MIN_BASE64_BLOB_LEN && !is_uri_path {
+            out.push_str(&value[copied..start]);
+            let _ = write!(out, "<{} bytes>", data_len * 3 / 4);
+            i += padding;
+            copied = i;
+        }
+    }
+
+    if copied == 0 {
+        return Cow::Borrowed(value);
+    }
+    out.push_str(&value[copied..]);
+    Cow::Owned(out)
+}
+
 /// Initialize the tracing subscriber with environment-based filtering.
 ///
 /// This function sets up the tracing subscriber with:
@@ -45,6 +236,7 @@ use tracing_subscriber::{
 /// - Timestamps in ISO 8601 format
 /// - Target module names
 /// - Span events for debugging async code
+/// - Field redaction controlled by `MCP_LOG_REDACT`
 ///
 /// # Panics
 ///
@@ -72,7 +264,8 @@ pub fn init_tracing() {
         .with_thread_names(false)
         .with_file(false)
         .with_line_number(false)
-        .with_span_events(FmtSpan::NONE);
+        .with_span_events(FmtSpan::NONE)
+        .fmt_fields(RedactingFields::from_env());
 
     tracing_subscriber::registry()
         .with(env_filter)
@@ -110,7 +303,8 @@ pub fn init_tracing_with_default(default_level: &str) {
         .with_thread_names(false)
         .with_file(false)
         .with_line_number(false)
-        .with_span_events(FmtSpan::NONE);
+        .with_span_events(FmtSpan::NONE)
+        .fmt_fields(RedactingFields::from_env());
 
     tracing_subscriber::registry()
         .with(env_filter)
@@ -138,6 +332,7 @@ pub fn init_tracing_with_default(default_level: &str) {
 /// let result = try_init_tracing();
 /// // result is Ok(()) or Err(()) depending on prior initialization
 /// ```
+#[allow(clippy::result_unit_err)] // public API; the only failure is "already initialized"
 pub fn try_init_tracing() -> Result<(), ()> {
     let env_filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new("info"));
@@ -149,7 +344,8 @@ pub fn try_init_tracing() -> Result<(), ()> {
         .with_thread_names(false)
         .with_file(false)
         .with_line_number(false)
-        .with_span_events(FmtSpan::NONE);
+        .with_span_events(FmtSpan::NONE)
+        .fmt_fields(RedactingFields::from_env());
 
     tracing_subscriber::registry()
         .with(env_filter)
@@ -158,6 +354,46 @@ pub fn try_init_tracing() -> Result<(), ()> {
         .map_err(|_| ())
 }
 
+/// Run `f` with console logging configured from the environment alone.
+///
+/// Servers load their configuration before installing the global subscriber,
+/// since the configuration decides how logs are redacted; this keeps warnings
+/// raised while loading it, such as unknown file keys, visible.
+pub fn with_startup_logging<T>(f: impl FnOnce() -> T) -> T {
+    let subscriber = tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .with(fmt::layer().fmt_fields(RedactingFields::from_env()));
+    tracing::subscriber::with_default(subscriber, f)
+}
+
+/// Records how long an operation took into its span's `duration_ms` field.
+///
+/// Create one at the top of an instrumented function whose span declares
+/// `duration_ms = tracing::field::Empty`; the elapsed milliseconds are
+/// recorded when the timer is dropped, on every return path. With the `otel`
+/// feature the field becomes a span attribute.
+#[derive(Debug)]
+pub struct SpanTimer {
+    span: Span,
+    started: Instant,
+}
+
+impl SpanTimer {
+    /// Start timing the current span.
+    pub fn start() -> Self {
+        Self {
+            span: Span::current(),
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Drop for SpanTimer {
+    fn drop(&mut self) {
+        self.span.record("duration_ms", self.started.elapsed().as_millis() as u64);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,6 +420,113 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_redact_replaces_base64_blobs() {
+        let blob = "iVBORw0KGgo".repeat(20);
+        let json = format!(r#"{{"bytesBase64Encoded":"{}=="}}"#, blob);
+        let redacted = redact(&json, 1000);
+        assert_eq!(redacted, format!(r#"{{"bytesBase64Encoded":"<{} bytes>"}}"#, blob.len() * 3 / 4));
+    }
+
+    #[test]
+    fn test_redact_keeps_gcs_paths() {
+        let uri = format!("gs://media-bucket/{}/out.png", "renders/2026-10-17/batch_0042".repeat(5));
+        assert_eq!(redact(&uri, 1000), uri);
+
+        let message = format!("Uploaded to {} ({} bytes)", uri, 1024);
+        assert_eq!(redact(&message, 1000), message);
+    }
+
+    #[test]
+    fn test_redact_keeps_short_values_and_words() {
+        assert!(matches!(redact("A cat in the rain", 256), Cow::Borrowed(_)));
+        let sentence = "a watercolor painting of a lighthouse at dusk ".repeat(3);
+        assert_eq!(redact(&sentence, 1000), sentence);
+    }
+
+    #[test]
+    fn test_redact_truncates_long_values() {
+        let prompt = "word ".repeat(100);
+        let redacted = redact(&prompt, 20);
+        assert!(redacted.starts_with("word word word word "));
+        assert!(redacted.ends_with("…[+480 chars]"), "{}", redacted);
+    }
+
+    #[test]
+    fn test_redacting_fields_from_setting() {
+        assert_eq!(RedactingFields::from_setting(None), RedactingFields::new(DEFAULT_REDACT_MAX_LEN));
+        assert_eq!(RedactingFields::from_setting(Some("on")), RedactingFields::new(DEFAULT_REDACT_MAX_LEN));
+        assert_eq!(RedactingFields::from_setting(Some("80")), RedactingFields::new(80));
+        assert_eq!(RedactingFields::from_setting(Some("off")), RedactingFields::disabled());
+        assert_eq!(RedactingFields::from_setting(Some("0")), RedactingFields::disabled());
+        assert_eq!(RedactingFields::disabled().apply(&"x".repeat(500)).len(), 500);
+    }
+
+    #[test]
+    fn test_redacting_fields_formats_events() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Buffer(Arc<Mutex<Vec<u8>>>);
+        impl std::io::Write for Buffer {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .fmt_fields(RedactingFields::new(12))
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            let data = "QUJD".repeat(64);
+            tracing::info!(prompt = %"a very long prompt indeed", data = %data, "Generating");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("Generating prompt=a very long …[+13 chars] data=<192 bytes>"), "{}", output);
+    }
+
+    #[test]
+    fn test_span_timer_records_duration() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Buffer(Arc<Mutex<Vec<u8>>>);
+        impl std::io::Write for Buffer {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .with_span_events(FmtSpan::CLOSE)
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("gcs_upload", duration_ms = tracing::field::Empty);
+            let _entered = span.enter();
+            let _timer = SpanTimer::start();
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("gcs_upload{duration_ms="), "{}", output);
+    }
+
     #[test]
     fn test_env_filter_parses_module_specific() {
         // Verify module-specific filters work
@@ -23,8 +23,18 @@
 //! let transport = args.transport.into_transport();
 //! ```
 
+use crate::config::{CONFIG_FILE_ENV, Config};
+use crate::error::ConfigError;
 use clap::Args;
 use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Default interval in seconds between SSE keep-alive pings.
+pub const DEFAULT_SSE_KEEP_ALIVE_SECS: u64 = 15;
+
+/// Default port for HTTP/SSE transport.
+pub const DEFAULT_PORT: u16 = 8080;
 
 /// Transport mode for MCP server communication.
 ///
@@ -119,9 +129,15 @@ pub struct TransportArgs {
     #[arg(long, default_value = "stdio", value_parser = parse_transport_mode)]
     pub transport: TransportMode,
 
-    /// Port for HTTP/SSE transport (default: 8080, or from PORT env var)
-    #[arg(long, env = "PORT", default_value = "8080")]
-    pub port: u16,
+    /// Port for HTTP/SSE transport (default: the configured port, from the
+    /// server's `<PREFIX>_PORT` or `PORT` env var, the config file, or 8080)
+    #[arg(long)]
+    pub port: Option<u16>,
+
+    /// Seconds between keep-alive pings on open SSE streams, so proxies do not
+    /// close idle connections during long operations; 0 disables them
+    #[arg(long, env = "SSE_KEEP_ALIVE_SECS", default_value_t = DEFAULT_SSE_KEEP_ALIVE_SECS)]
+    pub sse_keep_alive: u64,
 }
 
 /// Transport mode parsed from command line.
@@ -148,19 +164,74 @@ fn parse_transport_mode(s: &str) -> Result<TransportMode, String> {
 impl TransportArgs {
     /// Convert command-line arguments into a Transport configuration.
     pub fn into_transport(self) -> Transport {
+        self.into_transport_with_port(DEFAULT_PORT)
+    }
+
+    /// Convert command-line arguments into a Transport configuration, using
+    /// `default_port` (usually [`Config::port`]) when `--port` is not given.
+    pub fn into_transport_with_port(self, default_port: u16) -> Transport {
+        let port = self.port.unwrap_or(default_port);
         match self.transport {
             TransportMode::Stdio => Transport::Stdio,
-            TransportMode::Http => Transport::Http { port: self.port },
-            TransportMode::Sse => Transport::Sse { port: self.port },
+            TransportMode::Http => Transport::Http { port },
+            TransportMode::Sse => Transport::Sse { port },
         }
     }
+
+    /// Keep-alive ping interval for SSE streams, or `None` when disabled.
+    pub fn sse_keep_alive(&self) -> Option<Duration> {
+        (self.sse_keep_alive > 0).then(|| Duration::from_secs(self.sse_keep_alive))
+    }
 }
 
 impl Default for TransportArgs {
     fn default() -> Self {
         Self {
             transport: TransportMode::Stdio,
-            port: 8080,
+            port: None,
+            sse_keep_alive: DEFAULT_SSE_KEEP_ALIVE_SECS,
         }
     }
 }
+
+/// Command-line arguments for the configuration file.
+///
+/// Flatten next to [`TransportArgs`] to add `--config`:
+///
+/// ```ignore
+/// #[derive(Parser)]
+/// struct MyArgs {
+///     #[command(flatten)]
+///     config: ConfigArgs,
+///     #[command(flatten)]
+///     transport: TransportArgs,
+/// }
+///
+/// let args = MyArgs::parse();
+/// let config = args.config.load_with_prefix("VIDEO_")?;
+/// ```
+#[derive(Args, Debug, Clone, Default)]
+pub struct ConfigArgs {
+    /// TOML configuration file; environment variables override its values
+    #[arg(long = "config", env = CONFIG_FILE_ENV, value_name = "PATH")]
+    pub config_file: Option<PathBuf>,
+}
+
+impl ConfigArgs {
+    /// Load the configuration from the file (if any) and the environment.
+    ///
+    /// # Errors
+    /// See [`Config::load`].
+    pub fn load(&self) -> Result<Config, ConfigError> {
+        Config::load(self.config_file.as_deref())
+    }
+
+    /// Load the configuration, letting `prefix`ed environment variables
+    /// (e.g. `VIDEO_LOCATION`) override unprefixed ones.
+    ///
+    /// # Errors
+    /// See [`Config::load_with_prefix`].
+    pub fn load_with_prefix(&self, prefix: &str) -> Result<Config, ConfigError> {
+        Config::load_with_prefix(self.config_file.as_deref(), prefix)
+    }
+}
@@ -1,6 +1,7 @@
 //! Unit tests for transport configuration.
 
-use super::transport::{Transport, TransportArgs, TransportMode};
+use super::transport::{DEFAULT_SSE_KEEP_ALIVE_SECS, Transport, TransportArgs, TransportMode};
+use std::time::Duration;
 
 #[test]
 fn test_transport_default_is_stdio() {
@@ -42,14 +43,45 @@ fn test_transport_sse_constructor() {
 fn test_transport_args_default() {
     let args = TransportArgs::default();
     assert_eq!(args.transport, TransportMode::Stdio);
-    assert_eq!(args.port, 8080);
+    assert_eq!(args.port, None);
+    assert_eq!(args.sse_keep_alive(), Some(Duration::from_secs(DEFAULT_SSE_KEEP_ALIVE_SECS)));
+}
+
+#[test]
+fn test_transport_args_sse_keep_alive() {
+    let mut args = TransportArgs {
+        transport: TransportMode::Sse,
+        port: Some(8080),
+        sse_keep_alive: 30,
+    };
+    assert_eq!(args.sse_keep_alive(), Some(Duration::from_secs(30)));
+
+    // Zero disables the pings
+    args.sse_keep_alive = 0;
+    assert_eq!(args.sse_keep_alive(), None);
+}
+
+#[test]
+fn test_transport_args_parse_sse_keep_alive_flag() {
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        transport: TransportArgs,
+    }
+
+    let cli = Cli::try_parse_from(["server", "--transport", "sse", "--sse-keep-alive", "5"]).unwrap();
+    assert_eq!(cli.transport.transport, TransportMode::Sse);
+    assert_eq!(cli.transport.sse_keep_alive(), Some(Duration::from_secs(5)));
 }
 
 #[test]
 fn test_transport_args_into_transport_stdio() {
     let args = TransportArgs {
         transport: TransportMode::Stdio,
-        port: 9000,
+        port: Some(9000),
+        sse_keep_alive: DEFAULT_SSE_KEEP_ALIVE_SECS,
     };
     let transport = args.into_transport();
     assert!(transport.is_stdio());
@@ -61,7 +93,8 @@ fn test_transport_args_into_transport_stdio() {
 fn test_transport_args_into_transport_http() {
     let args = TransportArgs {
         transport: TransportMode::Http,
-        port: 3000,
+        port: Some(3000),
+        sse_keep_alive: DEFAULT_SSE_KEEP_ALIVE_SECS,
     };
     let transport = args.into_transport();
     assert!(transport.is_http());
@@ -72,13 +105,51 @@ fn test_transport_args_into_transport_http() {
 fn test_transport_args_into_transport_sse() {
     let args = TransportArgs {
         transport: TransportMode::Sse,
-        port: 4000,
+        port: Some(4000),
+        sse_keep_alive: DEFAULT_SSE_KEEP_ALIVE_SECS,
     };
     let transport = args.into_transport();
     assert!(transport.is_sse());
     assert_eq!(transport.port(), Some(4000));
 }
 
+#[test]
+fn test_transport_args_port_falls_back_to_config_port() {
+    let args = TransportArgs {
+        transport: TransportMode::Http,
+        port: None,
+        sse_keep_alive: DEFAULT_SSE_KEEP_ALIVE_SECS,
+    };
+    assert_eq!(args.clone().into_transport().port(), Some(8080));
+    assert_eq!(args.into_transport_with_port(9100).port(), Some(9100));
+
+    // An explicit --port wins over the config file
+    let args = TransportArgs {
+        transport: TransportMode::Http,
+        port: Some(3000),
+        sse_keep_alive: DEFAULT_SSE_KEEP_ALIVE_SECS,
+    };
+    assert_eq!(args.into_transport_with_port(9100).port(), Some(3000));
+}
+
+#[test]
+fn test_config_args_parse_config_flag() {
+    use super::transport::ConfigArgs;
+    use clap::Parser;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        config: ConfigArgs,
+        #[command(flatten)]
+        transport: TransportArgs,
+    }
+
+    let cli = Cli::try_parse_from(["server", "--config", "/etc/genmedia.toml", "--transport", "http"]).unwrap();
+    assert_eq!(cli.config.config_file.as_deref(), Some(std::path::Path::new("/etc/genmedia.toml")));
+    assert_eq!(cli.transport.transport, TransportMode::Http);
+}
+
 #[test]
 fn test_transport_equality() {
     assert_eq!(Transport::Stdio, Transport::Stdio);
@@ -136,6 +207,7 @@ fn test_stdio_is_default_transport_mode() {
 
 // Test transport cloning
 #[test]
+#[allow(clippy::clone_on_copy)] // exercises the Clone impl itself
 fn test_transport_clone() {
     let original = Transport::Http { port: 8080 };
     let cloned = original.clone();
@@ -152,6 +224,7 @@ fn test_transport_copy() {
 
 // Test TransportMode cloning
 #[test]
+#[allow(clippy::clone_on_copy)] // exercises the Clone impl itself
 fn test_transport_mode_clone() {
     let original = TransportMode::Http;
     let cloned = original.clone();
@@ -163,9 +236,10 @@ fn test_transport_mode_clone() {
 fn test_transport_args_clone() {
     let original = TransportArgs {
         transport: TransportMode::Http,
-        port: 9000,
+        port: Some(9000),
+        sse_keep_alive: DEFAULT_SSE_KEEP_ALIVE_SECS,
     };
     let cloned = original.clone();
     assert_eq!(cloned.transport, TransportMode::Http);
-    assert_eq!(cloned.port, 9000);
+    assert_eq!(cloned.port, Some(9000));
 }
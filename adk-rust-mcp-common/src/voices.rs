@@ -0,0 +1,247 @@
+//! Voice metadata from the Cloud TTS `voices.list` API.
+//!
+//! Compiled-in voice and language tables drift as Google adds voices, so the
+//! speech servers list them from the API instead. The list is fetched on
+//! first use (or when a client asks for a refresh) and kept in memory for
+//! [`VoiceCache::ttl`] (default one hour, `VOICE_CACHE_TTL_SECS` overrides
+//! it). A failed fetch is remembered for [`FALLBACK_RETRY_INTERVAL`], during
+//! which callers serve their static tables instead of retrying the API on
+//! every request.
+
+use crate::auth::AuthProvider;
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Base URL of the Cloud TTS API.
+pub const TTS_API_BASE_URL: &str = "https://texttospeech.googleapis.com";
+
+/// Environment variable overriding how long a fetched voice list is kept, in seconds.
+pub const VOICE_CACHE_TTL_ENV: &str = "VOICE_CACHE_TTL_SECS";
+
+/// Default time a fetched voice list is kept.
+pub const DEFAULT_VOICE_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// Time after a failed fetch during which the API is not called again.
+pub const FALLBACK_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A voice listed by the Cloud TTS API.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsVoice {
+    /// Voice name, e.g. `en-US-Chirp3-HD-Kore`
+    pub name: String,
+    /// Supported language codes
+    #[serde(default)]
+    pub language_codes: Vec<String>,
+    /// SSML gender
+    #[serde(default)]
+    pub ssml_gender: Option<String>,
+    /// Natural sample rate
+    #[serde(default)]
+    pub natural_sample_rate_hertz: Option<u32>,
+}
+
+impl TtsVoice {
+    /// The voice's short name if it is a Chirp3-HD voice, e.g. `Kore` for
+    /// `en-US-Chirp3-HD-Kore`. Gemini TTS uses these short names.
+    pub fn chirp3_hd_name(&self) -> Option<&str> {
+        self.name
+            .split_once("-Chirp3-HD-")
+            .map(|(_, name)| name)
+            .filter(|name| !name.is_empty())
+    }
+}
+
+/// Where a served voice list came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoiceSource {
+    /// Listed by the Cloud TTS API (possibly cached)
+    Api,
+    /// The server's built-in table, used when the API is unavailable
+    Fallback,
+}
+
+/// Cloud TTS voices list response.
+#[derive(Debug, Deserialize)]
+struct VoicesResponse {
+    #[serde(default)]
+    voices: Vec<TtsVoice>,
+}
+
+/// List all voices from the Cloud TTS API at `base_url`.
+///
+/// # Errors
+/// Returns `Error::Api` if the request fails, the response cannot be parsed,
+/// or it lists no voices.
+pub async fn fetch_voices(
+    http: &reqwest::Client,
+    auth: &AuthProvider,
+    base_url: &str,
+    project_id: &str,
+) -> Result<Vec<TtsVoice>, Error> {
+    let token = auth.get_token(&["https://www.googleapis.com/auth/cloud-platform"]).await?;
+
+    let endpoint = format!("{}/v1/voices", base_url);
+    debug!(endpoint = %endpoint, "Calling Cloud TTS voices API");
+
+    let response = http
+        .get(&endpoint)
+        .header("Authorization", format!("Bearer {}", token))
+        .header("x-goog-user-project", project_id)
+        .send()
+        .await
+        .map_err(|e| Error::api(&endpoint, 0, format!("Request failed: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(Error::api_response(&endpoint, status.as_u16(), body));
+    }
+
+    let parsed: VoicesResponse = response.json().await.map_err(|e| {
+        Error::api(&endpoint, status.as_u16(), format!("Failed to parse response: {}", e))
+    })?;
+    if parsed.voices.is_empty() {
+        return Err(Error::api(&endpoint, status.as_u16(), "No voices returned from API"));
+    }
+    Ok(parsed.voices)
+}
+
+enum Cached {
+    Voices(Arc<Vec<TtsVoice>>),
+    Failed,
+}
+
+struct Entry {
+    cached: Cached,
+    expires: Instant,
+}
+
+/// In-memory cache of the voice list with a time-to-live.
+pub struct VoiceCache {
+    ttl: Duration,
+    retry_interval: Duration,
+    entry: Mutex<Option<Entry>>,
+    /// Serializes fetches so concurrent misses call the API once
+    fetching: tokio::sync::Mutex<()>,
+}
+
+impl std::fmt::Debug for VoiceCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VoiceCache")
+            .field("ttl", &self.ttl)
+            .field("retry_interval", &self.retry_interval)
+            .finish()
+    }
+}
+
+impl Default for VoiceCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_VOICE_CACHE_TTL)
+    }
+}
+
+impl VoiceCache {
+    /// Create a cache keeping fetched lists for `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self::with_retry_interval(ttl, FALLBACK_RETRY_INTERVAL)
+    }
+
+    /// Create a cache keeping fetched lists for `ttl` and failures for `retry_interval`.
+    pub fn with_retry_interval(ttl: Duration, retry_interval: Duration) -> Self {
+        Self {
+            ttl,
+            retry_interval,
+            entry: Mutex::new(None),
+            fetching: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Create a cache configured by `VOICE_CACHE_TTL_SECS`.
+    ///
+    /// Missing or invalid values fall back to the default.
+    pub fn from_env() -> Self {
+        let ttl = std::env::var(VOICE_CACHE_TTL_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|&n| n > 0)
+            .map_or(DEFAULT_VOICE_CACHE_TTL, Duration::from_secs);
+        Self::new(ttl)
+    }
+
+    /// How long a fetched list is kept.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// The cached state, if it has not expired: `Some(Some(voices))` for a
+    /// fetched list and `Some(None)` for a recent failure.
+    fn lookup(&self) -> Option<Option<Arc<Vec<TtsVoice>>>> {
+        let entry = self.entry.lock().unwrap_or_else(|e| e.into_inner());
+        entry.as_ref().filter(|e| Instant::now() < e.expires).map(|e| match &e.cached {
+            Cached::Voices(voices) => Some(Arc::clone(voices)),
+            Cached::Failed => None,
+        })
+    }
+
+    /// The fetched list, if one is cached and has not expired.
+    pub fn cached(&self) -> Option<Arc<Vec<TtsVoice>>> {
+        self.lookup().flatten()
+    }
+
+    /// Return the cached voice list, calling `fetch` when it is missing,
+    /// expired, or `refresh` is set.
+    ///
+    /// Returns `None` when the fetch failed or failed recently; callers then
+    /// serve their static table. `refresh` retries a recent failure too, and
+    /// a failed refresh keeps an unexpired list.
+    pub async fn get<F, Fut>(&self, refresh: bool, fetch: F) -> Option<Arc<Vec<TtsVoice>>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<TtsVoice>, Error>>,
+    {
+        if !refresh {
+            if let Some(cached) = self.lookup() {
+                return cached;
+            }
+        }
+
+        let _fetching = self.fetching.lock().await;
+        // Another caller may have fetched while this one waited
+        if !refresh {
+            if let Some(cached) = self.lookup() {
+                return cached;
+            }
+        }
+
+        let (cached, ttl) = match fetch().await {
+            Ok(voices) => {
+                info!(count = voices.len(), "Fetched TTS voice list");
+                (Cached::Voices(Arc::new(voices)), self.ttl)
+            }
+            Err(e) => {
+                // A failed refresh keeps a list that is still fresh
+                if let Some(voices) = self.cached() {
+                    warn!(error = %e, "Failed to refresh TTS voice list; keeping the cached list");
+                    return Some(voices);
+                }
+                warn!(error = %e, "Failed to fetch TTS voice list; serving the built-in list");
+                (Cached::Failed, self.retry_interval)
+            }
+        };
+        let result = match &cached {
+            Cached::Voices(voices) => Some(Arc::clone(voices)),
+            Cached::Failed => None,
+        };
+        *self.entry.lock().unwrap_or_else(|e| e.into_inner()) = Some(Entry {
+            cached,
+            expires: Instant::now() + ttl,
+        });
+        result
+    }
+}
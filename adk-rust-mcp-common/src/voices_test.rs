@@ -0,0 +1,167 @@
+//! Unit tests for the voices module.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::auth::AuthProvider;
+use crate::error::Error;
+use crate::voices::{TtsVoice, VoiceCache, fetch_voices};
+
+fn voice(name: &str) -> TtsVoice {
+    TtsVoice {
+        name: name.to_string(),
+        language_codes: vec!["en-US".to_string()],
+        ssml_gender: Some("FEMALE".to_string()),
+        natural_sample_rate_hertz: Some(24000),
+    }
+}
+
+/// A fetch that counts how often it runs and lists one voice per run.
+async fn counted_fetch(calls: &AtomicUsize) -> Result<Vec<TtsVoice>, Error> {
+    let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+    Ok(vec![voice(&format!("en-US-Chirp3-HD-Voice{}", n))])
+}
+
+async fn failing_fetch(calls: &AtomicUsize) -> Result<Vec<TtsVoice>, Error> {
+    calls.fetch_add(1, Ordering::SeqCst);
+    Err(Error::api("https://texttospeech.googleapis.com/v1/voices", 503, "unavailable"))
+}
+
+#[test]
+fn chirp3_hd_name() {
+    assert_eq!(voice("en-US-Chirp3-HD-Kore").chirp3_hd_name(), Some("Kore"));
+    assert_eq!(voice("cmn-CN-Chirp3-HD-Zubenelgenubi").chirp3_hd_name(), Some("Zubenelgenubi"));
+    assert_eq!(voice("en-US-Neural2-A").chirp3_hd_name(), None);
+    assert_eq!(voice("en-US-Chirp3-HD-").chirp3_hd_name(), None);
+}
+
+#[tokio::test]
+async fn fetches_once_within_ttl() {
+    let cache = VoiceCache::new(Duration::from_secs(60));
+    let calls = AtomicUsize::new(0);
+
+    let first = cache.get(false, || counted_fetch(&calls)).await.unwrap();
+    let second = cache.get(false, || counted_fetch(&calls)).await.unwrap();
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(cache.cached().unwrap()[0].name, "en-US-Chirp3-HD-Voice1");
+}
+
+#[tokio::test]
+async fn refetches_after_ttl_expires() {
+    let cache = VoiceCache::new(Duration::from_millis(20));
+    let calls = AtomicUsize::new(0);
+
+    cache.get(false, || counted_fetch(&calls)).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    assert!(cache.cached().is_none());
+
+    let voices = cache.get(false, || counted_fetch(&calls)).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(voices[0].name, "en-US-Chirp3-HD-Voice2");
+}
+
+#[tokio::test]
+async fn refresh_bypasses_cache() {
+    let cache = VoiceCache::new(Duration::from_secs(60));
+    let calls = AtomicUsize::new(0);
+
+    cache.get(false, || counted_fetch(&calls)).await.unwrap();
+    let voices = cache.get(true, || counted_fetch(&calls)).await.unwrap();
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(voices[0].name, "en-US-Chirp3-HD-Voice2");
+}
+
+#[tokio::test]
+async fn failure_falls_back_until_retry_interval() {
+    let cache = VoiceCache::with_retry_interval(Duration::from_secs(60), Duration::from_millis(20));
+    let calls = AtomicUsize::new(0);
+
+    assert!(cache.get(false, || failing_fetch(&calls)).await.is_none());
+    // The failure is remembered rather than retried on every request
+    assert!(cache.get(false, || counted_fetch(&calls)).await.is_none());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    assert!(cache.get(false, || counted_fetch(&calls)).await.is_some());
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn refresh_retries_recent_failure() {
+    let cache = VoiceCache::new(Duration::from_secs(60));
+    let calls = AtomicUsize::new(0);
+
+    assert!(cache.get(false, || failing_fetch(&calls)).await.is_none());
+    assert!(cache.get(true, || counted_fetch(&calls)).await.is_some());
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn failed_refresh_keeps_cached_list() {
+    let cache = VoiceCache::new(Duration::from_secs(60));
+    let calls = AtomicUsize::new(0);
+
+    cache.get(false, || counted_fetch(&calls)).await.unwrap();
+    let voices = cache.get(true, || failing_fetch(&calls)).await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(voices[0].name, "en-US-Chirp3-HD-Voice1");
+}
+
+#[tokio::test]
+async fn fetch_voices_parses_response() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/voices"))
+        .and(header("Authorization", "Bearer test-token"))
+        .and(header("x-goog-user-project", "test-project"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "voices": [{
+                "name": "en-US-Chirp3-HD-Kore",
+                "languageCodes": ["en-US"],
+                "ssmlGender": "FEMALE",
+                "naturalSampleRateHertz": 24000
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let auth = AuthProvider::mock("test-token");
+    let voices = fetch_voices(&reqwest::Client::new(), &auth, &server.uri(), "test-project")
+        .await
+        .unwrap();
+
+    assert_eq!(voices, vec![voice("en-US-Chirp3-HD-Kore")]);
+}
+
+#[tokio::test]
+async fn fetch_voices_rejects_errors_and_empty_lists() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v1/voices"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v1/voices"))
+        .respond_with(ResponseTemplate::new(403).set_body_string("API not enabled"))
+        .mount(&server)
+        .await;
+
+    let auth = AuthProvider::mock("test-token");
+    let http = reqwest::Client::new();
+
+    let empty = fetch_voices(&http, &auth, &server.uri(), "test-project").await.unwrap_err();
+    assert!(empty.to_string().contains("No voices"), "{}", empty);
+
+    let denied = fetch_voices(&http, &auth, &server.uri(), "test-project").await.unwrap_err();
+    assert!(denied.to_string().contains("403"), "{}", denied);
+}
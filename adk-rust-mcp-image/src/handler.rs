@@ -4,15 +4,28 @@
 //! text-to-image generation using Google's Vertex AI Imagen API.
 
 use adk_rust_mcp_common::auth::AuthProvider;
-use adk_rust_mcp_common::config::Config;
+use adk_rust_mcp_common::cache::{self, CachedResult, ResultCache};
+use adk_rust_mcp_common::config::{self, Config};
 use adk_rust_mcp_common::error::Error;
 use adk_rust_mcp_common::gcs::{GcsClient, GcsUri};
-use adk_rust_mcp_common::models::{ImagenModel, ModelRegistry, IMAGEN_MODELS};
+use adk_rust_mcp_common::media_input;
+use adk_rust_mcp_common::metadata::{self, GenerationMetadata};
+use adk_rust_mcp_common::models::{
+    ImagenModel, ImagenUpscaleModel, ModelRegistry, IMAGEN_MODELS, IMAGEN_UPSCALE_MODELS,
+};
+use adk_rust_mcp_common::retry::{QUOTA_RETRY_STATUSES, RetryPolicy, send_with_retry};
+use crate::outpaint::{OutpaintTarget, outpaint_layout, pad_image_and_mask};
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use image::DynamicImage;
+use image::codecs::avif::AvifEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::io::Cursor;
 use std::path::Path;
-use tracing::{debug, info, instrument};
+use std::sync::Arc;
+use tracing::{debug, info, instrument, warn};
 
 /// Valid aspect ratios for image generation.
 pub const VALID_ASPECT_RATIOS: &[&str] = &["1:1", "3:4", "4:3", "9:16", "16:9"];
@@ -20,12 +33,136 @@ pub const VALID_ASPECT_RATIOS: &[&str] = &["1:1", "3:4", "4:3", "9:16", "16:9"];
 /// Default model for image generation.
 pub const DEFAULT_MODEL: &str = "imagen-4.0-generate-preview-06-06";
 
+/// Valid safety filter levels (Imagen `safetySetting`).
+pub use adk_rust_mcp_common::config::IMAGE_SAFETY_FILTER_LEVELS as VALID_SAFETY_FILTER_LEVELS;
+
+/// Valid person generation policies (Imagen `personGeneration`).
+pub use adk_rust_mcp_common::config::IMAGE_PERSON_GENERATION_POLICIES as VALID_PERSON_GENERATION;
+
+/// Output MIME types for generated and upscaled images.
+pub const VALID_OUTPUT_MIME_TYPES: &[&str] = &["image/png", "image/jpeg", "image/webp", "image/avif"];
+
+/// Output MIME types Imagen cannot emit; these are requested as PNG and
+/// transcoded locally.
+pub const TRANSCODED_OUTPUT_MIME_TYPES: &[&str] = &["image/webp", "image/avif"];
+
+/// Output MIME types that accept `compression_quality` (WebP output is lossless).
+pub const LOSSY_OUTPUT_MIME_TYPES: &[&str] = &["image/jpeg", "image/avif"];
+
+/// Minimum JPEG/AVIF compression quality.
+pub const MIN_COMPRESSION_QUALITY: u8 = 1;
+
+/// Maximum JPEG/AVIF compression quality.
+pub const MAX_COMPRESSION_QUALITY: u8 = 100;
+
+/// JPEG quality used for local transcoding when `compression_quality` is not set.
+pub const DEFAULT_JPEG_QUALITY: u8 = 75;
+
+/// AVIF quality used when `compression_quality` is not set.
+pub const DEFAULT_AVIF_QUALITY: u8 = 80;
+
+/// AVIF encoder speed (1 = slowest/smallest, 10 = fastest).
+const AVIF_ENCODER_SPEED: u8 = 8;
+
+/// Most attempts per Imagen predict call (generate, upscale), including the
+/// first; `API_MAX_ATTEMPTS` can lower it.
+pub const PREDICT_MAX_ATTEMPTS: u32 = 5;
+
 /// Minimum number of images that can be generated.
 pub const MIN_NUMBER_OF_IMAGES: u8 = 1;
 
 /// Maximum number of images that can be generated.
 pub const MAX_NUMBER_OF_IMAGES: u8 = 4;
 
+/// Style presets for `image_generate` and the prompt fragment each one adds.
+pub const STYLE_PRESETS: &[(&str, &str)] = &[
+    ("photorealistic", "photorealistic, natural lighting, sharp focus, high detail"),
+    ("cinematic", "cinematic film still, dramatic lighting, shallow depth of field"),
+    ("line_art", "clean black and white line art, minimal shading, white background"),
+    ("watercolor", "watercolor painting, soft washes, visible paper texture"),
+    ("oil_painting", "oil painting, visible brush strokes, rich colors"),
+    ("digital_art", "digital illustration, vibrant colors, crisp detail"),
+    ("anime", "anime style, cel shading, bold outlines"),
+    ("sketch", "pencil sketch, graphite shading, hand-drawn"),
+    ("3d_render", "3D render, global illumination, smooth materials"),
+    ("pixel_art", "pixel art, limited palette, crisp pixels"),
+];
+
+/// The prompt fragment for a style preset, if it is one of [`STYLE_PRESETS`].
+pub fn style_fragment(preset: &str) -> Option<&'static str> {
+    STYLE_PRESETS
+        .iter()
+        .find(|(name, _)| *name == preset)
+        .map(|(_, fragment)| *fragment)
+}
+
+/// Guess the prompt's language from the script its letters are written in.
+///
+/// Returns a language code when most letters belong to one non-Latin script
+/// (Hangul, kana, Han, Devanagari, Cyrillic, Arabic, Hebrew, Thai or Greek).
+/// Latin-script prompts return `None`: the script alone cannot tell English
+/// from Spanish or Portuguese.
+pub fn detect_prompt_language(prompt: &str) -> Option<&'static str> {
+    const SCRIPTS: &[(&str, &[(u32, u32)])] = &[
+        ("ko", &[(0x1100, 0x11FF), (0x3130, 0x318F), (0xAC00, 0xD7AF)]),
+        ("ja", &[(0x3040, 0x30FF)]),
+        ("zh", &[(0x3400, 0x4DBF), (0x4E00, 0x9FFF)]),
+        ("hi", &[(0x0900, 0x097F)]),
+        ("ru", &[(0x0400, 0x04FF)]),
+        ("ar", &[(0x0600, 0x06FF)]),
+        ("he", &[(0x0590, 0x05FF)]),
+        ("th", &[(0x0E00, 0x0E7F)]),
+        ("el", &[(0x0370, 0x03FF)]),
+    ];
+
+    let mut counts = [0usize; SCRIPTS.len()];
+    let mut letters = 0;
+    for c in prompt.chars().filter(|c| c.is_alphabetic()) {
+        letters += 1;
+        let code = c as u32;
+        if let Some(i) = SCRIPTS
+            .iter()
+            .position(|(_, ranges)| ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&code)))
+        {
+            counts[i] += 1;
+        }
+    }
+
+    // Japanese mixes kana with Han characters, so Han counts as Japanese once kana appear
+    const JA: usize = 1;
+    const ZH: usize = 2;
+    if counts[JA] > 0 {
+        counts[JA] += std::mem::take(&mut counts[ZH]);
+    }
+
+    let (index, &count) = counts.iter().enumerate().max_by_key(|&(_, count)| *count)?;
+    (count > 0 && count * 2 >= letters).then_some(SCRIPTS[index].0)
+}
+
+/// Whether `model` accepts prompts in `language`, comparing primary subtags
+/// (so "zh" is covered by "zh-CN").
+pub fn supports_prompt_language(model: &ImagenModel, language: &str) -> bool {
+    let primary = |code: &str| code.split('-').next().unwrap_or(code).to_ascii_lowercase();
+    model
+        .supported_languages
+        .iter()
+        .any(|supported| primary(supported) == primary(language))
+}
+
+/// Merge a style preset's fragment into `prompt`.
+///
+/// The fragment is appended after the caller's prompt; an unknown or absent
+/// preset leaves the prompt unchanged.
+pub fn apply_style_preset(prompt: &str, preset: Option<&str>) -> String {
+    match preset.and_then(style_fragment) {
+        Some(fragment) => {
+            let prompt = prompt.trim_end().trim_end_matches(['.', ',']);
+            format!("{}, {}", prompt, fragment)
+        }
+        None => prompt.to_string(),
+    }
+}
+
 /// Text-to-image generation parameters.
 ///
 /// These parameters control the image generation process via the Vertex AI Imagen API.
@@ -54,18 +191,81 @@ pub struct ImageGenerateParams {
     pub number_of_images: u8,
 
     /// Random seed for reproducible generation.
+    /// Requires `add_watermark: false`; Imagen ignores seeds on watermarked output.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub seed: Option<i64>,
 
+    /// Whether to embed an invisible SynthID watermark.
+    /// Imagen watermarks by default; must be explicitly `false` when `seed` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub add_watermark: Option<bool>,
+
     /// Output file path for saving the image locally.
     /// If not specified and output_uri is not specified, returns base64-encoded data.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_file: Option<String>,
 
-    /// Output storage URI (e.g., gs://bucket/path).
+    /// Output storage URI (e.g., gs://bucket/path); a bare object name is
+    /// placed in the configured `GCS_BUCKET`.
     /// If specified, uploads the image to the storage backend.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_uri: Option<String>,
+
+    /// Bypass the result cache for this request.
+    /// Requests with a seed and an output destination are cached by default.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_cache: bool,
+
+    /// Safety filter level: "block_low_and_above", "block_medium_and_above",
+    /// "block_only_high", or "block_none".
+    /// Falls back to `IMAGE_SAFETY_FILTER_LEVEL` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub safety_filter_level: Option<String>,
+
+    /// Whether people may be generated: "dont_allow", "allow_adult", or "allow_all".
+    /// Falls back to `IMAGE_PERSON_GENERATION` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub person_generation: Option<String>,
+
+    /// Output image format: "image/png" (default), "image/jpeg", "image/webp",
+    /// or "image/avif". WebP and AVIF are transcoded locally from PNG.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_mime_type: Option<String>,
+
+    /// Compression quality (1-100). Only valid with "image/jpeg" or "image/avif".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression_quality: Option<u8>,
+
+    /// Let Imagen rewrite the prompt with an LLM before generating.
+    /// The rewritten prompt is returned with each image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enhance_prompt: Option<bool>,
+
+    /// Vertex AI region for this request (e.g. "europe-west4").
+    /// Falls back to `LOCATION` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+
+    /// Style preset merged into the prompt: "photorealistic", "cinematic",
+    /// "line_art", "watercolor", "oil_painting", "digital_art", "anime",
+    /// "sketch", "3d_render", or "pixel_art".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style_preset: Option<String>,
+
+    /// How strongly the image follows the prompt; higher values follow it
+    /// more closely. Sent as-is to models that support it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guidance_scale: Option<f32>,
+
+    /// Language of the prompt: "auto", "en", "es", "hi", "ja", "ko", "pt",
+    /// "zh", "zh-CN", or "zh-TW".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    /// Write a `<name>.json` sidecar with the generation settings next to
+    /// each saved image. Requires `output_file` or `output_uri`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub write_metadata: bool,
 }
 
 fn default_model() -> String {
@@ -92,28 +292,67 @@ pub const UPSCALE_MODEL: &str = "imagen-4.0-upscale-preview";
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct ImageUpscaleParams {
     /// Source image to upscale.
-    /// Can be base64 data, local file path, or GCS URI.
+    /// Can be base64 data, data URI, local file path, GCS URI, or HTTPS URL.
     pub image: String,
 
     /// Upscale factor: "x2" or "x4".
     #[serde(default = "default_upscale_factor")]
     pub upscale_factor: String,
 
+    /// Upscale model to use. Defaults to the preferred model that supports
+    /// `upscale_factor`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
     /// Output file path for saving the upscaled image locally.
     /// If not specified and output_uri is not specified, returns base64-encoded data.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_file: Option<String>,
 
-    /// Output storage URI (e.g., gs://bucket/path).
+    /// Output storage URI (e.g., gs://bucket/path); a bare object name is
+    /// placed in the configured `GCS_BUCKET`. A URI ending in `/` is treated
+    /// as a directory and gets a name from the input and factor, e.g. `photo_x4.png`.
     /// If specified, uploads the upscaled image to the storage backend.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_uri: Option<String>,
+
+    /// Output image format: "image/png" (default), "image/jpeg", "image/webp",
+    /// or "image/avif". Formats other than PNG are transcoded locally.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_mime_type: Option<String>,
+
+    /// Compression quality (1-100). Only valid with "image/jpeg" or "image/avif".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression_quality: Option<u8>,
 }
 
 fn default_upscale_factor() -> String {
     "x2".to_string()
 }
 
+/// File name for an upscaled copy of `image`: its stem plus the factor,
+/// e.g. `photo_x4.png` for `gs://bucket/photo.jpg`.
+///
+/// Inputs without a recognizable image file name (base64 data, data URIs,
+/// extensionless URLs) are named `image_<factor>.png`.
+pub fn upscaled_file_name(image: &str, factor: &str) -> String {
+    let path = image.split(['?', '#']).next().unwrap_or(image);
+    let file = path.rsplit('/').next().unwrap_or(path);
+    let stem = match file.rsplit_once('.') {
+        Some((stem, ext))
+            if !stem.is_empty()
+                && matches!(
+                    ext.to_ascii_lowercase().as_str(),
+                    "png" | "jpg" | "jpeg" | "webp" | "avif" | "gif" | "bmp"
+                ) =>
+        {
+            stem
+        }
+        _ => "image",
+    };
+    format!("{}_{}.png", stem, factor)
+}
+
 impl ImageUpscaleParams {
     /// Validate the upscale parameters.
     pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
@@ -139,101 +378,167 @@ impl ImageUpscaleParams {
             });
         }
 
+        // Validate the model override and that it supports the factor
+        if let Some(name) = &self.model {
+            match ModelRegistry::resolve_imagen_upscale(name) {
+                None => errors.push(ValidationError {
+                    field: "model".to_string(),
+                    message: ModelRegistry::unknown_model_message(IMAGEN_UPSCALE_MODELS, name),
+                }),
+                Some(model)
+                    if VALID_UPSCALE_FACTORS.contains(&self.upscale_factor.as_str())
+                        && !model.supported_factors.contains(&self.upscale_factor.as_str()) =>
+                {
+                    errors.push(ValidationError {
+                        field: "upscale_factor".to_string(),
+                        message: format!(
+                            "Upscale factor '{}' is not supported by model {}. Valid options: {}",
+                            self.upscale_factor,
+                            model.id,
+                            model.supported_factors.join(", ")
+                        ),
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+
+        validate_output_format(self.output_mime_type.as_deref(), self.compression_quality, &mut errors);
+
         if errors.is_empty() {
             Ok(())
         } else {
             Err(errors)
         }
     }
+
+    /// The model to call: the requested one, or else the preferred model
+    /// supporting `upscale_factor`.
+    pub fn get_model(&self) -> Option<&'static ImagenUpscaleModel> {
+        match &self.model {
+            Some(name) => ModelRegistry::resolve_imagen_upscale(name),
+            None => ModelRegistry::imagen_upscale_for_factor(&self.upscale_factor),
+        }
+    }
+
+    /// Complete a directory-like `output_uri` (ending in `/`) with a file name
+    /// derived from the input image and the factor.
+    pub fn with_output_name(mut self) -> Self {
+        if let Some(uri) = self.output_uri.as_mut().filter(|uri| uri.ends_with('/')) {
+            uri.push_str(&upscaled_file_name(&self.image, &self.upscale_factor));
+        }
+        self
+    }
 }
 
-/// Validation error details for image generation parameters.
-#[derive(Debug, Clone)]
-pub struct ValidationError {
-    /// The field that failed validation.
-    pub field: String,
-    /// Description of the validation failure.
-    pub message: String,
+/// Default model for mask-based image editing.
+pub const EDIT_MODEL: &str = "imagen-3.0-capability-001";
+
+/// Editing mode for `image_edit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageEditMode {
+    /// Insert new content described by the prompt into the masked region.
+    InpaintInsert,
+    /// Remove the content in the masked region and fill it in.
+    InpaintRemove,
+    /// Replace the background while keeping the foreground subject.
+    BackgroundSwap,
 }
 
-impl std::fmt::Display for ValidationError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}: {}", self.field, self.message)
+impl ImageEditMode {
+    /// The parameter name of this mode, as accepted by the tool.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InpaintInsert => "inpaint_insert",
+            Self::InpaintRemove => "inpaint_remove",
+            Self::BackgroundSwap => "background_swap",
+        }
+    }
+
+    /// The `editMode` value expected by the Imagen API.
+    pub fn api_value(&self) -> &'static str {
+        match self {
+            Self::InpaintInsert => "EDIT_MODE_INPAINT_INSERTION",
+            Self::InpaintRemove => "EDIT_MODE_INPAINT_REMOVAL",
+            Self::BackgroundSwap => "EDIT_MODE_BGSWAP",
+        }
+    }
+
+    /// Whether this mode needs a caller-provided mask.
+    pub fn requires_mask(&self) -> bool {
+        matches!(self, Self::InpaintInsert | Self::InpaintRemove)
     }
 }
 
-impl ImageGenerateParams {
-    /// Validate the parameters against the model constraints.
-    ///
-    /// # Returns
-    /// - `Ok(())` if all parameters are valid
-    /// - `Err(Vec<ValidationError>)` with all validation errors
+/// Mask-based image editing parameters.
+///
+/// These parameters control image editing via the Vertex AI Imagen capability model.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ImageEditParams {
+    /// Source image to edit.
+    /// Can be base64 data, data URI, local file path, GCS URI, or HTTPS URL.
+    pub image: String,
+
+    /// Mask image marking the region to edit (white = edit, black = keep).
+    /// Can be base64 data, data URI, local file path, GCS URI, or HTTPS URL.
+    /// Required for inpaint modes; optional for background_swap, where the
+    /// background is detected automatically when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mask: Option<String>,
+
+    /// Text prompt describing the desired edit.
+    /// May be empty for inpaint_remove.
+    #[serde(default)]
+    pub prompt: String,
+
+    /// Editing mode: inpaint_insert, inpaint_remove, or background_swap.
+    pub edit_mode: ImageEditMode,
+
+    /// Number of images to generate (1-4).
+    #[serde(default = "default_number_of_images")]
+    pub number_of_images: u8,
+
+    /// Output file path for saving edited images locally.
+    /// If not specified and output_uri is not specified, returns base64-encoded data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_file: Option<String>,
+
+    /// Output storage URI (e.g., gs://bucket/path); a bare object name is
+    /// placed in the configured `GCS_BUCKET`.
+    /// If specified, uploads the edited images to the storage backend.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_uri: Option<String>,
+}
+
+impl ImageEditParams {
+    /// Validate the edit parameters.
     pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
         let mut errors = Vec::new();
 
-        // Resolve the model to get constraints
-        let model = ModelRegistry::resolve_imagen(&self.model);
-
-        // Validate model exists
-        if model.is_none() {
+        if self.image.trim().is_empty() {
             errors.push(ValidationError {
-                field: "model".to_string(),
-                message: format!(
-                    "Unknown model '{}'. Valid models: {}",
-                    self.model,
-                    IMAGEN_MODELS
-                        .iter()
-                        .map(|m| m.id)
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                ),
+                field: "image".to_string(),
+                message: "Image cannot be empty".to_string(),
             });
         }
 
-        // Validate prompt length (if model is known)
-        if let Some(model) = model {
-            if self.prompt.len() > model.max_prompt_length {
-                errors.push(ValidationError {
-                    field: "prompt".to_string(),
-                    message: format!(
-                        "Prompt length {} exceeds maximum {} for model {}",
-                        self.prompt.len(),
-                        model.max_prompt_length,
-                        model.id
-                    ),
-                });
-            }
+        let has_mask = self.mask.as_ref().is_some_and(|m| !m.trim().is_empty());
+        if self.edit_mode.requires_mask() && !has_mask {
+            errors.push(ValidationError {
+                field: "mask".to_string(),
+                message: format!("A mask is required for edit_mode '{}'", self.edit_mode.as_str()),
+            });
+        }
 
-            // Validate aspect ratio against model's supported ratios
-            if !model.supported_aspect_ratios.contains(&self.aspect_ratio.as_str()) {
-                errors.push(ValidationError {
-                    field: "aspect_ratio".to_string(),
-                    message: format!(
-                        "Invalid aspect ratio '{}'. Valid options for {}: {}",
-                        self.aspect_ratio,
-                        model.id,
-                        model.supported_aspect_ratios.join(", ")
-                    ),
-                });
-            }
-        } else {
-            // If model is unknown, validate against common aspect ratios
-            if !VALID_ASPECT_RATIOS.contains(&self.aspect_ratio.as_str()) {
-                errors.push(ValidationError {
-                    field: "aspect_ratio".to_string(),
-                    message: format!(
-                        "Invalid aspect ratio '{}'. Valid options: {}",
-                        self.aspect_ratio,
-                        VALID_ASPECT_RATIOS.join(", ")
-                    ),
-                });
-            }
+        if self.edit_mode != ImageEditMode::InpaintRemove && self.prompt.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "prompt".to_string(),
+                message: "Prompt cannot be empty".to_string(),
+            });
         }
 
-        // Validate number_of_images range
-        if self.number_of_images < MIN_NUMBER_OF_IMAGES
-            || self.number_of_images > MAX_NUMBER_OF_IMAGES
-        {
+        if self.number_of_images < MIN_NUMBER_OF_IMAGES || self.number_of_images > MAX_NUMBER_OF_IMAGES {
             errors.push(ValidationError {
                 field: "number_of_images".to_string(),
                 message: format!(
@@ -243,80 +548,614 @@ impl ImageGenerateParams {
             });
         }
 
-        // Validate prompt is not empty
-        if self.prompt.trim().is_empty() {
-            errors.push(ValidationError {
-                field: "prompt".to_string(),
-                message: "Prompt cannot be empty".to_string(),
-            });
-        }
-
         if errors.is_empty() {
             Ok(())
         } else {
             Err(errors)
         }
     }
+}
 
-    /// Get the resolved model definition.
-    pub fn get_model(&self) -> Option<&'static ImagenModel> {
-        ModelRegistry::resolve_imagen(&self.model)
+/// How `image_variation` uses the source image.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageVariationMode {
+    /// Keep the source's composition (its edges) and restyle it as the prompt
+    /// describes, e.g. "the same scene as a watercolor".
+    #[default]
+    Structure,
+    /// Generate the content described by the prompt in the visual style of the source.
+    Style,
+}
+
+impl ImageVariationMode {
+    /// The parameter name of this mode, as accepted by the tool.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Structure => "structure",
+            Self::Style => "style",
+        }
     }
 }
 
-/// Image generation handler.
+/// Image-conditioned generation parameters.
 ///
-/// Handles image generation requests using the Vertex AI Imagen API.
-pub struct ImageHandler {
-    /// Application configuration.
-    pub config: Config,
-    /// GCS client for storage operations.
-    pub gcs: GcsClient,
-    /// HTTP client for API requests.
-    pub http: reqwest::Client,
-    /// Authentication provider.
-    pub auth: AuthProvider,
+/// Unlike upscaling, which keeps the pixels and adds resolution, a variation
+/// is a new image guided by both the source image and the prompt.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ImageVariationParams {
+    /// Source image to derive the variation from.
+    /// Can be base64 data, data URI, local file path, GCS URI, or HTTPS URL.
+    pub image: String,
+
+    /// Text prompt describing the variation (e.g. "a watercolor painting").
+    pub prompt: String,
+
+    /// How the source image guides generation: structure or style.
+    #[serde(default)]
+    pub mode: ImageVariationMode,
+
+    /// Short description of the source's style (style mode only),
+    /// e.g. "pastel watercolor".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style_description: Option<String>,
+
+    /// Number of images to generate (1-4).
+    #[serde(default = "default_number_of_images")]
+    pub number_of_images: u8,
+
+    /// Output file path for saving generated images locally.
+    /// If not specified and output_uri is not specified, returns base64-encoded data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_file: Option<String>,
+
+    /// Output storage URI (e.g., gs://bucket/path); a bare object name is
+    /// placed in the configured `GCS_BUCKET`.
+    /// If specified, uploads the generated images to the storage backend.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_uri: Option<String>,
 }
 
-impl ImageHandler {
-    /// Create a new ImageHandler with the given configuration.
-    ///
-    /// # Errors
-    /// Returns an error if GCS client or auth provider initialization fails.
-    #[instrument(level = "debug", name = "image_handler_new", skip_all)]
-    pub async fn new(config: Config) -> Result<Self, Error> {
-        debug!("Initializing ImageHandler");
+impl ImageVariationParams {
+    /// Validate the variation parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
 
-        let auth = AuthProvider::new().await?;
-        let gcs = GcsClient::with_auth(AuthProvider::new().await?);
-        let http = reqwest::Client::new();
+        if self.image.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "image".to_string(),
+                message: "Image cannot be empty".to_string(),
+            });
+        }
 
-        Ok(Self {
-            config,
-            gcs,
-            http,
-            auth,
-        })
-    }
+        if self.prompt.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "prompt".to_string(),
+                message: "Prompt cannot be empty".to_string(),
+            });
+        }
 
-    /// Create a new ImageHandler with provided dependencies (for testing).
-    #[cfg(test)]
-    pub fn with_deps(config: Config, gcs: GcsClient, http: reqwest::Client, auth: AuthProvider) -> Self {
-        Self {
-            config,
-            gcs,
-            http,
-            auth,
+        if self.style_description.is_some() && self.mode != ImageVariationMode::Style {
+            errors.push(ValidationError {
+                field: "style_description".to_string(),
+                message: "style_description is only supported with mode 'style'".to_string(),
+            });
         }
-    }
 
-    /// Get the Vertex AI Imagen API endpoint for the given model.
-    pub fn get_endpoint(&self, model: &str) -> String {
-        format!(
+        if self.number_of_images < MIN_NUMBER_OF_IMAGES || self.number_of_images > MAX_NUMBER_OF_IMAGES {
+            errors.push(ValidationError {
+                field: "number_of_images".to_string(),
+                message: format!(
+                    "number_of_images must be between {} and {}, got {}",
+                    MIN_NUMBER_OF_IMAGES, MAX_NUMBER_OF_IMAGES, self.number_of_images
+                ),
+            });
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Mask dilation used for outpainting, blending the seam with the original.
+pub const OUTPAINT_MASK_DILATION: f64 = 0.03;
+
+/// Outpainting parameters.
+///
+/// Extends the canvas of an image to a target aspect ratio or explicit size,
+/// filling the new area with generated content.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ImageOutpaintParams {
+    /// Source image to extend.
+    /// Can be base64 data, data URI, local file path, GCS URI, or HTTPS URL.
+    pub image: String,
+
+    /// Text prompt describing the content of the extended area.
+    pub prompt: String,
+
+    /// Target aspect ratio (e.g. "16:9"). The canvas grows along one axis only.
+    /// Mutually exclusive with target_width/target_height.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_aspect_ratio: Option<String>,
+
+    /// Target canvas width in pixels (requires target_height).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_width: Option<u32>,
+
+    /// Target canvas height in pixels (requires target_width).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_height: Option<u32>,
+
+    /// Output file path for saving the outpainted image locally.
+    /// If not specified and output_uri is not specified, returns base64-encoded data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_file: Option<String>,
+
+    /// Output storage URI (e.g., gs://bucket/path); a bare object name is
+    /// placed in the configured `GCS_BUCKET`.
+    /// If specified, uploads the outpainted image to the storage backend.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_uri: Option<String>,
+}
+
+impl ImageOutpaintParams {
+    /// Validate the outpaint parameters.
+    ///
+    /// Size checks against the source image happen after it is loaded.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if self.image.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "image".to_string(),
+                message: "Image cannot be empty".to_string(),
+            });
+        }
+
+        if self.prompt.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "prompt".to_string(),
+                message: "Prompt cannot be empty".to_string(),
+            });
+        }
+
+        let has_dimensions = self.target_width.is_some() || self.target_height.is_some();
+        match (&self.target_aspect_ratio, has_dimensions) {
+            (Some(_), true) => errors.push(ValidationError {
+                field: "target_aspect_ratio".to_string(),
+                message: "Specify either target_aspect_ratio or target_width/target_height, not both".to_string(),
+            }),
+            (None, false) => errors.push(ValidationError {
+                field: "target_aspect_ratio".to_string(),
+                message: "Either target_aspect_ratio or target_width and target_height is required".to_string(),
+            }),
+            (Some(ratio), false) => {
+                if OutpaintTarget::parse_aspect_ratio(ratio).is_none() {
+                    errors.push(ValidationError {
+                        field: "target_aspect_ratio".to_string(),
+                        message: format!("Invalid aspect ratio '{}'. Expected W:H, e.g. 16:9", ratio),
+                    });
+                }
+            }
+            (None, true) => {
+                if self.target_width.is_none() || self.target_height.is_none() {
+                    errors.push(ValidationError {
+                        field: "target_width".to_string(),
+                        message: "target_width and target_height must be set together".to_string(),
+                    });
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// The requested canvas target, if the parameters describe one.
+    pub fn target(&self) -> Option<OutpaintTarget> {
+        match (&self.target_aspect_ratio, self.target_width, self.target_height) {
+            (Some(ratio), None, None) => OutpaintTarget::parse_aspect_ratio(ratio),
+            (None, Some(w), Some(h)) => Some(OutpaintTarget::Dimensions(w, h)),
+            _ => None,
+        }
+    }
+}
+
+/// Validate `output_mime_type` and `compression_quality`.
+fn validate_output_format(
+    output_mime_type: Option<&str>,
+    compression_quality: Option<u8>,
+    errors: &mut Vec<ValidationError>,
+) {
+    if let Some(mime_type) = output_mime_type {
+        if !VALID_OUTPUT_MIME_TYPES.contains(&mime_type) {
+            errors.push(ValidationError {
+                field: "output_mime_type".to_string(),
+                message: format!(
+                    "Invalid output MIME type '{}'. Valid options: {}",
+                    mime_type,
+                    VALID_OUTPUT_MIME_TYPES.join(", ")
+                ),
+            });
+        }
+    }
+
+    // Validate compression quality (lossy formats only)
+    if let Some(quality) = compression_quality {
+        if !output_mime_type.is_some_and(|m| LOSSY_OUTPUT_MIME_TYPES.contains(&m)) {
+            errors.push(ValidationError {
+                field: "compression_quality".to_string(),
+                message: format!(
+                    "compression_quality requires output_mime_type {}",
+                    LOSSY_OUTPUT_MIME_TYPES.iter().map(|m| format!("'{}'", m)).collect::<Vec<_>>().join(" or ")
+                ),
+            });
+        }
+        if !(MIN_COMPRESSION_QUALITY..=MAX_COMPRESSION_QUALITY).contains(&quality) {
+            errors.push(ValidationError {
+                field: "compression_quality".to_string(),
+                message: format!(
+                    "compression_quality must be between {} and {}, got {}",
+                    MIN_COMPRESSION_QUALITY, MAX_COMPRESSION_QUALITY, quality
+                ),
+            });
+        }
+    }
+}
+
+/// Validation error details for image generation parameters.
+#[derive(Debug, Clone)]
+pub struct ValidationError {
+    /// The field that failed validation.
+    pub field: String,
+    /// Description of the validation failure.
+    pub message: String,
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl ImageGenerateParams {
+    /// Validate the parameters against the model constraints.
+    ///
+    /// # Returns
+    /// - `Ok(())` if all parameters are valid
+    /// - `Err(Vec<ValidationError>)` with all validation errors
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        // Resolve the model to get constraints
+        let model = ModelRegistry::resolve_imagen(&self.model);
+
+        // Validate model exists
+        if model.is_none() {
+            errors.push(ValidationError {
+                field: "model".to_string(),
+                message: ModelRegistry::unknown_model_message(IMAGEN_MODELS, &self.model),
+            });
+        }
+
+        // Validate prompt length (if model is known), including any style fragment
+        if let Some(model) = model {
+            let prompt_length = self.styled_prompt().len();
+            if prompt_length > model.max_prompt_length {
+                errors.push(ValidationError {
+                    field: "prompt".to_string(),
+                    message: format!(
+                        "Prompt length {} exceeds maximum {} for model {}",
+                        prompt_length,
+                        model.max_prompt_length,
+                        model.id
+                    ),
+                });
+            }
+
+            if let Some(language) = &self.language {
+                if !model.supported_languages.contains(&language.as_str()) {
+                    errors.push(ValidationError {
+                        field: "language".to_string(),
+                        message: format!(
+                            "Invalid language '{}'. Valid options for {}: {}",
+                            language,
+                            model.id,
+                            model.supported_languages.join(", ")
+                        ),
+                    });
+                }
+            }
+
+            if self.guidance_scale.is_some() && !model.supports_guidance_scale {
+                errors.push(ValidationError {
+                    field: "guidance_scale".to_string(),
+                    message: format!("guidance_scale is not supported by model {}", model.id),
+                });
+            }
+
+            // Validate aspect ratio against model's supported ratios
+            if !model.supported_aspect_ratios.contains(&self.aspect_ratio.as_str()) {
+                errors.push(ValidationError {
+                    field: "aspect_ratio".to_string(),
+                    message: format!(
+                        "Invalid aspect ratio '{}'. Valid options for {}: {}",
+                        self.aspect_ratio,
+                        model.id,
+                        model.supported_aspect_ratios.join(", ")
+                    ),
+                });
+            }
+        } else {
+            // If model is unknown, validate against common aspect ratios
+            if !VALID_ASPECT_RATIOS.contains(&self.aspect_ratio.as_str()) {
+                errors.push(ValidationError {
+                    field: "aspect_ratio".to_string(),
+                    message: format!(
+                        "Invalid aspect ratio '{}'. Valid options: {}",
+                        self.aspect_ratio,
+                        VALID_ASPECT_RATIOS.join(", ")
+                    ),
+                });
+            }
+        }
+
+        // Validate number_of_images range
+        if self.number_of_images < MIN_NUMBER_OF_IMAGES
+            || self.number_of_images > MAX_NUMBER_OF_IMAGES
+        {
+            errors.push(ValidationError {
+                field: "number_of_images".to_string(),
+                message: format!(
+                    "number_of_images must be between {} and {}, got {}",
+                    MIN_NUMBER_OF_IMAGES, MAX_NUMBER_OF_IMAGES, self.number_of_images
+                ),
+            });
+        }
+
+        // Validate prompt is not empty
+        if self.prompt.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "prompt".to_string(),
+                message: "Prompt cannot be empty".to_string(),
+            });
+        }
+
+        // Imagen rejects seeded requests while the watermark is on (its default)
+        if self.seed.is_some() && self.add_watermark != Some(false) {
+            errors.push(ValidationError {
+                field: "add_watermark".to_string(),
+                message: "seed requires add_watermark to be false: Imagen cannot produce \
+                          reproducible output while the SynthID watermark is enabled (the default)"
+                    .to_string(),
+            });
+        }
+
+        // Validate safety filter level
+        if let Some(level) = &self.safety_filter_level {
+            if !VALID_SAFETY_FILTER_LEVELS.contains(&level.as_str()) {
+                errors.push(ValidationError {
+                    field: "safety_filter_level".to_string(),
+                    message: format!(
+                        "Invalid safety filter level '{}'. Valid options: {}",
+                        level,
+                        VALID_SAFETY_FILTER_LEVELS.join(", ")
+                    ),
+                });
+            }
+        }
+
+        // Validate person generation policy
+        if let Some(policy) = &self.person_generation {
+            if !VALID_PERSON_GENERATION.contains(&policy.as_str()) {
+                errors.push(ValidationError {
+                    field: "person_generation".to_string(),
+                    message: format!(
+                        "Invalid person generation '{}'. Valid options: {}",
+                        policy,
+                        VALID_PERSON_GENERATION.join(", ")
+                    ),
+                });
+            }
+        }
+
+        // Validate style preset
+        if let Some(preset) = &self.style_preset {
+            if style_fragment(preset).is_none() {
+                errors.push(ValidationError {
+                    field: "style_preset".to_string(),
+                    message: format!(
+                        "Invalid style preset '{}'. Valid options: {}",
+                        preset,
+                        STYLE_PRESETS
+                            .iter()
+                            .map(|(name, _)| *name)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                });
+            }
+        }
+
+        // Validate guidance scale
+        if let Some(scale) = self.guidance_scale {
+            if !scale.is_finite() || scale <= 0.0 {
+                errors.push(ValidationError {
+                    field: "guidance_scale".to_string(),
+                    message: format!("guidance_scale must be a positive number, got {}", scale),
+                });
+            }
+        }
+
+        // Validate the region override
+        if let Some(location) = &self.location {
+            if let Err(message) = config::validate_location(location) {
+                errors.push(ValidationError {
+                    field: "location".to_string(),
+                    message,
+                });
+            }
+        }
+
+        // Sidecars are written next to saved outputs
+        if self.write_metadata && self.output_file.is_none() && self.output_uri.is_none() {
+            errors.push(ValidationError {
+                field: "write_metadata".to_string(),
+                message: "write_metadata requires output_file or output_uri".to_string(),
+            });
+        }
+
+        validate_output_format(self.output_mime_type.as_deref(), self.compression_quality, &mut errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Warn when the prompt looks like a language `model` does not support.
+    ///
+    /// This is advisory only: detection is a script heuristic, and the request
+    /// is still sent.
+    pub fn language_warning(&self, model: &ImagenModel) -> Option<String> {
+        let detected = detect_prompt_language(&self.prompt)?;
+        (!supports_prompt_language(model, detected)).then(|| {
+            format!(
+                "The prompt appears to be in a language ({}) that {} does not support; results may not match the prompt. Supported: {}",
+                detected,
+                model.id,
+                model.supported_languages.join(", ")
+            )
+        })
+    }
+
+    /// The prompt sent to Imagen, with the style preset merged in.
+    pub fn styled_prompt(&self) -> String {
+        apply_style_preset(&self.prompt, self.style_preset.as_deref())
+    }
+
+    /// Imagen `outputOptions` for the requested format, if any was set.
+    ///
+    /// Formats Imagen cannot emit are requested as lossless PNG and
+    /// transcoded after the response arrives.
+    pub fn output_options(&self) -> Option<ImagenOutputOptions> {
+        self.output_mime_type.as_ref().map(|mime_type| {
+            if TRANSCODED_OUTPUT_MIME_TYPES.contains(&mime_type.as_str()) {
+                ImagenOutputOptions {
+                    mime_type: "image/png".to_string(),
+                    compression_quality: None,
+                }
+            } else {
+                ImagenOutputOptions {
+                    mime_type: mime_type.clone(),
+                    compression_quality: self.compression_quality,
+                }
+            }
+        })
+    }
+
+    /// Fill unset safety options from the server configuration.
+    pub fn with_config_defaults(mut self, config: &Config) -> Self {
+        if self.safety_filter_level.is_none() {
+            self.safety_filter_level = config.image_safety_filter_level.clone();
+        }
+        if self.person_generation.is_none() {
+            self.person_generation = config.image_person_generation.clone();
+        }
+        self
+    }
+
+    /// Get the resolved model definition.
+    pub fn get_model(&self) -> Option<&'static ImagenModel> {
+        ModelRegistry::resolve_imagen(&self.model)
+    }
+}
+
+/// Image generation handler.
+///
+/// Handles image generation requests using the Vertex AI Imagen API.
+pub struct ImageHandler {
+    /// Application configuration.
+    pub config: Config,
+    /// GCS client for storage operations.
+    pub gcs: GcsClient,
+    /// HTTP client for API requests.
+    pub http: reqwest::Client,
+    /// Authentication provider (shared with the GCS client).
+    pub auth: Arc<AuthProvider>,
+    /// Cache of deterministic generation results.
+    pub cache: Arc<dyn ResultCache>,
+}
+
+impl ImageHandler {
+    /// Create a new ImageHandler with the given configuration.
+    ///
+    /// # Errors
+    /// Returns an error if GCS client or auth provider initialization fails.
+    #[instrument(level = "debug", name = "image_handler_new", skip_all)]
+    pub async fn new(config: Config) -> Result<Self, Error> {
+        debug!("Initializing ImageHandler");
+
+        let auth = Arc::new(AuthProvider::new().await?);
+        Self::with_auth(config, auth)
+    }
+
+    /// Create a new ImageHandler around an existing auth provider.
+    ///
+    /// The provider (and its token cache) is shared by API calls, the GCS
+    /// client and a GCS-backed result cache.
+    ///
+    /// # Errors
+    /// Returns an error if the HTTP client or result cache cannot be configured.
+    pub fn with_auth(config: Config, auth: Arc<AuthProvider>) -> Result<Self, Error> {
+        let http = config.http.build()?;
+        let gcs = GcsClient::with_client(Arc::clone(&auth), http.clone());
+        let cache = cache::result_cache_from_config(&auth, &config)?;
+
+        Ok(Self {
+            config,
+            gcs,
+            http,
+            auth,
+            cache,
+        })
+    }
+
+    /// Create a new ImageHandler with provided dependencies (for testing).
+    #[cfg(test)]
+    pub fn with_deps(config: Config, gcs: GcsClient, http: reqwest::Client, auth: Arc<AuthProvider>) -> Self {
+        Self {
+            config,
+            gcs,
+            http,
+            auth,
+            cache: Arc::new(cache::InMemoryResultCache::new()),
+        }
+    }
+
+    /// Replace the result cache.
+    pub fn with_cache(mut self, cache: Arc<dyn ResultCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Get the Vertex AI Imagen API endpoint for the given model.
+    ///
+    /// `location` overrides the configured region for a single request.
+    pub fn get_endpoint(&self, model: &str, location: Option<&str>) -> String {
+        let location = self.config.location_or(location);
+        format!(
             "https://{}-aiplatform.googleapis.com/v1/projects/{}/locations/{}/publishers/google/models/{}:predict",
-            self.config.location,
+            location,
             self.config.project_id,
-            self.config.location,
+            location,
             model
         )
     }
@@ -327,10 +1166,13 @@ impl ImageHandler {
     /// * `params` - Image generation parameters
     ///
     /// # Returns
-    /// * `Ok(ImageGenerateResult)` - Generated images with their data or paths
+    /// * `Ok(ImageGenerateOutput)` - Generated images with their data or paths
     /// * `Err(Error)` - If validation fails, API call fails, or output handling fails
     #[instrument(level = "info", name = "generate_image", skip(self, params), fields(model = %params.model, aspect_ratio = %params.aspect_ratio))]
-    pub async fn generate_image(&self, params: ImageGenerateParams) -> Result<ImageGenerateResult, Error> {
+    pub async fn generate_image(&self, params: ImageGenerateParams) -> Result<ImageGenerateOutput, Error> {
+        let mut params = params.with_config_defaults(&self.config);
+        params.output_uri = self.resolve_output_uri(params.output_uri)?;
+
         // Validate parameters
         params.validate().map_err(|errors| {
             let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
@@ -339,21 +1181,42 @@ impl ImageHandler {
 
         // Resolve the model to get the canonical ID
         let model = params.get_model().ok_or_else(|| {
-            Error::validation(format!("Unknown model: {}", params.model))
+            Error::validation(ModelRegistry::unknown_model_message(IMAGEN_MODELS, &params.model))
         })?;
 
+        let warnings: Vec<String> = params.language_warning(model).into_iter().collect();
+        for warning in &warnings {
+            warn!(model_id = model.id, "{}", warning);
+        }
+
+        let cache_key = Self::result_cache_key(&params, model.id)?;
+        if let Some(key) = &cache_key {
+            if let Some(mut result) = self.cached_result(key, &params, model.id).await {
+                info!(cache_key = %key, "Returning cached image result");
+                result.warnings = warnings;
+                return Ok(result);
+            }
+        }
+
         info!(model_id = model.id, "Generating image with Imagen API");
 
         // Build the API request
         let request = ImagenRequest {
             instances: vec![ImagenInstance {
-                prompt: params.prompt.clone(),
+                prompt: params.styled_prompt(),
                 negative_prompt: params.negative_prompt.clone(),
             }],
             parameters: ImagenParameters {
                 sample_count: params.number_of_images,
                 aspect_ratio: params.aspect_ratio.clone(),
                 seed: params.seed,
+                add_watermark: params.add_watermark,
+                safety_setting: params.safety_filter_level.clone(),
+                person_generation: params.person_generation.clone(),
+                output_options: params.output_options(),
+                enhance_prompt: params.enhance_prompt,
+                guidance_scale: params.guidance_scale,
+                language: params.language.clone(),
             },
         };
 
@@ -361,22 +1224,22 @@ impl ImageHandler {
         let token = self.auth.get_token(&["https://www.googleapis.com/auth/cloud-platform"]).await?;
 
         // Make API request
-        let endpoint = self.get_endpoint(model.id);
+        let endpoint = self.get_endpoint(model.id, params.location.as_deref());
         debug!(endpoint = %endpoint, "Calling Imagen API");
 
-        let response = self.http
-            .post(&endpoint)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| Error::api(&endpoint, 0, format!("Request failed: {}", e)))?;
+        let response = send_with_retry(&self.predict_retry_policy(), &endpoint, || {
+            self.http
+                .post(&endpoint)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .json(&request)
+        })
+        .await?;
 
         let status = response.status();
         if !status.is_success() {
             let body = response.text().await.unwrap_or_default();
-            return Err(Error::api(&endpoint, status.as_u16(), body));
+            return Err(Error::api_response(&endpoint, status.as_u16(), body));
         }
 
         // Parse response
@@ -385,13 +1248,18 @@ impl ImageHandler {
         })?;
 
         // Extract images from response
+        let api_mime_type = params
+            .output_options()
+            .map_or_else(|| "image/png".to_string(), |options| options.mime_type);
         let images: Vec<GeneratedImage> = api_response
             .predictions
             .into_iter()
             .filter_map(|p| {
                 p.bytes_base64_encoded.map(|data| GeneratedImage {
                     data,
-                    mime_type: p.mime_type.unwrap_or_else(|| "image/png".to_string()),
+                    mime_type: p.mime_type.unwrap_or_else(|| api_mime_type.clone()),
+                    enhanced_prompt: p.prompt,
+                    info: None,
                 })
             })
             .collect();
@@ -402,28 +1270,356 @@ impl ImageHandler {
 
         info!(count = images.len(), "Received images from API");
 
+        // Convert to formats Imagen cannot emit (WebP, AVIF)
+        let images = match params.output_mime_type.as_deref() {
+            Some(mime_type) => images
+                .into_iter()
+                .map(|image| image.transcoded(mime_type, params.compression_quality))
+                .collect::<Result<Vec<_>, _>>()?,
+            None => images,
+        };
+
         // Handle output based on params
-        self.handle_output(images, &params).await
+        let written = self
+            .handle_output(images, params.output_file.as_deref(), params.output_uri.as_deref())
+            .await?;
+        let result = ImageGenerateOutput {
+            images: written.images,
+            failures: written.failures,
+            warnings,
+            model: model.id.to_string(),
+            prompt: params.prompt.clone(),
+        };
+
+        if params.write_metadata {
+            self.write_metadata(&params, &result).await;
+        }
+
+        if let Some(key) = &cache_key {
+            self.store_result(key, &result).await;
+        }
+
+        Ok(result)
     }
 
-    /// Handle output of generated images based on params.
+    /// Compute the result cache key for a generation request.
+    ///
+    /// Returns `None` when the request must not be cached: no seed (the output
+    /// is nondeterministic), no output destination (nothing to point back to),
+    /// or `no_cache` is set. The model is keyed by its canonical ID so aliases
+    /// share entries.
+    fn result_cache_key(params: &ImageGenerateParams, model_id: &str) -> Result<Option<String>, Error> {
+        if params.no_cache || params.seed.is_none() {
+            return Ok(None);
+        }
+        if params.output_uri.is_none() && params.output_file.is_none() {
+            return Ok(None);
+        }
+
+        let mut canonical = params.clone();
+        canonical.model = model_id.to_string();
+        cache::cache_key("image_generate", &canonical).map(Some)
+    }
+
+    /// Look up a cached result, treating cache errors and stale local files as misses.
+    async fn cached_result(
+        &self,
+        key: &str,
+        params: &ImageGenerateParams,
+        model_id: &str,
+    ) -> Option<ImageGenerateOutput> {
+        let cached = match self.cache.get(key).await {
+            Ok(Some(cached)) => cached,
+            Ok(None) => return None,
+            Err(e) => {
+                warn!(error = %e, "Result cache lookup failed");
+                return None;
+            }
+        };
+
+        if params.output_uri.is_none() && !cached.local_outputs_exist() {
+            return None;
+        }
+        Some(ImageGenerateOutput {
+            images: cached.uris.into_iter().map(ImageArtifact::from_location).collect(),
+            model: model_id.to_string(),
+            prompt: params.prompt.clone(),
+            failures: Vec::new(),
+            warnings: Vec::new(),
+        })
+    }
+
+    /// Write a metadata sidecar next to each saved image. Failures are logged,
+    /// not returned, since the images themselves were written.
+    async fn write_metadata(&self, params: &ImageGenerateParams, result: &ImageGenerateOutput) {
+        let metadata = GenerationMetadata {
+            negative_prompt: params.negative_prompt.clone(),
+            seed: params.seed,
+            aspect_ratio: Some(params.aspect_ratio.clone()),
+            ..GenerationMetadata::new(&params.prompt, &result.model)
+        };
+        for location in result.locations() {
+            match metadata::write_sidecar(&self.gcs, location, &metadata).await {
+                Ok(sidecar) => debug!(sidecar = %sidecar, "Wrote generation metadata"),
+                Err(e) => warn!(output = %location, error = %e, "Failed to write generation metadata"),
+            }
+        }
+    }
+
+    /// Record where a result was written. Failures are logged, not returned.
+    async fn store_result(&self, key: &str, result: &ImageGenerateOutput) {
+        let uris: Vec<String> = result.locations().into_iter().map(str::to_string).collect();
+        if uris.is_empty() {
+            return;
+        }
+        if let Err(e) = self.cache.put(key, &CachedResult { uris }).await {
+            warn!(error = %e, "Failed to store result in cache");
+        }
+    }
+
+    /// Edit an image using a mask and the Imagen capability model.
+    ///
+    /// # Arguments
+    /// * `params` - Image edit parameters
+    ///
+    /// # Returns
+    /// * `Ok(ImageGenerateOutput)` - Edited images with their data or paths
+    /// * `Err(Error)` - If validation fails, API call fails, or output handling fails
+    #[instrument(level = "info", name = "edit_image", skip(self, params), fields(edit_mode = ?params.edit_mode))]
+    pub async fn edit_image(&self, mut params: ImageEditParams) -> Result<ImageGenerateOutput, Error> {
+        params.output_uri = self.resolve_output_uri(params.output_uri)?;
+
+        // Validate parameters
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        info!(edit_mode = ?params.edit_mode, "Editing image with Imagen API");
+
+        // Resolve the source image and optional mask
+        let image_data = self.resolve_image_input(&params.image).await?;
+        let mask_data = match params.mask.as_deref().filter(|m| !m.trim().is_empty()) {
+            Some(mask) => Some(self.resolve_image_input(mask).await?),
+            None => None,
+        };
+
+        let request = ImagenEditRequest::new(&params, image_data, mask_data);
+        let images = self.call_edit_api(&request).await?;
+
+        info!(count = images.len(), "Received edited images from API");
+
+        let written = self
+            .handle_output(images, params.output_file.as_deref(), params.output_uri.as_deref())
+            .await?;
+        Ok(ImageGenerateOutput {
+            images: written.images,
+            failures: written.failures,
+            model: EDIT_MODEL.to_string(),
+            prompt: params.prompt,
+            warnings: Vec::new(),
+        })
+    }
+
+    /// Generate a variation of an image guided by a prompt.
+    ///
+    /// Uses the Imagen capability model's customization: in structure mode the
+    /// source's edge map is kept and the prompt sets the style; in style mode
+    /// the source's style is applied to the content the prompt describes.
+    ///
+    /// # Arguments
+    /// * `params` - Image variation parameters
+    ///
+    /// # Returns
+    /// * `Ok(ImageGenerateOutput)` - Generated images with their data or paths
+    /// * `Err(Error)` - If validation fails, API call fails, or output handling fails
+    #[instrument(level = "info", name = "vary_image", skip(self, params), fields(mode = ?params.mode))]
+    pub async fn vary_image(&self, mut params: ImageVariationParams) -> Result<ImageGenerateOutput, Error> {
+        params.output_uri = self.resolve_output_uri(params.output_uri)?;
+
+        // Validate parameters
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        info!(mode = ?params.mode, "Generating image variation with Imagen API");
+
+        let image_data = self.resolve_image_input(&params.image).await?;
+        let request = ImagenEditRequest::variation(&params, image_data);
+        let images = self.call_edit_api(&request).await?;
+
+        info!(count = images.len(), "Received image variations from API");
+
+        let written = self
+            .handle_output(images, params.output_file.as_deref(), params.output_uri.as_deref())
+            .await?;
+        Ok(ImageGenerateOutput {
+            images: written.images,
+            failures: written.failures,
+            model: EDIT_MODEL.to_string(),
+            prompt: params.prompt,
+            warnings: Vec::new(),
+        })
+    }
+
+    /// Extend an image's canvas to a new aspect ratio or size.
+    ///
+    /// The source is centered on the larger canvas and the model fills the
+    /// surrounding area, guided by the prompt.
+    ///
+    /// # Arguments
+    /// * `params` - Image outpaint parameters
+    ///
+    /// # Returns
+    /// * `Ok(ImageUpscaleResult)` - Outpainted image with its data or path
+    /// * `Err(Error)` - If validation fails, the source cannot be decoded, API call fails, or output handling fails
+    #[instrument(level = "info", name = "outpaint_image", skip(self, params))]
+    pub async fn outpaint_image(&self, mut params: ImageOutpaintParams) -> Result<ImageUpscaleResult, Error> {
+        params.output_uri = self.resolve_output_uri(params.output_uri)?;
+
+        // Validate parameters
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        let image_data = self.resolve_image_input(&params.image).await?;
+        let bytes = BASE64.decode(&image_data).map_err(|e| {
+            Error::validation(format!("Invalid base64 data: {}", e))
+        })?;
+        let source = image::load_from_memory(&bytes).map_err(|e| {
+            Error::validation(format!("Failed to decode source image: {}", e))
+        })?;
+
+        let target = params.target().ok_or_else(|| {
+            Error::validation("Either target_aspect_ratio or target_width and target_height must be set")
+        })?;
+        let layout = outpaint_layout(source.width(), source.height(), target)
+            .map_err(Error::validation)?;
+
+        info!(
+            source_width = source.width(),
+            source_height = source.height(),
+            canvas_width = layout.canvas_width,
+            canvas_height = layout.canvas_height,
+            "Outpainting image with Imagen API"
+        );
+
+        let (padded, mask) = pad_image_and_mask(&source, &layout);
+        let request = ImagenEditRequest::outpaint(
+            &params.prompt,
+            BASE64.encode(encode_png(&DynamicImage::ImageRgb8(padded))?),
+            BASE64.encode(encode_png(&DynamicImage::ImageLuma8(mask))?),
+        );
+
+        let image = self.call_edit_api(&request).await?.into_iter().next().ok_or_else(|| {
+            Error::api(self.get_endpoint(EDIT_MODEL, None), 200, "No image returned from API")
+        })?;
+
+        info!("Received outpainted image from API");
+
+        self.handle_single_output(image, params.output_file.as_deref(), params.output_uri.as_deref())
+            .await
+    }
+
+    /// Send an edit request to the Imagen capability model and collect the returned images.
+    async fn call_edit_api(&self, request: &ImagenEditRequest) -> Result<Vec<GeneratedImage>, Error> {
+        // Get auth token
+        let token = self.auth.get_token(&["https://www.googleapis.com/auth/cloud-platform"]).await?;
+
+        // Make API request
+        let endpoint = self.get_endpoint(EDIT_MODEL, None);
+        debug!(endpoint = %endpoint, "Calling Imagen edit API");
+
+        let response = send_with_retry(&RetryPolicy::from_config(&self.config), &endpoint, || {
+            self.http
+                .post(&endpoint)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .json(request)
+        })
+        .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::api_response(&endpoint, status.as_u16(), body));
+        }
+
+        // Parse response
+        let api_response: ImagenResponse = response.json().await.map_err(|e| {
+            Error::api(&endpoint, status.as_u16(), format!("Failed to parse response: {}", e))
+        })?;
+
+        let images: Vec<GeneratedImage> = api_response
+            .predictions
+            .into_iter()
+            .filter_map(|p| {
+                p.bytes_base64_encoded.map(|data| GeneratedImage {
+                    data,
+                    mime_type: p.mime_type.unwrap_or_else(|| "image/png".to_string()),
+                    enhanced_prompt: p.prompt,
+                    info: None,
+                })
+            })
+            .collect();
+
+        if images.is_empty() {
+            return Err(Error::api(&endpoint, 200, "No images returned from API"));
+        }
+
+        Ok(images)
+    }
+
+    /// Retry policy for Imagen predict calls (generate, upscale).
+    ///
+    /// Imagen quotas are tight, so 429 and 503 responses are retried with
+    /// backoff (or `Retry-After`) up to `API_MAX_ATTEMPTS` times, capped at
+    /// [`PREDICT_MAX_ATTEMPTS`]; other errors fail immediately.
+    fn predict_retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.config.api_max_attempts.min(PREDICT_MAX_ATTEMPTS),
+            ..RetryPolicy::from_config(&self.config)
+        }
+        .with_retry_statuses(QUOTA_RETRY_STATUSES)
+    }
+
+    /// Expand a bare object name in `output_uri` to a URI in the configured bucket.
+    fn resolve_output_uri(&self, output_uri: Option<String>) -> Result<Option<String>, Error> {
+        output_uri
+            .map(|uri| self.config.resolve_output_uri(&uri))
+            .transpose()
+    }
+
+    /// Write each image to the requested destination.
+    ///
+    /// Images are handled independently: one that cannot be decoded or written
+    /// is reported in `failures` while the others are kept. Fails only when no
+    /// image could be written.
     async fn handle_output(
         &self,
         images: Vec<GeneratedImage>,
-        params: &ImageGenerateParams,
-    ) -> Result<ImageGenerateResult, Error> {
+        output_file: Option<&str>,
+        output_uri: Option<&str>,
+    ) -> Result<WrittenImages, Error> {
         // If output_uri is specified, upload to storage
-        if let Some(output_uri) = &params.output_uri {
+        if let Some(output_uri) = output_uri {
             return self.upload_to_storage(images, output_uri).await;
         }
 
         // If output_file is specified, save to local file
-        if let Some(output_file) = &params.output_file {
+        if let Some(output_file) = output_file {
             return self.save_to_file(images, output_file).await;
         }
 
         // Otherwise, return base64-encoded data
-        Ok(ImageGenerateResult::Base64(images))
+        WrittenImages::collect(
+            images
+                .into_iter()
+                .map(|image| image.with_info().map(ImageArtifact::inline))
+                .collect(),
+        )
     }
 
     /// Upload images to cloud storage.
@@ -431,32 +1627,38 @@ impl ImageHandler {
         &self,
         images: Vec<GeneratedImage>,
         output_uri: &str,
-    ) -> Result<ImageGenerateResult, Error> {
-        let mut uris = Vec::new();
-
-        for (i, image) in images.iter().enumerate() {
-            // Decode base64 data
-            let data = BASE64.decode(&image.data).map_err(|e| {
-                Error::validation(format!("Invalid base64 data: {}", e))
-            })?;
+    ) -> Result<WrittenImages, Error> {
+        let count = images.len();
+        let mut outcomes = Vec::with_capacity(count);
 
+        for (i, image) in images.into_iter().enumerate() {
             // Determine the URI for this image
-            let uri = if images.len() == 1 {
+            let uri = if count == 1 {
                 output_uri.to_string()
             } else {
                 // Add index suffix for multiple images
                 // Handle GCS URIs properly - don't use Path which treats gs:// as filesystem path
-                Self::add_index_suffix_to_uri(output_uri, i, "image", "png")
+                Self::add_index_suffix_to_uri(output_uri, i, "image", extension_for_mime(&image.mime_type))
             };
-
-            // Parse GCS URI and upload
-            let gcs_uri = GcsUri::parse(&uri)?;
-            self.gcs.upload(&gcs_uri, &data, &image.mime_type).await?;
-            uris.push(uri);
+            let uri = with_mime_extension(&uri, &image.mime_type);
+            outcomes.push(self.upload_image(image, uri).await);
         }
 
-        info!(count = uris.len(), "Uploaded images to storage");
-        Ok(ImageGenerateResult::StorageUris(uris))
+        let written = WrittenImages::collect(outcomes)?;
+        info!(count = written.images.len(), failed = written.failures.len(), "Uploaded images to storage");
+        Ok(written)
+    }
+
+    /// Upload one image to `uri`.
+    async fn upload_image(&self, image: GeneratedImage, uri: String) -> Result<ImageArtifact, Error> {
+        let image = image.with_info()?;
+        let data = BASE64.decode(&image.data).map_err(|e| {
+            Error::validation(format!("Invalid base64 data: {}", e))
+        })?;
+
+        let gcs_uri = GcsUri::parse(&uri)?;
+        self.gcs.upload(&gcs_uri, &data, &image.mime_type).await?;
+        Ok(ImageArtifact::written(&image, uri))
     }
 
     /// Add an index suffix to a URI or path for multi-output scenarios.
@@ -512,23 +1714,25 @@ impl ImageHandler {
         &self,
         images: Vec<GeneratedImage>,
         output_file: &str,
-    ) -> Result<ImageGenerateResult, Error> {
-        let mut paths = Vec::new();
-
-        for (i, image) in images.iter().enumerate() {
-            // Decode base64 data
-            let data = BASE64.decode(&image.data).map_err(|e| {
-                Error::validation(format!("Invalid base64 data: {}", e))
-            })?;
-
+    ) -> Result<WrittenImages, Error> {
+        let output_file = self.config.resolve_local_path(output_file);
+        let output_file = output_file.to_string_lossy();
+        let output_file = output_file.as_ref();
+        let count = images.len();
+        let mut outcomes = Vec::with_capacity(count);
+
+        for (i, image) in images.into_iter().enumerate() {
             // Determine the path for this image
-            let path = if images.len() == 1 {
+            let path = if count == 1 {
                 output_file.to_string()
             } else {
                 // Add index suffix for multiple images
                 let p = Path::new(output_file);
                 let stem = p.file_stem().and_then(|s| s.to_str()).unwrap_or("image");
-                let ext = p.extension().and_then(|s| s.to_str()).unwrap_or("png");
+                let ext = p
+                    .extension()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or_else(|| extension_for_mime(&image.mime_type));
                 let parent = p.parent().and_then(|p| p.to_str()).unwrap_or("");
                 if parent.is_empty() {
                     format!("{}_{}.{}", stem, i, ext)
@@ -536,21 +1740,32 @@ impl ImageHandler {
                     format!("{}/{}_{}.{}", parent, stem, i, ext)
                 }
             };
+            let path = with_mime_extension(&path, &image.mime_type);
+            outcomes.push(Self::save_image(image, path).await);
+        }
 
-            // Ensure parent directory exists
-            if let Some(parent) = Path::new(&path).parent() {
-                if !parent.as_os_str().is_empty() {
-                    tokio::fs::create_dir_all(parent).await?;
-                }
-            }
+        let written = WrittenImages::collect(outcomes)?;
+        info!(count = written.images.len(), failed = written.failures.len(), "Saved images to local files");
+        Ok(written)
+    }
+
+    /// Save one image to `path`.
+    async fn save_image(image: GeneratedImage, path: String) -> Result<ImageArtifact, Error> {
+        let image = image.with_info()?;
+        let data = BASE64.decode(&image.data).map_err(|e| {
+            Error::validation(format!("Invalid base64 data: {}", e))
+        })?;
 
-            // Write to file
-            tokio::fs::write(&path, &data).await?;
-            paths.push(path);
+        // Ensure parent directory exists
+        if let Some(parent) = Path::new(&path).parent() {
+            if !parent.as_os_str().is_empty() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
         }
 
-        info!(count = paths.len(), "Saved images to local files");
-        Ok(ImageGenerateResult::LocalFiles(paths))
+        // Write to file
+        tokio::fs::write(&path, &data).await?;
+        Ok(ImageArtifact::written(&image, path))
     }
 
     /// Upscale an image using the Imagen Upscale API.
@@ -562,14 +1777,21 @@ impl ImageHandler {
     /// * `Ok(ImageUpscaleResult)` - Upscaled image with data or path
     /// * `Err(Error)` - If validation fails, API call fails, or output handling fails
     #[instrument(level = "info", name = "upscale_image", skip(self, params), fields(upscale_factor = %params.upscale_factor))]
-    pub async fn upscale_image(&self, params: ImageUpscaleParams) -> Result<ImageUpscaleResult, Error> {
+    pub async fn upscale_image(&self, mut params: ImageUpscaleParams) -> Result<ImageUpscaleResult, Error> {
+        params.output_uri = self.resolve_output_uri(params.output_uri)?;
+        let params = params.with_output_name();
+
         // Validate parameters
         params.validate().map_err(|errors| {
             let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
             Error::validation(messages.join("; "))
         })?;
 
-        info!(upscale_factor = %params.upscale_factor, "Upscaling image with Imagen Upscale API");
+        let model = params.get_model().ok_or_else(|| {
+            Error::validation(format!("No upscale model supports factor '{}'", params.upscale_factor))
+        })?;
+
+        info!(model_id = model.id, upscale_factor = %params.upscale_factor, "Upscaling image with Imagen Upscale API");
 
         // Resolve the image input
         let image_data = self.resolve_image_input(&params.image).await?;
@@ -591,22 +1813,22 @@ impl ImageHandler {
         let token = self.auth.get_token(&["https://www.googleapis.com/auth/cloud-platform"]).await?;
 
         // Make API request
-        let endpoint = self.get_upscale_endpoint();
+        let endpoint = self.get_upscale_endpoint(model.id);
         debug!(endpoint = %endpoint, "Calling Imagen Upscale API");
 
-        let response = self.http
-            .post(&endpoint)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| Error::api(&endpoint, 0, format!("Request failed: {}", e)))?;
+        let response = send_with_retry(&self.predict_retry_policy(), &endpoint, || {
+            self.http
+                .post(&endpoint)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .json(&request)
+        })
+        .await?;
 
         let status = response.status();
         if !status.is_success() {
             let body = response.text().await.unwrap_or_default();
-            return Err(Error::api(&endpoint, status.as_u16(), body));
+            return Err(Error::api_response(&endpoint, status.as_u16(), body));
         }
 
         // Parse response
@@ -621,117 +1843,138 @@ impl ImageHandler {
         let image_data = prediction.bytes_base64_encoded
             .ok_or_else(|| Error::api(&endpoint, 200, "No image data in response"))?;
 
-        let image = GeneratedImage {
+        let mut image = GeneratedImage {
             data: image_data,
             mime_type: prediction.mime_type.unwrap_or_else(|| "image/png".to_string()),
+            enhanced_prompt: None,
+            info: None,
         };
 
         info!("Received upscaled image from API");
 
+        if let Some(mime_type) = params.output_mime_type.as_deref() {
+            image = image.transcoded(mime_type, params.compression_quality)?;
+        }
+
         // Handle output based on params
-        self.handle_upscale_output(image, &params).await
+        self.handle_single_output(image, params.output_file.as_deref(), params.output_uri.as_deref())
+            .await
     }
 
-    /// Get the Vertex AI Imagen Upscale API endpoint.
-    pub fn get_upscale_endpoint(&self) -> String {
+    /// Get the Vertex AI Imagen Upscale API endpoint for `model_id`.
+    pub fn get_upscale_endpoint(&self, model_id: &str) -> String {
         format!(
             "https://{}-aiplatform.googleapis.com/v1/projects/{}/locations/{}/publishers/google/models/{}:predict",
             self.config.location,
             self.config.project_id,
             self.config.location,
-            UPSCALE_MODEL
+            model_id
         )
     }
 
-    /// Resolve image input to base64 data.
+    /// Resolve image input (base64, data URI, local path, GCS URI, or HTTPS URL) to base64 data.
     async fn resolve_image_input(&self, image: &str) -> Result<String, Error> {
-        // Check if it's a GCS URI first (explicit protocol)
-        if image.starts_with("gs://") {
-            let uri = GcsUri::parse(image)?;
-            let data = self.gcs.download(&uri).await?;
-            return Ok(BASE64.encode(&data));
-        }
-
-        // Check if it looks like a file path
-        let looks_like_path = image.starts_with('/')
-            || image.starts_with("./")
-            || image.starts_with("../")
-            || image.starts_with("~/")
-            || (image.len() < 500 && image.contains('/'));
-
-        if looks_like_path {
-            let path = Path::new(image);
-            if !path.exists() {
-                return Err(Error::validation(format!("Image file not found: {}", image)));
-            }
-            let data = tokio::fs::read(path).await?;
-            return Ok(BASE64.encode(&data));
-        }
-
-        // Try to validate as base64
-        if image.len() > 100 {
-            if BASE64.decode(image).is_ok() {
-                return Ok(image.to_string());
-            }
-        }
-
-        // Last resort: try as file path
-        let path = Path::new(image);
-        if path.exists() {
-            let data = tokio::fs::read(path).await?;
-            return Ok(BASE64.encode(&data));
-        }
-
-        // If nothing worked and it's long, assume it's base64
-        if image.len() > 100 {
-            return Ok(image.to_string());
-        }
-
-        Err(Error::validation(format!(
-            "Image input is not a valid file path, GCS URI, or base64 data"
-        )))
+        media_input::resolve_image_input(image, &self.gcs, &self.http).await
     }
 
-    /// Handle output of upscaled image based on params.
-    async fn handle_upscale_output(
+    /// Handle output of a single processed image based on the requested destination.
+    async fn handle_single_output(
         &self,
         image: GeneratedImage,
-        params: &ImageUpscaleParams,
+        output_file: Option<&str>,
+        output_uri: Option<&str>,
     ) -> Result<ImageUpscaleResult, Error> {
-        // If output_uri is specified, upload to storage
-        if let Some(output_uri) = &params.output_uri {
-            let data = BASE64.decode(&image.data).map_err(|e| {
-                Error::validation(format!("Invalid base64 data: {}", e))
-            })?;
-            let gcs_uri = GcsUri::parse(output_uri)?;
-            self.gcs.upload(&gcs_uri, &data, &image.mime_type).await?;
-            info!(uri = %output_uri, "Uploaded upscaled image to storage");
-            return Ok(ImageUpscaleResult::StorageUri(output_uri.clone()));
-        }
+        let written = self.handle_output(vec![image], output_file, output_uri).await?;
+        written
+            .images
+            .into_iter()
+            .next()
+            .map(ImageUpscaleResult::from)
+            .ok_or_else(|| Error::validation("No image to output"))
+    }
+}
 
-        // If output_file is specified, save to local file
-        if let Some(output_file) = &params.output_file {
-            let data = BASE64.decode(&image.data).map_err(|e| {
-                Error::validation(format!("Invalid base64 data: {}", e))
-            })?;
-
-            // Ensure parent directory exists
-            if let Some(parent) = Path::new(output_file).parent() {
-                if !parent.as_os_str().is_empty() {
-                    tokio::fs::create_dir_all(parent).await?;
-                }
-            }
+/// File extension for an image MIME type (`png` for unknown types).
+fn extension_for_mime(mime_type: &str) -> &'static str {
+    match mime_type {
+        "image/jpeg" => "jpg",
+        "image/webp" => "webp",
+        "image/avif" => "avif",
+        _ => "png",
+    }
+}
 
-            tokio::fs::write(output_file, &data).await?;
-            info!(path = %output_file, "Saved upscaled image to local file");
-            return Ok(ImageUpscaleResult::LocalFile(output_file.clone()));
-        }
+/// Image MIME type implied by a path or URI's extension (`image/png` if unknown).
+fn mime_for_location(location: &str) -> &'static str {
+    let name = location.rsplit('/').next().unwrap_or(location);
+    match name.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase()).as_deref() {
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        Some("avif") => "image/avif",
+        _ => "image/png",
+    }
+}
 
-        // Otherwise, return base64-encoded data
-        Ok(ImageUpscaleResult::Base64(image))
+/// Make a path or URI's extension agree with the image's MIME type.
+///
+/// A missing extension is appended; an image extension of a different format
+/// (e.g. `.png` for JPEG data) is replaced. Other extensions are kept as-is.
+fn with_mime_extension(path: &str, mime_type: &str) -> String {
+    let ext = extension_for_mime(mime_type);
+    let name_start = path.rfind('/').map_or(0, |i| i + 1);
+    let (stem, current) = match path[name_start..].rfind('.') {
+        Some(dot) => (&path[..name_start + dot], Some(&path[name_start + dot + 1..])),
+        None => (path, None),
+    };
+
+    match current.map(|e| e.to_ascii_lowercase()) {
+        None => format!("{}.{}", path, ext),
+        Some(current) => {
+            let matches = match ext {
+                "jpg" => current == "jpg" || current == "jpeg",
+                _ => current == ext,
+            };
+            let is_image_ext = matches!(current.as_str(), "png" | "jpg" | "jpeg" | "webp" | "avif");
+            if matches || !is_image_ext {
+                path.to_string()
+            } else {
+                format!("{}.{}", stem, ext)
+            }
+        }
     }
 }
 
+/// Encode an image as `mime_type` bytes (PNG for unknown types).
+///
+/// WebP output is lossless; `quality` applies to JPEG and AVIF.
+fn encode_image(image: &DynamicImage, mime_type: &str, quality: Option<u8>) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::new();
+    let result = match mime_type {
+        // JPEG has no alpha channel
+        "image/jpeg" => DynamicImage::ImageRgb8(image.to_rgb8()).write_with_encoder(
+            JpegEncoder::new_with_quality(&mut buf, quality.unwrap_or(DEFAULT_JPEG_QUALITY)),
+        ),
+        "image/webp" => image.write_with_encoder(WebPEncoder::new_lossless(&mut buf)),
+        "image/avif" => image.write_with_encoder(AvifEncoder::new_with_speed_quality(
+            &mut buf,
+            AVIF_ENCODER_SPEED,
+            quality.unwrap_or(DEFAULT_AVIF_QUALITY),
+        )),
+        _ => return encode_png(image),
+    };
+    result.map_err(|e| Error::validation(format!("Failed to encode {}: {}", mime_type, e)))?;
+    Ok(buf)
+}
+
+/// Encode an image as PNG bytes.
+fn encode_png(image: &DynamicImage) -> Result<Vec<u8>, Error> {
+    let mut buf = std::io::Cursor::new(Vec::new());
+    image
+        .write_to(&mut buf, image::ImageFormat::Png)
+        .map_err(|e| Error::validation(format!("Failed to encode PNG: {}", e)))?;
+    Ok(buf.into_inner())
+}
+
 // =============================================================================
 // API Request/Response Types
 // =============================================================================
@@ -767,6 +2010,38 @@ pub struct ImagenParameters {
     /// Random seed for reproducibility
     #[serde(skip_serializing_if = "Option::is_none")]
     pub seed: Option<i64>,
+    /// Whether to add a SynthID watermark (API default: true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub add_watermark: Option<bool>,
+    /// Safety filter level, e.g. "block_medium_and_above"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safety_setting: Option<String>,
+    /// Person generation policy, e.g. "dont_allow"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub person_generation: Option<String>,
+    /// Output format options
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_options: Option<ImagenOutputOptions>,
+    /// Whether to rewrite the prompt with an LLM before generating
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enhance_prompt: Option<bool>,
+    /// How strongly the output follows the prompt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guidance_scale: Option<f32>,
+    /// Prompt language, e.g. "ja" or "auto"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+/// Imagen output format options.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImagenOutputOptions {
+    /// Output MIME type ("image/png" or "image/jpeg")
+    pub mime_type: String,
+    /// JPEG compression quality (1-100)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compression_quality: Option<u8>,
 }
 
 /// Vertex AI Imagen API response.
@@ -784,6 +2059,246 @@ pub struct ImagenPrediction {
     pub bytes_base64_encoded: Option<String>,
     /// MIME type of the image
     pub mime_type: Option<String>,
+    /// Rewritten prompt (present when `enhancePrompt` was enabled)
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+// =============================================================================
+// Edit API Request Types
+// =============================================================================
+
+/// Reference ID of the source image in an edit request.
+const EDIT_RAW_REFERENCE_ID: u32 = 1;
+
+/// Reference ID of the mask in an edit request.
+const EDIT_MASK_REFERENCE_ID: u32 = 2;
+
+/// Vertex AI Imagen edit API request.
+#[derive(Debug, Serialize)]
+pub struct ImagenEditRequest {
+    /// Input instances (prompt plus reference images)
+    pub instances: Vec<ImagenEditInstance>,
+    /// Edit parameters
+    pub parameters: ImagenEditParameters,
+}
+
+impl ImagenEditRequest {
+    /// Build an edit request from validated params and resolved base64 inputs.
+    ///
+    /// When no mask is supplied (background_swap only), the request asks the
+    /// API to derive a background mask itself.
+    pub fn new(params: &ImageEditParams, image_data: String, mask_data: Option<String>) -> Self {
+        let mask_config = if mask_data.is_some() {
+            MaskImageConfig {
+                mask_mode: "MASK_MODE_USER_PROVIDED".to_string(),
+                dilation: None,
+            }
+        } else {
+            MaskImageConfig {
+                mask_mode: "MASK_MODE_BACKGROUND".to_string(),
+                dilation: None,
+            }
+        };
+        Self::build(
+            &params.prompt,
+            params.edit_mode.api_value(),
+            params.number_of_images,
+            image_data,
+            mask_data,
+            mask_config,
+        )
+    }
+
+    /// Build an outpainting request from a padded canvas and its mask.
+    pub fn outpaint(prompt: &str, padded_image: String, mask: String) -> Self {
+        Self::build(
+            prompt,
+            "EDIT_MODE_OUTPAINT",
+            1,
+            padded_image,
+            Some(mask),
+            MaskImageConfig {
+                mask_mode: "MASK_MODE_USER_PROVIDED".to_string(),
+                dilation: Some(OUTPAINT_MASK_DILATION),
+            },
+        )
+    }
+
+    /// Build a customization request using the source as a control or style reference.
+    ///
+    /// The prompt refers to the source as `[1]`, as the API expects.
+    pub fn variation(params: &ImageVariationParams, image_data: String) -> Self {
+        let reference_image = Some(EditImageInput { bytes_base64_encoded: image_data });
+        let (prompt, reference) = match params.mode {
+            ImageVariationMode::Structure => (
+                format!(
+                    "Generate an image aligning with the canny edge map [{}] to match the description: {}",
+                    EDIT_RAW_REFERENCE_ID, params.prompt
+                ),
+                EditReferenceImage {
+                    reference_type: "REFERENCE_TYPE_CONTROL".to_string(),
+                    reference_id: EDIT_RAW_REFERENCE_ID,
+                    reference_image,
+                    mask_image_config: None,
+                    control_image_config: Some(ControlImageConfig {
+                        control_type: "CONTROL_TYPE_CANNY".to_string(),
+                        enable_control_image_computation: true,
+                    }),
+                    style_image_config: None,
+                },
+            ),
+            ImageVariationMode::Style => (
+                format!(
+                    "Generate an image in style [{}] based on the following caption: {}",
+                    EDIT_RAW_REFERENCE_ID, params.prompt
+                ),
+                EditReferenceImage {
+                    reference_type: "REFERENCE_TYPE_STYLE".to_string(),
+                    reference_id: EDIT_RAW_REFERENCE_ID,
+                    reference_image,
+                    mask_image_config: None,
+                    control_image_config: None,
+                    style_image_config: Some(StyleImageConfig {
+                        style_description: params.style_description.clone(),
+                    }),
+                },
+            ),
+        };
+
+        Self {
+            instances: vec![ImagenEditInstance {
+                prompt,
+                reference_images: vec![reference],
+            }],
+            parameters: ImagenEditParameters {
+                edit_mode: None,
+                sample_count: params.number_of_images,
+            },
+        }
+    }
+
+    /// Assemble a request with the raw image as reference 1 and the mask as reference 2.
+    fn build(
+        prompt: &str,
+        edit_mode: &str,
+        sample_count: u8,
+        image_data: String,
+        mask_data: Option<String>,
+        mask_config: MaskImageConfig,
+    ) -> Self {
+        let mask_reference = EditReferenceImage {
+            reference_type: "REFERENCE_TYPE_MASK".to_string(),
+            reference_id: EDIT_MASK_REFERENCE_ID,
+            reference_image: mask_data.map(|data| EditImageInput { bytes_base64_encoded: data }),
+            mask_image_config: Some(mask_config),
+            control_image_config: None,
+            style_image_config: None,
+        };
+
+        Self {
+            instances: vec![ImagenEditInstance {
+                prompt: prompt.to_string(),
+                reference_images: vec![
+                    EditReferenceImage {
+                        reference_type: "REFERENCE_TYPE_RAW".to_string(),
+                        reference_id: EDIT_RAW_REFERENCE_ID,
+                        reference_image: Some(EditImageInput { bytes_base64_encoded: image_data }),
+                        mask_image_config: None,
+                        control_image_config: None,
+                        style_image_config: None,
+                    },
+                    mask_reference,
+                ],
+            }],
+            parameters: ImagenEditParameters {
+                edit_mode: Some(edit_mode.to_string()),
+                sample_count,
+            },
+        }
+    }
+}
+
+/// Imagen edit API instance.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImagenEditInstance {
+    /// Text prompt describing the edit
+    pub prompt: String,
+    /// Source image and mask references
+    pub reference_images: Vec<EditReferenceImage>,
+}
+
+/// Reference image in an edit request.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditReferenceImage {
+    /// Reference type: "REFERENCE_TYPE_RAW", "REFERENCE_TYPE_MASK",
+    /// "REFERENCE_TYPE_CONTROL", or "REFERENCE_TYPE_STYLE"
+    pub reference_type: String,
+    /// Reference ID, unique within the request
+    pub reference_id: u32,
+    /// Image data (omitted for auto-detected masks)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference_image: Option<EditImageInput>,
+    /// Mask configuration (mask references only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mask_image_config: Option<MaskImageConfig>,
+    /// Control configuration (control references only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub control_image_config: Option<ControlImageConfig>,
+    /// Style configuration (style references only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style_image_config: Option<StyleImageConfig>,
+}
+
+/// Edit image input.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditImageInput {
+    /// Base64-encoded image data
+    pub bytes_base64_encoded: String,
+}
+
+/// Mask configuration for a mask reference.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaskImageConfig {
+    /// Mask mode: "MASK_MODE_USER_PROVIDED" or "MASK_MODE_BACKGROUND"
+    pub mask_mode: String,
+    /// Fraction of the image size to grow the mask by, blending the seam
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dilation: Option<f64>,
+}
+
+/// Control configuration for a control reference.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlImageConfig {
+    /// Control type, e.g. "CONTROL_TYPE_CANNY"
+    pub control_type: String,
+    /// Let the API compute the control map from the raw source image
+    pub enable_control_image_computation: bool,
+}
+
+/// Style configuration for a style reference.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StyleImageConfig {
+    /// Optional short description of the reference style
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style_description: Option<String>,
+}
+
+/// Imagen edit API parameters.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImagenEditParameters {
+    /// Edit mode, e.g. "EDIT_MODE_INPAINT_INSERTION" (omitted for customization)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edit_mode: Option<String>,
+    /// Number of images to generate
+    pub sample_count: u8,
 }
 
 // =============================================================================
@@ -852,17 +2367,340 @@ pub struct GeneratedImage {
     pub data: String,
     /// MIME type of the image
     pub mime_type: String,
+    /// Prompt the model actually used, when it was rewritten (`enhance_prompt`)
+    pub enhanced_prompt: Option<String>,
+    /// Dimensions and size, filled in when the output is handled
+    pub info: Option<ImageInfo>,
 }
 
-/// Result of image generation.
-#[derive(Debug)]
-pub enum ImageGenerateResult {
-    /// Base64-encoded image data (when no output specified)
-    Base64(Vec<GeneratedImage>),
-    /// Local file paths (when output_file specified)
-    LocalFiles(Vec<String>),
-    /// Storage URIs (when output_uri specified)
-    StorageUris(Vec<String>),
+impl GeneratedImage {
+    /// Decode the image data and record its dimensions and size, unless
+    /// they are already known.
+    fn with_info(mut self) -> Result<Self, Error> {
+        if self.info.is_some() {
+            return Ok(self);
+        }
+        let data = BASE64.decode(&self.data).map_err(|e| {
+            Error::validation(format!("Invalid base64 data: {}", e))
+        })?;
+        self.info = Some(ImageInfo::from_bytes(&data));
+        Ok(self)
+    }
+
+    /// Re-encode the image as `mime_type`, keeping it as-is if it already is.
+    ///
+    /// `quality` applies to JPEG and AVIF. The dimensions are recorded since
+    /// AVIF headers cannot be read back.
+    pub fn transcoded(self, mime_type: &str, quality: Option<u8>) -> Result<Self, Error> {
+        if self.mime_type == mime_type {
+            return Ok(self);
+        }
+        let data = BASE64.decode(&self.data).map_err(|e| {
+            Error::validation(format!("Invalid base64 data: {}", e))
+        })?;
+        let decoded = image::load_from_memory(&data).map_err(|e| {
+            Error::validation(format!("Failed to decode {} image: {}", self.mime_type, e))
+        })?;
+        let encoded = encode_image(&decoded, mime_type, quality)?;
+        debug!(from = %self.mime_type, to = %mime_type, size_bytes = encoded.len(), "Transcoded image");
+
+        Ok(Self {
+            data: BASE64.encode(&encoded),
+            mime_type: mime_type.to_string(),
+            enhanced_prompt: self.enhanced_prompt,
+            info: Some(ImageInfo {
+                width: Some(decoded.width()),
+                height: Some(decoded.height()),
+                size_bytes: encoded.len() as u64,
+            }),
+        })
+    }
+}
+
+/// Dimensions and encoded size of an output image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ImageInfo {
+    /// Width in pixels (`None` if the header could not be read)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    /// Height in pixels (`None` if the header could not be read)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// Size of the encoded image in bytes
+    pub size_bytes: u64,
+}
+
+impl ImageInfo {
+    /// Read the dimensions from the image header without decoding pixels.
+    pub fn from_bytes(data: &[u8]) -> Self {
+        let (width, height) = image::ImageReader::new(Cursor::new(data))
+            .with_guessed_format()
+            .ok()
+            .and_then(|reader| reader.into_dimensions().ok())
+            .unzip();
+        Self {
+            width,
+            height,
+            size_bytes: data.len() as u64,
+        }
+    }
+}
+
+/// Per-image entry of the structured tool result.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImageSummary {
+    /// Local path or storage URI (absent for base64 output)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// MIME type of the image
+    pub mime_type: String,
+    /// Dimensions and size, when known
+    #[serde(flatten)]
+    pub info: Option<ImageInfo>,
+}
+
+impl From<&GeneratedImage> for ImageSummary {
+    fn from(image: &GeneratedImage) -> Self {
+        Self {
+            location: None,
+            mime_type: image.mime_type.clone(),
+            info: image.info,
+        }
+    }
+}
+
+impl From<&SavedImage> for ImageSummary {
+    fn from(image: &SavedImage) -> Self {
+        Self {
+            location: Some(image.location.clone()),
+            mime_type: image.mime_type.clone(),
+            info: image.info,
+        }
+    }
+}
+
+/// A generated image written to a local file or storage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedImage {
+    /// Local path or storage URI
+    pub location: String,
+    /// MIME type of the image
+    pub mime_type: String,
+    /// Prompt the model actually used, when it was rewritten (`enhance_prompt`)
+    pub enhanced_prompt: Option<String>,
+    /// Dimensions and size (`None` for cached results)
+    pub info: Option<ImageInfo>,
+}
+
+impl SavedImage {
+    /// Describe a previously written image from its location alone.
+    ///
+    /// The MIME type is inferred from the extension; the enhanced prompt and
+    /// dimensions are unknown.
+    pub fn from_location(location: String) -> Self {
+        let mime_type = mime_for_location(&location).to_string();
+        Self {
+            location,
+            mime_type,
+            enhanced_prompt: None,
+            info: None,
+        }
+    }
+}
+
+/// One output image of `image_generate`, `image_edit` or `image_variation`.
+///
+/// Exactly one of `data`, `local_path` and `gcs_uri` is set, depending on
+/// whether the image was returned inline, saved locally, or uploaded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ImageArtifact {
+    /// Base64-encoded image data (no output destination requested)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    /// Local file the image was saved to (output_file)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_path: Option<String>,
+    /// Storage URI the image was uploaded to (output_uri)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gcs_uri: Option<String>,
+    /// MIME type of the image
+    pub mime_type: String,
+    /// Width in pixels (unknown for cached results)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    /// Height in pixels (unknown for cached results)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// Size of the encoded image in bytes (unknown for cached results)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    /// Prompt the model actually used, when it was rewritten (`enhance_prompt`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enhanced_prompt: Option<String>,
+}
+
+impl ImageArtifact {
+    /// Artifact returning the image data inline.
+    fn inline(image: GeneratedImage) -> Self {
+        let info = image.info;
+        Self {
+            data: Some(image.data),
+            local_path: None,
+            gcs_uri: None,
+            mime_type: image.mime_type,
+            width: info.and_then(|i| i.width),
+            height: info.and_then(|i| i.height),
+            size_bytes: info.map(|i| i.size_bytes),
+            enhanced_prompt: image.enhanced_prompt,
+        }
+    }
+
+    /// Artifact for an image written to a local path or `gs://` URI.
+    fn written(image: &GeneratedImage, location: String) -> Self {
+        Self {
+            width: image.info.and_then(|i| i.width),
+            height: image.info.and_then(|i| i.height),
+            size_bytes: image.info.map(|i| i.size_bytes),
+            enhanced_prompt: image.enhanced_prompt.clone(),
+            mime_type: image.mime_type.clone(),
+            ..Self::from_location(location)
+        }
+    }
+
+    /// Describe a previously written image from its location alone.
+    ///
+    /// `gs://` locations become `gcs_uri`, anything else `local_path`. The MIME
+    /// type is inferred from the extension; dimensions are unknown.
+    pub fn from_location(location: String) -> Self {
+        let mime_type = mime_for_location(&location).to_string();
+        let (local_path, gcs_uri) = if location.starts_with("gs://") {
+            (None, Some(location))
+        } else {
+            (Some(location), None)
+        };
+        Self {
+            data: None,
+            local_path,
+            gcs_uri,
+            mime_type,
+            width: None,
+            height: None,
+            size_bytes: None,
+            enhanced_prompt: None,
+        }
+    }
+
+    /// Local path or storage URI (`None` for inline data).
+    pub fn location(&self) -> Option<&str> {
+        self.local_path.as_deref().or(self.gcs_uri.as_deref())
+    }
+
+    /// Dimensions and size, when known.
+    pub fn info(&self) -> Option<ImageInfo> {
+        self.size_bytes.map(|size_bytes| ImageInfo {
+            width: self.width,
+            height: self.height,
+            size_bytes,
+        })
+    }
+}
+
+impl From<ImageArtifact> for ImageUpscaleResult {
+    fn from(artifact: ImageArtifact) -> Self {
+        let info = artifact.info();
+        let saved = |location: String| SavedImage {
+            location,
+            mime_type: artifact.mime_type.clone(),
+            enhanced_prompt: artifact.enhanced_prompt.clone(),
+            info,
+        };
+        match (&artifact.local_path, &artifact.gcs_uri) {
+            (Some(path), _) => Self::LocalFile(saved(path.clone())),
+            (None, Some(uri)) => Self::StorageUri(saved(uri.clone())),
+            (None, None) => Self::Base64(GeneratedImage {
+                data: artifact.data.clone().unwrap_or_default(),
+                mime_type: artifact.mime_type.clone(),
+                enhanced_prompt: artifact.enhanced_prompt.clone(),
+                info,
+            }),
+        }
+    }
+}
+
+/// Result of image generation, editing, or variation.
+///
+/// This is also the tool's structured output, so its JSON schema is published
+/// as the tool output schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ImageGenerateOutput {
+    /// The output images
+    pub images: Vec<ImageArtifact>,
+    /// Images that were generated but could not be decoded or written
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<ImageFailure>,
+    /// Advisory notes about the request, e.g. an unsupported prompt language
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Canonical ID of the model that produced the images
+    pub model: String,
+    /// Prompt as given by the caller
+    pub prompt: String,
+}
+
+impl ImageGenerateOutput {
+    /// Rewritten prompts returned by the model, one per image that has one.
+    pub fn enhanced_prompts(&self) -> Vec<&str> {
+        self.images.iter().filter_map(|i| i.enhanced_prompt.as_deref()).collect()
+    }
+
+    /// Local paths and storage URIs of the images that were written out.
+    pub fn locations(&self) -> Vec<&str> {
+        self.images.iter().filter_map(ImageArtifact::location).collect()
+    }
+}
+
+/// A generated image that could not be decoded or written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ImageFailure {
+    /// Position of the image in the API response (0-based)
+    pub index: usize,
+    /// Why the image could not be output
+    pub error: String,
+}
+
+/// Images written by an output handler, and the ones that failed.
+#[derive(Debug, Default)]
+struct WrittenImages {
+    images: Vec<ImageArtifact>,
+    failures: Vec<ImageFailure>,
+}
+
+impl WrittenImages {
+    /// Collect per-image outcomes, in response order.
+    ///
+    /// Returns the first error when no image succeeded, so a single-image
+    /// request fails exactly as before.
+    fn collect(outcomes: Vec<Result<ImageArtifact, Error>>) -> Result<Self, Error> {
+        let mut written = Self::default();
+        let mut first_error = None;
+        for (index, outcome) in outcomes.into_iter().enumerate() {
+            match outcome {
+                Ok(artifact) => written.images.push(artifact),
+                Err(e) => {
+                    warn!(index, error = %e, "Failed to output image");
+                    written.failures.push(ImageFailure {
+                        index,
+                        error: e.to_string(),
+                    });
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) if written.images.is_empty() => Err(e),
+            _ => Ok(written),
+        }
+    }
 }
 
 /// Result of image upscaling.
@@ -870,15 +2708,54 @@ pub enum ImageGenerateResult {
 pub enum ImageUpscaleResult {
     /// Base64-encoded image data (when no output specified)
     Base64(GeneratedImage),
-    /// Local file path (when output_file specified)
-    LocalFile(String),
-    /// Storage URI (when output_uri specified)
-    StorageUri(String),
+    /// Saved local file (when output_file specified)
+    LocalFile(SavedImage),
+    /// Uploaded storage object (when output_uri specified)
+    StorageUri(SavedImage),
+}
+
+impl ImageUpscaleResult {
+    /// Location, format, dimensions and size of the image.
+    pub fn summary(&self) -> ImageSummary {
+        match self {
+            Self::Base64(image) => ImageSummary::from(image),
+            Self::LocalFile(image) | Self::StorageUri(image) => ImageSummary::from(image),
+        }
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handler_shares_one_auth_provider() {
+        let config = Config {
+            project_id: "test-project".to_string(),
+            ..Default::default()
+        };
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        let handler = ImageHandler::with_auth(config, Arc::clone(&auth)).unwrap();
+
+        assert!(Arc::ptr_eq(&handler.auth, &auth));
+        assert!(Arc::ptr_eq(handler.gcs.auth(), &auth));
+    }
+
+    #[test]
+    fn test_predict_retry_policy_honors_general_attempt_limit() {
+        for (api_max_attempts, expected) in [(1, 1), (3, 3), (10, PREDICT_MAX_ATTEMPTS)] {
+            let config = Config {
+                project_id: "test-project".to_string(),
+                api_max_attempts,
+                ..Default::default()
+            };
+            let handler = ImageHandler::with_auth(config, Arc::new(AuthProvider::mock("test-token"))).unwrap();
+
+            let policy = handler.predict_retry_policy();
+            assert_eq!(policy.max_attempts, expected, "API_MAX_ATTEMPTS={}", api_max_attempts);
+            assert_eq!(policy.retry_statuses, QUOTA_RETRY_STATUSES);
+        }
+    }
 
     #[test]
     fn test_default_params() {
@@ -901,8 +2778,20 @@ mod tests {
             aspect_ratio: "16:9".to_string(),
             number_of_images: 2,
             seed: Some(42),
+            add_watermark: Some(false),
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         assert!(params.validate().is_ok());
@@ -917,8 +2806,20 @@ mod tests {
             aspect_ratio: "1:1".to_string(),
             number_of_images: 0,
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = params.validate();
@@ -936,8 +2837,20 @@ mod tests {
             aspect_ratio: "1:1".to_string(),
             number_of_images: 5,
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = params.validate();
@@ -955,8 +2868,20 @@ mod tests {
             aspect_ratio: "2:1".to_string(),
             number_of_images: 1,
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = params.validate();
@@ -974,8 +2899,20 @@ mod tests {
             aspect_ratio: "1:1".to_string(),
             number_of_images: 1,
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = params.validate();
@@ -984,6 +2921,36 @@ mod tests {
         assert!(errors.iter().any(|e| e.field == "model"));
     }
 
+    #[test]
+    fn test_misspelled_model_suggests_closest() {
+        let params = ImageGenerateParams {
+            prompt: "A cat".to_string(),
+            negative_prompt: None,
+            model: "imagen-4.1".to_string(),
+            aspect_ratio: "1:1".to_string(),
+            number_of_images: 1,
+            seed: None,
+            add_watermark: None,
+            output_file: None,
+            output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
+        };
+
+        let errors = params.validate().unwrap_err();
+        let model_error = errors.iter().find(|e| e.field == "model").unwrap();
+        assert!(model_error.message.contains("did you mean 'imagen-4.0-generate-preview-06-06'?"));
+    }
+
     #[test]
     fn test_empty_prompt() {
         let params = ImageGenerateParams {
@@ -993,8 +2960,20 @@ mod tests {
             aspect_ratio: "1:1".to_string(),
             number_of_images: 1,
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = params.validate();
@@ -1013,8 +2992,20 @@ mod tests {
             aspect_ratio: "1:1".to_string(),
             number_of_images: 1,
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = params.validate();
@@ -1033,88 +3024,657 @@ mod tests {
             aspect_ratio: "1:1".to_string(),
             number_of_images: 1,
             seed: None,
+            add_watermark: None,
+            output_file: None,
+            output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
+        };
+
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_all_valid_aspect_ratios() {
+        for ratio in VALID_ASPECT_RATIOS {
+            let params = ImageGenerateParams {
+                prompt: "A cat".to_string(),
+                negative_prompt: None,
+                model: DEFAULT_MODEL.to_string(),
+                aspect_ratio: ratio.to_string(),
+                number_of_images: 1,
+                seed: None,
+                add_watermark: None,
+                output_file: None,
+                output_uri: None,
+                no_cache: false,
+                safety_filter_level: None,
+                person_generation: None,
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
+            };
+            assert!(params.validate().is_ok(), "Aspect ratio {} should be valid", ratio);
+        }
+    }
+
+    #[test]
+    fn test_all_valid_number_of_images() {
+        for n in MIN_NUMBER_OF_IMAGES..=MAX_NUMBER_OF_IMAGES {
+            let params = ImageGenerateParams {
+                prompt: "A cat".to_string(),
+                negative_prompt: None,
+                model: DEFAULT_MODEL.to_string(),
+                aspect_ratio: "1:1".to_string(),
+                number_of_images: n,
+                seed: None,
+                add_watermark: None,
+                output_file: None,
+                output_uri: None,
+                no_cache: false,
+                safety_filter_level: None,
+                person_generation: None,
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
+            };
+            assert!(params.validate().is_ok(), "number_of_images {} should be valid", n);
+        }
+    }
+
+    #[test]
+    fn test_get_model() {
+        let params = ImageGenerateParams {
+            prompt: "A cat".to_string(),
+            negative_prompt: None,
+            model: "imagen-4".to_string(),
+            aspect_ratio: "1:1".to_string(),
+            number_of_images: 1,
+            seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
+        };
+
+        let model = params.get_model();
+        assert!(model.is_some());
+        assert_eq!(model.unwrap().id, "imagen-4.0-generate-preview-06-06");
+    }
+
+    #[test]
+    fn test_serialization_roundtrip() {
+        let params = ImageGenerateParams {
+            prompt: "A cat".to_string(),
+            negative_prompt: Some("blurry".to_string()),
+            model: "imagen-4".to_string(),
+            aspect_ratio: "16:9".to_string(),
+            number_of_images: 2,
+            seed: Some(42),
+            add_watermark: Some(false),
+            output_file: Some("/tmp/output.png".to_string()),
+            output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
+        let json = serde_json::to_string(&params).unwrap();
+        let deserialized: ImageGenerateParams = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(params.prompt, deserialized.prompt);
+        assert_eq!(params.negative_prompt, deserialized.negative_prompt);
+        assert_eq!(params.model, deserialized.model);
+        assert_eq!(params.aspect_ratio, deserialized.aspect_ratio);
+        assert_eq!(params.number_of_images, deserialized.number_of_images);
+        assert_eq!(params.seed, deserialized.seed);
+        assert_eq!(params.add_watermark, deserialized.add_watermark);
+        assert_eq!(params.output_file, deserialized.output_file);
+    }
+
+    fn watermark_params(seed: Option<i64>, add_watermark: Option<bool>) -> ImageGenerateParams {
+        let mut params: ImageGenerateParams = serde_json::from_str(r#"{"prompt": "A cat"}"#).unwrap();
+        params.seed = seed;
+        params.add_watermark = add_watermark;
+        params
+    }
+
+    fn style_params(model: &str, style_preset: Option<&str>, guidance_scale: Option<f32>) -> ImageGenerateParams {
+        let mut params: ImageGenerateParams = serde_json::from_str(r#"{"prompt": "A cat"}"#).unwrap();
+        params.model = model.to_string();
+        params.style_preset = style_preset.map(str::to_string);
+        params.guidance_scale = guidance_scale;
+        params
+    }
+
+    #[test]
+    fn test_style_presets_have_fragments() {
+        for (name, fragment) in STYLE_PRESETS {
+            assert_eq!(style_fragment(name), Some(*fragment));
+            assert!(!fragment.is_empty());
+        }
+        assert_eq!(style_fragment("photorealistic"), Some("photorealistic, natural lighting, sharp focus, high detail"));
+        assert_eq!(style_fragment("Watercolor"), None);
+        assert_eq!(style_fragment("vaporwave"), None);
+    }
+
+    #[test]
+    fn test_apply_style_preset() {
+        assert_eq!(
+            apply_style_preset("A cat on a windowsill.", Some("line_art")),
+            "A cat on a windowsill, clean black and white line art, minimal shading, white background"
+        );
+        assert_eq!(
+            apply_style_preset("A harbor at dawn", Some("watercolor")),
+            "A harbor at dawn, watercolor painting, soft washes, visible paper texture"
+        );
+        assert_eq!(apply_style_preset("A cat", None), "A cat");
+        assert_eq!(apply_style_preset("A cat", Some("vaporwave")), "A cat");
+    }
+
+    #[test]
+    fn test_style_preset_validation() {
+        assert!(style_params(DEFAULT_MODEL, Some("watercolor"), None).validate().is_ok());
+
+        let errors = style_params(DEFAULT_MODEL, Some("vaporwave"), None).validate().unwrap_err();
+        let error = errors.iter().find(|e| e.field == "style_preset").unwrap();
+        assert!(error.message.contains("photorealistic"), "{}", error.message);
+    }
+
+    #[test]
+    fn test_style_preset_counts_toward_prompt_length() {
+        let mut params = style_params("imagen-3", Some("photorealistic"), None);
+        params.prompt = "a".repeat(470);
+        let errors = params.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "prompt"));
+
+        params.style_preset = None;
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_guidance_scale_validation() {
+        assert!(style_params("imagen-3", None, Some(7.5)).validate().is_ok());
+
+        for scale in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            let errors = style_params("imagen-3", None, Some(scale)).validate().unwrap_err();
+            assert!(errors.iter().any(|e| e.field == "guidance_scale"), "{scale}");
+        }
+
+        let errors = style_params("imagen-4", None, Some(7.5)).validate().unwrap_err();
+        let error = errors.iter().find(|e| e.field == "guidance_scale").unwrap();
+        assert!(error.message.contains("not supported"), "{}", error.message);
+    }
+
+    #[test]
+    fn test_detect_prompt_language() {
+        assert_eq!(detect_prompt_language("A cat on a windowsill"), None);
+        assert_eq!(detect_prompt_language("Un gato en la ventana"), None);
+        assert_eq!(detect_prompt_language("窓辺の猫"), Some("ja"));
+        assert_eq!(detect_prompt_language("ネコ"), Some("ja"));
+        assert_eq!(detect_prompt_language("窗台上的猫"), Some("zh"));
+        assert_eq!(detect_prompt_language("창가에 있는 고양이"), Some("ko"));
+        assert_eq!(detect_prompt_language("खिड़की पर बिल्ली"), Some("hi"));
+        assert_eq!(detect_prompt_language("Кошка на подоконнике"), Some("ru"));
+        assert_eq!(detect_prompt_language("قطة على النافذة"), Some("ar"));
+        assert_eq!(detect_prompt_language("Γάτα στο παράθυρο"), Some("el"));
+        assert_eq!(detect_prompt_language("12345 !?"), None);
+    }
+
+    #[test]
+    fn test_detect_prompt_language_needs_majority() {
+        // A single foreign word in an English prompt is not enough
+        assert_eq!(detect_prompt_language("A neon sign reading Москва over a street"), None);
+        assert_eq!(detect_prompt_language("Кошка на подоконнике, cat"), Some("ru"));
+    }
+
+    #[test]
+    fn test_supports_prompt_language() {
+        let model = ModelRegistry::resolve_imagen("imagen-4").unwrap();
+        assert!(supports_prompt_language(model, "ja"));
+        assert!(supports_prompt_language(model, "zh"));
+        assert!(supports_prompt_language(model, "zh-TW"));
+        assert!(!supports_prompt_language(model, "ru"));
+    }
+
+    #[test]
+    fn test_language_warning() {
+        let model = ModelRegistry::resolve_imagen("imagen-4").unwrap();
+        let mut params: ImageGenerateParams = serde_json::from_str(r#"{"prompt": "窓辺の猫"}"#).unwrap();
+        assert_eq!(params.language_warning(model), None);
+
+        params.prompt = "Кошка на подоконнике".to_string();
+        let warning = params.language_warning(model).unwrap();
+        assert!(warning.contains("(ru)"), "{}", warning);
+        assert!(params.validate().is_ok(), "the language warning must not fail validation");
+    }
+
+    #[test]
+    fn test_language_validation() {
+        let mut params: ImageGenerateParams = serde_json::from_str(r#"{"prompt": "A cat"}"#).unwrap();
+        for language in ["auto", "en", "ja", "zh-CN"] {
+            params.language = Some(language.to_string());
+            assert!(params.validate().is_ok(), "{language}");
+        }
+
+        params.language = Some("ru".to_string());
+        let errors = params.validate().unwrap_err();
+        let error = errors.iter().find(|e| e.field == "language").unwrap();
+        assert!(error.message.contains("zh-TW"), "{}", error.message);
+    }
+
+    fn format_params(mime_type: Option<&str>, quality: Option<u8>) -> ImageGenerateParams {
+        let mut params: ImageGenerateParams = serde_json::from_str(r#"{"prompt": "A cat"}"#).unwrap();
+        params.output_mime_type = mime_type.map(str::to_string);
+        params.compression_quality = quality;
+        params
+    }
+
+    #[test]
+    fn test_output_format_validation() {
+        assert!(format_params(Some("image/png"), None).validate().is_ok());
+        assert!(format_params(Some("image/jpeg"), Some(80)).validate().is_ok());
+
+        let errors = format_params(Some("image/gif"), None).validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "output_mime_type"));
+
+        let errors = format_params(Some("image/jpeg"), Some(0)).validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "compression_quality"));
+
+        let errors = format_params(Some("image/jpeg"), Some(101)).validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "compression_quality"));
+
+        assert!(format_params(Some("image/webp"), None).validate().is_ok());
+        assert!(format_params(Some("image/avif"), Some(50)).validate().is_ok());
+
+        // WebP is encoded losslessly
+        let errors = format_params(Some("image/webp"), Some(80)).validate().unwrap_err();
+        assert_eq!(errors[0].field, "compression_quality");
+    }
+
+    #[test]
+    fn test_compression_quality_requires_jpeg() {
+        for mime_type in [None, Some("image/png")] {
+            let errors = format_params(mime_type, Some(80)).validate().unwrap_err();
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].field, "compression_quality");
+            assert!(errors[0].message.contains("image/jpeg"));
+        }
+    }
+
+    #[test]
+    fn test_output_options_serialization() {
+        assert!(format_params(None, None).output_options().is_none());
+
+        let options = format_params(Some("image/jpeg"), Some(75)).output_options().unwrap();
+        assert_eq!(
+            serde_json::to_value(&options).unwrap(),
+            serde_json::json!({"mimeType": "image/jpeg", "compressionQuality": 75})
+        );
+
+        let options = format_params(Some("image/png"), None).output_options().unwrap();
+        assert_eq!(
+            serde_json::to_value(&options).unwrap(),
+            serde_json::json!({"mimeType": "image/png"})
+        );
+
+        // Transcoded formats are requested as PNG, without a quality
+        let options = format_params(Some("image/avif"), Some(60)).output_options().unwrap();
+        assert_eq!(
+            serde_json::to_value(&options).unwrap(),
+            serde_json::json!({"mimeType": "image/png"})
+        );
+    }
+
+    #[test]
+    fn test_with_mime_extension() {
+        assert_eq!(with_mime_extension("/tmp/out.png", "image/png"), "/tmp/out.png");
+        assert_eq!(with_mime_extension("/tmp/out.png", "image/jpeg"), "/tmp/out.jpg");
+        assert_eq!(with_mime_extension("/tmp/out.jpeg", "image/jpeg"), "/tmp/out.jpeg");
+        assert_eq!(with_mime_extension("/tmp/out", "image/jpeg"), "/tmp/out.jpg");
+        assert_eq!(with_mime_extension("gs://bucket/a.b/out_0.png", "image/jpeg"), "gs://bucket/a.b/out_0.jpg");
+        assert_eq!(with_mime_extension("gs://bucket/dir.v2/out", "image/png"), "gs://bucket/dir.v2/out.png");
+        assert_eq!(with_mime_extension("/tmp/out.img", "image/jpeg"), "/tmp/out.img");
+        assert_eq!(with_mime_extension("/tmp/out.png", "image/avif"), "/tmp/out.avif");
+        assert_eq!(with_mime_extension("/tmp/out.avif", "image/webp"), "/tmp/out.webp");
+    }
+
+    #[test]
+    fn test_seed_with_default_watermark_fails() {
+        let errors = watermark_params(Some(42), None).validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "add_watermark");
+        assert!(errors[0].message.contains("seed"));
+        assert!(errors[0].message.contains("false"));
+    }
+
+    #[test]
+    fn test_seed_with_watermark_enabled_fails() {
+        let errors = watermark_params(Some(42), Some(true)).validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "add_watermark"));
+    }
+
+    #[test]
+    fn test_seed_with_watermark_disabled_passes() {
+        assert!(watermark_params(Some(42), Some(false)).validate().is_ok());
+    }
+
+    #[test]
+    fn test_watermark_without_seed_passes() {
+        assert!(watermark_params(None, None).validate().is_ok());
+        assert!(watermark_params(None, Some(true)).validate().is_ok());
+        assert!(watermark_params(None, Some(false)).validate().is_ok());
+    }
+
+    // Tests for image_edit parameters
+    fn edit_params(edit_mode: ImageEditMode, mask: Option<&str>) -> ImageEditParams {
+        ImageEditParams {
+            image: "/tmp/source.png".to_string(),
+            mask: mask.map(str::to_string),
+            prompt: "A red sofa".to_string(),
+            edit_mode,
+            number_of_images: 1,
+            output_file: None,
+            output_uri: None,
+        }
+    }
+
+    #[test]
+    fn test_edit_params_inpaint_requires_mask() {
+        for mode in [ImageEditMode::InpaintInsert, ImageEditMode::InpaintRemove] {
+            let errors = edit_params(mode, None).validate().unwrap_err();
+            assert!(errors.iter().any(|e| e.field == "mask"), "{:?} should require a mask", mode);
+
+            let errors = edit_params(mode, Some("  ")).validate().unwrap_err();
+            assert!(errors.iter().any(|e| e.field == "mask"));
+
+            assert!(edit_params(mode, Some("gs://bucket/mask.png")).validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_edit_params_background_swap_mask_optional() {
+        assert!(edit_params(ImageEditMode::BackgroundSwap, None).validate().is_ok());
+        assert!(edit_params(ImageEditMode::BackgroundSwap, Some("/tmp/mask.png")).validate().is_ok());
+    }
+
+    #[test]
+    fn test_edit_params_prompt_rules() {
+        let mut params = edit_params(ImageEditMode::InpaintRemove, Some("/tmp/mask.png"));
+        params.prompt = String::new();
+        assert!(params.validate().is_ok(), "inpaint_remove allows an empty prompt");
+
+        params.edit_mode = ImageEditMode::InpaintInsert;
+        let errors = params.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "prompt"));
+    }
+
+    #[test]
+    fn test_edit_params_invalid_image_and_count() {
+        let mut params = edit_params(ImageEditMode::BackgroundSwap, None);
+        params.image = String::new();
+        params.number_of_images = 5;
+        let errors = params.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert!(fields.contains(&"image"));
+        assert!(fields.contains(&"number_of_images"));
+    }
+
+    #[test]
+    fn test_edit_params_deserialization() {
+        let params: ImageEditParams = serde_json::from_value(serde_json::json!({
+            "image": "gs://bucket/in.png",
+            "mask": "gs://bucket/mask.png",
+            "prompt": "A cat",
+            "edit_mode": "inpaint_insert"
+        }))
+        .unwrap();
+        assert_eq!(params.edit_mode, ImageEditMode::InpaintInsert);
+        assert_eq!(params.number_of_images, 1);
+
+        let bad: Result<ImageEditParams, _> = serde_json::from_value(serde_json::json!({
+            "image": "gs://bucket/in.png",
+            "edit_mode": "outpaint"
+        }));
+        assert!(bad.is_err());
+    }
+
+    // Tests for image_upscale parameters
+    fn upscale_params(factor: &str, model: Option<&str>) -> ImageUpscaleParams {
+        ImageUpscaleParams {
+            image: "gs://bucket/photos/photo.jpg".to_string(),
+            upscale_factor: factor.to_string(),
+            model: model.map(str::to_string),
+            output_file: None,
+            output_uri: None,
+            output_mime_type: None,
+            compression_quality: None,
+        }
+    }
+
+    #[test]
+    fn test_upscale_output_format_validation() {
+        let mut params = upscale_params("x2", None);
+        params.output_mime_type = Some("image/avif".to_string());
+        params.compression_quality = Some(60);
+        assert!(params.validate().is_ok());
+
+        params.output_mime_type = Some("image/webp".to_string());
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "compression_quality");
+    }
+
+    #[test]
+    fn test_upscale_model_selection() {
+        assert_eq!(upscale_params("x2", None).get_model().unwrap().id, UPSCALE_MODEL);
+        assert_eq!(upscale_params("x4", None).get_model().unwrap().id, "imagegeneration@002");
+        assert_eq!(
+            upscale_params("x2", Some("imagen-upscale-x4")).get_model().unwrap().id,
+            "imagegeneration@002"
+        );
+    }
+
+    #[test]
+    fn test_upscale_model_validation() {
+        assert!(upscale_params("x4", None).validate().is_ok());
+        assert!(upscale_params("x4", Some("imagegeneration@002")).validate().is_ok());
+
+        let errors = upscale_params("x4", Some("imagen-4-upscale")).validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "upscale_factor");
+        assert!(errors[0].message.contains("x2"));
+
+        let errors = upscale_params("x2", Some("imagen-3")).validate().unwrap_err();
+        assert_eq!(errors[0].field, "model");
+
+        // An invalid factor is reported once, not again against the model
+        let errors = upscale_params("x8", Some("imagen-4-upscale")).validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_upscaled_file_name() {
+        assert_eq!(upscaled_file_name("gs://bucket/photos/photo.jpg", "x4"), "photo_x4.png");
+        assert_eq!(upscaled_file_name("./in/cat.PNG", "x2"), "cat_x2.png");
+        assert_eq!(upscaled_file_name("https://example.com/a/b.webp?sig=abc", "x4"), "b_x4.png");
+        assert_eq!(upscaled_file_name("https://example.com/render", "x2"), "image_x2.png");
+        assert_eq!(upscaled_file_name("iVBORw0KGgoAAAANSUhEUg==", "x4"), "image_x4.png");
+        assert_eq!(upscaled_file_name("/tmp/.hidden.png", "x2"), ".hidden_x2.png");
+    }
+
+    #[test]
+    fn test_upscale_output_name_for_directory_uri() {
+        let mut params = upscale_params("x4", None);
+        params.output_uri = Some("gs://bucket/out/".to_string());
+        assert_eq!(
+            params.with_output_name().output_uri.as_deref(),
+            Some("gs://bucket/out/photo_x4.png")
+        );
+
+        let mut params = upscale_params("x4", None);
+        params.output_uri = Some("gs://bucket/out/big.png".to_string());
+        assert_eq!(
+            params.with_output_name().output_uri.as_deref(),
+            Some("gs://bucket/out/big.png")
+        );
+
+        assert!(upscale_params("x2", None).with_output_name().output_uri.is_none());
+    }
+
+    // Tests for image_variation parameters
+    fn variation_params(mode: ImageVariationMode) -> ImageVariationParams {
+        ImageVariationParams {
+            image: "/tmp/source.png".to_string(),
+            prompt: "A watercolor painting".to_string(),
+            mode,
+            style_description: None,
+            number_of_images: 1,
+            output_file: None,
+            output_uri: None,
+        }
+    }
+
+    #[test]
+    fn test_variation_params_valid() {
+        assert!(variation_params(ImageVariationMode::Structure).validate().is_ok());
+
+        let mut params = variation_params(ImageVariationMode::Style);
+        params.style_description = Some("pastel watercolor".to_string());
         assert!(params.validate().is_ok());
     }
 
     #[test]
-    fn test_all_valid_aspect_ratios() {
-        for ratio in VALID_ASPECT_RATIOS {
-            let params = ImageGenerateParams {
-                prompt: "A cat".to_string(),
-                negative_prompt: None,
-                model: DEFAULT_MODEL.to_string(),
-                aspect_ratio: ratio.to_string(),
-                number_of_images: 1,
-                seed: None,
-                output_file: None,
-                output_uri: None,
-            };
-            assert!(params.validate().is_ok(), "Aspect ratio {} should be valid", ratio);
+    fn test_variation_params_invalid() {
+        let mut params = variation_params(ImageVariationMode::Structure);
+        params.image = String::new();
+        params.prompt = "  ".to_string();
+        params.style_description = Some("pastel".to_string());
+        params.number_of_images = 0;
+        let errors = params.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["image", "prompt", "style_description", "number_of_images"]);
+    }
+
+    #[test]
+    fn test_variation_params_deserialization() {
+        let params: ImageVariationParams = serde_json::from_value(serde_json::json!({
+            "image": "https://example.com/photo.jpg",
+            "prompt": "As a pencil sketch"
+        }))
+        .unwrap();
+        assert_eq!(params.mode, ImageVariationMode::Structure);
+        assert_eq!(params.number_of_images, 1);
+
+        let bad: Result<ImageVariationParams, _> = serde_json::from_value(serde_json::json!({
+            "image": "gs://bucket/in.png",
+            "prompt": "A cat",
+            "mode": "upscale"
+        }));
+        assert!(bad.is_err());
+    }
+
+    // Tests for image_outpaint parameters
+    fn outpaint_params() -> ImageOutpaintParams {
+        ImageOutpaintParams {
+            image: "/tmp/source.png".to_string(),
+            prompt: "A wide mountain valley".to_string(),
+            target_aspect_ratio: Some("16:9".to_string()),
+            target_width: None,
+            target_height: None,
+            output_file: None,
+            output_uri: None,
         }
     }
 
     #[test]
-    fn test_all_valid_number_of_images() {
-        for n in MIN_NUMBER_OF_IMAGES..=MAX_NUMBER_OF_IMAGES {
-            let params = ImageGenerateParams {
-                prompt: "A cat".to_string(),
-                negative_prompt: None,
-                model: DEFAULT_MODEL.to_string(),
-                aspect_ratio: "1:1".to_string(),
-                number_of_images: n,
-                seed: None,
-                output_file: None,
-                output_uri: None,
-            };
-            assert!(params.validate().is_ok(), "number_of_images {} should be valid", n);
-        }
+    fn test_outpaint_params_aspect_ratio() {
+        let params = outpaint_params();
+        assert!(params.validate().is_ok());
+        assert_eq!(params.target(), Some(OutpaintTarget::AspectRatio(16, 9)));
     }
 
     #[test]
-    fn test_get_model() {
-        let params = ImageGenerateParams {
-            prompt: "A cat".to_string(),
-            negative_prompt: None,
-            model: "imagen-4".to_string(),
-            aspect_ratio: "1:1".to_string(),
-            number_of_images: 1,
-            seed: None,
-            output_file: None,
-            output_uri: None,
-        };
+    fn test_outpaint_params_dimensions() {
+        let mut params = outpaint_params();
+        params.target_aspect_ratio = None;
+        params.target_width = Some(2048);
+        params.target_height = Some(1024);
+        assert!(params.validate().is_ok());
+        assert_eq!(params.target(), Some(OutpaintTarget::Dimensions(2048, 1024)));
 
-        let model = params.get_model();
-        assert!(model.is_some());
-        assert_eq!(model.unwrap().id, "imagen-4.0-generate-preview-06-06");
+        params.target_height = None;
+        let errors = params.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "target_width"));
     }
 
     #[test]
-    fn test_serialization_roundtrip() {
-        let params = ImageGenerateParams {
-            prompt: "A cat".to_string(),
-            negative_prompt: Some("blurry".to_string()),
-            model: "imagen-4".to_string(),
-            aspect_ratio: "16:9".to_string(),
-            number_of_images: 2,
-            seed: Some(42),
-            output_file: Some("/tmp/output.png".to_string()),
-            output_uri: None,
-        };
+    fn test_outpaint_params_target_required_and_exclusive() {
+        let mut params = outpaint_params();
+        params.target_aspect_ratio = None;
+        assert!(params.validate().is_err());
+
+        let mut both = outpaint_params();
+        both.target_width = Some(2048);
+        both.target_height = Some(1024);
+        let errors = both.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "target_aspect_ratio"));
+    }
 
-        let json = serde_json::to_string(&params).unwrap();
-        let deserialized: ImageGenerateParams = serde_json::from_str(&json).unwrap();
+    #[test]
+    fn test_outpaint_params_invalid_ratio_and_prompt() {
+        let mut params = outpaint_params();
+        params.target_aspect_ratio = Some("wide".to_string());
+        params.prompt = " ".to_string();
+        let errors = params.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert!(fields.contains(&"target_aspect_ratio"));
+        assert!(fields.contains(&"prompt"));
+    }
 
-        assert_eq!(params.prompt, deserialized.prompt);
-        assert_eq!(params.negative_prompt, deserialized.negative_prompt);
-        assert_eq!(params.model, deserialized.model);
-        assert_eq!(params.aspect_ratio, deserialized.aspect_ratio);
-        assert_eq!(params.number_of_images, deserialized.number_of_images);
-        assert_eq!(params.seed, deserialized.seed);
-        assert_eq!(params.output_file, deserialized.output_file);
+    #[test]
+    fn test_encode_png_round_trip() {
+        let img = DynamicImage::ImageLuma8(image::GrayImage::from_pixel(3, 2, image::Luma([255])));
+        let bytes = encode_png(&img).unwrap();
+        let decoded = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (3, 2));
     }
 
     // Tests for GCS URI handling (P1 fix)
@@ -1207,6 +3767,35 @@ mod property_tests {
         ]
     }
 
+    /// Strategy to generate valid safety filter levels (or none)
+    fn valid_safety_filter_level_strategy() -> impl Strategy<Value = Option<String>> {
+        prop_oneof![
+            Just(None),
+            proptest::sample::select(VALID_SAFETY_FILTER_LEVELS).prop_map(|s| Some(s.to_string())),
+        ]
+    }
+
+    /// Strategy to generate valid person generation policies (or none)
+    fn valid_person_generation_strategy() -> impl Strategy<Value = Option<String>> {
+        prop_oneof![
+            Just(None),
+            proptest::sample::select(VALID_PERSON_GENERATION).prop_map(|s| Some(s.to_string())),
+        ]
+    }
+
+    /// Strategy to generate invalid enum values for the safety options
+    fn invalid_safety_option_strategy() -> impl Strategy<Value = String> {
+        prop_oneof![
+            Just("".to_string()),
+            Just("BLOCK_MEDIUM_AND_ABOVE".to_string()),
+            Just("allow".to_string()),
+            "[a-z_]{1,30}".prop_filter("Must not be a valid option", |s| {
+                !VALID_SAFETY_FILTER_LEVELS.contains(&s.as_str())
+                    && !VALID_PERSON_GENERATION.contains(&s.as_str())
+            }),
+        ]
+    }
+
     /// Strategy to generate valid prompts (non-empty, within length limits)
     fn valid_prompt_strategy() -> impl Strategy<Value = String> {
         "[a-zA-Z0-9 ]{1,100}".prop_map(|s| s.trim().to_string())
@@ -1227,8 +3816,20 @@ mod property_tests {
                 aspect_ratio: "1:1".to_string(),
                 number_of_images: num,
                 seed: None,
+                add_watermark: None,
                 output_file: None,
                 output_uri: None,
+                no_cache: false,
+                safety_filter_level: None,
+                person_generation: None,
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
             };
 
             let result = params.validate();
@@ -1253,8 +3854,20 @@ mod property_tests {
                 aspect_ratio: "1:1".to_string(),
                 number_of_images: num,
                 seed: None,
+                add_watermark: None,
                 output_file: None,
                 output_uri: None,
+                no_cache: false,
+                safety_filter_level: None,
+                person_generation: None,
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
             };
 
             let result = params.validate();
@@ -1292,8 +3905,20 @@ mod property_tests {
                 aspect_ratio: ratio.to_string(),
                 number_of_images: 1,
                 seed: None,
+                add_watermark: None,
                 output_file: None,
                 output_uri: None,
+                no_cache: false,
+                safety_filter_level: None,
+                person_generation: None,
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
             };
 
             let result = params.validate();
@@ -1318,8 +3943,20 @@ mod property_tests {
                 aspect_ratio: ratio.clone(),
                 number_of_images: 1,
                 seed: None,
+                add_watermark: None,
                 output_file: None,
                 output_uri: None,
+                no_cache: false,
+                safety_filter_level: None,
+                person_generation: None,
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
             };
 
             let result = params.validate();
@@ -1345,88 +3982,462 @@ mod property_tests {
             );
         }
 
-        /// Property: Combination of valid parameters should always pass validation
-        #[test]
-        fn valid_params_combination_passes(
-            num in valid_number_of_images_strategy(),
-            ratio in valid_aspect_ratio_strategy(),
-            prompt in valid_prompt_strategy(),
-        ) {
-            let params = ImageGenerateParams {
-                prompt,
+        /// Property: Combination of valid parameters should always pass validation
+        #[test]
+        fn valid_params_combination_passes(
+            num in valid_number_of_images_strategy(),
+            ratio in valid_aspect_ratio_strategy(),
+            prompt in valid_prompt_strategy(),
+            safety in valid_safety_filter_level_strategy(),
+            person in valid_person_generation_strategy(),
+        ) {
+            let params = ImageGenerateParams {
+                prompt,
+                negative_prompt: None,
+                model: DEFAULT_MODEL.to_string(),
+                aspect_ratio: ratio.to_string(),
+                number_of_images: num,
+                seed: None,
+                add_watermark: None,
+                output_file: None,
+                output_uri: None,
+                no_cache: false,
+                safety_filter_level: safety,
+                person_generation: person,
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
+            };
+
+            let result = params.validate();
+            prop_assert!(
+                result.is_ok(),
+                "Valid params (num={}, ratio='{}') should pass, but got: {:?}",
+                num,
+                ratio,
+                result.err()
+            );
+        }
+
+        /// Invalid safety_filter_level values are rejected with a field-specific error.
+        #[test]
+        fn invalid_safety_filter_level_fails_validation(
+            level in invalid_safety_option_strategy(),
+            prompt in valid_prompt_strategy(),
+        ) {
+            let params = ImageGenerateParams {
+                prompt,
+                negative_prompt: None,
+                model: DEFAULT_MODEL.to_string(),
+                aspect_ratio: "1:1".to_string(),
+                number_of_images: 1,
+                seed: None,
+                add_watermark: None,
+                output_file: None,
+                output_uri: None,
+                no_cache: false,
+                safety_filter_level: Some(level.clone()),
+                person_generation: None,
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
+            };
+
+            let errors = params.validate().unwrap_err();
+            prop_assert!(
+                errors.iter().any(|e| e.field == "safety_filter_level"),
+                "safety_filter_level '{}' should be rejected",
+                level
+            );
+        }
+
+        /// Invalid person_generation values are rejected with a field-specific error.
+        #[test]
+        fn invalid_person_generation_fails_validation(
+            policy in invalid_safety_option_strategy(),
+            prompt in valid_prompt_strategy(),
+        ) {
+            let params = ImageGenerateParams {
+                prompt,
+                negative_prompt: None,
+                model: DEFAULT_MODEL.to_string(),
+                aspect_ratio: "1:1".to_string(),
+                number_of_images: 1,
+                seed: None,
+                add_watermark: None,
+                output_file: None,
+                output_uri: None,
+                no_cache: false,
+                safety_filter_level: None,
+                person_generation: Some(policy.clone()),
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
+            };
+
+            let errors = params.validate().unwrap_err();
+            prop_assert!(
+                errors.iter().any(|e| e.field == "person_generation"),
+                "person_generation '{}' should be rejected",
+                policy
+            );
+        }
+    }
+}
+
+/// Unit tests for API interactions and error handling.
+/// These tests verify the handler's behavior with mocked API responses.
+#[cfg(test)]
+mod api_tests {
+    use super::*;
+
+    /// Test that ImagenRequest serializes correctly for the API.
+    #[test]
+    fn test_imagen_request_serialization() {
+        let request = ImagenRequest {
+            instances: vec![ImagenInstance {
+                prompt: "A beautiful sunset".to_string(),
+                negative_prompt: Some("blurry".to_string()),
+            }],
+            parameters: ImagenParameters {
+                sample_count: 2,
+                aspect_ratio: "16:9".to_string(),
+                seed: Some(42),
+                add_watermark: Some(false),
+                safety_setting: None,
+                person_generation: None,
+                output_options: None,
+                enhance_prompt: Some(true),
+                guidance_scale: None,
+                language: None,
+            },
+        };
+
+        let json = serde_json::to_value(&request).unwrap();
+        
+        // Verify structure
+        assert!(json["instances"].is_array());
+        assert_eq!(json["instances"][0]["prompt"], "A beautiful sunset");
+        assert_eq!(json["instances"][0]["negativePrompt"], "blurry");
+        assert_eq!(json["parameters"]["sampleCount"], 2);
+        assert_eq!(json["parameters"]["aspectRatio"], "16:9");
+        assert_eq!(json["parameters"]["seed"], 42);
+        assert_eq!(json["parameters"]["addWatermark"], false);
+        assert_eq!(json["parameters"]["enhancePrompt"], true);
+    }
+
+    /// Test that an inpaint edit request carries the raw image and a user mask.
+    #[test]
+    fn test_imagen_edit_request_serialization_with_mask() {
+        let params = ImageEditParams {
+            image: "/tmp/in.png".to_string(),
+            mask: Some("/tmp/mask.png".to_string()),
+            prompt: "A vase of flowers".to_string(),
+            edit_mode: ImageEditMode::InpaintInsert,
+            number_of_images: 2,
+            output_file: None,
+            output_uri: None,
+        };
+        let request = ImagenEditRequest::new(&params, "aW1n".to_string(), Some("bWFzaw==".to_string()));
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "instances": [{
+                    "prompt": "A vase of flowers",
+                    "referenceImages": [
+                        {
+                            "referenceType": "REFERENCE_TYPE_RAW",
+                            "referenceId": 1,
+                            "referenceImage": { "bytesBase64Encoded": "aW1n" }
+                        },
+                        {
+                            "referenceType": "REFERENCE_TYPE_MASK",
+                            "referenceId": 2,
+                            "referenceImage": { "bytesBase64Encoded": "bWFzaw==" },
+                            "maskImageConfig": { "maskMode": "MASK_MODE_USER_PROVIDED" }
+                        }
+                    ]
+                }],
+                "parameters": {
+                    "editMode": "EDIT_MODE_INPAINT_INSERTION",
+                    "sampleCount": 2
+                }
+            })
+        );
+    }
+
+    /// Test that a background swap without a mask asks the API to detect the background.
+    #[test]
+    fn test_imagen_edit_request_serialization_background_auto_mask() {
+        let params = ImageEditParams {
+            image: "/tmp/in.png".to_string(),
+            mask: None,
+            prompt: "A beach at sunset".to_string(),
+            edit_mode: ImageEditMode::BackgroundSwap,
+            number_of_images: 1,
+            output_file: None,
+            output_uri: None,
+        };
+        let request = ImagenEditRequest::new(&params, "aW1n".to_string(), None);
+
+        let json = serde_json::to_value(&request).unwrap();
+        let mask = &json["instances"][0]["referenceImages"][1];
+        assert_eq!(mask["referenceType"], "REFERENCE_TYPE_MASK");
+        assert_eq!(mask["maskImageConfig"]["maskMode"], "MASK_MODE_BACKGROUND");
+        assert!(mask.get("referenceImage").is_none());
+        assert_eq!(json["parameters"]["editMode"], "EDIT_MODE_BGSWAP");
+    }
+
+    /// Test that an outpaint request uses the outpaint mode and a dilated user mask.
+    #[test]
+    fn test_imagen_outpaint_request_serialization() {
+        let request = ImagenEditRequest::outpaint("A wide valley", "cGFk".to_string(), "bWFzaw==".to_string());
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["parameters"]["editMode"], "EDIT_MODE_OUTPAINT");
+        assert_eq!(json["parameters"]["sampleCount"], 1);
+        assert_eq!(json["instances"][0]["prompt"], "A wide valley");
+        let refs = &json["instances"][0]["referenceImages"];
+        assert_eq!(refs[0]["referenceImage"]["bytesBase64Encoded"], "cGFk");
+        assert_eq!(refs[1]["referenceType"], "REFERENCE_TYPE_MASK");
+        assert_eq!(refs[1]["referenceImage"]["bytesBase64Encoded"], "bWFzaw==");
+        assert_eq!(refs[1]["maskImageConfig"]["maskMode"], "MASK_MODE_USER_PROVIDED");
+        assert_eq!(refs[1]["maskImageConfig"]["dilation"], 0.03);
+    }
+
+    /// Test that a structure variation sends the source as a canny control reference.
+    #[test]
+    fn test_imagen_variation_request_structure() {
+        let params = ImageVariationParams {
+            image: "/tmp/in.png".to_string(),
+            prompt: "A watercolor painting".to_string(),
+            mode: ImageVariationMode::Structure,
+            style_description: None,
+            number_of_images: 3,
+            output_file: None,
+            output_uri: None,
+        };
+        let request = ImagenEditRequest::variation(&params, "aW1n".to_string());
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "instances": [{
+                    "prompt": "Generate an image aligning with the canny edge map [1] to match the description: A watercolor painting",
+                    "referenceImages": [{
+                        "referenceType": "REFERENCE_TYPE_CONTROL",
+                        "referenceId": 1,
+                        "referenceImage": { "bytesBase64Encoded": "aW1n" },
+                        "controlImageConfig": {
+                            "controlType": "CONTROL_TYPE_CANNY",
+                            "enableControlImageComputation": true
+                        }
+                    }]
+                }],
+                "parameters": { "sampleCount": 3 }
+            })
+        );
+    }
+
+    /// Test that a style variation sends the source as a style reference.
+    #[test]
+    fn test_imagen_variation_request_style() {
+        let params = ImageVariationParams {
+            image: "/tmp/in.png".to_string(),
+            prompt: "A lighthouse at dusk".to_string(),
+            mode: ImageVariationMode::Style,
+            style_description: Some("pastel watercolor".to_string()),
+            number_of_images: 1,
+            output_file: None,
+            output_uri: None,
+        };
+        let request = ImagenEditRequest::variation(&params, "aW1n".to_string());
+
+        let json = serde_json::to_value(&request).unwrap();
+        let instance = &json["instances"][0];
+        assert_eq!(
+            instance["prompt"],
+            "Generate an image in style [1] based on the following caption: A lighthouse at dusk"
+        );
+        let reference = &instance["referenceImages"][0];
+        assert_eq!(reference["referenceType"], "REFERENCE_TYPE_STYLE");
+        assert_eq!(reference["styleImageConfig"]["styleDescription"], "pastel watercolor");
+        assert!(reference.get("controlImageConfig").is_none());
+        assert!(json["parameters"].get("editMode").is_none());
+    }
+
+    /// Test the API values for every edit mode.
+    #[test]
+    fn test_edit_mode_api_values() {
+        assert_eq!(ImageEditMode::InpaintInsert.api_value(), "EDIT_MODE_INPAINT_INSERTION");
+        assert_eq!(ImageEditMode::InpaintRemove.api_value(), "EDIT_MODE_INPAINT_REMOVAL");
+        assert_eq!(ImageEditMode::BackgroundSwap.api_value(), "EDIT_MODE_BGSWAP");
+    }
+
+    /// Test that ImagenRequest serializes without optional fields when not provided.
+    #[test]
+    fn test_imagen_request_serialization_minimal() {
+        let request = ImagenRequest {
+            instances: vec![ImagenInstance {
+                prompt: "A cat".to_string(),
+                negative_prompt: None,
+            }],
+            parameters: ImagenParameters {
+                sample_count: 1,
+                aspect_ratio: "1:1".to_string(),
+                seed: None,
+                add_watermark: None,
+                safety_setting: None,
+                person_generation: None,
+                output_options: None,
+                enhance_prompt: None,
+                guidance_scale: None,
+                language: None,
+            },
+        };
+
+        let json = serde_json::to_value(&request).unwrap();
+        
+        // Verify optional fields are not present
+        assert!(json["instances"][0].get("negativePrompt").is_none());
+        assert!(json["parameters"].get("seed").is_none());
+        assert!(json["parameters"].get("addWatermark").is_none());
+        assert!(json["parameters"].get("safetySetting").is_none());
+        assert!(json["parameters"].get("personGeneration").is_none());
+        assert!(json["parameters"].get("enhancePrompt").is_none());
+        assert!(json["parameters"].get("guidanceScale").is_none());
+        assert!(json["parameters"].get("language").is_none());
+    }
+
+    /// Test that the style preset and guidance scale reach the request.
+    #[test]
+    fn test_imagen_request_serialization_style() {
+        let mut params: ImageGenerateParams = serde_json::from_str(r#"{"prompt": "A cat"}"#).unwrap();
+        params.style_preset = Some("anime".to_string());
+        let request = ImagenRequest {
+            instances: vec![ImagenInstance {
+                prompt: params.styled_prompt(),
                 negative_prompt: None,
-                model: DEFAULT_MODEL.to_string(),
-                aspect_ratio: ratio.to_string(),
-                number_of_images: num,
+            }],
+            parameters: ImagenParameters {
+                sample_count: 1,
+                aspect_ratio: "1:1".to_string(),
                 seed: None,
-                output_file: None,
-                output_uri: None,
-            };
+                add_watermark: None,
+                safety_setting: None,
+                person_generation: None,
+                output_options: None,
+                enhance_prompt: None,
+                guidance_scale: Some(12.0),
+                language: None,
+            },
+        };
 
-            let result = params.validate();
-            prop_assert!(
-                result.is_ok(),
-                "Valid params (num={}, ratio='{}') should pass, but got: {:?}",
-                num,
-                ratio,
-                result.err()
-            );
-        }
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["instances"][0]["prompt"], "A cat, anime style, cel shading, bold outlines");
+        assert_eq!(json["parameters"]["guidanceScale"], 12.0);
     }
-}
-
-/// Unit tests for API interactions and error handling.
-/// These tests verify the handler's behavior with mocked API responses.
-#[cfg(test)]
-mod api_tests {
-    use super::*;
 
-    /// Test that ImagenRequest serializes correctly for the API.
+    /// Test that the prompt language is sent as `language`.
     #[test]
-    fn test_imagen_request_serialization() {
+    fn test_imagen_request_serialization_language() {
         let request = ImagenRequest {
             instances: vec![ImagenInstance {
-                prompt: "A beautiful sunset".to_string(),
-                negative_prompt: Some("blurry".to_string()),
+                prompt: "窓辺の猫".to_string(),
+                negative_prompt: None,
             }],
             parameters: ImagenParameters {
-                sample_count: 2,
-                aspect_ratio: "16:9".to_string(),
-                seed: Some(42),
+                sample_count: 1,
+                aspect_ratio: "1:1".to_string(),
+                seed: None,
+                add_watermark: None,
+                safety_setting: None,
+                person_generation: None,
+                output_options: None,
+                enhance_prompt: None,
+                guidance_scale: None,
+                language: Some("ja".to_string()),
             },
         };
 
         let json = serde_json::to_value(&request).unwrap();
-        
-        // Verify structure
-        assert!(json["instances"].is_array());
-        assert_eq!(json["instances"][0]["prompt"], "A beautiful sunset");
-        assert_eq!(json["instances"][0]["negativePrompt"], "blurry");
-        assert_eq!(json["parameters"]["sampleCount"], 2);
-        assert_eq!(json["parameters"]["aspectRatio"], "16:9");
-        assert_eq!(json["parameters"]["seed"], 42);
+        assert_eq!(json["parameters"]["language"], "ja");
     }
 
-    /// Test that ImagenRequest serializes without optional fields when not provided.
+    /// Test that safety options serialize with the keys Imagen expects.
     #[test]
-    fn test_imagen_request_serialization_minimal() {
+    fn test_imagen_request_serialization_safety_options() {
         let request = ImagenRequest {
             instances: vec![ImagenInstance {
-                prompt: "A cat".to_string(),
+                prompt: "A crowded market".to_string(),
                 negative_prompt: None,
             }],
             parameters: ImagenParameters {
                 sample_count: 1,
                 aspect_ratio: "1:1".to_string(),
                 seed: None,
+                add_watermark: None,
+                safety_setting: Some("block_medium_and_above".to_string()),
+                person_generation: Some("dont_allow".to_string()),
+                output_options: None,
+                enhance_prompt: None,
+                guidance_scale: None,
+                language: None,
             },
         };
 
         let json = serde_json::to_value(&request).unwrap();
-        
-        // Verify optional fields are not present
-        assert!(json["instances"][0].get("negativePrompt").is_none());
-        assert!(json["parameters"].get("seed").is_none());
+        assert_eq!(
+            json["parameters"],
+            serde_json::json!({
+                "sampleCount": 1,
+                "aspectRatio": "1:1",
+                "safetySetting": "block_medium_and_above",
+                "personGeneration": "dont_allow"
+            })
+        );
+    }
+
+    /// Test that config defaults fill unset safety options without overriding explicit ones.
+    #[test]
+    fn test_with_config_defaults() {
+        let config = Config {
+            project_id: "test-project".to_string(),
+            image_safety_filter_level: Some("block_medium_and_above".to_string()),
+            image_person_generation: Some("dont_allow".to_string()),
+            ..Default::default()
+        };
+
+        let params: ImageGenerateParams = serde_json::from_str(r#"{"prompt": "A cat"}"#).unwrap();
+        let params = params.with_config_defaults(&config);
+        assert_eq!(params.safety_filter_level.as_deref(), Some("block_medium_and_above"));
+        assert_eq!(params.person_generation.as_deref(), Some("dont_allow"));
+
+        let explicit: ImageGenerateParams = serde_json::from_str(
+            r#"{"prompt": "A cat", "safety_filter_level": "block_only_high", "person_generation": "allow_adult"}"#,
+        )
+        .unwrap();
+        let explicit = explicit.with_config_defaults(&config);
+        assert_eq!(explicit.safety_filter_level.as_deref(), Some("block_only_high"));
+        assert_eq!(explicit.person_generation.as_deref(), Some("allow_adult"));
     }
 
     /// Test that ImagenResponse deserializes correctly.
@@ -1448,6 +4459,27 @@ mod api_tests {
         assert_eq!(response.predictions[0].mime_type, Some("image/png".to_string()));
     }
 
+    /// Test that the rewritten prompt is read from an enhanced prediction.
+    #[test]
+    fn test_imagen_response_enhanced_prompt() {
+        let json = r#"{
+            "predictions": [
+                {
+                    "bytesBase64Encoded": "base64data1",
+                    "mimeType": "image/png",
+                    "prompt": "A fluffy orange cat sitting on a windowsill, soft morning light"
+                }
+            ]
+        }"#;
+
+        let response: ImagenResponse = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            response.predictions[0].prompt.as_deref(),
+            Some("A fluffy orange cat sitting on a windowsill, soft morning light")
+        );
+    }
+
     /// Test that ImagenResponse handles multiple predictions.
     #[test]
     fn test_imagen_response_multiple_predictions() {
@@ -1503,25 +4535,79 @@ mod api_tests {
     fn test_get_endpoint() {
         let config = Config {
             project_id: "my-project".to_string(),
-            location: "us-central1".to_string(),
-            gcs_bucket: None,
-            port: 8080,
+            ..Default::default()
         };
 
-        // Create a minimal handler for testing endpoint construction
-        // We can't create a full handler without auth, but we can test the URL format
-        let expected_url = format!(
-            "https://{}-aiplatform.googleapis.com/v1/projects/{}/locations/{}/publishers/google/models/{}:predict",
-            config.location,
-            config.project_id,
-            config.location,
-            "imagen-4.0-generate-preview-05-20"
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        let gcs = GcsClient::with_auth(Arc::clone(&auth));
+        let handler = ImageHandler::with_deps(config, gcs, reqwest::Client::new(), auth);
+
+        assert_eq!(
+            handler.get_endpoint("imagen-4.0-generate-preview-06-06", None),
+            "https://us-central1-aiplatform.googleapis.com/v1/projects/my-project/locations/us-central1/publishers/google/models/imagen-4.0-generate-preview-06-06:predict"
+        );
+
+        // A per-request location replaces the region in both the host and the path
+        assert_eq!(
+            handler.get_endpoint("imagen-4.0-generate-preview-06-06", Some("europe-west4")),
+            "https://europe-west4-aiplatform.googleapis.com/v1/projects/my-project/locations/europe-west4/publishers/google/models/imagen-4.0-generate-preview-06-06:predict"
+        );
+
+        // x4 without a model override targets the model that supports it
+        let params: ImageUpscaleParams =
+            serde_json::from_value(serde_json::json!({"image": "/tmp/in.png", "upscale_factor": "x4"})).unwrap();
+        assert_eq!(
+            handler.get_upscale_endpoint(params.get_model().unwrap().id),
+            "https://us-central1-aiplatform.googleapis.com/v1/projects/my-project/locations/us-central1/publishers/google/models/imagegeneration@002:predict"
         );
+    }
+
+    /// Test that data URIs pasted by web clients resolve to their payload.
+    #[tokio::test]
+    async fn test_resolve_image_input_data_uri() {
+        let config = Config {
+            project_id: "my-project".to_string(),
+            ..Default::default()
+        };
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        let gcs = GcsClient::with_auth(Arc::clone(&auth));
+        let handler = ImageHandler::with_deps(config, gcs, reqwest::Client::new(), auth);
+
+        let png = BASE64.encode(b"\x89PNG\r\n\x1a\n");
+        let uri = format!("data:image/png;base64,{}", png);
+        assert_eq!(handler.resolve_image_input(&uri).await.unwrap(), png);
+
+        let err = handler.resolve_image_input("data:text/plain;base64,aGk=").await.unwrap_err();
+        assert!(err.to_string().contains("expected an image"), "{err}");
+    }
+
+    /// Test that unknown location overrides are rejected.
+    #[test]
+    fn test_location_validation() {
+        let mut params: ImageGenerateParams =
+            serde_json::from_value(serde_json::json!({ "prompt": "A cat" })).unwrap();
+        assert!(params.location.is_none());
+
+        params.location = Some("europe-west4".to_string());
+        assert!(params.validate().is_ok());
+
+        params.location = Some("mars-north1".to_string());
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "location");
+        assert!(errors[0].message.contains("mars-north1"));
+    }
 
-        assert!(expected_url.contains("us-central1-aiplatform.googleapis.com"));
-        assert!(expected_url.contains("my-project"));
-        assert!(expected_url.contains("imagen-4.0-generate-preview-05-20"));
-        assert!(expected_url.ends_with(":predict"));
+    #[test]
+    fn test_write_metadata_requires_output() {
+        let mut params: ImageGenerateParams =
+            serde_json::from_value(serde_json::json!({ "prompt": "A cat", "write_metadata": true })).unwrap();
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "write_metadata");
+
+        params.output_file = Some("cat.png".to_string());
+        assert!(params.validate().is_ok());
     }
 
     /// Test GeneratedImage structure.
@@ -1530,69 +4616,71 @@ mod api_tests {
         let image = GeneratedImage {
             data: "base64encodeddata".to_string(),
             mime_type: "image/png".to_string(),
+            enhanced_prompt: None,
+            info: None,
         };
 
         assert_eq!(image.data, "base64encodeddata");
         assert_eq!(image.mime_type, "image/png");
     }
 
-    /// Test ImageGenerateResult variants.
+    /// Test that inline artifacts carry the data and its dimensions.
     #[test]
-    fn test_image_generate_result_base64() {
-        let images = vec![
-            GeneratedImage {
-                data: "data1".to_string(),
-                mime_type: "image/png".to_string(),
-            },
-            GeneratedImage {
-                data: "data2".to_string(),
-                mime_type: "image/jpeg".to_string(),
-            },
-        ];
-
-        let result = ImageGenerateResult::Base64(images);
-        
-        match result {
-            ImageGenerateResult::Base64(imgs) => {
-                assert_eq!(imgs.len(), 2);
-                assert_eq!(imgs[0].data, "data1");
-                assert_eq!(imgs[1].mime_type, "image/jpeg");
-            }
-            _ => panic!("Expected Base64 variant"),
-        }
+    fn test_image_artifact_inline() {
+        let artifact = ImageArtifact::inline(GeneratedImage {
+            data: "data1".to_string(),
+            mime_type: "image/jpeg".to_string(),
+            enhanced_prompt: Some("A red cat".to_string()),
+            info: Some(ImageInfo { width: Some(4), height: Some(3), size_bytes: 10 }),
+        });
+
+        assert_eq!(artifact.data.as_deref(), Some("data1"));
+        assert_eq!(artifact.location(), None);
+        assert_eq!(artifact.mime_type, "image/jpeg");
+        assert_eq!((artifact.width, artifact.height, artifact.size_bytes), (Some(4), Some(3), Some(10)));
+        assert_eq!(artifact.enhanced_prompt.as_deref(), Some("A red cat"));
     }
 
-    /// Test ImageGenerateResult LocalFiles variant.
+    /// Test that locations map to local_path or gcs_uri.
     #[test]
-    fn test_image_generate_result_local_files() {
-        let paths = vec!["/tmp/image1.png".to_string(), "/tmp/image2.png".to_string()];
-        let result = ImageGenerateResult::LocalFiles(paths);
-        
-        match result {
-            ImageGenerateResult::LocalFiles(p) => {
-                assert_eq!(p.len(), 2);
-                assert!(p[0].contains("image1"));
-            }
-            _ => panic!("Expected LocalFiles variant"),
-        }
+    fn test_image_artifact_from_location() {
+        let local = ImageArtifact::from_location("/tmp/image1.jpg".to_string());
+        assert_eq!(local.local_path.as_deref(), Some("/tmp/image1.jpg"));
+        assert_eq!(local.gcs_uri, None);
+        assert_eq!(local.data, None);
+        assert_eq!(local.mime_type, "image/jpeg");
+
+        let stored = ImageArtifact::from_location("gs://bucket/image2.webp".to_string());
+        assert_eq!(stored.gcs_uri.as_deref(), Some("gs://bucket/image2.webp"));
+        assert_eq!(stored.local_path, None);
+        assert_eq!(stored.mime_type, "image/webp");
+        assert_eq!(stored.location(), Some("gs://bucket/image2.webp"));
+        assert_eq!(stored.info(), None);
     }
 
-    /// Test ImageGenerateResult StorageUris variant.
+    /// Test the serialized shape of the generate output.
     #[test]
-    fn test_image_generate_result_storage_uris() {
-        let uris = vec![
-            "gs://bucket/image1.png".to_string(),
-            "gs://bucket/image2.png".to_string(),
-        ];
-        let result = ImageGenerateResult::StorageUris(uris);
-        
-        match result {
-            ImageGenerateResult::StorageUris(u) => {
-                assert_eq!(u.len(), 2);
-                assert!(u[0].starts_with("gs://"));
-            }
-            _ => panic!("Expected StorageUris variant"),
-        }
+    fn test_image_generate_output_serialization() {
+        let output = ImageGenerateOutput {
+            images: vec![ImageArtifact::from_location("gs://bucket/cat.png".to_string())],
+            model: DEFAULT_MODEL.to_string(),
+            prompt: "A cat".to_string(),
+            failures: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "images": [{ "gcs_uri": "gs://bucket/cat.png", "mime_type": "image/png" }],
+                "model": DEFAULT_MODEL,
+                "prompt": "A cat"
+            })
+        );
+        let parsed: ImageGenerateOutput = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, output);
+        assert_eq!(parsed.locations(), vec!["gs://bucket/cat.png"]);
     }
 
     /// Test validation error formatting.
@@ -1617,8 +4705,20 @@ mod api_tests {
             aspect_ratio: "invalid".to_string(), // Invalid aspect ratio
             number_of_images: 10, // Out of range
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = params.validate();
@@ -1634,3 +4734,398 @@ mod api_tests {
         assert!(fields.contains(&"number_of_images"));
     }
 }
+
+#[cfg(test)]
+mod cache_tests {
+    use super::*;
+    use adk_rust_mcp_common::cache::InMemoryResultCache;
+
+    fn test_handler() -> ImageHandler {
+        let config = Config {
+            project_id: "test-project".to_string(),
+            ..Default::default()
+        };
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        let gcs = GcsClient::with_auth(Arc::clone(&auth));
+        ImageHandler::with_deps(config, gcs, reqwest::Client::new(), auth)
+    }
+
+    #[tokio::test]
+    async fn test_save_to_file_uses_returned_mime_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("photo.png");
+        let images = vec![
+            GeneratedImage {
+                data: BASE64.encode(b"jpeg-0"),
+                mime_type: "image/jpeg".to_string(),
+                enhanced_prompt: Some("A red cat, studio lighting".to_string()),
+                info: None,
+            },
+            GeneratedImage {
+                data: BASE64.encode(b"jpeg-1"),
+                mime_type: "image/jpeg".to_string(),
+                enhanced_prompt: None,
+                info: None,
+            },
+        ];
+
+        let result = test_handler().save_to_file(images, &output.to_string_lossy()).await.unwrap();
+        let paths = result.images;
+        let dir_str = dir.path().display();
+        let locations: Vec<_> = paths.iter().filter_map(|p| p.local_path.clone()).collect();
+        assert_eq!(locations, vec![format!("{}/photo_0.jpg", dir_str), format!("{}/photo_1.jpg", dir_str)]);
+        assert!(paths.iter().all(|p| p.data.is_none() && p.gcs_uri.is_none()));
+        assert_eq!(std::fs::read(&locations[1]).unwrap(), b"jpeg-1");
+        assert_eq!(paths[0].mime_type, "image/jpeg");
+        assert_eq!(paths[0].enhanced_prompt.as_deref(), Some("A red cat, studio lighting"));
+        assert_eq!(paths[1].enhanced_prompt, None);
+    }
+
+    #[tokio::test]
+    async fn test_save_to_file_places_relative_paths_in_download_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut handler = test_handler();
+        handler.config.download_dir = Some(dir.path().to_string_lossy().into_owned());
+        let images = vec![GeneratedImage {
+            data: BASE64.encode(b"png"),
+            mime_type: "image/png".to_string(),
+            enhanced_prompt: None,
+            info: None,
+        }];
+
+        let written = handler.save_to_file(images, "renders/cat.png").await.unwrap();
+
+        let expected = dir.path().join("renders/cat.png");
+        assert_eq!(written.images[0].local_path.as_deref(), Some(expected.to_string_lossy().as_ref()));
+        assert_eq!(std::fs::read(&expected).unwrap(), b"png");
+    }
+
+    #[tokio::test]
+    async fn test_save_to_file_keeps_images_written_before_and_after_a_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("batch.png");
+        let image = |data: String| GeneratedImage {
+            data,
+            mime_type: "image/png".to_string(),
+            enhanced_prompt: None,
+            info: None,
+        };
+        let images = vec![image(png_base64(2, 2)), image("not base64!".to_string()), image(png_base64(3, 3))];
+
+        let written = test_handler().save_to_file(images, &output.to_string_lossy()).await.unwrap();
+
+        let locations: Vec<_> = written.images.iter().filter_map(|i| i.local_path.clone()).collect();
+        let dir_str = dir.path().display();
+        assert_eq!(locations, vec![format!("{}/batch_0.png", dir_str), format!("{}/batch_2.png", dir_str)]);
+        assert!(locations.iter().all(|l| Path::new(l).exists()));
+        assert!(!dir.path().join("batch_1.png").exists());
+        assert_eq!(written.failures.len(), 1);
+        assert_eq!(written.failures[0].index, 1);
+        assert!(written.failures[0].error.contains("Invalid base64"), "{}", written.failures[0].error);
+    }
+
+    #[tokio::test]
+    async fn test_output_fails_when_no_image_is_written() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("batch.png");
+        let images = (0..2)
+            .map(|_| GeneratedImage {
+                data: "not base64!".to_string(),
+                mime_type: "image/png".to_string(),
+                enhanced_prompt: None,
+                info: None,
+            })
+            .collect();
+
+        let err = test_handler()
+            .handle_output(images, Some(&output.to_string_lossy()), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Validation(_)), "{:?}", err);
+    }
+
+    fn png_base64(width: u32, height: u32) -> String {
+        let mut bytes = Vec::new();
+        DynamicImage::new_rgb8(width, height)
+            .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .unwrap();
+        BASE64.encode(&bytes)
+    }
+
+    fn png_image(width: u32, height: u32) -> GeneratedImage {
+        GeneratedImage {
+            data: png_base64(width, height),
+            mime_type: "image/png".to_string(),
+            enhanced_prompt: Some("a cat".to_string()),
+            info: None,
+        }
+    }
+
+    #[test]
+    fn test_transcoded_webp() {
+        let image = png_image(6, 4).transcoded("image/webp", None).unwrap();
+        assert_eq!(image.mime_type, "image/webp");
+        assert_eq!(image.enhanced_prompt.as_deref(), Some("a cat"));
+
+        let bytes = BASE64.decode(&image.data).unwrap();
+        assert_eq!(&bytes[..4], b"RIFF");
+        assert_eq!(&bytes[8..12], b"WEBP");
+        let decoded = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (6, 4));
+    }
+
+    #[test]
+    fn test_transcoded_avif_records_info() {
+        let image = png_image(8, 8).transcoded("image/avif", Some(50)).unwrap();
+        assert_eq!(image.mime_type, "image/avif");
+
+        let bytes = BASE64.decode(&image.data).unwrap();
+        assert_eq!(&bytes[4..12], b"ftypavif");
+        let info = image.info.unwrap();
+        assert_eq!((info.width, info.height), (Some(8), Some(8)));
+        assert_eq!(info.size_bytes, bytes.len() as u64);
+
+        // Known info survives with_info, which cannot read AVIF headers
+        let image = image.with_info().unwrap();
+        assert_eq!(image.info.unwrap().width, Some(8));
+    }
+
+    #[test]
+    fn test_transcoded_same_type_is_unchanged() {
+        let original = png_image(2, 2);
+        let image = original.clone().transcoded("image/png", None).unwrap();
+        assert_eq!(image.data, original.data);
+        assert!(image.info.is_none());
+
+        let jpeg = original.transcoded("image/jpeg", Some(90)).unwrap();
+        assert_eq!(&BASE64.decode(&jpeg.data).unwrap()[..2], &[0xFF, 0xD8]);
+    }
+
+    #[tokio::test]
+    async fn test_save_transcoded_image_uses_format_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("cat.png");
+        let image = png_image(4, 4).transcoded("image/webp", None).unwrap();
+
+        let written = test_handler()
+            .handle_output(vec![image], Some(&output.to_string_lossy()), None)
+            .await
+            .unwrap();
+
+        let expected = dir.path().join("cat.webp");
+        assert!(expected.exists());
+        assert!(!output.exists());
+        assert_eq!(written.images.len(), 1);
+    }
+
+    #[test]
+    fn test_image_info_from_bytes() {
+        let data = BASE64.decode(png_base64(12, 7)).unwrap();
+        let info = ImageInfo::from_bytes(&data);
+        assert_eq!((info.width, info.height), (Some(12), Some(7)));
+        assert_eq!(info.size_bytes, data.len() as u64);
+
+        let unknown = ImageInfo::from_bytes(b"not an image");
+        assert_eq!((unknown.width, unknown.height), (None, None));
+        assert_eq!(unknown.size_bytes, 12);
+    }
+
+    #[tokio::test]
+    async fn test_write_metadata_beside_each_saved_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("cat.png");
+        let handler = test_handler();
+        let images = (0..2)
+            .map(|_| GeneratedImage {
+                data: png_base64(2, 2),
+                mime_type: "image/png".to_string(),
+                enhanced_prompt: None,
+                info: None,
+            })
+            .collect();
+        let params = ImageGenerateParams {
+            negative_prompt: Some("blurry".to_string()),
+            seed: Some(7),
+            add_watermark: Some(false),
+            aspect_ratio: "16:9".to_string(),
+            output_file: Some(output.to_string_lossy().into_owned()),
+            write_metadata: true,
+            ..serde_json::from_value(serde_json::json!({ "prompt": "A cat" })).unwrap()
+        };
+        let result = ImageGenerateOutput {
+            images: handler.handle_output(images, params.output_file.as_deref(), None).await.unwrap().images,
+            model: DEFAULT_MODEL.to_string(),
+            prompt: params.prompt.clone(),
+            failures: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        handler.write_metadata(&params, &result).await;
+
+        for i in 0..2 {
+            let sidecar = dir.path().join(format!("cat_{}.json", i));
+            let metadata: GenerationMetadata = serde_json::from_slice(&std::fs::read(&sidecar).unwrap()).unwrap();
+            assert_eq!(metadata.prompt, "A cat");
+            assert_eq!(metadata.negative_prompt.as_deref(), Some("blurry"));
+            assert_eq!(metadata.model, DEFAULT_MODEL);
+            assert_eq!(metadata.seed, Some(7));
+            assert_eq!(metadata.aspect_ratio.as_deref(), Some("16:9"));
+            assert_eq!(metadata.operation_name, None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_output_records_image_info() {
+        let image = GeneratedImage {
+            data: png_base64(4, 3),
+            mime_type: "image/png".to_string(),
+            enhanced_prompt: None,
+            info: None,
+        };
+        let result = test_handler().handle_output(vec![image], None, None).await.unwrap().images;
+
+        assert_eq!(result.len(), 1);
+        let json = serde_json::to_value(&result[0]).unwrap();
+        assert_eq!(json["mime_type"], "image/png");
+        assert_eq!(json["width"], 4);
+        assert_eq!(json["height"], 3);
+        assert!(json["size_bytes"].as_u64().unwrap() > 0);
+        assert!(json["data"].is_string());
+        assert!(json.get("local_path").is_none());
+        assert!(json.get("gcs_uri").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_handle_single_output_saves_with_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("upscaled.png");
+        let image = GeneratedImage {
+            data: png_base64(8, 8),
+            mime_type: "image/png".to_string(),
+            enhanced_prompt: None,
+            info: None,
+        };
+
+        let result = test_handler()
+            .handle_single_output(image, Some(&output.to_string_lossy()), None)
+            .await
+            .unwrap();
+        let ImageUpscaleResult::LocalFile(saved) = &result else {
+            panic!("expected a local file");
+        };
+        assert_eq!(saved.location, output.to_string_lossy());
+        let summary = result.summary();
+        assert_eq!(summary.location.as_deref(), Some(saved.location.as_str()));
+        let info = summary.info.unwrap();
+        assert_eq!((info.width, info.height), (Some(8), Some(8)));
+        assert_eq!(info.size_bytes, std::fs::metadata(&output).unwrap().len());
+    }
+
+    fn seeded_params() -> ImageGenerateParams {
+        ImageGenerateParams {
+            prompt: "A lighthouse at dusk".to_string(),
+            negative_prompt: None,
+            model: DEFAULT_MODEL.to_string(),
+            aspect_ratio: "1:1".to_string(),
+            number_of_images: 1,
+            seed: Some(7),
+            add_watermark: Some(false),
+            output_file: None,
+            output_uri: Some("gs://bucket/lighthouse.png".to_string()),
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
+        }
+    }
+
+    #[test]
+    fn test_cache_key_requires_seed_and_destination() {
+        let params = seeded_params();
+        assert!(ImageHandler::result_cache_key(&params, DEFAULT_MODEL).unwrap().is_some());
+
+        let mut no_seed = seeded_params();
+        no_seed.seed = None;
+        assert!(ImageHandler::result_cache_key(&no_seed, DEFAULT_MODEL).unwrap().is_none());
+
+        let mut base64_output = seeded_params();
+        base64_output.output_uri = None;
+        assert!(ImageHandler::result_cache_key(&base64_output, DEFAULT_MODEL).unwrap().is_none());
+
+        let mut opted_out = seeded_params();
+        opted_out.no_cache = true;
+        assert!(ImageHandler::result_cache_key(&opted_out, DEFAULT_MODEL).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_cache_key_uses_canonical_model_id() {
+        let mut alias = seeded_params();
+        alias.model = "imagen-4".to_string();
+        let canonical = seeded_params();
+        assert_eq!(
+            ImageHandler::result_cache_key(&alias, DEFAULT_MODEL).unwrap(),
+            ImageHandler::result_cache_key(&canonical, DEFAULT_MODEL).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_cache_key_changes_with_seed() {
+        let mut other = seeded_params();
+        other.seed = Some(8);
+        assert_ne!(
+            ImageHandler::result_cache_key(&seeded_params(), DEFAULT_MODEL).unwrap(),
+            ImageHandler::result_cache_key(&other, DEFAULT_MODEL).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_returns_cached_result_without_api_call() {
+        let cache = Arc::new(InMemoryResultCache::new());
+        let handler = test_handler().with_cache(cache.clone());
+        let params = seeded_params();
+        let model_id = params.get_model().unwrap().id;
+        let key = ImageHandler::result_cache_key(&params, model_id).unwrap().unwrap();
+        cache
+            .put(&key, &CachedResult { uris: vec!["gs://bucket/lighthouse.png".to_string()] })
+            .await
+            .unwrap();
+
+        // The mock token would be rejected by the real endpoint, so a result
+        // here can only come from the cache.
+        let prompt = params.prompt.clone();
+        let result = handler.generate_image(params).await.unwrap();
+        assert_eq!(
+            result,
+            ImageGenerateOutput {
+                images: vec![ImageArtifact::from_location("gs://bucket/lighthouse.png".to_string())],
+                model: model_id.to_string(),
+                prompt,
+                failures: Vec::new(),
+                warnings: Vec::new(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stale_local_cache_entry_is_a_miss() {
+        let cache = Arc::new(InMemoryResultCache::new());
+        let handler = test_handler().with_cache(cache.clone());
+        let mut params = seeded_params();
+        params.output_uri = None;
+        params.output_file = Some("/nonexistent/dir/lighthouse.png".to_string());
+        let key = ImageHandler::result_cache_key(&params, DEFAULT_MODEL).unwrap().unwrap();
+        cache
+            .put(&key, &CachedResult { uris: vec!["/nonexistent/dir/lighthouse.png".to_string()] })
+            .await
+            .unwrap();
+
+        assert!(handler.cached_result(&key, &params, DEFAULT_MODEL).await.is_none());
+    }
+}
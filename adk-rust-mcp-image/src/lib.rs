@@ -5,8 +5,13 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 pub mod handler;
+pub mod outpaint;
 pub mod resources;
 pub mod server;
 
-pub use handler::{ImageGenerateParams, ImageGenerateResult, ImageHandler, GeneratedImage};
+pub use handler::{
+    GeneratedImage, ImageArtifact, ImageEditMode, ImageEditParams, ImageFailure, ImageGenerateOutput,
+    ImageGenerateParams, ImageHandler, ImageInfo, ImageOutpaintParams, ImageSummary,
+    ImageUpscaleResult, ImageVariationMode, ImageVariationParams, SavedImage,
+};
 pub use server::ImageServer;
@@ -0,0 +1,218 @@
+//! Canvas layout and mask construction for outpainting.
+//!
+//! Outpainting extends an image beyond its original borders. The source is
+//! centered on a larger canvas, and a mask marks the new area (white) that the
+//! model should fill while keeping the original pixels (black) untouched.
+
+use image::{DynamicImage, GenericImage, GrayImage, Luma, Rgb, RgbImage};
+
+/// Largest canvas edge accepted for outpainting, in pixels.
+pub const MAX_OUTPAINT_DIMENSION: u32 = 4096;
+
+/// Requested canvas size for outpainting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutpaintTarget {
+    /// Smallest canvas with this aspect ratio (width, height) that contains the source.
+    AspectRatio(u32, u32),
+    /// Explicit canvas size in pixels; must be at least the source size.
+    Dimensions(u32, u32),
+}
+
+impl OutpaintTarget {
+    /// Parse an aspect ratio string such as `"16:9"`.
+    pub fn parse_aspect_ratio(ratio: &str) -> Option<Self> {
+        let (w, h) = ratio.split_once(':')?;
+        let w: u32 = w.trim().parse().ok()?;
+        let h: u32 = h.trim().parse().ok()?;
+        (w > 0 && h > 0).then_some(Self::AspectRatio(w, h))
+    }
+}
+
+/// Placement of the source image on the outpainting canvas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutpaintLayout {
+    /// Canvas width in pixels.
+    pub canvas_width: u32,
+    /// Canvas height in pixels.
+    pub canvas_height: u32,
+    /// Horizontal offset of the source's top-left corner.
+    pub offset_x: u32,
+    /// Vertical offset of the source's top-left corner.
+    pub offset_y: u32,
+}
+
+/// Compute the canvas size and centered offset for a source image.
+///
+/// # Errors
+/// Returns a message if the source is empty, the target is smaller than the
+/// source, the target would not add any pixels, or the canvas exceeds
+/// [`MAX_OUTPAINT_DIMENSION`].
+pub fn outpaint_layout(
+    source_width: u32,
+    source_height: u32,
+    target: OutpaintTarget,
+) -> Result<OutpaintLayout, String> {
+    if source_width == 0 || source_height == 0 {
+        return Err("Source image has no pixels".to_string());
+    }
+
+    let (canvas_width, canvas_height) = match target {
+        OutpaintTarget::AspectRatio(rw, rh) => {
+            let (sw, sh, rw, rh) = (source_width as u64, source_height as u64, rw as u64, rh as u64);
+            // Compare sw/sh with rw/rh without floating point.
+            if sw * rh < sh * rw {
+                // Source is narrower than the target ratio: widen it.
+                let width = (sh * rw).div_ceil(rh);
+                (u32::try_from(width).unwrap_or(u32::MAX), source_height)
+            } else {
+                // Source is wider (or equal): make it taller.
+                let height = (sw * rh).div_ceil(rw);
+                (source_width, u32::try_from(height).unwrap_or(u32::MAX))
+            }
+        }
+        OutpaintTarget::Dimensions(w, h) => {
+            if w < source_width || h < source_height {
+                return Err(format!(
+                    "Target size {}x{} is smaller than the source image {}x{}",
+                    w, h, source_width, source_height
+                ));
+            }
+            (w, h)
+        }
+    };
+
+    if canvas_width == source_width && canvas_height == source_height {
+        return Err(format!(
+            "Target canvas {}x{} matches the source image; nothing to outpaint",
+            canvas_width, canvas_height
+        ));
+    }
+
+    if canvas_width > MAX_OUTPAINT_DIMENSION || canvas_height > MAX_OUTPAINT_DIMENSION {
+        return Err(format!(
+            "Target canvas {}x{} exceeds the maximum of {}px per side",
+            canvas_width, canvas_height, MAX_OUTPAINT_DIMENSION
+        ));
+    }
+
+    Ok(OutpaintLayout {
+        canvas_width,
+        canvas_height,
+        offset_x: (canvas_width - source_width) / 2,
+        offset_y: (canvas_height - source_height) / 2,
+    })
+}
+
+/// Build the padded canvas and the outpainting mask.
+///
+/// The padded image has the source at the layout offset and black elsewhere.
+/// The mask is white (255) over the new area and black (0) over the source.
+pub fn pad_image_and_mask(source: &DynamicImage, layout: &OutpaintLayout) -> (RgbImage, GrayImage) {
+    let source = source.to_rgb8();
+
+    let mut padded = RgbImage::from_pixel(layout.canvas_width, layout.canvas_height, Rgb([0, 0, 0]));
+    // The layout guarantees the source fits, so copying cannot go out of bounds.
+    let _ = padded.copy_from(&source, layout.offset_x, layout.offset_y);
+
+    let mut mask = GrayImage::from_pixel(layout.canvas_width, layout.canvas_height, Luma([255]));
+    for y in layout.offset_y..layout.offset_y + source.height() {
+        for x in layout.offset_x..layout.offset_x + source.width() {
+            mask.put_pixel(x, y, Luma([0]));
+        }
+    }
+
+    (padded, mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_aspect_ratio() {
+        assert_eq!(OutpaintTarget::parse_aspect_ratio("16:9"), Some(OutpaintTarget::AspectRatio(16, 9)));
+        assert_eq!(OutpaintTarget::parse_aspect_ratio("1:1"), Some(OutpaintTarget::AspectRatio(1, 1)));
+        assert_eq!(OutpaintTarget::parse_aspect_ratio("16x9"), None);
+        assert_eq!(OutpaintTarget::parse_aspect_ratio("0:9"), None);
+    }
+
+    #[test]
+    fn test_square_to_landscape_widens() {
+        let layout = outpaint_layout(1024, 1024, OutpaintTarget::AspectRatio(16, 9)).unwrap();
+        assert_eq!(layout.canvas_height, 1024);
+        assert_eq!(layout.canvas_width, 1821); // ceil(1024 * 16 / 9)
+        assert_eq!(layout.offset_x, (1821 - 1024) / 2);
+        assert_eq!(layout.offset_y, 0);
+    }
+
+    #[test]
+    fn test_square_to_portrait_heightens() {
+        let layout = outpaint_layout(900, 900, OutpaintTarget::AspectRatio(9, 16)).unwrap();
+        assert_eq!(layout.canvas_width, 900);
+        assert_eq!(layout.canvas_height, 1600);
+        assert_eq!(layout.offset_x, 0);
+        assert_eq!(layout.offset_y, 350);
+    }
+
+    #[test]
+    fn test_landscape_to_square() {
+        let layout = outpaint_layout(1600, 900, OutpaintTarget::AspectRatio(1, 1)).unwrap();
+        assert_eq!((layout.canvas_width, layout.canvas_height), (1600, 1600));
+        assert_eq!((layout.offset_x, layout.offset_y), (0, 350));
+    }
+
+    #[test]
+    fn test_same_aspect_ratio_is_rejected() {
+        assert!(outpaint_layout(1600, 900, OutpaintTarget::AspectRatio(16, 9)).is_err());
+    }
+
+    #[test]
+    fn test_explicit_dimensions_center_source() {
+        let layout = outpaint_layout(100, 50, OutpaintTarget::Dimensions(301, 150)).unwrap();
+        assert_eq!((layout.canvas_width, layout.canvas_height), (301, 150));
+        assert_eq!((layout.offset_x, layout.offset_y), (100, 50));
+    }
+
+    #[test]
+    fn test_explicit_dimensions_smaller_than_source_rejected() {
+        let err = outpaint_layout(100, 100, OutpaintTarget::Dimensions(200, 99)).unwrap_err();
+        assert!(err.contains("smaller"));
+    }
+
+    #[test]
+    fn test_canvas_over_limit_rejected() {
+        let err = outpaint_layout(3000, 3000, OutpaintTarget::AspectRatio(16, 9)).unwrap_err();
+        assert!(err.contains("maximum"));
+    }
+
+    #[test]
+    fn test_empty_source_rejected() {
+        assert!(outpaint_layout(0, 10, OutpaintTarget::Dimensions(20, 20)).is_err());
+    }
+
+    #[test]
+    fn test_pad_image_and_mask() {
+        let source = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 2, Rgb([10, 20, 30])));
+        let layout = outpaint_layout(4, 2, OutpaintTarget::Dimensions(8, 6)).unwrap();
+        assert_eq!((layout.offset_x, layout.offset_y), (2, 2));
+
+        let (padded, mask) = pad_image_and_mask(&source, &layout);
+        assert_eq!(padded.dimensions(), (8, 6));
+        assert_eq!(mask.dimensions(), (8, 6));
+
+        // Source region keeps its pixels and is masked out.
+        assert_eq!(padded.get_pixel(2, 2), &Rgb([10, 20, 30]));
+        assert_eq!(padded.get_pixel(5, 3), &Rgb([10, 20, 30]));
+        assert_eq!(mask.get_pixel(2, 2), &Luma([0]));
+        assert_eq!(mask.get_pixel(5, 3), &Luma([0]));
+
+        // New area is black in the canvas and white in the mask.
+        assert_eq!(padded.get_pixel(0, 0), &Rgb([0, 0, 0]));
+        assert_eq!(mask.get_pixel(0, 0), &Luma([255]));
+        assert_eq!(mask.get_pixel(6, 3), &Luma([255]));
+        assert_eq!(mask.get_pixel(3, 4), &Luma([255]));
+
+        let masked_out = mask.pixels().filter(|p| p.0[0] == 0).count();
+        assert_eq!(masked_out, 4 * 2);
+    }
+}
@@ -5,22 +5,41 @@
 //! - `image://segmentation_classes` - List segmentation classes (Google provider specific)
 //! - `image://providers` - List available image providers
 
-use adk_rust_mcp_common::models::IMAGEN_MODELS;
+use crate::handler::{EDIT_MODEL, MAX_NUMBER_OF_IMAGES};
+use adk_rust_mcp_common::models::{OutputDimensions, IMAGEN_MODELS, IMAGEN_UPSCALE_MODELS};
 use serde::Serialize;
 
-/// Information about an available image generation model.
+/// Information about an available image model.
+///
+/// Generation models are selected with the `model` parameter of
+/// `image_generate`, upscale models with the `model` parameter of
+/// `image_upscale`; editing always runs on its dedicated model.
 #[derive(Debug, Clone, Serialize)]
 pub struct ModelInfo {
     /// Model identifier
     pub id: &'static str,
     /// Model aliases
     pub aliases: Vec<&'static str>,
-    /// Maximum prompt length in characters
+    /// Maximum prompt length in characters (0 if the model takes no prompt)
     pub max_prompt_length: usize,
-    /// Supported aspect ratios
+    /// Supported aspect ratios (empty if the output follows the input image)
     pub supported_aspect_ratios: Vec<&'static str>,
+    /// Output width and height in pixels for each supported aspect ratio
+    pub output_dimensions: Vec<OutputDimensions>,
     /// Maximum number of images per request
     pub max_images: u8,
+    /// Whether `image_generate` accepts `guidance_scale` for this model
+    pub supports_guidance_scale: bool,
+    /// Values accepted for `language` (empty if the parameter is not supported)
+    pub supported_languages: Vec<&'static str>,
+    /// Factors accepted by `image_upscale` (empty for non-upscale models)
+    pub supported_upscale_factors: Vec<&'static str>,
+    /// Whether the model generates images from text (`image_generate`)
+    pub supports_generation: bool,
+    /// Whether the model edits images (`image_edit`, `image_variation`)
+    pub supports_editing: bool,
+    /// Whether the model upscales images (`image_upscale`)
+    pub supports_upscaling: bool,
 }
 
 /// Information about an available image provider.
@@ -117,18 +136,58 @@ pub const SEGMENTATION_CLASSES: &[SegmentationClass] = &[
     },
 ];
 
-/// List all available image generation models.
+/// Maximum prompt length accepted by the edit model.
+const EDIT_MAX_PROMPT_LENGTH: usize = 480;
+
+/// List all available image models: generation models first, then the
+/// dedicated edit and upscale models.
 pub fn list_models() -> Vec<ModelInfo> {
-    IMAGEN_MODELS
-        .iter()
-        .map(|m| ModelInfo {
-            id: m.id,
-            aliases: m.aliases.to_vec(),
-            max_prompt_length: m.max_prompt_length,
-            supported_aspect_ratios: m.supported_aspect_ratios.to_vec(),
-            max_images: m.max_images,
-        })
-        .collect()
+    let generation = IMAGEN_MODELS.iter().map(|m| ModelInfo {
+        id: m.id,
+        aliases: m.aliases.to_vec(),
+        max_prompt_length: m.max_prompt_length,
+        supported_aspect_ratios: m.supported_aspect_ratios.to_vec(),
+        output_dimensions: m.output_dimensions.to_vec(),
+        max_images: m.max_images,
+        supports_guidance_scale: m.supports_guidance_scale,
+        supported_languages: m.supported_languages.to_vec(),
+        supported_upscale_factors: Vec::new(),
+        supports_generation: true,
+        supports_editing: false,
+        supports_upscaling: false,
+    });
+
+    let edit = ModelInfo {
+        id: EDIT_MODEL,
+        aliases: Vec::new(),
+        max_prompt_length: EDIT_MAX_PROMPT_LENGTH,
+        supported_aspect_ratios: Vec::new(),
+        output_dimensions: Vec::new(),
+        max_images: MAX_NUMBER_OF_IMAGES,
+        supports_guidance_scale: false,
+        supported_languages: Vec::new(),
+        supported_upscale_factors: Vec::new(),
+        supports_generation: false,
+        supports_editing: true,
+        supports_upscaling: false,
+    };
+
+    let upscale = IMAGEN_UPSCALE_MODELS.iter().map(|m| ModelInfo {
+        id: m.id,
+        aliases: m.aliases.to_vec(),
+        max_prompt_length: 0,
+        supported_aspect_ratios: Vec::new(),
+        output_dimensions: Vec::new(),
+        max_images: 1,
+        supports_guidance_scale: false,
+        supported_languages: Vec::new(),
+        supported_upscale_factors: m.supported_factors.to_vec(),
+        supports_generation: false,
+        supports_editing: false,
+        supports_upscaling: true,
+    });
+
+    generation.chain([edit]).chain(upscale).collect()
 }
 
 /// List all available segmentation classes.
@@ -173,6 +232,7 @@ pub fn providers_resource_json() -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::handler::UPSCALE_MODEL;
 
     #[test]
     fn test_list_models() {
@@ -188,7 +248,7 @@ mod tests {
     #[test]
     fn test_list_models_has_aliases() {
         let models = list_models();
-        for model in &models {
+        for model in models.iter().filter(|m| m.supports_generation) {
             assert!(!model.aliases.is_empty(), "Model {} should have aliases", model.id);
         }
     }
@@ -196,12 +256,43 @@ mod tests {
     #[test]
     fn test_list_models_has_aspect_ratios() {
         let models = list_models();
-        for model in &models {
+        for model in models.iter().filter(|m| m.supports_generation) {
             assert!(!model.supported_aspect_ratios.is_empty(), 
                 "Model {} should have supported aspect ratios", model.id);
         }
     }
 
+    #[test]
+    fn test_list_models_has_output_dimensions() {
+        let models = list_models();
+        for model in models.iter().filter(|m| m.supports_generation) {
+            let ratios: Vec<&str> = model.output_dimensions.iter().map(|d| d.aspect_ratio).collect();
+            assert_eq!(ratios, model.supported_aspect_ratios, "{}", model.id);
+        }
+        let imagen3 = models.iter().find(|m| m.id == "imagen-3.0-generate-002").unwrap();
+        let wide = imagen3.output_dimensions.iter().find(|d| d.aspect_ratio == "16:9").unwrap();
+        assert_eq!((wide.width, wide.height), (1408, 768));
+    }
+
+    #[test]
+    fn test_list_models_capabilities() {
+        let models = list_models();
+
+        let generation: Vec<&str> = models.iter().filter(|m| m.supports_generation).map(|m| m.id).collect();
+        assert_eq!(generation, IMAGEN_MODELS.iter().map(|m| m.id).collect::<Vec<_>>());
+
+        let editing: Vec<&str> = models.iter().filter(|m| m.supports_editing).map(|m| m.id).collect();
+        assert_eq!(editing, vec![EDIT_MODEL]);
+
+        let upscaling: Vec<&str> = models.iter().filter(|m| m.supports_upscaling).map(|m| m.id).collect();
+        assert_eq!(upscaling, IMAGEN_UPSCALE_MODELS.iter().map(|m| m.id).collect::<Vec<_>>());
+        assert_eq!(upscaling[0], UPSCALE_MODEL);
+        assert!(models
+            .iter()
+            .filter(|m| m.supports_upscaling)
+            .any(|m| m.supported_upscale_factors.contains(&"x4")));
+    }
+
     #[test]
     fn test_list_segmentation_classes() {
         let classes = list_segmentation_classes();
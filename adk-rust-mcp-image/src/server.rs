@@ -3,12 +3,21 @@
 //! This module provides the MCP server handler that exposes:
 //! - `image_generate` tool for text-to-image generation
 //! - `image_upscale` tool for image upscaling
+//! - `image_edit` tool for mask-based image editing
+//! - `image_outpaint` tool for extending image canvases
+//! - `image_variation` tool for prompt-guided variations of an image
+//! - `image_list_models` tool for discovering model capabilities
 //! - Resources for models, segmentation classes, and providers
 
-use crate::handler::{ImageGenerateParams, ImageGenerateResult, ImageHandler, ImageUpscaleParams, ImageUpscaleResult};
+use crate::handler::{
+    ImageEditMode, ImageEditParams, ImageGenerateOutput, ImageGenerateParams, ImageHandler,
+    ImageOutpaintParams, ImageUpscaleParams, ImageSummary, ImageUpscaleResult, ImageVariationMode,
+    ImageVariationParams,
+};
 use crate::resources;
 use adk_rust_mcp_common::config::Config;
-use adk_rust_mcp_common::error::Error;
+use adk_rust_mcp_common::error::{Error, ErrorCode};
+use adk_rust_mcp_common::idempotency::IdempotencyStore;
 use rmcp::{
     model::{
         CallToolResult, Content, ListResourcesResult, ReadResourceResult,
@@ -30,6 +39,8 @@ pub struct ImageServer {
     handler: Arc<RwLock<Option<ImageHandler>>>,
     /// Server configuration
     config: Config,
+    /// Results recorded by idempotency key
+    idempotency: Arc<IdempotencyStore>,
 }
 
 /// Tool parameters wrapper for image_generate.
@@ -49,15 +60,54 @@ pub struct ImageGenerateToolParams {
     /// Number of images to generate (1-4)
     #[serde(default)]
     pub number_of_images: Option<u8>,
-    /// Random seed for reproducibility
+    /// Random seed for reproducibility (requires add_watermark=false)
     #[serde(default)]
     pub seed: Option<i64>,
+    /// Add a SynthID watermark (Imagen default: true; must be false when seed is set)
+    #[serde(default)]
+    pub add_watermark: Option<bool>,
     /// Output file path for saving locally
     #[serde(default)]
     pub output_file: Option<String>,
-    /// Output storage URI (e.g., gs://bucket/path)
+    /// Output storage URI (gs://bucket/path, or an object name in GCS_BUCKET)
     #[serde(default)]
     pub output_uri: Option<String>,
+    /// Skip the result cache (seeded requests are cached by default)
+    #[serde(default)]
+    pub no_cache: Option<bool>,
+    /// Safety filter level (block_low_and_above, block_medium_and_above, block_only_high, block_none)
+    #[serde(default)]
+    pub safety_filter_level: Option<String>,
+    /// Person generation policy (dont_allow, allow_adult, allow_all)
+    #[serde(default)]
+    pub person_generation: Option<String>,
+    /// Output format: image/png (default), image/jpeg, image/webp, or image/avif
+    #[serde(default)]
+    pub output_mime_type: Option<String>,
+    /// Compression quality (1-100, requires image/jpeg or image/avif)
+    #[serde(default)]
+    pub compression_quality: Option<u8>,
+    /// Let Imagen rewrite the prompt before generating (the rewritten prompt is returned)
+    #[serde(default)]
+    pub enhance_prompt: Option<bool>,
+    /// Vertex AI region for this request, e.g. europe-west4 (default: LOCATION)
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Style preset merged into the prompt: photorealistic, cinematic, line_art, watercolor, oil_painting, digital_art, anime, sketch, 3d_render, pixel_art
+    #[serde(default)]
+    pub style_preset: Option<String>,
+    /// How closely the image follows the prompt (positive number, higher = closer); only for models with supports_guidance_scale in image://models
+    #[serde(default)]
+    pub guidance_scale: Option<f32>,
+    /// Prompt language: auto, en, es, hi, ja, ko, pt, zh, zh-CN, zh-TW (see supported_languages in image://models)
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Write a <name>.json sidecar with prompt, model, seed and settings next to each saved image
+    #[serde(default)]
+    pub write_metadata: Option<bool>,
+    /// Key making retries of this call return the first result instead of generating again (results expire after IDEMPOTENCY_TTL_SECS, default 1 hour)
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 impl From<ImageGenerateToolParams> for ImageGenerateParams {
@@ -69,8 +119,20 @@ impl From<ImageGenerateToolParams> for ImageGenerateParams {
             aspect_ratio: params.aspect_ratio.unwrap_or_else(|| "1:1".to_string()),
             number_of_images: params.number_of_images.unwrap_or(1),
             seed: params.seed,
+            add_watermark: params.add_watermark,
             output_file: params.output_file,
             output_uri: params.output_uri,
+            no_cache: params.no_cache.unwrap_or(false),
+            safety_filter_level: params.safety_filter_level,
+            person_generation: params.person_generation,
+            output_mime_type: params.output_mime_type,
+            compression_quality: params.compression_quality,
+            enhance_prompt: params.enhance_prompt,
+            location: params.location,
+            style_preset: params.style_preset,
+            guidance_scale: params.guidance_scale,
+            language: params.language,
+            write_metadata: params.write_metadata.unwrap_or(false),
         }
     }
 }
@@ -78,17 +140,26 @@ impl From<ImageGenerateToolParams> for ImageGenerateParams {
 /// Tool parameters wrapper for image_upscale.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ImageUpscaleToolParams {
-    /// Source image to upscale (base64 data, local path, or GCS URI)
+    /// Source image to upscale (base64 data, data URI, local path, GCS URI, or HTTPS URL)
     pub image: String,
     /// Upscale factor: "x2" or "x4" (default: "x2")
     #[serde(default)]
     pub upscale_factor: Option<String>,
+    /// Upscale model (see image://models); defaults to the preferred model supporting upscale_factor
+    #[serde(default)]
+    pub model: Option<String>,
     /// Output file path for saving locally
     #[serde(default)]
     pub output_file: Option<String>,
-    /// Output storage URI (e.g., gs://bucket/path)
+    /// Output storage URI (gs://bucket/path, or an object name in GCS_BUCKET); ending it in / names the file after the input, e.g. photo_x4.png
     #[serde(default)]
     pub output_uri: Option<String>,
+    /// Output format: image/png (default), image/jpeg, image/webp, or image/avif
+    #[serde(default)]
+    pub output_mime_type: Option<String>,
+    /// Compression quality (1-100, requires image/jpeg or image/avif)
+    #[serde(default)]
+    pub compression_quality: Option<u8>,
 }
 
 impl From<ImageUpscaleToolParams> for ImageUpscaleParams {
@@ -96,17 +167,153 @@ impl From<ImageUpscaleToolParams> for ImageUpscaleParams {
         Self {
             image: params.image,
             upscale_factor: params.upscale_factor.unwrap_or_else(|| "x2".to_string()),
+            model: params.model,
+            output_file: params.output_file,
+            output_uri: params.output_uri,
+            output_mime_type: params.output_mime_type,
+            compression_quality: params.compression_quality,
+        }
+    }
+}
+
+/// Tool parameters wrapper for image_edit.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ImageEditToolParams {
+    /// Source image to edit (base64 data, data URI, local path, GCS URI, or HTTPS URL)
+    pub image: String,
+    /// Mask image, white where the edit applies (base64 data, data URI, local path, GCS URI, or HTTPS URL).
+    /// Required for inpaint_insert and inpaint_remove.
+    #[serde(default)]
+    pub mask: Option<String>,
+    /// Text prompt describing the edit (may be empty for inpaint_remove)
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Edit mode: inpaint_insert, inpaint_remove, or background_swap
+    pub edit_mode: ImageEditMode,
+    /// Number of images to generate (1-4)
+    #[serde(default)]
+    pub number_of_images: Option<u8>,
+    /// Output file path for saving locally
+    #[serde(default)]
+    pub output_file: Option<String>,
+    /// Output storage URI (gs://bucket/path, or an object name in GCS_BUCKET)
+    #[serde(default)]
+    pub output_uri: Option<String>,
+}
+
+impl From<ImageEditToolParams> for ImageEditParams {
+    fn from(params: ImageEditToolParams) -> Self {
+        Self {
+            image: params.image,
+            mask: params.mask,
+            prompt: params.prompt.unwrap_or_default(),
+            edit_mode: params.edit_mode,
+            number_of_images: params.number_of_images.unwrap_or(1),
+            output_file: params.output_file,
+            output_uri: params.output_uri,
+        }
+    }
+}
+
+/// Tool parameters wrapper for image_outpaint.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ImageOutpaintToolParams {
+    /// Source image to extend (base64 data, data URI, local path, GCS URI, or HTTPS URL)
+    pub image: String,
+    /// Text prompt describing the content of the extended area
+    pub prompt: String,
+    /// Target aspect ratio, e.g. "16:9" (alternative to target_width/target_height)
+    #[serde(default)]
+    pub target_aspect_ratio: Option<String>,
+    /// Target canvas width in pixels (requires target_height)
+    #[serde(default)]
+    pub target_width: Option<u32>,
+    /// Target canvas height in pixels (requires target_width)
+    #[serde(default)]
+    pub target_height: Option<u32>,
+    /// Output file path for saving locally
+    #[serde(default)]
+    pub output_file: Option<String>,
+    /// Output storage URI (gs://bucket/path, or an object name in GCS_BUCKET)
+    #[serde(default)]
+    pub output_uri: Option<String>,
+}
+
+impl From<ImageOutpaintToolParams> for ImageOutpaintParams {
+    fn from(params: ImageOutpaintToolParams) -> Self {
+        Self {
+            image: params.image,
+            prompt: params.prompt,
+            target_aspect_ratio: params.target_aspect_ratio,
+            target_width: params.target_width,
+            target_height: params.target_height,
             output_file: params.output_file,
             output_uri: params.output_uri,
         }
     }
 }
 
+/// Tool parameters wrapper for image_variation.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ImageVariationToolParams {
+    /// Source image (base64 data, data URI, local path, GCS URI, or HTTPS URL)
+    pub image: String,
+    /// Text prompt describing the variation, e.g. "as a watercolor painting"
+    pub prompt: String,
+    /// structure (default): keep the composition and restyle it as the prompt describes;
+    /// style: generate the prompt's content in the style of the source
+    #[serde(default)]
+    pub mode: Option<ImageVariationMode>,
+    /// Short description of the source's style (style mode only)
+    #[serde(default)]
+    pub style_description: Option<String>,
+    /// Number of images to generate (1-4)
+    #[serde(default)]
+    pub number_of_images: Option<u8>,
+    /// Output file path for saving locally
+    #[serde(default)]
+    pub output_file: Option<String>,
+    /// Output storage URI (gs://bucket/path, or an object name in GCS_BUCKET)
+    #[serde(default)]
+    pub output_uri: Option<String>,
+}
+
+impl From<ImageVariationToolParams> for ImageVariationParams {
+    fn from(params: ImageVariationToolParams) -> Self {
+        Self {
+            image: params.image,
+            prompt: params.prompt,
+            mode: params.mode.unwrap_or_default(),
+            style_description: params.style_description,
+            number_of_images: params.number_of_images.unwrap_or(1),
+            output_file: params.output_file,
+            output_uri: params.output_uri,
+        }
+    }
+}
+
+/// Structured tool result listing each image's location, format, dimensions and size.
+fn images_structured_content(images: Vec<ImageSummary>) -> Option<serde_json::Value> {
+    Some(serde_json::json!({ "images": images }))
+}
+
+/// Rewritten prompts in the result, without repeats (all images usually share one).
+fn distinct_enhanced_prompts(result: &ImageGenerateOutput) -> Vec<String> {
+    let mut prompts: Vec<String> = Vec::new();
+    for prompt in result.enhanced_prompts() {
+        if !prompts.iter().any(|p| p == prompt) {
+            prompts.push(prompt.to_string());
+        }
+    }
+    prompts
+}
+
 impl ImageServer {
     /// Create a new ImageServer with the given configuration.
     pub fn new(config: Config) -> Self {
         Self {
             handler: Arc::new(RwLock::new(None)),
+            idempotency: Arc::new(IdempotencyStore::from_config(&config)),
             config,
         }
     }
@@ -126,7 +333,7 @@ impl ImageServer {
 
         // Ensure handler is initialized
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -136,26 +343,72 @@ impl ImageServer {
 
         let gen_params: ImageGenerateParams = params.into();
         let result = handler.generate_image(gen_params).await.map_err(|e| {
-            McpError::internal_error(format!("Image generation failed: {}", e), None)
+            e.to_mcp_error("Image generation failed")
         })?;
 
-        // Convert result to MCP content
-        let content = match result {
-            ImageGenerateResult::Base64(images) => {
-                images
-                    .into_iter()
-                    .map(|img| Content::image(img.data, img.mime_type))
-                    .collect()
-            }
-            ImageGenerateResult::LocalFiles(paths) => {
-                vec![Content::text(format!("Images saved to: {}", paths.join(", ")))]
-            }
-            ImageGenerateResult::StorageUris(uris) => {
-                vec![Content::text(format!("Images uploaded to: {}", uris.join(", ")))]
+        Self::generate_output_result(result, "Images")
+    }
+
+    /// JSON schema of [`ImageGenerateOutput`], published as the output schema of
+    /// `image_generate`, `image_edit` and `image_variation`.
+    pub fn generate_output_schema() -> Arc<serde_json::Map<String, serde_json::Value>> {
+        match serde_json::to_value(schemars::schema_for!(ImageGenerateOutput)).unwrap_or_default() {
+            serde_json::Value::Object(map) => Arc::new(map),
+            _ => Arc::new(serde_json::Map::new()),
+        }
+    }
+
+    /// Convert an [`ImageGenerateOutput`] into a tool result.
+    ///
+    /// Inline images become image content and written images are listed as
+    /// `"<label> saved to: ..."` or `"<label> uploaded to: ..."`, followed by
+    /// one line per image that could not be output. The output itself is the
+    /// structured content.
+    pub fn generate_output_result(output: ImageGenerateOutput, label: &str) -> Result<CallToolResult, McpError> {
+        let structured = serde_json::to_value(&output).map_err(|e| {
+            McpError::internal_error(format!("Failed to serialize output: {}", e), None)
+        })?;
+        let enhanced_prompts = distinct_enhanced_prompts(&output);
+        let failures: Vec<String> = output
+            .failures
+            .iter()
+            .map(|f| format!("Image {} could not be output: {}", f.index, f.error))
+            .collect();
+
+        let mut local_paths = Vec::new();
+        let mut gcs_uris = Vec::new();
+        let mut content: Vec<Content> = Vec::new();
+        for image in output.images {
+            if let Some(path) = image.local_path {
+                local_paths.push(path);
+            } else if let Some(uri) = image.gcs_uri {
+                gcs_uris.push(uri);
+            } else if let Some(data) = image.data {
+                content.push(Content::image(data, image.mime_type));
             }
-        };
+        }
+        if !local_paths.is_empty() {
+            content.push(Content::text(format!("{} saved to: {}", label, local_paths.join(", "))));
+        }
+        if !gcs_uris.is_empty() {
+            content.push(Content::text(format!("{} uploaded to: {}", label, gcs_uris.join(", "))));
+        }
+        content.extend(
+            enhanced_prompts
+                .into_iter()
+                .map(|prompt| Content::text(format!("Enhanced prompt: {}", prompt))),
+        );
+        content.extend(failures.into_iter().map(Content::text));
+        content.extend(
+            output
+                .warnings
+                .iter()
+                .map(|warning| Content::text(format!("Warning: {}", warning))),
+        );
 
-        Ok(CallToolResult::success(content))
+        let mut tool_result = CallToolResult::success(content);
+        tool_result.structured_content = Some(structured);
+        Ok(tool_result)
     }
 
     /// Upscale an image.
@@ -164,7 +417,7 @@ impl ImageServer {
 
         // Ensure handler is initialized
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -174,33 +427,140 @@ impl ImageServer {
 
         let upscale_params: ImageUpscaleParams = params.into();
         let result = handler.upscale_image(upscale_params).await.map_err(|e| {
-            McpError::internal_error(format!("Image upscaling failed: {}", e), None)
+            e.to_mcp_error("Image upscaling failed")
         })?;
 
+        let summary = result.summary();
+        let dimensions = summary
+            .info
+            .and_then(|info| Some(format!(" ({}x{})", info.width?, info.height?)))
+            .unwrap_or_default();
+        let structured = images_structured_content(vec![summary]);
+
         // Convert result to MCP content
         let content = match result {
             ImageUpscaleResult::Base64(image) => {
                 vec![Content::image(image.data, image.mime_type)]
             }
-            ImageUpscaleResult::LocalFile(path) => {
-                vec![Content::text(format!("Upscaled image saved to: {}", path))]
+            ImageUpscaleResult::LocalFile(image) => {
+                vec![Content::text(format!("Upscaled image saved to: {}{}", image.location, dimensions))]
             }
-            ImageUpscaleResult::StorageUri(uri) => {
-                vec![Content::text(format!("Upscaled image uploaded to: {}", uri))]
+            ImageUpscaleResult::StorageUri(image) => {
+                vec![Content::text(format!("Upscaled image uploaded to: {}{}", image.location, dimensions))]
             }
         };
 
-        Ok(CallToolResult::success(content))
+        let mut tool_result = CallToolResult::success(content);
+        tool_result.structured_content = structured;
+        Ok(tool_result)
+    }
+    /// Edit an image using a mask.
+    pub async fn edit_image(&self, params: ImageEditToolParams) -> Result<CallToolResult, McpError> {
+        info!(edit_mode = ?params.edit_mode, "Editing image");
+
+        // Ensure handler is initialized
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard.as_ref().ok_or_else(|| {
+            McpError::internal_error("Handler not initialized", None)
+        })?;
+
+        let edit_params: ImageEditParams = params.into();
+        let result = handler.edit_image(edit_params).await.map_err(|e| {
+            e.to_mcp_error("Image editing failed")
+        })?;
+        Self::generate_output_result(result, "Edited images")
+    }
+
+    /// Extend an image's canvas.
+    pub async fn outpaint_image(&self, params: ImageOutpaintToolParams) -> Result<CallToolResult, McpError> {
+        info!(target_aspect_ratio = ?params.target_aspect_ratio, "Outpainting image");
+
+        // Ensure handler is initialized
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard.as_ref().ok_or_else(|| {
+            McpError::internal_error("Handler not initialized", None)
+        })?;
+
+        let outpaint_params: ImageOutpaintParams = params.into();
+        let result = handler.outpaint_image(outpaint_params).await.map_err(|e| {
+            e.to_mcp_error("Image outpainting failed")
+        })?;
+
+        let structured = images_structured_content(vec![result.summary()]);
+
+        // Convert result to MCP content
+        let content = match result {
+            ImageUpscaleResult::Base64(image) => {
+                vec![Content::image(image.data, image.mime_type)]
+            }
+            ImageUpscaleResult::LocalFile(image) => {
+                vec![Content::text(format!("Outpainted image saved to: {}", image.location))]
+            }
+            ImageUpscaleResult::StorageUri(image) => {
+                vec![Content::text(format!("Outpainted image uploaded to: {}", image.location))]
+            }
+        };
+
+        let mut tool_result = CallToolResult::success(content);
+        tool_result.structured_content = structured;
+        Ok(tool_result)
+    }
+
+    /// Generate a prompt-guided variation of an image.
+    pub async fn vary_image(&self, params: ImageVariationToolParams) -> Result<CallToolResult, McpError> {
+        info!(mode = ?params.mode, "Generating image variation");
+
+        // Ensure handler is initialized
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard.as_ref().ok_or_else(|| {
+            McpError::internal_error("Handler not initialized", None)
+        })?;
+
+        let variation_params: ImageVariationParams = params.into();
+        let result = handler.vary_image(variation_params).await.map_err(|e| {
+            e.to_mcp_error("Image variation failed")
+        })?;
+        Self::generate_output_result(result, "Image variations")
+    }
+
+    /// List available Imagen models with their aliases and capabilities.
+    pub fn list_models(&self) -> Result<CallToolResult, McpError> {
+        let models = resources::list_models();
+        let models_json = serde_json::to_string_pretty(&models).map_err(|e| {
+            McpError::internal_error(format!("Failed to serialize models: {}", e), None)
+        })?;
+
+        let mut tool_result = CallToolResult::success(vec![Content::text(models_json)]);
+        tool_result.structured_content = Some(serde_json::json!({ "models": models }));
+        Ok(tool_result)
     }
 }
 
+// Method signatures mirror the rmcp trait, which returns `impl Future`.
+#[allow(clippy::manual_async_fn)]
 impl ServerHandler for ImageServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             instructions: Some(
                 "Image generation and processing server using Google Vertex AI Imagen API. \
                  Use image_generate to create images from text prompts, \
-                 and image_upscale to upscale existing images."
+                 image_upscale to upscale existing images, \
+                 image_edit to inpaint or swap backgrounds using a mask, \
+                 image_outpaint to extend an image to a wider or taller canvas, \
+                 image_variation to restyle an image or borrow its style from a prompt, \
+                 and image_list_models to see each model's aspect ratios and prompt limits."
                     .to_string(),
             ),
             capabilities: ServerCapabilities::builder()
@@ -236,6 +596,38 @@ impl ServerHandler for ImageServer {
                 _ => Arc::new(serde_json::Map::new()),
             };
 
+            // image_edit tool
+            let edit_schema = schema_for!(ImageEditToolParams);
+            let edit_schema_value = serde_json::to_value(&edit_schema).unwrap_or_default();
+            let edit_input_schema = match edit_schema_value {
+                serde_json::Value::Object(map) => Arc::new(map),
+                _ => Arc::new(serde_json::Map::new()),
+            };
+
+            // image_outpaint tool
+            let outpaint_schema = schema_for!(ImageOutpaintToolParams);
+            let outpaint_schema_value = serde_json::to_value(&outpaint_schema).unwrap_or_default();
+            let outpaint_input_schema = match outpaint_schema_value {
+                serde_json::Value::Object(map) => Arc::new(map),
+                _ => Arc::new(serde_json::Map::new()),
+            };
+
+            // image_variation tool
+            let variation_schema = schema_for!(ImageVariationToolParams);
+            let variation_schema_value = serde_json::to_value(&variation_schema).unwrap_or_default();
+            let variation_input_schema = match variation_schema_value {
+                serde_json::Value::Object(map) => Arc::new(map),
+                _ => Arc::new(serde_json::Map::new()),
+            };
+
+            // Shared output schema of image_generate, image_edit and image_variation
+            let generate_output_schema = Self::generate_output_schema();
+
+            // image_list_models tool (no parameters - must have type: "object")
+            let mut empty_schema_map = serde_json::Map::new();
+            empty_schema_map.insert("type".to_string(), serde_json::Value::String("object".to_string()));
+            let empty_schema = Arc::new(empty_schema_map);
+
             Ok(ListToolsResult {
                 tools: vec![
                     Tool {
@@ -249,15 +641,15 @@ impl ServerHandler for ImageServer {
                         annotations: None,
                         icons: None,
                         meta: None,
-                        output_schema: None,
+                        output_schema: Some(generate_output_schema.clone()),
                         title: None,
                     },
                     Tool {
                         name: Cow::Borrowed("image_upscale"),
                         description: Some(Cow::Borrowed(
-                            "Upscale an image using Google's Imagen 4.0 Upscale API. \
-                             Supports x2 and x4 upscale factors. \
-                             Accepts base64 image data, local file path, or GCS URI as input. \
+                            "Upscale an image using Google's Imagen Upscale API. \
+                             Supports x2 and x4 upscale factors; x4 selects a model that supports it unless model is given. \
+                             Accepts base64 image data, data URI, local file path, GCS URI, or HTTPS URL as input. \
                              Returns base64-encoded image data, local file path, or storage URI."
                         )),
                         input_schema: upscale_input_schema,
@@ -267,6 +659,69 @@ impl ServerHandler for ImageServer {
                         output_schema: None,
                         title: None,
                     },
+                    Tool {
+                        name: Cow::Borrowed("image_edit"),
+                        description: Some(Cow::Borrowed(
+                            "Edit an image using Google's Imagen capability model. \
+                             Modes: inpaint_insert and inpaint_remove (mask required), \
+                             background_swap (mask optional; the background is detected when omitted). \
+                             Image and mask accept base64 data, data URI, local file path, GCS URI, or HTTPS URL. \
+                             Returns base64-encoded image data, local file paths, or storage URIs."
+                        )),
+                        input_schema: edit_input_schema,
+                        annotations: None,
+                        icons: None,
+                        meta: None,
+                        output_schema: Some(generate_output_schema.clone()),
+                        title: None,
+                    },
+                    Tool {
+                        name: Cow::Borrowed("image_outpaint"),
+                        description: Some(Cow::Borrowed(
+                            "Extend an image's canvas using Google's Imagen capability model. \
+                             Give a target aspect ratio (e.g. 16:9) or explicit target_width/target_height; \
+                             the source is centered and the new area is generated from the prompt. \
+                             Accepts base64 image data, data URI, local file path, GCS URI, or HTTPS URL as input. \
+                             Returns base64-encoded image data, local file path, or storage URI."
+                        )),
+                        input_schema: outpaint_input_schema,
+                        annotations: None,
+                        icons: None,
+                        meta: None,
+                        output_schema: None,
+                        title: None,
+                    },
+                    Tool {
+                        name: Cow::Borrowed("image_variation"),
+                        description: Some(Cow::Borrowed(
+                            "Generate a new image from a source image and a prompt using Google's Imagen \
+                             capability model, e.g. the same scene as a watercolor. \
+                             Modes: structure (default; keeps the composition and restyles it) and \
+                             style (applies the source's style to the prompt's content). \
+                             Accepts base64 image data, data URI, local file path, GCS URI, or HTTPS URL as input. \
+                             Returns base64-encoded image data, local file paths, or storage URIs."
+                        )),
+                        input_schema: variation_input_schema,
+                        annotations: None,
+                        icons: None,
+                        meta: None,
+                        output_schema: Some(generate_output_schema.clone()),
+                        title: None,
+                    },
+                    Tool {
+                        name: Cow::Borrowed("image_list_models"),
+                        description: Some(Cow::Borrowed(
+                            "List available Imagen models with their IDs, aliases, maximum prompt \
+                             length, supported aspect ratios, maximum images per request, and \
+                             whether each model supports generation, editing, or upscaling."
+                        )),
+                        input_schema: empty_schema,
+                        annotations: None,
+                        icons: None,
+                        meta: None,
+                        output_schema: None,
+                        title: None,
+                    },
                 ],
                 next_cursor: None,
                 meta: None,
@@ -282,25 +737,59 @@ impl ServerHandler for ImageServer {
         async move {
             match params.name.as_ref() {
                 "image_generate" => {
+                    let arguments = params.arguments.clone().unwrap_or_default();
                     let tool_params: ImageGenerateToolParams = params
                         .arguments
                         .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
                         .transpose()
-                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), None))?
-                        .ok_or_else(|| McpError::invalid_params("Missing parameters", None))?;
+                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data())))?
+                        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))?;
 
-                    self.generate_image(tool_params).await
+                    self.idempotency
+                        .run("image_generate", &arguments, self.generate_image(tool_params))
+                        .await
                 }
                 "image_upscale" => {
                     let tool_params: ImageUpscaleToolParams = params
                         .arguments
                         .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
                         .transpose()
-                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), None))?
-                        .ok_or_else(|| McpError::invalid_params("Missing parameters", None))?;
+                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data())))?
+                        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))?;
 
                     self.upscale_image(tool_params).await
                 }
+                "image_edit" => {
+                    let tool_params: ImageEditToolParams = params
+                        .arguments
+                        .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
+                        .transpose()
+                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data())))?
+                        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))?;
+
+                    self.edit_image(tool_params).await
+                }
+                "image_outpaint" => {
+                    let tool_params: ImageOutpaintToolParams = params
+                        .arguments
+                        .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
+                        .transpose()
+                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data())))?
+                        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))?;
+
+                    self.outpaint_image(tool_params).await
+                }
+                "image_variation" => {
+                    let tool_params: ImageVariationToolParams = params
+                        .arguments
+                        .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
+                        .transpose()
+                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data())))?
+                        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))?;
+
+                    self.vary_image(tool_params).await
+                }
+                "image_list_models" => self.list_models(),
                 _ => Err(McpError::invalid_params(format!("Unknown tool: {}", params.name), None)),
             }
         }
@@ -320,7 +809,7 @@ impl ServerHandler for ImageServer {
                     uri: "image://models".to_string(),
                     name: "Available Image Models".to_string(),
                     title: None,
-                    description: Some("List of available image generation models".to_string()),
+                    description: Some("List of available image models and their capabilities".to_string()),
                     mime_type: Some("application/json".to_string()),
                     size: None,
                     icons: None,
@@ -400,9 +889,7 @@ mod tests {
     fn test_config() -> Config {
         Config {
             project_id: "test-project".to_string(),
-            location: "us-central1".to_string(),
-            gcs_bucket: None,
-            port: 8080,
+            ..Default::default()
         }
     }
 
@@ -422,8 +909,21 @@ mod tests {
             aspect_ratio: Some("16:9".to_string()),
             number_of_images: Some(2),
             seed: Some(42),
+            add_watermark: Some(false),
             output_file: None,
             output_uri: None,
+            no_cache: None,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: Some("europe-west4".to_string()),
+            idempotency_key: None,
+            write_metadata: None,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let gen_params: ImageGenerateParams = tool_params.into();
@@ -433,6 +933,8 @@ mod tests {
         assert_eq!(gen_params.aspect_ratio, "16:9");
         assert_eq!(gen_params.number_of_images, 2);
         assert_eq!(gen_params.seed, Some(42));
+        assert_eq!(gen_params.add_watermark, Some(false));
+        assert_eq!(gen_params.location.as_deref(), Some("europe-west4"));
     }
 
     #[test]
@@ -444,8 +946,21 @@ mod tests {
             aspect_ratio: None,
             number_of_images: None,
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: None,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            idempotency_key: None,
+            write_metadata: None,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let gen_params: ImageGenerateParams = tool_params.into();
@@ -453,4 +968,77 @@ mod tests {
         assert_eq!(gen_params.aspect_ratio, "1:1");
         assert_eq!(gen_params.number_of_images, 1);
     }
+
+    #[test]
+    fn test_tool_params_accept_idempotency_key() {
+        let tool_params: ImageGenerateToolParams = serde_json::from_value(serde_json::json!({
+            "prompt": "A cat",
+            "idempotency_key": "req-1"
+        }))
+        .unwrap();
+        assert_eq!(tool_params.idempotency_key.as_deref(), Some("req-1"));
+
+        let schema = serde_json::to_value(schemars::schema_for!(ImageGenerateToolParams)).unwrap();
+        assert!(schema["properties"]["idempotency_key"].is_object());
+    }
+
+    #[test]
+    fn test_variation_tool_params_defaults() {
+        let tool_params: ImageVariationToolParams = serde_json::from_value(serde_json::json!({
+            "image": "gs://bucket/photo.png",
+            "prompt": "As a watercolor painting"
+        }))
+        .unwrap();
+        let variation_params: ImageVariationParams = tool_params.into();
+        assert_eq!(variation_params.mode, ImageVariationMode::Structure);
+        assert_eq!(variation_params.style_description, None);
+        assert_eq!(variation_params.number_of_images, 1);
+    }
+
+    #[test]
+    fn test_edit_tool_params_defaults() {
+        let tool_params: ImageEditToolParams = serde_json::from_value(serde_json::json!({
+            "image": "gs://bucket/in.png",
+            "edit_mode": "background_swap"
+        }))
+        .unwrap();
+
+        let edit_params: ImageEditParams = tool_params.into();
+        assert_eq!(edit_params.edit_mode, ImageEditMode::BackgroundSwap);
+        assert_eq!(edit_params.prompt, "");
+        assert_eq!(edit_params.mask, None);
+        assert_eq!(edit_params.number_of_images, 1);
+    }
+
+    #[test]
+    fn test_list_models_returns_capabilities() {
+        let server = ImageServer::new(test_config());
+        let tool_result = server.list_models().unwrap();
+
+        let structured = tool_result.structured_content.expect("structured content");
+        let models = structured["models"].as_array().expect("models array");
+        let imagen3 = models
+            .iter()
+            .find(|m| m["id"] == "imagen-3.0-generate-002")
+            .expect("imagen 3 listed");
+        assert!(imagen3["aliases"].as_array().unwrap().contains(&serde_json::json!("imagen-3")));
+        assert!(imagen3["max_prompt_length"].as_u64().unwrap() > 0);
+        assert!(imagen3["supported_aspect_ratios"].is_array());
+        assert_eq!(imagen3["supports_generation"], true);
+        assert_eq!(imagen3["supports_editing"], false);
+
+        let edit = models
+            .iter()
+            .find(|m| m["supports_editing"] == true)
+            .expect("edit model listed");
+        assert_eq!(edit["id"], crate::handler::EDIT_MODEL);
+        let upscale = models
+            .iter()
+            .find(|m| m["supports_upscaling"] == true)
+            .expect("upscale model listed");
+        assert_eq!(upscale["id"], crate::handler::UPSCALE_MODEL);
+
+        let text = tool_result.content[0].as_text().expect("text content");
+        assert!(text.text.contains("imagen-4.0-generate-preview-06-06"));
+    }
 }
@@ -16,7 +16,7 @@ use adk_rust_mcp_common::config::Config;
 use adk_rust_mcp_common::gcs::GcsClient;
 use std::env;
 use std::path::PathBuf;
-use std::sync::Once;
+use std::sync::{Arc, Once};
 
 static INIT: Once = Once::new();
 
@@ -61,7 +61,7 @@ fn get_test_config() -> Option<Config> {
         project_id,
         location: env::var("LOCATION").unwrap_or_else(|_| "us-central1".to_string()),
         gcs_bucket: env::var("GCS_BUCKET").ok(),
-        port: 8080,
+        ..Default::default()
     })
 }
 
@@ -88,13 +88,13 @@ macro_rules! skip_if_no_integration {
 
 mod imagen_api_tests {
     use super::*;
-    use adk_rust_mcp_image::handler::{ImageGenerateParams, ImageHandler, ImageGenerateResult};
+    use adk_rust_mcp_image::handler::{ImageGenerateOutput, ImageGenerateParams, ImageHandler};
 
     /// The current Imagen 4 model ID
     const IMAGEN_4_MODEL: &str = "imagen-4.0-generate-preview-06-06";
 
     /// Helper to save base64 images to test output directory
-    fn save_test_images(images: &[adk_rust_mcp_image::GeneratedImage], prefix: &str) {
+    fn save_test_images(images: &[adk_rust_mcp_image::ImageArtifact], prefix: &str) {
         let output_dir = get_test_output_dir();
         for (i, img) in images.iter().enumerate() {
             let ext = if img.mime_type.contains("png") { "png" } else { "jpg" };
@@ -107,7 +107,7 @@ mod imagen_api_tests {
             
             if let Ok(data) = base64::Engine::decode(
                 &base64::engine::general_purpose::STANDARD,
-                &img.data
+                img.data.as_deref().unwrap_or_default()
             ) {
                 if let Err(e) = std::fs::write(&path, &data) {
                     eprintln!("Failed to save {}: {}", filename, e);
@@ -134,16 +134,28 @@ mod imagen_api_tests {
             aspect_ratio: "1:1".to_string(),
             number_of_images: 1,
             seed: None, // Seed not supported with watermark enabled
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = handler.generate_image(params).await;
         
         match result {
-            Ok(ImageGenerateResult::Base64(images)) => {
+            Ok(ImageGenerateOutput { images, .. }) if images.iter().all(|i| i.data.is_some()) => {
                 assert_eq!(images.len(), 1, "Should generate exactly 1 image");
-                assert!(!images[0].data.is_empty(), "Image data should not be empty");
+                assert!(!images[0].data.as_deref().unwrap_or_default().is_empty(), "Image data should not be empty");
                 assert!(images[0].mime_type.starts_with("image/"), "Should have image MIME type");
                 
                 // Save to test output
@@ -152,7 +164,7 @@ mod imagen_api_tests {
                 // Verify it's valid base64
                 let decoded = base64::Engine::decode(
                     &base64::engine::general_purpose::STANDARD,
-                    &images[0].data
+                    images[0].data.as_deref().unwrap_or_default()
                 );
                 assert!(decoded.is_ok(), "Should be valid base64 data");
                 
@@ -180,17 +192,29 @@ mod imagen_api_tests {
             aspect_ratio: "1:1".to_string(),
             number_of_images: 2,
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = handler.generate_image(params).await;
         
         match result {
-            Ok(ImageGenerateResult::Base64(images)) => {
+            Ok(ImageGenerateOutput { images, .. }) if images.iter().all(|i| i.data.is_some()) => {
                 assert_eq!(images.len(), 2, "Should generate exactly 2 images");
                 for (i, img) in images.iter().enumerate() {
-                    assert!(!img.data.is_empty(), "Image {} data should not be empty", i);
+                    assert!(!img.data.as_deref().unwrap_or_default().is_empty(), "Image {} data should not be empty", i);
                 }
                 
                 // Save to test output
@@ -217,14 +241,26 @@ mod imagen_api_tests {
             aspect_ratio: "16:9".to_string(),
             number_of_images: 1,
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = handler.generate_image(params).await;
         
         match result {
-            Ok(ImageGenerateResult::Base64(images)) => {
+            Ok(ImageGenerateOutput { images, .. }) if images.iter().all(|i| i.data.is_some()) => {
                 save_test_images(&images, "landscape_16x9");
             }
             Ok(_) => {}
@@ -247,14 +283,26 @@ mod imagen_api_tests {
             aspect_ratio: "1:1".to_string(),
             number_of_images: 1,
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = handler.generate_image(params).await;
         
         match result {
-            Ok(ImageGenerateResult::Base64(images)) => {
+            Ok(ImageGenerateOutput { images, .. }) if images.iter().all(|i| i.data.is_some()) => {
                 save_test_images(&images, "cat_on_couch");
             }
             Ok(_) => {}
@@ -281,16 +329,28 @@ mod imagen_api_tests {
             aspect_ratio: "1:1".to_string(),
             number_of_images: 1,
             seed: None,
+            add_watermark: None,
             output_file: Some(output_path.to_string_lossy().to_string()),
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = handler.generate_image(params).await;
         
         match result {
-            Ok(ImageGenerateResult::LocalFiles(paths)) => {
+            Ok(ImageGenerateOutput { images: paths, .. }) if paths.iter().all(|i| i.local_path.is_some()) => {
                 assert_eq!(paths.len(), 1, "Should have 1 output path");
-                let path = PathBuf::from(&paths[0]);
+                let path = PathBuf::from(paths[0].location().unwrap());
                 assert!(path.exists(), "Output file should exist");
                 
                 let metadata = std::fs::metadata(&path).expect("Should read file metadata");
@@ -322,18 +382,30 @@ mod imagen_api_tests {
             aspect_ratio: "1:1".to_string(),
             number_of_images: 2,
             seed: None,
+            add_watermark: None,
             output_file: Some(output_path.to_string_lossy().to_string()),
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = handler.generate_image(params).await;
         
         match result {
-            Ok(ImageGenerateResult::LocalFiles(paths)) => {
+            Ok(ImageGenerateOutput { images: paths, .. }) if paths.iter().all(|i| i.local_path.is_some()) => {
                 assert_eq!(paths.len(), 2, "Should have 2 output paths");
                 for path_str in &paths {
-                    let path = PathBuf::from(path_str);
-                    assert!(path.exists(), "Output file {} should exist", path_str);
+                    let path = PathBuf::from(path_str.location().unwrap());
+                    assert!(path.exists(), "Output file {} should exist", path_str.location().unwrap());
                     eprintln!("Saved: {}", path.display());
                 }
             }
@@ -365,7 +437,7 @@ mod auth_tests {
 
 mod gcs_tests {
     use super::*;
-    use adk_rust_mcp_image::handler::{ImageGenerateParams, ImageHandler, ImageGenerateResult};
+    use adk_rust_mcp_image::handler::{ImageGenerateOutput, ImageGenerateParams, ImageHandler};
 
     /// The current Imagen 4 model ID
     const IMAGEN_4_MODEL: &str = "imagen-4.0-generate-preview-06-06";
@@ -385,7 +457,7 @@ mod gcs_tests {
         };
 
         let auth = AuthProvider::new().await.expect("Failed to create auth");
-        let gcs = GcsClient::with_auth(auth);
+        let gcs = GcsClient::with_auth(Arc::new(auth));
 
         // Test data
         let test_data = b"Hello, integration test!";
@@ -440,21 +512,33 @@ mod gcs_tests {
             aspect_ratio: "16:9".to_string(),
             number_of_images: 1,
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: Some(output_uri.clone()),
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = handler.generate_image(params).await;
         
         match result {
-            Ok(ImageGenerateResult::StorageUris(uris)) => {
+            Ok(ImageGenerateOutput { images: uris, .. }) if uris.iter().all(|i| i.gcs_uri.is_some()) => {
                 assert_eq!(uris.len(), 1, "Should have 1 output URI");
-                eprintln!("Image uploaded to GCS: {}", uris[0]);
+                eprintln!("Image uploaded to GCS: {}", uris[0].location().unwrap());
                 
                 // Verify the file exists in GCS
                 let auth = AuthProvider::new().await.expect("Failed to create auth");
-                let gcs = GcsClient::with_auth(auth);
-                let uri = adk_rust_mcp_common::gcs::GcsUri::parse(&uris[0])
+                let gcs = GcsClient::with_auth(Arc::new(auth));
+                let uri = adk_rust_mcp_common::gcs::GcsUri::parse(uris[0].location().unwrap())
                     .expect("Should parse GCS URI");
                 
                 let exists = gcs.exists(&uri).await;
@@ -502,23 +586,35 @@ mod gcs_tests {
             aspect_ratio: "16:9".to_string(),
             number_of_images: 2,
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: Some(output_uri.clone()),
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = handler.generate_image(params).await;
         
         match result {
-            Ok(ImageGenerateResult::StorageUris(uris)) => {
+            Ok(ImageGenerateOutput { images: uris, .. }) if uris.iter().all(|i| i.gcs_uri.is_some()) => {
                 assert_eq!(uris.len(), 2, "Should have 2 output URIs");
                 
                 let auth = AuthProvider::new().await.expect("Failed to create auth");
-                let gcs = GcsClient::with_auth(auth);
+                let gcs = GcsClient::with_auth(Arc::new(auth));
                 
                 for (i, uri_str) in uris.iter().enumerate() {
-                    eprintln!("Image {} uploaded to GCS: {}", i, uri_str);
+                    eprintln!("Image {} uploaded to GCS: {}", i, uri_str.location().unwrap());
                     
-                    let uri = adk_rust_mcp_common::gcs::GcsUri::parse(uri_str)
+                    let uri = adk_rust_mcp_common::gcs::GcsUri::parse(uri_str.location().unwrap())
                         .expect("Should parse GCS URI");
                     
                     let exists = gcs.exists(&uri).await;
@@ -1,15 +1,21 @@
 //! Multimodal generation handler for the MCP Multimodal server.
 //!
 //! This module provides the `MultimodalHandler` struct and parameter types for
-//! image generation and text-to-speech using Google's Gemini API.
+//! image generation, image description, video analysis, and text-to-speech using
+//! Google's Gemini API.
 
 use adk_rust_mcp_common::auth::AuthProvider;
 use adk_rust_mcp_common::config::Config;
-use adk_rust_mcp_common::error::Error;
+use adk_rust_mcp_common::error::{Error, SafetyBlock};
+use adk_rust_mcp_common::gcs::{GcsClient, GcsUri};
+use adk_rust_mcp_common::media_input;
+use adk_rust_mcp_common::models::{GeminiModel, ModelRegistry, GEMINI_MODELS};
+use adk_rust_mcp_common::voices::{self, TtsVoice, VoiceCache, VoiceSource};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
+use std::sync::Arc;
 use tracing::{debug, info, instrument};
 
 /// Default model for multimodal image generation.
@@ -18,10 +24,77 @@ pub const DEFAULT_IMAGE_MODEL: &str = "gemini-2.5-flash-image";
 /// Default model for multimodal TTS.
 pub const DEFAULT_TTS_MODEL: &str = "gemini-2.5-flash-preview-tts";
 
+/// Default model for image description.
+pub const DEFAULT_DESCRIBE_MODEL: &str = "gemini-2.5-flash";
+
+/// Instruction sent with the image when no question is given.
+pub const DEFAULT_DESCRIBE_QUESTION: &str = "Describe this image in detail.";
+
+/// Maximum value accepted for `max_output_tokens`.
+pub const MAX_DESCRIBE_OUTPUT_TOKENS: u32 = 8192;
+
+/// Harm categories accepted in `safety_settings`.
+pub const SAFETY_CATEGORIES: &[&str] = &[
+    "HARM_CATEGORY_HARASSMENT",
+    "HARM_CATEGORY_HATE_SPEECH",
+    "HARM_CATEGORY_SEXUALLY_EXPLICIT",
+    "HARM_CATEGORY_DANGEROUS_CONTENT",
+    "HARM_CATEGORY_CIVIC_INTEGRITY",
+];
+
+/// Block thresholds accepted in `safety_settings`, from least to most strict.
+pub const SAFETY_THRESHOLDS: &[&str] = &[
+    "OFF",
+    "BLOCK_NONE",
+    "BLOCK_ONLY_HIGH",
+    "BLOCK_MEDIUM_AND_ABOVE",
+    "BLOCK_LOW_AND_ABOVE",
+];
+
+/// Finish reasons meaning a candidate was withheld on safety grounds.
+pub const SAFETY_FINISH_REASONS: &[&str] = &[
+    "SAFETY",
+    "PROHIBITED_CONTENT",
+    "BLOCKLIST",
+    "SPII",
+    "IMAGE_SAFETY",
+];
+
+/// Maximum value accepted for `generation_config.temperature`.
+pub const MAX_TEMPERATURE: f32 = 2.0;
+
+/// Instruction sent with the video when no question is given.
+pub const DEFAULT_ANALYZE_VIDEO_QUESTION: &str = "Summarize what happens in this video.";
+
+/// Largest local video sent inline (15 MiB). Base64 grows it by a third, which
+/// keeps the request under Gemini's 20 MB inline data limit; larger videos
+/// must be passed as GCS URIs.
+pub const MAX_INLINE_VIDEO_BYTES: u64 = 15 * 1024 * 1024;
+
+/// Video file extensions accepted by video analysis, with their MIME types.
+pub const SUPPORTED_VIDEO_TYPES: &[(&str, &str)] = &[
+    ("mp4", "video/mp4"),
+    ("mov", "video/quicktime"),
+    ("webm", "video/webm"),
+    ("mpeg", "video/mpeg"),
+    ("mpg", "video/mpeg"),
+    ("avi", "video/x-msvideo"),
+    ("flv", "video/x-flv"),
+    ("wmv", "video/wmv"),
+    ("3gp", "video/3gpp"),
+];
+
+/// Maximum number of reference images for image editing.
+pub const MAX_REFERENCE_IMAGES: usize = 3;
+
+/// Maximum decoded size of a single reference image (7 MiB, the Gemini inline image limit).
+pub const MAX_REFERENCE_IMAGE_BYTES: usize = 7 * 1024 * 1024;
+
 /// Default voice for multimodal TTS.
 pub const DEFAULT_VOICE: &str = "Kore";
 
-/// Available Gemini TTS voices.
+/// Gemini TTS voices accepted without consulting the voices API, and listed
+/// when it is unavailable.
 pub const AVAILABLE_VOICES: &[&str] = &[
     "Zephyr", "Puck", "Charon", "Kore", "Fenrir", "Leda", "Orus", "Aoede",
 ];
@@ -31,7 +104,8 @@ pub const AVAILABLE_STYLES: &[&str] = &[
     "neutral", "cheerful", "sad", "angry", "fearful", "surprised", "calm",
 ];
 
-/// Supported language codes for Gemini TTS.
+/// Language codes listed when the voices API is unavailable, and the names
+/// shown for them.
 pub const SUPPORTED_LANGUAGE_CODES: &[(&str, &str)] = &[
     ("en-US", "English (US)"),
     ("en-GB", "English (UK)"),
@@ -62,10 +136,29 @@ pub struct MultimodalImageParams {
     #[serde(default = "default_image_model")]
     pub model: String,
 
+    /// Images to edit or build on (base64 data, data URI, local file path, GCS
+    /// URI, or HTTPS URL). With references, the prompt is an edit instruction such
+    /// as "make this logo blue".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference_images: Option<Vec<String>>,
+
     /// Output file path for saving the image locally.
-    /// If not specified, returns base64-encoded data.
+    /// If neither this nor output_uri is specified, returns base64-encoded data.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_file: Option<String>,
+
+    /// GCS URI to upload the image to (gs://bucket/path, or an object name in
+    /// GCS_BUCKET). Takes precedence over output_file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_uri: Option<String>,
+
+    /// Safety filter thresholds per harm category; unset categories use the API defaults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub safety_settings: Option<Vec<SafetySetting>>,
+
+    /// Sampling options passed through to the Gemini generation config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation_config: Option<GenerationConfig>,
 }
 
 fn default_image_model() -> String {
@@ -93,15 +186,127 @@ pub struct MultimodalTtsParams {
     pub model: String,
 
     /// Output file path for saving the audio locally.
-    /// If not specified, returns base64-encoded data.
+    /// If neither this nor output_uri is specified, returns base64-encoded data.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_file: Option<String>,
+
+    /// GCS URI to upload the audio to (gs://bucket/path, or an object name in
+    /// GCS_BUCKET). Takes precedence over output_file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_uri: Option<String>,
+
+    /// Safety filter thresholds per harm category; unset categories use the API defaults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub safety_settings: Option<Vec<SafetySetting>>,
+
+    /// Sampling options passed through to the Gemini generation config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation_config: Option<GenerationConfig>,
 }
 
 fn default_tts_model() -> String {
     DEFAULT_TTS_MODEL.to_string()
 }
 
+/// Multimodal image description parameters.
+///
+/// These parameters control image understanding via the Gemini API.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct MultimodalDescribeParams {
+    /// Image to describe: base64 data, data URI, local file path, GCS URI, or HTTPS URL.
+    pub image: String,
+
+    /// Question or instruction about the image.
+    /// Defaults to a general request for a detailed description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub question: Option<String>,
+
+    /// Model to use for description.
+    #[serde(default = "default_describe_model")]
+    pub model: String,
+
+    /// Maximum number of tokens in the description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+
+    /// Safety filter thresholds per harm category; unset categories use the API defaults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub safety_settings: Option<Vec<SafetySetting>>,
+
+    /// Sampling options passed through to the Gemini generation config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation_config: Option<GenerationConfig>,
+}
+
+fn default_describe_model() -> String {
+    DEFAULT_DESCRIBE_MODEL.to_string()
+}
+
+/// Video analysis parameters.
+///
+/// These parameters control video understanding via the Gemini API.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct MultimodalAnalyzeVideoParams {
+    /// Video to analyze: GCS URI (preferred) or local file path. Local files
+    /// are sent inline and limited to 15 MiB.
+    pub video: String,
+
+    /// Question or instruction about the video.
+    /// Defaults to a request for a summary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub question: Option<String>,
+
+    /// Model to use for analysis.
+    #[serde(default = "default_describe_model")]
+    pub model: String,
+
+    /// Maximum number of tokens in the answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+
+    /// Safety filter thresholds per harm category; unset categories use the API defaults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub safety_settings: Option<Vec<SafetySetting>>,
+
+    /// Sampling options passed through to the Gemini generation config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation_config: Option<GenerationConfig>,
+}
+
+/// Safety filter threshold for one harm category.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct SafetySetting {
+    /// Harm category, e.g. "HARM_CATEGORY_DANGEROUS_CONTENT".
+    pub category: String,
+
+    /// Block threshold, e.g. "BLOCK_ONLY_HIGH".
+    pub threshold: String,
+}
+
+/// Sampling options for Gemini generation.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+pub struct GenerationConfig {
+    /// Sampling temperature (0.0-2.0).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    /// Nucleus sampling probability mass (0.0-1.0).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+
+    /// Number of highest-probability tokens to sample from (at least 1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<u32>,
+
+    /// Maximum number of tokens to generate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+
+    /// Seed for reproducible sampling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i32>,
+}
+
 /// Validation error details.
 #[derive(Debug, Clone)]
 pub struct ValidationError {
@@ -117,6 +322,143 @@ impl std::fmt::Display for ValidationError {
     }
 }
 
+/// Check that a model is a known Gemini model with the capability a tool needs.
+fn validate_model(
+    name: &str,
+    capability: &str,
+    supports: fn(&GeminiModel) -> bool,
+    errors: &mut Vec<ValidationError>,
+) {
+    let message = match ModelRegistry::resolve_gemini(name) {
+        None => ModelRegistry::unknown_model_message(GEMINI_MODELS, name),
+        Some(model) if !supports(model) => format!(
+            "Model '{}' does not support {}. Models that do: {}",
+            model.id,
+            capability,
+            GEMINI_MODELS
+                .iter()
+                .filter(|m| supports(m))
+                .map(|m| m.id)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Some(_) => return,
+    };
+    errors.push(ValidationError {
+        field: "model".to_string(),
+        message,
+    });
+}
+
+/// Check `safety_settings` and `generation_config` against the values Gemini accepts.
+fn validate_generation_settings(
+    safety_settings: Option<&[SafetySetting]>,
+    generation_config: Option<&GenerationConfig>,
+    errors: &mut Vec<ValidationError>,
+) {
+    for (i, setting) in safety_settings.unwrap_or_default().iter().enumerate() {
+        if !SAFETY_CATEGORIES.contains(&setting.category.as_str()) {
+            errors.push(ValidationError {
+                field: format!("safety_settings[{}].category", i),
+                message: format!(
+                    "Invalid category '{}'. Valid categories: {}",
+                    setting.category,
+                    SAFETY_CATEGORIES.join(", ")
+                ),
+            });
+        } else if let Some(j) = safety_settings
+            .unwrap_or_default()
+            .iter()
+            .take(i)
+            .position(|other| other.category == setting.category)
+        {
+            errors.push(ValidationError {
+                field: format!("safety_settings[{}].category", i),
+                message: format!("'{}' is already set by safety_settings[{}]", setting.category, j),
+            });
+        }
+        if !SAFETY_THRESHOLDS.contains(&setting.threshold.as_str()) {
+            errors.push(ValidationError {
+                field: format!("safety_settings[{}].threshold", i),
+                message: format!(
+                    "Invalid threshold '{}'. Valid thresholds: {}",
+                    setting.threshold,
+                    SAFETY_THRESHOLDS.join(", ")
+                ),
+            });
+        }
+    }
+
+    let Some(config) = generation_config else {
+        return;
+    };
+    if let Some(temperature) = config.temperature {
+        if !(0.0..=MAX_TEMPERATURE).contains(&temperature) {
+            errors.push(ValidationError {
+                field: "generation_config.temperature".to_string(),
+                message: format!("temperature must be between 0 and {}, got {}", MAX_TEMPERATURE, temperature),
+            });
+        }
+    }
+    if let Some(top_p) = config.top_p {
+        if !(0.0..=1.0).contains(&top_p) {
+            errors.push(ValidationError {
+                field: "generation_config.top_p".to_string(),
+                message: format!("top_p must be between 0 and 1, got {}", top_p),
+            });
+        }
+    }
+    if config.top_k == Some(0) {
+        errors.push(ValidationError {
+            field: "generation_config.top_k".to_string(),
+            message: "top_k must be at least 1, got 0".to_string(),
+        });
+    }
+    if let Some(tokens) = config.max_output_tokens {
+        if tokens == 0 || tokens > MAX_DESCRIBE_OUTPUT_TOKENS {
+            errors.push(ValidationError {
+                field: "generation_config.max_output_tokens".to_string(),
+                message: format!(
+                    "max_output_tokens must be between 1 and {}, got {}",
+                    MAX_DESCRIBE_OUTPUT_TOKENS, tokens
+                ),
+            });
+        }
+    }
+}
+
+/// Check that a resolved `output_uri` is a well-formed GCS URI.
+fn validate_output_uri(output_uri: Option<&str>, errors: &mut Vec<ValidationError>) {
+    let Some(uri) = output_uri else {
+        return;
+    };
+    let message = if !uri.starts_with("gs://") {
+        format!("output_uri must be a GCS URI starting with 'gs://', got '{}'", uri)
+    } else if let Err(e) = GcsUri::parse(uri) {
+        e.to_string()
+    } else {
+        return;
+    };
+    errors.push(ValidationError {
+        field: "output_uri".to_string(),
+        message,
+    });
+}
+
+/// Reject `max_output_tokens` given both at the top level and in `generation_config`.
+fn validate_single_token_limit(
+    max_output_tokens: Option<u32>,
+    generation_config: Option<&GenerationConfig>,
+    errors: &mut Vec<ValidationError>,
+) {
+    if max_output_tokens.is_some() && generation_config.is_some_and(|c| c.max_output_tokens.is_some()) {
+        errors.push(ValidationError {
+            field: "generation_config.max_output_tokens".to_string(),
+            message: "max_output_tokens is also set at the top level; set only one".to_string(),
+        });
+    }
+}
+
 impl MultimodalImageParams {
     /// Validate the parameters.
     pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
@@ -130,17 +472,67 @@ impl MultimodalImageParams {
             });
         }
 
+        validate_model(&self.model, "image generation", |m| m.supports_image_generation, &mut errors);
+
+        if let Some(ref references) = self.reference_images {
+            if references.is_empty() || references.len() > MAX_REFERENCE_IMAGES {
+                errors.push(ValidationError {
+                    field: "reference_images".to_string(),
+                    message: format!(
+                        "reference_images must contain between 1 and {} images, got {}",
+                        MAX_REFERENCE_IMAGES,
+                        references.len()
+                    ),
+                });
+            }
+            for (i, reference) in references.iter().enumerate() {
+                if reference.trim().is_empty() {
+                    errors.push(ValidationError {
+                        field: format!("reference_images[{}]", i),
+                        message: "Reference image cannot be empty".to_string(),
+                    });
+                }
+            }
+        }
+
+        validate_output_uri(self.output_uri.as_deref(), &mut errors);
+        validate_generation_settings(
+            self.safety_settings.as_deref(),
+            self.generation_config.as_ref(),
+            &mut errors,
+        );
+
         if errors.is_empty() {
             Ok(())
         } else {
             Err(errors)
         }
     }
+
+    /// Get the resolved model definition.
+    pub fn get_model(&self) -> Option<&'static GeminiModel> {
+        ModelRegistry::resolve_gemini(&self.model)
+    }
+
+    /// Whether this request edits reference images or generates from text alone.
+    pub fn mode(&self) -> ImageMode {
+        if self.reference_images.as_ref().is_some_and(|r| !r.is_empty()) {
+            ImageMode::Edit
+        } else {
+            ImageMode::Generate
+        }
+    }
 }
 
 impl MultimodalTtsParams {
-    /// Validate the parameters.
+    /// Validate the parameters, accepting only the built-in voices.
     pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        self.validate_with_voices(&[])
+    }
+
+    /// Validate the parameters, accepting `extra_voices` (e.g. voices listed
+    /// by the API) besides [`AVAILABLE_VOICES`].
+    pub fn validate_with_voices(&self, extra_voices: &[String]) -> Result<(), Vec<ValidationError>> {
         let mut errors = Vec::new();
 
         // Validate text is not empty
@@ -151,15 +543,19 @@ impl MultimodalTtsParams {
             });
         }
 
+        validate_model(&self.model, "text-to-speech", |m| m.supports_tts, &mut errors);
+
         // Validate voice if provided
         if let Some(ref voice) = self.voice {
-            if !AVAILABLE_VOICES.contains(&voice.as_str()) {
+            if !AVAILABLE_VOICES.contains(&voice.as_str()) && !extra_voices.contains(voice) {
+                let mut available: Vec<&str> = AVAILABLE_VOICES.to_vec();
+                available.extend(extra_voices.iter().map(String::as_str).filter(|v| !AVAILABLE_VOICES.contains(v)));
                 errors.push(ValidationError {
                     field: "voice".to_string(),
                     message: format!(
                         "Invalid voice '{}'. Available voices: {}",
                         voice,
-                        AVAILABLE_VOICES.join(", ")
+                        available.join(", ")
                     ),
                 });
             }
@@ -179,6 +575,13 @@ impl MultimodalTtsParams {
             }
         }
 
+        validate_output_uri(self.output_uri.as_deref(), &mut errors);
+        validate_generation_settings(
+            self.safety_settings.as_deref(),
+            self.generation_config.as_ref(),
+            &mut errors,
+        );
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -190,18 +593,186 @@ impl MultimodalTtsParams {
     pub fn get_voice(&self) -> &str {
         self.voice.as_deref().unwrap_or(DEFAULT_VOICE)
     }
+
+    /// Get the resolved model definition.
+    pub fn get_model(&self) -> Option<&'static GeminiModel> {
+        ModelRegistry::resolve_gemini(&self.model)
+    }
+}
+
+impl MultimodalDescribeParams {
+    /// Validate the parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if self.image.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "image".to_string(),
+                message: "Image cannot be empty".to_string(),
+            });
+        }
+
+        if let Some(ref question) = self.question {
+            if question.trim().is_empty() {
+                errors.push(ValidationError {
+                    field: "question".to_string(),
+                    message: "Question cannot be empty when provided".to_string(),
+                });
+            }
+        }
+
+        if let Some(tokens) = self.max_output_tokens {
+            if tokens == 0 || tokens > MAX_DESCRIBE_OUTPUT_TOKENS {
+                errors.push(ValidationError {
+                    field: "max_output_tokens".to_string(),
+                    message: format!(
+                        "max_output_tokens must be between 1 and {}, got {}",
+                        MAX_DESCRIBE_OUTPUT_TOKENS, tokens
+                    ),
+                });
+            }
+        }
+
+        validate_generation_settings(
+            self.safety_settings.as_deref(),
+            self.generation_config.as_ref(),
+            &mut errors,
+        );
+        validate_single_token_limit(self.max_output_tokens, self.generation_config.as_ref(), &mut errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Get the question to ask, defaulting if not specified.
+    pub fn get_question(&self) -> &str {
+        self.question.as_deref().unwrap_or(DEFAULT_DESCRIBE_QUESTION)
+    }
+}
+
+impl MultimodalAnalyzeVideoParams {
+    /// Validate the parameters.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if self.video.trim().is_empty() {
+            errors.push(ValidationError {
+                field: "video".to_string(),
+                message: "Video cannot be empty".to_string(),
+            });
+        } else if self.video.starts_with("gs://") {
+            if let Err(e) = GcsUri::parse(&self.video) {
+                errors.push(ValidationError {
+                    field: "video".to_string(),
+                    message: e.to_string(),
+                });
+            }
+        } else if media_input::is_http_url(&self.video) {
+            errors.push(ValidationError {
+                field: "video".to_string(),
+                message: "Video must be a GCS URI or local file path; upload remote videos to GCS first"
+                    .to_string(),
+            });
+        }
+
+        if !self.video.trim().is_empty() && video_mime_type(&self.video).is_none() {
+            let supported: Vec<&str> = SUPPORTED_VIDEO_TYPES.iter().map(|(ext, _)| *ext).collect();
+            errors.push(ValidationError {
+                field: "video".to_string(),
+                message: format!(
+                    "Unsupported video type for '{}'; expected one of: {}",
+                    self.video,
+                    supported.join(", ")
+                ),
+            });
+        }
+
+        if let Some(ref question) = self.question {
+            if question.trim().is_empty() {
+                errors.push(ValidationError {
+                    field: "question".to_string(),
+                    message: "Question cannot be empty when provided".to_string(),
+                });
+            }
+        }
+
+        if let Some(tokens) = self.max_output_tokens {
+            if tokens == 0 || tokens > MAX_DESCRIBE_OUTPUT_TOKENS {
+                errors.push(ValidationError {
+                    field: "max_output_tokens".to_string(),
+                    message: format!(
+                        "max_output_tokens must be between 1 and {}, got {}",
+                        MAX_DESCRIBE_OUTPUT_TOKENS, tokens
+                    ),
+                });
+            }
+        }
+
+        validate_generation_settings(
+            self.safety_settings.as_deref(),
+            self.generation_config.as_ref(),
+            &mut errors,
+        );
+        validate_single_token_limit(self.max_output_tokens, self.generation_config.as_ref(), &mut errors);
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Get the question to ask, defaulting if not specified.
+    pub fn get_question(&self) -> &str {
+        self.question.as_deref().unwrap_or(DEFAULT_ANALYZE_VIDEO_QUESTION)
+    }
+}
+
+/// Video MIME type for a path or URI, from its file extension.
+pub fn video_mime_type(path: &str) -> Option<&'static str> {
+    let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+    SUPPORTED_VIDEO_TYPES
+        .iter()
+        .find(|(ext, _)| *ext == extension)
+        .map(|(_, mime)| *mime)
+}
+
+/// Detect an image MIME type from its leading bytes.
+///
+/// Recognizes PNG, JPEG, GIF, and WebP; returns `None` for anything else.
+pub fn detect_image_mime_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
 }
 
 /// Multimodal generation handler.
 ///
-/// Handles image generation and TTS requests using the Gemini API.
+/// Handles image generation, image description, and TTS requests using the Gemini API.
 pub struct MultimodalHandler {
     /// Application configuration.
     pub config: Config,
+    /// GCS client for reading input images and uploading outputs.
+    pub gcs: GcsClient,
     /// HTTP client for API requests.
     pub http: reqwest::Client,
     /// Authentication provider.
-    pub auth: AuthProvider,
+    pub auth: Arc<AuthProvider>,
+    /// Voice list fetched from the Cloud TTS API.
+    pub voices: VoiceCache,
+    /// Base URL of the Cloud TTS API, which lists the voices.
+    pub tts_api_base_url: String,
 }
 
 impl MultimodalHandler {
@@ -213,16 +784,31 @@ impl MultimodalHandler {
     pub async fn new(config: Config) -> Result<Self, Error> {
         debug!("Initializing MultimodalHandler");
 
-        let auth = AuthProvider::new().await?;
-        let http = reqwest::Client::new();
-
-        Ok(Self { config, http, auth })
+        let auth = Arc::new(AuthProvider::new().await?);
+        let http = config.http.build()?;
+        let gcs = GcsClient::with_client(Arc::clone(&auth), http.clone());
+
+        Ok(Self {
+            config,
+            gcs,
+            http,
+            auth,
+            voices: VoiceCache::from_env(),
+            tts_api_base_url: voices::TTS_API_BASE_URL.to_string(),
+        })
     }
 
     /// Create a new MultimodalHandler with provided dependencies (for testing).
     #[cfg(test)]
-    pub fn with_deps(config: Config, http: reqwest::Client, auth: AuthProvider) -> Self {
-        Self { config, http, auth }
+    pub fn with_deps(config: Config, gcs: GcsClient, http: reqwest::Client, auth: Arc<AuthProvider>) -> Self {
+        Self {
+            config,
+            gcs,
+            http,
+            auth,
+            voices: VoiceCache::default(),
+            tts_api_base_url: voices::TTS_API_BASE_URL.to_string(),
+        }
     }
 
     /// Get the Gemini API endpoint for image generation.
@@ -247,45 +833,56 @@ impl MultimodalHandler {
         )
     }
 
+    /// Get the Gemini API endpoint for image description.
+    pub fn get_describe_endpoint(&self, model: &str) -> String {
+        format!(
+            "https://{}-aiplatform.googleapis.com/v1/projects/{}/locations/{}/publishers/google/models/{}:generateContent",
+            self.config.location,
+            self.config.project_id,
+            self.config.location,
+            model
+        )
+    }
+
 
-    /// Generate an image from a text prompt using Gemini.
+    /// Generate an image from a text prompt using Gemini, or edit reference images.
     ///
     /// # Arguments
     /// * `params` - Image generation parameters
     ///
     /// # Returns
-    /// * `Ok(ImageGenerateResult)` - Generated image with data or path
-    /// * `Err(Error)` - If validation fails, API call fails, or output handling fails
+    /// * `Ok(GeneratedImageOutcome)` - Generated image with data or path, and whether it was an edit
+    /// * `Err(Error)` - If validation fails, a reference image cannot be read, API call fails,
+    ///   or output handling fails
     #[instrument(level = "info", name = "multimodal_generate_image", skip(self, params))]
     pub async fn generate_image(
         &self,
-        params: MultimodalImageParams,
-    ) -> Result<ImageGenerateResult, Error> {
+        mut params: MultimodalImageParams,
+    ) -> Result<GeneratedImageOutcome, Error> {
+        params.output_uri = self.resolve_output_uri(params.output_uri)?;
+
         // Validate parameters
         params.validate().map_err(|errors| {
             let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
             Error::validation(messages.join("; "))
         })?;
 
-        info!(model = %params.model, "Generating image with Gemini API");
+        // Resolve the model to get the canonical ID
+        let model = params.get_model().ok_or_else(|| {
+            Error::validation(ModelRegistry::unknown_model_message(GEMINI_MODELS, &params.model))
+        })?;
+
+        let references = self
+            .resolve_reference_images(params.reference_images.as_deref().unwrap_or_default())
+            .await?;
+        let mode = params.mode();
+
+        info!(model_id = model.id, ?mode, references = references.len(), "Generating image with Gemini API");
 
         // Build the API request
-        let request = GeminiImageRequest {
-            contents: vec![GeminiContent {
-                role: "user".to_string(),
-                parts: vec![GeminiPart::Text {
-                    text: format!("Generate an image of: {}", params.prompt),
-                }],
-            }],
-            generation_config: GeminiGenerationConfig {
-                response_modalities: vec!["TEXT".to_string(), "IMAGE".to_string()],
-                image_config: Some(GeminiImageConfig {
-                    aspect_ratio: "1:1".to_string(),
-                }),
-                temperature: None,
-                max_output_tokens: None,
-            },
-        };
+        let request = GeminiImageRequest::new(&params.prompt, references)
+            .with_settings(params.safety_settings.as_deref(), params.generation_config.as_ref());
+        let reference_images = request.reference_count();
 
         // Get auth token
         let token = self
@@ -294,7 +891,7 @@ impl MultimodalHandler {
             .await?;
 
         // Make API request
-        let endpoint = self.get_image_endpoint(&params.model);
+        let endpoint = self.get_image_endpoint(model.id);
         debug!(endpoint = %endpoint, "Calling Gemini API for image generation");
 
         let response = self
@@ -310,7 +907,7 @@ impl MultimodalHandler {
         let status = response.status();
         if !status.is_success() {
             let body = response.text().await.unwrap_or_default();
-            return Err(Error::api(&endpoint, status.as_u16(), body));
+            return Err(Error::api_response(&endpoint, status.as_u16(), body));
         }
 
         // Get raw response text for debugging
@@ -330,12 +927,48 @@ impl MultimodalHandler {
         })?;
 
         // Extract image from response
+        api_response.check_safety(&request.safety_settings)?;
         let image = self.extract_image_from_response(&api_response)?;
 
         info!("Received image from Gemini API");
 
         // Handle output based on params
-        self.handle_image_output(image, &params).await
+        let output = self.handle_image_output(image, &params).await?;
+        Ok(GeneratedImageOutcome {
+            output,
+            mode,
+            reference_images,
+        })
+    }
+
+    /// Resolve reference images to inline data, checking format and size.
+    async fn resolve_reference_images(&self, images: &[String]) -> Result<Vec<GeminiInlineData>, Error> {
+        let mut references = Vec::with_capacity(images.len());
+        for (i, image) in images.iter().enumerate() {
+            let data = self.resolve_image_input(image).await?;
+            let bytes = BASE64.decode(&data).map_err(|e| {
+                Error::validation(format!("reference_images[{}]: Invalid base64 image data: {}", i, e))
+            })?;
+            if bytes.len() > MAX_REFERENCE_IMAGE_BYTES {
+                return Err(Error::validation(format!(
+                    "reference_images[{}]: image is {} bytes, the limit is {} bytes",
+                    i,
+                    bytes.len(),
+                    MAX_REFERENCE_IMAGE_BYTES
+                )));
+            }
+            let mime_type = detect_image_mime_type(&bytes).ok_or_else(|| {
+                Error::validation(format!(
+                    "reference_images[{}]: Unsupported image format; expected PNG, JPEG, GIF, or WebP",
+                    i
+                ))
+            })?;
+            references.push(GeminiInlineData {
+                mime_type: mime_type.to_string(),
+                data,
+            });
+        }
+        Ok(references)
     }
 
     /// Synthesize speech from text using Gemini.
@@ -347,15 +980,30 @@ impl MultimodalHandler {
     /// * `Ok(TtsResult)` - Generated audio with data or path
     /// * `Err(Error)` - If validation fails, API call fails, or output handling fails
     #[instrument(level = "info", name = "multimodal_synthesize_speech", skip(self, params))]
-    pub async fn synthesize_speech(&self, params: MultimodalTtsParams) -> Result<TtsResult, Error> {
+    pub async fn synthesize_speech(&self, mut params: MultimodalTtsParams) -> Result<TtsResult, Error> {
+        params.output_uri = self.resolve_output_uri(params.output_uri)?;
+
+        // Voices newer than the built-in table are checked against the API's list
+        let extra_voices: Vec<String> = match params.voice.as_deref() {
+            Some(voice) if !AVAILABLE_VOICES.contains(&voice) => {
+                self.list_voices(false).await.voices.into_iter().map(|v| v.name).collect()
+            }
+            _ => Vec::new(),
+        };
+
         // Validate parameters
-        params.validate().map_err(|errors| {
+        params.validate_with_voices(&extra_voices).map_err(|errors| {
             let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
             Error::validation(messages.join("; "))
         })?;
 
+        // Resolve the model to get the canonical ID
+        let model = params.get_model().ok_or_else(|| {
+            Error::validation(ModelRegistry::unknown_model_message(GEMINI_MODELS, &params.model))
+        })?;
+
         let voice = params.get_voice();
-        info!(voice = %voice, model = %params.model, "Synthesizing speech with Gemini API");
+        info!(voice = %voice, model_id = model.id, "Synthesizing speech with Gemini API");
 
         // Build the prompt with style if provided
         let prompt = if let Some(ref style) = params.style {
@@ -382,8 +1030,11 @@ impl MultimodalHandler {
                         },
                     },
                 },
+                sampling: GeminiSamplingConfig::default(),
             },
-        };
+            safety_settings: Vec::new(),
+        }
+        .with_settings(params.safety_settings.as_deref(), params.generation_config.as_ref());
 
         // Get auth token
         let token = self
@@ -392,7 +1043,7 @@ impl MultimodalHandler {
             .await?;
 
         // Make API request
-        let endpoint = self.get_tts_endpoint(&params.model);
+        let endpoint = self.get_tts_endpoint(model.id);
         debug!(endpoint = %endpoint, "Calling Gemini API for TTS");
 
         let response = self
@@ -408,7 +1059,7 @@ impl MultimodalHandler {
         let status = response.status();
         if !status.is_success() {
             let body = response.text().await.unwrap_or_default();
-            return Err(Error::api(&endpoint, status.as_u16(), body));
+            return Err(Error::api_response(&endpoint, status.as_u16(), body));
         }
 
         // Get raw response text for debugging
@@ -428,6 +1079,7 @@ impl MultimodalHandler {
         })?;
 
         // Extract audio from response
+        api_response.check_safety(&request.safety_settings)?;
         let audio = self.extract_audio_from_response(&api_response)?;
 
         info!("Received audio from Gemini API");
@@ -436,26 +1088,218 @@ impl MultimodalHandler {
         self.handle_audio_output(audio, &params).await
     }
 
-    /// List available voices.
-    pub fn list_voices(&self) -> Vec<VoiceInfo> {
-        AVAILABLE_VOICES
-            .iter()
-            .map(|&name| VoiceInfo {
-                name: name.to_string(),
-                description: format!("Gemini TTS voice: {}", name),
-            })
-            .collect()
-    }
+    /// Describe an image using Gemini.
+    ///
+    /// # Arguments
+    /// * `params` - Image description parameters
+    ///
+    /// # Returns
+    /// * `Ok(ImageDescribeResult)` - Text description and detected MIME type
+    /// * `Err(Error)` - If validation fails, the image cannot be read, or the API call fails
+    #[instrument(level = "info", name = "multimodal_describe_image", skip(self, params))]
+    pub async fn describe_image(
+        &self,
+        params: MultimodalDescribeParams,
+    ) -> Result<ImageDescribeResult, Error> {
+        // Validate parameters
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
 
-    /// List supported language codes.
-    pub fn list_language_codes(&self) -> Vec<LanguageCodeInfo> {
-        SUPPORTED_LANGUAGE_CODES
-            .iter()
-            .map(|&(code, name)| LanguageCodeInfo {
-                code: code.to_string(),
-                name: name.to_string(),
+        // Resolve the image and detect its format
+        let image_data = self.resolve_image_input(&params.image).await?;
+        let bytes = BASE64
+            .decode(&image_data)
+            .map_err(|e| Error::validation(format!("Invalid base64 image data: {}", e)))?;
+        let mime_type = detect_image_mime_type(&bytes).ok_or_else(|| {
+            Error::validation("Unsupported image format; expected PNG, JPEG, GIF, or WebP")
+        })?;
+
+        info!(model = %params.model, mime_type, "Describing image with Gemini API");
+
+        let request = GeminiDescribeRequest::new(
+            params.get_question(),
+            mime_type,
+            image_data,
+            params.max_output_tokens,
+        )
+        .with_settings(params.safety_settings.as_deref(), params.generation_config.as_ref());
+
+        let api_response = self.generate_text(&params.model, &request).await?;
+        let (description, truncated) = self.extract_text_from_response(&api_response)?;
+
+        info!(chars = description.len(), truncated, "Received image description from Gemini API");
+
+        Ok(ImageDescribeResult {
+            description,
+            mime_type: mime_type.to_string(),
+            model: params.model,
+            truncated,
+        })
+    }
+
+    /// Answer a question about a video, or summarize it, using Gemini.
+    ///
+    /// GCS videos are referenced by URI; local files are sent inline and must
+    /// be at most [`MAX_INLINE_VIDEO_BYTES`].
+    ///
+    /// # Arguments
+    /// * `params` - Video analysis parameters
+    ///
+    /// # Returns
+    /// * `Ok(VideoAnalysisResult)` - Text answer and token usage
+    /// * `Err(Error)` - If validation fails, a local video is missing or too
+    ///   large to inline, or the API call fails
+    #[instrument(level = "info", name = "multimodal_analyze_video", skip(self, params))]
+    pub async fn analyze_video(
+        &self,
+        params: MultimodalAnalyzeVideoParams,
+    ) -> Result<VideoAnalysisResult, Error> {
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        let mime_type = video_mime_type(&params.video)
+            .ok_or_else(|| Error::validation(format!("Unsupported video type for '{}'", params.video)))?;
+        let video = self.resolve_video_part(&params.video, mime_type).await?;
+
+        info!(model = %params.model, mime_type, "Analyzing video with Gemini API");
+
+        let request = GeminiDescribeRequest::with_media(
+            video,
+            params.get_question(),
+            params.max_output_tokens,
+        )
+        .with_settings(params.safety_settings.as_deref(), params.generation_config.as_ref());
+        let api_response = self.generate_text(&params.model, &request).await?;
+        let (answer, truncated) = self.extract_text_from_response(&api_response)?;
+        let usage = api_response.usage_metadata.as_ref().map(TokenUsage::from);
+
+        info!(chars = answer.len(), truncated, usage = ?usage, "Received video analysis from Gemini API");
+
+        Ok(VideoAnalysisResult {
+            answer,
+            mime_type: mime_type.to_string(),
+            model: params.model,
+            truncated,
+            usage,
+        })
+    }
+
+    /// Build the request part for a video: a `fileData` reference for GCS
+    /// URIs, or inline base64 data for small local files.
+    async fn resolve_video_part(&self, video: &str, mime_type: &str) -> Result<GeminiPart, Error> {
+        if video.starts_with("gs://") {
+            return Ok(GeminiPart::FileData {
+                file_data: GeminiFileData {
+                    mime_type: mime_type.to_string(),
+                    file_uri: video.to_string(),
+                },
+            });
+        }
+
+        let metadata = tokio::fs::metadata(video)
+            .await
+            .map_err(|_| Error::validation(format!("Video file not found: {}", video)))?;
+        if metadata.len() > MAX_INLINE_VIDEO_BYTES {
+            return Err(Error::validation(format!(
+                "Video '{}' is {} bytes, above the {} byte limit for local files; \
+                 upload it to GCS (e.g. with storage_upload) and pass the gs:// URI instead",
+                video,
+                metadata.len(),
+                MAX_INLINE_VIDEO_BYTES
+            )));
+        }
+
+        let data = tokio::fs::read(video).await?;
+        Ok(GeminiPart::InlineData {
+            inline_data: GeminiInlineData {
+                mime_type: mime_type.to_string(),
+                data: BASE64.encode(data),
+            },
+        })
+    }
+
+    /// Send a text-output request to the Gemini `generateContent` endpoint.
+    ///
+    /// Responses blocked by the safety filters become [`Error::SafetyBlocked`].
+    async fn generate_text(&self, model: &str, request: &GeminiDescribeRequest) -> Result<GeminiResponse, Error> {
+        let token = self
+            .auth
+            .get_token(&["https://www.googleapis.com/auth/cloud-platform"])
+            .await?;
+
+        let endpoint = self.get_describe_endpoint(model);
+        debug!(endpoint = %endpoint, "Calling Gemini API for text output");
+
+        let response = self
+            .http
+            .post(&endpoint)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| Error::api(&endpoint, 0, format!("Request failed: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::api_response(&endpoint, status.as_u16(), body));
+        }
+
+        let api_response: GeminiResponse = response.json().await.map_err(|e| {
+            Error::api(&endpoint, status.as_u16(), format!("Failed to parse response: {}", e))
+        })?;
+        api_response.check_safety(&request.safety_settings)?;
+        Ok(api_response)
+    }
+
+    /// The Cloud TTS voice list, cached for the voice cache's TTL; `refresh`
+    /// fetches it again. `None` when the API is unavailable.
+    async fn tts_voices(&self, refresh: bool) -> Option<Arc<Vec<TtsVoice>>> {
+        self.voices
+            .get(refresh, || {
+                voices::fetch_voices(&self.http, &self.auth, &self.tts_api_base_url, &self.config.project_id)
             })
-            .collect()
+            .await
+    }
+
+    /// List available voices.
+    ///
+    /// Gemini TTS voices share their names with the Chirp3-HD voices listed by
+    /// the Cloud TTS API. When that API is unavailable, [`AVAILABLE_VOICES`]
+    /// are returned.
+    pub async fn list_voices(&self, refresh: bool) -> VoiceList {
+        match self.tts_voices(refresh).await {
+            Some(all) => VoiceList {
+                voices: gemini_voices(&all),
+                source: VoiceSource::Api,
+            },
+            None => VoiceList {
+                voices: fallback_voices(),
+                source: VoiceSource::Fallback,
+            },
+        }
+    }
+
+    /// List supported language codes.
+    ///
+    /// Codes come from the Chirp3-HD voices listed by the Cloud TTS API, or
+    /// from [`SUPPORTED_LANGUAGE_CODES`] when it is unavailable.
+    pub async fn list_language_codes(&self, refresh: bool) -> LanguageCodeList {
+        match self.tts_voices(refresh).await {
+            Some(all) => LanguageCodeList {
+                language_codes: gemini_language_codes(&all),
+                source: VoiceSource::Api,
+            },
+            None => LanguageCodeList {
+                language_codes: fallback_language_codes(),
+                source: VoiceSource::Fallback,
+            },
+        }
     }
 
     /// Extract image data from Gemini response.
@@ -483,6 +1327,41 @@ impl MultimodalHandler {
         ))
     }
 
+    /// Extract text from a Gemini response.
+    ///
+    /// Joins the text parts of the first candidate that has any, and reports
+    /// whether generation stopped at the output token limit.
+    fn extract_text_from_response(&self, response: &GeminiResponse) -> Result<(String, bool), Error> {
+        for candidate in &response.candidates {
+            let Some(ref content) = candidate.content else {
+                continue;
+            };
+            let text: String = content
+                .parts
+                .iter()
+                .filter_map(|part| match part {
+                    GeminiResponsePart::Text { text } => Some(text.as_str()),
+                    GeminiResponsePart::InlineData { .. } => None,
+                })
+                .collect();
+            if !text.trim().is_empty() {
+                let truncated = candidate.finish_reason.as_deref() == Some("MAX_TOKENS");
+                return Ok((text.trim().to_string(), truncated));
+            }
+        }
+
+        Err(Error::api(
+            "gemini",
+            200,
+            "No text found in response".to_string(),
+        ))
+    }
+
+    /// Resolve image input (base64, data URI, local path, GCS URI, or HTTPS URL) to base64 data.
+    async fn resolve_image_input(&self, image: &str) -> Result<String, Error> {
+        media_input::resolve_image_input(image, &self.gcs, &self.http).await
+    }
+
     /// Extract audio data from Gemini response.
     fn extract_audio_from_response(
         &self,
@@ -514,6 +1393,13 @@ impl MultimodalHandler {
         image: GeneratedImage,
         params: &MultimodalImageParams,
     ) -> Result<ImageGenerateResult, Error> {
+        // If output_uri is specified, upload to GCS
+        if let Some(output_uri) = &params.output_uri {
+            self.upload_to_gcs(&image.data, &image.mime_type, output_uri).await?;
+            info!(uri = %output_uri, "Uploaded image to GCS");
+            return Ok(ImageGenerateResult::GcsUri(output_uri.clone()));
+        }
+
         // If output_file is specified, save to local file
         if let Some(output_file) = &params.output_file {
             return self.save_image_to_file(image, output_file).await;
@@ -529,6 +1415,13 @@ impl MultimodalHandler {
         audio: GeneratedAudio,
         params: &MultimodalTtsParams,
     ) -> Result<TtsResult, Error> {
+        // If output_uri is specified, upload to GCS
+        if let Some(output_uri) = &params.output_uri {
+            self.upload_to_gcs(&audio.data, &audio.mime_type, output_uri).await?;
+            info!(uri = %output_uri, "Uploaded audio to GCS");
+            return Ok(TtsResult::GcsUri(output_uri.clone()));
+        }
+
         // If output_file is specified, save to local file
         if let Some(output_file) = &params.output_file {
             return self.save_audio_to_file(audio, output_file).await;
@@ -538,12 +1431,34 @@ impl MultimodalHandler {
         Ok(TtsResult::Base64(audio))
     }
 
+    /// Expand a bare object name in `output_uri` to a URI in the configured bucket.
+    fn resolve_output_uri(&self, output_uri: Option<String>) -> Result<Option<String>, Error> {
+        output_uri
+            .map(|uri| self.config.resolve_output_uri(&uri))
+            .transpose()
+    }
+
+    /// Decode base64 data and upload it to GCS.
+    async fn upload_to_gcs(&self, data: &str, mime_type: &str, output_uri: &str) -> Result<(), Error> {
+        let data = BASE64
+            .decode(data)
+            .map_err(|e| Error::validation(format!("Invalid base64 data: {}", e)))?;
+
+        let gcs_uri = GcsUri::parse(output_uri)?;
+        self.gcs.upload(&gcs_uri, &data, mime_type).await?;
+        Ok(())
+    }
+
     /// Save image to local file.
     async fn save_image_to_file(
         &self,
         image: GeneratedImage,
         output_file: &str,
     ) -> Result<ImageGenerateResult, Error> {
+        let output_file = self.config.resolve_local_path(output_file);
+        let output_file = output_file.to_string_lossy();
+        let output_file = output_file.as_ref();
+
         // Decode base64 data
         let data = BASE64
             .decode(&image.data)
@@ -569,6 +1484,10 @@ impl MultimodalHandler {
         audio: GeneratedAudio,
         output_file: &str,
     ) -> Result<TtsResult, Error> {
+        let output_file = self.config.resolve_local_path(output_file);
+        let output_file = output_file.to_string_lossy();
+        let output_file = output_file.as_ref();
+
         // Decode base64 data
         let data = BASE64
             .decode(&audio.data)
@@ -602,6 +1521,59 @@ pub struct GeminiImageRequest {
     pub contents: Vec<GeminiContent>,
     /// Generation configuration
     pub generation_config: GeminiGenerationConfig,
+    /// Safety filter thresholds
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub safety_settings: Vec<SafetySetting>,
+}
+
+impl GeminiImageRequest {
+    /// Build a request generating an image from `prompt`.
+    ///
+    /// Reference images are sent as inline data parts ahead of the prompt,
+    /// which is then passed through as the edit instruction.
+    pub fn new(prompt: &str, references: Vec<GeminiInlineData>) -> Self {
+        let text = if references.is_empty() {
+            format!("Generate an image of: {}", prompt)
+        } else {
+            prompt.to_string()
+        };
+        let mut parts: Vec<GeminiPart> = references
+            .into_iter()
+            .map(|inline_data| GeminiPart::InlineData { inline_data })
+            .collect();
+        parts.push(GeminiPart::Text { text });
+
+        Self {
+            contents: vec![GeminiContent {
+                role: "user".to_string(),
+                parts,
+            }],
+            generation_config: GeminiGenerationConfig {
+                response_modalities: vec!["TEXT".to_string(), "IMAGE".to_string()],
+                image_config: Some(GeminiImageConfig {
+                    aspect_ratio: "1:1".to_string(),
+                }),
+                sampling: GeminiSamplingConfig::default(),
+            },
+            safety_settings: Vec::new(),
+        }
+    }
+
+    /// Apply caller-supplied safety settings and sampling options.
+    pub fn with_settings(mut self, safety_settings: Option<&[SafetySetting]>, config: Option<&GenerationConfig>) -> Self {
+        self.safety_settings = safety_settings.unwrap_or_default().to_vec();
+        self.generation_config.sampling.apply(config);
+        self
+    }
+
+    /// Number of reference images in the request.
+    pub fn reference_count(&self) -> usize {
+        self.contents
+            .iter()
+            .flat_map(|content| &content.parts)
+            .filter(|part| matches!(part, GeminiPart::InlineData { .. }))
+            .count()
+    }
 }
 
 /// Gemini API request for TTS.
@@ -612,6 +1584,18 @@ pub struct GeminiTtsRequest {
     pub contents: Vec<GeminiContent>,
     /// Generation configuration
     pub generation_config: GeminiTtsGenerationConfig,
+    /// Safety filter thresholds
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub safety_settings: Vec<SafetySetting>,
+}
+
+impl GeminiTtsRequest {
+    /// Apply caller-supplied safety settings and sampling options.
+    pub fn with_settings(mut self, safety_settings: Option<&[SafetySetting]>, config: Option<&GenerationConfig>) -> Self {
+        self.safety_settings = safety_settings.unwrap_or_default().to_vec();
+        self.generation_config.sampling.apply(config);
+        self
+    }
 }
 
 /// Gemini content structure.
@@ -623,12 +1607,88 @@ pub struct GeminiContent {
     pub parts: Vec<GeminiPart>,
 }
 
+/// Gemini API request for text output about media (image description, video analysis).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiDescribeRequest {
+    /// Content parts (media and question)
+    pub contents: Vec<GeminiContent>,
+    /// Generation configuration
+    pub generation_config: GeminiTextGenerationConfig,
+    /// Safety filter thresholds
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub safety_settings: Vec<SafetySetting>,
+}
+
+impl GeminiDescribeRequest {
+    /// Build a request asking `question` about a base64-encoded image.
+    pub fn new(question: &str, mime_type: &str, data: String, max_output_tokens: Option<u32>) -> Self {
+        let image = GeminiPart::InlineData {
+            inline_data: GeminiInlineData {
+                mime_type: mime_type.to_string(),
+                data,
+            },
+        };
+        Self::with_media(image, question, max_output_tokens)
+    }
+
+    /// Build a request asking `question` about a media part, which comes first.
+    pub fn with_media(media: GeminiPart, question: &str, max_output_tokens: Option<u32>) -> Self {
+        Self {
+            contents: vec![GeminiContent {
+                role: "user".to_string(),
+                parts: vec![
+                    media,
+                    GeminiPart::Text {
+                        text: question.to_string(),
+                    },
+                ],
+            }],
+            generation_config: GeminiTextGenerationConfig {
+                response_modalities: vec!["TEXT".to_string()],
+                sampling: GeminiSamplingConfig {
+                    max_output_tokens,
+                    ..Default::default()
+                },
+            },
+            safety_settings: Vec::new(),
+        }
+    }
+
+    /// Apply caller-supplied safety settings and sampling options.
+    pub fn with_settings(mut self, safety_settings: Option<&[SafetySetting]>, config: Option<&GenerationConfig>) -> Self {
+        self.safety_settings = safety_settings.unwrap_or_default().to_vec();
+        self.generation_config.sampling.apply(config);
+        self
+    }
+}
+
 /// Gemini content part (request).
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum GeminiPart {
     /// Text content
     Text { text: String },
+    /// Inline data (e.g. an input image)
+    InlineData {
+        #[serde(rename = "inlineData")]
+        inline_data: GeminiInlineData,
+    },
+    /// Reference to a file in GCS (e.g. an input video)
+    FileData {
+        #[serde(rename = "fileData")]
+        file_data: GeminiFileData,
+    },
+}
+
+/// Gemini file reference by URI.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiFileData {
+    /// MIME type
+    pub mime_type: String,
+    /// GCS URI of the file
+    pub file_uri: String,
 }
 
 /// Gemini generation config for image generation.
@@ -640,12 +1700,44 @@ pub struct GeminiGenerationConfig {
     /// Image configuration
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image_config: Option<GeminiImageConfig>,
+    /// Sampling options
+    #[serde(flatten)]
+    pub sampling: GeminiSamplingConfig,
+}
+
+/// Gemini sampling options shared by every generation config.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiSamplingConfig {
     /// Temperature for generation
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
+    /// Nucleus sampling probability mass
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Top-k sampling
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<u32>,
     /// Max output tokens
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_output_tokens: Option<u32>,
+    /// Sampling seed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i32>,
+}
+
+impl GeminiSamplingConfig {
+    /// Overlay the options set in `config`, keeping current values for the rest.
+    pub fn apply(&mut self, config: Option<&GenerationConfig>) {
+        let Some(config) = config else {
+            return;
+        };
+        self.temperature = config.temperature.or(self.temperature);
+        self.top_p = config.top_p.or(self.top_p);
+        self.top_k = config.top_k.or(self.top_k);
+        self.max_output_tokens = config.max_output_tokens.or(self.max_output_tokens);
+        self.seed = config.seed.or(self.seed);
+    }
 }
 
 /// Gemini image configuration.
@@ -656,6 +1748,17 @@ pub struct GeminiImageConfig {
     pub aspect_ratio: String,
 }
 
+/// Gemini generation config for text output.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiTextGenerationConfig {
+    /// Response modalities (TEXT)
+    pub response_modalities: Vec<String>,
+    /// Sampling options
+    #[serde(flatten)]
+    pub sampling: GeminiSamplingConfig,
+}
+
 /// Gemini generation config for TTS.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -664,6 +1767,9 @@ pub struct GeminiTtsGenerationConfig {
     pub response_modalities: Vec<String>,
     /// Speech configuration
     pub speech_config: GeminiSpeechConfig,
+    /// Sampling options
+    #[serde(flatten)]
+    pub sampling: GeminiSamplingConfig,
 }
 
 /// Gemini speech configuration.
@@ -697,6 +1803,117 @@ pub struct GeminiResponse {
     /// Response candidates
     #[serde(default)]
     pub candidates: Vec<GeminiCandidate>,
+    /// Feedback on the prompt, set when it was blocked
+    #[serde(default)]
+    pub prompt_feedback: Option<GeminiPromptFeedback>,
+    /// Token counts for the request
+    #[serde(default)]
+    pub usage_metadata: Option<GeminiUsageMetadata>,
+}
+
+impl GeminiResponse {
+    /// Why the prompt or the first candidate was blocked on safety grounds, if it was.
+    ///
+    /// The category is the rating the API marked as blocked, or else the one
+    /// with the highest harm probability; the threshold is looked up in the
+    /// request's `safety_settings`.
+    pub fn safety_block(&self, safety_settings: &[SafetySetting]) -> Option<SafetyBlock> {
+        let (reason, ratings) = match self.prompt_feedback {
+            Some(GeminiPromptFeedback {
+                block_reason: Some(ref reason),
+                ref safety_ratings,
+            }) => (reason, safety_ratings),
+            _ => {
+                let candidate = self.candidates.first()?;
+                let reason = candidate
+                    .finish_reason
+                    .as_ref()
+                    .filter(|reason| SAFETY_FINISH_REASONS.contains(&reason.as_str()))?;
+                (reason, &candidate.safety_ratings)
+            }
+        };
+
+        let rating = ratings.iter().find(|r| r.blocked).or_else(|| {
+            ratings
+                .iter()
+                .filter(|r| r.probability_rank() > 0)
+                .max_by_key(|r| r.probability_rank())
+        });
+        let threshold = rating.and_then(|r| {
+            safety_settings
+                .iter()
+                .find(|s| s.category == r.category)
+                .map(|s| s.threshold.clone())
+        });
+
+        Some(SafetyBlock {
+            reason: reason.clone(),
+            category: rating.map(|r| r.category.clone()),
+            threshold,
+            probability: rating.and_then(|r| r.probability.clone()),
+        })
+    }
+
+    /// Fail with [`Error::SafetyBlocked`] if the response was blocked.
+    pub fn check_safety(&self, safety_settings: &[SafetySetting]) -> Result<(), Error> {
+        match self.safety_block(safety_settings) {
+            Some(block) => Err(Error::SafetyBlocked(block)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Gemini feedback on the prompt.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiPromptFeedback {
+    /// Why the prompt was blocked (e.g. "SAFETY", "PROHIBITED_CONTENT")
+    #[serde(default)]
+    pub block_reason: Option<String>,
+    /// Safety ratings of the prompt
+    #[serde(default)]
+    pub safety_ratings: Vec<GeminiSafetyRating>,
+}
+
+/// Gemini safety rating for one harm category.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiSafetyRating {
+    /// Harm category
+    pub category: String,
+    /// Harm probability (NEGLIGIBLE, LOW, MEDIUM, HIGH)
+    #[serde(default)]
+    pub probability: Option<String>,
+    /// Whether this rating caused the block
+    #[serde(default)]
+    pub blocked: bool,
+}
+
+impl GeminiSafetyRating {
+    /// Ordering of the harm probability; 0 for NEGLIGIBLE or unknown.
+    fn probability_rank(&self) -> u8 {
+        match self.probability.as_deref() {
+            Some("LOW") => 1,
+            Some("MEDIUM") => 2,
+            Some("HIGH") => 3,
+            _ => 0,
+        }
+    }
+}
+
+/// Gemini token usage metadata.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeminiUsageMetadata {
+    /// Tokens in the prompt, including media
+    #[serde(default)]
+    pub prompt_token_count: u32,
+    /// Tokens in the generated candidates
+    #[serde(default)]
+    pub candidates_token_count: u32,
+    /// Total tokens billed
+    #[serde(default)]
+    pub total_token_count: u32,
 }
 
 /// Gemini response candidate.
@@ -705,6 +1922,12 @@ pub struct GeminiResponse {
 pub struct GeminiCandidate {
     /// Content
     pub content: Option<GeminiResponseContent>,
+    /// Why generation stopped (e.g. "STOP", "MAX_TOKENS", "SAFETY")
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+    /// Safety ratings of the candidate
+    #[serde(default)]
+    pub safety_ratings: Vec<GeminiSafetyRating>,
 }
 
 /// Gemini response content.
@@ -728,7 +1951,7 @@ pub enum GeminiResponsePart {
 }
 
 /// Gemini inline data (base64 encoded).
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GeminiInlineData {
     /// MIME type
@@ -766,6 +1989,29 @@ pub enum ImageGenerateResult {
     Base64(GeneratedImage),
     /// Local file path (when output_file specified)
     LocalFile(String),
+    /// GCS URI (when output_uri specified)
+    GcsUri(String),
+}
+
+/// Kind of image request performed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageMode {
+    /// Generated from the text prompt alone
+    Generate,
+    /// Edited or varied from reference images
+    Edit,
+}
+
+/// Outcome of an image generation call.
+#[derive(Debug)]
+pub struct GeneratedImageOutcome {
+    /// Where the image was delivered
+    pub output: ImageGenerateResult,
+    /// Whether reference images were edited or the image was generated from text
+    pub mode: ImageMode,
+    /// Number of reference images sent
+    pub reference_images: usize,
 }
 
 /// Result of TTS synthesis.
@@ -775,6 +2021,58 @@ pub enum TtsResult {
     Base64(GeneratedAudio),
     /// Local file path (when output_file specified)
     LocalFile(String),
+    /// GCS URI (when output_uri specified)
+    GcsUri(String),
+}
+
+/// Result of image description.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageDescribeResult {
+    /// Text description returned by the model
+    pub description: String,
+    /// Detected MIME type of the input image
+    pub mime_type: String,
+    /// Model used for the description
+    pub model: String,
+    /// Whether the description was cut off by `max_output_tokens`
+    pub truncated: bool,
+}
+
+/// Token usage reported by the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TokenUsage {
+    /// Tokens in the prompt, including the video
+    pub prompt_tokens: u32,
+    /// Tokens in the answer
+    pub output_tokens: u32,
+    /// Total tokens
+    pub total_tokens: u32,
+}
+
+impl From<&GeminiUsageMetadata> for TokenUsage {
+    fn from(usage: &GeminiUsageMetadata) -> Self {
+        Self {
+            prompt_tokens: usage.prompt_token_count,
+            output_tokens: usage.candidates_token_count,
+            total_tokens: usage.total_token_count,
+        }
+    }
+}
+
+/// Result of video analysis.
+#[derive(Debug, Clone, Serialize)]
+pub struct VideoAnalysisResult {
+    /// Text answer returned by the model
+    pub answer: String,
+    /// MIME type of the input video
+    pub mime_type: String,
+    /// Model used for the analysis
+    pub model: String,
+    /// Whether the answer was cut off by `max_output_tokens`
+    pub truncated: bool,
+    /// Token usage, when the API reports it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
 }
 
 /// Voice information.
@@ -795,6 +2093,78 @@ pub struct LanguageCodeInfo {
     pub name: String,
 }
 
+/// Available voices and where the list came from.
+#[derive(Debug, Clone, Serialize)]
+pub struct VoiceList {
+    /// Gemini TTS voices
+    pub voices: Vec<VoiceInfo>,
+    /// Whether the list came from the API or the built-in table
+    pub source: VoiceSource,
+}
+
+/// Supported language codes and where the list came from.
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageCodeList {
+    /// Language codes
+    pub language_codes: Vec<LanguageCodeInfo>,
+    /// Whether the list came from the API or the built-in table
+    pub source: VoiceSource,
+}
+
+fn voice_info(name: &str) -> VoiceInfo {
+    VoiceInfo {
+        name: name.to_string(),
+        description: format!("Gemini TTS voice: {}", name),
+    }
+}
+
+/// Gemini voice names among `voices`: the Chirp3-HD short names, sorted.
+pub fn gemini_voices(voices: &[TtsVoice]) -> Vec<VoiceInfo> {
+    let mut names: Vec<&str> = voices.iter().filter_map(TtsVoice::chirp3_hd_name).collect();
+    names.sort_unstable();
+    names.dedup();
+    names.into_iter().map(voice_info).collect()
+}
+
+/// Language codes of the Chirp3-HD voices among `voices`, sorted. Codes
+/// missing from [`SUPPORTED_LANGUAGE_CODES`] are named by their code.
+pub fn gemini_language_codes(voices: &[TtsVoice]) -> Vec<LanguageCodeInfo> {
+    let mut codes: Vec<&str> = voices
+        .iter()
+        .filter(|v| v.chirp3_hd_name().is_some())
+        .flat_map(|v| v.language_codes.iter().map(String::as_str))
+        .collect();
+    codes.sort_unstable();
+    codes.dedup();
+    codes
+        .into_iter()
+        .map(|code| LanguageCodeInfo {
+            code: code.to_string(),
+            name: SUPPORTED_LANGUAGE_CODES
+                .iter()
+                .find(|(known, _)| *known == code)
+                .map_or(code, |(_, name)| name)
+                .to_string(),
+        })
+        .collect()
+}
+
+/// The built-in voices, [`AVAILABLE_VOICES`].
+pub fn fallback_voices() -> Vec<VoiceInfo> {
+    AVAILABLE_VOICES.iter().map(|name| voice_info(name)).collect()
+}
+
+/// The built-in language codes, [`SUPPORTED_LANGUAGE_CODES`].
+pub fn fallback_language_codes() -> Vec<LanguageCodeInfo> {
+    SUPPORTED_LANGUAGE_CODES
+        .iter()
+        .map(|&(code, name)| LanguageCodeInfo {
+            code: code.to_string(),
+            name: name.to_string(),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -812,7 +2182,11 @@ mod tests {
         let params = MultimodalImageParams {
             prompt: "A beautiful sunset".to_string(),
             model: DEFAULT_IMAGE_MODEL.to_string(),
+            reference_images: None,
             output_file: None,
+            output_uri: None,
+            safety_settings: None,
+            generation_config: None,
         };
 
         assert!(params.validate().is_ok());
@@ -823,7 +2197,11 @@ mod tests {
         let params = MultimodalImageParams {
             prompt: "   ".to_string(),
             model: DEFAULT_IMAGE_MODEL.to_string(),
+            reference_images: None,
             output_file: None,
+            output_uri: None,
+            safety_settings: None,
+            generation_config: None,
         };
 
         let result = params.validate();
@@ -832,6 +2210,37 @@ mod tests {
         assert!(errors.iter().any(|e| e.field == "prompt"));
     }
 
+    #[test]
+    fn test_image_model_alias_and_capability() {
+        let mut params: MultimodalImageParams =
+            serde_json::from_str(r#"{"prompt": "A red fox", "model": "flash-image"}"#).unwrap();
+        assert!(params.validate().is_ok());
+        assert_eq!(params.get_model().unwrap().id, DEFAULT_IMAGE_MODEL);
+
+        // A known model without image output names the models that have it
+        params.model = "pro".to_string();
+        let errors = params.validate().unwrap_err();
+        let error = errors.iter().find(|e| e.field == "model").unwrap();
+        assert!(error.message.contains("does not support image generation"));
+        assert!(error.message.contains(DEFAULT_IMAGE_MODEL));
+
+        params.model = "gemini-9-ultra".to_string();
+        let errors = params.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "model" && e.message.contains("Unknown model")));
+    }
+
+    #[test]
+    fn test_tts_model_alias_and_capability() {
+        let mut params: MultimodalTtsParams =
+            serde_json::from_str(r#"{"text": "Hello", "model": "pro-tts"}"#).unwrap();
+        assert!(params.validate().is_ok());
+        assert_eq!(params.get_model().unwrap().id, "gemini-2.5-pro-preview-tts");
+
+        params.model = DEFAULT_IMAGE_MODEL.to_string();
+        let errors = params.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "model" && e.message.contains("does not support text-to-speech")));
+    }
+
     #[test]
     fn test_default_tts_params() {
         let params: MultimodalTtsParams =
@@ -850,6 +2259,9 @@ mod tests {
             style: Some("cheerful".to_string()),
             model: DEFAULT_TTS_MODEL.to_string(),
             output_file: None,
+            output_uri: None,
+            safety_settings: None,
+            generation_config: None,
         };
 
         assert!(params.validate().is_ok());
@@ -863,6 +2275,9 @@ mod tests {
             style: None,
             model: DEFAULT_TTS_MODEL.to_string(),
             output_file: None,
+            output_uri: None,
+            safety_settings: None,
+            generation_config: None,
         };
 
         let result = params.validate();
@@ -879,6 +2294,9 @@ mod tests {
             style: None,
             model: DEFAULT_TTS_MODEL.to_string(),
             output_file: None,
+            output_uri: None,
+            safety_settings: None,
+            generation_config: None,
         };
 
         let result = params.validate();
@@ -887,6 +2305,51 @@ mod tests {
         assert!(errors.iter().any(|e| e.field == "voice"));
     }
 
+    #[test]
+    fn test_voice_listed_by_api_is_valid() {
+        let params = MultimodalTtsParams {
+            voice: Some("Sulafat".to_string()),
+            ..serde_json::from_str(r#"{"text": "Hello"}"#).unwrap()
+        };
+        assert!(params.validate().is_err());
+        assert!(params.validate_with_voices(&["Kore".to_string(), "Sulafat".to_string()]).is_ok());
+
+        let errors = params.validate_with_voices(&["Achird".to_string()]).unwrap_err();
+        assert!(errors[0].message.ends_with("Aoede, Achird"), "{}", errors[0].message);
+    }
+
+    fn tts_voice(name: &str, language_codes: &[&str]) -> TtsVoice {
+        TtsVoice {
+            name: name.to_string(),
+            language_codes: language_codes.iter().map(|c| c.to_string()).collect(),
+            ssml_gender: None,
+            natural_sample_rate_hertz: None,
+        }
+    }
+
+    #[test]
+    fn test_gemini_voices_and_language_codes_from_api_list() {
+        let listed = vec![
+            tts_voice("en-US-Chirp3-HD-Sulafat", &["en-US"]),
+            tts_voice("de-DE-Chirp3-HD-Sulafat", &["de-DE"]),
+            tts_voice("sw-KE-Chirp3-HD-Kore", &["sw-KE"]),
+            tts_voice("fr-FR-Neural2-A", &["fr-FR"]),
+        ];
+
+        let voices = gemini_voices(&listed);
+        let names: Vec<&str> = voices.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, ["Kore", "Sulafat"]);
+        assert_eq!(voices[1].description, "Gemini TTS voice: Sulafat");
+
+        let codes = gemini_language_codes(&listed);
+        let pairs: Vec<(&str, &str)> = codes.iter().map(|c| (c.code.as_str(), c.name.as_str())).collect();
+        // Non-Chirp3-HD voices are skipped; unknown codes are named by their code
+        assert_eq!(
+            pairs,
+            [("de-DE", "German (Germany)"), ("en-US", "English (US)"), ("sw-KE", "sw-KE")]
+        );
+    }
+
     #[test]
     fn test_invalid_style() {
         let params = MultimodalTtsParams {
@@ -895,6 +2358,9 @@ mod tests {
             style: Some("invalid_style".to_string()),
             model: DEFAULT_TTS_MODEL.to_string(),
             output_file: None,
+            output_uri: None,
+            safety_settings: None,
+            generation_config: None,
         };
 
         let result = params.validate();
@@ -911,6 +2377,9 @@ mod tests {
             style: None,
             model: DEFAULT_TTS_MODEL.to_string(),
             output_file: None,
+            output_uri: None,
+            safety_settings: None,
+            generation_config: None,
         };
 
         assert_eq!(params.get_voice(), DEFAULT_VOICE);
@@ -924,6 +2393,9 @@ mod tests {
             style: None,
             model: DEFAULT_TTS_MODEL.to_string(),
             output_file: None,
+            output_uri: None,
+            safety_settings: None,
+            generation_config: None,
         };
 
         assert_eq!(params.get_voice(), "Puck");
@@ -938,6 +2410,9 @@ mod tests {
                 style: None,
                 model: DEFAULT_TTS_MODEL.to_string(),
                 output_file: None,
+                output_uri: None,
+                safety_settings: None,
+                generation_config: None,
             };
             assert!(
                 params.validate().is_ok(),
@@ -956,6 +2431,9 @@ mod tests {
                 style: Some(style.to_string()),
                 model: DEFAULT_TTS_MODEL.to_string(),
                 output_file: None,
+                output_uri: None,
+                safety_settings: None,
+                generation_config: None,
             };
             assert!(
                 params.validate().is_ok(),
@@ -970,7 +2448,11 @@ mod tests {
         let params = MultimodalImageParams {
             prompt: "A cat".to_string(),
             model: "custom-model".to_string(),
+            reference_images: None,
             output_file: Some("/tmp/output.png".to_string()),
+            output_uri: None,
+            safety_settings: None,
+            generation_config: None,
         };
 
         let json = serde_json::to_string(&params).unwrap();
@@ -989,6 +2471,9 @@ mod tests {
             style: Some("cheerful".to_string()),
             model: "custom-model".to_string(),
             output_file: Some("/tmp/output.wav".to_string()),
+            output_uri: None,
+            safety_settings: None,
+            generation_config: None,
         };
 
         let json = serde_json::to_string(&params).unwrap();
@@ -1000,4 +2485,708 @@ mod tests {
         assert_eq!(params.model, deserialized.model);
         assert_eq!(params.output_file, deserialized.output_file);
     }
+
+    const PNG_HEADER: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    fn test_handler() -> MultimodalHandler {
+        let config = Config {
+            project_id: "test-project".to_string(),
+            ..Default::default()
+        };
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        let gcs = GcsClient::with_auth(Arc::clone(&auth));
+        MultimodalHandler::with_deps(config, gcs, reqwest::Client::new(), auth)
+    }
+
+    #[test]
+    fn test_default_describe_params() {
+        let params: MultimodalDescribeParams =
+            serde_json::from_str(r#"{"image": "gs://bucket/photo.png"}"#).unwrap();
+        assert_eq!(params.model, DEFAULT_DESCRIBE_MODEL);
+        assert_eq!(params.get_question(), DEFAULT_DESCRIBE_QUESTION);
+        assert!(params.max_output_tokens.is_none());
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_describe_params() {
+        let params = MultimodalDescribeParams {
+            image: " ".to_string(),
+            question: Some("".to_string()),
+            model: DEFAULT_DESCRIBE_MODEL.to_string(),
+            max_output_tokens: Some(0),
+            safety_settings: None,
+            generation_config: None,
+        };
+
+        let errors = params.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["image", "question", "max_output_tokens"]);
+
+        let too_many = MultimodalDescribeParams {
+            image: "gs://bucket/photo.png".to_string(),
+            question: None,
+            model: DEFAULT_DESCRIBE_MODEL.to_string(),
+            max_output_tokens: Some(MAX_DESCRIBE_OUTPUT_TOKENS + 1),
+            safety_settings: None,
+            generation_config: None,
+        };
+        assert!(too_many.validate().is_err());
+    }
+
+    #[test]
+    fn test_detect_image_mime_type() {
+        assert_eq!(detect_image_mime_type(PNG_HEADER), Some("image/png"));
+        assert_eq!(detect_image_mime_type(&[0xFF, 0xD8, 0xFF, 0xE0]), Some("image/jpeg"));
+        assert_eq!(detect_image_mime_type(b"GIF89a..."), Some("image/gif"));
+        assert_eq!(detect_image_mime_type(b"RIFF\x10\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(detect_image_mime_type(b"RIFF\x10\0\0\0WAVEfmt "), None);
+        assert_eq!(detect_image_mime_type(b"hello"), None);
+    }
+
+    #[test]
+    fn test_describe_request_serialization() {
+        let request = GeminiDescribeRequest::new("What is this?", "image/png", "aGVsbG8=".to_string(), Some(256));
+        let json = serde_json::to_value(&request).unwrap();
+
+        let parts = &json["contents"][0]["parts"];
+        assert_eq!(parts[0]["inlineData"]["mimeType"], "image/png");
+        assert_eq!(parts[0]["inlineData"]["data"], "aGVsbG8=");
+        assert_eq!(parts[1]["text"], "What is this?");
+        assert_eq!(json["generationConfig"]["responseModalities"][0], "TEXT");
+        assert_eq!(json["generationConfig"]["maxOutputTokens"], 256);
+
+        let request = GeminiDescribeRequest::new("What is this?", "image/png", String::new(), None);
+        let json = serde_json::to_value(&request).unwrap();
+        assert!(json["generationConfig"].get("maxOutputTokens").is_none());
+    }
+
+    fn dangerous_only_high() -> Vec<SafetySetting> {
+        vec![SafetySetting {
+            category: "HARM_CATEGORY_DANGEROUS_CONTENT".to_string(),
+            threshold: "BLOCK_ONLY_HIGH".to_string(),
+        }]
+    }
+
+    #[test]
+    fn test_request_serializes_safety_settings_and_generation_config() {
+        let config = GenerationConfig {
+            temperature: Some(0.5),
+            top_p: Some(0.9),
+            top_k: Some(32),
+            max_output_tokens: Some(1024),
+            seed: Some(42),
+        };
+        let request = GeminiImageRequest::new("A red circle", Vec::new())
+            .with_settings(Some(&dangerous_only_high()), Some(&config));
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(
+            json["safetySettings"],
+            serde_json::json!([{"category": "HARM_CATEGORY_DANGEROUS_CONTENT", "threshold": "BLOCK_ONLY_HIGH"}])
+        );
+        let generation = &json["generationConfig"];
+        assert_eq!(generation["responseModalities"], serde_json::json!(["TEXT", "IMAGE"]));
+        assert_eq!(generation["imageConfig"]["aspectRatio"], "1:1");
+        assert_eq!(generation["temperature"], 0.5);
+        assert!((generation["topP"].as_f64().unwrap() - 0.9).abs() < 1e-6);
+        assert_eq!(generation["topK"], 32);
+        assert_eq!(generation["maxOutputTokens"], 1024);
+        assert_eq!(generation["seed"], 42);
+
+        // Nothing set: neither block is sent
+        let json = serde_json::to_value(GeminiImageRequest::new("A red circle", Vec::new()).with_settings(None, None)).unwrap();
+        assert!(json.get("safetySettings").is_none());
+        assert!(json["generationConfig"].get("temperature").is_none());
+        assert!(json["generationConfig"].get("seed").is_none());
+    }
+
+    #[test]
+    fn test_describe_request_merges_generation_config() {
+        let config = GenerationConfig {
+            seed: Some(7),
+            ..Default::default()
+        };
+        let request = GeminiDescribeRequest::new("What is this?", "image/png", String::new(), Some(256))
+            .with_settings(None, Some(&config));
+        let json = serde_json::to_value(&request).unwrap();
+
+        // The top-level token limit survives options that do not set one
+        assert_eq!(json["generationConfig"]["maxOutputTokens"], 256);
+        assert_eq!(json["generationConfig"]["seed"], 7);
+        assert_eq!(json["generationConfig"]["responseModalities"][0], "TEXT");
+    }
+
+    #[test]
+    fn test_generation_settings_validation() {
+        let mut params = MultimodalDescribeParams {
+            image: "gs://bucket/photo.png".to_string(),
+            question: None,
+            model: DEFAULT_DESCRIBE_MODEL.to_string(),
+            max_output_tokens: Some(100),
+            safety_settings: Some(dangerous_only_high()),
+            generation_config: Some(GenerationConfig {
+                temperature: Some(1.0),
+                top_p: Some(0.95),
+                top_k: Some(40),
+                max_output_tokens: None,
+                seed: Some(-1),
+            }),
+        };
+        assert!(params.validate().is_ok());
+
+        params.safety_settings = Some(vec![
+            SafetySetting {
+                category: "HARM_CATEGORY_VIOLENCE".to_string(),
+                threshold: "BLOCK_NONE".to_string(),
+            },
+            SafetySetting {
+                category: "HARM_CATEGORY_HARASSMENT".to_string(),
+                threshold: "BLOCK_SOME".to_string(),
+            },
+            SafetySetting {
+                category: "HARM_CATEGORY_HARASSMENT".to_string(),
+                threshold: "OFF".to_string(),
+            },
+        ]);
+        params.generation_config = Some(GenerationConfig {
+            temperature: Some(2.5),
+            top_p: Some(1.5),
+            top_k: Some(0),
+            max_output_tokens: Some(50),
+            seed: None,
+        });
+        let errors = params.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "safety_settings[0].category",
+                "safety_settings[1].threshold",
+                "safety_settings[2].category",
+                "generation_config.temperature",
+                "generation_config.top_p",
+                "generation_config.top_k",
+                "generation_config.max_output_tokens",
+            ]
+        );
+        assert!(errors[0].message.contains("HARM_CATEGORY_DANGEROUS_CONTENT"));
+        assert!(errors[1].message.contains("BLOCK_ONLY_HIGH"));
+        assert_eq!(errors[2].message, "'HARM_CATEGORY_HARASSMENT' is already set by safety_settings[1]");
+        assert_eq!(errors[3].message, "temperature must be between 0 and 2, got 2.5");
+        assert!(errors[6].message.contains("set only one"));
+    }
+
+    #[test]
+    fn test_prompt_block_maps_to_safety_error() {
+        let response: GeminiResponse = serde_json::from_value(serde_json::json!({
+            "promptFeedback": {
+                "blockReason": "SAFETY",
+                "safetyRatings": [
+                    {"category": "HARM_CATEGORY_HARASSMENT", "probability": "NEGLIGIBLE"},
+                    {"category": "HARM_CATEGORY_DANGEROUS_CONTENT", "probability": "HIGH", "blocked": true}
+                ]
+            }
+        }))
+        .unwrap();
+
+        let err = response.check_safety(&dangerous_only_high()).unwrap_err();
+        let Error::SafetyBlocked(block) = err else {
+            panic!("expected a safety error, got {err}");
+        };
+        assert_eq!(
+            block,
+            SafetyBlock {
+                reason: "SAFETY".to_string(),
+                category: Some("HARM_CATEGORY_DANGEROUS_CONTENT".to_string()),
+                threshold: Some("BLOCK_ONLY_HIGH".to_string()),
+                probability: Some("HIGH".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_candidate_safety_finish_maps_to_safety_error() {
+        let response: GeminiResponse = serde_json::from_value(serde_json::json!({
+            "candidates": [{
+                "finishReason": "IMAGE_SAFETY",
+                "safetyRatings": [
+                    {"category": "HARM_CATEGORY_HATE_SPEECH", "probability": "LOW"},
+                    {"category": "HARM_CATEGORY_SEXUALLY_EXPLICIT", "probability": "MEDIUM"}
+                ]
+            }]
+        }))
+        .unwrap();
+
+        // No rating is marked blocked: the most probable harm is reported, at the default threshold
+        let block = response.safety_block(&dangerous_only_high()).unwrap();
+        assert_eq!(block.reason, "IMAGE_SAFETY");
+        assert_eq!(block.category.as_deref(), Some("HARM_CATEGORY_SEXUALLY_EXPLICIT"));
+        assert_eq!(block.probability.as_deref(), Some("MEDIUM"));
+        assert!(block.threshold.is_none());
+    }
+
+    #[test]
+    fn test_unblocked_response_passes_safety_check() {
+        let response: GeminiResponse = serde_json::from_value(serde_json::json!({
+            "promptFeedback": {"safetyRatings": []},
+            "candidates": [{
+                "content": {"parts": [{"text": "A cat."}]},
+                "finishReason": "STOP",
+                "safetyRatings": [{"category": "HARM_CATEGORY_HARASSMENT", "probability": "NEGLIGIBLE"}]
+            }]
+        }))
+        .unwrap();
+        assert!(response.check_safety(&[]).is_ok());
+
+        let empty: GeminiResponse = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(empty.check_safety(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_extract_text_from_response() {
+        let handler = test_handler();
+        let response: GeminiResponse = serde_json::from_value(serde_json::json!({
+            "candidates": [{
+                "content": {"parts": [{"text": "A red bicycle "}, {"text": "leaning on a wall."}]},
+                "finishReason": "MAX_TOKENS"
+            }]
+        }))
+        .unwrap();
+
+        let (text, truncated) = handler.extract_text_from_response(&response).unwrap();
+        assert_eq!(text, "A red bicycle leaning on a wall.");
+        assert!(truncated);
+
+        let empty: GeminiResponse = serde_json::from_value(serde_json::json!({"candidates": []})).unwrap();
+        assert!(handler.extract_text_from_response(&empty).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_image_input_local_and_base64() {
+        let handler = test_handler();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("photo.png");
+        std::fs::write(&path, PNG_HEADER).unwrap();
+
+        let from_file = handler.resolve_image_input(path.to_str().unwrap()).await.unwrap();
+        assert_eq!(from_file, BASE64.encode(PNG_HEADER));
+
+        let encoded = BASE64.encode([PNG_HEADER; 8].concat());
+        assert_eq!(handler.resolve_image_input(&encoded).await.unwrap(), encoded);
+
+        assert!(handler.resolve_image_input("/nonexistent/photo.png").await.is_err());
+    }
+
+    fn image_params(reference_images: Option<Vec<&str>>) -> MultimodalImageParams {
+        MultimodalImageParams {
+            reference_images: reference_images.map(|r| r.into_iter().map(String::from).collect()),
+            ..serde_json::from_str(r#"{"prompt": "make this logo blue"}"#).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_reference_images_validation() {
+        assert!(image_params(None).validate().is_ok());
+        assert!(image_params(Some(vec!["gs://b/1.png", "gs://b/2.png", "gs://b/3.png"])).validate().is_ok());
+
+        let errors = image_params(Some(vec![])).validate().unwrap_err();
+        assert_eq!(errors[0].field, "reference_images");
+
+        let errors = image_params(Some(vec!["a.png"; MAX_REFERENCE_IMAGES + 1])).validate().unwrap_err();
+        assert!(errors[0].message.contains("between 1 and 3 images, got 4"), "{}", errors[0].message);
+
+        let errors = image_params(Some(vec!["gs://b/1.png", " "])).validate().unwrap_err();
+        assert_eq!(errors[0].field, "reference_images[1]");
+    }
+
+    #[test]
+    fn test_image_mode() {
+        assert_eq!(image_params(None).mode(), ImageMode::Generate);
+        assert_eq!(image_params(Some(vec!["gs://b/1.png"])).mode(), ImageMode::Edit);
+        assert_eq!(serde_json::to_value(ImageMode::Edit).unwrap(), "edit");
+    }
+
+    #[test]
+    fn test_image_request_serialization_text_only() {
+        let request = GeminiImageRequest::new("A red circle", Vec::new());
+        let json = serde_json::to_value(&request).unwrap();
+
+        let parts = json["contents"][0]["parts"].as_array().unwrap();
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0]["text"], "Generate an image of: A red circle");
+        assert_eq!(json["generationConfig"]["responseModalities"], serde_json::json!(["TEXT", "IMAGE"]));
+        assert_eq!(request.reference_count(), 0);
+    }
+
+    #[test]
+    fn test_image_request_serialization_with_references() {
+        let references = vec![
+            GeminiInlineData {
+                mime_type: "image/png".to_string(),
+                data: "cG5n".to_string(),
+            },
+            GeminiInlineData {
+                mime_type: "image/jpeg".to_string(),
+                data: "anBn".to_string(),
+            },
+        ];
+        let request = GeminiImageRequest::new("make this logo blue", references);
+        let json = serde_json::to_value(&request).unwrap();
+
+        let parts = json["contents"][0]["parts"].as_array().unwrap();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0]["inlineData"]["mimeType"], "image/png");
+        assert_eq!(parts[0]["inlineData"]["data"], "cG5n");
+        assert_eq!(parts[1]["inlineData"]["mimeType"], "image/jpeg");
+        assert_eq!(parts[2]["text"], "make this logo blue");
+        assert!(parts[2].get("inlineData").is_none());
+        assert_eq!(json["contents"][0]["role"], "user");
+        assert_eq!(request.reference_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_reference_images() {
+        let handler = test_handler();
+        let dir = tempfile::tempdir().unwrap();
+        let png = dir.path().join("logo.png");
+        std::fs::write(&png, PNG_HEADER).unwrap();
+
+        let references = handler
+            .resolve_reference_images(&[png.to_string_lossy().to_string()])
+            .await
+            .unwrap();
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].mime_type, "image/png");
+        assert_eq!(references[0].data, BASE64.encode(PNG_HEADER));
+
+        let text = dir.path().join("notes.png");
+        std::fs::write(&text, b"not an image").unwrap();
+        let err = handler
+            .resolve_reference_images(&[png.to_string_lossy().to_string(), text.to_string_lossy().to_string()])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("reference_images[1]: Unsupported image format"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_reference_image_rejected() {
+        let handler = test_handler();
+        let dir = tempfile::tempdir().unwrap();
+        let large = dir.path().join("large.png");
+        let mut data = PNG_HEADER.to_vec();
+        data.resize(MAX_REFERENCE_IMAGE_BYTES + 1, 0);
+        std::fs::write(&large, data).unwrap();
+
+        let err = handler
+            .resolve_reference_images(&[large.to_string_lossy().to_string()])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("reference_images[0]: image is 7340033 bytes"), "{}", err);
+    }
+
+    fn video_params(video: &str) -> MultimodalAnalyzeVideoParams {
+        serde_json::from_value(serde_json::json!({"video": video})).unwrap()
+    }
+
+    #[test]
+    fn test_analyze_video_params_validation() {
+        let params = video_params("gs://bucket/clips/cat.mp4");
+        assert_eq!(params.model, DEFAULT_DESCRIBE_MODEL);
+        assert_eq!(params.get_question(), DEFAULT_ANALYZE_VIDEO_QUESTION);
+        assert!(params.validate().is_ok());
+        assert!(video_params("/tmp/clip.MOV").validate().is_ok());
+
+        let errors = video_params("https://example.com/clip.mp4").validate().unwrap_err();
+        assert!(errors[0].message.contains("GCS URI or local file path"), "{}", errors[0].message);
+
+        let errors = video_params("/tmp/clip.gif").validate().unwrap_err();
+        assert!(errors[0].message.contains("Unsupported video type"), "{}", errors[0].message);
+
+        let errors = video_params("gs://bucket").validate().unwrap_err();
+        assert_eq!(errors[0].field, "video");
+    }
+
+    #[test]
+    fn test_video_mime_type() {
+        assert_eq!(video_mime_type("gs://b/veo/sample_0.mp4"), Some("video/mp4"));
+        assert_eq!(video_mime_type("/tmp/take.MOV"), Some("video/quicktime"));
+        assert_eq!(video_mime_type("/tmp/clip.webm"), Some("video/webm"));
+        assert_eq!(video_mime_type("/tmp/clip.mkv"), None);
+        assert_eq!(video_mime_type("/tmp/clip"), None);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_video_part() {
+        let handler = test_handler();
+
+        let part = handler.resolve_video_part("gs://bucket/veo/cat.mp4", "video/mp4").await.unwrap();
+        let request = GeminiDescribeRequest::with_media(part, "Is there a cat?", None);
+        let json = serde_json::to_value(&request).unwrap();
+        let parts = &json["contents"][0]["parts"];
+        assert_eq!(parts[0]["fileData"]["fileUri"], "gs://bucket/veo/cat.mp4");
+        assert_eq!(parts[0]["fileData"]["mimeType"], "video/mp4");
+        assert_eq!(parts[1]["text"], "Is there a cat?");
+
+        let dir = tempfile::tempdir().unwrap();
+        let small = dir.path().join("clip.mp4");
+        std::fs::write(&small, b"ftypmp42").unwrap();
+        let part = handler.resolve_video_part(small.to_str().unwrap(), "video/mp4").await.unwrap();
+        let json = serde_json::to_value(&part).unwrap();
+        assert_eq!(json["inlineData"]["data"], BASE64.encode(b"ftypmp42"));
+    }
+
+    #[tokio::test]
+    async fn test_large_local_video_rejected_with_gcs_guidance() {
+        let handler = test_handler();
+        let dir = tempfile::tempdir().unwrap();
+        let large = dir.path().join("long.mp4");
+        std::fs::File::create(&large).unwrap().set_len(MAX_INLINE_VIDEO_BYTES + 1).unwrap();
+
+        let err = handler
+            .resolve_video_part(large.to_str().unwrap(), "video/mp4")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("upload it to GCS"), "{}", err);
+
+        let err = handler.resolve_video_part("/nonexistent/clip.mp4", "video/mp4").await.unwrap_err();
+        assert!(err.to_string().contains("Video file not found"), "{}", err);
+    }
+
+    #[test]
+    fn test_usage_metadata_parsed() {
+        let response: GeminiResponse = serde_json::from_value(serde_json::json!({
+            "candidates": [{"content": {"parts": [{"text": "A cat chases a ball."}]}, "finishReason": "STOP"}],
+            "usageMetadata": {"promptTokenCount": 2071, "candidatesTokenCount": 7, "totalTokenCount": 2078}
+        }))
+        .unwrap();
+
+        let usage = response.usage_metadata.as_ref().map(TokenUsage::from).unwrap();
+        assert_eq!(usage, TokenUsage { prompt_tokens: 2071, output_tokens: 7, total_tokens: 2078 });
+    }
+}
+
+/// Output handling tests against a fake GCS server.
+#[cfg(test)]
+mod output_tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn handler_for(server: &MockServer, gcs_bucket: Option<&str>) -> MultimodalHandler {
+        let config = Config {
+            project_id: "test-project".to_string(),
+            gcs_bucket: gcs_bucket.map(String::from),
+            ..Default::default()
+        };
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        let gcs = GcsClient::with_base_url(Arc::clone(&auth), server.uri());
+        MultimodalHandler::with_deps(config, gcs, reqwest::Client::new(), auth)
+    }
+
+    fn image() -> GeneratedImage {
+        GeneratedImage {
+            data: BASE64.encode(b"\x89PNG\r\n\x1a\n"),
+            mime_type: "image/png".to_string(),
+        }
+    }
+
+    fn audio() -> GeneratedAudio {
+        GeneratedAudio {
+            data: BASE64.encode([0u8; 16]),
+            mime_type: "audio/L16;codec=pcm;rate=24000".to_string(),
+        }
+    }
+
+    fn image_params(output_file: Option<&str>, output_uri: Option<&str>) -> MultimodalImageParams {
+        MultimodalImageParams {
+            output_file: output_file.map(String::from),
+            output_uri: output_uri.map(String::from),
+            ..serde_json::from_str(r#"{"prompt": "A red circle"}"#).unwrap()
+        }
+    }
+
+    fn tts_params(output_file: Option<&str>, output_uri: Option<&str>) -> MultimodalTtsParams {
+        MultimodalTtsParams {
+            output_file: output_file.map(String::from),
+            output_uri: output_uri.map(String::from),
+            ..serde_json::from_str(r#"{"text": "Hello"}"#).unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_output_uri_takes_precedence_over_output_file() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/upload/storage/v1/b/bucket/o"))
+            .and(query_param("name", "images/circle.png"))
+            .and(header("Content-Type", "image/png"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("circle.png");
+        let params = image_params(Some(&local.to_string_lossy()), Some("gs://bucket/images/circle.png"));
+
+        let result = handler_for(&server, None).handle_image_output(image(), &params).await.unwrap();
+
+        match result {
+            ImageGenerateResult::GcsUri(uri) => assert_eq!(uri, "gs://bucket/images/circle.png"),
+            other => panic!("Expected GcsUri, got {:?}", other),
+        }
+        assert!(!local.exists(), "Local file should not be written when uploading");
+    }
+
+    #[tokio::test]
+    async fn test_audio_uploaded_with_returned_mime_type() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/upload/storage/v1/b/bucket/o"))
+            .and(query_param("name", "speech/hello.pcm"))
+            .and(header("Content-Type", "audio/L16;codec=pcm;rate=24000"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let params = tts_params(None, Some("gs://bucket/speech/hello.pcm"));
+        let result = handler_for(&server, None).handle_audio_output(audio(), &params).await.unwrap();
+
+        match result {
+            TtsResult::GcsUri(uri) => assert_eq!(uri, "gs://bucket/speech/hello.pcm"),
+            other => panic!("Expected GcsUri, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_output_file_creates_parent_directories() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("nested").join("hello.pcm");
+        let params = tts_params(Some(&local.to_string_lossy()), None);
+
+        let result = handler_for(&server, None).handle_audio_output(audio(), &params).await.unwrap();
+
+        match result {
+            TtsResult::LocalFile(path) => assert_eq!(path, local.to_string_lossy()),
+            other => panic!("Expected LocalFile, got {:?}", other),
+        }
+        assert_eq!(std::fs::read(&local).unwrap(), vec![0u8; 16]);
+    }
+
+    #[tokio::test]
+    async fn test_relative_output_file_uses_download_dir() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let mut handler = handler_for(&server, None);
+        handler.config.download_dir = Some(dir.path().to_string_lossy().into_owned());
+
+        let result = handler
+            .handle_image_output(image(), &image_params(Some("renders/cat.png"), None))
+            .await
+            .unwrap();
+
+        let expected = dir.path().join("renders/cat.png");
+        match result {
+            ImageGenerateResult::LocalFile(path) => assert_eq!(path, expected.to_string_lossy()),
+            other => panic!("Expected LocalFile, got {:?}", other),
+        }
+        assert!(expected.exists());
+    }
+
+    #[tokio::test]
+    async fn test_base64_returned_without_outputs() {
+        let server = MockServer::start().await;
+        let result = handler_for(&server, None)
+            .handle_image_output(image(), &image_params(None, None))
+            .await
+            .unwrap();
+        assert!(matches!(result, ImageGenerateResult::Base64(_)));
+    }
+
+    #[tokio::test]
+    async fn test_voice_lists_fetched_once_and_cached() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/voices"))
+            .and(header("x-goog-user-project", "test-project"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "voices": [
+                    {"name": "en-US-Chirp3-HD-Sulafat", "languageCodes": ["en-US"]},
+                    {"name": "ja-JP-Chirp3-HD-Kore", "languageCodes": ["ja-JP"]}
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let mut handler = handler_for(&server, None);
+        handler.tts_api_base_url = server.uri();
+
+        let voices = handler.list_voices(false).await;
+        assert_eq!(voices.source, VoiceSource::Api);
+        assert_eq!(voices.voices.len(), 2);
+
+        let codes = handler.list_language_codes(false).await;
+        assert_eq!(codes.source, VoiceSource::Api);
+        assert_eq!(codes.language_codes[1].name, "Japanese (Japan)");
+    }
+
+    #[tokio::test]
+    async fn test_voice_lists_fall_back_when_api_fails() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/voices"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("Cloud TTS API not enabled"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let mut handler = handler_for(&server, None);
+        handler.tts_api_base_url = server.uri();
+
+        let voices = handler.list_voices(false).await;
+        assert_eq!(voices.source, VoiceSource::Fallback);
+        assert_eq!(voices.voices.len(), AVAILABLE_VOICES.len());
+
+        // The failure is not retried right away
+        let codes = handler.list_language_codes(false).await;
+        assert_eq!(codes.source, VoiceSource::Fallback);
+        assert_eq!(codes.language_codes.len(), SUPPORTED_LANGUAGE_CODES.len());
+    }
+
+    #[test]
+    fn test_output_uri_resolution_and_validation() {
+        let server_uri = "http://localhost";
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        let config = |bucket: Option<&str>| Config {
+            project_id: "test-project".to_string(),
+            gcs_bucket: bucket.map(String::from),
+            ..Default::default()
+        };
+        let handler = MultimodalHandler::with_deps(
+            config(Some("media-bucket")),
+            GcsClient::with_base_url(Arc::clone(&auth), server_uri.to_string()),
+            reqwest::Client::new(),
+            Arc::clone(&auth),
+        );
+
+        // Bare object names go to GCS_BUCKET
+        assert_eq!(
+            handler.resolve_output_uri(Some("renders/cat.png".to_string())).unwrap().as_deref(),
+            Some("gs://media-bucket/renders/cat.png")
+        );
+
+        let without_bucket = MultimodalHandler::with_deps(
+            config(None),
+            GcsClient::with_base_url(Arc::clone(&auth), server_uri.to_string()),
+            reqwest::Client::new(),
+            auth,
+        );
+        assert!(without_bucket.resolve_output_uri(Some("renders/cat.png".to_string())).is_err());
+
+        let errors = image_params(None, Some("gs://")).validate().unwrap_err();
+        assert_eq!(errors[0].field, "output_uri");
+        assert!(tts_params(None, Some("gs://bucket/hello.pcm")).validate().is_ok());
+    }
 }
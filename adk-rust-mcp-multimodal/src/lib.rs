@@ -1,7 +1,7 @@
 //! ADK Rust MCP Multimodal Server Library
 //!
 //! This library provides multimodal generation capabilities using Google's Gemini API,
-//! including image generation and text-to-speech synthesis.
+//! including image generation, image description, video analysis, and text-to-speech synthesis.
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
@@ -10,7 +10,9 @@ pub mod resources;
 pub mod server;
 
 pub use handler::{
-    GeneratedAudio, GeneratedImage, ImageGenerateResult, LanguageCodeInfo, MultimodalHandler,
-    MultimodalImageParams, MultimodalTtsParams, TtsResult, VoiceInfo,
+    GeneratedAudio, GeneratedImage, GeneratedImageOutcome, GenerationConfig, ImageDescribeResult,
+    ImageGenerateResult, ImageMode, LanguageCodeInfo, LanguageCodeList, MultimodalAnalyzeVideoParams, MultimodalDescribeParams,
+    MultimodalHandler, MultimodalImageParams, MultimodalTtsParams, SafetySetting, TokenUsage, TtsResult,
+    VideoAnalysisResult, VoiceInfo, VoiceList,
 };
 pub use server::MultimodalServer;
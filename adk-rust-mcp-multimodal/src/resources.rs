@@ -2,10 +2,28 @@
 //!
 //! This module provides resource content for:
 //! - `multimodal://language_codes` - Supported language codes for TTS
+//! - `multimodal://voices` - Available TTS voices
+//! - `multimodal://models` - Gemini models and their capabilities
 
-use crate::handler::{AVAILABLE_VOICES, SUPPORTED_LANGUAGE_CODES};
+use crate::handler::{LanguageCodeInfo, VoiceInfo};
+use adk_rust_mcp_common::models::GEMINI_MODELS;
 use serde::{Deserialize, Serialize};
 
+/// Information about an available Gemini model.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelInfo {
+    /// Model identifier
+    pub id: &'static str,
+    /// Model aliases
+    pub aliases: Vec<&'static str>,
+    /// Whether the model can be used with multimodal_image_generate
+    pub supports_image_generation: bool,
+    /// Whether the model can be used with multimodal_speech_synthesize
+    pub supports_tts: bool,
+    /// Whether the model accepts audio and video input
+    pub supports_audio_input: bool,
+}
+
 /// Language code entry for the resource.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LanguageCodeEntry {
@@ -25,25 +43,44 @@ pub struct VoiceEntry {
 }
 
 /// Get the language codes resource as JSON.
-pub fn language_codes_resource_json() -> String {
-    let codes: Vec<LanguageCodeEntry> = SUPPORTED_LANGUAGE_CODES
+pub fn language_codes_resource_json(codes: &[LanguageCodeInfo]) -> String {
+    let codes: Vec<LanguageCodeEntry> = codes
         .iter()
-        .map(|&(code, name)| LanguageCodeEntry {
-            code: code.to_string(),
-            name: name.to_string(),
+        .map(|c| LanguageCodeEntry {
+            code: c.code.clone(),
+            name: c.name.clone(),
         })
         .collect();
 
     serde_json::to_string_pretty(&codes).unwrap_or_else(|_| "[]".to_string())
 }
 
+/// List all available Gemini models.
+pub fn list_models() -> Vec<ModelInfo> {
+    GEMINI_MODELS
+        .iter()
+        .map(|m| ModelInfo {
+            id: m.id,
+            aliases: m.aliases.to_vec(),
+            supports_image_generation: m.supports_image_generation,
+            supports_tts: m.supports_tts,
+            supports_audio_input: m.supports_audio_input,
+        })
+        .collect()
+}
+
+/// Get the models resource as JSON.
+pub fn models_resource_json() -> String {
+    serde_json::to_string_pretty(&list_models()).unwrap_or_else(|_| "[]".to_string())
+}
+
 /// Get the voices resource as JSON.
-pub fn voices_resource_json() -> String {
-    let voices: Vec<VoiceEntry> = AVAILABLE_VOICES
+pub fn voices_resource_json(voices: &[VoiceInfo]) -> String {
+    let voices: Vec<VoiceEntry> = voices
         .iter()
-        .map(|&name| VoiceEntry {
-            name: name.to_string(),
-            description: format!("Gemini TTS voice: {}", name),
+        .map(|v| VoiceEntry {
+            name: v.name.clone(),
+            description: v.description.clone(),
         })
         .collect();
 
@@ -56,7 +93,7 @@ mod tests {
 
     #[test]
     fn test_language_codes_resource_json() {
-        let json = language_codes_resource_json();
+        let json = language_codes_resource_json(&crate::handler::fallback_language_codes());
         assert!(json.contains("en-US"));
         assert!(json.contains("English (US)"));
 
@@ -65,9 +102,23 @@ mod tests {
         assert!(!parsed.is_empty());
     }
 
+    #[test]
+    fn test_list_models_includes_defaults() {
+        let models = list_models();
+        let image = models.iter().find(|m| m.id == crate::handler::DEFAULT_IMAGE_MODEL).unwrap();
+        assert!(image.supports_image_generation);
+        let tts = models.iter().find(|m| m.id == crate::handler::DEFAULT_TTS_MODEL).unwrap();
+        assert!(tts.supports_tts);
+        let describe = models.iter().find(|m| m.id == crate::handler::DEFAULT_DESCRIBE_MODEL).unwrap();
+        assert!(describe.aliases.contains(&"flash"));
+
+        let json: serde_json::Value = serde_json::from_str(&models_resource_json()).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), models.len());
+    }
+
     #[test]
     fn test_voices_resource_json() {
-        let json = voices_resource_json();
+        let json = voices_resource_json(&crate::handler::fallback_voices());
         assert!(json.contains("Kore"));
         assert!(json.contains("Puck"));
 
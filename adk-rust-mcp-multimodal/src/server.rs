@@ -3,15 +3,21 @@
 //! This module provides the MCP server handler that exposes:
 //! - `multimodal_image_generate` tool for image generation using Gemini
 //! - `multimodal_speech_synthesize` tool for TTS using Gemini
+//! - `multimodal_image_describe` tool for describing images using Gemini
+//! - `multimodal_analyze_video` tool for summarizing or questioning videos using Gemini
 //! - `multimodal_list_voices` tool for listing available voices
-//! - Resources for language codes
+//! - `multimodal_list_models` tool for discovering model capabilities
+//! - Resources for language codes, voices, and models
 
 use crate::handler::{
-    ImageGenerateResult, MultimodalHandler, MultimodalImageParams, MultimodalTtsParams, TtsResult,
+    fallback_language_codes, fallback_voices, GeneratedImageOutcome, GenerationConfig, ImageDescribeResult,
+    ImageGenerateResult, LanguageCodeInfo, MultimodalAnalyzeVideoParams, MultimodalDescribeParams,
+    MultimodalHandler, MultimodalImageParams, MultimodalTtsParams, SafetySetting, TtsResult,
+    VideoAnalysisResult, VoiceInfo, VoiceList,
 };
 use crate::resources;
 use adk_rust_mcp_common::config::Config;
-use adk_rust_mcp_common::error::Error;
+use adk_rust_mcp_common::error::{Error, ErrorCode};
 use rmcp::{
     model::{
         CallToolResult, Content, ListResourcesResult, ReadResourceResult, ResourceContents,
@@ -43,9 +49,21 @@ pub struct ImageGenerateToolParams {
     /// Model to use for generation
     #[serde(default)]
     pub model: Option<String>,
+    /// Up to 3 images to edit or build on (base64 data, data URI, local file path, GCS URI, or HTTPS URL); the prompt then describes the edit, e.g. "make this logo blue"
+    #[serde(default)]
+    pub reference_images: Option<Vec<String>>,
     /// Output file path for saving locally
     #[serde(default)]
     pub output_file: Option<String>,
+    /// Output GCS URI (gs://bucket/path, or an object name in GCS_BUCKET); takes precedence over output_file
+    #[serde(default)]
+    pub output_uri: Option<String>,
+    /// Safety filter thresholds, e.g. [{"category": "HARM_CATEGORY_DANGEROUS_CONTENT", "threshold": "BLOCK_ONLY_HIGH"}]
+    #[serde(default)]
+    pub safety_settings: Option<Vec<SafetySetting>>,
+    /// Sampling options: temperature (0-2), top_p (0-1), top_k, max_output_tokens, seed
+    #[serde(default)]
+    pub generation_config: Option<GenerationConfig>,
 }
 
 impl From<ImageGenerateToolParams> for MultimodalImageParams {
@@ -55,7 +73,11 @@ impl From<ImageGenerateToolParams> for MultimodalImageParams {
             model: params
                 .model
                 .unwrap_or_else(|| crate::handler::DEFAULT_IMAGE_MODEL.to_string()),
+            reference_images: params.reference_images,
             output_file: params.output_file,
+            output_uri: params.output_uri,
+            safety_settings: params.safety_settings,
+            generation_config: params.generation_config,
         }
     }
 }
@@ -77,6 +99,15 @@ pub struct SpeechSynthesizeToolParams {
     /// Output file path for saving locally
     #[serde(default)]
     pub output_file: Option<String>,
+    /// Output GCS URI (gs://bucket/path, or an object name in GCS_BUCKET); takes precedence over output_file
+    #[serde(default)]
+    pub output_uri: Option<String>,
+    /// Safety filter thresholds, e.g. [{"category": "HARM_CATEGORY_DANGEROUS_CONTENT", "threshold": "BLOCK_ONLY_HIGH"}]
+    #[serde(default)]
+    pub safety_settings: Option<Vec<SafetySetting>>,
+    /// Sampling options: temperature (0-2), top_p (0-1), top_k, max_output_tokens, seed
+    #[serde(default)]
+    pub generation_config: Option<GenerationConfig>,
 }
 
 impl From<SpeechSynthesizeToolParams> for MultimodalTtsParams {
@@ -89,10 +120,95 @@ impl From<SpeechSynthesizeToolParams> for MultimodalTtsParams {
                 .model
                 .unwrap_or_else(|| crate::handler::DEFAULT_TTS_MODEL.to_string()),
             output_file: params.output_file,
+            output_uri: params.output_uri,
+            safety_settings: params.safety_settings,
+            generation_config: params.generation_config,
+        }
+    }
+}
+
+/// Tool parameters wrapper for multimodal_analyze_video.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AnalyzeVideoToolParams {
+    /// Video to analyze: GCS URI (preferred) or local file path up to 15 MiB
+    pub video: String,
+    /// Question or instruction about the video (default: summarize it)
+    #[serde(default)]
+    pub question: Option<String>,
+    /// Model to use for analysis
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Maximum number of tokens in the answer (1-8192)
+    #[serde(default)]
+    pub max_output_tokens: Option<u32>,
+    /// Safety filter thresholds, e.g. [{"category": "HARM_CATEGORY_DANGEROUS_CONTENT", "threshold": "BLOCK_ONLY_HIGH"}]
+    #[serde(default)]
+    pub safety_settings: Option<Vec<SafetySetting>>,
+    /// Sampling options: temperature (0-2), top_p (0-1), top_k, max_output_tokens, seed
+    #[serde(default)]
+    pub generation_config: Option<GenerationConfig>,
+}
+
+impl From<AnalyzeVideoToolParams> for MultimodalAnalyzeVideoParams {
+    fn from(params: AnalyzeVideoToolParams) -> Self {
+        Self {
+            video: params.video,
+            question: params.question,
+            model: params
+                .model
+                .unwrap_or_else(|| crate::handler::DEFAULT_DESCRIBE_MODEL.to_string()),
+            max_output_tokens: params.max_output_tokens,
+            safety_settings: params.safety_settings,
+            generation_config: params.generation_config,
+        }
+    }
+}
+
+/// Tool parameters wrapper for multimodal_image_describe.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ImageDescribeToolParams {
+    /// Image to describe: base64 data, data URI, local file path, GCS URI, or HTTPS URL
+    pub image: String,
+    /// Question or instruction about the image (default: describe it in detail)
+    #[serde(default)]
+    pub question: Option<String>,
+    /// Model to use for description
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Maximum number of tokens in the description (1-8192)
+    #[serde(default)]
+    pub max_output_tokens: Option<u32>,
+    /// Safety filter thresholds, e.g. [{"category": "HARM_CATEGORY_DANGEROUS_CONTENT", "threshold": "BLOCK_ONLY_HIGH"}]
+    #[serde(default)]
+    pub safety_settings: Option<Vec<SafetySetting>>,
+    /// Sampling options: temperature (0-2), top_p (0-1), top_k, max_output_tokens, seed
+    #[serde(default)]
+    pub generation_config: Option<GenerationConfig>,
+}
+
+impl From<ImageDescribeToolParams> for MultimodalDescribeParams {
+    fn from(params: ImageDescribeToolParams) -> Self {
+        Self {
+            image: params.image,
+            question: params.question,
+            model: params
+                .model
+                .unwrap_or_else(|| crate::handler::DEFAULT_DESCRIBE_MODEL.to_string()),
+            max_output_tokens: params.max_output_tokens,
+            safety_settings: params.safety_settings,
+            generation_config: params.generation_config,
         }
     }
 }
 
+/// Tool parameters wrapper for multimodal_list_voices.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct ListVoicesToolParams {
+    /// Fetch the voice list from the API again instead of using the cached copy
+    #[serde(default)]
+    pub refresh: Option<bool>,
+}
+
 impl MultimodalServer {
     /// Create a new MultimodalServer with the given configuration.
     pub fn new(config: Config) -> Self {
@@ -121,7 +237,7 @@ impl MultimodalServer {
 
         // Ensure handler is initialized
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -131,20 +247,10 @@ impl MultimodalServer {
 
         let gen_params: MultimodalImageParams = params.into();
         let result = handler.generate_image(gen_params).await.map_err(|e| {
-            McpError::internal_error(format!("Image generation failed: {}", e), None)
+            e.to_mcp_error("Image generation failed")
         })?;
 
-        // Convert result to MCP content
-        let content = match result {
-            ImageGenerateResult::Base64(image) => {
-                vec![Content::image(image.data, image.mime_type)]
-            }
-            ImageGenerateResult::LocalFile(path) => {
-                vec![Content::text(format!("Image saved to: {}", path))]
-            }
-        };
-
-        Ok(CallToolResult::success(content))
+        Ok(format_image_result(result))
     }
 
     /// Synthesize speech from text.
@@ -156,7 +262,7 @@ impl MultimodalServer {
 
         // Ensure handler is initialized
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -166,7 +272,7 @@ impl MultimodalServer {
 
         let tts_params: MultimodalTtsParams = params.into();
         let result = handler.synthesize_speech(tts_params).await.map_err(|e| {
-            McpError::internal_error(format!("Speech synthesis failed: {}", e), None)
+            e.to_mcp_error("Speech synthesis failed")
         })?;
 
         // Convert result to MCP content
@@ -180,18 +286,71 @@ impl MultimodalServer {
             TtsResult::LocalFile(path) => {
                 vec![Content::text(format!("Audio saved to: {}", path))]
             }
+            TtsResult::GcsUri(uri) => {
+                vec![Content::text(format!("Audio uploaded to: {}", uri))]
+            }
         };
 
         Ok(CallToolResult::success(content))
     }
 
+    /// Describe an image.
+    pub async fn describe_image(
+        &self,
+        params: ImageDescribeToolParams,
+    ) -> Result<CallToolResult, McpError> {
+        info!(question = ?params.question, "Describing image with Gemini");
+
+        // Ensure handler is initialized
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard
+            .as_ref()
+            .ok_or_else(|| McpError::internal_error("Handler not initialized", None))?;
+
+        let describe_params: MultimodalDescribeParams = params.into();
+        let result = handler.describe_image(describe_params).await.map_err(|e| {
+            e.to_mcp_error("Image description failed")
+        })?;
+
+        Ok(format_describe_result(&result))
+    }
+
+    /// Summarize or answer a question about a video.
+    pub async fn analyze_video(
+        &self,
+        params: AnalyzeVideoToolParams,
+    ) -> Result<CallToolResult, McpError> {
+        info!(video = %params.video, question = ?params.question, "Analyzing video with Gemini");
+
+        // Ensure handler is initialized
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard
+            .as_ref()
+            .ok_or_else(|| McpError::internal_error("Handler not initialized", None))?;
+
+        let analyze_params: MultimodalAnalyzeVideoParams = params.into();
+        let result = handler.analyze_video(analyze_params).await.map_err(|e| {
+            e.to_mcp_error("Video analysis failed")
+        })?;
+
+        Ok(format_analysis_result(&result))
+    }
+
     /// List available voices.
-    pub async fn list_voices(&self) -> Result<CallToolResult, McpError> {
+    pub async fn list_voices(&self, params: ListVoicesToolParams) -> Result<CallToolResult, McpError> {
         info!("Listing available Gemini TTS voices");
 
         // Ensure handler is initialized
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -199,17 +358,112 @@ impl MultimodalServer {
             .as_ref()
             .ok_or_else(|| McpError::internal_error("Handler not initialized", None))?;
 
-        let voices = handler.list_voices();
+        let list = handler.list_voices(params.refresh.unwrap_or(false)).await;
+        Ok(format_voice_list(&list))
+    }
 
-        // Format voices as JSON
-        let voices_json = serde_json::to_string_pretty(&voices).map_err(|e| {
-            McpError::internal_error(format!("Failed to serialize voices: {}", e), None)
+    /// Voices for the voices resource, or the built-in list when the handler
+    /// cannot be initialized.
+    async fn resource_voices(&self) -> Vec<VoiceInfo> {
+        if self.ensure_handler().await.is_err() {
+            return fallback_voices();
+        }
+        match self.handler.read().await.as_ref() {
+            Some(handler) => handler.list_voices(false).await.voices,
+            None => fallback_voices(),
+        }
+    }
+
+    /// Language codes for the language codes resource, or the built-in list
+    /// when the handler cannot be initialized.
+    async fn resource_language_codes(&self) -> Vec<LanguageCodeInfo> {
+        if self.ensure_handler().await.is_err() {
+            return fallback_language_codes();
+        }
+        match self.handler.read().await.as_ref() {
+            Some(handler) => handler.list_language_codes(false).await.language_codes,
+            None => fallback_language_codes(),
+        }
+    }
+
+    /// List available Gemini models with their aliases and capabilities.
+    pub fn list_models(&self) -> Result<CallToolResult, McpError> {
+        let models = resources::list_models();
+        let models_json = serde_json::to_string_pretty(&models).map_err(|e| {
+            McpError::internal_error(format!("Failed to serialize models: {}", e), None)
         })?;
 
-        Ok(CallToolResult::success(vec![Content::text(voices_json)]))
+        let mut tool_result = CallToolResult::success(vec![Content::text(models_json)]);
+        tool_result.structured_content = Some(serde_json::json!({ "models": models }));
+        Ok(tool_result)
+    }
+}
+
+/// Format a voice list as JSON text, with the list's source in the structured output.
+fn format_voice_list(list: &VoiceList) -> CallToolResult {
+    let voices_json = serde_json::to_string_pretty(&list.voices).unwrap_or_else(|_| "[]".to_string());
+    let mut tool_result = CallToolResult::success(vec![Content::text(voices_json)]);
+    tool_result.structured_content = serde_json::to_value(list).ok();
+    tool_result
+}
+
+/// Format a generated or edited image as MCP content with structured output.
+fn format_image_result(result: GeneratedImageOutcome) -> CallToolResult {
+    let (content, location) = match result.output {
+        ImageGenerateResult::Base64(image) => (vec![Content::image(image.data, image.mime_type)], None),
+        ImageGenerateResult::LocalFile(path) => {
+            (vec![Content::text(format!("Image saved to: {}", path))], Some(path))
+        }
+        ImageGenerateResult::GcsUri(uri) => {
+            (vec![Content::text(format!("Image uploaded to: {}", uri))], Some(uri))
+        }
+    };
+
+    let mut tool_result = CallToolResult::success(content);
+    tool_result.structured_content = Some(serde_json::json!({
+        "mode": result.mode,
+        "reference_images": result.reference_images,
+        "output": location,
+    }));
+    tool_result
+}
+
+/// Format an image description as text content with structured output.
+fn format_describe_result(result: &ImageDescribeResult) -> CallToolResult {
+    let mut content = vec![Content::text(result.description.clone())];
+    if result.truncated {
+        content.push(Content::text(
+            "Note: the description was cut off at max_output_tokens.",
+        ));
     }
+
+    let mut tool_result = CallToolResult::success(content);
+    tool_result.structured_content = serde_json::to_value(result).ok();
+    tool_result
 }
 
+/// Format a video analysis as text content with structured output.
+fn format_analysis_result(result: &VideoAnalysisResult) -> CallToolResult {
+    let mut content = vec![Content::text(result.answer.clone())];
+    if result.truncated {
+        content.push(Content::text(
+            "Note: the answer was cut off at max_output_tokens.",
+        ));
+    }
+    if let Some(usage) = result.usage {
+        content.push(Content::text(format!(
+            "Tokens: {} prompt, {} output, {} total",
+            usage.prompt_tokens, usage.output_tokens, usage.total_tokens
+        )));
+    }
+
+    let mut tool_result = CallToolResult::success(content);
+    tool_result.structured_content = serde_json::to_value(result).ok();
+    tool_result
+}
+
+// Method signatures mirror the rmcp trait, which returns `impl Future`.
+#[allow(clippy::manual_async_fn)]
 impl ServerHandler for MultimodalServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -217,7 +471,10 @@ impl ServerHandler for MultimodalServer {
                 "Multimodal generation server using Google Gemini API. \
                  Use multimodal_image_generate to create images from text prompts, \
                  multimodal_speech_synthesize for text-to-speech, \
-                 and multimodal_list_voices to see available voices."
+                 multimodal_image_describe to describe or answer questions about an image, \
+                 multimodal_analyze_video to summarize or check a video (e.g. a generated Veo clip), \
+                 multimodal_list_voices to see available voices, and multimodal_list_models \
+                 to see which Gemini models each tool accepts."
                     .to_string(),
             ),
             capabilities: ServerCapabilities::builder()
@@ -254,7 +511,31 @@ impl ServerHandler for MultimodalServer {
                 _ => Arc::new(serde_json::Map::new()),
             };
 
-            // multimodal_list_voices tool (no parameters - must have type: "object")
+            // multimodal_image_describe tool
+            let describe_schema = schema_for!(ImageDescribeToolParams);
+            let describe_schema_value = serde_json::to_value(&describe_schema).unwrap_or_default();
+            let describe_input_schema = match describe_schema_value {
+                serde_json::Value::Object(map) => Arc::new(map),
+                _ => Arc::new(serde_json::Map::new()),
+            };
+
+            // multimodal_analyze_video tool
+            let video_schema = schema_for!(AnalyzeVideoToolParams);
+            let video_schema_value = serde_json::to_value(&video_schema).unwrap_or_default();
+            let video_input_schema = match video_schema_value {
+                serde_json::Value::Object(map) => Arc::new(map),
+                _ => Arc::new(serde_json::Map::new()),
+            };
+
+            // multimodal_list_voices tool
+            let voices_schema = schema_for!(ListVoicesToolParams);
+            let voices_schema_value = serde_json::to_value(&voices_schema).unwrap_or_default();
+            let voices_input_schema = match voices_schema_value {
+                serde_json::Value::Object(map) => Arc::new(map),
+                _ => Arc::new(serde_json::Map::new()),
+            };
+
+            // multimodal_list_models tool (no parameters - must have type: "object")
             let mut empty_schema_map = serde_json::Map::new();
             empty_schema_map.insert("type".to_string(), serde_json::Value::String("object".to_string()));
             let empty_schema = Arc::new(empty_schema_map);
@@ -264,8 +545,11 @@ impl ServerHandler for MultimodalServer {
                     Tool {
                         name: Cow::Borrowed("multimodal_image_generate"),
                         description: Some(Cow::Borrowed(
-                            "Generate images from a text prompt using Google's Gemini API. \
-                             Returns base64-encoded image data or saves to a local file.",
+                            "Generate images from a text prompt using Google's Gemini API, or edit \
+                             up to 3 reference_images (base64, data URI, local path, GCS URI, or HTTPS URL) \
+                             following the prompt, e.g. \"make this logo blue\". \
+                             Returns base64-encoded image data or saves to a local file; \
+                             structured output reports mode \"generate\" or \"edit\".",
                         )),
                         input_schema: image_input_schema,
                         annotations: None,
@@ -288,10 +572,54 @@ impl ServerHandler for MultimodalServer {
                         output_schema: None,
                         title: None,
                     },
+                    Tool {
+                        name: Cow::Borrowed("multimodal_image_describe"),
+                        description: Some(Cow::Borrowed(
+                            "Describe an image or answer a question about it using Google's Gemini API. \
+                             Accepts base64 image data, data URI, local file path, GCS URI, or HTTPS URL as input. \
+                             Returns the text description and the detected image MIME type.",
+                        )),
+                        input_schema: describe_input_schema,
+                        annotations: None,
+                        icons: None,
+                        meta: None,
+                        output_schema: None,
+                        title: None,
+                    },
+                    Tool {
+                        name: Cow::Borrowed("multimodal_analyze_video"),
+                        description: Some(Cow::Borrowed(
+                            "Summarize a video or answer a question about it using Google's Gemini API, \
+                             e.g. to check that a generated clip shows what was asked for. \
+                             Pass a GCS URI (preferred) or a local file up to 15 MiB. \
+                             Returns the text answer and token usage.",
+                        )),
+                        input_schema: video_input_schema,
+                        annotations: None,
+                        icons: None,
+                        meta: None,
+                        output_schema: None,
+                        title: None,
+                    },
                     Tool {
                         name: Cow::Borrowed("multimodal_list_voices"),
                         description: Some(Cow::Borrowed(
-                            "List available Gemini TTS voices.",
+                            "List available Gemini TTS voices. The list comes from the Cloud TTS API \
+                             and is cached; set refresh to fetch it again. The structured result's \
+                             source is \"fallback\" when the API was unavailable and a built-in list was returned.",
+                        )),
+                        input_schema: voices_input_schema,
+                        annotations: None,
+                        icons: None,
+                        meta: None,
+                        output_schema: None,
+                        title: None,
+                    },
+                    Tool {
+                        name: Cow::Borrowed("multimodal_list_models"),
+                        description: Some(Cow::Borrowed(
+                            "List available Gemini models with their IDs, aliases (e.g. flash, pro), \
+                             and whether they support image generation, text-to-speech, and audio/video input.",
                         )),
                         input_schema: empty_schema,
                         annotations: None,
@@ -320,9 +648,9 @@ impl ServerHandler for MultimodalServer {
                         .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
                         .transpose()
                         .map_err(|e| {
-                            McpError::invalid_params(format!("Invalid parameters: {}", e), None)
+                            McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data()))
                         })?
-                        .ok_or_else(|| McpError::invalid_params("Missing parameters", None))?;
+                        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))?;
 
                     self.generate_image(tool_params).await
                 }
@@ -332,13 +660,49 @@ impl ServerHandler for MultimodalServer {
                         .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
                         .transpose()
                         .map_err(|e| {
-                            McpError::invalid_params(format!("Invalid parameters: {}", e), None)
+                            McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data()))
                         })?
-                        .ok_or_else(|| McpError::invalid_params("Missing parameters", None))?;
+                        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))?;
 
                     self.synthesize_speech(tool_params).await
                 }
-                "multimodal_list_voices" => self.list_voices().await,
+                "multimodal_image_describe" => {
+                    let tool_params: ImageDescribeToolParams = params
+                        .arguments
+                        .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
+                        .transpose()
+                        .map_err(|e| {
+                            McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data()))
+                        })?
+                        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))?;
+
+                    self.describe_image(tool_params).await
+                }
+                "multimodal_analyze_video" => {
+                    let tool_params: AnalyzeVideoToolParams = params
+                        .arguments
+                        .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
+                        .transpose()
+                        .map_err(|e| {
+                            McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data()))
+                        })?
+                        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))?;
+
+                    self.analyze_video(tool_params).await
+                }
+                "multimodal_list_voices" => {
+                    let tool_params: ListVoicesToolParams = params
+                        .arguments
+                        .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
+                        .transpose()
+                        .map_err(|e| {
+                            McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data()))
+                        })?
+                        .unwrap_or_default();
+
+                    self.list_voices(tool_params).await
+                }
+                "multimodal_list_models" => self.list_models(),
                 _ => Err(McpError::invalid_params(
                     format!("Unknown tool: {}", params.name),
                     None,
@@ -383,8 +747,22 @@ impl ServerHandler for MultimodalServer {
                 annotations: None,
             };
 
+            let models_resource = rmcp::model::Resource {
+                raw: rmcp::model::RawResource {
+                    uri: "multimodal://models".to_string(),
+                    name: "Available Gemini Models".to_string(),
+                    title: None,
+                    description: Some("List of Gemini models with their aliases and capabilities".to_string()),
+                    mime_type: Some("application/json".to_string()),
+                    size: None,
+                    icons: None,
+                    meta: None,
+                },
+                annotations: None,
+            };
+
             Ok(ListResourcesResult {
-                resources: vec![language_codes_resource, voices_resource],
+                resources: vec![language_codes_resource, voices_resource, models_resource],
                 next_cursor: None,
                 meta: None,
             })
@@ -401,8 +779,11 @@ impl ServerHandler for MultimodalServer {
             debug!(uri = %uri, "Reading resource");
 
             let content = match uri.as_str() {
-                "multimodal://language_codes" => resources::language_codes_resource_json(),
-                "multimodal://voices" => resources::voices_resource_json(),
+                "multimodal://language_codes" => {
+                    resources::language_codes_resource_json(&self.resource_language_codes().await)
+                }
+                "multimodal://voices" => resources::voices_resource_json(&self.resource_voices().await),
+                "multimodal://models" => resources::models_resource_json(),
                 _ => {
                     return Err(McpError::resource_not_found(
                         format!("Unknown resource: {}", uri),
@@ -426,9 +807,7 @@ mod tests {
     fn test_config() -> Config {
         Config {
             project_id: "test-project".to_string(),
-            location: "us-central1".to_string(),
-            gcs_bucket: None,
-            port: 8080,
+            ..Default::default()
         }
     }
 
@@ -444,12 +823,17 @@ mod tests {
         let tool_params = ImageGenerateToolParams {
             prompt: "A cat".to_string(),
             model: Some("custom-model".to_string()),
+            reference_images: Some(vec!["gs://bucket/logo.png".to_string()]),
             output_file: Some("/tmp/output.png".to_string()),
+            output_uri: None,
+            safety_settings: None,
+            generation_config: None,
         };
 
         let gen_params: MultimodalImageParams = tool_params.into();
         assert_eq!(gen_params.prompt, "A cat");
         assert_eq!(gen_params.model, "custom-model");
+        assert_eq!(gen_params.reference_images, Some(vec!["gs://bucket/logo.png".to_string()]));
         assert_eq!(gen_params.output_file, Some("/tmp/output.png".to_string()));
     }
 
@@ -458,7 +842,11 @@ mod tests {
         let tool_params = ImageGenerateToolParams {
             prompt: "A cat".to_string(),
             model: None,
+            reference_images: None,
             output_file: None,
+            output_uri: None,
+            safety_settings: None,
+            generation_config: None,
         };
 
         let gen_params: MultimodalImageParams = tool_params.into();
@@ -474,6 +862,9 @@ mod tests {
             style: Some("cheerful".to_string()),
             model: Some("custom-model".to_string()),
             output_file: Some("/tmp/output.wav".to_string()),
+            output_uri: None,
+            safety_settings: None,
+            generation_config: None,
         };
 
         let tts_params: MultimodalTtsParams = tool_params.into();
@@ -492,6 +883,9 @@ mod tests {
             style: None,
             model: None,
             output_file: None,
+            output_uri: None,
+            safety_settings: None,
+            generation_config: None,
         };
 
         let tts_params: MultimodalTtsParams = tool_params.into();
@@ -499,4 +893,126 @@ mod tests {
         assert!(tts_params.voice.is_none());
         assert!(tts_params.style.is_none());
     }
+
+    #[test]
+    fn test_describe_tool_params_defaults() {
+        let tool_params: ImageDescribeToolParams =
+            serde_json::from_value(serde_json::json!({"image": "gs://bucket/photo.jpg"})).unwrap();
+
+        let describe_params: MultimodalDescribeParams = tool_params.into();
+        assert_eq!(describe_params.image, "gs://bucket/photo.jpg");
+        assert_eq!(describe_params.model, crate::handler::DEFAULT_DESCRIBE_MODEL);
+        assert!(describe_params.question.is_none());
+        assert!(describe_params.max_output_tokens.is_none());
+        assert!(describe_params.safety_settings.is_none());
+        assert!(describe_params.generation_config.is_none());
+    }
+
+    #[test]
+    fn test_analyze_tool_params_pass_generation_settings() {
+        let tool_params: AnalyzeVideoToolParams = serde_json::from_value(serde_json::json!({
+            "video": "gs://bucket/clip.mp4",
+            "safety_settings": [{"category": "HARM_CATEGORY_HARASSMENT", "threshold": "BLOCK_NONE"}],
+            "generation_config": {"temperature": 0.2, "seed": 9}
+        }))
+        .unwrap();
+
+        let analyze_params: MultimodalAnalyzeVideoParams = tool_params.into();
+        let safety_settings = analyze_params.safety_settings.as_deref().unwrap();
+        assert_eq!(safety_settings[0].category, "HARM_CATEGORY_HARASSMENT");
+        assert_eq!(safety_settings[0].threshold, "BLOCK_NONE");
+        let config = analyze_params.generation_config.as_ref().unwrap();
+        assert_eq!(config.temperature, Some(0.2));
+        assert_eq!(config.seed, Some(9));
+        assert!(config.top_k.is_none());
+        assert!(analyze_params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_format_describe_result() {
+        let result = ImageDescribeResult {
+            description: "A red bicycle.".to_string(),
+            mime_type: "image/jpeg".to_string(),
+            model: crate::handler::DEFAULT_DESCRIBE_MODEL.to_string(),
+            truncated: true,
+        };
+
+        let tool_result = format_describe_result(&result);
+        assert_eq!(tool_result.content.len(), 2);
+        assert_eq!(tool_result.content[0].as_text().unwrap().text, "A red bicycle.");
+
+        let structured = tool_result.structured_content.expect("structured content");
+        assert_eq!(structured["mime_type"], "image/jpeg");
+        assert_eq!(structured["truncated"], true);
+    }
+
+    #[test]
+    fn test_format_analysis_result_includes_usage() {
+        let result = VideoAnalysisResult {
+            answer: "Yes, a cat chases a red ball.".to_string(),
+            mime_type: "video/mp4".to_string(),
+            model: crate::handler::DEFAULT_DESCRIBE_MODEL.to_string(),
+            truncated: false,
+            usage: Some(crate::handler::TokenUsage {
+                prompt_tokens: 2071,
+                output_tokens: 9,
+                total_tokens: 2080,
+            }),
+        };
+
+        let tool_result = format_analysis_result(&result);
+        assert_eq!(tool_result.content.len(), 2);
+        assert_eq!(tool_result.content[0].as_text().unwrap().text, "Yes, a cat chases a red ball.");
+        assert_eq!(tool_result.content[1].as_text().unwrap().text, "Tokens: 2071 prompt, 9 output, 2080 total");
+
+        let structured = tool_result.structured_content.expect("structured content");
+        assert_eq!(structured["usage"]["total_tokens"], 2080);
+        assert_eq!(structured["mime_type"], "video/mp4");
+    }
+
+    #[test]
+    fn test_list_models_returns_capabilities() {
+        let server = MultimodalServer::new(test_config());
+        let tool_result = server.list_models().unwrap();
+
+        let structured = tool_result.structured_content.expect("structured content");
+        let models = structured["models"].as_array().expect("models array");
+        let pro = models.iter().find(|m| m["id"] == "gemini-2.5-pro").expect("pro listed");
+        assert_eq!(pro["aliases"][0], "pro");
+        assert_eq!(pro["supports_audio_input"], true);
+        assert_eq!(pro["supports_image_generation"], false);
+
+        let text = tool_result.content[0].as_text().expect("text content");
+        assert!(text.text.contains(crate::handler::DEFAULT_TTS_MODEL));
+    }
+
+    #[test]
+    fn test_format_image_result_reports_mode() {
+        let result = GeneratedImageOutcome {
+            output: ImageGenerateResult::LocalFile("/tmp/logo-blue.png".to_string()),
+            mode: crate::handler::ImageMode::Edit,
+            reference_images: 1,
+        };
+
+        let tool_result = format_image_result(result);
+        assert_eq!(tool_result.content[0].as_text().unwrap().text, "Image saved to: /tmp/logo-blue.png");
+
+        let structured = tool_result.structured_content.expect("structured content");
+        assert_eq!(structured["mode"], "edit");
+        assert_eq!(structured["reference_images"], 1);
+        assert_eq!(structured["output"], "/tmp/logo-blue.png");
+    }
+
+    #[test]
+    fn test_format_image_result_gcs_uri() {
+        let result = GeneratedImageOutcome {
+            output: ImageGenerateResult::GcsUri("gs://bucket/logo.png".to_string()),
+            mode: crate::handler::ImageMode::Generate,
+            reference_images: 0,
+        };
+
+        let tool_result = format_image_result(result);
+        assert_eq!(tool_result.content[0].as_text().unwrap().text, "Image uploaded to: gs://bucket/logo.png");
+        assert_eq!(tool_result.structured_content.expect("structured content")["output"], "gs://bucket/logo.png");
+    }
 }
@@ -30,7 +30,7 @@ fn get_test_config() -> Option<Config> {
         project_id,
         location: env::var("LOCATION").unwrap_or_else(|_| "us-central1".to_string()),
         gcs_bucket: env::var("GCS_BUCKET").ok(),
-        port: 8080,
+        ..Default::default()
     })
 }
 
@@ -78,10 +78,14 @@ async fn test_image_generation_base64() {
     let params = MultimodalImageParams {
         prompt: "A simple red circle on a white background".to_string(),
         model: "gemini-2.5-flash-image".to_string(),
+        reference_images: None,
         output_file: None,
+        output_uri: None,
+        safety_settings: None,
+        generation_config: None,
     };
 
-    let result = handler.generate_image(params).await;
+    let result = handler.generate_image(params).await.map(|outcome| outcome.output);
 
     match result {
         Ok(adk_rust_mcp_multimodal::ImageGenerateResult::Base64(image)) => {
@@ -114,10 +118,14 @@ async fn test_image_generation_to_file() {
     let params = MultimodalImageParams {
         prompt: "A simple blue square on a white background".to_string(),
         model: "gemini-2.5-flash-image".to_string(),
+        reference_images: None,
         output_file: Some(output_path.to_string_lossy().to_string()),
+        output_uri: None,
+        safety_settings: None,
+        generation_config: None,
     };
 
-    let result = handler.generate_image(params).await;
+    let result = handler.generate_image(params).await.map(|outcome| outcome.output);
 
     match result {
         Ok(adk_rust_mcp_multimodal::ImageGenerateResult::LocalFile(path)) => {
@@ -153,6 +161,9 @@ async fn test_tts_base64() {
         style: None,
         model: "gemini-2.5-flash-preview-tts".to_string(),
         output_file: None,
+        output_uri: None,
+        safety_settings: None,
+        generation_config: None,
     };
 
     let result = handler.synthesize_speech(params).await;
@@ -188,6 +199,9 @@ async fn test_tts_with_style() {
         style: Some("cheerful".to_string()),
         model: "gemini-2.5-flash-preview-tts".to_string(),
         output_file: None,
+        output_uri: None,
+        safety_settings: None,
+        generation_config: None,
     };
 
     let result = handler.synthesize_speech(params).await;
@@ -222,6 +236,9 @@ async fn test_tts_to_file() {
         style: None,
         model: "gemini-2.5-flash-preview-tts".to_string(),
         output_file: Some(output_path.to_string_lossy().to_string()),
+        output_uri: None,
+        safety_settings: None,
+        generation_config: None,
     };
 
     let result = handler.synthesize_speech(params).await;
@@ -254,7 +271,7 @@ async fn test_list_voices() {
         .await
         .expect("Failed to create handler");
 
-    let voices = handler.list_voices();
+    let voices = handler.list_voices(false).await.voices;
 
     assert!(!voices.is_empty(), "Should have at least one voice");
     println!("Available voices: {:?}", voices);
@@ -274,7 +291,7 @@ async fn test_list_language_codes() {
         .await
         .expect("Failed to create handler");
 
-    let codes = handler.list_language_codes();
+    let codes = handler.list_language_codes(false).await.language_codes;
 
     assert!(!codes.is_empty(), "Should have at least one language code");
     println!("Supported language codes: {:?}", codes);
@@ -293,7 +310,11 @@ fn test_image_params_validation_empty_prompt() {
     let params = MultimodalImageParams {
         prompt: "".to_string(),
         model: "test-model".to_string(),
+        reference_images: None,
         output_file: None,
+        output_uri: None,
+        safety_settings: None,
+        generation_config: None,
     };
 
     let result = params.validate();
@@ -310,6 +331,9 @@ fn test_tts_params_validation_empty_text() {
         style: None,
         model: "test-model".to_string(),
         output_file: None,
+        output_uri: None,
+        safety_settings: None,
+        generation_config: None,
     };
 
     let result = params.validate();
@@ -326,6 +350,9 @@ fn test_tts_params_validation_invalid_voice() {
         style: None,
         model: "test-model".to_string(),
         output_file: None,
+        output_uri: None,
+        safety_settings: None,
+        generation_config: None,
     };
 
     let result = params.validate();
@@ -342,6 +369,9 @@ fn test_tts_params_validation_invalid_style() {
         style: Some("invalid_style".to_string()),
         model: "test-model".to_string(),
         output_file: None,
+        output_uri: None,
+        safety_settings: None,
+        generation_config: None,
     };
 
     let result = params.validate();
@@ -356,8 +386,11 @@ fn test_tts_params_validation_valid() {
         text: "Hello world".to_string(),
         voice: Some("Kore".to_string()),
         style: Some("cheerful".to_string()),
-        model: "test-model".to_string(),
+        model: "gemini-2.5-flash-preview-tts".to_string(),
         output_file: None,
+        output_uri: None,
+        safety_settings: None,
+        generation_config: None,
     };
 
     assert!(params.validate().is_ok());
@@ -4,15 +4,21 @@
 //! music generation using Google's Vertex AI Lyria API.
 
 use adk_rust_mcp_common::auth::AuthProvider;
-use adk_rust_mcp_common::config::Config;
+use adk_rust_mcp_common::cache::{self, CachedResult, ResultCache};
+use adk_rust_mcp_common::config::{self, Config};
 use adk_rust_mcp_common::error::Error;
+use adk_rust_mcp_common::ffmpeg;
 use adk_rust_mcp_common::gcs::{GcsClient, GcsUri};
-use adk_rust_mcp_common::models::{LyriaModel, ModelRegistry};
+use adk_rust_mcp_common::metadata::{self, GenerationMetadata};
+use adk_rust_mcp_common::models::{LyriaModel, ModelRegistry, LYRIA_1_0};
+use adk_rust_mcp_common::retry::{RetryPolicy, send_with_retry};
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
-use tracing::{debug, info, instrument};
+use std::sync::Arc;
+use tracing::{debug, info, instrument, warn};
+use uuid::Uuid;
 
 /// Default model for music generation.
 pub const DEFAULT_MODEL: &str = "lyria-1.0";
@@ -20,8 +26,100 @@ pub const DEFAULT_MODEL: &str = "lyria-1.0";
 /// Minimum number of samples that can be generated.
 pub const MIN_SAMPLE_COUNT: u8 = 1;
 
-/// Maximum number of samples that can be generated.
-pub const MAX_SAMPLE_COUNT: u8 = 4;
+/// Maximum number of samples the default model generates per request.
+pub const MAX_SAMPLE_COUNT: u8 = LYRIA_1_0.max_samples;
+
+/// Default MP3 bitrate when `output_format` is mp3.
+pub const DEFAULT_MP3_BITRATE: &str = "192k";
+
+/// Lowest and highest MP3 bitrates (kbps) libmp3lame accepts.
+pub const MP3_BITRATE_RANGE_KBPS: (u32, u32) = (32, 320);
+
+/// Object prefix for uploads to the configured bucket without an explicit URI.
+pub const DEFAULT_GCS_PREFIX: &str = "music";
+
+/// Slowest and fastest accepted tempo in beats per minute.
+pub const BPM_RANGE: (u16, u16) = (40, 220);
+
+/// Key tonics accepted in `key`, combined with a mode (e.g. "F# minor").
+pub const MUSICAL_KEY_TONICS: &[&str] = &[
+    "C", "C#", "Db", "D", "D#", "Eb", "E", "F", "F#", "Gb", "G", "G#", "Ab", "A", "A#", "Bb", "B",
+];
+
+/// Key modes accepted in `key`.
+pub const MUSICAL_KEY_MODES: &[&str] = &["major", "minor"];
+
+/// Canonical spelling of a musical key such as "a minor" or "Bb Major"
+/// (`A minor`, `Bb major`), or `None` if the tonic or mode is not recognized.
+pub fn normalize_musical_key(key: &str) -> Option<String> {
+    let mut parts = key.split_whitespace();
+    let (tonic, mode) = (parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+    let tonic = MUSICAL_KEY_TONICS.iter().find(|t| t.eq_ignore_ascii_case(tonic))?;
+    let mode = MUSICAL_KEY_MODES.iter().find(|m| m.eq_ignore_ascii_case(mode))?;
+    Some(format!("{} {}", tonic, mode))
+}
+
+/// How `bpm` and `key` reach the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlsMode {
+    /// Sent as request parameters, for models with structured controls.
+    Parameters,
+    /// Appended to the prompt text.
+    Prompt,
+}
+
+/// Audio encoding of the returned or saved samples.
+///
+/// Lyria always produces WAV; mp3 is encoded locally with ffmpeg.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(tag = "format", rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Uncompressed WAV as returned by the API.
+    #[default]
+    Wav,
+    /// MP3 at the given bitrate, e.g. "128k" (default: 192k).
+    Mp3 {
+        /// Target bitrate such as "128k" or "320k".
+        #[serde(default = "default_mp3_bitrate")]
+        bitrate: String,
+    },
+}
+
+fn default_mp3_bitrate() -> String {
+    DEFAULT_MP3_BITRATE.to_string()
+}
+
+impl OutputFormat {
+    /// Whether this is the API's native WAV output.
+    pub fn is_wav(&self) -> bool {
+        matches!(self, OutputFormat::Wav)
+    }
+
+    /// MIME type of files in this format.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            OutputFormat::Wav => "audio/wav",
+            OutputFormat::Mp3 { .. } => "audio/mpeg",
+        }
+    }
+
+    /// File extension used when naming indexed outputs.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Wav => "wav",
+            OutputFormat::Mp3 { .. } => "mp3",
+        }
+    }
+}
+
+/// Parse an MP3 bitrate such as "192k" or "192" into kbps.
+fn parse_mp3_bitrate_kbps(bitrate: &str) -> Option<u32> {
+    let digits = bitrate.trim().strip_suffix(['k', 'K']).unwrap_or(bitrate.trim());
+    digits.parse().ok()
+}
 
 /// Music generation parameters.
 ///
@@ -36,6 +134,7 @@ pub struct MusicGenerateParams {
     pub negative_prompt: Option<String>,
 
     /// Random seed for reproducible generation.
+    /// With `sample_count > 1`, sample `i` is generated with `seed + i`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub seed: Option<i64>,
 
@@ -43,15 +142,53 @@ pub struct MusicGenerateParams {
     #[serde(default = "default_sample_count")]
     pub sample_count: u8,
 
+    /// Requested length of each sample in seconds.
+    /// Must be within the model's supported range; the model default is used when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<u32>,
+
+    /// Tempo in beats per minute (40-220).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bpm: Option<u16>,
+
+    /// Musical key, a tonic and mode such as "A minor" or "F# major".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+
     /// Output file path for saving the WAV locally.
     /// If not specified and output_gcs_uri is not specified, returns base64-encoded data.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_file: Option<String>,
 
     /// Output GCS URI for saving the WAV to cloud storage.
-    /// Format: gs://bucket/path/to/output.wav
+    /// Format: gs://bucket/path/to/output.wav, or an object name in `GCS_BUCKET`
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_gcs_uri: Option<String>,
+
+    /// Upload to `gs://<GCS_BUCKET>/music/<uuid>.<ext>` when no
+    /// `output_gcs_uri` is given. Requires `GCS_BUCKET`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub upload: bool,
+
+    /// Bypass the result cache for this request.
+    /// Requests with a seed and an output destination are cached by default.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_cache: bool,
+
+    /// Vertex AI region for this request (e.g. "us-central1").
+    /// Falls back to `LOCATION` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+
+    /// Write a `<name>.json` sidecar with the generation settings next to
+    /// each saved sample. Requires `output_file`, `output_gcs_uri` or `upload`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub write_metadata: bool,
+
+    /// Encoding of the returned or saved audio (default: WAV).
+    /// MP3 requires ffmpeg on the server.
+    #[serde(default, skip_serializing_if = "OutputFormat::is_wav")]
+    pub output_format: OutputFormat,
 }
 
 fn default_sample_count() -> u8 {
@@ -90,15 +227,95 @@ impl MusicGenerateParams {
             });
         }
 
-        // Validate sample_count range
-        if self.sample_count < MIN_SAMPLE_COUNT || self.sample_count > MAX_SAMPLE_COUNT {
-            errors.push(ValidationError {
-                field: "sample_count".to_string(),
-                message: format!(
-                    "sample_count must be between {} and {}, got {}",
-                    MIN_SAMPLE_COUNT, MAX_SAMPLE_COUNT, self.sample_count
-                ),
-            });
+        if let Some(model) = self.get_model() {
+            // Validate prompt length as sent, including any appended tempo and key
+            let prompt_length = match self.controls_mode() {
+                Some(ControlsMode::Prompt) => self.prompt_with_controls().chars().count(),
+                _ => self.prompt.chars().count(),
+            };
+            if prompt_length > model.max_prompt_length {
+                errors.push(ValidationError {
+                    field: "prompt".to_string(),
+                    message: format!(
+                        "Prompt length {} exceeds maximum {} characters for model {}",
+                        prompt_length, model.max_prompt_length, model.id
+                    ),
+                });
+            }
+
+            if let Some(negative_prompt) = &self.negative_prompt {
+                let length = negative_prompt.chars().count();
+                if length > model.max_prompt_length {
+                    errors.push(ValidationError {
+                        field: "negative_prompt".to_string(),
+                        message: format!(
+                            "Negative prompt length {} exceeds maximum {} characters for model {}",
+                            length, model.max_prompt_length, model.id
+                        ),
+                    });
+                }
+            }
+
+            // Validate sample_count against the model's limit
+            if self.sample_count < MIN_SAMPLE_COUNT || self.sample_count > model.max_samples {
+                errors.push(ValidationError {
+                    field: "sample_count".to_string(),
+                    message: format!(
+                        "sample_count must be between {} and {} for model {}, got {}",
+                        MIN_SAMPLE_COUNT, model.max_samples, model.id, self.sample_count
+                    ),
+                });
+            }
+        }
+
+        // Each sample gets its own seed, which must not overflow
+        if let Some(seed) = self.seed {
+            if self.sample_count > 1 && seed.checked_add(i64::from(self.sample_count) - 1).is_none() {
+                errors.push(ValidationError {
+                    field: "seed".to_string(),
+                    message: format!(
+                        "seed + sample_count - 1 must not exceed {} (sample i uses seed + i), got seed {} with sample_count {}",
+                        i64::MAX, seed, self.sample_count
+                    ),
+                });
+            }
+        }
+
+        // Validate duration against the model's range
+        if let (Some(duration), Some(model)) = (self.duration_seconds, self.get_model()) {
+            if duration < model.min_duration_seconds || duration > model.max_duration_seconds {
+                errors.push(ValidationError {
+                    field: "duration_seconds".to_string(),
+                    message: format!(
+                        "duration_seconds must be between {} and {} for model {}, got {}",
+                        model.min_duration_seconds, model.max_duration_seconds, model.id, duration
+                    ),
+                });
+            }
+        }
+
+        if let Some(bpm) = self.bpm {
+            let (min, max) = BPM_RANGE;
+            if !(min..=max).contains(&bpm) {
+                errors.push(ValidationError {
+                    field: "bpm".to_string(),
+                    message: format!("bpm must be between {} and {}, got {}", min, max, bpm),
+                });
+            }
+        }
+
+        if let Some(key) = &self.key {
+            if normalize_musical_key(key).is_none() {
+                errors.push(ValidationError {
+                    field: "key".to_string(),
+                    message: format!(
+                        "key must be a tonic ({}) followed by {}, got '{}'",
+                        MUSICAL_KEY_TONICS.join(", "),
+                        MUSICAL_KEY_MODES.join(" or "),
+                        key
+                    ),
+                });
+            }
         }
 
         // Validate output_gcs_uri format if provided
@@ -114,6 +331,38 @@ impl MusicGenerateParams {
             }
         }
 
+        // Validate the region override
+        if let Some(location) = &self.location {
+            if let Err(message) = config::validate_location(location) {
+                errors.push(ValidationError {
+                    field: "location".to_string(),
+                    message,
+                });
+            }
+        }
+
+        // Validate the MP3 bitrate
+        if let OutputFormat::Mp3 { bitrate } = &self.output_format {
+            let (min, max) = MP3_BITRATE_RANGE_KBPS;
+            if !parse_mp3_bitrate_kbps(bitrate).is_some_and(|kbps| (min..=max).contains(&kbps)) {
+                errors.push(ValidationError {
+                    field: "output_format.bitrate".to_string(),
+                    message: format!(
+                        "bitrate must be between {}k and {}k (e.g. \"192k\"), got '{}'",
+                        min, max, bitrate
+                    ),
+                });
+            }
+        }
+
+        // Sidecars are written next to saved outputs
+        if self.write_metadata && !self.has_destination() {
+            errors.push(ValidationError {
+                field: "write_metadata".to_string(),
+                message: "write_metadata requires output_file, output_gcs_uri or upload".to_string(),
+            });
+        }
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -121,6 +370,49 @@ impl MusicGenerateParams {
         }
     }
 
+    /// Seed of each sample, or `None` for unseeded requests.
+    ///
+    /// Sample `i` uses `seed + i`, so any one variation can be regenerated
+    /// on its own with `sample_count: 1`.
+    pub fn sample_seeds(&self) -> Option<Vec<i64>> {
+        let seed = self.seed?;
+        Some((0..i64::from(self.sample_count)).map(|i| seed.saturating_add(i)).collect())
+    }
+
+    /// Whether the samples are saved somewhere rather than returned inline.
+    pub fn has_destination(&self) -> bool {
+        self.output_file.is_some() || self.output_gcs_uri.is_some() || self.upload
+    }
+
+    /// How `bpm` and `key` are passed to the model, or `None` when neither is set.
+    pub fn controls_mode(&self) -> Option<ControlsMode> {
+        if self.bpm.is_none() && self.key.is_none() {
+            return None;
+        }
+        if self.get_model().is_some_and(|m| m.supports_structured_controls) {
+            Some(ControlsMode::Parameters)
+        } else {
+            Some(ControlsMode::Prompt)
+        }
+    }
+
+    /// The prompt with `bpm` and `key` appended, e.g.
+    /// `"Calm piano, at 120 BPM, in A minor"`.
+    ///
+    /// Trailing whitespace and periods are trimmed from the prompt first so the
+    /// result is the same however the prompt ends.
+    pub fn prompt_with_controls(&self) -> String {
+        let mut prompt = self.prompt.trim_end().trim_end_matches('.').trim_end().to_string();
+        if let Some(bpm) = self.bpm {
+            prompt.push_str(&format!(", at {} BPM", bpm));
+        }
+        if let Some(key) = &self.key {
+            let key = normalize_musical_key(key).unwrap_or_else(|| key.clone());
+            prompt.push_str(&format!(", in {}", key));
+        }
+        prompt
+    }
+
     /// Get the resolved model definition.
     pub fn get_model(&self) -> Option<&'static LyriaModel> {
         ModelRegistry::resolve_lyria(DEFAULT_MODEL)
@@ -137,8 +429,10 @@ pub struct MusicHandler {
     pub gcs: GcsClient,
     /// HTTP client for API requests.
     pub http: reqwest::Client,
-    /// Authentication provider.
-    pub auth: AuthProvider,
+    /// Authentication provider (shared with the GCS client).
+    pub auth: Arc<AuthProvider>,
+    /// Cache of deterministic generation results.
+    pub cache: Arc<dyn ResultCache>,
 }
 
 impl MusicHandler {
@@ -150,36 +444,48 @@ impl MusicHandler {
     pub async fn new(config: Config) -> Result<Self, Error> {
         debug!("Initializing MusicHandler");
 
-        let auth = AuthProvider::new().await?;
-        let gcs = GcsClient::with_auth(AuthProvider::new().await?);
-        let http = reqwest::Client::new();
+        let auth = Arc::new(AuthProvider::new().await?);
+        let http = config.http.build()?;
+        let gcs = GcsClient::with_client(Arc::clone(&auth), http.clone());
+        let cache = cache::result_cache_from_config(&auth, &config)?;
 
         Ok(Self {
             config,
             gcs,
             http,
             auth,
+            cache,
         })
     }
 
     /// Create a new MusicHandler with provided dependencies (for testing).
     #[cfg(test)]
-    pub fn with_deps(config: Config, gcs: GcsClient, http: reqwest::Client, auth: AuthProvider) -> Self {
+    pub fn with_deps(config: Config, gcs: GcsClient, http: reqwest::Client, auth: Arc<AuthProvider>) -> Self {
         Self {
             config,
             gcs,
             http,
             auth,
+            cache: Arc::new(cache::InMemoryResultCache::new()),
         }
     }
 
+    /// Replace the result cache.
+    pub fn with_cache(mut self, cache: Arc<dyn ResultCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
     /// Get the Vertex AI Lyria API endpoint.
-    pub fn get_endpoint(&self) -> String {
+    ///
+    /// `location` overrides the configured region for a single request.
+    pub fn get_endpoint(&self, location: Option<&str>) -> String {
+        let location = self.config.location_or(location);
         format!(
             "https://{}-aiplatform.googleapis.com/v1/projects/{}/locations/{}/publishers/google/models/{}:predict",
-            self.config.location,
+            location,
             self.config.project_id,
-            self.config.location,
+            location,
             "lyria-002"
         )
     }
@@ -193,59 +499,99 @@ impl MusicHandler {
     /// * `Ok(MusicGenerateResult)` - Generated music with their data or paths
     /// * `Err(Error)` - If validation fails, API call fails, or output handling fails
     #[instrument(level = "info", name = "generate_music", skip(self, params))]
-    pub async fn generate_music(&self, params: MusicGenerateParams) -> Result<MusicGenerateResult, Error> {
+    pub async fn generate_music(&self, mut params: MusicGenerateParams) -> Result<MusicGenerateResult, Error> {
+        // Bare object names go to the configured bucket
+        params.output_gcs_uri = params
+            .output_gcs_uri
+            .map(|uri| self.config.resolve_output_uri(&uri))
+            .transpose()?;
+
         // Validate parameters
         params.validate().map_err(|errors| {
             let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
             Error::validation(messages.join("; "))
         })?;
 
-        info!(sample_count = params.sample_count, "Generating music with Lyria API");
+        let cache_key = Self::result_cache_key(&params)?;
+        if let Some(key) = &cache_key {
+            if let Some(result) = self.cached_result(key, &params).await {
+                info!(cache_key = %key, "Returning cached music result");
+                return Ok(result);
+            }
+        }
 
-        // Build the API request
-        let request = LyriaRequest {
-            instances: vec![LyriaInstance {
-                prompt: params.prompt.clone(),
-                negative_prompt: params.negative_prompt.clone(),
-            }],
-            parameters: LyriaParameters {
-                sample_count: params.sample_count,
-                seed: params.seed,
-            },
-        };
+        // Synthesized after the cache lookup so repeated requests share a key
+        if params.upload && params.output_gcs_uri.is_none() {
+            params.output_gcs_uri = Some(self.default_output_uri(&params.output_format)?);
+        }
+
+        info!(sample_count = params.sample_count, duration_seconds = ?params.duration_seconds, "Generating music with Lyria API");
 
         // Get auth token
         let token = self.auth.get_token(&["https://www.googleapis.com/auth/cloud-platform"]).await?;
+        let endpoint = self.get_endpoint(params.location.as_deref());
+
+        // Seeded requests make one call per sample so each has a known seed
+        let mut samples = Vec::new();
+        for request in Self::build_requests(&params) {
+            let seed = request.parameters.seed;
+            let mut batch = self.predict(&endpoint, &token, &request).await?;
+            for sample in &mut batch {
+                sample.seed = seed;
+            }
+            samples.extend(batch);
+        }
 
-        // Make API request
-        let endpoint = self.get_endpoint();
-        debug!(endpoint = %endpoint, "Calling Lyria API");
+        info!(count = samples.len(), "Received audio samples from API");
 
-        let response = self.http
-            .post(&endpoint)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| Error::api(&endpoint, 0, format!("Request failed: {}", e)))?;
+        let samples = match &params.output_format {
+            OutputFormat::Wav => samples,
+            OutputFormat::Mp3 { bitrate } => Self::encode_mp3(&self.config.ffmpeg_binary(), samples, bitrate).await?,
+        };
+
+        // Handle output based on params
+        let result = self.handle_output(samples, &params).await?;
+
+        if params.write_metadata {
+            self.write_metadata(&params, &result).await;
+        }
+
+        if let Some(key) = &cache_key {
+            self.store_result(key, &result).await;
+        }
+
+        Ok(result)
+    }
+
+    /// Send one Lyria request and decode the returned samples.
+    async fn predict(&self, endpoint: &str, token: &str, request: &LyriaRequest) -> Result<Vec<GeneratedAudio>, Error> {
+        debug!(endpoint = %endpoint, seed = ?request.parameters.seed, "Calling Lyria API");
+
+        let response = send_with_retry(&RetryPolicy::from_config(&self.config), endpoint, || {
+            self.http
+                .post(endpoint)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .json(request)
+        })
+        .await?;
 
         let status = response.status();
         if !status.is_success() {
             let body = response.text().await.unwrap_or_default();
-            return Err(Error::api(&endpoint, status.as_u16(), body));
+            return Err(Error::api_response(endpoint, status.as_u16(), body));
         }
 
         // Get raw response for debugging
         let response_text = response.text().await.map_err(|e| {
-            Error::api(&endpoint, status.as_u16(), format!("Failed to read response: {}", e))
+            Error::api(endpoint, status.as_u16(), format!("Failed to read response: {}", e))
         })?;
         
         debug!(response = %response_text.chars().take(500).collect::<String>(), "Raw Lyria API response");
 
         // Parse response
         let api_response: LyriaResponse = serde_json::from_str(&response_text).map_err(|e| {
-            Error::api(&endpoint, status.as_u16(), format!("Failed to parse response: {}. Raw: {}", e, &response_text[..response_text.len().min(500)]))
+            Error::api(endpoint, status.as_u16(), format!("Failed to parse response: {}. Raw: {}", e, &response_text[..response_text.len().min(500)]))
         })?;
 
         // Extract audio samples from response
@@ -253,21 +599,173 @@ impl MusicHandler {
             .predictions
             .into_iter()
             .filter_map(|p| {
-                p.bytes_base64_encoded.map(|data| GeneratedAudio {
-                    data,
-                    mime_type: p.mime_type.unwrap_or_else(|| "audio/wav".to_string()),
+                p.bytes_base64_encoded.map(|data| {
+                    GeneratedAudio::from_base64(data, p.mime_type.unwrap_or_else(|| "audio/wav".to_string()))
                 })
             })
             .collect();
 
         if samples.is_empty() {
-            return Err(Error::api(&endpoint, 200, "No audio samples returned from API"));
+            return Err(Error::api(endpoint, 200, "No audio samples returned from API"));
         }
 
-        info!(count = samples.len(), "Received audio samples from API");
+        Ok(samples)
+    }
 
-        // Handle output based on params
-        self.handle_output(samples, &params).await
+    /// Build the Lyria requests for `params`: one per sample when seeded
+    /// (Lyria does not combine `seed` with `sample_count`), otherwise one
+    /// request for all samples.
+    fn build_requests(params: &MusicGenerateParams) -> Vec<LyriaRequest> {
+        match params.sample_seeds() {
+            Some(seeds) => seeds
+                .into_iter()
+                .map(|seed| {
+                    let mut request = Self::build_request(params);
+                    request.parameters.seed = Some(seed);
+                    request.parameters.sample_count = 1;
+                    request
+                })
+                .collect(),
+            None => vec![Self::build_request(params)],
+        }
+    }
+
+    /// Build the Lyria request, passing `bpm` and `key` as parameters or in
+    /// the prompt depending on the model.
+    fn build_request(params: &MusicGenerateParams) -> LyriaRequest {
+        let mode = params.controls_mode();
+        let structured = mode == Some(ControlsMode::Parameters);
+        let prompt = match mode {
+            Some(ControlsMode::Prompt) => params.prompt_with_controls(),
+            _ => params.prompt.clone(),
+        };
+        if let Some(mode) = mode {
+            debug!(?mode, "Applying tempo and key controls");
+        }
+
+        LyriaRequest {
+            instances: vec![LyriaInstance {
+                prompt,
+                negative_prompt: params.negative_prompt.clone(),
+            }],
+            parameters: LyriaParameters {
+                sample_count: params.sample_count,
+                seed: params.seed,
+                duration_seconds: params.duration_seconds,
+                bpm: params.bpm.filter(|_| structured),
+                key: params
+                    .key
+                    .as_deref()
+                    .and_then(normalize_musical_key)
+                    .filter(|_| structured),
+            },
+        }
+    }
+
+    /// Re-encode WAV samples as MP3, keeping the duration, sample rate and
+    /// channels read from the WAV headers.
+    async fn encode_mp3(
+        binary: &str,
+        samples: Vec<GeneratedAudio>,
+        bitrate: &str,
+    ) -> Result<Vec<GeneratedAudio>, Error> {
+        let mut encoded = Vec::with_capacity(samples.len());
+        for sample in samples {
+            let wav = BASE64.decode(&sample.data).map_err(|e| {
+                Error::validation(format!("Invalid base64 data: {}", e))
+            })?;
+            let mp3 = ffmpeg::encode_mp3(binary, &wav, bitrate).await.map_err(|e| {
+                Error::ffmpeg(format!("Could not convert generated music to MP3: {}", e))
+            })?;
+            debug!(wav_bytes = wav.len(), mp3_bytes = mp3.len(), "Encoded sample as MP3");
+            encoded.push(GeneratedAudio {
+                data: BASE64.encode(&mp3),
+                mime_type: OutputFormat::Mp3 { bitrate: bitrate.to_string() }.mime_type().to_string(),
+                size_bytes: Some(mp3.len() as u64),
+                ..sample
+            });
+        }
+        Ok(encoded)
+    }
+
+    /// Generated upload location in the configured bucket, used for `upload`
+    /// requests without an `output_gcs_uri`.
+    ///
+    /// # Errors
+    /// Returns `Error::Validation` if `GCS_BUCKET` is not configured.
+    fn default_output_uri(&self, format: &OutputFormat) -> Result<String, Error> {
+        let object = format!("{}/{}.{}", DEFAULT_GCS_PREFIX, Uuid::new_v4(), format.extension());
+        self.config.resolve_output_uri(&object)
+    }
+
+    /// Compute the result cache key for a generation request.
+    ///
+    /// Returns `None` when the request must not be cached: no seed (the output
+    /// is nondeterministic), no output destination (nothing to point back to),
+    /// or `no_cache` is set.
+    fn result_cache_key(params: &MusicGenerateParams) -> Result<Option<String>, Error> {
+        if params.no_cache || params.seed.is_none() {
+            return Ok(None);
+        }
+        if !params.has_destination() {
+            return Ok(None);
+        }
+
+        cache::cache_key("music_generate", params).map(Some)
+    }
+
+    /// Look up a cached result, treating cache errors and stale local files as misses.
+    async fn cached_result(&self, key: &str, params: &MusicGenerateParams) -> Option<MusicGenerateResult> {
+        let cached = match self.cache.get(key).await {
+            Ok(Some(cached)) => cached,
+            Ok(None) => return None,
+            Err(e) => {
+                warn!(error = %e, "Result cache lookup failed");
+                return None;
+            }
+        };
+
+        if params.output_gcs_uri.is_some() || params.upload {
+            Some(MusicGenerateResult::GcsUris(SavedAudio::cached(cached.uris, params)))
+        } else if cached.local_outputs_exist() {
+            Some(MusicGenerateResult::LocalFiles(SavedAudio::cached(cached.uris, params)))
+        } else {
+            None
+        }
+    }
+
+    /// Write a metadata sidecar next to each saved sample. Failures are logged,
+    /// not returned, since the audio itself was written.
+    async fn write_metadata(&self, params: &MusicGenerateParams, result: &MusicGenerateResult) {
+        let saved = match result {
+            MusicGenerateResult::GcsUris(saved) | MusicGenerateResult::LocalFiles(saved) => saved,
+            MusicGenerateResult::Base64(_) => return,
+        };
+        let model = params.get_model().map_or(DEFAULT_MODEL, |m| m.id);
+        for audio in saved {
+            let metadata = GenerationMetadata {
+                negative_prompt: params.negative_prompt.clone(),
+                seed: audio.seed.or(params.seed),
+                ..GenerationMetadata::new(&params.prompt, model)
+            };
+            match metadata::write_sidecar(&self.gcs, &audio.location, &metadata).await {
+                Ok(sidecar) => debug!(sidecar = %sidecar, "Wrote generation metadata"),
+                Err(e) => warn!(output = %audio.location, error = %e, "Failed to write generation metadata"),
+            }
+        }
+    }
+
+    /// Record where a result was written. Failures are logged, not returned.
+    async fn store_result(&self, key: &str, result: &MusicGenerateResult) {
+        let uris = match result {
+            MusicGenerateResult::GcsUris(saved) | MusicGenerateResult::LocalFiles(saved) => {
+                saved.iter().map(|audio| audio.location.clone()).collect()
+            }
+            MusicGenerateResult::Base64(_) => return,
+        };
+        if let Err(e) = self.cache.put(key, &CachedResult { uris }).await {
+            warn!(error = %e, "Failed to store result in cache");
+        }
     }
 
     /// Handle output of generated audio samples based on params.
@@ -277,13 +775,14 @@ impl MusicHandler {
         params: &MusicGenerateParams,
     ) -> Result<MusicGenerateResult, Error> {
         // If output_gcs_uri is specified, upload to GCS
+        let ext = params.output_format.extension();
         if let Some(output_uri) = &params.output_gcs_uri {
-            return self.upload_to_gcs(samples, output_uri).await;
+            return self.upload_to_gcs(samples, output_uri, ext).await;
         }
 
         // If output_file is specified, save to local file
         if let Some(output_file) = &params.output_file {
-            return self.save_to_file(samples, output_file).await;
+            return self.save_to_file(samples, output_file, ext).await;
         }
 
         // Otherwise, return base64-encoded data
@@ -295,8 +794,9 @@ impl MusicHandler {
         &self,
         samples: Vec<GeneratedAudio>,
         output_uri: &str,
+        ext: &str,
     ) -> Result<MusicGenerateResult, Error> {
-        let mut uris = Vec::new();
+        let mut saved = Vec::new();
 
         for (i, sample) in samples.iter().enumerate() {
             // Decode base64 data
@@ -310,21 +810,30 @@ impl MusicHandler {
             } else {
                 // Add index suffix for multiple samples
                 // Handle GCS URIs properly - don't use Path which treats gs:// as filesystem path
-                Self::add_index_suffix_to_gcs_uri(output_uri, i, "audio", "wav")
+                Self::add_index_suffix_to_uri(output_uri, i, "audio", ext)
             };
 
             // Parse GCS URI and upload
             let gcs_uri = GcsUri::parse(&uri)?;
             self.gcs.upload(&gcs_uri, &data, &sample.mime_type).await?;
-            uris.push(uri);
+            saved.push(SavedAudio {
+                location: uri,
+                seed: sample.seed,
+                mime_type: sample.mime_type.clone(),
+                size_bytes: Some(data.len() as u64),
+                duration_seconds: sample.duration_seconds,
+                sample_rate: sample.sample_rate,
+                channels: sample.channels,
+            });
         }
 
-        info!(count = uris.len(), "Uploaded audio samples to GCS");
-        Ok(MusicGenerateResult::GcsUris(uris))
+        info!(count = saved.len(), "Uploaded audio samples to GCS");
+        Ok(MusicGenerateResult::GcsUris(saved))
     }
 
-    /// Add an index suffix to a GCS URI for multi-output scenarios.
-    fn add_index_suffix_to_gcs_uri(uri: &str, index: usize, default_stem: &str, default_ext: &str) -> String {
+    /// Add an index suffix to a URI or path for multi-output scenarios.
+    /// Handles both GCS URIs (gs://bucket/path) and local paths correctly.
+    fn add_index_suffix_to_uri(uri: &str, index: usize, default_stem: &str, default_ext: &str) -> String {
         // For GCS URIs, extract the path portion after gs://bucket/
         if let Some(stripped) = uri.strip_prefix("gs://") {
             if let Some(slash_pos) = stripped.find('/') {
@@ -357,8 +866,16 @@ impl MusicHandler {
                 format!("{}/{}_{}.{}", uri, default_stem, index, default_ext)
             }
         } else {
-            // Shouldn't happen since we validate GCS URIs, but handle gracefully
-            format!("{}_{}", uri, index)
+            // Local filesystem path - use Path
+            let path = Path::new(uri);
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(default_stem);
+            let ext = path.extension().and_then(|s| s.to_str()).unwrap_or(default_ext);
+            let parent = path.parent().and_then(|p| p.to_str()).unwrap_or("");
+            if parent.is_empty() {
+                format!("{}_{}.{}", stem, index, ext)
+            } else {
+                format!("{}/{}_{}.{}", parent, stem, index, ext)
+            }
         }
     }
 
@@ -367,8 +884,12 @@ impl MusicHandler {
         &self,
         samples: Vec<GeneratedAudio>,
         output_file: &str,
+        ext: &str,
     ) -> Result<MusicGenerateResult, Error> {
-        let mut paths = Vec::new();
+        let output_file = self.config.resolve_local_path(output_file);
+        let output_file = output_file.to_string_lossy();
+        let output_file = output_file.as_ref();
+        let mut saved = Vec::new();
 
         for (i, sample) in samples.iter().enumerate() {
             // Decode base64 data
@@ -380,16 +901,7 @@ impl MusicHandler {
             let path = if samples.len() == 1 {
                 output_file.to_string()
             } else {
-                // Add index suffix for multiple samples
-                let p = Path::new(output_file);
-                let stem = p.file_stem().and_then(|s| s.to_str()).unwrap_or("audio");
-                let ext = p.extension().and_then(|s| s.to_str()).unwrap_or("wav");
-                let parent = p.parent().and_then(|p| p.to_str()).unwrap_or("");
-                if parent.is_empty() {
-                    format!("{}_{}.{}", stem, i, ext)
-                } else {
-                    format!("{}/{}_{}.{}", parent, stem, i, ext)
-                }
+                Self::add_index_suffix_to_uri(output_file, i, "audio", ext)
             };
 
             // Ensure parent directory exists
@@ -401,11 +913,19 @@ impl MusicHandler {
 
             // Write to file
             tokio::fs::write(&path, &data).await?;
-            paths.push(path);
+            saved.push(SavedAudio {
+                location: path,
+                seed: sample.seed,
+                mime_type: sample.mime_type.clone(),
+                size_bytes: Some(data.len() as u64),
+                duration_seconds: sample.duration_seconds,
+                sample_rate: sample.sample_rate,
+                channels: sample.channels,
+            });
         }
 
-        info!(count = paths.len(), "Saved audio samples to local files");
-        Ok(MusicGenerateResult::LocalFiles(paths))
+        info!(count = saved.len(), "Saved audio samples to local files");
+        Ok(MusicGenerateResult::LocalFiles(saved))
     }
 }
 
@@ -442,6 +962,15 @@ pub struct LyriaParameters {
     /// Random seed for reproducibility
     #[serde(skip_serializing_if = "Option::is_none")]
     pub seed: Option<i64>,
+    /// Requested audio length in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<u32>,
+    /// Tempo in beats per minute (structured-control models only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bpm: Option<u16>,
+    /// Musical key (structured-control models only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
 }
 
 /// Vertex AI Lyria API response.
@@ -472,6 +1001,77 @@ pub struct GeneratedAudio {
     pub data: String,
     /// MIME type of the audio
     pub mime_type: String,
+    /// Size of the decoded audio in bytes (`None` if the data is not valid base64)
+    pub size_bytes: Option<u64>,
+    /// Length of the audio, read from the WAV header (`None` if unreadable)
+    pub duration_seconds: Option<f64>,
+    /// Sample rate in Hz, read from the WAV header (`None` if unreadable)
+    pub sample_rate: Option<u32>,
+    /// Number of channels, read from the WAV header (`None` if unreadable)
+    pub channels: Option<u16>,
+    /// Seed this sample was generated with (`None` for unseeded requests)
+    pub seed: Option<i64>,
+}
+
+impl GeneratedAudio {
+    /// Wrap base64 audio returned by the API, reading its size and WAV header.
+    pub fn from_base64(data: String, mime_type: String) -> Self {
+        let bytes = BASE64.decode(&data).ok();
+        let header = bytes.as_deref().and_then(parse_wav_header);
+        Self {
+            size_bytes: bytes.map(|b| b.len() as u64),
+            duration_seconds: header.map(|h| h.duration_seconds),
+            sample_rate: header.map(|h| h.sample_rate),
+            channels: header.map(|h| h.channels),
+            seed: None,
+            data,
+            mime_type,
+        }
+    }
+}
+
+/// A generated sample written to a local file or GCS.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SavedAudio {
+    /// Local path or GCS URI
+    pub location: String,
+    /// MIME type of the written file
+    pub mime_type: String,
+    /// Size of the written file in bytes (`None` for cached results)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    /// Length of the audio, read from the WAV header (`None` for cached results)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<f64>,
+    /// Sample rate in Hz, read from the WAV header (`None` for cached results)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_rate: Option<u32>,
+    /// Number of channels, read from the WAV header (`None` for cached results)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channels: Option<u16>,
+    /// Seed this sample was generated with (`None` for unseeded requests)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+}
+
+impl SavedAudio {
+    /// Saved samples recalled from the result cache, whose audio is not re-read.
+    fn cached(locations: Vec<String>, params: &MusicGenerateParams) -> Vec<Self> {
+        let seeds = params.sample_seeds().unwrap_or_default();
+        locations
+            .into_iter()
+            .enumerate()
+            .map(|(i, location)| Self {
+                location,
+                mime_type: params.output_format.mime_type().to_string(),
+                size_bytes: None,
+                duration_seconds: None,
+                sample_rate: None,
+                channels: None,
+                seed: seeds.get(i).copied(),
+            })
+            .collect()
+    }
 }
 
 /// Result of music generation.
@@ -479,10 +1079,68 @@ pub struct GeneratedAudio {
 pub enum MusicGenerateResult {
     /// Base64-encoded audio data (when no output specified)
     Base64(Vec<GeneratedAudio>),
-    /// Local file paths (when output_file specified)
-    LocalFiles(Vec<String>),
-    /// GCS URIs (when output_gcs_uri specified)
-    GcsUris(Vec<String>),
+    /// Saved local files (when output_file specified)
+    LocalFiles(Vec<SavedAudio>),
+    /// Uploaded GCS objects (when output_gcs_uri specified)
+    GcsUris(Vec<SavedAudio>),
+}
+
+/// Audio properties read from a WAV header.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WavHeader {
+    /// Length of the audio in seconds
+    pub duration_seconds: f64,
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+    /// Number of channels
+    pub channels: u16,
+}
+
+/// Parse the `fmt ` and `data` chunks of a RIFF/WAVE file.
+///
+/// Returns `None` if the bytes are not a WAV file, either chunk is missing,
+/// or the byte rate is zero. A `data` size running past the end of the buffer
+/// (as written by streaming encoders) is clamped to the bytes actually present.
+pub fn parse_wav_header(data: &[u8]) -> Option<WavHeader> {
+    if data.len() < 12 || &data[..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return None;
+    }
+
+    // (channels, sample rate, byte rate) from the fmt chunk
+    let mut format = None;
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let id = &data[pos..pos + 4];
+        let size = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().ok()?) as usize;
+        let body = pos + 8;
+        match id {
+            b"fmt " if size >= 12 && body + 12 <= data.len() => {
+                let channels = u16::from_le_bytes(data[body + 2..body + 4].try_into().ok()?);
+                let sample_rate = u32::from_le_bytes(data[body + 4..body + 8].try_into().ok()?);
+                let byte_rate = u32::from_le_bytes(data[body + 8..body + 12].try_into().ok()?);
+                format = Some((channels, sample_rate, byte_rate));
+            }
+            b"data" => {
+                let len = size.min(data.len() - body);
+                let (channels, sample_rate, byte_rate) = format.filter(|f| f.2 > 0)?;
+                return Some(WavHeader {
+                    duration_seconds: len as f64 / byte_rate as f64,
+                    sample_rate,
+                    channels,
+                });
+            }
+            _ => {}
+        }
+        // Chunks are padded to an even length
+        pos = body.checked_add(size)?.checked_add(size % 2)?;
+    }
+    None
+}
+
+/// Duration in seconds of a RIFF/WAVE file, from its `fmt ` byte rate and
+/// `data` chunk size. See [`parse_wav_header`].
+pub fn wav_duration_seconds(data: &[u8]) -> Option<f64> {
+    parse_wav_header(data).map(|header| header.duration_seconds)
 }
 
 
@@ -500,6 +1158,52 @@ mod tests {
         assert!(params.output_gcs_uri.is_none());
     }
 
+    #[test]
+    fn test_get_endpoint_location_override() {
+        let config = Config {
+            project_id: "my-project".to_string(),
+            ..Default::default()
+        };
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        let gcs = GcsClient::with_auth(Arc::clone(&auth));
+        let handler = MusicHandler::with_deps(config, gcs, reqwest::Client::new(), auth);
+
+        assert_eq!(
+            handler.get_endpoint(None),
+            "https://us-central1-aiplatform.googleapis.com/v1/projects/my-project/locations/us-central1/publishers/google/models/lyria-002:predict"
+        );
+        assert_eq!(
+            handler.get_endpoint(Some("europe-west4")),
+            "https://europe-west4-aiplatform.googleapis.com/v1/projects/my-project/locations/europe-west4/publishers/google/models/lyria-002:predict"
+        );
+    }
+
+    #[test]
+    fn test_location_validation() {
+        let mut params: MusicGenerateParams = serde_json::from_str(r#"{"prompt": "upbeat jazz"}"#).unwrap();
+        assert!(params.location.is_none());
+
+        params.location = Some("europe-west4".to_string());
+        assert!(params.validate().is_ok());
+
+        params.location = Some("mars-north1".to_string());
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "location");
+    }
+
+    #[test]
+    fn test_write_metadata_requires_output() {
+        let mut params: MusicGenerateParams =
+            serde_json::from_str(r#"{"prompt": "upbeat jazz", "write_metadata": true}"#).unwrap();
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "write_metadata");
+
+        params.output_gcs_uri = Some("gs://bucket/jazz.wav".to_string());
+        assert!(params.validate().is_ok());
+    }
+
     #[test]
     fn test_valid_params() {
         let params = MusicGenerateParams {
@@ -507,8 +1211,16 @@ mod tests {
             negative_prompt: Some("drums, loud".to_string()),
             seed: Some(42),
             sample_count: 2,
+            duration_seconds: None,
+            bpm: None,
+            key: None,
             output_file: None,
             output_gcs_uri: None,
+            upload: false,
+            no_cache: false,
+            location: None,
+            write_metadata: false,
+            output_format: OutputFormat::Wav,
         };
 
         assert!(params.validate().is_ok());
@@ -521,8 +1233,16 @@ mod tests {
             negative_prompt: None,
             seed: None,
             sample_count: 0,
+            duration_seconds: None,
+            bpm: None,
+            key: None,
             output_file: None,
             output_gcs_uri: None,
+            upload: false,
+            no_cache: false,
+            location: None,
+            write_metadata: false,
+            output_format: OutputFormat::Wav,
         };
 
         let result = params.validate();
@@ -538,8 +1258,16 @@ mod tests {
             negative_prompt: None,
             seed: None,
             sample_count: 5,
+            duration_seconds: None,
+            bpm: None,
+            key: None,
             output_file: None,
             output_gcs_uri: None,
+            upload: false,
+            no_cache: false,
+            location: None,
+            write_metadata: false,
+            output_format: OutputFormat::Wav,
         };
 
         let result = params.validate();
@@ -548,6 +1276,21 @@ mod tests {
         assert!(errors.iter().any(|e| e.field == "sample_count"));
     }
 
+    #[test]
+    fn test_sample_count_limit_comes_from_model() {
+        let model = ModelRegistry::resolve_lyria(DEFAULT_MODEL).unwrap();
+        let mut params: MusicGenerateParams = serde_json::from_str(r#"{"prompt": "A song"}"#).unwrap();
+
+        params.sample_count = model.max_samples;
+        assert!(params.validate().is_ok());
+
+        params.sample_count = model.max_samples + 1;
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors[0].field, "sample_count");
+        let expected = format!("between 1 and {} for model {}", model.max_samples, model.id);
+        assert!(errors[0].message.contains(&expected), "{}", errors[0].message);
+    }
+
     #[test]
     fn test_empty_prompt() {
         let params = MusicGenerateParams {
@@ -555,8 +1298,16 @@ mod tests {
             negative_prompt: None,
             seed: None,
             sample_count: 1,
+            duration_seconds: None,
+            bpm: None,
+            key: None,
             output_file: None,
             output_gcs_uri: None,
+            upload: false,
+            no_cache: false,
+            location: None,
+            write_metadata: false,
+            output_format: OutputFormat::Wav,
         };
 
         let result = params.validate();
@@ -565,6 +1316,29 @@ mod tests {
         assert!(errors.iter().any(|e| e.field == "prompt"));
     }
 
+    #[test]
+    fn test_prompt_length_limit_counts_appended_controls() {
+        let model = ModelRegistry::resolve_lyria(DEFAULT_MODEL).unwrap();
+        let mut params: MusicGenerateParams = serde_json::from_value(serde_json::json!({
+            "prompt": "é".repeat(model.max_prompt_length),
+        }))
+        .unwrap();
+        // The limit is in characters, not bytes
+        assert!(params.validate().is_ok());
+
+        // Tempo and key are folded into the prompt for this model
+        params.bpm = Some(120);
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "prompt");
+        assert!(errors[0].message.contains(&format!("maximum {} characters", model.max_prompt_length)));
+
+        params.bpm = None;
+        params.negative_prompt = Some("x".repeat(model.max_prompt_length + 1));
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors[0].field, "negative_prompt");
+    }
+
     #[test]
     fn test_invalid_gcs_uri() {
         let params = MusicGenerateParams {
@@ -572,8 +1346,16 @@ mod tests {
             negative_prompt: None,
             seed: None,
             sample_count: 1,
+            duration_seconds: None,
+            bpm: None,
+            key: None,
             output_file: None,
             output_gcs_uri: Some("/local/path/output.wav".to_string()),
+            upload: false,
+            no_cache: false,
+            location: None,
+            write_metadata: false,
+            output_format: OutputFormat::Wav,
         };
 
         let result = params.validate();
@@ -589,8 +1371,16 @@ mod tests {
             negative_prompt: None,
             seed: None,
             sample_count: 1,
+            duration_seconds: None,
+            bpm: None,
+            key: None,
             output_file: None,
             output_gcs_uri: Some("gs://bucket/output.wav".to_string()),
+            upload: false,
+            no_cache: false,
+            location: None,
+            write_metadata: false,
+            output_format: OutputFormat::Wav,
         };
 
         assert!(params.validate().is_ok());
@@ -604,8 +1394,16 @@ mod tests {
                 negative_prompt: None,
                 seed: None,
                 sample_count: n,
+                duration_seconds: None,
+                bpm: None,
+                key: None,
                 output_file: None,
                 output_gcs_uri: None,
+                upload: false,
+                no_cache: false,
+                location: None,
+                write_metadata: false,
+                output_format: OutputFormat::Wav,
             };
             assert!(params.validate().is_ok(), "sample_count {} should be valid", n);
         }
@@ -618,8 +1416,16 @@ mod tests {
             negative_prompt: Some("vocals".to_string()),
             seed: Some(42),
             sample_count: 2,
+            duration_seconds: None,
+            bpm: None,
+            key: None,
             output_file: Some("/tmp/output.wav".to_string()),
             output_gcs_uri: None,
+            upload: false,
+            no_cache: false,
+            location: None,
+            write_metadata: false,
+            output_format: OutputFormat::Wav,
         };
 
         let json = serde_json::to_string(&params).unwrap();
@@ -636,21 +1442,21 @@ mod tests {
     #[test]
     fn test_add_index_suffix_to_gcs_uri_simple() {
         let uri = "gs://bucket/output.wav";
-        let result = MusicHandler::add_index_suffix_to_gcs_uri(uri, 0, "audio", "wav");
+        let result = MusicHandler::add_index_suffix_to_uri(uri, 0, "audio", "wav");
         assert_eq!(result, "gs://bucket/output_0.wav");
     }
 
     #[test]
     fn test_add_index_suffix_to_gcs_uri_with_path() {
         let uri = "gs://bucket/path/to/output.wav";
-        let result = MusicHandler::add_index_suffix_to_gcs_uri(uri, 1, "audio", "wav");
+        let result = MusicHandler::add_index_suffix_to_uri(uri, 1, "audio", "wav");
         assert_eq!(result, "gs://bucket/path/to/output_1.wav");
     }
 
     #[test]
     fn test_add_index_suffix_to_gcs_uri_no_extension() {
         let uri = "gs://bucket/output";
-        let result = MusicHandler::add_index_suffix_to_gcs_uri(uri, 2, "audio", "wav");
+        let result = MusicHandler::add_index_suffix_to_uri(uri, 2, "audio", "wav");
         assert_eq!(result, "gs://bucket/output_2.wav");
     }
 
@@ -658,10 +1464,301 @@ mod tests {
     fn test_add_index_suffix_preserves_gs_prefix() {
         // This is the key test for the P1 bug - ensure gs:// is preserved, not mangled to gs:/
         let uri = "gs://my-bucket/folder/music.wav";
-        let result = MusicHandler::add_index_suffix_to_gcs_uri(uri, 0, "audio", "wav");
+        let result = MusicHandler::add_index_suffix_to_uri(uri, 0, "audio", "wav");
         assert!(result.starts_with("gs://"), "URI should start with gs://, got: {}", result);
         assert_eq!(result, "gs://my-bucket/folder/music_0.wav");
     }
+
+    #[test]
+    fn test_add_index_suffix_to_local_path() {
+        let result = MusicHandler::add_index_suffix_to_uri("/tmp/output.wav", 0, "audio", "wav");
+        assert_eq!(result, "/tmp/output_0.wav");
+    }
+
+    #[test]
+    fn test_add_index_suffix_to_local_path_no_dir() {
+        let result = MusicHandler::add_index_suffix_to_uri("output", 3, "audio", "wav");
+        assert_eq!(result, "output_3.wav");
+    }
+
+    #[test]
+    fn test_output_format_defaults_to_wav_and_is_omitted() {
+        let params: MusicGenerateParams = serde_json::from_str(r#"{"prompt": "a sting"}"#).unwrap();
+        assert_eq!(params.output_format, OutputFormat::Wav);
+        // Keeps result cache keys of existing WAV requests unchanged
+        assert!(serde_json::to_value(&params).unwrap().get("output_format").is_none());
+    }
+
+    #[test]
+    fn test_output_format_mp3_bitrate() {
+        let params: MusicGenerateParams =
+            serde_json::from_str(r#"{"prompt": "a sting", "output_format": {"format": "mp3"}}"#).unwrap();
+        assert_eq!(params.output_format, OutputFormat::Mp3 { bitrate: DEFAULT_MP3_BITRATE.to_string() });
+        assert_eq!(params.output_format.mime_type(), "audio/mpeg");
+        assert_eq!(params.output_format.extension(), "mp3");
+        assert!(params.validate().is_ok());
+
+        let params: MusicGenerateParams = serde_json::from_str(
+            r#"{"prompt": "a sting", "output_format": {"format": "mp3", "bitrate": "320k"}}"#,
+        ).unwrap();
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_mp3_bitrate() {
+        for bitrate in ["8k", "999k", "fast", ""] {
+            let params = MusicGenerateParams {
+                output_format: OutputFormat::Mp3 { bitrate: bitrate.to_string() },
+                ..serde_json::from_str(r#"{"prompt": "a sting"}"#).unwrap()
+            };
+            let errors = params.validate().unwrap_err();
+            assert_eq!(errors[0].field, "output_format.bitrate", "bitrate '{}'", bitrate);
+            assert!(errors[0].message.contains("32k and 320k"), "{}", errors[0].message);
+        }
+    }
+
+    #[test]
+    fn test_duration_validation_states_model_range() {
+        let mut params: MusicGenerateParams = serde_json::from_str(r#"{"prompt": "a sting"}"#).unwrap();
+        let model = params.get_model().unwrap();
+
+        for duration in [model.min_duration_seconds, 15, model.max_duration_seconds] {
+            params.duration_seconds = Some(duration);
+            assert!(params.validate().is_ok(), "{} should be valid", duration);
+        }
+
+        for duration in [0, model.min_duration_seconds - 1, model.max_duration_seconds + 1] {
+            params.duration_seconds = Some(duration);
+            let errors = params.validate().unwrap_err();
+            assert_eq!(errors[0].field, "duration_seconds");
+            let range = format!("between {} and {}", model.min_duration_seconds, model.max_duration_seconds);
+            assert!(errors[0].message.contains(&range), "{}", errors[0].message);
+            assert!(errors[0].message.contains(model.id), "{}", errors[0].message);
+        }
+    }
+
+    #[test]
+    fn test_duration_serialized_into_request() {
+        let parameters = LyriaParameters {
+            sample_count: 1,
+            seed: None,
+            duration_seconds: Some(15),
+            bpm: None,
+            key: None,
+        };
+        let json = serde_json::to_value(&parameters).unwrap();
+        assert_eq!(json, serde_json::json!({"sampleCount": 1, "durationSeconds": 15}));
+
+        let parameters = LyriaParameters { duration_seconds: None, ..parameters };
+        assert!(serde_json::to_value(&parameters).unwrap().get("durationSeconds").is_none());
+
+        let parameters = LyriaParameters {
+            bpm: Some(120),
+            key: Some("A minor".to_string()),
+            ..parameters
+        };
+        let json = serde_json::to_value(&parameters).unwrap();
+        assert_eq!(json, serde_json::json!({"sampleCount": 1, "bpm": 120, "key": "A minor"}));
+    }
+
+    #[test]
+    fn test_normalize_musical_key() {
+        assert_eq!(normalize_musical_key("A minor").as_deref(), Some("A minor"));
+        assert_eq!(normalize_musical_key("  bb   MAJOR ").as_deref(), Some("Bb major"));
+        assert_eq!(normalize_musical_key("f# minor").as_deref(), Some("F# minor"));
+        for key in ["", "A", "H minor", "A dorian", "A minor key", "Cb major"] {
+            assert_eq!(normalize_musical_key(key), None, "'{}'", key);
+        }
+    }
+
+    #[test]
+    fn test_bpm_and_key_validation() {
+        let mut params: MusicGenerateParams = serde_json::from_str(r#"{"prompt": "a groove"}"#).unwrap();
+        params.bpm = Some(BPM_RANGE.0);
+        params.key = Some("e minor".to_string());
+        assert!(params.validate().is_ok());
+        params.bpm = Some(BPM_RANGE.1);
+        assert!(params.validate().is_ok());
+
+        params.bpm = Some(BPM_RANGE.1 + 1);
+        params.key = Some("E phrygian".to_string());
+        let errors = params.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "bpm" && e.message.contains("between 40 and 220")));
+        assert!(errors.iter().any(|e| e.field == "key" && e.message.contains("E phrygian")));
+
+        params.bpm = Some(BPM_RANGE.0 - 1);
+        params.key = None;
+        assert_eq!(params.validate().unwrap_err()[0].field, "bpm");
+    }
+
+    #[test]
+    fn test_prompt_with_controls_is_stable() {
+        let mut params: MusicGenerateParams = serde_json::from_str(r#"{"prompt": "Calm piano"}"#).unwrap();
+        assert_eq!(params.controls_mode(), None);
+        assert_eq!(params.prompt_with_controls(), "Calm piano");
+
+        params.bpm = Some(120);
+        params.key = Some("a minor".to_string());
+        assert_eq!(params.prompt_with_controls(), "Calm piano, at 120 BPM, in A minor");
+
+        // Trailing punctuation and whitespace do not change the result
+        params.prompt = "Calm piano.  ".to_string();
+        assert_eq!(params.prompt_with_controls(), "Calm piano, at 120 BPM, in A minor");
+
+        params.bpm = None;
+        assert_eq!(params.prompt_with_controls(), "Calm piano, in A minor");
+        params.bpm = Some(90);
+        params.key = None;
+        assert_eq!(params.prompt_with_controls(), "Calm piano, at 90 BPM");
+    }
+
+    #[test]
+    fn test_build_request_folds_controls_into_prompt() {
+        let mut params: MusicGenerateParams = serde_json::from_str(r#"{"prompt": "Calm piano"}"#).unwrap();
+        params.bpm = Some(120);
+        params.key = Some("A minor".to_string());
+
+        // lyria-1.0 has no structured controls
+        assert_eq!(params.controls_mode(), Some(ControlsMode::Prompt));
+        let request = MusicHandler::build_request(&params);
+        assert_eq!(request.instances[0].prompt, "Calm piano, at 120 BPM, in A minor");
+        assert_eq!(request.parameters.bpm, None);
+        assert_eq!(request.parameters.key, None);
+
+        params.bpm = None;
+        params.key = None;
+        assert_eq!(MusicHandler::build_request(&params).instances[0].prompt, "Calm piano");
+    }
+
+    #[test]
+    fn test_seeded_multi_sample_requests_get_distinct_seeds() {
+        let mut params: MusicGenerateParams = serde_json::from_str(r#"{"prompt": "Calm piano"}"#).unwrap();
+        params.seed = Some(100);
+        params.sample_count = 3;
+
+        assert_eq!(params.sample_seeds(), Some(vec![100, 101, 102]));
+        let requests = MusicHandler::build_requests(&params);
+        let seeds: Vec<Option<i64>> = requests.iter().map(|r| r.parameters.seed).collect();
+        assert_eq!(seeds, vec![Some(100), Some(101), Some(102)]);
+        assert!(requests.iter().all(|r| r.parameters.sample_count == 1));
+    }
+
+    #[test]
+    fn test_unseeded_request_asks_for_all_samples_at_once() {
+        let mut params: MusicGenerateParams = serde_json::from_str(r#"{"prompt": "Calm piano"}"#).unwrap();
+        params.sample_count = 3;
+
+        assert_eq!(params.sample_seeds(), None);
+        let requests = MusicHandler::build_requests(&params);
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].parameters.sample_count, 3);
+        assert_eq!(requests[0].parameters.seed, None);
+    }
+
+    #[test]
+    fn test_seed_overflow_rejected() {
+        let mut params: MusicGenerateParams = serde_json::from_str(r#"{"prompt": "Calm piano"}"#).unwrap();
+        params.seed = Some(i64::MAX);
+        assert!(params.validate().is_ok());
+
+        params.sample_count = 2;
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "seed");
+        assert!(errors[0].message.contains("sample i uses seed + i"), "{}", errors[0].message);
+
+        params.seed = Some(i64::MAX - 1);
+        assert!(params.validate().is_ok());
+    }
+
+    /// Build a WAV file with the given format and `data_len` bytes of silence.
+    fn wav_bytes(sample_rate: u32, channels: u16, bits: u16, data_len: u32, extra_chunk: bool) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&1u16.to_le_bytes());
+        fmt.extend_from_slice(&channels.to_le_bytes());
+        fmt.extend_from_slice(&sample_rate.to_le_bytes());
+        fmt.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        fmt.extend_from_slice(&block_align.to_le_bytes());
+        fmt.extend_from_slice(&bits.to_le_bytes());
+
+        let mut body = b"WAVE".to_vec();
+        body.extend_from_slice(b"fmt ");
+        body.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+        body.extend_from_slice(&fmt);
+        if extra_chunk {
+            // Odd-sized chunk, padded to an even length
+            body.extend_from_slice(b"LIST");
+            body.extend_from_slice(&3u32.to_le_bytes());
+            body.extend_from_slice(b"abc\0");
+        }
+        body.extend_from_slice(b"data");
+        body.extend_from_slice(&data_len.to_le_bytes());
+        body.extend(std::iter::repeat_n(0u8, data_len as usize));
+
+        let mut wav = b"RIFF".to_vec();
+        wav.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        wav.extend(body);
+        wav
+    }
+
+    #[test]
+    fn test_wav_duration_seconds() {
+        // 48 kHz stereo 16-bit: 192000 bytes per second
+        assert_eq!(wav_duration_seconds(&wav_bytes(48_000, 2, 16, 96_000, false)), Some(0.5));
+        assert_eq!(wav_duration_seconds(&wav_bytes(8_000, 1, 8, 16_000, true)), Some(2.0));
+    }
+
+    #[test]
+    fn test_parse_wav_header_fixture() {
+        // 0.25s of a 440 Hz tone, 8 kHz mono 16-bit
+        let wav = include_bytes!("../tests/fixtures/tone_8k_mono.wav");
+        assert_eq!(
+            parse_wav_header(wav),
+            Some(WavHeader { duration_seconds: 0.25, sample_rate: 8_000, channels: 1 })
+        );
+    }
+
+    #[test]
+    fn test_parse_wav_header_skips_extra_chunks() {
+        let header = parse_wav_header(&wav_bytes(48_000, 2, 16, 96_000, true)).unwrap();
+        assert_eq!((header.sample_rate, header.channels), (48_000, 2));
+        assert_eq!(header.duration_seconds, 0.5);
+    }
+
+    #[test]
+    fn test_generated_audio_reads_wav_header() {
+        let wav = include_bytes!("../tests/fixtures/tone_8k_mono.wav");
+        let audio = GeneratedAudio::from_base64(BASE64.encode(wav), "audio/wav".to_string());
+        assert_eq!(audio.size_bytes, Some(wav.len() as u64));
+        assert_eq!(audio.duration_seconds, Some(0.25));
+        assert_eq!(audio.sample_rate, Some(8_000));
+        assert_eq!(audio.channels, Some(1));
+
+        let audio = GeneratedAudio::from_base64(BASE64.encode(b"ID3\x03"), "audio/mpeg".to_string());
+        assert_eq!(audio.size_bytes, Some(4));
+        assert_eq!((audio.duration_seconds, audio.sample_rate, audio.channels), (None, None, None));
+
+        let audio = GeneratedAudio::from_base64("not base64!".to_string(), "audio/wav".to_string());
+        assert_eq!(audio.size_bytes, None);
+    }
+
+    #[test]
+    fn test_wav_duration_clamps_oversized_data_chunk() {
+        let mut wav = wav_bytes(8_000, 1, 8, 4_000, false);
+        let data_size = wav.len() - 4_000 - 4;
+        wav[data_size..data_size + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(wav_duration_seconds(&wav), Some(0.5));
+    }
+
+    #[test]
+    fn test_wav_duration_rejects_non_wav() {
+        assert_eq!(wav_duration_seconds(b""), None);
+        assert_eq!(wav_duration_seconds(b"RIFF"), None);
+        assert_eq!(wav_duration_seconds(b"ID3\x03\0\0\0\0\0\0"), None);
+        // Header without a data chunk
+        assert_eq!(wav_duration_seconds(&wav_bytes(8_000, 1, 8, 0, false)[..36]), None);
+    }
 }
 
 
@@ -707,8 +1804,16 @@ mod property_tests {
                 negative_prompt: None,
                 seed: None,
                 sample_count: num,
+                duration_seconds: None,
+                bpm: None,
+                key: None,
                 output_file: None,
                 output_gcs_uri: None,
+                upload: false,
+                no_cache: false,
+                location: None,
+                write_metadata: false,
+                output_format: OutputFormat::Wav,
             };
 
             let result = params.validate();
@@ -731,8 +1836,16 @@ mod property_tests {
                 negative_prompt: None,
                 seed: None,
                 sample_count: num,
+                duration_seconds: None,
+                bpm: None,
+                key: None,
                 output_file: None,
                 output_gcs_uri: None,
+                upload: false,
+                no_cache: false,
+                location: None,
+                write_metadata: false,
+                output_format: OutputFormat::Wav,
             };
 
             let result = params.validate();
@@ -760,8 +1873,16 @@ mod property_tests {
                 negative_prompt: None,
                 seed: None,
                 sample_count: num,
+                duration_seconds: None,
+                bpm: None,
+                key: None,
                 output_file: None,
                 output_gcs_uri: None,
+                upload: false,
+                no_cache: false,
+                location: None,
+                write_metadata: false,
+                output_format: OutputFormat::Wav,
             };
 
             let result = params.validate();
@@ -787,8 +1908,16 @@ mod property_tests {
                 negative_prompt: None,
                 seed: None,
                 sample_count: 1,
+                duration_seconds: None,
+                bpm: None,
+                key: None,
                 output_file: None,
                 output_gcs_uri: Some(gcs_uri.clone()),
+                upload: false,
+                no_cache: false,
+                location: None,
+                write_metadata: false,
+                output_format: OutputFormat::Wav,
             };
 
             let result = params.validate();
@@ -811,8 +1940,16 @@ mod property_tests {
                 negative_prompt: None,
                 seed: None,
                 sample_count: 1,
+                duration_seconds: None,
+                bpm: None,
+                key: None,
                 output_file: None,
                 output_gcs_uri: Some(path.clone()),
+                upload: false,
+                no_cache: false,
+                location: None,
+                write_metadata: false,
+                output_format: OutputFormat::Wav,
             };
 
             let result = params.validate();
@@ -829,5 +1966,338 @@ mod property_tests {
                 path
             );
         }
+
+        /// Property: Any seed is accepted alongside a valid sample_count
+        #[test]
+        fn any_seed_passes_validation(
+            seed in any::<i64>(),
+            num in valid_sample_count_strategy(),
+            prompt in valid_prompt_strategy(),
+        ) {
+            let params = MusicGenerateParams {
+                prompt,
+                negative_prompt: None,
+                seed: Some(seed),
+                sample_count: num,
+                duration_seconds: None,
+                bpm: None,
+                key: None,
+                output_file: None,
+                output_gcs_uri: None,
+                upload: false,
+                no_cache: false,
+                location: None,
+                write_metadata: false,
+                output_format: OutputFormat::Wav,
+            };
+
+            let result = params.validate();
+            prop_assert!(
+                result.is_ok(),
+                "seed {} with sample_count {} should be valid, but got errors: {:?}",
+                seed,
+                num,
+                result.err()
+            );
+        }
+
+        /// Property: sample_count and seed reach the Lyria request unchanged
+        #[test]
+        fn lyria_parameters_serialize_sample_count_and_seed(
+            num in valid_sample_count_strategy(),
+            seed in proptest::option::of(any::<i64>()),
+        ) {
+            let parameters = LyriaParameters {
+                sample_count: num,
+                seed,
+                duration_seconds: None,
+                bpm: None,
+                key: None,
+            };
+
+            let json = serde_json::to_value(&parameters).unwrap();
+            prop_assert_eq!(json["sampleCount"].as_u64(), Some(u64::from(num)));
+            match seed {
+                Some(seed) => prop_assert_eq!(json["seed"].as_i64(), Some(seed)),
+                None => prop_assert!(json.get("seed").is_none()),
+            }
+        }
+
+        /// Property: Every sample of a multi-sample request gets its own file name
+        #[test]
+        fn indexed_output_names_are_distinct(
+            num in 2..=MAX_SAMPLE_COUNT,
+            stem in "[a-z][a-z0-9_]{0,15}",
+            gcs in any::<bool>(),
+        ) {
+            let output = if gcs {
+                format!("gs://bucket/music/{}.wav", stem)
+            } else {
+                format!("/tmp/music/{}.wav", stem)
+            };
+
+            let names: Vec<String> = (0..num as usize)
+                .map(|i| MusicHandler::add_index_suffix_to_uri(&output, i, "audio", "wav"))
+                .collect();
+            let unique: std::collections::HashSet<&String> = names.iter().collect();
+            prop_assert_eq!(unique.len(), names.len());
+            for (i, name) in names.iter().enumerate() {
+                let suffix = format!("{}_{}.wav", stem, i);
+                prop_assert!(name.ends_with(&suffix), "{} should end with {}", name, suffix);
+                prop_assert_eq!(name.starts_with("gs://"), gcs);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod cache_tests {
+    use super::*;
+    use adk_rust_mcp_common::cache::InMemoryResultCache;
+
+    fn test_handler() -> MusicHandler {
+        let config = Config {
+            project_id: "test-project".to_string(),
+            ..Default::default()
+        };
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        let gcs = GcsClient::with_auth(Arc::clone(&auth));
+        MusicHandler::with_deps(config, gcs, reqwest::Client::new(), auth)
+    }
+
+    fn seeded_params() -> MusicGenerateParams {
+        MusicGenerateParams {
+            prompt: "Calm piano".to_string(),
+            negative_prompt: None,
+            seed: Some(11),
+            sample_count: 1,
+            duration_seconds: None,
+            bpm: None,
+            key: None,
+            output_file: None,
+            output_gcs_uri: Some("gs://bucket/piano.wav".to_string()),
+            upload: false,
+            no_cache: false,
+            location: None,
+            write_metadata: false,
+            output_format: OutputFormat::Wav,
+        }
+    }
+
+    #[test]
+    fn test_cache_key_requires_seed_and_destination() {
+        assert!(MusicHandler::result_cache_key(&seeded_params()).unwrap().is_some());
+
+        let mut no_seed = seeded_params();
+        no_seed.seed = None;
+        assert!(MusicHandler::result_cache_key(&no_seed).unwrap().is_none());
+
+        let mut base64_output = seeded_params();
+        base64_output.output_gcs_uri = None;
+        assert!(MusicHandler::result_cache_key(&base64_output).unwrap().is_none());
+
+        let mut opted_out = seeded_params();
+        opted_out.no_cache = true;
+        assert!(MusicHandler::result_cache_key(&opted_out).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_generate_returns_cached_result_without_api_call() {
+        let cache = Arc::new(InMemoryResultCache::new());
+        let handler = test_handler().with_cache(cache.clone());
+        let params = seeded_params();
+        let key = MusicHandler::result_cache_key(&params).unwrap().unwrap();
+        cache
+            .put(&key, &CachedResult { uris: vec!["gs://bucket/piano.wav".to_string()] })
+            .await
+            .unwrap();
+
+        let result = handler.generate_music(params).await.unwrap();
+        match result {
+            MusicGenerateResult::GcsUris(saved) => {
+                assert_eq!(saved.len(), 1);
+                assert_eq!(saved[0].location, "gs://bucket/piano.wav");
+                assert_eq!(saved[0].duration_seconds, None);
+            }
+            other => panic!("Expected cached GCS URIs, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_result_reports_sample_seeds() {
+        let cache = Arc::new(InMemoryResultCache::new());
+        let handler = test_handler().with_cache(cache.clone());
+        let params = MusicGenerateParams {
+            sample_count: 2,
+            ..seeded_params()
+        };
+        let key = MusicHandler::result_cache_key(&params).unwrap().unwrap();
+        let uris = vec!["gs://bucket/piano_0.wav".to_string(), "gs://bucket/piano_1.wav".to_string()];
+        cache.put(&key, &CachedResult { uris }).await.unwrap();
+
+        match handler.generate_music(params).await.unwrap() {
+            MusicGenerateResult::GcsUris(saved) => {
+                let seeds: Vec<Option<i64>> = saved.iter().map(|s| s.seed).collect();
+                assert_eq!(seeds, vec![Some(11), Some(12)]);
+            }
+            other => panic!("Expected cached GCS URIs, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bare_output_name_resolves_to_configured_bucket() {
+        let cache = Arc::new(InMemoryResultCache::new());
+        let mut handler = test_handler().with_cache(cache.clone());
+        handler.config.gcs_bucket = Some("media".to_string());
+
+        // The cache key is computed from the resolved URI
+        let key = MusicHandler::result_cache_key(&MusicGenerateParams {
+            output_gcs_uri: Some("gs://media/tracks/piano.wav".to_string()),
+            ..seeded_params()
+        })
+        .unwrap()
+        .unwrap();
+        cache
+            .put(&key, &CachedResult { uris: vec!["gs://media/tracks/piano.wav".to_string()] })
+            .await
+            .unwrap();
+
+        let params = MusicGenerateParams {
+            output_gcs_uri: Some("tracks/piano.wav".to_string()),
+            ..seeded_params()
+        };
+        match handler.generate_music(params).await.unwrap() {
+            MusicGenerateResult::GcsUris(saved) => assert_eq!(saved[0].location, "gs://media/tracks/piano.wav"),
+            other => panic!("Expected cached GCS URIs, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_saved_audio_carries_wav_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("tone.wav");
+        let wav = include_bytes!("../tests/fixtures/tone_8k_mono.wav");
+        let samples = vec![GeneratedAudio::from_base64(BASE64.encode(wav), "audio/wav".to_string())];
+
+        let result = test_handler()
+            .save_to_file(samples, output.to_str().unwrap(), "wav")
+            .await
+            .unwrap();
+        let MusicGenerateResult::LocalFiles(saved) = result else {
+            panic!("Expected local files, got {:?}", result);
+        };
+        assert_eq!(saved[0].size_bytes, Some(wav.len() as u64));
+        assert_eq!(saved[0].duration_seconds, Some(0.25));
+        assert_eq!(saved[0].sample_rate, Some(8_000));
+        assert_eq!(saved[0].channels, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_save_to_file_places_relative_paths_in_download_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut handler = test_handler();
+        handler.config.download_dir = Some(dir.path().to_string_lossy().into_owned());
+        let samples = vec![GeneratedAudio::from_base64(BASE64.encode(b"RIFF"), "audio/wav".to_string())];
+
+        let result = handler.save_to_file(samples, "music/piano.wav", "wav").await.unwrap();
+
+        let expected = dir.path().join("music/piano.wav");
+        let MusicGenerateResult::LocalFiles(saved) = result else {
+            panic!("Expected LocalFiles");
+        };
+        assert_eq!(saved[0].location, expected.to_string_lossy());
+        assert_eq!(std::fs::read(&expected).unwrap(), b"RIFF");
+    }
+
+    #[tokio::test]
+    async fn test_write_metadata_beside_each_saved_sample() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("piano.wav");
+        let handler = test_handler();
+        let samples = (0..2)
+            .map(|_| GeneratedAudio::from_base64(BASE64.encode(b"RIFF"), "audio/wav".to_string()))
+            .collect();
+        let params = MusicGenerateParams {
+            negative_prompt: Some("drums".to_string()),
+            output_file: Some(output.to_string_lossy().into_owned()),
+            output_gcs_uri: None,
+            write_metadata: true,
+            ..seeded_params()
+        };
+        let result = handler.save_to_file(samples, params.output_file.as_deref().unwrap(), "wav").await.unwrap();
+
+        handler.write_metadata(&params, &result).await;
+
+        for i in 0..2 {
+            let sidecar = dir.path().join(format!("piano_{}.json", i));
+            let metadata: GenerationMetadata = serde_json::from_slice(&std::fs::read(&sidecar).unwrap()).unwrap();
+            assert_eq!(metadata.prompt, "Calm piano");
+            assert_eq!(metadata.negative_prompt.as_deref(), Some("drums"));
+            assert_eq!(metadata.seed, Some(11));
+            assert_eq!(metadata.aspect_ratio, None);
+            assert!(metadata.model.starts_with("lyria"), "{}", metadata.model);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bare_output_name_without_bucket_fails() {
+        let params = MusicGenerateParams {
+            output_gcs_uri: Some("tracks/piano.wav".to_string()),
+            ..seeded_params()
+        };
+        let err = test_handler().generate_music(params).await.unwrap_err();
+        assert!(matches!(err, Error::Validation(_)), "Expected validation error, got {:?}", err);
+        assert!(err.to_string().contains("GCS_BUCKET"));
+    }
+
+    #[test]
+    fn test_default_output_uri_uses_bucket_prefix_and_format() {
+        let mut handler = test_handler();
+        handler.config.gcs_bucket = Some("media".to_string());
+
+        let wav = handler.default_output_uri(&OutputFormat::Wav).unwrap();
+        assert!(wav.starts_with("gs://media/music/"), "{}", wav);
+        assert!(wav.ends_with(".wav"), "{}", wav);
+
+        let mp3 = handler
+            .default_output_uri(&OutputFormat::Mp3 { bitrate: DEFAULT_MP3_BITRATE.to_string() })
+            .unwrap();
+        assert!(mp3.ends_with(".mp3"), "{}", mp3);
+        assert_ne!(handler.default_output_uri(&OutputFormat::Wav).unwrap(), wav);
+    }
+
+    #[tokio::test]
+    async fn test_upload_without_bucket_fails() {
+        let params = MusicGenerateParams {
+            output_gcs_uri: None,
+            upload: true,
+            ..seeded_params()
+        };
+        let err = test_handler().generate_music(params).await.unwrap_err();
+        assert!(matches!(err, Error::Validation(_)), "Expected validation error, got {:?}", err);
+        assert!(err.to_string().contains("GCS_BUCKET"));
+    }
+
+    #[tokio::test]
+    async fn test_upload_request_returns_cached_gcs_uri() {
+        let cache = Arc::new(InMemoryResultCache::new());
+        let handler = test_handler().with_cache(cache.clone());
+        let params = MusicGenerateParams {
+            output_gcs_uri: None,
+            upload: true,
+            ..seeded_params()
+        };
+
+        // The key is computed before a location is generated, so it is stable
+        let key = MusicHandler::result_cache_key(&params).unwrap().unwrap();
+        cache
+            .put(&key, &CachedResult { uris: vec!["gs://media/music/first.wav".to_string()] })
+            .await
+            .unwrap();
+
+        match handler.generate_music(params).await.unwrap() {
+            MusicGenerateResult::GcsUris(saved) => assert_eq!(saved[0].location, "gs://media/music/first.wav"),
+            other => panic!("Expected cached GCS URIs, got {:?}", other),
+        }
     }
 }
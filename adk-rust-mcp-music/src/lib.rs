@@ -5,7 +5,11 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 pub mod handler;
+pub mod resources;
 pub mod server;
 
-pub use handler::{MusicGenerateParams, MusicGenerateResult, MusicHandler, GeneratedAudio};
+pub use handler::{
+    ControlsMode, GeneratedAudio, MusicGenerateParams, MusicGenerateResult, MusicHandler, OutputFormat, SavedAudio,
+    WavHeader, parse_wav_header,
+};
 pub use server::MusicServer;
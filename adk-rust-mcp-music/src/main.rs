@@ -2,7 +2,8 @@
 //!
 //! MCP server for music generation using Vertex AI Lyria API.
 
-use adk_rust_mcp_common::{Config, McpServerBuilder, TransportArgs};
+use adk_rust_mcp_common::tracing::with_startup_logging;
+use adk_rust_mcp_common::{ConfigArgs, McpServerBuilder, ServerIdentity, TransportArgs};
 use adk_rust_mcp_music::MusicServer;
 use anyhow::Result;
 use clap::Parser;
@@ -14,35 +15,52 @@ use adk_rust_mcp_common::otel::{init_tracing_with_optional_otel, OtelConfig};
 #[command(name = "adk-rust-mcp-music")]
 #[command(about = "MCP server for music generation using Vertex AI Lyria API")]
 struct Args {
+    /// Configuration file
+    #[command(flatten)]
+    config: ConfigArgs,
+
     #[command(flatten)]
     transport: TransportArgs,
 }
 
+/// Prefix of environment variables that override the shared ones for this server.
+const ENV_PREFIX: &str = "MUSIC_";
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Load configuration first, since it decides how logs are redacted
+    let args = Args::parse();
+    let config = with_startup_logging(|| args.config.load_with_prefix(ENV_PREFIX))?;
+
     // Initialize tracing with optional OpenTelemetry support
     #[cfg(feature = "otel")]
     let _otel_guard = {
-        let config = OtelConfig::from_env()
+        let otel = OtelConfig::from_env()
             .unwrap_or_default()
-            .with_service_name("adk-rust-mcp-music");
-        init_tracing_with_optional_otel(config).await
+            .with_service_name("adk-rust-mcp-music")
+            .with_redaction(config.log_redaction);
+        init_tracing_with_optional_otel(otel).await
     };
 
     #[cfg(not(feature = "otel"))]
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .fmt_fields(config.log_redaction)
         .init();
 
     tracing::info!("adk-rust-mcp-music server starting...");
 
-    let args = Args::parse();
-    let config = Config::from_env()?;
+    let port = config.port;
+    let identity = ServerIdentity::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+        .with_config(&config);
     let server = MusicServer::new(config);
-    let transport = args.transport.into_transport();
+    let sse_keep_alive = args.transport.sse_keep_alive();
+    let transport = args.transport.into_transport_with_port(port);
 
     McpServerBuilder::new(server)
+        .with_server_identity(identity)
         .with_transport(transport)
+        .with_sse_keep_alive(sse_keep_alive)
         .run()
         .await?;
 
@@ -0,0 +1,89 @@
+//! MCP Resources for the Music server.
+//!
+//! This module provides resource implementations for:
+//! - `music://models` - List available music generation models
+
+use adk_rust_mcp_common::models::LYRIA_MODELS;
+use serde::Serialize;
+
+/// Information about an available music generation model.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelInfo {
+    /// Model identifier
+    pub id: &'static str,
+    /// Model aliases
+    pub aliases: Vec<&'static str>,
+    /// Shortest supported clip length in seconds
+    pub min_duration_seconds: u32,
+    /// Longest supported clip length in seconds
+    pub max_duration_seconds: u32,
+    /// Maximum number of samples per request
+    pub max_samples: u8,
+    /// Maximum prompt length in characters
+    pub max_prompt_length: usize,
+    /// Whether tempo and key are sent as model parameters rather than in the prompt
+    pub supports_structured_controls: bool,
+    /// Whether the model can continue an existing clip
+    pub supports_continuation: bool,
+}
+
+/// List all available music generation models.
+pub fn list_models() -> Vec<ModelInfo> {
+    LYRIA_MODELS
+        .iter()
+        .map(|m| ModelInfo {
+            id: m.id,
+            aliases: m.aliases.to_vec(),
+            min_duration_seconds: m.min_duration_seconds,
+            max_duration_seconds: m.max_duration_seconds,
+            max_samples: m.max_samples,
+            max_prompt_length: m.max_prompt_length,
+            supports_structured_controls: m.supports_structured_controls,
+            supports_continuation: m.supports_continuation,
+        })
+        .collect()
+}
+
+/// Get models resource as JSON string.
+pub fn models_resource_json() -> String {
+    serde_json::to_string_pretty(&list_models()).unwrap_or_else(|_| "[]".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_models() {
+        let models = list_models();
+        let model_ids: Vec<&str> = models.iter().map(|m| m.id).collect();
+        assert!(model_ids.contains(&"lyria-1.0"));
+    }
+
+    #[test]
+    fn test_list_models_has_aliases() {
+        for model in list_models() {
+            assert!(!model.aliases.is_empty(), "Model {} should have aliases", model.id);
+        }
+    }
+
+    #[test]
+    fn test_lyria_1_constraints() {
+        let models = list_models();
+        let lyria = models.iter().find(|m| m.id == "lyria-1.0").unwrap();
+        assert_eq!(lyria.min_duration_seconds, 10);
+        assert_eq!(lyria.max_duration_seconds, 180);
+        assert_eq!(lyria.max_samples, 4);
+        assert_eq!(lyria.max_prompt_length, 2000);
+        assert!(!lyria.supports_continuation);
+    }
+
+    #[test]
+    fn test_models_resource_json() {
+        let json = models_resource_json();
+        assert!(json.starts_with('['));
+        assert!(json.contains("\"lyria-1.0\""));
+        assert!(json.contains("\"supports_continuation\": false"));
+        assert!(json.contains("\"max_samples\": 4"));
+    }
+}
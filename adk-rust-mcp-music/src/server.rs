@@ -2,14 +2,21 @@
 //!
 //! This module provides the MCP server handler that exposes:
 //! - `music_generate` tool for music generation
+//! - `music_list_models` tool for listing available Lyria models
+//! - `music://models` resource with the same model list
 
-use crate::handler::{MusicGenerateParams, MusicGenerateResult, MusicHandler};
+use crate::handler::{
+    ControlsMode, GeneratedAudio, MusicGenerateParams, MusicGenerateResult, MusicHandler, OutputFormat,
+    SavedAudio,
+};
+use crate::resources;
 use adk_rust_mcp_common::config::Config;
-use adk_rust_mcp_common::error::Error;
+use adk_rust_mcp_common::error::{Error, ErrorCode};
+use adk_rust_mcp_common::idempotency::IdempotencyStore;
 use rmcp::{
     model::{
-        CallToolResult, Content, ListResourcesResult, ReadResourceResult,
-        ServerCapabilities, ServerInfo,
+        CallToolResult, Content, ListResourcesResult, ReadResourceResult, ResourceContents,
+        ServerCapabilities, ServerInfo, Tool,
     },
     ErrorData as McpError, ServerHandler,
 };
@@ -27,6 +34,8 @@ pub struct MusicServer {
     handler: Arc<RwLock<Option<MusicHandler>>>,
     /// Server configuration
     config: Config,
+    /// Results recorded by idempotency key
+    idempotency: Arc<IdempotencyStore>,
 }
 
 /// Tool parameters wrapper for music_generate.
@@ -37,18 +46,45 @@ pub struct MusicGenerateToolParams {
     /// Negative prompt - what to avoid in the generated music
     #[serde(default)]
     pub negative_prompt: Option<String>,
-    /// Random seed for reproducibility
+    /// Random seed for reproducibility; with sample_count > 1, sample i uses seed + i
     #[serde(default)]
     pub seed: Option<i64>,
     /// Number of samples to generate (1-4)
     #[serde(default)]
     pub sample_count: Option<u8>,
+    /// Length of each sample in seconds (lyria-1.0: 10-180; default: model default)
+    #[serde(default)]
+    pub duration_seconds: Option<u32>,
+    /// Tempo in beats per minute (40-220)
+    #[serde(default)]
+    pub bpm: Option<u16>,
+    /// Musical key, e.g. "A minor" or "F# major"
+    #[serde(default)]
+    pub key: Option<String>,
     /// Output file path for saving locally
     #[serde(default)]
     pub output_file: Option<String>,
-    /// Output GCS URI (e.g., gs://bucket/path)
+    /// Output GCS URI (gs://bucket/path, or an object name in GCS_BUCKET)
     #[serde(default)]
     pub output_gcs_uri: Option<String>,
+    /// Upload to gs://<GCS_BUCKET>/music/<uuid>.<ext> when output_gcs_uri is not given
+    #[serde(default)]
+    pub upload: Option<bool>,
+    /// Skip the result cache (seeded requests are cached by default)
+    #[serde(default)]
+    pub no_cache: Option<bool>,
+    /// Vertex AI region for this request, e.g. us-central1 (default: LOCATION)
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Write a <name>.json sidecar with prompt, model and seed next to each saved sample
+    #[serde(default)]
+    pub write_metadata: Option<bool>,
+    /// Audio encoding: {"format": "wav"} (default) or {"format": "mp3", "bitrate": "192k"}; mp3 needs ffmpeg
+    #[serde(default)]
+    pub output_format: Option<OutputFormat>,
+    /// Key making retries of this call return the first result instead of generating again (results expire after IDEMPOTENCY_TTL_SECS, default 1 hour)
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 impl From<MusicGenerateToolParams> for MusicGenerateParams {
@@ -58,8 +94,16 @@ impl From<MusicGenerateToolParams> for MusicGenerateParams {
             negative_prompt: params.negative_prompt,
             seed: params.seed,
             sample_count: params.sample_count.unwrap_or(1),
+            duration_seconds: params.duration_seconds,
+            bpm: params.bpm,
+            key: params.key,
             output_file: params.output_file,
             output_gcs_uri: params.output_gcs_uri,
+            upload: params.upload.unwrap_or(false),
+            no_cache: params.no_cache.unwrap_or(false),
+            location: params.location,
+            write_metadata: params.write_metadata.unwrap_or(false),
+            output_format: params.output_format.unwrap_or_default(),
         }
     }
 }
@@ -69,6 +113,7 @@ impl MusicServer {
     pub fn new(config: Config) -> Self {
         Self {
             handler: Arc::new(RwLock::new(None)),
+            idempotency: Arc::new(IdempotencyStore::from_config(&config)),
             config,
         }
     }
@@ -88,7 +133,7 @@ impl MusicServer {
 
         // Ensure handler is initialized
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -97,40 +142,178 @@ impl MusicServer {
         })?;
 
         let gen_params: MusicGenerateParams = params.into();
+        let controls_note = describe_controls(&gen_params);
         let result = handler.generate_music(gen_params).await.map_err(|e| {
-            McpError::internal_error(format!("Music generation failed: {}", e), None)
+            e.to_mcp_error("Music generation failed")
         })?;
 
-        // Convert result to MCP content
-        let content = match result {
-            MusicGenerateResult::Base64(samples) => {
+        // Convert result to MCP content, with per-sample metadata as structured content
+        let (mut content, samples): (Vec<Content>, Vec<serde_json::Value>) = match result {
+            MusicGenerateResult::Base64(samples) => (
                 samples
-                    .into_iter()
+                    .iter()
                     .map(|s| Content::text(format!("data:{};base64,{}", s.mime_type, s.data)))
-                    .collect()
-            }
-            MusicGenerateResult::LocalFiles(paths) => {
-                vec![Content::text(format!("Audio saved to: {}", paths.join(", ")))]
-            }
-            MusicGenerateResult::GcsUris(uris) => {
-                vec![Content::text(format!("Audio uploaded to: {}", uris.join(", ")))]
-            }
+                    .collect(),
+                samples.iter().map(generated_metadata).collect(),
+            ),
+            MusicGenerateResult::LocalFiles(saved) => (
+                vec![Content::text(format!("Audio saved to: {}", describe_saved(&saved)))],
+                saved_metadata(&saved),
+            ),
+            MusicGenerateResult::GcsUris(saved) => (
+                vec![Content::text(format!("Audio uploaded to: {}", describe_saved(&saved)))],
+                saved_metadata(&saved),
+            ),
+        };
+        content.extend(controls_note.map(Content::text));
+
+        let mut tool_result = CallToolResult::success(content);
+        tool_result.structured_content = Some(serde_json::json!({ "samples": samples }));
+        Ok(tool_result)
+    }
+
+    /// List available Lyria models with their durations, sample limits and capabilities.
+    pub fn list_models(&self) -> Result<CallToolResult, McpError> {
+        let models = resources::list_models();
+        let models_json = serde_json::to_string_pretty(&models).map_err(|e| {
+            McpError::internal_error(format!("Failed to serialize models: {}", e), None)
+        })?;
+
+        let mut tool_result = CallToolResult::success(vec![Content::text(models_json)]);
+        tool_result.structured_content = Some(serde_json::json!({ "models": models }));
+        Ok(tool_result)
+    }
+
+    /// Tools advertised by the server.
+    pub fn tool_definitions() -> Vec<Tool> {
+        use schemars::schema_for;
+
+        let schema = schema_for!(MusicGenerateToolParams);
+        let schema_value = serde_json::to_value(&schema).unwrap_or_default();
+
+        // Convert to Map
+        let input_schema = match schema_value {
+            serde_json::Value::Object(map) => Arc::new(map),
+            _ => Arc::new(serde_json::Map::new()),
         };
 
-        Ok(CallToolResult::success(content))
+        // music_list_models tool (no parameters - must have type: "object")
+        let mut empty_schema_map = serde_json::Map::new();
+        empty_schema_map.insert("type".to_string(), serde_json::Value::String("object".to_string()));
+        let empty_schema = Arc::new(empty_schema_map);
+
+        vec![
+            Tool {
+                name: Cow::Borrowed("music_generate"),
+                description: Some(Cow::Borrowed(
+                    "Generate music from a text prompt using Google's Lyria API. \
+                     Returns base64-encoded WAV data, local file paths, or GCS URIs \
+                     depending on output parameters."
+                )),
+                input_schema,
+                annotations: None,
+                icons: None,
+                meta: None,
+                output_schema: None,
+                title: None,
+            },
+            Tool {
+                name: Cow::Borrowed("music_list_models"),
+                description: Some(Cow::Borrowed(
+                    "List available Lyria models with their IDs, aliases, supported durations, \
+                     maximum sample count, and whether continuation is available."
+                )),
+                input_schema: empty_schema,
+                annotations: None,
+                icons: None,
+                meta: None,
+                output_schema: None,
+                title: None,
+            },
+        ]
+    }
+}
+
+/// Which path `bpm` and `key` took, or `None` when neither was requested.
+fn describe_controls(params: &MusicGenerateParams) -> Option<String> {
+    match params.controls_mode()? {
+        ControlsMode::Parameters => Some("Tempo and key sent as model parameters".to_string()),
+        ControlsMode::Prompt => Some(format!(
+            "Tempo and key added to the prompt: \"{}\"",
+            params.prompt_with_controls()
+        )),
     }
 }
 
+/// Metadata of a base64 sample, without the audio itself.
+fn generated_metadata(audio: &GeneratedAudio) -> serde_json::Value {
+    let mut metadata = serde_json::json!({ "mime_type": audio.mime_type });
+    let fields = [
+        ("size_bytes", audio.size_bytes.map(serde_json::Value::from)),
+        ("duration_seconds", audio.duration_seconds.map(serde_json::Value::from)),
+        ("sample_rate", audio.sample_rate.map(serde_json::Value::from)),
+        ("channels", audio.channels.map(serde_json::Value::from)),
+        ("seed", audio.seed.map(serde_json::Value::from)),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            metadata[name] = value;
+        }
+    }
+    metadata
+}
+
+/// Metadata of each saved sample, including its location.
+fn saved_metadata(saved: &[SavedAudio]) -> Vec<serde_json::Value> {
+    saved
+        .iter()
+        .filter_map(|audio| serde_json::to_value(audio).ok())
+        .collect()
+}
+
+/// Comma-separated locations, each followed by its duration, sample rate and
+/// channel count (when known), MIME type, size and seed (when known).
+fn describe_saved(saved: &[SavedAudio]) -> String {
+    saved
+        .iter()
+        .map(|audio| {
+            let mut details = Vec::new();
+            if let Some(duration) = audio.duration_seconds {
+                details.push(format!("{:.1}s", duration));
+            }
+            if let Some(sample_rate) = audio.sample_rate {
+                details.push(format!("{} Hz", sample_rate));
+            }
+            if let Some(channels) = audio.channels {
+                details.push(format!("{} ch", channels));
+            }
+            details.push(audio.mime_type.clone());
+            if let Some(size) = audio.size_bytes {
+                details.push(format!("{} bytes", size));
+            }
+            if let Some(seed) = audio.seed {
+                details.push(format!("seed {}", seed));
+            }
+            format!("{} ({})", audio.location, details.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Method signatures mirror the rmcp trait, which returns `impl Future`.
+#[allow(clippy::manual_async_fn)]
 impl ServerHandler for MusicServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             instructions: Some(
                 "Music generation server using Google Vertex AI Lyria API. \
-                 Use the music_generate tool to create music from text prompts."
+                 Use the music_generate tool to create music from text prompts, and \
+                 music_list_models to see each model's durations and sample limits."
                     .to_string(),
             ),
             capabilities: ServerCapabilities::builder()
                 .enable_tools()
+                .enable_resources()
                 .build(),
             ..Default::default()
         }
@@ -142,33 +325,8 @@ impl ServerHandler for MusicServer {
         _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
     ) -> impl std::future::Future<Output = Result<rmcp::model::ListToolsResult, McpError>> + Send + '_ {
         async move {
-            use rmcp::model::{ListToolsResult, Tool};
-            use schemars::schema_for;
-
-            let schema = schema_for!(MusicGenerateToolParams);
-            let schema_value = serde_json::to_value(&schema).unwrap_or_default();
-            
-            // Convert to Map
-            let input_schema = match schema_value {
-                serde_json::Value::Object(map) => Arc::new(map),
-                _ => Arc::new(serde_json::Map::new()),
-            };
-
-            Ok(ListToolsResult {
-                tools: vec![Tool {
-                    name: Cow::Borrowed("music_generate"),
-                    description: Some(Cow::Borrowed(
-                        "Generate music from a text prompt using Google's Lyria API. \
-                         Returns base64-encoded WAV data, local file paths, or GCS URIs \
-                         depending on output parameters."
-                    )),
-                    input_schema,
-                    annotations: None,
-                    icons: None,
-                    meta: None,
-                    output_schema: None,
-                    title: None,
-                }],
+            Ok(rmcp::model::ListToolsResult {
+                tools: Self::tool_definitions(),
                 next_cursor: None,
                 meta: None,
             })
@@ -183,15 +341,19 @@ impl ServerHandler for MusicServer {
         async move {
             match params.name.as_ref() {
                 "music_generate" => {
+                    let arguments = params.arguments.clone().unwrap_or_default();
                     let tool_params: MusicGenerateToolParams = params
                         .arguments
                         .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
                         .transpose()
-                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), None))?
-                        .ok_or_else(|| McpError::invalid_params("Missing parameters", None))?;
+                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data())))?
+                        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))?;
 
-                    self.generate_music(tool_params).await
+                    self.idempotency
+                        .run("music_generate", &arguments, self.generate_music(tool_params))
+                        .await
                 }
+                "music_list_models" => self.list_models(),
                 _ => Err(McpError::invalid_params(format!("Unknown tool: {}", params.name), None)),
             }
         }
@@ -203,10 +365,24 @@ impl ServerHandler for MusicServer {
         _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
     ) -> impl std::future::Future<Output = Result<ListResourcesResult, McpError>> + Send + '_ {
         async move {
-            debug!("Listing resources (none available for music server)");
-            
+            debug!("Listing resources");
+
+            let models_resource = rmcp::model::Resource {
+                raw: rmcp::model::RawResource {
+                    uri: "music://models".to_string(),
+                    name: "Available Music Models".to_string(),
+                    title: None,
+                    description: Some("List of available music generation models".to_string()),
+                    mime_type: Some("application/json".to_string()),
+                    size: None,
+                    icons: None,
+                    meta: None,
+                },
+                annotations: None,
+            };
+
             Ok(ListResourcesResult {
-                resources: vec![],
+                resources: vec![models_resource],
                 next_cursor: None,
                 meta: None,
             })
@@ -222,10 +398,19 @@ impl ServerHandler for MusicServer {
             let uri = &params.uri;
             debug!(uri = %uri, "Reading resource");
 
-            Err(McpError::resource_not_found(
-                format!("Unknown resource: {}", uri),
-                None,
-            ))
+            let content = match uri.as_str() {
+                "music://models" => resources::models_resource_json(),
+                _ => {
+                    return Err(McpError::resource_not_found(
+                        format!("Unknown resource: {}", uri),
+                        None,
+                    ));
+                }
+            };
+
+            Ok(ReadResourceResult {
+                contents: vec![ResourceContents::text(content, uri.clone())],
+            })
         }
     }
 }
@@ -237,9 +422,7 @@ mod tests {
     fn test_config() -> Config {
         Config {
             project_id: "test-project".to_string(),
-            location: "us-central1".to_string(),
-            gcs_bucket: None,
-            port: 8080,
+            ..Default::default()
         }
     }
 
@@ -248,6 +431,27 @@ mod tests {
         let server = MusicServer::new(test_config());
         let info = server.get_info();
         assert!(info.instructions.is_some());
+        assert!(info.capabilities.resources.is_some());
+    }
+
+    #[test]
+    fn test_list_models_returns_constraints() {
+        let server = MusicServer::new(test_config());
+        let tool_result = server.list_models().unwrap();
+
+        let structured = tool_result.structured_content.expect("structured content");
+        let models = structured["models"].as_array().expect("models array");
+        let lyria = models.iter().find(|m| m["id"] == "lyria-1.0").expect("lyria listed");
+        assert_eq!(lyria["min_duration_seconds"], 10);
+        assert_eq!(lyria["max_duration_seconds"], 180);
+        assert_eq!(lyria["max_samples"], 4);
+        assert_eq!(lyria["supports_continuation"], false);
+    }
+
+    #[test]
+    fn test_tool_definitions() {
+        let names: Vec<_> = MusicServer::tool_definitions().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, ["music_generate", "music_list_models"]);
     }
 
     #[test]
@@ -257,8 +461,17 @@ mod tests {
             negative_prompt: Some("vocals".to_string()),
             seed: Some(42),
             sample_count: Some(2),
+            duration_seconds: Some(30),
+            bpm: None,
+            key: None,
             output_file: None,
             output_gcs_uri: None,
+            upload: None,
+            no_cache: None,
+            location: None,
+            idempotency_key: None,
+            write_metadata: None,
+            output_format: None,
         };
 
         let gen_params: MusicGenerateParams = tool_params.into();
@@ -266,6 +479,77 @@ mod tests {
         assert_eq!(gen_params.negative_prompt, Some("vocals".to_string()));
         assert_eq!(gen_params.seed, Some(42));
         assert_eq!(gen_params.sample_count, 2);
+        assert_eq!(gen_params.duration_seconds, Some(30));
+    }
+
+    #[test]
+    fn test_describe_saved_includes_known_durations() {
+        let saved = [
+            SavedAudio {
+                location: "/tmp/a_0.mp3".to_string(),
+                mime_type: "audio/mpeg".to_string(),
+                size_bytes: Some(480_000),
+                duration_seconds: Some(30.0),
+                sample_rate: Some(48_000),
+                channels: Some(2),
+                seed: Some(7),
+            },
+            SavedAudio {
+                location: "/tmp/a_1.wav".to_string(),
+                mime_type: "audio/wav".to_string(),
+                size_bytes: None,
+                duration_seconds: None,
+                sample_rate: None,
+                channels: None,
+                seed: None,
+            },
+        ];
+        assert_eq!(
+            describe_saved(&saved),
+            "/tmp/a_0.mp3 (30.0s, 48000 Hz, 2 ch, audio/mpeg, 480000 bytes, seed 7), /tmp/a_1.wav (audio/wav)"
+        );
+
+        let metadata = saved_metadata(&saved);
+        assert_eq!(metadata[0]["sample_rate"], 48_000);
+        assert_eq!(metadata[0]["channels"], 2);
+        assert_eq!(metadata[0]["seed"], 7);
+        assert_eq!(metadata[1], serde_json::json!({ "location": "/tmp/a_1.wav", "mime_type": "audio/wav" }));
+    }
+
+    #[test]
+    fn test_generated_metadata_omits_audio() {
+        let audio = GeneratedAudio {
+            data: "UklGRg==".to_string(),
+            mime_type: "audio/wav".to_string(),
+            size_bytes: Some(4),
+            duration_seconds: Some(0.5),
+            sample_rate: Some(48_000),
+            channels: Some(2),
+            seed: None,
+        };
+        assert_eq!(
+            generated_metadata(&audio),
+            serde_json::json!({
+                "mime_type": "audio/wav",
+                "size_bytes": 4,
+                "duration_seconds": 0.5,
+                "sample_rate": 48_000,
+                "channels": 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_describe_controls_reports_prompt_fold_in() {
+        let mut params: MusicGenerateParams = serde_json::from_str(r#"{"prompt": "Calm piano"}"#).unwrap();
+        assert_eq!(describe_controls(&params), None);
+
+        params.bpm = Some(120);
+        params.key = Some("a minor".to_string());
+        assert_eq!(
+            describe_controls(&params).as_deref(),
+            Some("Tempo and key added to the prompt: \"Calm piano, at 120 BPM, in A minor\"")
+        );
     }
 
     #[test]
@@ -275,8 +559,17 @@ mod tests {
             negative_prompt: None,
             seed: None,
             sample_count: None,
+            duration_seconds: None,
+            bpm: None,
+            key: None,
             output_file: None,
             output_gcs_uri: None,
+            upload: None,
+            no_cache: None,
+            location: None,
+            idempotency_key: None,
+            write_metadata: None,
+            output_format: None,
         };
 
         let gen_params: MusicGenerateParams = tool_params.into();
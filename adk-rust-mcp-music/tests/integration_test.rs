@@ -9,7 +9,7 @@
 //! - Access to Vertex AI Lyria API
 
 use adk_rust_mcp_common::config::Config;
-use adk_rust_mcp_music::handler::{MusicGenerateParams, MusicHandler};
+use adk_rust_mcp_music::handler::{MusicGenerateParams, MusicHandler, OutputFormat};
 use std::env;
 use std::path::PathBuf;
 use std::sync::Once;
@@ -33,7 +33,7 @@ fn get_test_config() -> Option<Config> {
         project_id,
         location: env::var("LOCATION").unwrap_or_else(|_| "us-central1".to_string()),
         gcs_bucket: env::var("GCS_BUCKET").ok(),
-        port: 8080,
+        ..Default::default()
     })
 }
 
@@ -81,8 +81,16 @@ async fn test_validation_empty_prompt() {
         negative_prompt: None,
         seed: None,
         sample_count: 1,
+        duration_seconds: None,
+        bpm: None,
+        key: None,
         output_file: None,
         output_gcs_uri: None,
+        upload: false,
+        no_cache: false,
+        location: None,
+        write_metadata: false,
+        output_format: OutputFormat::Wav,
     };
 
     let result = params.validate();
@@ -98,8 +106,16 @@ async fn test_validation_invalid_sample_count() {
         negative_prompt: None,
         seed: None,
         sample_count: 5, // Invalid: max is 4
+        duration_seconds: None,
+        bpm: None,
+        key: None,
         output_file: None,
         output_gcs_uri: None,
+        upload: false,
+        no_cache: false,
+        location: None,
+        write_metadata: false,
+        output_format: OutputFormat::Wav,
     };
 
     let result = params.validate();
@@ -115,8 +131,16 @@ async fn test_validation_valid_params() {
         negative_prompt: Some("drums".to_string()),
         seed: Some(42),
         sample_count: 2,
+        duration_seconds: None,
+        bpm: None,
+        key: None,
         output_file: None,
         output_gcs_uri: None,
+        upload: false,
+        no_cache: false,
+        location: None,
+        write_metadata: false,
+        output_format: OutputFormat::Wav,
     };
 
     assert!(params.validate().is_ok());
@@ -151,8 +175,16 @@ mod lyria_api_tests {
             negative_prompt: Some("vocals, drums".to_string()),
             seed: Some(12345),
             sample_count: 1,
+            duration_seconds: None,
+            bpm: None,
+            key: None,
             output_file: None,
             output_gcs_uri: None,
+            upload: false,
+            no_cache: false,
+            location: None,
+            write_metadata: false,
+            output_format: OutputFormat::Wav,
         };
         
         eprintln!("Starting music generation (this may take a while)...");
@@ -163,6 +195,7 @@ mod lyria_api_tests {
                 assert!(!samples.is_empty(), "Should have at least one sample");
                 assert!(!samples[0].data.is_empty(), "Audio data should not be empty");
                 assert!(samples[0].mime_type.starts_with("audio/"), "Should have audio MIME type");
+                assert!(samples[0].duration_seconds.is_some_and(|d| d > 0.0), "Duration should be read from the WAV header");
                 eprintln!("Generated {} audio sample(s)", samples.len());
             }
             Ok(other) => panic!("Expected Base64 result, got {:?}", other),
@@ -189,17 +222,25 @@ mod lyria_api_tests {
             negative_prompt: None,
             seed: Some(54321),
             sample_count: 1,
+            duration_seconds: None,
+            bpm: None,
+            key: None,
             output_file: Some(output_path.to_string_lossy().to_string()),
             output_gcs_uri: None,
+            upload: false,
+            no_cache: false,
+            location: None,
+            write_metadata: false,
+            output_format: OutputFormat::Wav,
         };
         
         eprintln!("Starting music generation to file (this may take a while)...");
         let result = handler.generate_music(params).await;
         
         match result {
-            Ok(MusicGenerateResult::LocalFiles(paths)) => {
-                assert_eq!(paths.len(), 1, "Should have 1 output path");
-                let path = std::path::PathBuf::from(&paths[0]);
+            Ok(MusicGenerateResult::LocalFiles(saved)) => {
+                assert_eq!(saved.len(), 1, "Should have 1 output path");
+                let path = std::path::PathBuf::from(&saved[0].location);
                 assert!(path.exists(), "Output file should exist");
                 
                 let metadata = std::fs::metadata(&path).expect("Should read file metadata");
@@ -231,19 +272,27 @@ mod lyria_api_tests {
             negative_prompt: None,
             seed: Some(99999),
             sample_count: 2,
+            duration_seconds: None,
+            bpm: None,
+            key: None,
             output_file: Some(output_path.to_string_lossy().to_string()),
             output_gcs_uri: None,
+            upload: false,
+            no_cache: false,
+            location: None,
+            write_metadata: false,
+            output_format: OutputFormat::Wav,
         };
         
         eprintln!("Starting music generation with 2 samples (this may take a while)...");
         let result = handler.generate_music(params).await;
         
         match result {
-            Ok(MusicGenerateResult::LocalFiles(paths)) => {
-                assert_eq!(paths.len(), 2, "Should have 2 output paths");
-                for path_str in &paths {
-                    let path = std::path::PathBuf::from(path_str);
-                    assert!(path.exists(), "Output file {} should exist", path_str);
+            Ok(MusicGenerateResult::LocalFiles(saved)) => {
+                assert_eq!(saved.len(), 2, "Should have 2 output paths");
+                for audio in &saved {
+                    let path = std::path::PathBuf::from(&audio.location);
+                    assert!(path.exists(), "Output file {} should exist", audio.location);
                     eprintln!("Music saved to: {}", path.display());
                 }
             }
@@ -278,18 +327,26 @@ mod lyria_api_tests {
             negative_prompt: None,
             seed: Some(77777),
             sample_count: 1,
+            duration_seconds: None,
+            bpm: None,
+            key: None,
             output_file: None,
             output_gcs_uri: Some(output_uri.clone()),
+            upload: false,
+            no_cache: false,
+            location: None,
+            write_metadata: false,
+            output_format: OutputFormat::Wav,
         };
         
         eprintln!("Starting music generation to GCS (this may take a while)...");
         let result = handler.generate_music(params).await;
         
         match result {
-            Ok(MusicGenerateResult::GcsUris(uris)) => {
-                assert_eq!(uris.len(), 1, "Should have 1 output URI");
-                assert!(uris[0].starts_with("gs://"), "Should be a GCS URI");
-                eprintln!("Music uploaded to GCS: {}", uris[0]);
+            Ok(MusicGenerateResult::GcsUris(saved)) => {
+                assert_eq!(saved.len(), 1, "Should have 1 output URI");
+                assert!(saved[0].location.starts_with("gs://"), "Should be a GCS URI");
+                eprintln!("Music uploaded to GCS: {}", saved[0].location);
             }
             Ok(other) => panic!("Expected GcsUris result, got {:?}", other),
             Err(e) => panic!("Music generation to GCS failed: {}", e),
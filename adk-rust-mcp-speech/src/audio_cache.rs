@@ -0,0 +1,256 @@
+//! On-disk cache of synthesized audio.
+//!
+//! Cloud TTS output is deterministic for a given request body, so repeated
+//! synthesis of the same prompt (UI strings, IVR menus, notification sounds)
+//! can be served from disk instead of calling the API again.
+//!
+//! Entries are stored as `<key>.wav` files under `SPEECH_CACHE_DIR`, where the
+//! key is [`cache_key`] over the full TTS request (input, voice, rate, pitch,
+//! encoding and sample rate). The directory is bounded by total size and entry
+//! count; when either limit is exceeded the least recently used entries are
+//! removed. Recency is the file's modification time, refreshed on every hit.
+
+use adk_rust_mcp_common::cache::cache_key;
+use adk_rust_mcp_common::error::{ConfigError, Error};
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+/// Environment variable enabling the cache (directory to store entries in).
+pub const SPEECH_CACHE_DIR_ENV: &str = "SPEECH_CACHE_DIR";
+
+/// Environment variable bounding the total size of cached audio in bytes.
+pub const SPEECH_CACHE_MAX_BYTES_ENV: &str = "SPEECH_CACHE_MAX_BYTES";
+
+/// Environment variable bounding the number of cached entries.
+pub const SPEECH_CACHE_MAX_ENTRIES_ENV: &str = "SPEECH_CACHE_MAX_ENTRIES";
+
+/// Default size bound (256 MiB).
+pub const DEFAULT_CACHE_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Default entry count bound.
+pub const DEFAULT_CACHE_MAX_ENTRIES: usize = 1000;
+
+/// File extension of cache entries.
+const ENTRY_EXTENSION: &str = "wav";
+
+/// Content-addressed, size-bounded audio cache in a local directory.
+#[derive(Debug)]
+pub struct AudioCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    max_entries: usize,
+    /// Serializes writes and eviction within this process.
+    write_lock: Mutex<()>,
+}
+
+impl AudioCache {
+    /// Create a cache in `dir` with the given bounds.
+    ///
+    /// The directory is created on the first write.
+    pub fn new(dir: impl Into<PathBuf>, max_bytes: u64, max_entries: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes,
+            max_entries,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Build the cache selected by the environment.
+    ///
+    /// Returns `None` when `SPEECH_CACHE_DIR` is unset or empty.
+    ///
+    /// # Errors
+    /// Returns `ConfigError::InvalidValue` if a size bound is not a positive
+    /// integer.
+    pub fn from_env() -> Result<Option<Self>, Error> {
+        let dir = match std::env::var(SPEECH_CACHE_DIR_ENV) {
+            Ok(dir) if !dir.trim().is_empty() => PathBuf::from(dir.trim()),
+            _ => return Ok(None),
+        };
+        let max_bytes = bound_from_env(SPEECH_CACHE_MAX_BYTES_ENV, DEFAULT_CACHE_MAX_BYTES)?;
+        let max_entries = bound_from_env(SPEECH_CACHE_MAX_ENTRIES_ENV, DEFAULT_CACHE_MAX_ENTRIES as u64)?;
+
+        info!(dir = %dir.display(), max_bytes, max_entries, "Using on-disk speech cache");
+        Ok(Some(Self::new(dir, max_bytes, max_entries as usize)))
+    }
+
+    /// Compute the cache key for a TTS request body.
+    ///
+    /// # Errors
+    /// Returns `Error::Validation` if the request cannot be serialized.
+    pub fn key<T: Serialize>(request: &T) -> Result<String, Error> {
+        cache_key("speech_synthesize", request)
+    }
+
+    /// Path of the entry for `key`.
+    pub fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", key, ENTRY_EXTENSION))
+    }
+
+    /// Look up cached audio, marking the entry as recently used.
+    ///
+    /// Read failures are treated as misses.
+    pub async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let path = self.entry_path(key);
+        let data = match tokio::fs::read(&path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Failed to read speech cache entry");
+                return None;
+            }
+        };
+
+        if let Err(e) = touch(&path) {
+            debug!(path = %path.display(), error = %e, "Failed to refresh speech cache entry");
+        }
+        Some(data)
+    }
+
+    /// Store audio for `key`, then evict entries beyond the size bounds.
+    ///
+    /// # Errors
+    /// Returns `Error::Io` if the entry cannot be written.
+    pub async fn put(&self, key: &str, data: &[u8]) -> Result<(), Error> {
+        let _guard = self.write_lock.lock().await;
+
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let path = self.entry_path(key);
+        // Write then rename so concurrent readers never see a partial entry
+        let partial = self.dir.join(format!("{}.partial", key));
+        tokio::fs::write(&partial, data).await?;
+        tokio::fs::rename(&partial, &path).await?;
+
+        let evicted = self.evict()?;
+        if evicted > 0 {
+            debug!(evicted, "Evicted speech cache entries");
+        }
+        Ok(())
+    }
+
+    /// Remove least recently used entries until both bounds are met.
+    ///
+    /// Returns the number of entries removed.
+    fn evict(&self) -> Result<usize, Error> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(ENTRY_EXTENSION) {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            entries.push((modified, metadata.len(), path));
+        }
+
+        // Newest first; everything past the bounds is removed
+        entries.sort_by_key(|(modified, _, _)| Reverse(*modified));
+
+        let mut kept_bytes = 0u64;
+        let mut removed = 0;
+        for (index, (_, size, path)) in entries.into_iter().enumerate() {
+            if index < self.max_entries && kept_bytes + size <= self.max_bytes {
+                kept_bytes += size;
+                continue;
+            }
+            match std::fs::remove_file(&path) {
+                Ok(()) => removed += 1,
+                Err(e) => warn!(path = %path.display(), error = %e, "Failed to evict speech cache entry"),
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// Read a positive integer bound from the environment.
+fn bound_from_env(name: &str, default: u64) -> Result<u64, ConfigError> {
+    match std::env::var(name) {
+        Ok(value) if !value.trim().is_empty() => value
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .filter(|&bound| bound > 0)
+            .ok_or_else(|| ConfigError::invalid_value(name, format!("expected a positive integer, got '{}'", value))),
+        _ => Ok(default),
+    }
+}
+
+/// Set a file's modification time to now.
+fn touch(path: &Path) -> std::io::Result<()> {
+    std::fs::File::options()
+        .write(true)
+        .open(path)?
+        .set_modified(SystemTime::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn age(cache: &AudioCache, key: &str, seconds: u64) {
+        std::fs::File::options()
+            .write(true)
+            .open(cache.entry_path(key))
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(seconds))
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_put_then_get_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AudioCache::new(dir.path().join("cache"), 1024, 10);
+
+        assert!(cache.get("abc").await.is_none());
+        cache.put("abc", b"RIFF").await.unwrap();
+        assert_eq!(cache.get("abc").await.unwrap(), b"RIFF");
+    }
+
+    #[tokio::test]
+    async fn test_evicts_least_recently_used_by_count() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AudioCache::new(dir.path(), 1024, 2);
+
+        cache.put("a", b"1").await.unwrap();
+        age(&cache, "a", 30);
+        cache.put("b", b"2").await.unwrap();
+        age(&cache, "b", 20);
+
+        // Reading "a" makes "b" the least recently used
+        assert!(cache.get("a").await.is_some());
+        cache.put("c", b"3").await.unwrap();
+
+        assert!(cache.get("a").await.is_some());
+        assert!(cache.get("b").await.is_none());
+        assert!(cache.get("c").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_evicts_oldest_beyond_byte_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AudioCache::new(dir.path(), 10, 100);
+
+        cache.put("old", &[0u8; 6]).await.unwrap();
+        age(&cache, "old", 60);
+        cache.put("new", &[0u8; 6]).await.unwrap();
+
+        assert!(cache.get("old").await.is_none());
+        assert!(cache.get("new").await.is_some());
+    }
+
+    #[test]
+    fn test_key_depends_on_request_content() {
+        let a = AudioCache::key(&serde_json::json!({"text": "hi", "rate": 1.0})).unwrap();
+        let b = AudioCache::key(&serde_json::json!({"rate": 1.0, "text": "hi"})).unwrap();
+        let c = AudioCache::key(&serde_json::json!({"text": "hi", "rate": 1.5})).unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+}
@@ -6,11 +6,55 @@
 use adk_rust_mcp_common::auth::AuthProvider;
 use adk_rust_mcp_common::config::Config;
 use adk_rust_mcp_common::error::Error;
+use adk_rust_mcp_common::gcs::{GcsClient, GcsUri};
+use adk_rust_mcp_common::voices::{self, VoiceCache, VoiceSource};
+use crate::audio_cache::AudioCache;
+use crate::lexicon::{self, LexiconCache, LexiconFormat, LexiconVersion};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
-use tracing::{debug, info, instrument};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{debug, info, instrument, warn};
+
+/// Base URL of the Cloud TTS API.
+pub const TTS_API_BASE_URL: &str = voices::TTS_API_BASE_URL;
+
+/// Chirp3-HD voices and their SSML genders, served in `en-US` when the
+/// voices API is unavailable.
+pub const FALLBACK_VOICES: &[(&str, &str)] = &[
+    ("Achernar", "FEMALE"),
+    ("Achird", "MALE"),
+    ("Algenib", "MALE"),
+    ("Algieba", "MALE"),
+    ("Alnilam", "MALE"),
+    ("Aoede", "FEMALE"),
+    ("Autonoe", "FEMALE"),
+    ("Callirrhoe", "FEMALE"),
+    ("Charon", "MALE"),
+    ("Despina", "FEMALE"),
+    ("Enceladus", "MALE"),
+    ("Erinome", "FEMALE"),
+    ("Fenrir", "MALE"),
+    ("Gacrux", "FEMALE"),
+    ("Iapetus", "MALE"),
+    ("Kore", "FEMALE"),
+    ("Laomedeia", "FEMALE"),
+    ("Leda", "FEMALE"),
+    ("Orus", "MALE"),
+    ("Puck", "MALE"),
+    ("Pulcherrima", "FEMALE"),
+    ("Rasalgethi", "MALE"),
+    ("Sadachbia", "MALE"),
+    ("Sadaltager", "MALE"),
+    ("Schedar", "MALE"),
+    ("Sulafat", "FEMALE"),
+    ("Umbriel", "MALE"),
+    ("Vindemiatrix", "FEMALE"),
+    ("Zephyr", "FEMALE"),
+    ("Zubenelgenubi", "MALE"),
+];
 
 /// Default voice for speech synthesis.
 pub const DEFAULT_VOICE: &str = "en-US-Chirp3-HD-Achernar";
@@ -36,6 +80,34 @@ pub const MIN_PITCH: f32 = -20.0;
 /// Maximum pitch (semitones).
 pub const MAX_PITCH: f32 = 20.0;
 
+/// Minimum volume gain (dB).
+pub const MIN_VOLUME_GAIN_DB: f64 = -96.0;
+
+/// Maximum volume gain (dB).
+pub const MAX_VOLUME_GAIN_DB: f64 = 16.0;
+
+/// Audio effects profiles accepted by Cloud TTS, applied in the given order.
+pub const VALID_EFFECTS_PROFILES: &[&str] = &[
+    "wearable-class-device",
+    "handset-class-device",
+    "headphone-class-device",
+    "small-bluetooth-speaker-class-device",
+    "medium-bluetooth-speaker-class-device",
+    "large-home-entertainment-class-device",
+    "large-automotive-class-device",
+    "telephony-class-application",
+];
+
+/// Longest piece of text, in characters, synthesized per streamed chunk.
+pub const STREAMING_CHUNK_CHARS: usize = 400;
+
+/// Largest text or SSML input, in bytes, Cloud TTS accepts in one request.
+pub const MAX_INPUT_BYTES: usize = 5000;
+
+/// Largest text, in bytes, accepted for streaming synthesis, which sends it
+/// in pieces of at most [`STREAMING_CHUNK_CHARS`] characters.
+pub const MAX_STREAMING_TEXT_BYTES: usize = 100_000;
+
 /// Valid pronunciation alphabets.
 pub const VALID_ALPHABETS: &[&str] = &["ipa", "x-sampa"];
 
@@ -43,7 +115,7 @@ pub const VALID_ALPHABETS: &[&str] = &["ipa", "x-sampa"];
 /// Custom pronunciation for a word.
 ///
 /// Allows specifying phonetic pronunciation using IPA or X-SAMPA alphabets.
-#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
 pub struct Pronunciation {
     /// The word to apply custom pronunciation to.
     pub word: String,
@@ -121,14 +193,34 @@ pub struct SpeechSynthesizeParams {
     #[serde(default)]
     pub pitch: f32,
 
+    /// Volume gain in dB (-96.0 to 16.0, default 0.0).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume_gain_db: Option<f64>,
+
+    /// Audio effects profiles to optimize the audio for playback devices
+    /// (e.g., "headphone-class-device"), applied in order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effects_profile_id: Option<Vec<String>>,
+
     /// Custom pronunciations for specific words.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub pronunciations: Option<Vec<Pronunciation>>,
 
+    /// Pronunciation lexicon file (local path or gs:// URI), JSON or CSV.
+    /// Merged with `pronunciations`; inline entries win on conflict.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lexicon_file: Option<String>,
+
     /// Output file path for saving the WAV locally.
-    /// If not specified, returns base64-encoded data.
+    /// If not specified and output_gcs_uri is not specified, returns base64-encoded data.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_file: Option<String>,
+
+    /// Output GCS URI for saving the WAV to cloud storage.
+    /// Format: gs://bucket/path/to/output.wav, or an object name in `GCS_BUCKET`.
+    /// Takes precedence over output_file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_gcs_uri: Option<String>,
 }
 
 fn default_language_code() -> String {
@@ -155,13 +247,53 @@ impl std::fmt::Display for ValidationError {
     }
 }
 
+/// Check a Cloud TTS voice name against its expected format and language.
+///
+/// Voice names have the form `<language>-<REGION>-<model>-<variant>`, e.g.
+/// `en-US-Chirp3-HD-Achernar`; the language and region must match
+/// `language_code` (compared case-insensitively).
+pub fn validate_voice_name(voice: &str, language_code: &str) -> Result<(), String> {
+    let segments: Vec<&str> = voice.split('-').collect();
+    let well_formed = segments.len() >= 3
+        && segments.iter().all(|s| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric()))
+        && (2..=3).contains(&segments[0].len())
+        && segments[0].chars().all(|c| c.is_ascii_lowercase())
+        && (segments[1].len() == 2 && segments[1].chars().all(|c| c.is_ascii_uppercase())
+            || segments[1].len() == 3 && segments[1].chars().all(|c| c.is_ascii_digit()));
+    if !well_formed {
+        return Err(format!(
+            "voice must look like '<language>-<REGION>-<model>-<variant>' (e.g. '{}'), got '{}'",
+            DEFAULT_VOICE, voice
+        ));
+    }
+
+    let voice_language = format!("{}-{}", segments[0], segments[1]);
+    if !voice_language.eq_ignore_ascii_case(language_code.trim()) {
+        return Err(format!(
+            "voice '{}' is for language '{}', but language_code is '{}'",
+            voice, voice_language, language_code
+        ));
+    }
+    Ok(())
+}
+
 impl SpeechSynthesizeParams {
-    /// Validate the parameters.
+    /// Validate the parameters for a single Cloud TTS request.
+    ///
+    /// `text` is limited to [`MAX_INPUT_BYTES`].
     ///
     /// # Returns
     /// - `Ok(())` if all parameters are valid
     /// - `Err(Vec<ValidationError>)` with all validation errors
     pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        self.validate_with_text_limit(MAX_INPUT_BYTES)
+    }
+
+    /// Validate the parameters, allowing up to `max_text_bytes` of text.
+    ///
+    /// Streaming synthesis splits text into several requests and passes
+    /// [`MAX_STREAMING_TEXT_BYTES`].
+    pub fn validate_with_text_limit(&self, max_text_bytes: usize) -> Result<(), Vec<ValidationError>> {
         let mut errors = Vec::new();
 
         // Validate text is not empty
@@ -170,10 +302,35 @@ impl SpeechSynthesizeParams {
                 field: "text".to_string(),
                 message: "Text cannot be empty".to_string(),
             });
+        } else if self.text.len() > max_text_bytes {
+            let hint = if max_text_bytes < MAX_STREAMING_TEXT_BYTES {
+                "; use speech_synthesize_streaming for longer text"
+            } else {
+                ""
+            };
+            errors.push(ValidationError {
+                field: "text".to_string(),
+                message: format!(
+                    "Text is {} bytes, the limit is {} bytes{}",
+                    self.text.len(),
+                    max_text_bytes,
+                    hint
+                ),
+            });
+        }
+
+        // Validate voice name format and that it speaks the requested language
+        if let Some(ref voice) = self.voice {
+            if let Err(message) = validate_voice_name(voice, &self.language_code) {
+                errors.push(ValidationError {
+                    field: "voice".to_string(),
+                    message,
+                });
+            }
         }
 
-        // Validate speaking_rate range
-        if self.speaking_rate < MIN_SPEAKING_RATE || self.speaking_rate > MAX_SPEAKING_RATE {
+        // Validate speaking_rate range (NaN is rejected too)
+        if !(MIN_SPEAKING_RATE..=MAX_SPEAKING_RATE).contains(&self.speaking_rate) {
             errors.push(ValidationError {
                 field: "speaking_rate".to_string(),
                 message: format!(
@@ -183,8 +340,8 @@ impl SpeechSynthesizeParams {
             });
         }
 
-        // Validate pitch range
-        if self.pitch < MIN_PITCH || self.pitch > MAX_PITCH {
+        // Validate pitch range (NaN is rejected too)
+        if !(MIN_PITCH..=MAX_PITCH).contains(&self.pitch) {
             errors.push(ValidationError {
                 field: "pitch".to_string(),
                 message: format!(
@@ -194,6 +351,35 @@ impl SpeechSynthesizeParams {
             });
         }
 
+        // Validate volume_gain_db range (NaN is rejected too)
+        if let Some(gain) = self.volume_gain_db {
+            if !(MIN_VOLUME_GAIN_DB..=MAX_VOLUME_GAIN_DB).contains(&gain) {
+                errors.push(ValidationError {
+                    field: "volume_gain_db".to_string(),
+                    message: format!(
+                        "volume_gain_db must be between {} and {} dB, got {}",
+                        MIN_VOLUME_GAIN_DB, MAX_VOLUME_GAIN_DB, gain
+                    ),
+                });
+            }
+        }
+
+        // Validate effects profiles against the known device profiles
+        if let Some(ref profiles) = self.effects_profile_id {
+            for (i, profile) in profiles.iter().enumerate() {
+                if !VALID_EFFECTS_PROFILES.contains(&profile.as_str()) {
+                    errors.push(ValidationError {
+                        field: format!("effects_profile_id[{}]", i),
+                        message: format!(
+                            "Unknown effects profile '{}'. Must be one of: {}",
+                            profile,
+                            VALID_EFFECTS_PROFILES.join(", ")
+                        ),
+                    });
+                }
+            }
+        }
+
         // Validate pronunciations if provided
         if let Some(ref pronunciations) = self.pronunciations {
             for (i, pron) in pronunciations.iter().enumerate() {
@@ -206,6 +392,37 @@ impl SpeechSynthesizeParams {
             }
         }
 
+        // Validate the lexicon path; its contents are checked when loaded
+        if let Some(ref path) = self.lexicon_file {
+            if path.trim().is_empty() {
+                errors.push(ValidationError {
+                    field: "lexicon_file".to_string(),
+                    message: "lexicon_file cannot be empty".to_string(),
+                });
+            } else if LexiconFormat::from_path(path).is_none() {
+                errors.push(ValidationError {
+                    field: "lexicon_file".to_string(),
+                    message: format!(
+                        "lexicon_file must end in .json or .csv, got '{}'",
+                        path
+                    ),
+                });
+            }
+        }
+
+        // Validate output_gcs_uri format if provided
+        if let Some(ref uri) = self.output_gcs_uri {
+            if !uri.starts_with("gs://") {
+                errors.push(ValidationError {
+                    field: "output_gcs_uri".to_string(),
+                    message: format!(
+                        "output_gcs_uri must be a GCS URI starting with 'gs://', got '{}'",
+                        uri
+                    ),
+                });
+            }
+        }
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -218,15 +435,61 @@ impl SpeechSynthesizeParams {
         self.voice.as_deref().unwrap_or(DEFAULT_VOICE)
     }
 
+    /// Check that the input sent to Cloud TTS fits in one request.
+    ///
+    /// With pronunciations the input is the generated SSML, which is longer
+    /// than `text`, so this is checked after the lexicon is merged.
+    pub fn validate_input_size(&self) -> Result<(), ValidationError> {
+        if self.pronunciations.is_none() {
+            if self.text.len() > MAX_INPUT_BYTES {
+                return Err(ValidationError {
+                    field: "text".to_string(),
+                    message: format!(
+                        "Text is {} bytes, the limit is {} bytes per request",
+                        self.text.len(),
+                        MAX_INPUT_BYTES
+                    ),
+                });
+            }
+            return Ok(());
+        }
+
+        let ssml_bytes = self.build_ssml().len();
+        if ssml_bytes > MAX_INPUT_BYTES {
+            return Err(ValidationError {
+                field: "text".to_string(),
+                message: format!(
+                    "SSML with pronunciations applied is {} bytes, the limit is {} bytes per request",
+                    ssml_bytes, MAX_INPUT_BYTES
+                ),
+            });
+        }
+        Ok(())
+    }
+
     /// Build SSML text with pronunciations applied.
+    ///
+    /// The text is scanned once, so a word is never matched inside markup
+    /// inserted for another. Where words overlap, the longest match wins.
     pub fn build_ssml(&self) -> String {
-        let mut text = self.text.clone();
-
-        // Apply pronunciations if provided
-        if let Some(ref pronunciations) = self.pronunciations {
-            for pron in pronunciations {
-                // Replace word with SSML phoneme
-                text = text.replace(&pron.word, &pron.to_ssml());
+        let mut pronunciations: Vec<&Pronunciation> = self
+            .pronunciations
+            .iter()
+            .flatten()
+            .filter(|p| !p.word.is_empty())
+            .collect();
+        pronunciations.sort_by_key(|p| std::cmp::Reverse(p.word.len()));
+
+        let mut text = String::with_capacity(self.text.len());
+        let mut rest = self.text.as_str();
+        while let Some(c) = rest.chars().next() {
+            // Replace word with SSML phoneme
+            if let Some(pron) = pronunciations.iter().find(|p| rest.starts_with(&p.word)) {
+                text.push_str(&pron.to_ssml());
+                rest = &rest[pron.word.len()..];
+            } else {
+                text.push(c);
+                rest = &rest[c.len_utf8()..];
             }
         }
 
@@ -242,43 +505,72 @@ impl SpeechSynthesizeParams {
 pub struct SpeechHandler {
     /// Application configuration.
     pub config: Config,
+    /// GCS client for storage operations.
+    pub gcs: GcsClient,
     /// HTTP client for API requests.
     pub http: reqwest::Client,
-    /// Authentication provider.
-    pub auth: AuthProvider,
+    /// Authentication provider (shared with the GCS client).
+    pub auth: Arc<AuthProvider>,
+    /// Parsed pronunciation lexicons.
+    pub lexicons: LexiconCache,
+    /// On-disk cache of synthesized audio (enabled by `SPEECH_CACHE_DIR`).
+    pub audio_cache: Option<Arc<AudioCache>>,
+    /// Base URL of the Cloud TTS API.
+    pub api_base_url: String,
+    /// Voice list fetched from the Cloud TTS API.
+    pub voices: VoiceCache,
 }
 
 impl SpeechHandler {
     /// Create a new SpeechHandler with the given configuration.
     ///
     /// # Errors
-    /// Returns an error if auth provider initialization fails.
+    /// Returns an error if GCS client or auth provider initialization fails.
     #[instrument(level = "debug", name = "speech_handler_new", skip_all)]
     pub async fn new(config: Config) -> Result<Self, Error> {
         debug!("Initializing SpeechHandler");
 
-        let auth = AuthProvider::new().await?;
-        let http = reqwest::Client::new();
-
-        Ok(Self { config, http, auth })
+        let auth = Arc::new(AuthProvider::new().await?);
+        let http = config.http.build()?;
+        let gcs = GcsClient::with_client(Arc::clone(&auth), http.clone());
+        let audio_cache = AudioCache::from_env()?.map(Arc::new);
+
+        Ok(Self {
+            config,
+            gcs,
+            http,
+            auth,
+            lexicons: LexiconCache::new(),
+            audio_cache,
+            api_base_url: TTS_API_BASE_URL.to_string(),
+            voices: VoiceCache::from_env(),
+        })
     }
 
     /// Create a new SpeechHandler with provided dependencies (for testing).
     #[cfg(test)]
-    pub fn with_deps(config: Config, http: reqwest::Client, auth: AuthProvider) -> Self {
-        Self { config, http, auth }
+    pub fn with_deps(config: Config, gcs: GcsClient, http: reqwest::Client, auth: Arc<AuthProvider>) -> Self {
+        Self {
+            config,
+            gcs,
+            http,
+            auth,
+            lexicons: LexiconCache::new(),
+            audio_cache: None,
+            api_base_url: TTS_API_BASE_URL.to_string(),
+            voices: VoiceCache::default(),
+        }
     }
 
-    /// Get the Cloud TTS API endpoint.
-    pub fn get_endpoint(&self) -> String {
-        format!(
-            "https://texttospeech.googleapis.com/v1/text:synthesize"
-        )
+    /// Replace the synthesized audio cache.
+    pub fn with_audio_cache(mut self, cache: Arc<AudioCache>) -> Self {
+        self.audio_cache = Some(cache);
+        self
     }
 
-    /// Get the Cloud TTS voices list endpoint.
-    pub fn get_voices_endpoint(&self) -> String {
-        format!("https://texttospeech.googleapis.com/v1/voices")
+    /// Get the Cloud TTS API endpoint.
+    pub fn get_endpoint(&self) -> String {
+        format!("{}/v1/text:synthesize", self.api_base_url)
     }
 
     /// Synthesize speech from text.
@@ -287,18 +579,136 @@ impl SpeechHandler {
     /// * `params` - Speech synthesis parameters
     ///
     /// # Returns
-    /// * `Ok(SpeechSynthesizeResult)` - Generated audio with data or path
+    /// * `Ok(SynthesizedSpeech)` - Generated audio with data or path, and whether it was cached
     /// * `Err(Error)` - If validation fails, API call fails, or output handling fails
     #[instrument(level = "info", name = "synthesize_speech", skip(self, params))]
-    pub async fn synthesize(&self, params: SpeechSynthesizeParams) -> Result<SpeechSynthesizeResult, Error> {
+    pub async fn synthesize(&self, params: SpeechSynthesizeParams) -> Result<SynthesizedSpeech, Error> {
+        let params = self.prepare(params, MAX_INPUT_BYTES).await?;
+        params
+            .validate_input_size()
+            .map_err(|e| Error::validation(e.to_string()))?;
+
+        info!(voice = %params.get_voice(), "Synthesizing speech with Cloud TTS API");
+        let request = Self::build_request(&params);
+
+        // Serve repeated requests from the on-disk cache
+        let cache_entry = self.cache_entry(&request)?;
+        if let Some(audio) = self.cached_audio(&cache_entry).await {
+            let output = self.handle_output(audio, &params).await?;
+            return Ok(SynthesizedSpeech { output, cached: true });
+        }
+
+        let audio = self.call_tts(&request).await?;
+        if let Ok(data) = BASE64.decode(&audio.data) {
+            self.store_audio(&cache_entry, &data).await;
+        } else {
+            warn!("Not caching undecodable audio");
+        }
+
+        // Handle output based on params
+        let output = self.handle_output(audio, &params).await?;
+        Ok(SynthesizedSpeech { output, cached: false })
+    }
+
+    /// Synthesize speech sentence by sentence, sending each clip on `chunks`
+    /// as soon as it is ready.
+    ///
+    /// The text is split at sentence boundaries into pieces of at most
+    /// [`STREAMING_CHUNK_CHARS`] characters (a longer sentence is sent whole),
+    /// which are synthesized in order. The clips are then joined into one WAV
+    /// that is delivered like [`SpeechHandler::synthesize`]. A cache hit is sent
+    /// as a single chunk. Chunks are dropped if the receiver has gone away.
+    ///
+    /// Text may be up to [`MAX_STREAMING_TEXT_BYTES`]; each piece must fit in
+    /// one request.
+    ///
+    /// # Errors
+    /// Returns an error if validation fails, any API call fails, or output
+    /// handling fails.
+    #[instrument(level = "info", name = "synthesize_speech_streaming", skip_all)]
+    pub async fn synthesize_streaming(
+        &self,
+        params: SpeechSynthesizeParams,
+        chunks: mpsc::Sender<SpeechChunk>,
+    ) -> Result<SynthesizedSpeech, Error> {
+        let params = self.prepare(params, MAX_STREAMING_TEXT_BYTES).await?;
+
+        let cache_entry = self.cache_entry(&Self::build_request(&params))?;
+        if let Some(audio) = self.cached_audio(&cache_entry).await {
+            send_chunk(&chunks, SpeechChunk { index: 0, total: 1, audio: audio.clone() }).await;
+            let output = self.handle_output(audio, &params).await?;
+            return Ok(SynthesizedSpeech { output, cached: true });
+        }
+
+        let pieces: Vec<SpeechSynthesizeParams> = split_sentences(&params.text, STREAMING_CHUNK_CHARS)
+            .into_iter()
+            .map(|piece| SpeechSynthesizeParams {
+                text: piece,
+                ..params.clone()
+            })
+            .collect();
+
+        // A single overlong sentence is sent whole; reject it before any API call
+        for (index, piece) in pieces.iter().enumerate() {
+            piece.validate_input_size().map_err(|e| {
+                Error::validation(format!("{} (in sentence {})", e, index + 1))
+            })?;
+        }
+
+        let total = pieces.len();
+        info!(voice = %params.get_voice(), chunks = total, "Streaming speech with Cloud TTS API");
+
+        let mut clips = Vec::with_capacity(total);
+        for (index, piece_params) in pieces.into_iter().enumerate() {
+            let audio = self.call_tts(&Self::build_request(&piece_params)).await?;
+            let data = BASE64
+                .decode(&audio.data)
+                .map_err(|e| Error::api(self.get_endpoint(), 200, format!("Invalid base64 audio: {}", e)))?;
+            clips.push(data);
+            send_chunk(&chunks, SpeechChunk { index, total, audio }).await;
+        }
+
+        let joined = join_wav_clips(&clips).ok_or_else(|| {
+            Error::api(self.get_endpoint(), 200, "Cannot join streamed clips: not matching LINEAR16 WAV audio")
+        })?;
+        self.store_audio(&cache_entry, &joined).await;
+
+        let audio = GeneratedAudio {
+            data: BASE64.encode(&joined),
+            mime_type: "audio/wav".to_string(),
+        };
+        let output = self.handle_output(audio, &params).await?;
+        Ok(SynthesizedSpeech { output, cached: false })
+    }
+
+    /// Resolve outputs, validate and merge the lexicon into `params`.
+    ///
+    /// `max_text_bytes` bounds the length of `text`.
+    async fn prepare(&self, mut params: SpeechSynthesizeParams, max_text_bytes: usize) -> Result<SpeechSynthesizeParams, Error> {
+        // Bare object names go to the configured bucket
+        params.output_gcs_uri = params
+            .output_gcs_uri
+            .map(|uri| self.config.resolve_output_uri(&uri))
+            .transpose()?;
+
         // Validate parameters
-        params.validate().map_err(|errors| {
+        params.validate_with_text_limit(max_text_bytes).map_err(|errors| {
             let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
             Error::validation(messages.join("; "))
         })?;
 
-        info!(voice = %params.get_voice(), "Synthesizing speech with Cloud TTS API");
+        // Merge the lexicon under any inline pronunciations
+        if let Some(path) = &params.lexicon_file {
+            let lexicon = self.load_lexicon(path).await?;
+            let inline = params.pronunciations.take().unwrap_or_default();
+            params.pronunciations = Some(lexicon::merge_pronunciations(inline, &lexicon))
+                .filter(|merged| !merged.is_empty());
+        }
+        Ok(params)
+    }
 
+    /// Build the Cloud TTS request for `params`.
+    fn build_request(params: &SpeechSynthesizeParams) -> TtsRequest {
         // Determine if we need SSML (for pronunciations)
         let (input, use_ssml) = if params.pronunciations.is_some() {
             (params.build_ssml(), true)
@@ -306,8 +716,7 @@ impl SpeechHandler {
             (params.text.clone(), false)
         };
 
-        // Build the API request
-        let request = TtsRequest {
+        TtsRequest {
             input: TtsInput {
                 text: if use_ssml { None } else { Some(input.clone()) },
                 ssml: if use_ssml { Some(input) } else { None },
@@ -320,10 +729,43 @@ impl SpeechHandler {
                 audio_encoding: "LINEAR16".to_string(),
                 speaking_rate: Some(params.speaking_rate),
                 pitch: Some(params.pitch),
+                volume_gain_db: params.volume_gain_db,
+                effects_profile_id: params.effects_profile_id.clone(),
                 sample_rate_hertz: Some(24000),
             },
-        };
+        }
+    }
 
+    /// The audio cache and the key of `request` in it, when caching is on.
+    fn cache_entry(&self, request: &TtsRequest) -> Result<Option<(&AudioCache, String)>, Error> {
+        match &self.audio_cache {
+            Some(cache) => Ok(Some((cache.as_ref(), AudioCache::key(request)?))),
+            None => Ok(None),
+        }
+    }
+
+    /// Cached audio for `entry`, if any.
+    async fn cached_audio(&self, entry: &Option<(&AudioCache, String)>) -> Option<GeneratedAudio> {
+        let (cache, key) = entry.as_ref()?;
+        let data = cache.get(key).await?;
+        info!(cache_key = %key, "Returning cached speech audio");
+        Some(GeneratedAudio {
+            data: BASE64.encode(&data),
+            mime_type: "audio/wav".to_string(),
+        })
+    }
+
+    /// Store freshly synthesized audio under `entry`; failures are logged.
+    async fn store_audio(&self, entry: &Option<(&AudioCache, String)>, data: &[u8]) {
+        if let Some((cache, key)) = entry {
+            if let Err(e) = cache.put(key, data).await {
+                warn!(error = %e, "Failed to store speech cache entry");
+            }
+        }
+    }
+
+    /// Send a synthesis request to the Cloud TTS API.
+    async fn call_tts(&self, request: &TtsRequest) -> Result<GeneratedAudio, Error> {
         // Get auth token
         let token = self
             .auth
@@ -340,7 +782,7 @@ impl SpeechHandler {
             .header("Authorization", format!("Bearer {}", token))
             .header("Content-Type", "application/json")
             .header("x-goog-user-project", &self.config.project_id)
-            .json(&request)
+            .json(request)
             .send()
             .await
             .map_err(|e| Error::api(&endpoint, 0, format!("Request failed: {}", e)))?;
@@ -348,7 +790,7 @@ impl SpeechHandler {
         let status = response.status();
         if !status.is_success() {
             let body = response.text().await.unwrap_or_default();
-            return Err(Error::api(&endpoint, status.as_u16(), body));
+            return Err(Error::api_response(&endpoint, status.as_u16(), body));
         }
 
         // Parse response
@@ -367,74 +809,89 @@ impl SpeechHandler {
 
         info!("Received audio data from Cloud TTS API");
 
-        let audio = GeneratedAudio {
+        Ok(GeneratedAudio {
             data: audio_data,
             mime_type: "audio/wav".to_string(),
-        };
-
-        // Handle output based on params
-        self.handle_output(audio, &params).await
+        })
     }
 
 
-    /// List available voices.
+    /// List available Chirp3-HD voices.
     ///
-    /// # Returns
-    /// * `Ok(Vec<VoiceInfo>)` - List of available voices
-    /// * `Err(Error)` - If API call fails
+    /// The list comes from the Cloud TTS voices API and is cached for the
+    /// voice cache's TTL; `refresh` fetches it again. When the API cannot be
+    /// reached, the built-in [`FALLBACK_VOICES`] are returned.
     #[instrument(level = "info", name = "list_voices", skip(self))]
-    pub async fn list_voices(&self) -> Result<Vec<VoiceInfo>, Error> {
-        info!("Listing available voices from Cloud TTS API");
-
-        // Get auth token
-        let token = self
-            .auth
-            .get_token(&["https://www.googleapis.com/auth/cloud-platform"])
-            .await?;
+    pub async fn list_voices(&self, refresh: bool) -> VoiceList {
+        let fetched = self
+            .voices
+            .get(refresh, || {
+                voices::fetch_voices(&self.http, &self.auth, &self.api_base_url, &self.config.project_id)
+            })
+            .await;
+
+        match fetched {
+            Some(all) => {
+                let voices: Vec<VoiceInfo> = all
+                    .iter()
+                    .filter(|v| v.chirp3_hd_name().is_some())
+                    .map(VoiceInfo::from)
+                    .collect();
+                debug!(count = voices.len(), "Found Chirp3-HD voices");
+                VoiceList { voices, source: VoiceSource::Api }
+            }
+            None => VoiceList {
+                voices: fallback_voices(),
+                source: VoiceSource::Fallback,
+            },
+        }
+    }
 
-        // Make API request
-        let endpoint = self.get_voices_endpoint();
-        debug!(endpoint = %endpoint, "Calling Cloud TTS voices API");
+    /// Load a pronunciation lexicon from a local path or GCS URI.
+    ///
+    /// Parsed lexicons are reused until the file's modification time (or the
+    /// object's generation) changes.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is malformed.
+    pub async fn load_lexicon(&self, path: &str) -> Result<Arc<Vec<Pronunciation>>, Error> {
+        let format = LexiconFormat::from_path(path).ok_or_else(|| {
+            Error::validation(format!("lexicon_file must end in .json or .csv, got '{}'", path))
+        })?;
 
-        let response = self
-            .http
-            .get(&endpoint)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("x-goog-user-project", &self.config.project_id)
-            .send()
-            .await
-            .map_err(|e| Error::api(&endpoint, 0, format!("Request failed: {}", e)))?;
+        let (version, gcs_uri) = if path.starts_with("gs://") {
+            let uri = GcsUri::parse(path)?;
+            let generation = match uri.generation {
+                Some(generation) => generation,
+                None => self.gcs.generation(&uri).await?,
+            };
+            (LexiconVersion::Generation(generation), Some(uri))
+        } else {
+            let modified = tokio::fs::metadata(path)
+                .await
+                .and_then(|m| m.modified())
+                .map_err(|e| Error::validation(format!("Cannot read lexicon '{}': {}", path, e)))?;
+            (LexiconVersion::Modified(modified), None)
+        };
 
-        let status = response.status();
-        if !status.is_success() {
-            let body = response.text().await.unwrap_or_default();
-            return Err(Error::api(&endpoint, status.as_u16(), body));
+        if let Some(cached) = self.lexicons.get(path, &version) {
+            debug!(path = %path, "Using cached lexicon");
+            return Ok(cached);
         }
 
-        // Parse response
-        let api_response: VoicesResponse = response.json().await.map_err(|e| {
-            Error::api(
-                &endpoint,
-                status.as_u16(),
-                format!("Failed to parse response: {}", e),
-            )
-        })?;
-
-        // Filter for Chirp3-HD voices
-        let chirp3_voices: Vec<VoiceInfo> = api_response
-            .voices
-            .into_iter()
-            .filter(|v| v.name.contains("Chirp3-HD"))
-            .map(|v| VoiceInfo {
-                name: v.name,
-                language_codes: v.language_codes,
-                ssml_gender: v.ssml_gender,
-                natural_sample_rate_hertz: v.natural_sample_rate_hertz,
-            })
-            .collect();
+        let bytes = match &gcs_uri {
+            Some(uri) => self.gcs.download(uri).await?,
+            None => tokio::fs::read(path)
+                .await
+                .map_err(|e| Error::validation(format!("Cannot read lexicon '{}': {}", path, e)))?,
+        };
+        let content = String::from_utf8(bytes)
+            .map_err(|_| Error::validation(format!("Lexicon '{}' is not valid UTF-8", path)))?;
 
-        info!(count = chirp3_voices.len(), "Found Chirp3-HD voices");
-        Ok(chirp3_voices)
+        let parsed = Arc::new(lexicon::parse_lexicon(path, &content, format)?);
+        info!(path = %path, entries = parsed.len(), "Loaded pronunciation lexicon");
+        self.lexicons.insert(path, version, Arc::clone(&parsed));
+        Ok(parsed)
     }
 
     /// Handle output of generated audio based on params.
@@ -443,6 +900,11 @@ impl SpeechHandler {
         audio: GeneratedAudio,
         params: &SpeechSynthesizeParams,
     ) -> Result<SpeechSynthesizeResult, Error> {
+        // If output_gcs_uri is specified, upload to GCS
+        if let Some(output_uri) = &params.output_gcs_uri {
+            return self.upload_to_gcs(audio, output_uri).await;
+        }
+
         // If output_file is specified, save to local file
         if let Some(output_file) = &params.output_file {
             return self.save_to_file(audio, output_file).await;
@@ -452,12 +914,34 @@ impl SpeechHandler {
         Ok(SpeechSynthesizeResult::Base64(audio))
     }
 
+    /// Upload audio to GCS.
+    async fn upload_to_gcs(
+        &self,
+        audio: GeneratedAudio,
+        output_uri: &str,
+    ) -> Result<SpeechSynthesizeResult, Error> {
+        // Decode base64 data
+        let data = BASE64.decode(&audio.data).map_err(|e| {
+            Error::validation(format!("Invalid base64 data: {}", e))
+        })?;
+
+        let gcs_uri = GcsUri::parse(output_uri)?;
+        self.gcs.upload(&gcs_uri, &data, &audio.mime_type).await?;
+
+        info!(uri = %output_uri, "Uploaded audio to GCS");
+        Ok(SpeechSynthesizeResult::GcsUri(output_uri.to_string()))
+    }
+
     /// Save audio to local file.
     async fn save_to_file(
         &self,
         audio: GeneratedAudio,
         output_file: &str,
     ) -> Result<SpeechSynthesizeResult, Error> {
+        let output_file = self.config.resolve_local_path(output_file);
+        let output_file = output_file.to_string_lossy();
+        let output_file = output_file.as_ref();
+
         // Decode base64 data
         let data = BASE64.decode(&audio.data).map_err(|e| {
             Error::validation(format!("Invalid base64 data: {}", e))
@@ -528,6 +1012,12 @@ pub struct TtsAudioConfig {
     /// Pitch adjustment
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pitch: Option<f32>,
+    /// Volume gain in dB
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume_gain_db: Option<f64>,
+    /// Audio effects profiles
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effects_profile_id: Option<Vec<String>>,
     /// Sample rate in Hz
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sample_rate_hertz: Option<u32>,
@@ -541,27 +1031,6 @@ pub struct TtsResponse {
     pub audio_content: String,
 }
 
-/// Cloud TTS voices list response.
-#[derive(Debug, Deserialize)]
-pub struct VoicesResponse {
-    /// List of available voices
-    pub voices: Vec<ApiVoiceInfo>,
-}
-
-/// Voice information from API.
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ApiVoiceInfo {
-    /// Voice name
-    pub name: String,
-    /// Supported language codes
-    pub language_codes: Vec<String>,
-    /// SSML gender
-    pub ssml_gender: Option<String>,
-    /// Natural sample rate
-    pub natural_sample_rate_hertz: Option<u32>,
-}
-
 // =============================================================================
 // Result Types
 // =============================================================================
@@ -588,6 +1057,39 @@ pub struct VoiceInfo {
     pub natural_sample_rate_hertz: Option<u32>,
 }
 
+impl From<&voices::TtsVoice> for VoiceInfo {
+    fn from(voice: &voices::TtsVoice) -> Self {
+        Self {
+            name: voice.name.clone(),
+            language_codes: voice.language_codes.clone(),
+            ssml_gender: voice.ssml_gender.clone(),
+            natural_sample_rate_hertz: voice.natural_sample_rate_hertz,
+        }
+    }
+}
+
+/// Available voices and where the list came from.
+#[derive(Debug, Clone, Serialize)]
+pub struct VoiceList {
+    /// Chirp3-HD voices
+    pub voices: Vec<VoiceInfo>,
+    /// Whether the list came from the API or the built-in table
+    pub source: VoiceSource,
+}
+
+/// The built-in Chirp3-HD voices in `en-US`.
+pub fn fallback_voices() -> Vec<VoiceInfo> {
+    FALLBACK_VOICES
+        .iter()
+        .map(|&(name, gender)| VoiceInfo {
+            name: format!("{}-Chirp3-HD-{}", DEFAULT_LANGUAGE_CODE, name),
+            language_codes: vec![DEFAULT_LANGUAGE_CODE.to_string()],
+            ssml_gender: Some(gender.to_string()),
+            natural_sample_rate_hertz: Some(24000),
+        })
+        .collect()
+}
+
 /// Result of speech synthesis.
 #[derive(Debug)]
 pub enum SpeechSynthesizeResult {
@@ -595,6 +1097,122 @@ pub enum SpeechSynthesizeResult {
     Base64(GeneratedAudio),
     /// Local file path (when output_file specified)
     LocalFile(String),
+    /// Uploaded GCS URI (when output_gcs_uri specified)
+    GcsUri(String),
+}
+
+/// One clip of a streamed synthesis.
+#[derive(Debug, Clone)]
+pub struct SpeechChunk {
+    /// Position of the clip (0-based)
+    pub index: usize,
+    /// Number of clips in the stream
+    pub total: usize,
+    /// The clip as a standalone WAV
+    pub audio: GeneratedAudio,
+}
+
+/// Outcome of a synthesis call.
+#[derive(Debug)]
+pub struct SynthesizedSpeech {
+    /// Where the audio was delivered
+    pub output: SpeechSynthesizeResult,
+    /// Whether the audio was served from the on-disk cache
+    pub cached: bool,
+}
+
+
+/// Send a streamed clip, ignoring a receiver that has gone away.
+async fn send_chunk(chunks: &mpsc::Sender<SpeechChunk>, chunk: SpeechChunk) {
+    if chunks.send(chunk).await.is_err() {
+        debug!("Speech chunk receiver closed");
+    }
+}
+
+/// Split text into pieces of whole sentences of at most `max_chars`
+/// characters each.
+///
+/// Sentences end at `.`, `!`, `?` or `…` followed by whitespace, or at a line
+/// break. A sentence longer than `max_chars` becomes a piece of its own.
+/// Pieces are trimmed; text without any content yields no pieces.
+pub fn split_sentences(text: &str, max_chars: usize) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let next_is_space = chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+        if c == '\n' || (matches!(c, '.' | '!' | '?' | '…') && next_is_space) {
+            let end = i + c.len_utf8();
+            sentences.push(&text[start..end]);
+            start = end;
+        }
+    }
+    sentences.push(&text[start..]);
+
+    let mut pieces: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for sentence in sentences.into_iter().map(str::trim).filter(|s| !s.is_empty()) {
+        if !current.is_empty() && current.chars().count() + 1 + sentence.chars().count() > max_chars {
+            pieces.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(sentence);
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+/// Locate the `fmt ` chunk body and the `data` chunk of a RIFF/WAVE file.
+///
+/// Returns the fmt body and the byte range of the data payload.
+fn wav_layout(data: &[u8]) -> Option<(&[u8], std::ops::Range<usize>)> {
+    if data.len() < 12 || &data[..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return None;
+    }
+
+    let mut format = None;
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let id = &data[pos..pos + 4];
+        let size = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().ok()?) as usize;
+        let body = pos + 8;
+        match id {
+            b"fmt " => format = Some(data.get(body..body.checked_add(size)?)?),
+            b"data" => return Some((format?, body..body + size.min(data.len() - body))),
+            _ => {}
+        }
+        // Chunks are padded to an even length
+        pos = body.checked_add(size)?.checked_add(size % 2)?;
+    }
+    None
+}
+
+/// Join WAV clips with the same format into one WAV.
+///
+/// The first clip's header is kept with its sizes updated. Returns `None` if
+/// there are no clips, a clip is not a WAV file, or the formats differ.
+pub fn join_wav_clips(clips: &[Vec<u8>]) -> Option<Vec<u8>> {
+    let (first, rest) = clips.split_first()?;
+    let (format, first_data) = wav_layout(first)?;
+
+    let mut joined = first[..first_data.end].to_vec();
+    for clip in rest {
+        let (clip_format, data) = wav_layout(clip)?;
+        if clip_format != format {
+            return None;
+        }
+        joined.extend_from_slice(&clip[data]);
+    }
+
+    let data_len = u32::try_from(joined.len() - first_data.start).ok()?;
+    let riff_len = u32::try_from(joined.len() - 8).ok()?;
+    joined[first_data.start - 4..first_data.start].copy_from_slice(&data_len.to_le_bytes());
+    joined[4..8].copy_from_slice(&riff_len.to_le_bytes());
+    Some(joined)
 }
 
 
@@ -612,6 +1230,8 @@ mod tests {
         assert!(params.voice.is_none());
         assert!(params.pronunciations.is_none());
         assert!(params.output_file.is_none());
+        assert!(params.volume_gain_db.is_none());
+        assert!(params.effects_profile_id.is_none());
     }
 
     #[test]
@@ -624,6 +1244,10 @@ mod tests {
             pitch: 2.0,
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
         assert!(params.validate().is_ok());
@@ -639,6 +1263,10 @@ mod tests {
             pitch: 0.0,
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
         let result = params.validate();
@@ -647,6 +1275,46 @@ mod tests {
         assert!(errors.iter().any(|e| e.field == "text"));
     }
 
+    #[test]
+    fn test_text_byte_limit() {
+        let mut params: SpeechSynthesizeParams = serde_json::from_value(serde_json::json!({
+            "text": "a".repeat(MAX_INPUT_BYTES),
+        }))
+        .unwrap();
+        assert!(params.validate().is_ok());
+
+        // Multi-byte characters count by their UTF-8 length
+        params.text = "é".repeat(MAX_INPUT_BYTES / 2 + 1);
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "text");
+        assert!(errors[0].message.contains("speech_synthesize_streaming"), "{}", errors[0].message);
+
+        assert!(params.validate_with_text_limit(MAX_STREAMING_TEXT_BYTES).is_ok());
+        params.text = "a".repeat(MAX_STREAMING_TEXT_BYTES + 1);
+        assert!(params.validate_with_text_limit(MAX_STREAMING_TEXT_BYTES).is_err());
+    }
+
+    #[test]
+    fn test_input_size_counts_generated_ssml() {
+        let word = "tomato ";
+        let mut params: SpeechSynthesizeParams = serde_json::from_value(serde_json::json!({
+            "text": word.repeat(MAX_INPUT_BYTES / word.len()),
+        }))
+        .unwrap();
+        assert!(params.validate().is_ok());
+        assert!(params.validate_input_size().is_ok());
+
+        params.pronunciations = Some(vec![Pronunciation {
+            word: "tomato".to_string(),
+            phonetic: "təˈmeɪtoʊ".to_string(),
+            alphabet: "ipa".to_string(),
+        }]);
+        let error = params.validate_input_size().unwrap_err();
+        assert_eq!(error.field, "text");
+        assert!(error.message.contains("SSML"));
+    }
+
     #[test]
     fn test_speaking_rate_too_low() {
         let params = SpeechSynthesizeParams {
@@ -657,6 +1325,10 @@ mod tests {
             pitch: 0.0,
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
         let result = params.validate();
@@ -675,6 +1347,10 @@ mod tests {
             pitch: 0.0,
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
         let result = params.validate();
@@ -693,6 +1369,10 @@ mod tests {
             pitch: -25.0,
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
         let result = params.validate();
@@ -711,6 +1391,10 @@ mod tests {
             pitch: 25.0,
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
         let result = params.validate();
@@ -730,6 +1414,10 @@ mod tests {
             pitch: 0.0,
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
         assert!(params.validate().is_ok());
 
@@ -742,6 +1430,10 @@ mod tests {
             pitch: 0.0,
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
         assert!(params.validate().is_ok());
     }
@@ -757,6 +1449,10 @@ mod tests {
             pitch: MIN_PITCH,
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
         assert!(params.validate().is_ok());
 
@@ -769,8 +1465,164 @@ mod tests {
             pitch: MAX_PITCH,
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
+        };
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_just_outside_boundaries_rejected() {
+        let base = SpeechSynthesizeParams {
+            text: "Hello".to_string(),
+            voice: None,
+            language_code: "en-US".to_string(),
+            speaking_rate: 1.0,
+            pitch: 0.0,
+            pronunciations: None,
+            output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
+        };
+
+        for rate in [MIN_SPEAKING_RATE - 0.01, MAX_SPEAKING_RATE + 0.01] {
+            let params = SpeechSynthesizeParams { speaking_rate: rate, ..base.clone() };
+            let errors = params.validate().unwrap_err();
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].field, "speaking_rate");
+            assert!(errors[0].message.contains("between 0.25 and 4"), "{}", errors[0].message);
+        }
+
+        for pitch in [MIN_PITCH - 0.1, MAX_PITCH + 0.1] {
+            let params = SpeechSynthesizeParams { pitch, ..base.clone() };
+            let errors = params.validate().unwrap_err();
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].field, "pitch");
+            assert!(errors[0].message.contains("between -20 and 20 semitones"), "{}", errors[0].message);
+        }
+    }
+
+    #[test]
+    fn test_non_finite_rate_and_pitch_rejected() {
+        let params = SpeechSynthesizeParams {
+            text: "Hello".to_string(),
+            voice: None,
+            language_code: "en-US".to_string(),
+            speaking_rate: f32::NAN,
+            pitch: f32::INFINITY,
+            pronunciations: None,
+            output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
+        };
+        let errors = params.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["speaking_rate", "pitch"]);
+    }
+
+    #[test]
+    fn test_volume_gain_db_range() {
+        let base = SpeechSynthesizeParams {
+            text: "Hello".to_string(),
+            voice: None,
+            language_code: "en-US".to_string(),
+            speaking_rate: 1.0,
+            pitch: 0.0,
+            pronunciations: None,
+            output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
+        };
+
+        for gain in [MIN_VOLUME_GAIN_DB, -6.0, MAX_VOLUME_GAIN_DB] {
+            let params = SpeechSynthesizeParams { volume_gain_db: Some(gain), ..base.clone() };
+            assert!(params.validate().is_ok(), "{} dB should be valid", gain);
+        }
+
+        for gain in [MIN_VOLUME_GAIN_DB - 0.1, MAX_VOLUME_GAIN_DB + 0.1, f64::NAN] {
+            let params = SpeechSynthesizeParams { volume_gain_db: Some(gain), ..base.clone() };
+            let errors = params.validate().unwrap_err();
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].field, "volume_gain_db");
+            assert!(errors[0].message.contains("between -96 and 16 dB"), "{}", errors[0].message);
+        }
+    }
+
+    #[test]
+    fn test_effects_profile_validation() {
+        let mut params = SpeechSynthesizeParams {
+            text: "Hello".to_string(),
+            voice: None,
+            language_code: "en-US".to_string(),
+            speaking_rate: 1.0,
+            pitch: 0.0,
+            pronunciations: None,
+            output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: Some(vec![
+                "headphone-class-device".to_string(),
+                "telephony-class-application".to_string(),
+            ]),
         };
         assert!(params.validate().is_ok());
+
+        params.effects_profile_id = Some(vec![
+            "headphone-class-device".to_string(),
+            "headphones".to_string(),
+        ]);
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "effects_profile_id[1]");
+        assert!(errors[0].message.contains("Unknown effects profile 'headphones'"));
+    }
+
+    #[test]
+    fn test_output_gcs_uri_validation() {
+        let mut params: SpeechSynthesizeParams = serde_json::from_str(
+            r#"{"text": "Hello", "output_gcs_uri": "gs://bucket/speech/hello.wav"}"#,
+        )
+        .unwrap();
+        assert!(params.validate().is_ok());
+
+        params.output_gcs_uri = Some("/tmp/hello.wav".to_string());
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "output_gcs_uri");
+        assert!(errors[0].message.contains("gs://"));
+    }
+
+    #[test]
+    fn test_audio_config_serialization() {
+        let config = TtsAudioConfig {
+            audio_encoding: "LINEAR16".to_string(),
+            speaking_rate: Some(1.0),
+            pitch: Some(0.0),
+            volume_gain_db: Some(-3.5),
+            effects_profile_id: Some(vec!["headphone-class-device".to_string()]),
+            sample_rate_hertz: Some(24000),
+        };
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["volumeGainDb"], -3.5);
+        assert_eq!(json["effectsProfileId"], serde_json::json!(["headphone-class-device"]));
+
+        let config = TtsAudioConfig {
+            volume_gain_db: None,
+            effects_profile_id: None,
+            ..config
+        };
+        let json = serde_json::to_value(&config).unwrap();
+        assert!(json.get("volumeGainDb").is_none());
+        assert!(json.get("effectsProfileId").is_none());
     }
 
     #[test]
@@ -857,6 +1709,10 @@ mod tests {
                 alphabet: "ipa".to_string(),
             }]),
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
         let ssml = params.build_ssml();
@@ -866,6 +1722,28 @@ mod tests {
         assert!(!ssml.contains("tomato</speak>")); // tomato should be wrapped in phoneme
     }
 
+    #[test]
+    fn test_build_ssml_does_not_match_inside_markup() {
+        let pron = |word: &str, phonetic: &str| Pronunciation {
+            word: word.to_string(),
+            phonetic: phonetic.to_string(),
+            alphabet: "ipa".to_string(),
+        };
+        let params = SpeechSynthesizeParams {
+            pronunciations: Some(vec![
+                pron("ADK", "eɪ diː keɪ"),
+                pron("ADK Rust", "eɪ diː keɪ rʌst"),
+                pron("ph", "piː eɪtʃ"),
+            ]),
+            ..serde_json::from_str(r#"{"text": "ADK Rust and ADK"}"#).unwrap()
+        };
+
+        assert_eq!(
+            params.build_ssml(),
+            r#"<speak><phoneme alphabet="ipa" ph="eɪ diː keɪ rʌst">ADK Rust</phoneme> and <phoneme alphabet="ipa" ph="eɪ diː keɪ">ADK</phoneme></speak>"#
+        );
+    }
+
     #[test]
     fn test_build_ssml_without_pronunciations() {
         let params = SpeechSynthesizeParams {
@@ -876,6 +1754,10 @@ mod tests {
             pitch: 0.0,
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
         let ssml = params.build_ssml();
@@ -892,6 +1774,10 @@ mod tests {
             pitch: 0.0,
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
         assert_eq!(params.get_voice(), DEFAULT_VOICE);
@@ -907,11 +1793,46 @@ mod tests {
             pitch: 0.0,
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
         assert_eq!(params.get_voice(), "custom-voice");
     }
 
+    #[test]
+    fn test_voice_name_validation() {
+        assert!(validate_voice_name("en-US-Chirp3-HD-Achernar", "en-US").is_ok());
+        assert!(validate_voice_name("cmn-CN-Chirp3-HD-Kore", "cmn-CN").is_ok());
+        assert!(validate_voice_name("es-419-Standard-A", "es-419").is_ok());
+        assert!(validate_voice_name("en-US-Chirp3-HD-Achernar", "en-us").is_ok());
+
+        let malformed = validate_voice_name("custom-voice", "en-US").unwrap_err();
+        assert!(malformed.contains("'custom-voice'"), "{}", malformed);
+        assert!(validate_voice_name("en-US-", "en-US").is_err());
+        assert!(validate_voice_name("EN-US-Chirp3-HD-Achernar", "en-US").is_err());
+        assert!(validate_voice_name("en-US Chirp3-HD-Achernar", "en-US").is_err());
+
+        let mismatch = validate_voice_name("de-DE-Chirp3-HD-Achernar", "en-US").unwrap_err();
+        assert!(mismatch.contains("'de-DE'") && mismatch.contains("'en-US'"), "{}", mismatch);
+    }
+
+    #[test]
+    fn test_invalid_voice_reported_with_other_errors() {
+        let params = SpeechSynthesizeParams {
+            text: "".to_string(),
+            voice: Some("Achernar".to_string()),
+            speaking_rate: 9.0,
+            ..serde_json::from_str(r#"{"text": "Hello"}"#).unwrap()
+        };
+
+        let errors = params.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["text", "voice", "speaking_rate"]);
+    }
+
     #[test]
     fn test_params_with_invalid_pronunciation() {
         let params = SpeechSynthesizeParams {
@@ -926,6 +1847,10 @@ mod tests {
                 alphabet: "invalid".to_string(),
             }]),
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
         let result = params.validate();
@@ -934,6 +1859,61 @@ mod tests {
         assert!(errors.iter().any(|e| e.field.contains("pronunciations")));
     }
 
+    #[test]
+    fn test_split_sentences_packs_whole_sentences() {
+        let text = "One two. Three four! Five six? Seven.";
+        assert_eq!(split_sentences(text, 100), vec![text.to_string()]);
+        assert_eq!(
+            split_sentences(text, 20),
+            vec!["One two. Three four!", "Five six? Seven."]
+        );
+        // A sentence longer than the limit is kept whole
+        assert_eq!(split_sentences(text, 5), vec!["One two.", "Three four!", "Five six?", "Seven."]);
+    }
+
+    #[test]
+    fn test_split_sentences_boundaries() {
+        // Decimal points and abbreviations without a following space do not split
+        assert_eq!(split_sentences("Pi is 3.14 today", 5), vec!["Pi is 3.14 today"]);
+        assert_eq!(split_sentences("Line one\nLine two", 5), vec!["Line one", "Line two"]);
+        assert_eq!(split_sentences("Wait… what", 5), vec!["Wait…", "what"]);
+        assert!(split_sentences("  \n ", 10).is_empty());
+    }
+
+    /// A mono 16-bit 24 kHz WAV with `payload` as its samples.
+    pub(super) fn wav(payload: &[u8]) -> Vec<u8> {
+        let mut wav = b"RIFF".to_vec();
+        wav.extend_from_slice(&(36 + payload.len() as u32).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&24000u32.to_le_bytes());
+        wav.extend_from_slice(&48000u32.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        wav.extend_from_slice(payload);
+        wav
+    }
+
+    #[test]
+    fn test_join_wav_clips() {
+        let joined = join_wav_clips(&[wav(&[1, 2]), wav(&[3, 4, 5, 6])]).unwrap();
+        assert_eq!(joined, wav(&[1, 2, 3, 4, 5, 6]));
+        assert_eq!(join_wav_clips(&[wav(&[1, 2])]).unwrap(), wav(&[1, 2]));
+    }
+
+    #[test]
+    fn test_join_wav_clips_rejects_mismatched_or_invalid() {
+        let mut stereo = wav(&[1, 2, 3, 4]);
+        stereo[22] = 2;
+        assert!(join_wav_clips(&[wav(&[1, 2]), stereo]).is_none());
+        assert!(join_wav_clips(&[wav(&[1, 2]), b"not a wav".to_vec()]).is_none());
+        assert!(join_wav_clips(&[]).is_none());
+    }
+
     #[test]
     fn test_serialization_roundtrip() {
         let params = SpeechSynthesizeParams {
@@ -948,6 +1928,10 @@ mod tests {
                 alphabet: "ipa".to_string(),
             }]),
             output_file: Some("/tmp/output.wav".to_string()),
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: Some(-6.0),
+            effects_profile_id: Some(vec!["small-bluetooth-speaker-class-device".to_string()]),
         };
 
         let json = serde_json::to_string(&params).unwrap();
@@ -959,9 +1943,494 @@ mod tests {
         assert_eq!(params.speaking_rate, deserialized.speaking_rate);
         assert_eq!(params.pitch, deserialized.pitch);
         assert_eq!(params.output_file, deserialized.output_file);
+        assert_eq!(params.output_gcs_uri, deserialized.output_gcs_uri);
+        assert_eq!(params.volume_gain_db, deserialized.volume_gain_db);
+        assert_eq!(params.effects_profile_id, deserialized.effects_profile_id);
     }
 }
 
+/// Output handling and lexicon loading tests against a fake GCS server.
+#[cfg(test)]
+mod output_tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn handler_for(server: &MockServer, gcs_bucket: Option<&str>) -> SpeechHandler {
+        let config = Config {
+            project_id: "test-project".to_string(),
+            gcs_bucket: gcs_bucket.map(String::from),
+            ..Default::default()
+        };
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        let gcs = GcsClient::with_base_url(Arc::clone(&auth), server.uri());
+        SpeechHandler::with_deps(config, gcs, reqwest::Client::new(), auth)
+    }
+
+    fn audio() -> GeneratedAudio {
+        GeneratedAudio {
+            data: BASE64.encode(b"RIFF....WAVE"),
+            mime_type: "audio/wav".to_string(),
+        }
+    }
+
+    fn params(output_file: Option<&str>, output_gcs_uri: Option<&str>) -> SpeechSynthesizeParams {
+        SpeechSynthesizeParams {
+            output_file: output_file.map(String::from),
+            output_gcs_uri: output_gcs_uri.map(String::from),
+            lexicon_file: None,
+            ..serde_json::from_str(r#"{"text": "Hello"}"#).unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gcs_uri_takes_precedence_over_output_file() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/upload/storage/v1/b/bucket/o"))
+            .and(query_param("name", "speech/hello.wav"))
+            .and(header("Content-Type", "audio/wav"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("hello.wav");
+        let params = params(Some(&local.to_string_lossy()), Some("gs://bucket/speech/hello.wav"));
+
+        let result = handler_for(&server, None).handle_output(audio(), &params).await.unwrap();
+
+        match result {
+            SpeechSynthesizeResult::GcsUri(uri) => assert_eq!(uri, "gs://bucket/speech/hello.wav"),
+            other => panic!("Expected GcsUri, got {:?}", other),
+        }
+        assert!(!local.exists(), "Local file should not be written when uploading");
+    }
+
+    #[tokio::test]
+    async fn test_output_file_used_without_gcs_uri() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("nested").join("hello.wav");
+        let params = params(Some(&local.to_string_lossy()), None);
+
+        let result = handler_for(&server, None).handle_output(audio(), &params).await.unwrap();
+
+        match result {
+            SpeechSynthesizeResult::LocalFile(path) => assert_eq!(path, local.to_string_lossy()),
+            other => panic!("Expected LocalFile, got {:?}", other),
+        }
+        assert_eq!(std::fs::read(&local).unwrap(), b"RIFF....WAVE");
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_relative_output_file_uses_download_dir() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let mut handler = handler_for(&server, None);
+        handler.config.download_dir = Some(dir.path().to_string_lossy().into_owned());
+
+        let result = handler.handle_output(audio(), &params(Some("greetings/hello.wav"), None)).await.unwrap();
+
+        let expected = dir.path().join("greetings/hello.wav");
+        match result {
+            SpeechSynthesizeResult::LocalFile(path) => assert_eq!(path, expected.to_string_lossy()),
+            other => panic!("Expected LocalFile, got {:?}", other),
+        }
+        assert_eq!(std::fs::read(&expected).unwrap(), b"RIFF....WAVE");
+    }
+
+    #[tokio::test]
+    async fn test_base64_returned_without_outputs() {
+        let server = MockServer::start().await;
+
+        let result = handler_for(&server, None).handle_output(audio(), &params(None, None)).await.unwrap();
+
+        match result {
+            SpeechSynthesizeResult::Base64(generated) => assert_eq!(generated.data, audio().data),
+            other => panic!("Expected Base64, got {:?}", other),
+        }
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_upload_failure_is_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/upload/storage/v1/b/bucket/o"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("Access denied"))
+            .mount(&server)
+            .await;
+
+        let err = handler_for(&server, None)
+            .handle_output(audio(), &params(None, Some("gs://bucket/speech/hello.wav")))
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("Access denied"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_local_lexicon_cache_invalidated_by_mtime() {
+        let server = MockServer::start().await;
+        let handler = handler_for(&server, None);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("brands.csv");
+        let path_str = path.to_string_lossy().to_string();
+
+        std::fs::write(&path, "Zavora,zəˈvɔːrə\n").unwrap();
+        let first = handler.load_lexicon(&path_str).await.unwrap();
+        assert_eq!(first[0].phonetic, "zəˈvɔːrə");
+
+        // Unchanged files are served from the cache
+        let second = handler.load_lexicon(&path_str).await.unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // Rewriting the file with a new mtime reloads it
+        std::fs::write(&path, "Zavora,zaˈvoːra\n").unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(60))
+            .unwrap();
+        let third = handler.load_lexicon(&path_str).await.unwrap();
+        assert_eq!(third[0].phonetic, "zaˈvoːra");
+        assert_eq!(handler.lexicons.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_lexicon_errors_are_not_cached() {
+        let server = MockServer::start().await;
+        let handler = handler_for(&server, None);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("brands.json");
+        let path_str = path.to_string_lossy().to_string();
+
+        let err = handler.load_lexicon(&path_str).await.unwrap_err();
+        assert!(err.to_string().contains("Cannot read lexicon"), "{}", err);
+
+        std::fs::write(&path, "[\n  {\"word\": \"ADK\"}\n]").unwrap();
+        let err = handler.load_lexicon(&path_str).await.unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{}", err);
+        assert!(handler.lexicons.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_gcs_lexicon_cache_invalidated_by_generation() {
+        let server = MockServer::start().await;
+        let metadata = |generation: &str| {
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "generation": generation }))
+        };
+        Mock::given(method("GET"))
+            .and(path("/storage/v1/b/bucket/o/lexicons%2Fbrands.csv"))
+            .and(query_param("alt", "media"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ADK,eɪ diː keɪ\n"))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/storage/v1/b/bucket/o/lexicons%2Fbrands.csv"))
+            .respond_with(metadata("1"))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+
+        let handler = handler_for(&server, None);
+        let uri = "gs://bucket/lexicons/brands.csv";
+        let first = handler.load_lexicon(uri).await.unwrap();
+        let second = handler.load_lexicon(uri).await.unwrap();
+        assert!(Arc::ptr_eq(&first, &second), "Same generation should hit the cache");
+
+        // A new generation downloads the object again
+        Mock::given(method("GET"))
+            .and(path("/storage/v1/b/bucket/o/lexicons%2Fbrands.csv"))
+            .respond_with(metadata("2"))
+            .mount(&server)
+            .await;
+        let third = handler.load_lexicon(uri).await.unwrap();
+        assert!(!Arc::ptr_eq(&first, &third));
+        assert_eq!(third[0].word, "ADK");
+    }
+
+    fn mock_tts(audio_bytes: &[u8]) -> Mock {
+        Mock::given(method("POST"))
+            .and(path("/v1/text:synthesize"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "audioContent": BASE64.encode(audio_bytes),
+            })))
+    }
+
+    fn cached_handler(server: &MockServer, cache_dir: &Path) -> SpeechHandler {
+        let mut handler = handler_for(server, None)
+            .with_audio_cache(Arc::new(AudioCache::new(cache_dir, 1024 * 1024, 100)));
+        handler.api_base_url = server.uri();
+        handler
+    }
+
+    #[tokio::test]
+    async fn test_repeated_synthesis_served_from_cache() {
+        let server = MockServer::start().await;
+        mock_tts(b"RIFF....WAVE").expect(1).mount(&server).await;
+        let dir = tempfile::tempdir().unwrap();
+        let handler = cached_handler(&server, dir.path());
+
+        let first = handler.synthesize(params(None, None)).await.unwrap();
+        let second = handler.synthesize(params(None, None)).await.unwrap();
+
+        assert!(!first.cached);
+        assert!(second.cached);
+        match second.output {
+            SpeechSynthesizeResult::Base64(generated) => assert_eq!(generated.data, audio().data),
+            other => panic!("Expected Base64, got {:?}", other),
+        }
+        // MockServer verifies `expect(1)` on drop
+    }
+
+    #[tokio::test]
+    async fn test_cache_hit_still_writes_requested_output() {
+        let server = MockServer::start().await;
+        mock_tts(b"RIFF....WAVE").expect(1).mount(&server).await;
+        let dir = tempfile::tempdir().unwrap();
+        let handler = cached_handler(&server, &dir.path().join("cache"));
+
+        handler.synthesize(params(None, None)).await.unwrap();
+        let local = dir.path().join("hello.wav");
+        let result = handler
+            .synthesize(params(Some(&local.to_string_lossy()), None))
+            .await
+            .unwrap();
+
+        assert!(result.cached);
+        assert_eq!(std::fs::read(&local).unwrap(), b"RIFF....WAVE");
+    }
+
+    #[tokio::test]
+    async fn test_changed_settings_miss_the_cache() {
+        let server = MockServer::start().await;
+        mock_tts(b"RIFF....WAVE").expect(2).mount(&server).await;
+        let dir = tempfile::tempdir().unwrap();
+        let handler = cached_handler(&server, dir.path());
+
+        handler.synthesize(params(None, None)).await.unwrap();
+        let faster = SpeechSynthesizeParams {
+            speaking_rate: 1.5,
+            ..params(None, None)
+        };
+        let result = handler.synthesize(faster).await.unwrap();
+
+        assert!(!result.cached);
+    }
+
+    #[tokio::test]
+    async fn test_api_errors_are_not_cached() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/text:synthesize"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("backend error"))
+            .expect(2)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let handler = cached_handler(&server, dir.path());
+
+        assert!(handler.synthesize(params(None, None)).await.is_err());
+        assert!(handler.synthesize(params(None, None)).await.is_err());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_bare_object_name_requires_bucket() {
+        let server = MockServer::start().await;
+
+        let err = handler_for(&server, None)
+            .synthesize(params(None, Some("speech/hello.wav")))
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("GCS_BUCKET is not configured"), "{}", err);
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+
+    fn long_params() -> SpeechSynthesizeParams {
+        let sentence = format!("{}.", "word ".repeat(60).trim_end());
+        SpeechSynthesizeParams {
+            text: format!("{} {}", sentence, sentence),
+            ..params(None, None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streaming_sends_each_clip_and_joins_them() {
+        let server = MockServer::start().await;
+        mock_tts(&super::tests::wav(&[1, 2])).expect(2).mount(&server).await;
+        let mut handler = handler_for(&server, None);
+        handler.api_base_url = server.uri();
+
+        let (tx, mut rx) = mpsc::channel(8);
+        let result = handler.synthesize_streaming(long_params(), tx).await.unwrap();
+
+        let mut chunks = Vec::new();
+        while let Some(chunk) = rx.recv().await {
+            chunks.push(chunk);
+        }
+        assert_eq!(chunks.len(), 2);
+        assert_eq!((chunks[1].index, chunks[1].total), (1, 2));
+        assert_eq!(BASE64.decode(&chunks[0].audio.data).unwrap(), super::tests::wav(&[1, 2]));
+
+        assert!(!result.cached);
+        match result.output {
+            SpeechSynthesizeResult::Base64(audio) => {
+                assert_eq!(BASE64.decode(&audio.data).unwrap(), super::tests::wav(&[1, 2, 1, 2]));
+            }
+            other => panic!("Expected Base64, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streaming_cache_hit_is_a_single_chunk() {
+        let server = MockServer::start().await;
+        mock_tts(&super::tests::wav(&[7, 7])).expect(2).mount(&server).await;
+        let dir = tempfile::tempdir().unwrap();
+        let handler = cached_handler(&server, dir.path());
+
+        let (tx, _rx) = mpsc::channel(8);
+        handler.synthesize_streaming(long_params(), tx).await.unwrap();
+
+        // The joined audio is cached under the whole request
+        let (tx, mut rx) = mpsc::channel(8);
+        let result = handler.synthesize_streaming(long_params(), tx).await.unwrap();
+        assert!(result.cached);
+        let chunk = rx.recv().await.unwrap();
+        assert_eq!((chunk.index, chunk.total), (0, 1));
+        assert_eq!(BASE64.decode(&chunk.audio.data).unwrap(), super::tests::wav(&[7, 7, 7, 7]));
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_streaming_accepts_text_beyond_one_request() {
+        let server = MockServer::start().await;
+        mock_tts(&super::tests::wav(&[1, 2])).mount(&server).await;
+        let mut handler = handler_for(&server, None);
+        handler.api_base_url = server.uri();
+
+        let sentence = format!("{}.", "word ".repeat(60).trim_end());
+        let text = vec![sentence; 30].join(" ");
+        assert!(text.len() > MAX_INPUT_BYTES);
+        let long = SpeechSynthesizeParams { text, ..params(None, None) };
+
+        let error = handler.synthesize(long.clone()).await.unwrap_err();
+        assert!(error.to_string().contains("speech_synthesize_streaming"));
+
+        let (tx, _rx) = mpsc::channel(64);
+        let result = handler.synthesize_streaming(long, tx).await.unwrap();
+        assert!(!result.cached);
+        assert_eq!(server.received_requests().await.unwrap().len(), 30);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_rejects_overlong_sentence_before_calling_api() {
+        let server = MockServer::start().await;
+        mock_tts(&super::tests::wav(&[1, 2])).expect(0).mount(&server).await;
+        let mut handler = handler_for(&server, None);
+        handler.api_base_url = server.uri();
+
+        let text = format!("Short start. {}.", "word ".repeat(MAX_INPUT_BYTES / 5 + 1).trim_end());
+        let (tx, _rx) = mpsc::channel(8);
+        let error = handler
+            .synthesize_streaming(SpeechSynthesizeParams { text, ..params(None, None) }, tx)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("in sentence 2"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_continues_without_a_receiver() {
+        let server = MockServer::start().await;
+        mock_tts(&super::tests::wav(&[1, 2])).expect(2).mount(&server).await;
+        let mut handler = handler_for(&server, None);
+        handler.api_base_url = server.uri();
+
+        let (tx, rx) = mpsc::channel(1);
+        drop(rx);
+        let result = handler.synthesize_streaming(long_params(), tx).await.unwrap();
+        assert!(matches!(result.output, SpeechSynthesizeResult::Base64(_)));
+    }
+
+    #[tokio::test]
+    async fn test_api_error_body_is_parsed() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/text:synthesize"))
+            .respond_with(ResponseTemplate::new(429).set_body_json(serde_json::json!({
+                "error": {
+                    "code": 429,
+                    "message": "Quota exceeded for texttospeech.googleapis.com/chirp3_requests.",
+                    "status": "RESOURCE_EXHAUSTED"
+                }
+            })))
+            .mount(&server)
+            .await;
+        let mut handler = handler_for(&server, None);
+        handler.api_base_url = server.uri();
+
+        let error = handler.synthesize(params(None, None)).await.unwrap_err();
+        assert!(
+            error.to_string().ends_with(
+                "(HTTP 429 RESOURCE_EXHAUSTED): Quota exceeded for texttospeech.googleapis.com/chirp3_requests."
+            ),
+            "{}",
+            error
+        );
+        assert_eq!(error.to_data()["status"], "RESOURCE_EXHAUSTED");
+    }
+
+    #[tokio::test]
+    async fn test_list_voices_cached_from_api() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/voices"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "voices": [
+                    {"name": "en-US-Chirp3-HD-Sulafat", "languageCodes": ["en-US"], "ssmlGender": "FEMALE"},
+                    {"name": "en-US-Neural2-A", "languageCodes": ["en-US"]}
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let mut handler = handler_for(&server, None);
+        handler.api_base_url = server.uri();
+
+        let list = handler.list_voices(false).await;
+        assert_eq!(list.source, VoiceSource::Api);
+        let names: Vec<&str> = list.voices.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, ["en-US-Chirp3-HD-Sulafat"]);
+
+        // Served from the cache
+        assert_eq!(handler.list_voices(false).await.voices.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_voices_falls_back_when_api_fails() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/voices"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("API not enabled"))
+            .expect(2)
+            .mount(&server)
+            .await;
+        let mut handler = handler_for(&server, None);
+        handler.api_base_url = server.uri();
+
+        let list = handler.list_voices(false).await;
+        assert_eq!(list.source, VoiceSource::Fallback);
+        assert_eq!(list.voices.len(), FALLBACK_VOICES.len());
+        assert!(list.voices.iter().any(|v| v.name == DEFAULT_VOICE));
+
+        // refresh retries the API despite the recent failure
+        assert_eq!(handler.list_voices(true).await.source, VoiceSource::Fallback);
+    }
+}
 
 #[cfg(test)]
 mod property_tests {
@@ -997,7 +2466,8 @@ mod property_tests {
     /// Strategy to generate invalid pitch values (< -20.0 or > 20.0)
     fn invalid_pitch_strategy() -> impl Strategy<Value = f32> {
         prop_oneof![
-            (-50.0f32..MIN_PITCH).prop_map(|x| (x * 10.0).round() / 10.0),
+            // Upper bound kept a step below MIN_PITCH so rounding cannot land on it
+            (-50.0f32..=MIN_PITCH - 0.1).prop_map(|x| (x * 10.0).round() / 10.0),
             (MAX_PITCH + 0.1..50.0f32).prop_map(|x| (x * 10.0).round() / 10.0),
         ]
     }
@@ -1024,6 +2494,10 @@ mod property_tests {
                 pitch: 0.0,
                 pronunciations: None,
                 output_file: None,
+                output_gcs_uri: None,
+                lexicon_file: None,
+                volume_gain_db: None,
+                effects_profile_id: None,
             };
 
             let result = params.validate();
@@ -1049,6 +2523,10 @@ mod property_tests {
                 pitch: 0.0,
                 pronunciations: None,
                 output_file: None,
+                output_gcs_uri: None,
+                lexicon_file: None,
+                volume_gain_db: None,
+                effects_profile_id: None,
             };
 
             let result = params.validate();
@@ -1080,6 +2558,10 @@ mod property_tests {
                 pitch,
                 pronunciations: None,
                 output_file: None,
+                output_gcs_uri: None,
+                lexicon_file: None,
+                volume_gain_db: None,
+                effects_profile_id: None,
             };
 
             let result = params.validate();
@@ -1105,6 +2587,10 @@ mod property_tests {
                 pitch,
                 pronunciations: None,
                 output_file: None,
+                output_gcs_uri: None,
+                lexicon_file: None,
+                volume_gain_db: None,
+                effects_profile_id: None,
             };
 
             let result = params.validate();
@@ -1137,6 +2623,10 @@ mod property_tests {
                 pitch,
                 pronunciations: None,
                 output_file: None,
+                output_gcs_uri: None,
+                lexicon_file: None,
+                volume_gain_db: None,
+                effects_profile_id: None,
             };
 
             let result = params.validate();
@@ -1150,6 +2640,80 @@ mod property_tests {
         }
     }
 
+    /// Strategy to generate well-formed voice names with their language code
+    fn valid_voice_strategy() -> impl Strategy<Value = (String, String)> {
+        ("[a-z]{2,3}", "[A-Z]{2}", "[A-Za-z0-9]{1,8}", "[A-Za-z0-9]{1,12}").prop_map(
+            |(language, region, model, variant)| {
+                (
+                    format!("{}-{}-{}-{}", language, region, model, variant),
+                    format!("{}-{}", language, region),
+                )
+            },
+        )
+    }
+
+    /// Strategy to generate malformed voice names
+    fn invalid_voice_strategy() -> impl Strategy<Value = String> {
+        prop_oneof![
+            // Bare voice names without a language prefix
+            "[A-Za-z]{1,12}",
+            // Missing model segment
+            "[a-z]{2}-[A-Z]{2}",
+            // Uppercase language
+            "[A-Z]{2}-[A-Z]{2}-[A-Za-z0-9]{1,8}",
+            // Characters outside the name alphabet
+            "[a-z]{2}-[A-Z]{2}-[A-Za-z]{1,8}[ _.][A-Za-z]{1,8}",
+        ]
+    }
+
+    proptest! {
+        /// Well-formed voice names matching the language code pass validation
+        #[test]
+        fn valid_voice_passes_validation((voice, language_code) in valid_voice_strategy()) {
+            let params = SpeechSynthesizeParams {
+                voice: Some(voice.clone()),
+                language_code,
+                ..serde_json::from_str(r#"{"text": "Hello"}"#).unwrap()
+            };
+
+            let result = params.validate();
+            prop_assert!(result.is_ok(), "voice '{}' should be valid, got {:?}", voice, result.err());
+        }
+
+        /// Malformed voice names fail validation on the voice field
+        #[test]
+        fn invalid_voice_fails_validation(voice in invalid_voice_strategy()) {
+            let params = SpeechSynthesizeParams {
+                voice: Some(voice.clone()),
+                ..serde_json::from_str(r#"{"text": "Hello"}"#).unwrap()
+            };
+
+            let errors = params.validate().unwrap_err();
+            prop_assert!(
+                errors.iter().any(|e| e.field == "voice"),
+                "voice '{}' should be rejected",
+                voice
+            );
+        }
+
+        /// A voice for a different language than language_code fails validation
+        #[test]
+        fn voice_language_mismatch_fails_validation(
+            (voice, language_code) in valid_voice_strategy(),
+            other in "[a-z]{2,3}-[A-Z]{2}",
+        ) {
+            prop_assume!(!other.eq_ignore_ascii_case(&language_code));
+            let params = SpeechSynthesizeParams {
+                voice: Some(voice.clone()),
+                language_code: other.clone(),
+                ..serde_json::from_str(r#"{"text": "Hello"}"#).unwrap()
+            };
+
+            let errors = params.validate().unwrap_err();
+            prop_assert!(errors.iter().any(|e| e.field == "voice" && e.message.contains(&other)));
+        }
+    }
+
     // Feature: rust-mcp-genmedia, Property 12: Pronunciation Alphabet Validation
     // **Validates: Requirements 7.9**
     //
@@ -1251,6 +2815,10 @@ mod property_tests {
                     alphabet: alphabet.clone(),
                 }]),
                 output_file: None,
+                output_gcs_uri: None,
+                lexicon_file: None,
+                volume_gain_db: None,
+                effects_profile_id: None,
             };
 
             let result = params.validate();
@@ -1282,6 +2850,10 @@ mod property_tests {
                     alphabet: alphabet.clone(),
                 }]),
                 output_file: None,
+                output_gcs_uri: None,
+                lexicon_file: None,
+                volume_gain_db: None,
+                effects_profile_id: None,
             };
 
             let result = params.validate();
@@ -1313,6 +2885,10 @@ mod property_tests {
                 pitch,
                 pronunciations: None,
                 output_file: None,
+                output_gcs_uri: None,
+                lexicon_file: None,
+                volume_gain_db: None,
+                effects_profile_id: None,
             };
 
             let result = params.validate();
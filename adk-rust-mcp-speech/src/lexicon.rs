@@ -0,0 +1,349 @@
+//! Pronunciation lexicon files for the speech server.
+//!
+//! A lexicon is a JSON or CSV file of word → phonetic mappings, so brand and
+//! product glossaries don't have to be passed inline on every call. Parsed
+//! lexicons are cached by path and modification time (or object generation
+//! for `gs://` URIs) and merged with inline pronunciations.
+//!
+//! # Formats
+//!
+//! JSON files hold an array of entries; `alphabet` defaults to `"ipa"`:
+//!
+//! ```json
+//! [
+//!   {"word": "Zavora", "phonetic": "zəˈvɔːrə"},
+//!   {"word": "ADK", "phonetic": "eI di: keI", "alphabet": "x-sampa"}
+//! ]
+//! ```
+//!
+//! CSV files hold one `word,phonetic[,alphabet]` entry per line. Blank lines,
+//! lines starting with `#`, and a leading `word,phonetic,alphabet` header are
+//! skipped.
+
+use crate::handler::{Pronunciation, VALID_ALPHABETS};
+use adk_rust_mcp_common::error::Error;
+use serde::{Deserialize, Deserializer};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Alphabet used when a lexicon entry doesn't name one.
+pub const DEFAULT_LEXICON_ALPHABET: &str = "ipa";
+
+/// Lexicon file format, selected by file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LexiconFormat {
+    /// `.json`: an array of `{"word", "phonetic", "alphabet"}` objects
+    Json,
+    /// `.csv`: `word,phonetic[,alphabet]` lines
+    Csv,
+}
+
+impl LexiconFormat {
+    /// Select the format from the extension of a path or `gs://` URI.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let ext = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// A lexicon entry as written in a JSON lexicon.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LexiconEntry {
+    #[serde(deserialize_with = "non_empty")]
+    word: String,
+    #[serde(deserialize_with = "non_empty")]
+    phonetic: String,
+    #[serde(default = "default_alphabet", deserialize_with = "alphabet")]
+    alphabet: String,
+}
+
+fn default_alphabet() -> String {
+    DEFAULT_LEXICON_ALPHABET.to_string()
+}
+
+fn non_empty<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    if value.trim().is_empty() {
+        return Err(serde::de::Error::custom("value cannot be empty"));
+    }
+    Ok(value)
+}
+
+fn alphabet<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    check_alphabet(&value).map_err(serde::de::Error::custom)?;
+    Ok(value.to_lowercase())
+}
+
+fn check_alphabet(alphabet: &str) -> Result<(), String> {
+    if VALID_ALPHABETS.contains(&alphabet.to_lowercase().as_str()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid alphabet '{}'. Must be one of: {}",
+            alphabet,
+            VALID_ALPHABETS.join(", ")
+        ))
+    }
+}
+
+/// Parse lexicon file contents.
+///
+/// `source` names the file in error messages, which also give the line of
+/// the offending entry.
+///
+/// # Errors
+/// Returns `Error::Validation` if the contents are malformed or an entry is
+/// missing a word or phonetic spelling or uses an unknown alphabet.
+pub fn parse_lexicon(source: &str, content: &str, format: LexiconFormat) -> Result<Vec<Pronunciation>, Error> {
+    match format {
+        LexiconFormat::Json => parse_json(source, content),
+        LexiconFormat::Csv => parse_csv(source, content),
+    }
+}
+
+fn parse_json(source: &str, content: &str) -> Result<Vec<Pronunciation>, Error> {
+    // serde_json errors end with "at line N column M"
+    let entries: Vec<LexiconEntry> = serde_json::from_str(content)
+        .map_err(|e| Error::validation(format!("Invalid lexicon '{}': {}", source, e)))?;
+
+    Ok(entries
+        .into_iter()
+        .map(|e| Pronunciation {
+            word: e.word,
+            phonetic: e.phonetic,
+            alphabet: e.alphabet,
+        })
+        .collect())
+}
+
+fn parse_csv(source: &str, content: &str) -> Result<Vec<Pronunciation>, Error> {
+    let mut entries = Vec::new();
+    let mut seen_entry = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let invalid = |message: String| {
+            Error::validation(format!(
+                "Invalid lexicon '{}' at line {}: {}",
+                source, line_number, message
+            ))
+        };
+
+        // An optional header precedes the first entry
+        if !seen_entry && fields[0].eq_ignore_ascii_case("word") {
+            seen_entry = true;
+            continue;
+        }
+        seen_entry = true;
+
+        let (word, phonetic, alphabet) = match fields.as_slice() {
+            [word, phonetic] => (*word, *phonetic, DEFAULT_LEXICON_ALPHABET),
+            [word, phonetic, alphabet] => (*word, *phonetic, *alphabet),
+            _ => {
+                return Err(invalid(format!(
+                    "expected 'word,phonetic[,alphabet]', got {} fields",
+                    fields.len()
+                )))
+            }
+        };
+
+        let pronunciation = Pronunciation {
+            word: word.to_string(),
+            phonetic: phonetic.to_string(),
+            alphabet: alphabet.to_lowercase(),
+        };
+        pronunciation.validate().map_err(|e| invalid(e.message))?;
+        entries.push(pronunciation);
+    }
+
+    Ok(entries)
+}
+
+/// Merge inline pronunciations with lexicon entries.
+///
+/// Inline entries come first and win over lexicon entries for the same word;
+/// within the lexicon, the first entry for a word is kept.
+pub fn merge_pronunciations(inline: Vec<Pronunciation>, lexicon: &[Pronunciation]) -> Vec<Pronunciation> {
+    let mut words: HashSet<String> = inline.iter().map(|p| p.word.clone()).collect();
+    let mut merged = inline;
+    for entry in lexicon {
+        if words.insert(entry.word.clone()) {
+            merged.push(entry.clone());
+        }
+    }
+    merged
+}
+
+/// Version of a lexicon file, used to tell whether a cached parse is current.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LexiconVersion {
+    /// Modification time of a local file
+    Modified(SystemTime),
+    /// Generation of a GCS object
+    Generation(u64),
+}
+
+/// A parsed lexicon and the file version it was parsed from.
+type CachedLexicon = (LexiconVersion, Arc<Vec<Pronunciation>>);
+
+/// Parsed lexicons keyed by path.
+#[derive(Debug, Default)]
+pub struct LexiconCache {
+    entries: Mutex<HashMap<String, CachedLexicon>>,
+}
+
+impl LexiconCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the cached lexicon for `path` if it was parsed from `version`.
+    pub fn get(&self, path: &str, version: &LexiconVersion) -> Option<Arc<Vec<Pronunciation>>> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(path)
+            .filter(|(cached, _)| cached == version)
+            .map(|(_, lexicon)| Arc::clone(lexicon))
+    }
+
+    /// Store a parsed lexicon, replacing any older version for `path`.
+    pub fn insert(&self, path: &str, version: LexiconVersion, lexicon: Arc<Vec<Pronunciation>>) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.insert(path.to_string(), (version, lexicon));
+    }
+
+    /// Number of cached lexicons.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(word: &str, phonetic: &str) -> Pronunciation {
+        Pronunciation {
+            word: word.to_string(),
+            phonetic: phonetic.to_string(),
+            alphabet: "ipa".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(LexiconFormat::from_path("/tmp/glossary.json"), Some(LexiconFormat::Json));
+        assert_eq!(LexiconFormat::from_path("gs://bucket/lexicons/brands.CSV"), Some(LexiconFormat::Csv));
+        assert_eq!(LexiconFormat::from_path("/tmp/glossary.txt"), None);
+        assert_eq!(LexiconFormat::from_path("/tmp/glossary"), None);
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let content = r#"[
+            {"word": "Zavora", "phonetic": "zəˈvɔːrə"},
+            {"word": "ADK", "phonetic": "eI di: keI", "alphabet": "X-SAMPA"}
+        ]"#;
+        let lexicon = parse_lexicon("brands.json", content, LexiconFormat::Json).unwrap();
+        assert_eq!(lexicon.len(), 2);
+        assert_eq!(lexicon[0], entry("Zavora", "zəˈvɔːrə"));
+        assert_eq!(lexicon[1].alphabet, "x-sampa");
+    }
+
+    #[test]
+    fn test_parse_json_reports_line() {
+        let content = "[\n  {\"word\": \"Zavora\", \"phonetic\": \"zəˈvɔːrə\"},\n  {\"word\": \"ADK\", \"phonetic\": \"eI\", \"alphabet\": \"klingon\"}\n]";
+        let err = parse_lexicon("brands.json", content, LexiconFormat::Json).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("brands.json"), "{}", message);
+        assert!(message.contains("Invalid alphabet 'klingon'"), "{}", message);
+        assert!(message.contains("line 3"), "{}", message);
+
+        let content = "[\n  {\"word\": \"Zavora\"}\n]";
+        let message = parse_lexicon("brands.json", content, LexiconFormat::Json).unwrap_err().to_string();
+        assert!(message.contains("missing field `phonetic`"), "{}", message);
+        assert!(message.contains("line 2"), "{}", message);
+
+        let content = "[\n  {\"word\": \"  \", \"phonetic\": \"x\"}\n]";
+        let message = parse_lexicon("brands.json", content, LexiconFormat::Json).unwrap_err().to_string();
+        assert!(message.contains("cannot be empty"), "{}", message);
+        assert!(message.contains("line 2"), "{}", message);
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let content = "word,phonetic,alphabet\n# brand names\nZavora, zəˈvɔːrə\n\nADK,eI di: keI,x-sampa\n";
+        let lexicon = parse_lexicon("brands.csv", content, LexiconFormat::Csv).unwrap();
+        assert_eq!(lexicon, vec![
+            entry("Zavora", "zəˈvɔːrə"),
+            Pronunciation {
+                alphabet: "x-sampa".to_string(),
+                ..entry("ADK", "eI di: keI")
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_parse_csv_reports_line() {
+        let content = "Zavora,zəˈvɔːrə\nADK\n";
+        let message = parse_lexicon("brands.csv", content, LexiconFormat::Csv).unwrap_err().to_string();
+        assert!(message.contains("brands.csv' at line 2"), "{}", message);
+        assert!(message.contains("got 1 fields"), "{}", message);
+
+        let content = "# comment\nZavora,zəˈvɔːrə\nADK,eI,klingon\n";
+        let message = parse_lexicon("brands.csv", content, LexiconFormat::Csv).unwrap_err().to_string();
+        assert!(message.contains("at line 3"), "{}", message);
+        assert!(message.contains("Invalid alphabet 'klingon'"), "{}", message);
+
+        let content = "Zavora,\n";
+        let message = parse_lexicon("brands.csv", content, LexiconFormat::Csv).unwrap_err().to_string();
+        assert!(message.contains("at line 1"), "{}", message);
+        assert!(message.contains("Phonetic representation cannot be empty"), "{}", message);
+    }
+
+    #[test]
+    fn test_merge_inline_wins() {
+        let inline = vec![entry("Zavora", "inline")];
+        let lexicon = vec![entry("ADK", "a"), entry("Zavora", "lexicon"), entry("ADK", "b")];
+
+        let merged = merge_pronunciations(inline, &lexicon);
+        assert_eq!(merged, vec![entry("Zavora", "inline"), entry("ADK", "a")]);
+    }
+
+    #[test]
+    fn test_cache_matches_version() {
+        let cache = LexiconCache::new();
+        let v1 = LexiconVersion::Generation(1);
+        let v2 = LexiconVersion::Generation(2);
+        cache.insert("gs://b/l.json", v1.clone(), Arc::new(vec![entry("a", "x")]));
+
+        assert_eq!(cache.get("gs://b/l.json", &v1).unwrap()[0], entry("a", "x"));
+        assert!(cache.get("gs://b/l.json", &v2).is_none(), "A newer version must miss");
+        assert!(cache.get("gs://b/other.json", &v1).is_none());
+
+        cache.insert("gs://b/l.json", v2.clone(), Arc::new(vec![entry("a", "y")]));
+        assert_eq!(cache.len(), 1, "Newer versions replace older ones");
+        assert!(cache.get("gs://b/l.json", &v1).is_none());
+        assert_eq!(cache.get("gs://b/l.json", &v2).unwrap()[0], entry("a", "y"));
+    }
+}
@@ -4,10 +4,14 @@
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+pub mod audio_cache;
 pub mod handler;
+pub mod lexicon;
 pub mod server;
 
+pub use audio_cache::AudioCache;
 pub use handler::{
-    GeneratedAudio, Pronunciation, SpeechHandler, SpeechSynthesizeParams, SpeechSynthesizeResult,
+    GeneratedAudio, Pronunciation, SpeechChunk, SpeechHandler, SpeechSynthesizeParams,
+    SpeechSynthesizeResult, SynthesizedSpeech,
 };
 pub use server::SpeechServer;
@@ -2,13 +2,17 @@
 //!
 //! This module provides the MCP server handler that exposes:
 //! - `speech_synthesize` tool for text-to-speech synthesis
+//! - `speech_synthesize_streaming` tool sending clips as they are synthesized
 //! - `speech_list_voices` tool for listing available voices
 
 use crate::handler::{
-    Pronunciation, SpeechHandler, SpeechSynthesizeParams, SpeechSynthesizeResult,
+    Pronunciation, SpeechChunk, SpeechHandler, SpeechSynthesizeParams, SpeechSynthesizeResult,
+    SynthesizedSpeech, VoiceList,
 };
 use adk_rust_mcp_common::config::Config;
-use adk_rust_mcp_common::error::Error;
+use adk_rust_mcp_common::error::{Error, ErrorCode};
+use adk_rust_mcp_common::idempotency::IdempotencyStore;
+use adk_rust_mcp_common::server::ContentSink;
 use rmcp::{
     model::{
         CallToolResult, Content, ListResourcesResult, ReadResourceResult, ServerCapabilities,
@@ -20,9 +24,12 @@ use schemars::JsonSchema;
 use serde::Deserialize;
 use std::borrow::Cow;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
 use tracing::{debug, info};
 
+/// Clips buffered between the handler and the client.
+const STREAM_BUFFER: usize = 4;
+
 /// MCP Server for speech synthesis.
 #[derive(Clone)]
 pub struct SpeechServer {
@@ -30,6 +37,8 @@ pub struct SpeechServer {
     handler: Arc<RwLock<Option<SpeechHandler>>>,
     /// Server configuration
     config: Config,
+    /// Results recorded by idempotency key
+    idempotency: Arc<IdempotencyStore>,
 }
 
 /// Tool parameters wrapper for speech_synthesize.
@@ -49,12 +58,30 @@ pub struct SpeechSynthesizeToolParams {
     /// Pitch adjustment in semitones (-20.0 to 20.0, default 0.0)
     #[serde(default)]
     pub pitch: Option<f32>,
+    /// Volume gain in dB (-96.0 to 16.0, default 0.0)
+    #[serde(default)]
+    pub volume_gain_db: Option<f64>,
+    /// Audio effects profiles for playback devices, applied in order: wearable-class-device,
+    /// handset-class-device, headphone-class-device, small-bluetooth-speaker-class-device,
+    /// medium-bluetooth-speaker-class-device, large-home-entertainment-class-device,
+    /// large-automotive-class-device, telephony-class-application
+    #[serde(default)]
+    pub effects_profile_id: Option<Vec<String>>,
     /// Custom pronunciations for specific words
     #[serde(default)]
     pub pronunciations: Option<Vec<PronunciationToolParam>>,
+    /// Pronunciation lexicon file (local path or gs:// URI): a JSON array of {word, phonetic, alphabet} or CSV lines of word,phonetic[,alphabet]. Inline pronunciations win on conflict
+    #[serde(default)]
+    pub lexicon_file: Option<String>,
     /// Output file path for saving locally
     #[serde(default)]
     pub output_file: Option<String>,
+    /// Output GCS URI (gs://bucket/path, or an object name in GCS_BUCKET); takes precedence over output_file
+    #[serde(default)]
+    pub output_gcs_uri: Option<String>,
+    /// Key making retries of this call return the first result instead of generating again (results expire after IDEMPOTENCY_TTL_SECS, default 1 hour)
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 /// Pronunciation parameter for tool input.
@@ -91,17 +118,30 @@ impl From<SpeechSynthesizeToolParams> for SpeechSynthesizeParams {
             pronunciations: params
                 .pronunciations
                 .map(|p| p.into_iter().map(Into::into).collect()),
+            lexicon_file: params.lexicon_file,
             output_file: params.output_file,
+            output_gcs_uri: params.output_gcs_uri,
+            volume_gain_db: params.volume_gain_db,
+            effects_profile_id: params.effects_profile_id,
         }
     }
 }
 
+/// Tool parameters wrapper for speech_list_voices.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct ListVoicesToolParams {
+    /// Fetch the voice list from the API again instead of using the cached copy
+    #[serde(default)]
+    pub refresh: Option<bool>,
+}
+
 
 impl SpeechServer {
     /// Create a new SpeechServer with the given configuration.
     pub fn new(config: Config) -> Self {
         Self {
             handler: Arc::new(RwLock::new(None)),
+            idempotency: Arc::new(IdempotencyStore::from_config(&config)),
             config,
         }
     }
@@ -124,7 +164,7 @@ impl SpeechServer {
 
         // Ensure handler is initialized
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -134,32 +174,66 @@ impl SpeechServer {
 
         let synth_params: SpeechSynthesizeParams = params.into();
         let result = handler.synthesize(synth_params).await.map_err(|e| {
-            McpError::internal_error(format!("Speech synthesis failed: {}", e), None)
+            e.to_mcp_error("Speech synthesis failed")
         })?;
 
-        // Convert result to MCP content
-        let content = match result {
-            SpeechSynthesizeResult::Base64(audio) => {
-                vec![Content::text(format!(
-                    "data:{};base64,{}",
-                    audio.mime_type, audio.data
-                ))]
-            }
-            SpeechSynthesizeResult::LocalFile(path) => {
-                vec![Content::text(format!("Audio saved to: {}", path))]
+        Ok(synthesis_result(result))
+    }
+
+    /// Synthesize speech, pushing each clip to `sink` as it is ready.
+    ///
+    /// Without a sink (stdio transport, or no progress token) this is the same
+    /// as [`SpeechServer::synthesize`].
+    pub async fn synthesize_streaming(
+        &self,
+        params: SpeechSynthesizeToolParams,
+        sink: Option<ContentSink>,
+    ) -> Result<CallToolResult, McpError> {
+        let Some(mut sink) = sink else {
+            debug!("Transport cannot stream; synthesizing in one piece");
+            return self.synthesize(params).await;
+        };
+        info!(text_len = params.text.len(), "Streaming speech synthesis");
+
+        // Ensure handler is initialized
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard
+            .as_ref()
+            .ok_or_else(|| McpError::internal_error("Handler not initialized", None))?;
+
+        let (chunks_tx, mut chunks_rx) = mpsc::channel::<SpeechChunk>(STREAM_BUFFER);
+        let forward = async {
+            while let Some(chunk) = chunks_rx.recv().await {
+                let content = format!("data:{};base64,{}", chunk.audio.mime_type, chunk.audio.data);
+                let total = u32::try_from(chunk.total).ok();
+                if let Err(e) = sink.push(content, total).await {
+                    debug!(error = %e, index = chunk.index, "Failed to send speech chunk");
+                }
             }
         };
+        let (result, ()) = tokio::join!(handler.synthesize_streaming(params.into(), chunks_tx), forward);
+        let result = result.map_err(|e| {
+            e.to_mcp_error("Speech synthesis failed")
+        })?;
 
-        Ok(CallToolResult::success(content))
+        let mut tool_result = synthesis_result(result);
+        if let Some(serde_json::Value::Object(structured)) = tool_result.structured_content.as_mut() {
+            structured.insert("chunks".to_string(), sink.sent().into());
+        }
+        Ok(tool_result)
     }
 
     /// List available voices.
-    pub async fn list_voices(&self) -> Result<CallToolResult, McpError> {
+    pub async fn list_voices(&self, params: ListVoicesToolParams) -> Result<CallToolResult, McpError> {
         info!("Listing available voices");
 
         // Ensure handler is initialized
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -167,20 +241,49 @@ impl SpeechServer {
             .as_ref()
             .ok_or_else(|| McpError::internal_error("Handler not initialized", None))?;
 
-        let voices = handler.list_voices().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to list voices: {}", e), None)
-        })?;
+        let list = handler.list_voices(params.refresh.unwrap_or(false)).await;
+        Ok(voice_list_result(&list))
+    }
+}
 
-        // Format voices as JSON
-        let voices_json = serde_json::to_string_pretty(&voices).map_err(|e| {
-            McpError::internal_error(format!("Failed to serialize voices: {}", e), None)
-        })?;
+/// Convert a voice list to MCP content: the voices as JSON text, plus the
+/// list's source in the structured result.
+fn voice_list_result(list: &VoiceList) -> CallToolResult {
+    let voices_json = serde_json::to_string_pretty(&list.voices).unwrap_or_else(|_| "[]".to_string());
+    let mut tool_result = CallToolResult::success(vec![Content::text(voices_json)]);
+    tool_result.structured_content = serde_json::to_value(list).ok();
+    tool_result
+}
 
-        Ok(CallToolResult::success(vec![Content::text(voices_json)]))
-    }
+/// Convert a synthesis outcome to MCP content.
+fn synthesis_result(result: SynthesizedSpeech) -> CallToolResult {
+    let (content, location) = match result.output {
+        SpeechSynthesizeResult::Base64(audio) => (
+            vec![Content::text(format!(
+                "data:{};base64,{}",
+                audio.mime_type, audio.data
+            ))],
+            None,
+        ),
+        SpeechSynthesizeResult::LocalFile(path) => {
+            (vec![Content::text(format!("Audio saved to: {}", path))], Some(path))
+        }
+        SpeechSynthesizeResult::GcsUri(uri) => {
+            (vec![Content::text(format!("Audio uploaded to: {}", uri))], Some(uri))
+        }
+    };
+
+    let mut tool_result = CallToolResult::success(content);
+    tool_result.structured_content = Some(serde_json::json!({
+        "output": location,
+        "cached": result.cached,
+    }));
+    tool_result
 }
 
 
+// Method signatures mirror the rmcp trait, which returns `impl Future`.
+#[allow(clippy::manual_async_fn)]
 impl ServerHandler for SpeechServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -213,10 +316,13 @@ impl ServerHandler for SpeechServer {
                 _ => Arc::new(serde_json::Map::new()),
             };
 
-            // speech_list_voices tool (no parameters - must have type: "object")
-            let mut empty_schema_map = serde_json::Map::new();
-            empty_schema_map.insert("type".to_string(), serde_json::Value::String("object".to_string()));
-            let empty_schema = Arc::new(empty_schema_map);
+            // speech_list_voices tool
+            let voices_schema = schema_for!(ListVoicesToolParams);
+            let voices_schema_value = serde_json::to_value(&voices_schema).unwrap_or_default();
+            let voices_input_schema = match voices_schema_value {
+                serde_json::Value::Object(map) => Arc::new(map),
+                _ => Arc::new(serde_json::Map::new()),
+            };
 
             Ok(ListToolsResult {
                 tools: vec![
@@ -224,8 +330,30 @@ impl ServerHandler for SpeechServer {
                         name: Cow::Borrowed("speech_synthesize"),
                         description: Some(Cow::Borrowed(
                             "Convert text to speech using Google Cloud TTS Chirp3-HD voices. \
-                             Returns base64-encoded WAV audio or saves to a local file. \
-                             Supports custom pronunciations using IPA or X-SAMPA phonetic alphabets.",
+                             Returns base64-encoded WAV audio, saves to a local file (output_file), \
+                             or uploads to GCS (output_gcs_uri, which takes precedence). \
+                             Controls speaking_rate (0.25-4.0), pitch (-20 to 20 semitones), \
+                             volume_gain_db (-96 to 16 dB) and effects_profile_id (device profiles such \
+                             as headphone-class-device). \
+                             Supports custom pronunciations using IPA or X-SAMPA phonetic alphabets, \
+                             inline or from a JSON/CSV lexicon_file.",
+                        )),
+                        input_schema: Arc::clone(&synth_input_schema),
+                        annotations: None,
+                        icons: None,
+                        meta: None,
+                        output_schema: None,
+                        title: None,
+                    },
+                    Tool {
+                        name: Cow::Borrowed("speech_synthesize_streaming"),
+                        description: Some(Cow::Borrowed(
+                            "Same parameters and result as speech_synthesize, for long narrations. \
+                             Over the HTTP/SSE transport the text is synthesized sentence by sentence \
+                             and each clip is sent as soon as it is ready in a progress notification \
+                             (message: data:audio/wav;base64,...) for the request's progressToken; \
+                             the final result holds the whole audio or its saved location. \
+                             On stdio, or without a progressToken, it behaves like speech_synthesize.",
                         )),
                         input_schema: synth_input_schema,
                         annotations: None,
@@ -237,9 +365,12 @@ impl ServerHandler for SpeechServer {
                     Tool {
                         name: Cow::Borrowed("speech_list_voices"),
                         description: Some(Cow::Borrowed(
-                            "List available Chirp3-HD voices with their supported languages.",
+                            "List available Chirp3-HD voices with their supported languages. \
+                             The list comes from the Cloud TTS API and is cached; set refresh to fetch it again. \
+                             The structured result's source is \"fallback\" when the API was unavailable \
+                             and a built-in list was returned.",
                         )),
-                        input_schema: empty_schema,
+                        input_schema: voices_input_schema,
                         annotations: None,
                         icons: None,
                         meta: None,
@@ -256,23 +387,44 @@ impl ServerHandler for SpeechServer {
     fn call_tool(
         &self,
         params: rmcp::model::CallToolRequestParams,
-        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+        context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
     ) -> impl std::future::Future<Output = Result<CallToolResult, McpError>> + Send + '_ {
         async move {
             match params.name.as_ref() {
-                "speech_synthesize" => {
+                name @ ("speech_synthesize" | "speech_synthesize_streaming") => {
+                    let arguments = params.arguments.clone().unwrap_or_default();
                     let tool_params: SpeechSynthesizeToolParams = params
                         .arguments
                         .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
                         .transpose()
                         .map_err(|e| {
-                            McpError::invalid_params(format!("Invalid parameters: {}", e), None)
+                            McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data()))
+                        })?
+                        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))?;
+
+                    if name == "speech_synthesize" {
+                        self.idempotency
+                            .run(name, &arguments, self.synthesize(tool_params))
+                            .await
+                    } else {
+                        let sink = ContentSink::for_request(&context);
+                        self.idempotency
+                            .run(name, &arguments, self.synthesize_streaming(tool_params, sink))
+                            .await
+                    }
+                }
+                "speech_list_voices" => {
+                    let tool_params: ListVoicesToolParams = params
+                        .arguments
+                        .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
+                        .transpose()
+                        .map_err(|e| {
+                            McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data()))
                         })?
-                        .ok_or_else(|| McpError::invalid_params("Missing parameters", None))?;
+                        .unwrap_or_default();
 
-                    self.synthesize(tool_params).await
+                    self.list_voices(tool_params).await
                 }
-                "speech_list_voices" => self.list_voices().await,
                 _ => Err(McpError::invalid_params(
                     format!("Unknown tool: {}", params.name),
                     None,
@@ -322,9 +474,7 @@ mod tests {
     fn test_config() -> Config {
         Config {
             project_id: "test-project".to_string(),
-            location: "us-central1".to_string(),
-            gcs_bucket: None,
-            port: 8080,
+            ..Default::default()
         }
     }
 
@@ -348,7 +498,12 @@ mod tests {
                 phonetic: "həˈloʊ".to_string(),
                 alphabet: "ipa".to_string(),
             }]),
+            lexicon_file: Some("/etc/speech/brands.csv".to_string()),
             output_file: None,
+            output_gcs_uri: Some("gs://bucket/speech/hello.wav".to_string()),
+            volume_gain_db: Some(-3.0),
+            effects_profile_id: Some(vec!["headphone-class-device".to_string()]),
+            idempotency_key: None,
         };
 
         let synth_params: SpeechSynthesizeParams = tool_params.into();
@@ -357,7 +512,14 @@ mod tests {
         assert_eq!(synth_params.language_code, "en-US");
         assert_eq!(synth_params.speaking_rate, 1.5);
         assert_eq!(synth_params.pitch, 2.0);
+        assert_eq!(synth_params.volume_gain_db, Some(-3.0));
+        assert_eq!(
+            synth_params.effects_profile_id,
+            Some(vec!["headphone-class-device".to_string()])
+        );
         assert!(synth_params.pronunciations.is_some());
+        assert_eq!(synth_params.output_gcs_uri, Some("gs://bucket/speech/hello.wav".to_string()));
+        assert_eq!(synth_params.lexicon_file, Some("/etc/speech/brands.csv".to_string()));
     }
 
     #[test]
@@ -369,7 +531,12 @@ mod tests {
             speaking_rate: None,
             pitch: None,
             pronunciations: None,
+            lexicon_file: None,
             output_file: None,
+            output_gcs_uri: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
+            idempotency_key: None,
         };
 
         let synth_params: SpeechSynthesizeParams = tool_params.into();
@@ -378,6 +545,25 @@ mod tests {
         assert_eq!(synth_params.pitch, 0.0);
     }
 
+    #[test]
+    fn test_voice_list_result() {
+        let list = VoiceList {
+            voices: crate::handler::fallback_voices(),
+            source: adk_rust_mcp_common::voices::VoiceSource::Fallback,
+        };
+
+        let result = voice_list_result(&list);
+        let text: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+        assert_eq!(text.as_array().unwrap().len(), list.voices.len());
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["source"], "fallback");
+        assert_eq!(structured["voices"][0]["name"], "en-US-Chirp3-HD-Achernar");
+
+        let params: ListVoicesToolParams = serde_json::from_str(r#"{"refresh": true}"#).unwrap();
+        assert_eq!(params.refresh, Some(true));
+    }
+
     #[test]
     fn test_pronunciation_conversion() {
         let tool_pron = PronunciationToolParam {
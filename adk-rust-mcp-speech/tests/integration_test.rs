@@ -9,6 +9,7 @@
 //! - Access to Cloud TTS API
 
 use adk_rust_mcp_common::config::Config;
+use adk_rust_mcp_common::voices::VoiceSource;
 use adk_rust_mcp_speech::handler::{
     Pronunciation, SpeechHandler, SpeechSynthesizeParams, DEFAULT_LANGUAGE_CODE,
     DEFAULT_SPEAKING_RATE, MAX_PITCH, MAX_SPEAKING_RATE, MIN_PITCH, MIN_SPEAKING_RATE,
@@ -36,7 +37,7 @@ fn get_test_config() -> Option<Config> {
         project_id,
         location: env::var("LOCATION").unwrap_or_else(|_| "us-central1".to_string()),
         gcs_bucket: env::var("GCS_BUCKET").ok(),
-        port: 8080,
+        ..Default::default()
     })
 }
 
@@ -91,6 +92,10 @@ async fn test_validation_empty_text() {
         pitch: 0.0,
         pronunciations: None,
         output_file: None,
+        output_gcs_uri: None,
+        lexicon_file: None,
+        volume_gain_db: None,
+        effects_profile_id: None,
     };
 
     let result = params.validate();
@@ -109,6 +114,10 @@ async fn test_validation_invalid_speaking_rate_low() {
         pitch: 0.0,
         pronunciations: None,
         output_file: None,
+        output_gcs_uri: None,
+        lexicon_file: None,
+        volume_gain_db: None,
+        effects_profile_id: None,
     };
 
     let result = params.validate();
@@ -127,6 +136,10 @@ async fn test_validation_invalid_speaking_rate_high() {
         pitch: 0.0,
         pronunciations: None,
         output_file: None,
+        output_gcs_uri: None,
+        lexicon_file: None,
+        volume_gain_db: None,
+        effects_profile_id: None,
     };
 
     let result = params.validate();
@@ -145,6 +158,10 @@ async fn test_validation_invalid_pitch_low() {
         pitch: -25.0, // Invalid: min is -20.0
         pronunciations: None,
         output_file: None,
+        output_gcs_uri: None,
+        lexicon_file: None,
+        volume_gain_db: None,
+        effects_profile_id: None,
     };
 
     let result = params.validate();
@@ -163,6 +180,10 @@ async fn test_validation_invalid_pitch_high() {
         pitch: 25.0, // Invalid: max is 20.0
         pronunciations: None,
         output_file: None,
+        output_gcs_uri: None,
+        lexicon_file: None,
+        volume_gain_db: None,
+        effects_profile_id: None,
     };
 
     let result = params.validate();
@@ -185,6 +206,10 @@ async fn test_validation_invalid_pronunciation_alphabet() {
             alphabet: "invalid".to_string(), // Invalid alphabet
         }]),
         output_file: None,
+        output_gcs_uri: None,
+        lexicon_file: None,
+        volume_gain_db: None,
+        effects_profile_id: None,
     };
 
     let result = params.validate();
@@ -205,6 +230,10 @@ async fn test_validation_valid_params() {
         pitch: 2.0,
         pronunciations: None,
         output_file: None,
+        output_gcs_uri: None,
+        lexicon_file: None,
+        volume_gain_db: None,
+        effects_profile_id: None,
     };
 
     assert!(params.validate().is_ok());
@@ -224,6 +253,10 @@ async fn test_validation_valid_params_with_pronunciation() {
             alphabet: "ipa".to_string(),
         }]),
         output_file: None,
+        output_gcs_uri: None,
+        lexicon_file: None,
+        volume_gain_db: None,
+        effects_profile_id: None,
     };
 
     assert!(params.validate().is_ok());
@@ -240,6 +273,10 @@ async fn test_validation_boundary_values() {
         pitch: MIN_PITCH,
         pronunciations: None,
         output_file: None,
+        output_gcs_uri: None,
+        lexicon_file: None,
+        volume_gain_db: None,
+        effects_profile_id: None,
     };
     assert!(params.validate().is_ok());
 
@@ -252,6 +289,10 @@ async fn test_validation_boundary_values() {
         pitch: MAX_PITCH,
         pronunciations: None,
         output_file: None,
+        output_gcs_uri: None,
+        lexicon_file: None,
+        volume_gain_db: None,
+        effects_profile_id: None,
     };
     assert!(params.validate().is_ok());
 }
@@ -270,6 +311,10 @@ async fn test_ssml_generation() {
             alphabet: "ipa".to_string(),
         }]),
         output_file: None,
+        output_gcs_uri: None,
+        lexicon_file: None,
+        volume_gain_db: None,
+        effects_profile_id: None,
     };
 
     let ssml = params.build_ssml();
@@ -313,10 +358,14 @@ mod chirp3_api_tests {
             pitch: 0.0,
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
         eprintln!("Starting speech synthesis...");
-        let result = handler.synthesize(params).await;
+        let result = handler.synthesize(params).await.map(|speech| speech.output);
 
         match result {
             Ok(SpeechSynthesizeResult::Base64(audio)) => {
@@ -355,10 +404,14 @@ mod chirp3_api_tests {
             pitch: 0.0,
             pronunciations: None,
             output_file: Some(output_path.to_string_lossy().to_string()),
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
         eprintln!("Starting speech synthesis to file...");
-        let result = handler.synthesize(params).await;
+        let result = handler.synthesize(params).await.map(|speech| speech.output);
 
         match result {
             Ok(SpeechSynthesizeResult::LocalFile(path)) => {
@@ -402,9 +455,13 @@ mod chirp3_api_tests {
             pitch: 5.0,
             pronunciations: None,
             output_file: Some(output_path.to_string_lossy().to_string()),
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
-        let result = handler.synthesize(params).await;
+        let result = handler.synthesize(params).await.map(|speech| speech.output);
 
         match result {
             Ok(SpeechSynthesizeResult::LocalFile(path)) => {
@@ -444,9 +501,13 @@ mod chirp3_api_tests {
                 alphabet: "ipa".to_string(),
             }]),
             output_file: Some(output_path.to_string_lossy().to_string()),
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
-        let result = handler.synthesize(params).await;
+        let result = handler.synthesize(params).await.map(|speech| speech.output);
 
         match result {
             Ok(SpeechSynthesizeResult::LocalFile(path)) => {
@@ -470,10 +531,10 @@ mod chirp3_api_tests {
             .await
             .expect("Failed to create handler");
 
-        let result = handler.list_voices().await;
-        assert!(result.is_ok(), "List voices failed: {:?}", result.err());
+        let result = handler.list_voices(true).await;
+        assert_eq!(result.source, VoiceSource::Api, "Voices API was not reachable");
 
-        let voices = result.unwrap();
+        let voices = result.voices;
         // Should have at least some Chirp3-HD voices
         assert!(!voices.is_empty(), "No Chirp3-HD voices found");
         
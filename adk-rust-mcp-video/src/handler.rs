@@ -4,16 +4,21 @@
 //! video generation using Google's Vertex AI Veo API.
 
 use adk_rust_mcp_common::auth::AuthProvider;
-use adk_rust_mcp_common::config::Config;
-use adk_rust_mcp_common::error::Error;
+use adk_rust_mcp_common::config::{self, Config};
+use adk_rust_mcp_common::error::{Error, GcsError, GcsOperation};
 use adk_rust_mcp_common::gcs::{GcsClient, GcsUri};
+use adk_rust_mcp_common::media_input::{self, ResolvedImage};
+use adk_rust_mcp_common::metadata::{self, GenerationMetadata};
 use adk_rust_mcp_common::models::{ModelRegistry, VeoModel, VEO_MODELS};
-use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use adk_rust_mcp_common::retry::{RetryPolicy, retry_with_backoff, send_with_retry};
+use crate::resources::SessionRegistry;
+use futures::stream::{self, StreamExt};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
-use std::time::Duration;
-use tracing::{debug, info, instrument};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, instrument, warn};
 
 /// Valid aspect ratios for video generation.
 pub const VALID_ASPECT_RATIOS: &[&str] = &["16:9", "9:16"];
@@ -36,11 +41,91 @@ pub const MAX_DURATION_SECONDS: u8 = 8;
 /// Default aspect ratio.
 pub const DEFAULT_ASPECT_RATIO: &str = "16:9";
 
+/// Valid camera motions and the corresponding Veo API `cameraControl` values.
+pub const CAMERA_MOTIONS: &[(&str, &str)] = &[
+    ("static", "FIXED"),
+    ("pan_left", "PAN_LEFT"),
+    ("pan_right", "PAN_RIGHT"),
+    ("tilt_up", "TILT_UP"),
+    ("tilt_down", "TILT_DOWN"),
+    ("zoom_in", "ZOOM_IN"),
+    ("zoom_out", "ZOOM_OUT"),
+    ("dolly_in", "PUSH_IN"),
+    ("dolly_out", "PULL_OUT"),
+];
+
+/// Look up the Veo API `cameraControl` value for a camera motion.
+pub fn camera_control_value(motion: &str) -> Option<&'static str> {
+    CAMERA_MOTIONS
+        .iter()
+        .find(|(name, _)| *name == motion)
+        .map(|(_, value)| *value)
+}
+
+/// Validate a camera motion against the model's capabilities.
+/// Check a per-request region override against the known Vertex AI regions.
+fn validate_location(location: Option<&str>, errors: &mut Vec<ValidationError>) {
+    if let Some(Err(message)) = location.map(config::validate_location) {
+        errors.push(ValidationError {
+            field: "location".to_string(),
+            message,
+        });
+    }
+}
+
+fn validate_camera_motion(
+    camera_motion: Option<&str>,
+    model: Option<&VeoModel>,
+    errors: &mut Vec<ValidationError>,
+) {
+    let Some(motion) = camera_motion else {
+        return;
+    };
+
+    if camera_control_value(motion).is_none() {
+        errors.push(ValidationError {
+            field: "camera_motion".to_string(),
+            message: format!(
+                "Invalid camera motion '{}'. Valid options: {}",
+                motion,
+                CAMERA_MOTIONS
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        });
+    }
+
+    if let Some(model) = model {
+        if !model.supports_camera_control {
+            errors.push(ValidationError {
+                field: "camera_motion".to_string(),
+                message: format!("camera_motion is not supported by model {}", model.id),
+            });
+        }
+    }
+}
+
 /// LRO polling configuration
 pub const LRO_INITIAL_DELAY_MS: u64 = 5000;
 pub const LRO_MAX_DELAY_MS: u64 = 60000;
 pub const LRO_BACKOFF_MULTIPLIER: f64 = 1.5;
-pub const LRO_MAX_ATTEMPTS: u32 = 120; // ~30 minutes max with backoff
+pub const LRO_MAX_ATTEMPTS: u32 = 120; // secondary guard; the deadline normally ends polling first
+
+/// Default overall time allowed for a video generation operation, unless
+/// `VIDEO_LRO_TIMEOUT_SECS` or `[video] lro_timeout_secs` sets one.
+pub const LRO_DEFAULT_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+
+/// Maximum number of shots in one batch request.
+pub const MAX_BATCH_SHOTS: usize = 10;
+
+/// Default number of shots generated at the same time.
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 3;
+
+/// Maximum number of shots generated at the same time.
+pub const MAX_BATCH_CONCURRENCY: usize = 4;
 
 /// Text-to-video generation parameters.
 ///
@@ -65,17 +150,22 @@ pub struct VideoT2vParams {
     pub duration_seconds: u8,
 
     /// GCS URI for output (required by Veo API).
-    /// Format: gs://bucket/path/to/output.mp4
+    /// Format: gs://bucket/path/to/output.mp4, or an object name in `GCS_BUCKET`
     pub output_gcs_uri: String,
 
     /// Whether to also download the video locally after generation.
     #[serde(default)]
     pub download_local: bool,
 
-    /// Local path to save the video if download_local is true.
+    /// Local path to save the video if download_local is true. Defaults to the
+    /// object's file name in MCP_DOWNLOAD_DIR (a temp directory when unset).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub local_path: Option<String>,
 
+    /// Delete the GCS copy after a successful local download (requires download_local).
+    #[serde(default)]
+    pub cleanup_gcs: bool,
+
     /// Whether to generate audio (only supported on Veo 3.x models).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub generate_audio: Option<bool>,
@@ -83,6 +173,21 @@ pub struct VideoT2vParams {
     /// Random seed for reproducible generation.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub seed: Option<i64>,
+
+    /// Camera motion hint (e.g. "pan_left", "zoom_in", "static").
+    /// Only supported on models with camera control.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub camera_motion: Option<String>,
+
+    /// Vertex AI region for this request (e.g. "us-central1").
+    /// Falls back to `LOCATION` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+
+    /// Write a `<name>.json` sidecar with the generation settings next to the
+    /// video (and next to the local copy when downloaded).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub write_metadata: bool,
 }
 
 fn default_model() -> String {
@@ -97,6 +202,24 @@ fn default_duration_seconds() -> u8 {
     DEFAULT_DURATION_SECONDS
 }
 
+fn default_batch_concurrency() -> usize {
+    DEFAULT_BATCH_CONCURRENCY
+}
+
+/// Batch text-to-video parameters.
+///
+/// Each shot is a full text-to-video request. Shots run concurrently, up to
+/// `max_concurrency` at a time, and a failed shot does not stop the others.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct VideoBatchParams {
+    /// Shots to generate, each with its own prompt and output URI.
+    pub shots: Vec<VideoT2vParams>,
+
+    /// Number of shots generated at the same time (1-4).
+    #[serde(default = "default_batch_concurrency")]
+    pub max_concurrency: usize,
+}
+
 /// Image-to-video generation parameters.
 ///
 /// These parameters control the image-to-video generation process via the Vertex AI Veo API.
@@ -104,7 +227,7 @@ fn default_duration_seconds() -> u8 {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct VideoI2vParams {
     /// Source image for video generation (first frame for interpolation).
-    /// Can be base64 data, local file path, or GCS URI.
+    /// Can be base64 data, data URI, local file path, GCS URI, or HTTPS URL.
     pub image: String,
 
     /// Text prompt describing the desired video motion.
@@ -112,7 +235,7 @@ pub struct VideoI2vParams {
 
     /// Last frame image for interpolation mode.
     /// If provided, generates a video interpolating between `image` and `last_frame_image`.
-    /// Can be base64 data, local file path, or GCS URI.
+    /// Can be base64 data, data URI, local file path, GCS URI, or HTTPS URL.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub last_frame_image: Option<String>,
 
@@ -131,20 +254,40 @@ pub struct VideoI2vParams {
     pub duration_seconds: u8,
 
     /// GCS URI for output (required by Veo API).
-    /// Format: gs://bucket/path/to/output.mp4
+    /// Format: gs://bucket/path/to/output.mp4, or an object name in `GCS_BUCKET`
     pub output_gcs_uri: String,
 
     /// Whether to also download the video locally after generation.
     #[serde(default)]
     pub download_local: bool,
 
-    /// Local path to save the video if download_local is true.
+    /// Local path to save the video if download_local is true. Defaults to the
+    /// object's file name in MCP_DOWNLOAD_DIR (a temp directory when unset).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub local_path: Option<String>,
 
+    /// Delete the GCS copy after a successful local download (requires download_local).
+    #[serde(default)]
+    pub cleanup_gcs: bool,
+
     /// Random seed for reproducible generation.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub seed: Option<i64>,
+
+    /// Camera motion hint (e.g. "pan_left", "zoom_in", "static").
+    /// Only supported on models with camera control.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub camera_motion: Option<String>,
+
+    /// Vertex AI region for this request (e.g. "us-central1").
+    /// Falls back to `LOCATION` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+
+    /// Write a `<name>.json` sidecar with the generation settings next to the
+    /// video (and next to the local copy when downloaded).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub write_metadata: bool,
 }
 
 /// Video extension parameters.
@@ -170,20 +313,35 @@ pub struct VideoExtendParams {
     pub duration_seconds: u8,
 
     /// GCS URI for output (required by Veo API).
-    /// Format: gs://bucket/path/to/output.mp4
+    /// Format: gs://bucket/path/to/output.mp4, or an object name in `GCS_BUCKET`
     pub output_gcs_uri: String,
 
     /// Whether to also download the video locally after generation.
     #[serde(default)]
     pub download_local: bool,
 
-    /// Local path to save the video if download_local is true.
+    /// Local path to save the video if download_local is true. Defaults to the
+    /// object's file name in MCP_DOWNLOAD_DIR (a temp directory when unset).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub local_path: Option<String>,
 
+    /// Delete the GCS copy after a successful local download (requires download_local).
+    #[serde(default)]
+    pub cleanup_gcs: bool,
+
     /// Random seed for reproducible generation.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub seed: Option<i64>,
+
+    /// Vertex AI region for this request (e.g. "us-central1").
+    /// Falls back to `LOCATION` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+
+    /// Write a `<name>.json` sidecar with the generation settings next to the
+    /// video (and next to the local copy when downloaded).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub write_metadata: bool,
 }
 
 /// Validation error details for video generation parameters.
@@ -217,15 +375,7 @@ impl VideoT2vParams {
         if model.is_none() {
             errors.push(ValidationError {
                 field: "model".to_string(),
-                message: format!(
-                    "Unknown model '{}'. Valid models: {}",
-                    self.model,
-                    VEO_MODELS
-                        .iter()
-                        .map(|m| m.id)
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                ),
+                message: ModelRegistry::unknown_model_message(VEO_MODELS, &self.model),
             });
         }
 
@@ -298,6 +448,9 @@ impl VideoT2vParams {
             }
         }
 
+        // Validate camera motion
+        validate_camera_motion(self.camera_motion.as_deref(), model, &mut errors);
+
         // Validate output_gcs_uri is a valid GCS URI
         if !self.output_gcs_uri.starts_with("gs://") {
             errors.push(ValidationError {
@@ -309,6 +462,16 @@ impl VideoT2vParams {
             });
         }
 
+        validate_location(self.location.as_deref(), &mut errors);
+
+        // Local-only output needs a local copy to keep
+        if self.cleanup_gcs && !self.download_local {
+            errors.push(ValidationError {
+                field: "cleanup_gcs".to_string(),
+                message: "cleanup_gcs requires download_local to be true".to_string(),
+            });
+        }
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -338,15 +501,7 @@ impl VideoI2vParams {
         if model.is_none() {
             errors.push(ValidationError {
                 field: "model".to_string(),
-                message: format!(
-                    "Unknown model '{}'. Valid models: {}",
-                    self.model,
-                    VEO_MODELS
-                        .iter()
-                        .map(|m| m.id)
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                ),
+                message: ModelRegistry::unknown_model_message(VEO_MODELS, &self.model),
             });
         }
 
@@ -416,6 +571,9 @@ impl VideoI2vParams {
             }
         }
 
+        // Validate camera motion
+        validate_camera_motion(self.camera_motion.as_deref(), model, &mut errors);
+
         // Validate output_gcs_uri is a valid GCS URI
         if !self.output_gcs_uri.starts_with("gs://") {
             errors.push(ValidationError {
@@ -427,6 +585,16 @@ impl VideoI2vParams {
             });
         }
 
+        validate_location(self.location.as_deref(), &mut errors);
+
+        // Local-only output needs a local copy to keep
+        if self.cleanup_gcs && !self.download_local {
+            errors.push(ValidationError {
+                field: "cleanup_gcs".to_string(),
+                message: "cleanup_gcs requires download_local to be true".to_string(),
+            });
+        }
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -456,15 +624,7 @@ impl VideoExtendParams {
         if model.is_none() {
             errors.push(ValidationError {
                 field: "model".to_string(),
-                message: format!(
-                    "Unknown model '{}'. Valid models: {}",
-                    self.model,
-                    VEO_MODELS
-                        .iter()
-                        .map(|m| m.id)
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                ),
+                message: ModelRegistry::unknown_model_message(VEO_MODELS, &self.model),
             });
         }
 
@@ -521,6 +681,16 @@ impl VideoExtendParams {
             });
         }
 
+        validate_location(self.location.as_deref(), &mut errors);
+
+        // Local-only output needs a local copy to keep
+        if self.cleanup_gcs && !self.download_local {
+            errors.push(ValidationError {
+                field: "cleanup_gcs".to_string(),
+                message: "cleanup_gcs requires download_local to be true".to_string(),
+            });
+        }
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -534,6 +704,96 @@ impl VideoExtendParams {
     }
 }
 
+impl VideoBatchParams {
+    /// Validate the batch and every shot in it.
+    ///
+    /// Shot errors are reported with the shot's index, e.g. `shots[2].prompt`.
+    /// Output URIs must be distinct so shots do not overwrite each other.
+    ///
+    /// # Returns
+    /// - `Ok(())` if all parameters are valid
+    /// - `Err(Vec<ValidationError>)` with all validation errors
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        if self.shots.is_empty() {
+            errors.push(ValidationError {
+                field: "shots".to_string(),
+                message: "shots cannot be empty".to_string(),
+            });
+        } else if self.shots.len() > MAX_BATCH_SHOTS {
+            errors.push(ValidationError {
+                field: "shots".to_string(),
+                message: format!(
+                    "shots must contain at most {} entries, got {}",
+                    MAX_BATCH_SHOTS,
+                    self.shots.len()
+                ),
+            });
+        }
+
+        if self.max_concurrency == 0 || self.max_concurrency > MAX_BATCH_CONCURRENCY {
+            errors.push(ValidationError {
+                field: "max_concurrency".to_string(),
+                message: format!(
+                    "max_concurrency must be between 1 and {}, got {}",
+                    MAX_BATCH_CONCURRENCY, self.max_concurrency
+                ),
+            });
+        }
+
+        for (index, shot) in self.shots.iter().enumerate() {
+            if let Err(shot_errors) = shot.validate() {
+                errors.extend(shot_errors.into_iter().map(|e| ValidationError {
+                    field: format!("shots[{}].{}", index, e.field),
+                    message: e.message,
+                }));
+            }
+
+            if let Some(first) = self.shots[..index]
+                .iter()
+                .position(|other| other.output_gcs_uri == shot.output_gcs_uri)
+            {
+                errors.push(ValidationError {
+                    field: format!("shots[{}].output_gcs_uri", index),
+                    message: format!(
+                        "output_gcs_uri '{}' is already used by shots[{}]",
+                        shot.output_gcs_uri, first
+                    ),
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Run `task` over `items` with at most `limit` tasks in flight.
+///
+/// Every item runs to completion regardless of the others' outcome. Results
+/// are returned in input order.
+async fn run_concurrently<T, R, F, Fut>(items: Vec<T>, limit: usize, task: F) -> Vec<Result<R, Error>>
+where
+    F: Fn(usize, T) -> Fut,
+    Fut: std::future::Future<Output = Result<R, Error>>,
+{
+    let mut outcomes: Vec<(usize, Result<R, Error>)> = stream::iter(items.into_iter().enumerate())
+        .map(|(index, item)| {
+            let fut = task(index, item);
+            async move { (index, fut.await) }
+        })
+        .buffer_unordered(limit.max(1))
+        .collect()
+        .await;
+
+    outcomes.sort_by_key(|(index, _)| *index);
+    outcomes.into_iter().map(|(_, outcome)| outcome).collect()
+}
+
 /// Video generation handler.
 ///
 /// Handles video generation requests using the Vertex AI Veo API.
@@ -544,8 +804,12 @@ pub struct VideoHandler {
     pub gcs: GcsClient,
     /// HTTP client for API requests.
     pub http: reqwest::Client,
-    /// Authentication provider.
-    pub auth: AuthProvider,
+    /// Authentication provider (shared with the GCS client).
+    pub auth: Arc<AuthProvider>,
+    /// Registry of videos generated during the session.
+    pub registry: Arc<SessionRegistry>,
+    /// Overall time allowed for polling one long-running operation.
+    pub lro_timeout: Duration,
 }
 
 impl VideoHandler {
@@ -557,48 +821,81 @@ impl VideoHandler {
     pub async fn new(config: Config) -> Result<Self, Error> {
         debug!("Initializing VideoHandler");
 
-        let auth = AuthProvider::new().await?;
-        let gcs = GcsClient::with_auth(AuthProvider::new().await?);
-        let http = reqwest::Client::new();
+        let auth = Arc::new(AuthProvider::new().await?);
+        Self::with_auth(config, auth)
+    }
+
+    /// Create a new VideoHandler around an existing auth provider.
+    ///
+    /// The provider (and its token cache) is shared by API calls and the GCS client.
+    ///
+    /// # Errors
+    /// Returns an error if the HTTP client cannot be configured.
+    pub fn with_auth(config: Config, auth: Arc<AuthProvider>) -> Result<Self, Error> {
+        let http = config.http.build()?;
+        let gcs = GcsClient::with_client(Arc::clone(&auth), http.clone());
+
+        let lro_timeout = config.video_lro_timeout.unwrap_or(LRO_DEFAULT_TIMEOUT);
 
         Ok(Self {
             config,
             gcs,
             http,
             auth,
+            registry: Arc::new(SessionRegistry::default()),
+            lro_timeout,
         })
     }
 
+    /// Use a shared session registry (e.g. one owned by the server).
+    pub fn with_registry(mut self, registry: Arc<SessionRegistry>) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Set the overall time allowed for polling one long-running operation.
+    pub fn with_lro_timeout(mut self, timeout: Duration) -> Self {
+        self.lro_timeout = timeout;
+        self
+    }
+
     /// Create a new VideoHandler with provided dependencies (for testing).
     #[cfg(test)]
-    pub fn with_deps(config: Config, gcs: GcsClient, http: reqwest::Client, auth: AuthProvider) -> Self {
+    pub fn with_deps(config: Config, gcs: GcsClient, http: reqwest::Client, auth: Arc<AuthProvider>) -> Self {
         Self {
             config,
             gcs,
             http,
             auth,
+            registry: Arc::new(SessionRegistry::default()),
+            lro_timeout: LRO_DEFAULT_TIMEOUT,
         }
     }
 
     /// Get the Vertex AI Veo API endpoint for generating videos.
-    pub fn get_generate_endpoint(&self, model: &str) -> String {
+    ///
+    /// `location` overrides the configured region for a single request.
+    pub fn get_generate_endpoint(&self, model: &str, location: Option<&str>) -> String {
+        let location = self.config.location_or(location);
         format!(
             "https://{}-aiplatform.googleapis.com/v1/projects/{}/locations/{}/publishers/google/models/{}:predictLongRunning",
-            self.config.location,
+            location,
             self.config.project_id,
-            self.config.location,
+            location,
             model
         )
     }
 
     /// Get the Vertex AI endpoint for fetching LRO status.
     /// Uses the fetchPredictOperation endpoint which requires the operation name in the request body.
-    pub fn get_fetch_operation_endpoint(&self, model: &str) -> String {
+    /// The operation must be polled in the region that started it.
+    pub fn get_fetch_operation_endpoint(&self, model: &str, location: Option<&str>) -> String {
+        let location = self.config.location_or(location);
         format!(
             "https://{}-aiplatform.googleapis.com/v1/projects/{}/locations/{}/publishers/google/models/{}:fetchPredictOperation",
-            self.config.location,
+            location,
             self.config.project_id,
-            self.config.location,
+            location,
             model
         )
     }
@@ -612,7 +909,10 @@ impl VideoHandler {
     /// * `Ok(VideoGenerateResult)` - Generated video with GCS URI and optional local path
     /// * `Err(Error)` - If validation fails, API call fails, or output handling fails
     #[instrument(level = "info", name = "generate_video_t2v", skip(self, params), fields(model = %params.model, aspect_ratio = %params.aspect_ratio))]
-    pub async fn generate_video_t2v(&self, params: VideoT2vParams) -> Result<VideoGenerateResult, Error> {
+    pub async fn generate_video_t2v(&self, mut params: VideoT2vParams) -> Result<VideoGenerateResult, Error> {
+        // Bare object names go to the configured bucket
+        params.output_gcs_uri = self.config.resolve_output_uri(&params.output_gcs_uri)?;
+
         // Validate parameters
         params.validate().map_err(|errors| {
             let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
@@ -621,7 +921,7 @@ impl VideoHandler {
 
         // Resolve the model to get the canonical ID
         let model = params.get_model().ok_or_else(|| {
-            Error::validation(format!("Unknown model: {}", params.model))
+            Error::validation(ModelRegistry::unknown_model_message(VEO_MODELS, &params.model))
         })?;
 
         info!(model_id = model.id, "Generating video with Veo API (text-to-video)");
@@ -637,6 +937,7 @@ impl VideoHandler {
                 duration_seconds: Some(params.duration_seconds),
                 generate_audio: if model.supports_audio { params.generate_audio } else { None },
                 seed: params.seed,
+                camera_control: params.camera_motion.as_deref().and_then(camera_control_value).map(String::from),
             },
         };
 
@@ -644,22 +945,22 @@ impl VideoHandler {
         let token = self.auth.get_token(&["https://www.googleapis.com/auth/cloud-platform"]).await?;
 
         // Make API request to start LRO
-        let endpoint = self.get_generate_endpoint(model.id);
+        let endpoint = self.get_generate_endpoint(model.id, params.location.as_deref());
         debug!(endpoint = %endpoint, "Calling Veo API");
 
-        let response = self.http
-            .post(&endpoint)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| Error::api(&endpoint, 0, format!("Request failed: {}", e)))?;
+        let response = send_with_retry(&RetryPolicy::from_config(&self.config), &endpoint, || {
+            self.http
+                .post(&endpoint)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .json(&request)
+        })
+        .await?;
 
         let status = response.status();
         if !status.is_success() {
             let body = response.text().await.unwrap_or_default();
-            return Err(Error::api(&endpoint, status.as_u16(), body));
+            return Err(Error::api_response(&endpoint, status.as_u16(), body));
         }
 
         // Parse LRO response
@@ -670,10 +971,71 @@ impl VideoHandler {
         info!(operation_name = %lro_response.name, "Started video generation LRO");
 
         // Poll for completion
-        let result = self.poll_lro(&lro_response.name, model.id).await?;
+        let result = self
+            .poll_lro(&lro_response.name, model.id, params.location.as_deref())
+            .await?;
 
         // Handle output
-        self.handle_output(result, &params.output_gcs_uri, params.download_local, params.local_path.as_deref()).await
+        let output = OutputOptions {
+            prompt: &params.prompt,
+            output_gcs_uri: &params.output_gcs_uri,
+            download_local: params.download_local,
+            local_path: params.local_path.as_deref(),
+            cleanup_gcs: params.cleanup_gcs,
+            metadata: params.write_metadata.then(|| GenerationMetadata {
+                seed: params.seed,
+                aspect_ratio: Some(params.aspect_ratio.clone()),
+                ..GenerationMetadata::new(&params.prompt, model.id)
+            }),
+        };
+        self.handle_output(result, &output, params.duration_seconds, params.seed).await
+    }
+
+    /// Generate several text-to-video shots concurrently.
+    ///
+    /// Up to `max_concurrency` shots are started and polled at the same time.
+    /// A shot that fails is reported in its entry of the result; the other
+    /// shots still run to completion.
+    ///
+    /// # Errors
+    /// Returns `Error::Validation` if the batch or any shot is invalid; no
+    /// shot is started in that case.
+    #[instrument(level = "info", name = "generate_video_batch", skip(self, params), fields(shots = params.shots.len(), max_concurrency = params.max_concurrency))]
+    pub async fn generate_video_batch(&self, mut params: VideoBatchParams) -> Result<VideoBatchResult, Error> {
+        // Resolve output URIs first so duplicates are caught across both forms
+        for shot in &mut params.shots {
+            shot.output_gcs_uri = self.config.resolve_output_uri(&shot.output_gcs_uri)?;
+        }
+
+        params.validate().map_err(|errors| {
+            let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+            Error::validation(messages.join("; "))
+        })?;
+
+        let prompts: Vec<String> = params.shots.iter().map(|shot| shot.prompt.clone()).collect();
+        let outcomes = run_concurrently(params.shots, params.max_concurrency, |index, shot| async move {
+            let outcome = self.generate_video_t2v(shot).await;
+            if let Err(e) = &outcome {
+                warn!(index, error = %e, "Batch shot failed");
+            }
+            outcome
+        })
+        .await;
+
+        let shots: Vec<BatchShotResult> = outcomes
+            .into_iter()
+            .zip(prompts)
+            .enumerate()
+            .map(|(index, (outcome, prompt))| match outcome {
+                Ok(video) => BatchShotResult { index, prompt, video: Some(video), error: None },
+                Err(e) => BatchShotResult { index, prompt, video: None, error: Some(e.to_string()) },
+            })
+            .collect();
+        let succeeded = shots.iter().filter(|shot| shot.video.is_some()).count();
+        let failed = shots.len() - succeeded;
+
+        info!(succeeded, failed, "Batch video generation finished");
+        Ok(VideoBatchResult { shots, succeeded, failed })
     }
 
     /// Generate video from an image.
@@ -685,7 +1047,10 @@ impl VideoHandler {
     /// * `Ok(VideoGenerateResult)` - Generated video with GCS URI and optional local path
     /// * `Err(Error)` - If validation fails, API call fails, or output handling fails
     #[instrument(level = "info", name = "generate_video_i2v", skip(self, params), fields(model = %params.model, aspect_ratio = %params.aspect_ratio))]
-    pub async fn generate_video_i2v(&self, params: VideoI2vParams) -> Result<VideoGenerateResult, Error> {
+    pub async fn generate_video_i2v(&self, mut params: VideoI2vParams) -> Result<VideoGenerateResult, Error> {
+        // Bare object names go to the configured bucket
+        params.output_gcs_uri = self.config.resolve_output_uri(&params.output_gcs_uri)?;
+
         // Validate parameters
         params.validate().map_err(|errors| {
             let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
@@ -694,7 +1059,7 @@ impl VideoHandler {
 
         // Resolve the model to get the canonical ID
         let model = params.get_model().ok_or_else(|| {
-            Error::validation(format!("Unknown model: {}", params.model))
+            Error::validation(ModelRegistry::unknown_model_message(VEO_MODELS, &params.model))
         })?;
 
         // Determine mode: interpolation or standard I2V
@@ -706,14 +1071,11 @@ impl VideoHandler {
         }
 
         // Resolve the image input (first frame)
-        let image_data = self.resolve_image_input(&params.image).await?;
+        let image = self.resolve_image(&params.image).await?;
 
         // Resolve last frame if provided (interpolation mode)
         let last_frame = if let Some(ref last_frame_path) = params.last_frame_image {
-            let last_frame_data = self.resolve_image_input(last_frame_path).await?;
-            Some(VeoImageInput {
-                bytes_base64_encoded: last_frame_data,
-            })
+            Some(self.resolve_image(last_frame_path).await?)
         } else {
             None
         };
@@ -722,9 +1084,7 @@ impl VideoHandler {
         let request = VeoI2vRequest {
             instances: vec![VeoI2vInstance {
                 prompt: params.prompt.clone(),
-                image: VeoImageInput {
-                    bytes_base64_encoded: image_data,
-                },
+                image,
             }],
             parameters: VeoI2vParameters {
                 aspect_ratio: Some(params.aspect_ratio.clone()),
@@ -732,6 +1092,7 @@ impl VideoHandler {
                 duration_seconds: Some(params.duration_seconds),
                 generate_audio: None, // I2V doesn't support audio generation
                 seed: params.seed,
+                camera_control: params.camera_motion.as_deref().and_then(camera_control_value).map(String::from),
                 last_frame,
             },
         };
@@ -740,22 +1101,22 @@ impl VideoHandler {
         let token = self.auth.get_token(&["https://www.googleapis.com/auth/cloud-platform"]).await?;
 
         // Make API request to start LRO
-        let endpoint = self.get_generate_endpoint(model.id);
+        let endpoint = self.get_generate_endpoint(model.id, params.location.as_deref());
         debug!(endpoint = %endpoint, "Calling Veo API");
 
-        let response = self.http
-            .post(&endpoint)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| Error::api(&endpoint, 0, format!("Request failed: {}", e)))?;
+        let response = send_with_retry(&RetryPolicy::from_config(&self.config), &endpoint, || {
+            self.http
+                .post(&endpoint)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .json(&request)
+        })
+        .await?;
 
         let status = response.status();
         if !status.is_success() {
             let body = response.text().await.unwrap_or_default();
-            return Err(Error::api(&endpoint, status.as_u16(), body));
+            return Err(Error::api_response(&endpoint, status.as_u16(), body));
         }
 
         // Parse LRO response
@@ -766,10 +1127,24 @@ impl VideoHandler {
         info!(operation_name = %lro_response.name, "Started video generation LRO");
 
         // Poll for completion
-        let result = self.poll_lro(&lro_response.name, model.id).await?;
+        let result = self
+            .poll_lro(&lro_response.name, model.id, params.location.as_deref())
+            .await?;
 
         // Handle output
-        self.handle_output(result, &params.output_gcs_uri, params.download_local, params.local_path.as_deref()).await
+        let output = OutputOptions {
+            prompt: &params.prompt,
+            output_gcs_uri: &params.output_gcs_uri,
+            download_local: params.download_local,
+            local_path: params.local_path.as_deref(),
+            cleanup_gcs: params.cleanup_gcs,
+            metadata: params.write_metadata.then(|| GenerationMetadata {
+                seed: params.seed,
+                aspect_ratio: Some(params.aspect_ratio.clone()),
+                ..GenerationMetadata::new(&params.prompt, model.id)
+            }),
+        };
+        self.handle_output(result, &output, params.duration_seconds, params.seed).await
     }
 
     /// Extend an existing video.
@@ -781,7 +1156,10 @@ impl VideoHandler {
     /// * `Ok(VideoGenerateResult)` - Extended video with GCS URI and optional local path
     /// * `Err(Error)` - If validation fails, API call fails, or output handling fails
     #[instrument(level = "info", name = "extend_video", skip(self, params), fields(model = %params.model))]
-    pub async fn extend_video(&self, params: VideoExtendParams) -> Result<VideoGenerateResult, Error> {
+    pub async fn extend_video(&self, mut params: VideoExtendParams) -> Result<VideoGenerateResult, Error> {
+        // Bare object names go to the configured bucket
+        params.output_gcs_uri = self.config.resolve_output_uri(&params.output_gcs_uri)?;
+
         // Validate parameters
         params.validate().map_err(|errors| {
             let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
@@ -790,7 +1168,7 @@ impl VideoHandler {
 
         // Resolve the model to get the canonical ID
         let model = params.get_model().ok_or_else(|| {
-            Error::validation(format!("Unknown model: {}", params.model))
+            Error::validation(ModelRegistry::unknown_model_message(VEO_MODELS, &params.model))
         })?;
 
         info!(model_id = model.id, "Extending video with Veo API");
@@ -815,22 +1193,22 @@ impl VideoHandler {
         let token = self.auth.get_token(&["https://www.googleapis.com/auth/cloud-platform"]).await?;
 
         // Make API request to start LRO
-        let endpoint = self.get_generate_endpoint(model.id);
+        let endpoint = self.get_generate_endpoint(model.id, params.location.as_deref());
         debug!(endpoint = %endpoint, "Calling Veo API for video extension");
 
-        let response = self.http
-            .post(&endpoint)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| Error::api(&endpoint, 0, format!("Request failed: {}", e)))?;
+        let response = send_with_retry(&RetryPolicy::from_config(&self.config), &endpoint, || {
+            self.http
+                .post(&endpoint)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .json(&request)
+        })
+        .await?;
 
         let status = response.status();
         if !status.is_success() {
             let body = response.text().await.unwrap_or_default();
-            return Err(Error::api(&endpoint, status.as_u16(), body));
+            return Err(Error::api_response(&endpoint, status.as_u16(), body));
         }
 
         // Parse LRO response
@@ -841,135 +1219,97 @@ impl VideoHandler {
         info!(operation_name = %lro_response.name, "Started video extension LRO");
 
         // Poll for completion
-        let result = self.poll_lro(&lro_response.name, model.id).await?;
+        let result = self
+            .poll_lro(&lro_response.name, model.id, params.location.as_deref())
+            .await?;
 
         // Handle output
-        self.handle_output(result, &params.output_gcs_uri, params.download_local, params.local_path.as_deref()).await
+        let output = OutputOptions {
+            prompt: &params.prompt,
+            output_gcs_uri: &params.output_gcs_uri,
+            download_local: params.download_local,
+            local_path: params.local_path.as_deref(),
+            cleanup_gcs: params.cleanup_gcs,
+            metadata: params.write_metadata.then(|| GenerationMetadata {
+                seed: params.seed,
+                ..GenerationMetadata::new(&params.prompt, model.id)
+            }),
+        };
+        self.handle_output(result, &output, params.duration_seconds, params.seed).await
     }
 
-    /// Resolve image input to base64 data.
-    ///
-    /// Handles three input formats:
-    /// - Base64 data (already encoded)
-    /// - Local file path
-    /// - GCS URI
-    async fn resolve_image_input(&self, image: &str) -> Result<String, Error> {
-        // Check if it's a GCS URI first (explicit protocol)
-        if image.starts_with("gs://") {
-            let uri = GcsUri::parse(image)?;
-            let data = self.gcs.download(&uri).await?;
-            return Ok(BASE64.encode(&data));
-        }
-
-        // Check if it looks like a file path:
-        // - Starts with / (absolute path)
-        // - Starts with ./ or ../ (relative path)
-        // - Contains common path patterns like file extensions with preceding path separators
-        // - Is short enough to be a reasonable path (base64 images are typically very long)
-        let looks_like_path = image.starts_with('/')
-            || image.starts_with("./")
-            || image.starts_with("../")
-            || image.starts_with("~/")
-            || (image.len() < 500 && image.contains('/') && Self::has_file_extension(image));
-
-        if looks_like_path {
-            // Treat as local file path
-            let path = Path::new(image);
-            if !path.exists() {
-                return Err(Error::validation(format!("Image file not found: {}", image)));
-            }
-            let data = tokio::fs::read(path).await?;
-            return Ok(BASE64.encode(&data));
-        }
-
-        // Try to validate as base64 - if it decodes successfully, it's base64
-        // This handles the case where base64 contains '/' characters
-        if image.len() > 100 {
-            if BASE64.decode(image).is_ok() {
-                return Ok(image.to_string());
-            }
-        }
-
-        // Last resort: try as file path (might be a relative path without ./)
-        let path = Path::new(image);
-        if path.exists() {
-            let data = tokio::fs::read(path).await?;
-            return Ok(BASE64.encode(&data));
-        }
-
-        // If nothing worked and it's long, assume it's base64 (might be malformed)
-        if image.len() > 100 {
-            return Ok(image.to_string());
-        }
-
-        Err(Error::validation(format!(
-            "Image input '{}' is not a valid file path, GCS URI, or base64 data",
-            if image.len() > 50 { &image[..50] } else { image }
-        )))
+    /// Resolve image input (base64, data URI, local path, GCS URI, or HTTPS URL)
+    /// to a Veo image, keeping the MIME type a data URI declares.
+    async fn resolve_image(&self, image: &str) -> Result<VeoImageInput, Error> {
+        media_input::resolve_image(image, &self.gcs, &self.http)
+            .await
+            .map(VeoImageInput::from)
     }
 
-    /// Check if a string ends with a common image file extension.
-    fn has_file_extension(s: &str) -> bool {
-        let lower = s.to_lowercase();
-        lower.ends_with(".png")
-            || lower.ends_with(".jpg")
-            || lower.ends_with(".jpeg")
-            || lower.ends_with(".gif")
-            || lower.ends_with(".webp")
-            || lower.ends_with(".bmp")
-            || lower.ends_with(".tiff")
-            || lower.ends_with(".tif")
+    /// Derive a default local file name from a GCS object name.
+    ///
+    /// Uses the last non-empty path segment of the object name, falling back to
+    /// `output.mp4` when there is none and appending `.mp4` when the segment has
+    /// no extension. The file is placed in [`Config::download_dir`].
+    pub fn default_local_file_name(object: &str) -> String {
+        let name = object
+            .rsplit('/')
+            .find(|segment| !segment.is_empty())
+            .unwrap_or("output.mp4");
+
+        if Path::new(name).extension().is_some() {
+            name.to_string()
+        } else {
+            format!("{}.mp4", name)
+        }
     }
 
     /// Poll a long-running operation until completion.
     ///
     /// Uses exponential backoff with configurable parameters.
     /// Uses the fetchPredictOperation endpoint which requires the operation name in the request body.
-    pub async fn poll_lro(&self, operation_name: &str, model: &str) -> Result<LroResult, Error> {
+    /// `location` must match the region the operation was started in.
+    ///
+    /// Polling stops at [`VideoHandler::lro_timeout`] after the first call,
+    /// however the backoff has accumulated: the last wait is shortened to end
+    /// at the deadline, and each poll request is abandoned if it is still
+    /// running then. [`LRO_MAX_ATTEMPTS`] remains as a secondary cap. The
+    /// timeout error reports the time actually spent polling.
+    pub async fn poll_lro(&self, operation_name: &str, model: &str, location: Option<&str>) -> Result<LroResult, Error> {
+        let started = Instant::now();
+        let deadline = started + self.lro_timeout;
         let mut delay_ms = LRO_INITIAL_DELAY_MS;
         let mut attempts = 0;
 
         loop {
             attempts += 1;
-            if attempts > LRO_MAX_ATTEMPTS {
-                // Calculate approximate timeout in seconds
-                let timeout_seconds = (LRO_MAX_ATTEMPTS as u64) * (LRO_MAX_DELAY_MS / 1000);
-                return Err(Error::timeout(timeout_seconds));
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || attempts > LRO_MAX_ATTEMPTS {
+                let elapsed = started.elapsed();
+                warn!(
+                    operation_name = %operation_name,
+                    attempts = attempts - 1,
+                    elapsed_secs = elapsed.as_secs(),
+                    "LRO polling timed out"
+                );
+                return Err(Error::timeout(elapsed.as_secs()));
             }
 
-            // Wait before polling
-            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
-
-            // Get auth token
-            let token = self.auth.get_token(&["https://www.googleapis.com/auth/cloud-platform"]).await?;
+            // Wait before polling, but never past the deadline
+            tokio::time::sleep(Duration::from_millis(delay_ms).min(remaining)).await;
 
             // Poll the operation using fetchPredictOperation
-            let endpoint = self.get_fetch_operation_endpoint(model);
+            let endpoint = self.get_fetch_operation_endpoint(model, location);
             debug!(endpoint = %endpoint, attempt = attempts, "Polling LRO");
 
-            // Build the fetch request with operation name in body
-            let fetch_request = FetchOperationRequest {
-                operation_name: operation_name.to_string(),
+            // A hung request must not outlast the deadline either; once it
+            // passes, the check above reports the timeout
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let Ok(lro_status) = tokio::time::timeout(remaining, self.fetch_operation(&endpoint, operation_name)).await
+            else {
+                continue;
             };
-
-            let response = self.http
-                .post(&endpoint)
-                .header("Authorization", format!("Bearer {}", token))
-                .header("Content-Type", "application/json")
-                .json(&fetch_request)
-                .send()
-                .await
-                .map_err(|e| Error::api(&endpoint, 0, format!("Poll request failed: {}", e)))?;
-
-            let status = response.status();
-            if !status.is_success() {
-                let body = response.text().await.unwrap_or_default();
-                return Err(Error::api(&endpoint, status.as_u16(), body));
-            }
-
-            let lro_status: LroStatusResponse = response.json().await.map_err(|e| {
-                Error::api(&endpoint, status.as_u16(), format!("Failed to parse LRO status: {}", e))
-            })?;
+            let lro_status = lro_status?;
 
             if lro_status.done.unwrap_or(false) {
                 // Check for error
@@ -986,6 +1326,8 @@ impl VideoHandler {
                     info!(operation_name = %operation_name, attempts = attempts, "LRO completed successfully");
                     return Ok(LroResult {
                         videos: response.videos.unwrap_or_default(),
+                        operation_name: operation_name.to_string(),
+                        model: model.to_string(),
                     });
                 }
 
@@ -1005,13 +1347,80 @@ impl VideoHandler {
         }
     }
 
+    /// Fetch the current status of `operation_name` from `endpoint`.
+    async fn fetch_operation(&self, endpoint: &str, operation_name: &str) -> Result<LroStatusResponse, Error> {
+        let token = self.auth.get_token(&["https://www.googleapis.com/auth/cloud-platform"]).await?;
+
+        // Build the fetch request with operation name in body
+        let fetch_request = FetchOperationRequest {
+            operation_name: operation_name.to_string(),
+        };
+
+        let response = self.http
+            .post(endpoint)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .json(&fetch_request)
+            .send()
+            .await
+            .map_err(|e| Error::api(endpoint, 0, format!("Poll request failed: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::api_response(endpoint, status.as_u16(), body));
+        }
+
+        response.json().await.map_err(|e| {
+            Error::api(endpoint, status.as_u16(), format!("Failed to parse LRO status: {}", e))
+        })
+    }
+
     /// Handle output of generated video.
+    /// Download a generated video to `local_file`, retrying transient failures.
+    ///
+    /// Generation has already succeeded at this point, so a flaky download is
+    /// retried with the configured API backoff instead of losing the result.
+    /// Each attempt truncates the file and streams the object straight to disk
+    /// so large videos are never buffered in memory.
+    async fn download_video(&self, uri: &GcsUri, local_file: &str) -> Result<u64, Error> {
+        let policy = RetryPolicy::from_config(&self.config);
+        let result = retry_with_backoff(
+            &policy,
+            "video download",
+            |attempt| async move {
+                if attempt > 1 {
+                    warn!(
+                        gcs_uri = %uri,
+                        attempt,
+                        "Video generation succeeded and is available in GCS; retrying local download"
+                    );
+                }
+                let mut file = tokio::fs::File::create(local_file).await?;
+                Ok(self.gcs.download_to_writer(uri, &mut file).await?)
+            },
+            |e: &Error| matches!(e, Error::Gcs(gcs) if gcs.is_transient()),
+        )
+        .await;
+
+        // Tell the caller the video itself is safe; only the local copy failed
+        result.map_err(|e| match e {
+            Error::Gcs(GcsError::OperationFailed { uri, message, .. }) => GcsError::operation_failed(
+                uri,
+                GcsOperation::Download,
+                format!("{} (generation succeeded; the video is still available at this URI)", message),
+            )
+            .into(),
+            other => other,
+        })
+    }
+
     async fn handle_output(
         &self,
         result: LroResult,
-        output_gcs_uri: &str,
-        download_local: bool,
-        local_path: Option<&str>,
+        options: &OutputOptions<'_>,
+        duration_seconds: u8,
+        seed: Option<i64>,
     ) -> Result<VideoGenerateResult, Error> {
         // Get the first generated video
         let video = result.videos.first().ok_or_else(|| {
@@ -1019,39 +1428,125 @@ impl VideoHandler {
         })?;
 
         let gcs_uri = video.gcs_uri.clone()
-            .unwrap_or_else(|| output_gcs_uri.to_string());
+            .unwrap_or_else(|| options.output_gcs_uri.to_string());
 
         info!(gcs_uri = %gcs_uri, "Video generated successfully");
 
+        let mut output = VideoGenerateResult {
+            gcs_uri,
+            local_path: None,
+            mime_type: video.mime_type.clone().unwrap_or_else(|| "video/mp4".to_string()),
+            model: result.model.clone(),
+            duration_seconds,
+            seed_used: seed,
+            operation_name: result.operation_name.clone(),
+            gcs_deleted: false,
+            warning: None,
+        };
+
         // If download_local is requested, download the video
-        if download_local {
-            let local_file = if let Some(path) = local_path {
-                path.to_string()
-            } else {
-                // Generate a default local path from the GCS URI
-                let uri = GcsUri::parse(&gcs_uri)?;
-                format!("./{}", uri.object.split('/').last().unwrap_or("output.mp4"))
+        if options.download_local {
+            let uri = GcsUri::parse(&output.gcs_uri)?;
+            let local_file = match options.local_path {
+                Some(path) => self.config.resolve_local_path(path),
+                None => self.config.download_dir().join(Self::default_local_file_name(&uri.object)),
+            };
+            let local_file = local_file.to_string_lossy().into_owned();
+
+            // Reject directories and make sure the parent exists before downloading
+            let path = Path::new(&local_file);
+            if path.is_dir() {
+                return Err(Error::validation(format!(
+                    "local_path '{}' is a directory; provide a file path",
+                    local_file
+                )));
+            }
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+
+            let bytes = match self.download_video(&uri, &local_file).await {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    let _ = tokio::fs::remove_file(&local_file).await;
+                    return Err(e);
+                }
             };
 
-            let uri = GcsUri::parse(&gcs_uri)?;
-            let data = self.gcs.download(&uri).await?;
-            tokio::fs::write(&local_file, &data).await?;
+            info!(local_file = %local_file, bytes, "Video downloaded locally");
 
-            info!(local_file = %local_file, "Video downloaded locally");
+            output.local_path = Some(local_file);
 
-            return Ok(VideoGenerateResult {
-                gcs_uri,
-                local_path: Some(local_file),
-            });
+            // Local-only mode: a failed delete leaves the object behind but the
+            // local copy is already safe, so report it instead of failing
+            if options.cleanup_gcs {
+                match self.gcs.delete(&uri).await {
+                    Ok(()) => {
+                        info!(gcs_uri = %output.gcs_uri, "Deleted GCS copy after local download");
+                        output.gcs_deleted = true;
+                    }
+                    Err(e) => {
+                        warn!(gcs_uri = %output.gcs_uri, error = %e, "Failed to delete GCS copy");
+                        output.warning = Some(format!("Failed to delete GCS copy: {}", e));
+                    }
+                }
+            }
         }
 
-        Ok(VideoGenerateResult {
-            gcs_uri,
-            local_path: None,
-        })
+        if let Some(metadata) = &options.metadata {
+            let metadata = GenerationMetadata {
+                operation_name: Some(result.operation_name.clone()),
+                ..metadata.clone()
+            };
+            let gcs_copy = (!output.gcs_deleted).then_some(&output.gcs_uri);
+            for location in output.local_path.iter().chain(gcs_copy) {
+                match metadata::write_sidecar(&self.gcs, location, &metadata).await {
+                    Ok(sidecar) => debug!(sidecar = %sidecar, "Wrote generation metadata"),
+                    Err(e) => warn!(output = %location, error = %e, "Failed to write generation metadata"),
+                }
+            }
+        }
+
+        // Register every returned video so clients can list what this session produced
+        self.registry.register(
+            output.gcs_uri.clone(),
+            output.local_path.clone(),
+            output.mime_type.clone(),
+            options.prompt,
+            &result.model,
+        );
+        for extra in result.videos.iter().skip(1) {
+            if let Some(uri) = &extra.gcs_uri {
+                self.registry.register(
+                    uri.clone(),
+                    None,
+                    extra.mime_type.clone().unwrap_or_else(|| "video/mp4".to_string()),
+                    options.prompt,
+                    &result.model,
+                );
+            }
+        }
+
+        Ok(output)
     }
 }
 
+/// Output handling options shared by all generation modes.
+struct OutputOptions<'a> {
+    /// Prompt used for generation (recorded in the session registry)
+    prompt: &'a str,
+    /// Requested GCS output URI (used if the API doesn't return one)
+    output_gcs_uri: &'a str,
+    /// Whether to download the video locally
+    download_local: bool,
+    /// Local path override for the download
+    local_path: Option<&'a str>,
+    /// Whether to delete the GCS copy after downloading
+    cleanup_gcs: bool,
+    /// Metadata to write as sidecars next to the outputs (write_metadata)
+    metadata: Option<GenerationMetadata>,
+}
+
 // =============================================================================
 // API Request/Response Types
 // =============================================================================
@@ -1096,6 +1591,18 @@ pub struct VeoI2vInstance {
 pub struct VeoImageInput {
     /// Base64-encoded image data
     pub bytes_base64_encoded: String,
+    /// MIME type of the image, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+impl From<ResolvedImage> for VeoImageInput {
+    fn from(image: ResolvedImage) -> Self {
+        Self {
+            bytes_base64_encoded: image.data,
+            mime_type: image.mime_type,
+        }
+    }
 }
 
 /// Veo API parameters for I2V (includes last_frame for interpolation).
@@ -1120,6 +1627,9 @@ pub struct VeoI2vParameters {
     /// Last frame for interpolation mode
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_frame: Option<VeoImageInput>,
+    /// Camera control value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub camera_control: Option<String>,
 }
 
 /// Vertex AI Veo API request for video extension.
@@ -1184,6 +1694,9 @@ pub struct VeoParameters {
     /// Random seed for reproducibility
     #[serde(skip_serializing_if = "Option::is_none")]
     pub seed: Option<i64>,
+    /// Camera control value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub camera_control: Option<String>,
 }
 
 /// Long-running operation response.
@@ -1251,21 +1764,81 @@ pub struct VideoOutput {
 pub struct LroResult {
     /// Generated videos
     pub videos: Vec<VideoOutput>,
+    /// Name of the completed operation
+    pub operation_name: String,
+    /// Model that produced the videos
+    pub model: String,
 }
 
 /// Result of video generation.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct VideoGenerateResult {
     /// GCS URI of the generated video
     pub gcs_uri: String,
     /// Local file path if downloaded
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub local_path: Option<String>,
+    /// MIME type of the video
+    pub mime_type: String,
+    /// Canonical ID of the model used
+    pub model: String,
+    /// Duration of the generated video in seconds
+    pub duration_seconds: u8,
+    /// Seed passed to the API, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed_used: Option<i64>,
+    /// Name of the long-running operation that produced the video
+    pub operation_name: String,
+    /// Whether the GCS copy was deleted after downloading (cleanup_gcs)
+    pub gcs_deleted: bool,
+    /// Non-fatal issue encountered while handling the output
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+/// Outcome of one shot in a batch.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BatchShotResult {
+    /// Position of the shot in the request
+    pub index: usize,
+    /// Prompt of the shot
+    pub prompt: String,
+    /// Generated video, if the shot succeeded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub video: Option<VideoGenerateResult>,
+    /// Error message, if the shot failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of batch video generation.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct VideoBatchResult {
+    /// Per-shot outcomes, in request order
+    pub shots: Vec<BatchShotResult>,
+    /// Number of shots that produced a video
+    pub succeeded: usize,
+    /// Number of shots that failed
+    pub failed: usize,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_handler_shares_one_auth_provider() {
+        let config = Config {
+            project_id: "test-project".to_string(),
+            ..Default::default()
+        };
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        let handler = VideoHandler::with_auth(config, Arc::clone(&auth)).unwrap();
+
+        assert!(Arc::ptr_eq(&handler.auth, &auth));
+        assert!(Arc::ptr_eq(handler.gcs.auth(), &auth));
+    }
+
     #[test]
     fn test_default_t2v_params() {
         let params: VideoT2vParams = serde_json::from_str(r#"{
@@ -1290,8 +1863,12 @@ mod tests {
             output_gcs_uri: "gs://bucket/output.mp4".to_string(),
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             generate_audio: Some(true),
             seed: Some(42),
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
 
         assert!(params.validate().is_ok());
@@ -1307,8 +1884,12 @@ mod tests {
             output_gcs_uri: "gs://bucket/output.mp4".to_string(),
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             generate_audio: None,
             seed: None,
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
 
         let result = params.validate();
@@ -1327,8 +1908,12 @@ mod tests {
             output_gcs_uri: "gs://bucket/output.mp4".to_string(),
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             generate_audio: None,
             seed: None,
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
 
         let result = params.validate();
@@ -1347,8 +1932,12 @@ mod tests {
             output_gcs_uri: "gs://bucket/output.mp4".to_string(),
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             generate_audio: None,
             seed: None,
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
 
         let result = params.validate();
@@ -1367,8 +1956,12 @@ mod tests {
             output_gcs_uri: "gs://bucket/output.mp4".to_string(),
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             generate_audio: None,
             seed: None,
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
 
         let result = params.validate();
@@ -1377,6 +1970,29 @@ mod tests {
         assert!(errors.iter().any(|e| e.field == "model"));
     }
 
+    #[test]
+    fn test_misspelled_model_suggests_closest() {
+        let params = VideoT2vParams {
+            prompt: "A cat".to_string(),
+            model: "veo-3.1".to_string(),
+            aspect_ratio: "16:9".to_string(),
+            duration_seconds: 6,
+            output_gcs_uri: "gs://bucket/output.mp4".to_string(),
+            download_local: false,
+            local_path: None,
+            cleanup_gcs: false,
+            generate_audio: None,
+            seed: None,
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
+        };
+
+        let errors = params.validate().unwrap_err();
+        let model_error = errors.iter().find(|e| e.field == "model").unwrap();
+        assert!(model_error.message.contains("did you mean 'veo-3.0-generate-preview'?"));
+    }
+
     #[test]
     fn test_empty_prompt() {
         let params = VideoT2vParams {
@@ -1387,8 +2003,12 @@ mod tests {
             output_gcs_uri: "gs://bucket/output.mp4".to_string(),
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             generate_audio: None,
             seed: None,
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
 
         let result = params.validate();
@@ -1407,8 +2027,12 @@ mod tests {
             output_gcs_uri: "/local/path/output.mp4".to_string(), // Not a GCS URI
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             generate_audio: None,
             seed: None,
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
 
         let result = params.validate();
@@ -1427,8 +2051,12 @@ mod tests {
             output_gcs_uri: "gs://bucket/output.mp4".to_string(),
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             generate_audio: Some(true), // Should fail
             seed: None,
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
 
         let result = params.validate();
@@ -1447,8 +2075,12 @@ mod tests {
             output_gcs_uri: "gs://bucket/output.mp4".to_string(),
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             generate_audio: Some(true),
             seed: None,
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
 
         assert!(params.validate().is_ok());
@@ -1465,8 +2097,12 @@ mod tests {
                 output_gcs_uri: "gs://bucket/output.mp4".to_string(),
                 download_local: false,
                 local_path: None,
+                cleanup_gcs: false,
                 generate_audio: None,
                 seed: None,
+                camera_motion: None,
+                location: None,
+                write_metadata: false,
             };
             assert!(params.validate().is_ok(), "Aspect ratio {} should be valid", ratio);
         }
@@ -1483,8 +2119,12 @@ mod tests {
                 output_gcs_uri: "gs://bucket/output.mp4".to_string(),
                 download_local: false,
                 local_path: None,
+                cleanup_gcs: false,
                 generate_audio: None,
                 seed: None,
+                camera_motion: None,
+                location: None,
+                write_metadata: false,
             };
             assert!(params.validate().is_ok(), "Duration {} should be valid", dur);
         }
@@ -1500,8 +2140,12 @@ mod tests {
             output_gcs_uri: "gs://bucket/output.mp4".to_string(),
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             generate_audio: None,
             seed: None,
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
 
         let model = params.get_model();
@@ -1535,7 +2179,11 @@ mod tests {
             output_gcs_uri: "gs://bucket/output.mp4".to_string(),
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             seed: Some(42),
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
 
         assert!(params.validate().is_ok());
@@ -1553,7 +2201,11 @@ mod tests {
             output_gcs_uri: "gs://bucket/output.mp4".to_string(),
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             seed: None,
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
 
         let result = params.validate();
@@ -1583,8 +2235,12 @@ mod tests {
             output_gcs_uri: "/local/path".to_string(), // Invalid GCS URI
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             generate_audio: None,
             seed: None,
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
 
         let result = params.validate();
@@ -1594,40 +2250,205 @@ mod tests {
         assert!(errors.len() >= 3, "Expected at least 3 validation errors, got {}", errors.len());
     }
 
-    // Tests for base64 detection (P2 fix)
     #[test]
-    fn test_has_file_extension_png() {
-        assert!(VideoHandler::has_file_extension("image.png"));
-        assert!(VideoHandler::has_file_extension("path/to/image.PNG"));
+    fn test_camera_motion_on_veo3_succeeds() {
+        let params: VideoT2vParams = serde_json::from_str(r#"{
+            "prompt": "A drone shot over a canyon",
+            "model": "veo-3",
+            "output_gcs_uri": "gs://bucket/output.mp4",
+            "camera_motion": "dolly_in"
+        }"#).unwrap();
+
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_camera_motion() {
+        let params: VideoT2vParams = serde_json::from_str(r#"{
+            "prompt": "A drone shot over a canyon",
+            "model": "veo-3",
+            "output_gcs_uri": "gs://bucket/output.mp4",
+            "camera_motion": "barrel_roll"
+        }"#).unwrap();
+
+        let errors = params.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "camera_motion" && e.message.contains("pan_left")));
+    }
+
+    #[test]
+    fn test_camera_motion_on_veo2_fails() {
+        let params: VideoI2vParams = serde_json::from_str(r#"{
+            "image": "/path/to/image.png",
+            "prompt": "The camera pans across the scene",
+            "model": "veo-2",
+            "output_gcs_uri": "gs://bucket/output.mp4",
+            "camera_motion": "pan_left"
+        }"#).unwrap();
+
+        let errors = params.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "camera_motion" && e.message.contains("veo-2.0-generate-001")));
+    }
+
+    #[test]
+    fn test_cleanup_gcs_requires_download_local() {
+        let params: VideoT2vParams = serde_json::from_str(r#"{
+            "prompt": "A cat walking",
+            "output_gcs_uri": "gs://bucket/output.mp4",
+            "cleanup_gcs": true
+        }"#).unwrap();
+
+        let errors = params.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "cleanup_gcs"));
+    }
+
+    #[test]
+    fn test_cleanup_gcs_with_download_local() {
+        let params: VideoExtendParams = serde_json::from_str(r#"{
+            "video_input": "gs://bucket/input.mp4",
+            "prompt": "Continue the scene",
+            "output_gcs_uri": "gs://bucket/output.mp4",
+            "download_local": true,
+            "cleanup_gcs": true
+        }"#).unwrap();
+
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn test_camera_control_value() {
+        assert_eq!(camera_control_value("static"), Some("FIXED"));
+        assert_eq!(camera_control_value("dolly_out"), Some("PULL_OUT"));
+        assert_eq!(camera_control_value("sideways"), None);
+    }
+
+    #[test]
+    fn test_default_local_file_name_nested_object() {
+        assert_eq!(VideoHandler::default_local_file_name("videos/2024/clip.mp4"), "clip.mp4");
+    }
+
+    #[test]
+    fn test_default_local_file_name_no_slash() {
+        assert_eq!(VideoHandler::default_local_file_name("clip.mp4"), "clip.mp4");
+    }
+
+    #[test]
+    fn test_default_local_file_name_no_extension() {
+        assert_eq!(VideoHandler::default_local_file_name("videos/sample_0"), "sample_0.mp4");
+        assert_eq!(VideoHandler::default_local_file_name("clip"), "clip.mp4");
+    }
+
+    #[test]
+    fn test_default_local_file_name_trailing_slash() {
+        assert_eq!(VideoHandler::default_local_file_name("videos/run-1/"), "run-1.mp4");
+    }
+
+    #[test]
+    fn test_default_local_file_name_empty_object() {
+        assert_eq!(VideoHandler::default_local_file_name(""), "output.mp4");
+        assert_eq!(VideoHandler::default_local_file_name("///"), "output.mp4");
+    }
+
+    fn batch_params(shots: serde_json::Value) -> VideoBatchParams {
+        serde_json::from_value(serde_json::json!({ "shots": shots })).unwrap()
     }
 
     #[test]
-    fn test_has_file_extension_jpg() {
-        assert!(VideoHandler::has_file_extension("photo.jpg"));
-        assert!(VideoHandler::has_file_extension("photo.jpeg"));
-        assert!(VideoHandler::has_file_extension("photo.JPEG"));
+    fn test_valid_batch_params() {
+        let params = batch_params(serde_json::json!([
+            {"prompt": "Opening shot", "output_gcs_uri": "gs://bucket/shot-1.mp4"},
+            {"prompt": "Closing shot", "output_gcs_uri": "gs://bucket/shot-2.mp4"}
+        ]));
+
+        assert_eq!(params.max_concurrency, DEFAULT_BATCH_CONCURRENCY);
+        assert!(params.validate().is_ok());
     }
 
     #[test]
-    fn test_has_file_extension_other_formats() {
-        assert!(VideoHandler::has_file_extension("image.gif"));
-        assert!(VideoHandler::has_file_extension("image.webp"));
-        assert!(VideoHandler::has_file_extension("image.bmp"));
-        assert!(VideoHandler::has_file_extension("image.tiff"));
-        assert!(VideoHandler::has_file_extension("image.tif"));
+    fn test_batch_shot_errors_carry_index() {
+        let params = batch_params(serde_json::json!([
+            {"prompt": "Opening shot", "output_gcs_uri": "gs://bucket/shot-1.mp4"},
+            {"prompt": "  ", "output_gcs_uri": "gs://bucket/shot-2.mp4", "duration_seconds": 5}
+        ]));
+
+        let errors = params.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "shots[1].prompt"));
+        assert!(errors.iter().any(|e| e.field == "shots[1].duration_seconds"));
+        assert!(!errors.iter().any(|e| e.field.starts_with("shots[0]")));
     }
 
     #[test]
-    fn test_has_file_extension_no_extension() {
-        assert!(!VideoHandler::has_file_extension("noextension"));
-        assert!(!VideoHandler::has_file_extension("path/to/file"));
+    fn test_batch_rejects_duplicate_output_uris() {
+        let params = batch_params(serde_json::json!([
+            {"prompt": "Take one", "output_gcs_uri": "gs://bucket/shot.mp4"},
+            {"prompt": "Take two", "output_gcs_uri": "gs://bucket/shot.mp4"}
+        ]));
+
+        let errors = params.validate().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "shots[1].output_gcs_uri");
+        assert!(errors[0].message.contains("shots[0]"));
     }
 
     #[test]
-    fn test_has_file_extension_wrong_extension() {
-        assert!(!VideoHandler::has_file_extension("file.txt"));
-        assert!(!VideoHandler::has_file_extension("file.mp4"));
-        assert!(!VideoHandler::has_file_extension("file.pdf"));
+    fn test_batch_size_and_concurrency_bounds() {
+        let empty = batch_params(serde_json::json!([]));
+        assert!(empty.validate().unwrap_err().iter().any(|e| e.field == "shots"));
+
+        let shots: Vec<_> = (0..=MAX_BATCH_SHOTS)
+            .map(|i| serde_json::json!({"prompt": "Shot", "output_gcs_uri": format!("gs://bucket/{}.mp4", i)}))
+            .collect();
+        let too_many = batch_params(serde_json::Value::Array(shots));
+        assert!(too_many.validate().unwrap_err().iter().any(|e| e.field == "shots"));
+
+        let mut params = batch_params(serde_json::json!([
+            {"prompt": "Shot", "output_gcs_uri": "gs://bucket/shot.mp4"}
+        ]));
+        for concurrency in [0, MAX_BATCH_CONCURRENCY + 1] {
+            params.max_concurrency = concurrency;
+            assert!(params.validate().unwrap_err().iter().any(|e| e.field == "max_concurrency"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_concurrently_bounds_in_flight_tasks() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let outcomes = run_concurrently((0..6).collect(), 2, |_, item: u32| {
+            let (in_flight, peak) = (&in_flight, &peak);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok(item * 10)
+            }
+        })
+        .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        let values: Vec<u32> = outcomes.into_iter().map(Result::unwrap).collect();
+        assert_eq!(values, vec![0, 10, 20, 30, 40, 50]);
+    }
+
+    #[tokio::test]
+    async fn test_run_concurrently_keeps_partial_results() {
+        // Later items finish first; failures must not cancel the rest
+        let outcomes = run_concurrently(vec![30u64, 20, 10], 3, |index, delay| async move {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            if index == 1 {
+                Err(Error::validation("shot failed"))
+            } else {
+                Ok(index)
+            }
+        })
+        .await;
+
+        assert_eq!(outcomes.len(), 3);
+        assert_eq!(outcomes[0].as_ref().unwrap(), &0);
+        assert!(outcomes[1].as_ref().unwrap_err().to_string().contains("shot failed"));
+        assert_eq!(outcomes[2].as_ref().unwrap(), &2);
     }
 }
 
@@ -1701,8 +2522,12 @@ mod property_tests {
                 output_gcs_uri: gcs_uri,
                 download_local: false,
                 local_path: None,
+                cleanup_gcs: false,
                 generate_audio: None,
                 seed: None,
+                camera_motion: None,
+                location: None,
+                write_metadata: false,
             };
 
             let result = params.validate();
@@ -1729,8 +2554,12 @@ mod property_tests {
                 output_gcs_uri: gcs_uri,
                 download_local: false,
                 local_path: None,
+                cleanup_gcs: false,
                 generate_audio: None,
                 seed: None,
+                camera_motion: None,
+                location: None,
+                write_metadata: false,
             };
 
             let result = params.validate();
@@ -1796,8 +2625,12 @@ mod property_tests {
                 output_gcs_uri: "gs://bucket/output.mp4".to_string(),
                 download_local: true,
                 local_path: Some("/tmp/video.mp4".to_string()),
+                cleanup_gcs: false,
                 generate_audio: None, // Veo 2 doesn't support audio
                 seed: Some(42),
+                camera_motion: None,
+                location: None,
+                write_metadata: false,
             };
 
             // Verify explicit values are preserved
@@ -1825,8 +2658,12 @@ mod property_tests {
                 output_gcs_uri: gcs_uri,
                 download_local: false,
                 local_path: None,
+                cleanup_gcs: false,
                 generate_audio: None,
                 seed: None,
+                camera_motion: None,
+                location: None,
+                write_metadata: false,
             };
 
             let result = params.validate();
@@ -1912,26 +2749,29 @@ mod lro_property_tests {
         /// Property 11: Total timeout is reasonable for video generation
         #[test]
         fn total_timeout_reasonable(_dummy in Just(())) {
-            // Calculate approximate total timeout
+            let total_minutes = LRO_DEFAULT_TIMEOUT.as_secs() / 60;
+
+            // Video generation can take several minutes, so timeout should be at least 10 minutes
+            prop_assert!(total_minutes >= 10,
+                "Total timeout {} minutes should be at least 10 minutes", total_minutes);
+
+            // But not more than 2 hours (reasonable upper bound)
+            prop_assert!(total_minutes <= 120,
+                "Total timeout {} minutes should not exceed 120 minutes", total_minutes);
+
+            // The attempt cap is only a secondary guard: the backoff it allows
+            // must outlast the deadline so the deadline decides the timeout
             let mut total_ms = 0u64;
             let mut delay_ms = LRO_INITIAL_DELAY_MS;
-            
             for _ in 0..LRO_MAX_ATTEMPTS {
                 total_ms += delay_ms;
                 delay_ms = ((delay_ms as f64) * LRO_BACKOFF_MULTIPLIER) as u64;
                 delay_ms = delay_ms.min(LRO_MAX_DELAY_MS);
             }
-            
-            let total_minutes = total_ms / 60000;
-            
-            // Video generation can take several minutes, so timeout should be at least 10 minutes
-            prop_assert!(total_minutes >= 10, 
-                "Total timeout {} minutes should be at least 10 minutes", total_minutes);
-            
-            // But not more than 2 hours (reasonable upper bound)
-            prop_assert!(total_minutes <= 120,
-                "Total timeout {} minutes should not exceed 120 minutes", total_minutes);
+            prop_assert!(Duration::from_millis(total_ms) > LRO_DEFAULT_TIMEOUT,
+                "Attempt cap ({} ms of backoff) should not end polling before the deadline", total_ms);
         }
+
     }
 }
 
@@ -1955,6 +2795,7 @@ mod api_tests {
                 duration_seconds: Some(6),
                 generate_audio: Some(true),
                 seed: Some(42),
+                camera_control: None,
             },
         };
 
@@ -1983,6 +2824,7 @@ mod api_tests {
                 duration_seconds: None,
                 generate_audio: None,
                 seed: None,
+                camera_control: None,
             },
         };
 
@@ -2005,6 +2847,7 @@ mod api_tests {
                 prompt: "The cat starts walking".to_string(),
                 image: VeoImageInput {
                     bytes_base64_encoded: "base64imagedata".to_string(),
+                    mime_type: None,
                 },
             }],
             parameters: VeoI2vParameters {
@@ -2013,6 +2856,7 @@ mod api_tests {
                 duration_seconds: Some(6),
                 generate_audio: None,
                 seed: None,
+                camera_control: None,
                 last_frame: None,
             },
         };
@@ -2023,9 +2867,92 @@ mod api_tests {
         assert!(json["instances"].is_array());
         assert_eq!(json["instances"][0]["prompt"], "The cat starts walking");
         assert_eq!(json["instances"][0]["image"]["bytesBase64Encoded"], "base64imagedata");
+        assert!(json["instances"][0]["image"].get("mimeType").is_none());
         assert_eq!(json["parameters"]["aspectRatio"], "9:16");
     }
 
+    /// Test that the MIME type of a data URI image is sent to Veo.
+    #[test]
+    fn test_veo_image_input_from_data_uri() {
+        let resolved = media_input::parse_data_uri("data:image/jpeg;base64,/9j/4AAQ").unwrap();
+        let image = VeoImageInput::from(resolved);
+
+        let json = serde_json::to_value(&image).unwrap();
+        assert_eq!(json, serde_json::json!({
+            "bytesBase64Encoded": "/9j/4AAQ",
+            "mimeType": "image/jpeg"
+        }));
+    }
+
+    /// Test the exact JSON emitted for a T2V request with camera control.
+    #[test]
+    fn test_veo_t2v_request_serialization_camera_control() {
+        let request = VeoT2vRequest {
+            instances: vec![VeoT2vInstance {
+                prompt: "A lighthouse at dusk".to_string(),
+            }],
+            parameters: VeoParameters {
+                aspect_ratio: Some("16:9".to_string()),
+                storage_uri: "gs://bucket/output.mp4".to_string(),
+                duration_seconds: Some(8),
+                generate_audio: None,
+                seed: None,
+                camera_control: camera_control_value("pan_right").map(String::from),
+            },
+        };
+
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(json, serde_json::json!({
+            "instances": [{ "prompt": "A lighthouse at dusk" }],
+            "parameters": {
+                "aspectRatio": "16:9",
+                "storageUri": "gs://bucket/output.mp4",
+                "durationSeconds": 8,
+                "cameraControl": "PAN_RIGHT"
+            }
+        }));
+    }
+
+    /// Test the exact JSON emitted for an I2V request with camera control.
+    #[test]
+    fn test_veo_i2v_request_serialization_camera_control() {
+        let request = VeoI2vRequest {
+            instances: vec![VeoI2vInstance {
+                prompt: "Slowly move closer".to_string(),
+                image: VeoImageInput {
+                    bytes_base64_encoded: "base64imagedata".to_string(),
+                    mime_type: None,
+                },
+            }],
+            parameters: VeoI2vParameters {
+                aspect_ratio: Some("9:16".to_string()),
+                storage_uri: "gs://bucket/output.mp4".to_string(),
+                duration_seconds: Some(6),
+                generate_audio: None,
+                seed: Some(7),
+                last_frame: None,
+                camera_control: camera_control_value("zoom_in").map(String::from),
+            },
+        };
+
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(json, serde_json::json!({
+            "instances": [{
+                "prompt": "Slowly move closer",
+                "image": { "bytesBase64Encoded": "base64imagedata" }
+            }],
+            "parameters": {
+                "aspectRatio": "9:16",
+                "storageUri": "gs://bucket/output.mp4",
+                "durationSeconds": 6,
+                "seed": 7,
+                "cameraControl": "ZOOM_IN"
+            }
+        }));
+    }
+
     /// Test that LroResponse deserializes correctly.
     #[test]
     fn test_lro_response_deserialization() {
@@ -2102,53 +3029,77 @@ mod api_tests {
         assert_eq!(error.message, Some("Invalid prompt".to_string()));
     }
 
-    /// Test endpoint URL construction for generate.
-    #[test]
-    fn test_get_generate_endpoint() {
+    fn endpoint_handler() -> VideoHandler {
         let config = Config {
             project_id: "my-project".to_string(),
-            location: "us-central1".to_string(),
-            gcs_bucket: None,
-            port: 8080,
+            ..Default::default()
         };
+        let auth = Arc::new(AuthProvider::mock("test-token"));
+        let gcs = GcsClient::with_auth(Arc::clone(&auth));
+        VideoHandler::with_deps(config, gcs, reqwest::Client::new(), auth)
+    }
 
-        let expected_url = format!(
-            "https://{}-aiplatform.googleapis.com/v1/projects/{}/locations/{}/publishers/google/models/{}:predictLongRunning",
-            config.location,
-            config.project_id,
-            config.location,
-            "veo-3.0-generate-preview"
-        );
+    /// Test endpoint URL construction for generate.
+    #[test]
+    fn test_get_generate_endpoint() {
+        let handler = endpoint_handler();
 
-        assert!(expected_url.contains("us-central1-aiplatform.googleapis.com"));
-        assert!(expected_url.contains("my-project"));
-        assert!(expected_url.contains("veo-3.0-generate-preview"));
-        assert!(expected_url.ends_with(":predictLongRunning"));
+        assert_eq!(
+            handler.get_generate_endpoint("veo-3.0-generate-preview", None),
+            "https://us-central1-aiplatform.googleapis.com/v1/projects/my-project/locations/us-central1/publishers/google/models/veo-3.0-generate-preview:predictLongRunning"
+        );
+        assert_eq!(
+            handler.get_generate_endpoint("veo-3.0-generate-preview", Some("europe-west4")),
+            "https://europe-west4-aiplatform.googleapis.com/v1/projects/my-project/locations/europe-west4/publishers/google/models/veo-3.0-generate-preview:predictLongRunning"
+        );
     }
 
     /// Test endpoint URL construction for fetch operation (LRO polling).
     #[test]
     fn test_get_fetch_operation_endpoint() {
-        let config = Config {
-            project_id: "my-project".to_string(),
-            location: "us-central1".to_string(),
-            gcs_bucket: None,
-            port: 8080,
-        };
+        let handler = endpoint_handler();
 
-        let model = "veo-3.0-generate-preview";
-        let expected_url = format!(
-            "https://{}-aiplatform.googleapis.com/v1/projects/{}/locations/{}/publishers/google/models/{}:fetchPredictOperation",
-            config.location,
-            config.project_id,
-            config.location,
-            model
+        assert_eq!(
+            handler.get_fetch_operation_endpoint("veo-3.0-generate-preview", None),
+            "https://us-central1-aiplatform.googleapis.com/v1/projects/my-project/locations/us-central1/publishers/google/models/veo-3.0-generate-preview:fetchPredictOperation"
+        );
+        assert_eq!(
+            handler.get_fetch_operation_endpoint("veo-3.0-generate-preview", Some("us-east4")),
+            "https://us-east4-aiplatform.googleapis.com/v1/projects/my-project/locations/us-east4/publishers/google/models/veo-3.0-generate-preview:fetchPredictOperation"
         );
+    }
 
-        assert!(expected_url.contains("us-central1-aiplatform.googleapis.com"));
-        assert!(expected_url.contains("my-project"));
-        assert!(expected_url.contains(model));
-        assert!(expected_url.ends_with(":fetchPredictOperation"));
+    /// Test that unknown location overrides are rejected for every video tool.
+    #[test]
+    fn test_location_validation() {
+        let mut t2v: VideoT2vParams = serde_json::from_value(serde_json::json!({
+            "prompt": "A cat",
+            "output_gcs_uri": "gs://bucket/out.mp4"
+        }))
+        .unwrap();
+        assert!(t2v.validate().is_ok());
+        t2v.location = Some("europe-west4".to_string());
+        assert!(t2v.validate().is_ok());
+        t2v.location = Some("mars-north1".to_string());
+        assert!(t2v.validate().unwrap_err().iter().any(|e| e.field == "location"));
+
+        let i2v: VideoI2vParams = serde_json::from_value(serde_json::json!({
+            "image": "gs://bucket/frame.png",
+            "prompt": "A cat",
+            "output_gcs_uri": "gs://bucket/out.mp4",
+            "location": "mars-north1"
+        }))
+        .unwrap();
+        assert!(i2v.validate().unwrap_err().iter().any(|e| e.field == "location"));
+
+        let extend: VideoExtendParams = serde_json::from_value(serde_json::json!({
+            "video_input": "gs://bucket/in.mp4",
+            "prompt": "A cat",
+            "output_gcs_uri": "gs://bucket/out.mp4",
+            "location": "mars-north1"
+        }))
+        .unwrap();
+        assert!(extend.validate().unwrap_err().iter().any(|e| e.field == "location"));
     }
 
     /// Test FetchOperationRequest serialization.
@@ -2169,6 +3120,13 @@ mod api_tests {
         let result = VideoGenerateResult {
             gcs_uri: "gs://bucket/output.mp4".to_string(),
             local_path: None,
+            mime_type: "video/mp4".to_string(),
+            model: "veo-3.0-generate-preview".to_string(),
+            duration_seconds: 8,
+            seed_used: None,
+            operation_name: "projects/p/locations/us-central1/operations/op1".to_string(),
+            gcs_deleted: false,
+            warning: None,
         };
 
         assert_eq!(result.gcs_uri, "gs://bucket/output.mp4");
@@ -2181,12 +3139,67 @@ mod api_tests {
         let result = VideoGenerateResult {
             gcs_uri: "gs://bucket/output.mp4".to_string(),
             local_path: Some("/tmp/output.mp4".to_string()),
+            mime_type: "video/mp4".to_string(),
+            model: "veo-3.0-generate-preview".to_string(),
+            duration_seconds: 8,
+            seed_used: Some(42),
+            operation_name: "projects/p/locations/us-central1/operations/op1".to_string(),
+            gcs_deleted: false,
+            warning: None,
         };
 
         assert_eq!(result.gcs_uri, "gs://bucket/output.mp4");
         assert_eq!(result.local_path, Some("/tmp/output.mp4".to_string()));
     }
 
+    /// Test VideoGenerateResult serializes all fields for structured output.
+    #[test]
+    fn test_video_generate_result_serialization() {
+        let result = VideoGenerateResult {
+            gcs_uri: "gs://bucket/output.mp4".to_string(),
+            local_path: Some("/tmp/output.mp4".to_string()),
+            mime_type: "video/mp4".to_string(),
+            model: "veo-2.0-generate-001".to_string(),
+            duration_seconds: 6,
+            seed_used: Some(42),
+            operation_name: "projects/p/locations/us-central1/operations/op1".to_string(),
+            gcs_deleted: false,
+            warning: None,
+        };
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json, serde_json::json!({
+            "gcs_uri": "gs://bucket/output.mp4",
+            "local_path": "/tmp/output.mp4",
+            "mime_type": "video/mp4",
+            "model": "veo-2.0-generate-001",
+            "duration_seconds": 6,
+            "seed_used": 42,
+            "operation_name": "projects/p/locations/us-central1/operations/op1",
+            "gcs_deleted": false
+        }));
+    }
+
+    /// Test VideoGenerateResult omits absent optional fields.
+    #[test]
+    fn test_video_generate_result_serialization_minimal() {
+        let result = VideoGenerateResult {
+            gcs_uri: "gs://bucket/output.mp4".to_string(),
+            local_path: None,
+            mime_type: "video/mp4".to_string(),
+            model: "veo-3.0-generate-preview".to_string(),
+            duration_seconds: 8,
+            seed_used: None,
+            operation_name: "op1".to_string(),
+            gcs_deleted: false,
+            warning: None,
+        };
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert!(json.get("local_path").is_none());
+        assert!(json.get("seed_used").is_none());
+    }
+
     /// Test LroResult structure.
     #[test]
     fn test_lro_result() {
@@ -2195,6 +3208,8 @@ mod api_tests {
                 gcs_uri: Some("gs://bucket/output.mp4".to_string()),
                 mime_type: Some("video/mp4".to_string()),
             }],
+            operation_name: "op1".to_string(),
+            model: "veo-3.0-generate-preview".to_string(),
         };
 
         assert_eq!(result.videos.len(), 1);
@@ -2206,8 +3221,387 @@ mod api_tests {
     fn test_lro_result_empty() {
         let result = LroResult {
             videos: vec![],
+            operation_name: "op1".to_string(),
+            model: "veo-3.0-generate-preview".to_string(),
         };
 
         assert!(result.videos.is_empty());
     }
 }
+
+/// Output handling tests against a fake GCS server.
+#[cfg(test)]
+mod output_tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path, path_regex, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const TEST_TOKEN: &str = "test-token";
+
+    fn handler_for(server: &MockServer) -> VideoHandler {
+        let config = Config {
+            project_id: "test-project".to_string(),
+            ..Default::default()
+        };
+        let auth = Arc::new(AuthProvider::mock(TEST_TOKEN));
+        let gcs = GcsClient::with_base_url(Arc::clone(&auth), server.uri());
+        VideoHandler::with_deps(config, gcs, reqwest::Client::new(), auth)
+    }
+
+    fn lro_result() -> LroResult {
+        LroResult {
+            videos: vec![VideoOutput {
+                gcs_uri: Some("gs://bucket/videos/output.mp4".to_string()),
+                mime_type: Some("video/mp4".to_string()),
+            }],
+            operation_name: "op1".to_string(),
+            model: "veo-3.0-generate-preview".to_string(),
+        }
+    }
+
+    async fn mount_download(server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path_regex(r"/storage/v1/b/bucket/o/.*"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"video bytes".to_vec()))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_gcs_deletes_after_download() {
+        let server = MockServer::start().await;
+        mount_download(&server).await;
+        Mock::given(method("DELETE"))
+            .and(path_regex(r"/storage/v1/b/bucket/o/.*"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("output.mp4");
+        let local_str = local.to_string_lossy().to_string();
+        let options = OutputOptions {
+            prompt: "A cat walking",
+            output_gcs_uri: "gs://bucket/videos/output.mp4",
+            download_local: true,
+            local_path: Some(&local_str),
+            cleanup_gcs: true,
+            metadata: None,
+        };
+
+        let result = handler_for(&server)
+            .handle_output(lro_result(), &options, 8, None)
+            .await
+            .unwrap();
+
+        assert!(result.gcs_deleted);
+        assert!(result.warning.is_none());
+        assert_eq!(result.local_path.as_deref(), Some(local_str.as_str()));
+        assert_eq!(std::fs::read(&local).unwrap(), b"video bytes");
+    }
+
+    #[tokio::test]
+    async fn test_write_metadata_beside_gcs_and_local_outputs() {
+        let server = MockServer::start().await;
+        mount_download(&server).await;
+        Mock::given(method("POST"))
+            .and(path("/upload/storage/v1/b/bucket/o"))
+            .and(query_param("name", "videos/output.json"))
+            .and(header("Content-Type", "application/json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("clip.mp4");
+        let local_str = local.to_string_lossy().to_string();
+        let options = OutputOptions {
+            prompt: "A cat walking",
+            output_gcs_uri: "gs://bucket/videos/output.mp4",
+            download_local: true,
+            local_path: Some(&local_str),
+            cleanup_gcs: false,
+            metadata: Some(GenerationMetadata {
+                seed: Some(5),
+                aspect_ratio: Some("16:9".to_string()),
+                ..GenerationMetadata::new("A cat walking", "veo-3.0-generate-preview")
+            }),
+        };
+
+        handler_for(&server)
+            .handle_output(lro_result(), &options, 8, Some(5))
+            .await
+            .unwrap();
+
+        let local_sidecar: GenerationMetadata =
+            serde_json::from_slice(&std::fs::read(dir.path().join("clip.json")).unwrap()).unwrap();
+        assert_eq!(local_sidecar.prompt, "A cat walking");
+        assert_eq!(local_sidecar.model, "veo-3.0-generate-preview");
+        assert_eq!(local_sidecar.seed, Some(5));
+        assert_eq!(local_sidecar.aspect_ratio.as_deref(), Some("16:9"));
+        assert_eq!(local_sidecar.operation_name.as_deref(), Some("op1"));
+
+        let uploads = server.received_requests().await.unwrap();
+        let upload = uploads.iter().find(|r| r.method.as_str() == "POST").unwrap();
+        let gcs_sidecar: GenerationMetadata = serde_json::from_slice(&upload.body).unwrap();
+        assert_eq!(gcs_sidecar, local_sidecar);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_gcs_delete_failure_is_warning() {
+        let server = MockServer::start().await;
+        mount_download(&server).await;
+        Mock::given(method("DELETE"))
+            .and(path_regex(r"/storage/v1/b/bucket/o/.*"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("Access denied"))
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("output.mp4");
+        let local_str = local.to_string_lossy().to_string();
+        let options = OutputOptions {
+            prompt: "A cat walking",
+            output_gcs_uri: "gs://bucket/videos/output.mp4",
+            download_local: true,
+            local_path: Some(&local_str),
+            cleanup_gcs: true,
+            metadata: None,
+        };
+
+        let result = handler_for(&server)
+            .handle_output(lro_result(), &options, 8, None)
+            .await
+            .unwrap();
+
+        assert!(!result.gcs_deleted);
+        let warning = result.warning.expect("delete failure should be reported");
+        assert!(warning.contains("403"), "warning: {}", warning);
+        assert!(local.exists());
+    }
+
+    #[tokio::test]
+    async fn test_no_delete_without_cleanup_gcs() {
+        let server = MockServer::start().await;
+        mount_download(&server).await;
+        Mock::given(method("DELETE"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let local_str = dir.path().join("output.mp4").to_string_lossy().to_string();
+        let options = OutputOptions {
+            prompt: "A cat walking",
+            output_gcs_uri: "gs://bucket/videos/output.mp4",
+            download_local: true,
+            local_path: Some(&local_str),
+            cleanup_gcs: false,
+            metadata: None,
+        };
+
+        let result = handler_for(&server)
+            .handle_output(lro_result(), &options, 8, None)
+            .await
+            .unwrap();
+
+        assert!(!result.gcs_deleted);
+        assert!(result.warning.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_download_local_streams_large_video() {
+        let server = MockServer::start().await;
+        let video: Vec<u8> = (0..6 * 1024 * 1024).map(|i| (i % 253) as u8).collect();
+        Mock::given(method("GET"))
+            .and(path_regex(r"/storage/v1/b/bucket/o/.*"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(video.clone()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("nested").join("large.mp4");
+        let local_str = local.to_string_lossy().to_string();
+        let options = OutputOptions {
+            prompt: "A long pan across a city",
+            output_gcs_uri: "gs://bucket/videos/output.mp4",
+            download_local: true,
+            local_path: Some(&local_str),
+            cleanup_gcs: false,
+            metadata: None,
+        };
+
+        let result = handler_for(&server)
+            .handle_output(lro_result(), &options, 8, None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.local_path.as_deref(), Some(local_str.as_str()));
+        assert_eq!(std::fs::read(&local).unwrap(), video);
+    }
+
+    #[tokio::test]
+    async fn test_failed_download_removes_partial_file() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex(r"/storage/v1/b/bucket/o/.*"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Not found"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("output.mp4");
+        let local_str = local.to_string_lossy().to_string();
+        let options = OutputOptions {
+            prompt: "A cat walking",
+            output_gcs_uri: "gs://bucket/videos/output.mp4",
+            download_local: true,
+            local_path: Some(&local_str),
+            cleanup_gcs: false,
+            metadata: None,
+        };
+
+        let err = handler_for(&server)
+            .handle_output(lro_result(), &options, 8, None)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("404"), "{}", err);
+        assert!(err.to_string().contains("still available"), "{}", err);
+        assert!(!local.exists(), "partial download should be removed");
+    }
+
+    #[tokio::test]
+    async fn test_transient_download_failure_is_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path_regex(r"/storage/v1/b/bucket/o/.*"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("Backend busy"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        mount_download(&server).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let local = dir.path().join("output.mp4");
+        let local_str = local.to_string_lossy().to_string();
+        let options = OutputOptions {
+            prompt: "A cat walking",
+            output_gcs_uri: "gs://bucket/videos/output.mp4",
+            download_local: true,
+            local_path: Some(&local_str),
+            cleanup_gcs: false,
+            metadata: None,
+        };
+
+        let result = handler_for(&server)
+            .handle_output(lro_result(), &options, 8, None)
+            .await
+            .unwrap();
+
+        assert_eq!(result.local_path.as_deref(), Some(local_str.as_str()));
+        assert_eq!(std::fs::read(&local).unwrap(), b"video bytes");
+    }
+
+    #[tokio::test]
+    async fn test_download_without_local_path_uses_download_dir() {
+        let server = MockServer::start().await;
+        mount_download(&server).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut handler = handler_for(&server);
+        handler.config.download_dir = Some(dir.path().join("downloads").to_string_lossy().into_owned());
+        let options = OutputOptions {
+            prompt: "A cat walking",
+            output_gcs_uri: "gs://bucket/videos/output.mp4",
+            download_local: true,
+            local_path: None,
+            cleanup_gcs: false,
+            metadata: None,
+        };
+
+        let result = handler.handle_output(lro_result(), &options, 8, None).await.unwrap();
+
+        let expected = dir.path().join("downloads/output.mp4");
+        assert_eq!(result.local_path.as_deref(), Some(expected.to_string_lossy().as_ref()));
+        assert_eq!(std::fs::read(&expected).unwrap(), b"video bytes");
+
+        // Relative local paths are placed in the download directory too
+        let options = OutputOptions { local_path: Some("shots/one.mp4"), ..options };
+        let result = handler.handle_output(lro_result(), &options, 8, None).await.unwrap();
+        let expected = dir.path().join("downloads/shots/one.mp4");
+        assert_eq!(result.local_path.as_deref(), Some(expected.to_string_lossy().as_ref()));
+        assert!(expected.exists());
+    }
+
+    #[tokio::test]
+    async fn test_poll_lro_stops_at_deadline() {
+        let server = MockServer::start().await;
+        let handler = handler_for(&server).with_lro_timeout(Duration::ZERO);
+
+        let started = Instant::now();
+        let err = handler.poll_lro("op1", "veo-3.0-generate-preview", None).await.unwrap_err();
+
+        // The deadline is checked before the first wait, so nothing is polled
+        assert!(matches!(err, Error::Timeout(0)), "{}", err);
+        assert!(started.elapsed() < Duration::from_millis(LRO_INITIAL_DELAY_MS));
+    }
+
+    #[tokio::test]
+    async fn test_poll_request_is_cut_off_at_deadline() {
+        // A proxy that accepts connections and never answers, so polls hang
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = reqwest::Proxy::all(format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+
+        let server = MockServer::start().await;
+        let mut handler = handler_for(&server).with_lro_timeout(Duration::from_millis(200));
+        handler.http = reqwest::Client::builder().proxy(proxy).build().unwrap();
+
+        // The deadline falls while the first poll request is in flight
+        let started = Instant::now();
+        let err = handler.poll_lro("op1", "veo-3.0-generate-preview", None).await.unwrap_err();
+
+        assert!(matches!(err, Error::Timeout(0)), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(2), "{:?}", started.elapsed());
+    }
+
+    #[tokio::test]
+    async fn test_handle_output_registers_all_videos() {
+        let server = MockServer::start().await;
+        let handler = handler_for(&server);
+
+        let mut result = lro_result();
+        result.videos.push(VideoOutput {
+            gcs_uri: Some("gs://bucket/videos/output-1.mp4".to_string()),
+            mime_type: None,
+        });
+        let options = OutputOptions {
+            prompt: "A cat walking",
+            output_gcs_uri: "gs://bucket/videos/output.mp4",
+            download_local: false,
+            local_path: None,
+            cleanup_gcs: false,
+            metadata: None,
+        };
+
+        handler.handle_output(result, &options, 8, None).await.unwrap();
+
+        let entries = handler.registry.list();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].gcs_uri, "gs://bucket/videos/output.mp4");
+        assert_eq!(entries[1].gcs_uri, "gs://bucket/videos/output-1.mp4");
+        assert!(entries.iter().all(|e| e.prompt == "A cat walking" && e.model == "veo-3.0-generate-preview"));
+    }
+}
@@ -8,5 +8,8 @@ pub mod handler;
 pub mod resources;
 pub mod server;
 
-pub use handler::{VideoT2vParams, VideoI2vParams, VideoExtendParams, VideoGenerateResult, VideoHandler};
+pub use handler::{
+    BatchShotResult, VideoBatchParams, VideoBatchResult, VideoT2vParams, VideoI2vParams, VideoExtendParams,
+    VideoGenerateResult, VideoHandler,
+};
 pub use server::VideoServer;
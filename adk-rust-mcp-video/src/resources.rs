@@ -3,9 +3,19 @@
 //! This module provides resource implementations for:
 //! - `video://models` - List available video generation models
 //! - `video://providers` - List available video providers
+//! - `genmedia://video/<uuid>` - Videos generated during the current session
 
-use adk_rust_mcp_common::models::VEO_MODELS;
+use adk_rust_mcp_common::models::{OutputDimensions, VEO_MODELS};
 use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// URI prefix for videos generated during the session.
+pub const SESSION_VIDEO_URI_PREFIX: &str = "genmedia://video/";
+
+/// Default number of generated videos kept in the session registry.
+pub const DEFAULT_SESSION_CAPACITY: usize = 100;
 
 /// Information about an available video generation model.
 #[derive(Debug, Clone, Serialize)]
@@ -16,10 +26,14 @@ pub struct ModelInfo {
     pub aliases: Vec<&'static str>,
     /// Supported aspect ratios
     pub supported_aspect_ratios: Vec<&'static str>,
+    /// Output width and height in pixels for each supported aspect ratio
+    pub output_dimensions: Vec<OutputDimensions>,
     /// Supported durations in seconds
     pub supported_durations: Vec<u8>,
     /// Whether the model supports audio generation
     pub supports_audio: bool,
+    /// Whether the model supports camera motion controls
+    pub supports_camera_control: bool,
 }
 
 /// Information about an available video provider.
@@ -35,6 +49,101 @@ pub struct ProviderInfo {
     pub is_default: bool,
 }
 
+/// A video generated during the current session.
+#[derive(Debug, Clone, Serialize)]
+pub struct GeneratedVideo {
+    /// Resource URI (`genmedia://video/<uuid>`)
+    pub uri: String,
+    /// GCS URI of the generated video
+    pub gcs_uri: String,
+    /// Local file path if the video was downloaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_path: Option<String>,
+    /// MIME type of the video
+    pub mime_type: String,
+    /// Prompt used for generation
+    pub prompt: String,
+    /// Canonical ID of the model used
+    pub model: String,
+    /// Creation time as seconds since the Unix epoch
+    pub created_at: u64,
+}
+
+/// In-memory registry of videos generated during the session.
+///
+/// Only the most recent `capacity` entries are kept.
+#[derive(Debug)]
+pub struct SessionRegistry {
+    entries: Mutex<VecDeque<GeneratedVideo>>,
+    capacity: usize,
+}
+
+impl Default for SessionRegistry {
+    fn default() -> Self {
+        Self::new(DEFAULT_SESSION_CAPACITY)
+    }
+}
+
+impl SessionRegistry {
+    /// Create an empty registry holding at most `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    /// Register a generated video and return its entry.
+    pub fn register(
+        &self,
+        gcs_uri: String,
+        local_path: Option<String>,
+        mime_type: String,
+        prompt: &str,
+        model: &str,
+    ) -> GeneratedVideo {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let entry = GeneratedVideo {
+            uri: format!("{}{}", SESSION_VIDEO_URI_PREFIX, uuid::Uuid::new_v4()),
+            gcs_uri,
+            local_path,
+            mime_type,
+            prompt: prompt.to_string(),
+            model: model.to_string(),
+            created_at,
+        };
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        while entries.len() >= self.capacity.max(1) {
+            entries.pop_front();
+        }
+        entries.push_back(entry.clone());
+        entry
+    }
+
+    /// List registered videos, oldest first.
+    pub fn list(&self) -> Vec<GeneratedVideo> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.iter().cloned().collect()
+    }
+
+    /// Look up a registered video by its resource URI.
+    pub fn get(&self, uri: &str) -> Option<GeneratedVideo> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.iter().find(|e| e.uri == uri).cloned()
+    }
+
+    /// Get a registered video as a JSON string.
+    pub fn resource_json(&self, uri: &str) -> Option<String> {
+        self.get(uri)
+            .and_then(|entry| serde_json::to_string_pretty(&entry).ok())
+    }
+}
+
 /// List all available video generation models.
 pub fn list_models() -> Vec<ModelInfo> {
     VEO_MODELS
@@ -43,8 +152,10 @@ pub fn list_models() -> Vec<ModelInfo> {
             id: m.id,
             aliases: m.aliases.to_vec(),
             supported_aspect_ratios: m.supported_aspect_ratios.to_vec(),
+            output_dimensions: m.output_dimensions.to_vec(),
             supported_durations: m.supported_durations.to_vec(),
             supports_audio: m.supports_audio,
+            supports_camera_control: m.supports_camera_control,
         })
         .collect()
 }
@@ -108,6 +219,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_list_models_has_output_dimensions() {
+        for model in list_models() {
+            let ratios: Vec<&str> = model.output_dimensions.iter().map(|d| d.aspect_ratio).collect();
+            assert_eq!(ratios, model.supported_aspect_ratios, "{}", model.id);
+            let portrait = model.output_dimensions.iter().find(|d| d.aspect_ratio == "9:16").unwrap();
+            assert_eq!((portrait.width, portrait.height), (720, 1280));
+        }
+    }
+
     #[test]
     fn test_veo3_supports_audio() {
         let models = list_models();
@@ -148,4 +269,55 @@ mod tests {
         assert!(json.starts_with('['));
         assert!(json.contains("google-veo"));
     }
+
+    fn register(registry: &SessionRegistry, n: usize) -> GeneratedVideo {
+        registry.register(
+            format!("gs://bucket/video-{}.mp4", n),
+            None,
+            "video/mp4".to_string(),
+            "A cat walking",
+            "veo-3.0-generate-preview",
+        )
+    }
+
+    #[test]
+    fn test_session_registry_register_and_get() {
+        let registry = SessionRegistry::default();
+        let entry = register(&registry, 1);
+
+        assert!(entry.uri.starts_with(SESSION_VIDEO_URI_PREFIX));
+        assert!(entry.created_at > 0);
+
+        let found = registry.get(&entry.uri).expect("entry should be registered");
+        assert_eq!(found.gcs_uri, "gs://bucket/video-1.mp4");
+        assert_eq!(found.prompt, "A cat walking");
+        assert!(registry.get("genmedia://video/unknown").is_none());
+    }
+
+    #[test]
+    fn test_session_registry_is_capped() {
+        let registry = SessionRegistry::new(3);
+        let first = register(&registry, 0);
+        for n in 1..5 {
+            register(&registry, n);
+        }
+
+        let entries = registry.list();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].gcs_uri, "gs://bucket/video-2.mp4");
+        assert_eq!(entries[2].gcs_uri, "gs://bucket/video-4.mp4");
+        assert!(registry.get(&first.uri).is_none(), "oldest entry should be evicted");
+    }
+
+    #[test]
+    fn test_session_registry_resource_json() {
+        let registry = SessionRegistry::default();
+        let entry = register(&registry, 1);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&registry.resource_json(&entry.uri).unwrap()).unwrap();
+        assert_eq!(json["uri"], entry.uri.as_str());
+        assert_eq!(json["model"], "veo-3.0-generate-preview");
+        assert!(json.get("local_path").is_none());
+    }
 }
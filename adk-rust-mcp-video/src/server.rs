@@ -4,12 +4,18 @@
 //! - `video_generate` tool for text-to-video generation
 //! - `video_from_image` tool for image-to-video generation
 //! - `video_extend` tool for video extension
-//! - Resources for models and providers
+//! - `video_generate_batch` tool for generating several shots concurrently
+//! - `video_list_models` tool for discovering model capabilities
+//! - Resources for models, providers, and videos generated in the session
 
-use crate::handler::{VideoT2vParams, VideoI2vParams, VideoExtendParams, VideoGenerateResult, VideoHandler};
-use crate::resources;
+use crate::handler::{
+    VideoBatchParams, VideoBatchResult, VideoT2vParams, VideoI2vParams, VideoExtendParams,
+    VideoGenerateResult, VideoHandler,
+};
+use crate::resources::{self, SessionRegistry, SESSION_VIDEO_URI_PREFIX};
 use adk_rust_mcp_common::config::Config;
-use adk_rust_mcp_common::error::Error;
+use adk_rust_mcp_common::error::{Error, ErrorCode};
+use adk_rust_mcp_common::idempotency::IdempotencyStore;
 use rmcp::{
     model::{
         CallToolResult, Content, ListResourcesResult, ReadResourceResult,
@@ -31,6 +37,10 @@ pub struct VideoServer {
     handler: Arc<RwLock<Option<VideoHandler>>>,
     /// Server configuration
     config: Config,
+    /// Results recorded by idempotency key
+    idempotency: Arc<IdempotencyStore>,
+    /// Videos generated during this session (exposed as resources)
+    registry: Arc<SessionRegistry>,
 }
 
 /// Tool parameters wrapper for video_generate (text-to-video).
@@ -47,7 +57,7 @@ pub struct VideoGenerateToolParams {
     /// Duration in seconds (5-8)
     #[serde(default)]
     pub duration_seconds: Option<u8>,
-    /// GCS URI for output (required)
+    /// GCS URI for output (required; a bare object name uses GCS_BUCKET)
     pub output_gcs_uri: String,
     /// Whether to download locally after generation
     #[serde(default)]
@@ -55,12 +65,27 @@ pub struct VideoGenerateToolParams {
     /// Local path for download
     #[serde(default)]
     pub local_path: Option<String>,
+    /// Delete the GCS copy after downloading locally (requires download_local)
+    #[serde(default)]
+    pub cleanup_gcs: Option<bool>,
     /// Whether to generate audio (Veo 3.x only)
     #[serde(default)]
     pub generate_audio: Option<bool>,
     /// Random seed for reproducibility
     #[serde(default)]
     pub seed: Option<i64>,
+    /// Camera motion (static, pan_left, pan_right, tilt_up, tilt_down, zoom_in, zoom_out, dolly_in, dolly_out)
+    #[serde(default)]
+    pub camera_motion: Option<String>,
+    /// Vertex AI region for this request, e.g. us-central1 (default: LOCATION)
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Write a <name>.json sidecar with prompt, model, seed and operation name next to the video
+    #[serde(default)]
+    pub write_metadata: Option<bool>,
+    /// Key making retries of this call return the first result instead of generating again (results expire after IDEMPOTENCY_TTL_SECS, default 1 hour)
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 impl From<VideoGenerateToolParams> for VideoT2vParams {
@@ -73,8 +98,12 @@ impl From<VideoGenerateToolParams> for VideoT2vParams {
             output_gcs_uri: params.output_gcs_uri,
             download_local: params.download_local.unwrap_or(false),
             local_path: params.local_path,
+            cleanup_gcs: params.cleanup_gcs.unwrap_or(false),
             generate_audio: params.generate_audio,
             seed: params.seed,
+            camera_motion: params.camera_motion,
+            location: params.location,
+            write_metadata: params.write_metadata.unwrap_or(false),
         }
     }
 }
@@ -82,11 +111,11 @@ impl From<VideoGenerateToolParams> for VideoT2vParams {
 /// Tool parameters wrapper for video_from_image (image-to-video).
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct VideoFromImageToolParams {
-    /// Source image (base64 data, local path, or GCS URI)
+    /// Source image (base64 data, data URI, local path, GCS URI, or HTTPS URL)
     pub image: String,
     /// Text prompt describing the desired video motion
     pub prompt: String,
-    /// Last frame image for interpolation mode (base64 data, local path, or GCS URI).
+    /// Last frame image for interpolation mode (base64 data, data URI, local path, GCS URI, or HTTPS URL).
     /// If provided, generates a video interpolating between `image` and `last_frame_image`.
     #[serde(default)]
     pub last_frame_image: Option<String>,
@@ -99,7 +128,7 @@ pub struct VideoFromImageToolParams {
     /// Duration in seconds (5-8)
     #[serde(default)]
     pub duration_seconds: Option<u8>,
-    /// GCS URI for output (required)
+    /// GCS URI for output (required; a bare object name uses GCS_BUCKET)
     pub output_gcs_uri: String,
     /// Whether to download locally after generation
     #[serde(default)]
@@ -107,9 +136,24 @@ pub struct VideoFromImageToolParams {
     /// Local path for download
     #[serde(default)]
     pub local_path: Option<String>,
+    /// Delete the GCS copy after downloading locally (requires download_local)
+    #[serde(default)]
+    pub cleanup_gcs: Option<bool>,
     /// Random seed for reproducibility
     #[serde(default)]
     pub seed: Option<i64>,
+    /// Camera motion (static, pan_left, pan_right, tilt_up, tilt_down, zoom_in, zoom_out, dolly_in, dolly_out)
+    #[serde(default)]
+    pub camera_motion: Option<String>,
+    /// Vertex AI region for this request, e.g. us-central1 (default: LOCATION)
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Write a <name>.json sidecar with prompt, model, seed and operation name next to the video
+    #[serde(default)]
+    pub write_metadata: Option<bool>,
+    /// Key making retries of this call return the first result instead of generating again (results expire after IDEMPOTENCY_TTL_SECS, default 1 hour)
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 impl From<VideoFromImageToolParams> for VideoI2vParams {
@@ -124,7 +168,11 @@ impl From<VideoFromImageToolParams> for VideoI2vParams {
             output_gcs_uri: params.output_gcs_uri,
             download_local: params.download_local.unwrap_or(false),
             local_path: params.local_path,
+            cleanup_gcs: params.cleanup_gcs.unwrap_or(false),
             seed: params.seed,
+            camera_motion: params.camera_motion,
+            location: params.location,
+            write_metadata: params.write_metadata.unwrap_or(false),
         }
     }
 }
@@ -142,7 +190,7 @@ pub struct VideoExtendToolParams {
     /// Duration in seconds (5-8)
     #[serde(default)]
     pub duration_seconds: Option<u8>,
-    /// GCS URI for output (required)
+    /// GCS URI for output (required; a bare object name uses GCS_BUCKET)
     pub output_gcs_uri: String,
     /// Whether to download locally after generation
     #[serde(default)]
@@ -150,9 +198,21 @@ pub struct VideoExtendToolParams {
     /// Local path for download
     #[serde(default)]
     pub local_path: Option<String>,
+    /// Delete the GCS copy after downloading locally (requires download_local)
+    #[serde(default)]
+    pub cleanup_gcs: Option<bool>,
     /// Random seed for reproducibility
     #[serde(default)]
     pub seed: Option<i64>,
+    /// Vertex AI region for this request, e.g. us-central1 (default: LOCATION)
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Write a <name>.json sidecar with prompt, model, seed and operation name next to the video
+    #[serde(default)]
+    pub write_metadata: Option<bool>,
+    /// Key making retries of this call return the first result instead of generating again (results expire after IDEMPOTENCY_TTL_SECS, default 1 hour)
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 impl From<VideoExtendToolParams> for VideoExtendParams {
@@ -165,7 +225,32 @@ impl From<VideoExtendToolParams> for VideoExtendParams {
             output_gcs_uri: params.output_gcs_uri,
             download_local: params.download_local.unwrap_or(false),
             local_path: params.local_path,
+            cleanup_gcs: params.cleanup_gcs.unwrap_or(false),
             seed: params.seed,
+            location: params.location,
+            write_metadata: params.write_metadata.unwrap_or(false),
+        }
+    }
+}
+
+/// Tool parameters wrapper for video_generate_batch.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct VideoBatchToolParams {
+    /// Shots to generate (1-10), each taking the same fields as video_generate; output_gcs_uri values must differ
+    pub shots: Vec<VideoT2vParams>,
+    /// Number of shots generated at the same time, 1-4 (default: 3)
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
+    /// Key making retries of this call return the first result instead of generating again (results expire after IDEMPOTENCY_TTL_SECS, default 1 hour)
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+}
+
+impl From<VideoBatchToolParams> for VideoBatchParams {
+    fn from(params: VideoBatchToolParams) -> Self {
+        Self {
+            shots: params.shots,
+            max_concurrency: params.max_concurrency.unwrap_or(crate::handler::DEFAULT_BATCH_CONCURRENCY),
         }
     }
 }
@@ -175,7 +260,9 @@ impl VideoServer {
     pub fn new(config: Config) -> Self {
         Self {
             handler: Arc::new(RwLock::new(None)),
+            idempotency: Arc::new(IdempotencyStore::from_config(&config)),
             config,
+            registry: Arc::new(SessionRegistry::default()),
         }
     }
 
@@ -183,7 +270,9 @@ impl VideoServer {
     async fn ensure_handler(&self) -> Result<(), Error> {
         let mut handler = self.handler.write().await;
         if handler.is_none() {
-            *handler = Some(VideoHandler::new(self.config.clone()).await?);
+            let new_handler = VideoHandler::new(self.config.clone()).await?
+                .with_registry(Arc::clone(&self.registry));
+            *handler = Some(new_handler);
         }
         Ok(())
     }
@@ -194,7 +283,7 @@ impl VideoServer {
 
         // Ensure handler is initialized
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -204,12 +293,11 @@ impl VideoServer {
 
         let gen_params: VideoT2vParams = params.into();
         let result = handler.generate_video_t2v(gen_params).await.map_err(|e| {
-            McpError::internal_error(format!("Video generation failed: {}", e), None)
+            e.to_mcp_error("Video generation failed")
         })?;
 
         // Convert result to MCP content
-        let content = self.format_result(&result);
-        Ok(CallToolResult::success(content))
+        Ok(self.format_result(&result))
     }
 
     /// Generate video from an image.
@@ -218,7 +306,7 @@ impl VideoServer {
 
         // Ensure handler is initialized
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -228,12 +316,11 @@ impl VideoServer {
 
         let gen_params: VideoI2vParams = params.into();
         let result = handler.generate_video_i2v(gen_params).await.map_err(|e| {
-            McpError::internal_error(format!("Video generation failed: {}", e), None)
+            e.to_mcp_error("Video generation failed")
         })?;
 
         // Convert result to MCP content
-        let content = self.format_result(&result);
-        Ok(CallToolResult::success(content))
+        Ok(self.format_result(&result))
     }
 
     /// Extend an existing video.
@@ -242,7 +329,7 @@ impl VideoServer {
 
         // Ensure handler is initialized
         self.ensure_handler().await.map_err(|e| {
-            McpError::internal_error(format!("Failed to initialize handler: {}", e), None)
+            e.to_mcp_error("Failed to initialize handler")
         })?;
 
         let handler_guard = self.handler.read().await;
@@ -252,31 +339,113 @@ impl VideoServer {
 
         let extend_params: VideoExtendParams = params.into();
         let result = handler.extend_video(extend_params).await.map_err(|e| {
-            McpError::internal_error(format!("Video extension failed: {}", e), None)
+            e.to_mcp_error("Video extension failed")
         })?;
 
         // Convert result to MCP content
-        let content = self.format_result(&result);
-        Ok(CallToolResult::success(content))
+        Ok(self.format_result(&result))
     }
 
-    /// Format the video generation result as MCP content.
-    fn format_result(&self, result: &VideoGenerateResult) -> Vec<Content> {
-        let mut message = format!("Video generated: {}", result.gcs_uri);
-        if let Some(local_path) = &result.local_path {
-            message.push_str(&format!("\nDownloaded to: {}", local_path));
+    /// Generate several text-to-video shots concurrently.
+    pub async fn generate_video_batch(&self, params: VideoBatchToolParams) -> Result<CallToolResult, McpError> {
+        info!(shots = params.shots.len(), "Generating video batch");
+
+        // Ensure handler is initialized
+        self.ensure_handler().await.map_err(|e| {
+            e.to_mcp_error("Failed to initialize handler")
+        })?;
+
+        let handler_guard = self.handler.read().await;
+        let handler = handler_guard.as_ref().ok_or_else(|| {
+            McpError::internal_error("Handler not initialized", None)
+        })?;
+
+        let batch_params: VideoBatchParams = params.into();
+        let result = handler.generate_video_batch(batch_params).await.map_err(|e| {
+            e.to_mcp_error("Batch video generation failed")
+        })?;
+
+        Ok(self.format_batch_result(&result))
+    }
+
+    /// Format a batch result with one line per shot.
+    ///
+    /// The call succeeds as long as the batch ran; failed shots are listed
+    /// with their errors and counted in the structured output.
+    fn format_batch_result(&self, result: &VideoBatchResult) -> CallToolResult {
+        let mut message = format!(
+            "Generated {} of {} videos",
+            result.succeeded,
+            result.shots.len()
+        );
+        for shot in &result.shots {
+            match (&shot.video, &shot.error) {
+                (Some(video), _) => {
+                    let location = video.local_path.as_deref().unwrap_or(&video.gcs_uri);
+                    message.push_str(&format!("\n[{}] {}", shot.index, location));
+                    if let Some(warning) = &video.warning {
+                        message.push_str(&format!(" (warning: {})", warning));
+                    }
+                }
+                (None, error) => message.push_str(&format!(
+                    "\n[{}] Failed: {}",
+                    shot.index,
+                    error.as_deref().unwrap_or("unknown error")
+                )),
+            }
+        }
+
+        let mut tool_result = CallToolResult::success(vec![Content::text(message)]);
+        tool_result.structured_content = serde_json::to_value(result).ok();
+        tool_result
+    }
+
+    /// Format the video generation result as MCP content with structured output.
+    fn format_result(&self, result: &VideoGenerateResult) -> CallToolResult {
+        let mut message = match (&result.local_path, result.gcs_deleted) {
+            (Some(local_path), true) => format!(
+                "Video generated: {}\nGCS copy removed after download",
+                local_path
+            ),
+            (Some(local_path), false) => format!(
+                "Video generated: {}\nDownloaded to: {}",
+                result.gcs_uri, local_path
+            ),
+            (None, _) => format!("Video generated: {}", result.gcs_uri),
+        };
+        if let Some(warning) = &result.warning {
+            message.push_str(&format!("\nWarning: {}", warning));
         }
-        vec![Content::text(message)]
+
+        let mut tool_result = CallToolResult::success(vec![Content::text(message)]);
+        tool_result.structured_content = serde_json::to_value(result).ok();
+        tool_result
+    }
+
+    /// List available Veo models with their aliases and capabilities.
+    pub fn list_models(&self) -> Result<CallToolResult, McpError> {
+        let models = resources::list_models();
+        let models_json = serde_json::to_string_pretty(&models).map_err(|e| {
+            McpError::internal_error(format!("Failed to serialize models: {}", e), None)
+        })?;
+
+        let mut tool_result = CallToolResult::success(vec![Content::text(models_json)]);
+        tool_result.structured_content = Some(serde_json::json!({ "models": models }));
+        Ok(tool_result)
     }
 }
 
+// Method signatures mirror the rmcp trait, which returns `impl Future`.
+#[allow(clippy::manual_async_fn)]
 impl ServerHandler for VideoServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             instructions: Some(
                 "Video generation server using Google Vertex AI Veo API. \
                  Use video_generate for text-to-video, video_from_image for image-to-video, \
-                 and video_extend to extend existing videos."
+                 video_extend to extend existing videos, video_generate_batch to generate \
+                 several shots concurrently, and video_list_models to see \
+                 each model's supported aspect ratios, durations, and audio support."
                     .to_string(),
             ),
             capabilities: ServerCapabilities::builder()
@@ -320,6 +489,31 @@ impl ServerHandler for VideoServer {
                 _ => Arc::new(serde_json::Map::new()),
             };
 
+            // video_generate_batch tool
+            let batch_schema = schema_for!(VideoBatchToolParams);
+            let batch_schema_value = serde_json::to_value(&batch_schema).unwrap_or_default();
+            let batch_input_schema = match batch_schema_value {
+                serde_json::Value::Object(map) => Arc::new(map),
+                _ => Arc::new(serde_json::Map::new()),
+            };
+            let batch_output_schema = match serde_json::to_value(schema_for!(VideoBatchResult)).unwrap_or_default() {
+                serde_json::Value::Object(map) => Some(Arc::new(map)),
+                _ => None,
+            };
+
+            // video_list_models tool (no parameters - must have type: "object")
+            let mut empty_schema_map = serde_json::Map::new();
+            empty_schema_map.insert("type".to_string(), serde_json::Value::String("object".to_string()));
+            let empty_schema = Arc::new(empty_schema_map);
+
+            // Shared output schema for all video tools
+            let output_schema = schema_for!(VideoGenerateResult);
+            let output_schema_value = serde_json::to_value(&output_schema).unwrap_or_default();
+            let output_schema = match output_schema_value {
+                serde_json::Value::Object(map) => Some(Arc::new(map)),
+                _ => None,
+            };
+
             Ok(ListToolsResult {
                 tools: vec![
                     Tool {
@@ -332,14 +526,14 @@ impl ServerHandler for VideoServer {
                         annotations: None,
                         icons: None,
                         meta: None,
-                        output_schema: None,
+                        output_schema: output_schema.clone(),
                         title: None,
                     },
                     Tool {
                         name: Cow::Borrowed("video_from_image"),
                         description: Some(Cow::Borrowed(
                             "Generate video from an image using Google's Veo API. \
-                             Accepts base64 image data, local file path, or GCS URI as input. \
+                             Accepts base64 image data, data URI, local file path, GCS URI, or HTTPS URL as input. \
                              Supports interpolation mode: provide both `image` (first frame) and \
                              `last_frame_image` (last frame) to generate a video interpolating between them. \
                              Requires a GCS URI for output. Returns the GCS URI of the generated video."
@@ -348,7 +542,7 @@ impl ServerHandler for VideoServer {
                         annotations: None,
                         icons: None,
                         meta: None,
-                        output_schema: None,
+                        output_schema: output_schema.clone(),
                         title: None,
                     },
                     Tool {
@@ -363,6 +557,34 @@ impl ServerHandler for VideoServer {
                         annotations: None,
                         icons: None,
                         meta: None,
+                        output_schema: output_schema.clone(),
+                        title: None,
+                    },
+                    Tool {
+                        name: Cow::Borrowed("video_generate_batch"),
+                        description: Some(Cow::Borrowed(
+                            "Generate up to 10 text-to-video shots with Google's Veo API, running up to \
+                             max_concurrency shots at the same time. Each shot takes the same fields as \
+                             video_generate and needs its own output GCS URI. A failed shot does not stop \
+                             the others; each shot's video or error is returned in request order."
+                        )),
+                        input_schema: batch_input_schema,
+                        annotations: None,
+                        icons: None,
+                        meta: None,
+                        output_schema: batch_output_schema,
+                        title: None,
+                    },
+                    Tool {
+                        name: Cow::Borrowed("video_list_models"),
+                        description: Some(Cow::Borrowed(
+                            "List available Veo models with their IDs, aliases, supported aspect \
+                             ratios and durations, and audio and camera control support."
+                        )),
+                        input_schema: empty_schema,
+                        annotations: None,
+                        icons: None,
+                        meta: None,
                         output_schema: None,
                         title: None,
                     },
@@ -381,35 +603,58 @@ impl ServerHandler for VideoServer {
         async move {
             match params.name.as_ref() {
                 "video_generate" => {
+                    let arguments = params.arguments.clone().unwrap_or_default();
                     let tool_params: VideoGenerateToolParams = params
                         .arguments
                         .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
                         .transpose()
-                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), None))?
-                        .ok_or_else(|| McpError::invalid_params("Missing parameters", None))?;
+                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data())))?
+                        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))?;
 
-                    self.generate_video(tool_params).await
+                    self.idempotency
+                        .run("video_generate", &arguments, self.generate_video(tool_params))
+                        .await
                 }
                 "video_from_image" => {
+                    let arguments = params.arguments.clone().unwrap_or_default();
                     let tool_params: VideoFromImageToolParams = params
                         .arguments
                         .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
                         .transpose()
-                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), None))?
-                        .ok_or_else(|| McpError::invalid_params("Missing parameters", None))?;
+                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data())))?
+                        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))?;
 
-                    self.generate_video_from_image(tool_params).await
+                    self.idempotency
+                        .run("video_from_image", &arguments, self.generate_video_from_image(tool_params))
+                        .await
                 }
                 "video_extend" => {
+                    let arguments = params.arguments.clone().unwrap_or_default();
                     let tool_params: VideoExtendToolParams = params
                         .arguments
                         .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
                         .transpose()
-                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), None))?
-                        .ok_or_else(|| McpError::invalid_params("Missing parameters", None))?;
+                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data())))?
+                        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))?;
+
+                    self.idempotency
+                        .run("video_extend", &arguments, self.extend_video(tool_params))
+                        .await
+                }
+                "video_generate_batch" => {
+                    let arguments = params.arguments.clone().unwrap_or_default();
+                    let tool_params: VideoBatchToolParams = params
+                        .arguments
+                        .map(|args| serde_json::from_value(serde_json::Value::Object(args)))
+                        .transpose()
+                        .map_err(|e| McpError::invalid_params(format!("Invalid parameters: {}", e), Some(ErrorCode::Validation.to_data())))?
+                        .ok_or_else(|| McpError::invalid_params("Missing parameters", Some(ErrorCode::Validation.to_data())))?;
 
-                    self.extend_video(tool_params).await
+                    self.idempotency
+                        .run("video_generate_batch", &arguments, self.generate_video_batch(tool_params))
+                        .await
                 }
+                "video_list_models" => self.list_models(),
                 _ => Err(McpError::invalid_params(format!("Unknown tool: {}", params.name), None)),
             }
         }
@@ -451,8 +696,25 @@ impl ServerHandler for VideoServer {
                 annotations: None,
             };
 
+            let mut resources = vec![models_resource, providers_resource];
+            resources.extend(self.registry.list().into_iter().map(|video| {
+                rmcp::model::Resource {
+                    raw: rmcp::model::RawResource {
+                        uri: video.uri,
+                        name: format!("Generated video: {}", video.gcs_uri),
+                        title: None,
+                        description: Some(video.prompt),
+                        mime_type: Some("application/json".to_string()),
+                        size: None,
+                        icons: None,
+                        meta: None,
+                    },
+                    annotations: None,
+                }
+            }));
+
             Ok(ListResourcesResult {
-                resources: vec![models_resource, providers_resource],
+                resources,
                 next_cursor: None,
                 meta: None,
             })
@@ -471,6 +733,14 @@ impl ServerHandler for VideoServer {
             let content = match uri.as_str() {
                 "video://models" => resources::models_resource_json(),
                 "video://providers" => resources::providers_resource_json(),
+                session_uri if session_uri.starts_with(SESSION_VIDEO_URI_PREFIX) => {
+                    self.registry.resource_json(session_uri).ok_or_else(|| {
+                        McpError::resource_not_found(
+                            format!("Unknown or expired video resource: {}", uri),
+                            None,
+                        )
+                    })?
+                }
                 _ => {
                     return Err(McpError::resource_not_found(
                         format!("Unknown resource: {}", uri),
@@ -493,9 +763,7 @@ mod tests {
     fn test_config() -> Config {
         Config {
             project_id: "test-project".to_string(),
-            location: "us-central1".to_string(),
-            gcs_bucket: None,
-            port: 8080,
+            ..Default::default()
         }
     }
 
@@ -516,8 +784,13 @@ mod tests {
             output_gcs_uri: "gs://bucket/output.mp4".to_string(),
             download_local: Some(true),
             local_path: Some("/tmp/output.mp4".to_string()),
+            cleanup_gcs: None,
             generate_audio: Some(true),
             seed: Some(42),
+            camera_motion: None,
+            location: None,
+            idempotency_key: None,
+            write_metadata: None,
         };
 
         let gen_params: VideoT2vParams = tool_params.into();
@@ -542,8 +815,13 @@ mod tests {
             output_gcs_uri: "gs://bucket/output.mp4".to_string(),
             download_local: None,
             local_path: None,
+            cleanup_gcs: None,
             generate_audio: None,
             seed: None,
+            camera_motion: None,
+            location: None,
+            idempotency_key: None,
+            write_metadata: None,
         };
 
         let gen_params: VideoT2vParams = tool_params.into();
@@ -565,7 +843,12 @@ mod tests {
             output_gcs_uri: "gs://bucket/output.mp4".to_string(),
             download_local: Some(true),
             local_path: Some("/tmp/output.mp4".to_string()),
+            cleanup_gcs: None,
             seed: Some(42),
+            camera_motion: None,
+            location: None,
+            idempotency_key: None,
+            write_metadata: None,
         };
 
         let gen_params: VideoI2vParams = tool_params.into();
@@ -588,7 +871,12 @@ mod tests {
             output_gcs_uri: "gs://bucket/output.mp4".to_string(),
             download_local: None,
             local_path: None,
+            cleanup_gcs: None,
             seed: None,
+            camera_motion: None,
+            location: None,
+            idempotency_key: None,
+            write_metadata: None,
         };
 
         let gen_params: VideoI2vParams = tool_params.into();
@@ -597,4 +885,133 @@ mod tests {
         assert_eq!(gen_params.duration_seconds, crate::handler::DEFAULT_DURATION_SECONDS);
         assert!(!gen_params.download_local);
     }
+
+    #[test]
+    fn test_format_result_includes_structured_content() {
+        let server = VideoServer::new(test_config());
+        let result = VideoGenerateResult {
+            gcs_uri: "gs://bucket/output.mp4".to_string(),
+            local_path: Some("./output.mp4".to_string()),
+            mime_type: "video/mp4".to_string(),
+            model: "veo-3.0-generate-preview".to_string(),
+            duration_seconds: 8,
+            seed_used: Some(7),
+            operation_name: "op1".to_string(),
+            gcs_deleted: false,
+            warning: None,
+        };
+
+        let tool_result = server.format_result(&result);
+        let structured = tool_result.structured_content.expect("structured content");
+        assert_eq!(structured["gcs_uri"], "gs://bucket/output.mp4");
+        assert_eq!(structured["model"], "veo-3.0-generate-preview");
+        assert_eq!(structured["seed_used"], 7);
+        assert_eq!(structured["operation_name"], "op1");
+        assert_eq!(tool_result.is_error, Some(false));
+
+        let text = tool_result.content[0].as_text().expect("text content");
+        assert!(text.text.contains("Downloaded to: ./output.mp4"));
+    }
+
+    #[test]
+    fn test_format_result_local_only() {
+        let server = VideoServer::new(test_config());
+        let result = VideoGenerateResult {
+            gcs_uri: "gs://bucket/output.mp4".to_string(),
+            local_path: Some("./output.mp4".to_string()),
+            mime_type: "video/mp4".to_string(),
+            model: "veo-3.0-generate-preview".to_string(),
+            duration_seconds: 8,
+            seed_used: None,
+            operation_name: "op1".to_string(),
+            gcs_deleted: true,
+            warning: None,
+        };
+
+        let tool_result = server.format_result(&result);
+        let text = tool_result.content[0].as_text().expect("text content");
+        assert!(text.text.contains("./output.mp4"));
+        assert!(!text.text.contains("gs://bucket/output.mp4"));
+        assert_eq!(tool_result.structured_content.unwrap()["gcs_deleted"], true);
+    }
+
+    #[test]
+    fn test_batch_tool_params_defaults() {
+        let tool_params: VideoBatchToolParams = serde_json::from_value(serde_json::json!({
+            "shots": [{"prompt": "Opening shot", "output_gcs_uri": "shots/1.mp4"}]
+        }))
+        .unwrap();
+
+        let batch_params: VideoBatchParams = tool_params.into();
+        assert_eq!(batch_params.max_concurrency, crate::handler::DEFAULT_BATCH_CONCURRENCY);
+        assert_eq!(batch_params.shots[0].model, crate::handler::DEFAULT_MODEL);
+        assert_eq!(batch_params.shots[0].duration_seconds, crate::handler::DEFAULT_DURATION_SECONDS);
+    }
+
+    #[test]
+    fn test_format_batch_result_lists_failed_shots() {
+        use crate::handler::BatchShotResult;
+
+        let server = VideoServer::new(test_config());
+        let result = VideoBatchResult {
+            shots: vec![
+                BatchShotResult {
+                    index: 0,
+                    prompt: "Opening shot".to_string(),
+                    video: Some(VideoGenerateResult {
+                        gcs_uri: "gs://bucket/shot-1.mp4".to_string(),
+                        local_path: None,
+                        mime_type: "video/mp4".to_string(),
+                        model: "veo-3.0-generate-preview".to_string(),
+                        duration_seconds: 8,
+                        seed_used: None,
+                        operation_name: "op1".to_string(),
+                        gcs_deleted: false,
+                        warning: None,
+                    }),
+                    error: None,
+                },
+                BatchShotResult {
+                    index: 1,
+                    prompt: "Closing shot".to_string(),
+                    video: None,
+                    error: Some("Video generation failed: blocked by safety filter".to_string()),
+                },
+            ],
+            succeeded: 1,
+            failed: 1,
+        };
+
+        let tool_result = server.format_batch_result(&result);
+        assert_eq!(tool_result.is_error, Some(false));
+
+        let text = tool_result.content[0].as_text().expect("text content");
+        assert!(text.text.starts_with("Generated 1 of 2 videos"));
+        assert!(text.text.contains("[0] gs://bucket/shot-1.mp4"));
+        assert!(text.text.contains("[1] Failed: Video generation failed: blocked by safety filter"));
+
+        let structured = tool_result.structured_content.expect("structured content");
+        assert_eq!(structured["failed"], 1);
+        assert_eq!(structured["shots"][0]["video"]["operation_name"], "op1");
+        assert!(structured["shots"][1].get("video").is_none());
+    }
+
+    #[test]
+    fn test_list_models_returns_capabilities() {
+        let server = VideoServer::new(test_config());
+        let tool_result = server.list_models().unwrap();
+
+        let structured = tool_result.structured_content.expect("structured content");
+        let models = structured["models"].as_array().expect("models array");
+        let veo3 = models
+            .iter()
+            .find(|m| m["id"] == "veo-3.0-generate-preview")
+            .expect("veo 3 listed");
+        assert!(!veo3["aliases"].as_array().unwrap().is_empty());
+        assert_eq!(veo3["supports_audio"], true);
+        assert!(veo3["supported_durations"].is_array());
+
+        let text = tool_result.content[0].as_text().expect("text content");
+        assert!(text.text.contains("veo-2.0-generate-001"));
+    }
 }
@@ -60,7 +60,7 @@ fn get_test_config() -> Option<Config> {
         project_id,
         location: env::var("LOCATION").unwrap_or_else(|_| "us-central1".to_string()),
         gcs_bucket: env::var("GCS_BUCKET").ok(),
-        port: 8080,
+        ..Default::default()
     })
 }
 
@@ -121,8 +121,12 @@ async fn test_validation_errors() {
         output_gcs_uri: "gs://bucket/output.mp4".to_string(),
         download_local: false,
         local_path: None,
+        cleanup_gcs: false,
         generate_audio: None,
         seed: None,
+        camera_motion: None,
+        location: None,
+        write_metadata: false,
     };
     
     let result = handler.generate_video_t2v(params).await;
@@ -160,8 +164,12 @@ mod veo_api_tests {
             output_gcs_uri: output_uri.clone(),
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             generate_audio: None,
             seed: Some(42),
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
         
         eprintln!("Starting video generation (this may take 2-5 minutes)...");
@@ -202,8 +210,12 @@ mod veo_api_tests {
             output_gcs_uri: output_uri.clone(),
             download_local: true,
             local_path: Some(local_path.to_string_lossy().to_string()),
+            cleanup_gcs: false,
             generate_audio: None,
             seed: Some(123),
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
         
         eprintln!("Starting video generation with local download (this may take 2-5 minutes)...");
@@ -252,8 +264,12 @@ mod veo_api_tests {
             output_gcs_uri: output_uri.clone(),
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             generate_audio: Some(true), // Enable audio generation
             seed: Some(456),
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
         
         eprintln!("Starting video generation with audio (this may take 2-5 minutes)...");
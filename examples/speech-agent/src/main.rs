@@ -93,6 +93,8 @@ async fn main() -> Result<()> {
              Speech parameters:\n\
              - speaking_rate: 0.25 (slow) to 4.0 (fast), default 1.0\n\
              - pitch: -20.0 to +20.0 semitones, default 0.0\n\
+             - volume_gain_db: -96.0 to +16.0 dB, default 0.0\n\
+             - effects_profile_id: device profiles such as headphone-class-device or telephony-class-application\n\
              - voice: Chirp3-HD voices available\n\n\
              You can also use custom pronunciations with IPA or X-SAMPA phonetic alphabets.\n\n\
              When users ask you to 'say' something, use speech_synthesize.\n\
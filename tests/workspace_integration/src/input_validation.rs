@@ -22,8 +22,20 @@ mod tests {
             aspect_ratio: "1:1".to_string(),
             number_of_images: 10, // Invalid: max is 4
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = params.validate();
@@ -49,8 +61,20 @@ mod tests {
             aspect_ratio: "2:1".to_string(), // Invalid
             number_of_images: 1,
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = params.validate();
@@ -76,8 +100,20 @@ mod tests {
             aspect_ratio: "1:1".to_string(),
             number_of_images: 1,
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = params.validate();
@@ -105,8 +141,12 @@ mod tests {
             output_gcs_uri: "gs://bucket/video.mp4".to_string(),
             download_local: false,
             local_path: None,
+            cleanup_gcs: false,
             generate_audio: None,
             seed: None,
+            camera_motion: None,
+            location: None,
+            write_metadata: false,
         };
 
         let result = params.validate();
@@ -123,7 +163,7 @@ mod tests {
     /// **Validates: Requirements 3.9**
     #[test]
     fn test_music_params_validation_rejects_invalid() {
-        use adk_rust_mcp_music::MusicGenerateParams;
+        use adk_rust_mcp_music::{MusicGenerateParams, OutputFormat};
 
         // Test with invalid sample_count (out of range)
         let params = MusicGenerateParams {
@@ -131,8 +171,16 @@ mod tests {
             negative_prompt: None,
             seed: None,
             sample_count: 10, // Invalid: max is 4
+            duration_seconds: None,
+            bpm: None,
+            key: None,
             output_file: None,
             output_gcs_uri: None,
+            upload: false,
+            no_cache: false,
+            location: None,
+            write_metadata: false,
+            output_format: OutputFormat::Wav,
         };
 
         let result = params.validate();
@@ -160,6 +208,10 @@ mod tests {
             pitch: 0.0,
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
         let result = params.validate();
@@ -187,6 +239,10 @@ mod tests {
             pitch: 50.0, // Invalid: max is 20.0
             pronunciations: None,
             output_file: None,
+            output_gcs_uri: None,
+            lexicon_file: None,
+            volume_gain_db: None,
+            effects_profile_id: None,
         };
 
         let result = params.validate();
@@ -212,8 +268,20 @@ mod tests {
             aspect_ratio: "16:9".to_string(),
             number_of_images: 2,
             seed: Some(42),
+            add_watermark: Some(false),
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = params.validate();
@@ -233,8 +301,20 @@ mod tests {
             aspect_ratio: "2:1".to_string(), // Invalid: not supported
             number_of_images: 10, // Invalid: out of range
             seed: None,
+            add_watermark: None,
             output_file: None,
             output_uri: None,
+            no_cache: false,
+            safety_filter_level: None,
+            person_generation: None,
+            output_mime_type: None,
+            compression_quality: None,
+            enhance_prompt: None,
+            location: None,
+            write_metadata: false,
+            style_preset: None,
+            guidance_scale: None,
+            language: None,
         };
 
         let result = params.validate();
@@ -320,8 +400,20 @@ mod property_tests {
                 aspect_ratio: "1:1".to_string(),
                 number_of_images: 1,
                 seed: None,
+                add_watermark: None,
                 output_file: None,
                 output_uri: None,
+                no_cache: false,
+                safety_filter_level: None,
+                person_generation: None,
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
             };
 
             let result = params.validate();
@@ -340,8 +432,20 @@ mod property_tests {
                 aspect_ratio: "1:1".to_string(),
                 number_of_images: 1,
                 seed: None,
+                add_watermark: None,
                 output_file: None,
                 output_uri: None,
+                no_cache: false,
+                safety_filter_level: None,
+                person_generation: None,
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
             };
 
             let result = params.validate();
@@ -366,8 +470,20 @@ mod property_tests {
                 aspect_ratio: "1:1".to_string(),
                 number_of_images: num,
                 seed: None,
+                add_watermark: None,
                 output_file: None,
                 output_uri: None,
+                no_cache: false,
+                safety_filter_level: None,
+                person_generation: None,
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
             };
 
             let result = params.validate();
@@ -386,8 +502,20 @@ mod property_tests {
                 aspect_ratio: "1:1".to_string(),
                 number_of_images: num,
                 seed: None,
+                add_watermark: None,
                 output_file: None,
                 output_uri: None,
+                no_cache: false,
+                safety_filter_level: None,
+                person_generation: None,
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
             };
 
             let result = params.validate();
@@ -412,8 +540,20 @@ mod property_tests {
                 aspect_ratio: ratio.clone(),
                 number_of_images: 1,
                 seed: None,
+                add_watermark: None,
                 output_file: None,
                 output_uri: None,
+                no_cache: false,
+                safety_filter_level: None,
+                person_generation: None,
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
             };
 
             let result = params.validate();
@@ -432,8 +572,20 @@ mod property_tests {
                 aspect_ratio: ratio.clone(),
                 number_of_images: 1,
                 seed: None,
+                add_watermark: None,
                 output_file: None,
                 output_uri: None,
+                no_cache: false,
+                safety_filter_level: None,
+                person_generation: None,
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
             };
 
             let result = params.validate();
@@ -462,8 +614,20 @@ mod property_tests {
                 aspect_ratio: ratio,
                 number_of_images: num,
                 seed: None,
+                add_watermark: None,
                 output_file: None,
                 output_uri: None,
+                no_cache: false,
+                safety_filter_level: None,
+                person_generation: None,
+                output_mime_type: None,
+                compression_quality: None,
+                enhance_prompt: None,
+                location: None,
+                write_metadata: false,
+                style_preset: None,
+                guidance_scale: None,
+                language: None,
             };
 
             let result = params.validate();
@@ -487,6 +651,10 @@ mod property_tests {
                 pitch: 0.0,
                 pronunciations: None,
                 output_file: None,
+                output_gcs_uri: None,
+                lexicon_file: None,
+                volume_gain_db: None,
+                effects_profile_id: None,
             };
             assert!(params.validate().is_ok(), "speaking_rate {} should be valid", rate);
         }
@@ -501,6 +669,10 @@ mod property_tests {
                 pitch: 0.0,
                 pronunciations: None,
                 output_file: None,
+                output_gcs_uri: None,
+                lexicon_file: None,
+                volume_gain_db: None,
+                effects_profile_id: None,
             };
             let result = params.validate();
             assert!(result.is_err(), "speaking_rate {} should be invalid", rate);
@@ -525,6 +697,10 @@ mod property_tests {
                 pitch,
                 pronunciations: None,
                 output_file: None,
+                output_gcs_uri: None,
+                lexicon_file: None,
+                volume_gain_db: None,
+                effects_profile_id: None,
             };
             assert!(params.validate().is_ok(), "pitch {} should be valid", pitch);
         }
@@ -539,6 +715,10 @@ mod property_tests {
                 pitch,
                 pronunciations: None,
                 output_file: None,
+                output_gcs_uri: None,
+                lexicon_file: None,
+                volume_gain_db: None,
+                effects_profile_id: None,
             };
             let result = params.validate();
             assert!(result.is_err(), "pitch {} should be invalid", pitch);
@@ -551,7 +731,7 @@ mod property_tests {
     /// **Validates: Requirements 3.9**
     #[test]
     fn music_sample_count_validation() {
-        use adk_rust_mcp_music::MusicGenerateParams;
+        use adk_rust_mcp_music::{MusicGenerateParams, OutputFormat};
 
         // Valid sample_count (1-4)
         for count in 1u8..=4u8 {
@@ -560,8 +740,16 @@ mod property_tests {
                 negative_prompt: None,
                 seed: None,
                 sample_count: count,
+                duration_seconds: None,
+                bpm: None,
+                key: None,
                 output_file: None,
                 output_gcs_uri: None,
+                upload: false,
+                no_cache: false,
+                location: None,
+                write_metadata: false,
+                output_format: OutputFormat::Wav,
             };
             assert!(params.validate().is_ok(), "sample_count {} should be valid", count);
         }
@@ -573,8 +761,16 @@ mod property_tests {
                 negative_prompt: None,
                 seed: None,
                 sample_count: count,
+                duration_seconds: None,
+                bpm: None,
+                key: None,
                 output_file: None,
                 output_gcs_uri: None,
+                upload: false,
+                no_cache: false,
+                location: None,
+                write_metadata: false,
+                output_format: OutputFormat::Wav,
             };
             let result = params.validate();
             assert!(result.is_err(), "sample_count {} should be invalid", count);
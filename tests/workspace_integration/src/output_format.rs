@@ -6,9 +6,11 @@
 //!
 //! **Validates: Requirements 3.11**
 
+#[cfg(test)]
 use rmcp::model::{CallToolResult, Content, RawContent};
 
 /// Validates that a CallToolResult has valid content format.
+#[cfg(test)]
 fn validate_tool_result(result: &CallToolResult) -> Result<(), String> {
     // Result should have content
     if result.content.is_empty() && !result.is_error.unwrap_or(false) {
@@ -24,6 +26,7 @@ fn validate_tool_result(result: &CallToolResult) -> Result<(), String> {
 }
 
 /// Validates that a Content item has valid structure.
+#[cfg(test)]
 fn validate_content(content: &Content) -> Result<(), String> {
     match &content.raw {
         RawContent::Text(text_content) => {
@@ -81,6 +84,80 @@ fn validate_content(content: &Content) -> Result<(), String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::Value;
+
+    /// Validates a value against a JSON schema as generated by schemars.
+    ///
+    /// Covers the subset schemars emits for output types: `type` (single or a
+    /// list including "null"), `required`, `properties`, `items`, and local
+    /// `$ref`s into `definitions`.
+    fn validate_against_schema(value: &Value, schema: &Value, root: &Value, path: &str) -> Result<(), String> {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = reference
+                .strip_prefix("#/definitions/")
+                .ok_or_else(|| format!("{}: unsupported $ref {}", path, reference))?;
+            let target = root
+                .get("definitions")
+                .and_then(|d| d.get(name))
+                .ok_or_else(|| format!("{}: missing definition {}", path, name))?;
+            return validate_against_schema(value, target, root, path);
+        }
+
+        if let Some(types) = schema.get("type") {
+            let allowed: Vec<&str> = match types {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+                _ => return Err(format!("{}: invalid type keyword", path)),
+            };
+            let actual = match value {
+                Value::Null => "null",
+                Value::Bool(_) => "boolean",
+                Value::Number(n) if n.is_u64() || n.is_i64() => "integer",
+                Value::Number(_) => "number",
+                Value::String(_) => "string",
+                Value::Array(_) => "array",
+                Value::Object(_) => "object",
+            };
+            let matches = allowed.contains(&actual) || (actual == "integer" && allowed.contains(&"number"));
+            if !matches {
+                return Err(format!("{}: expected {:?}, got {}", path, allowed, actual));
+            }
+        }
+
+        if let (Some(required), Value::Object(obj)) = (schema.get("required").and_then(Value::as_array), value) {
+            for field in required.iter().filter_map(Value::as_str) {
+                if !obj.contains_key(field) {
+                    return Err(format!("{}: missing required field '{}'", path, field));
+                }
+            }
+        }
+
+        if let (Some(properties), Value::Object(obj)) = (schema.get("properties").and_then(Value::as_object), value) {
+            for (key, field_value) in obj {
+                let field_schema = properties
+                    .get(key)
+                    .ok_or_else(|| format!("{}: unexpected field '{}'", path, key))?;
+                validate_against_schema(field_value, field_schema, root, &format!("{}.{}", path, key))?;
+            }
+        }
+
+        if let (Some(items), Value::Array(elements)) = (schema.get("items"), value) {
+            for (i, element) in elements.iter().enumerate() {
+                validate_against_schema(element, items, root, &format!("{}[{}]", path, i))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates a tool result's structured content against the tool's output schema.
+    fn validate_structured_output(result: &CallToolResult, schema: &Value) -> Result<(), String> {
+        let structured = result
+            .structured_content
+            .as_ref()
+            .ok_or_else(|| "Result should have structured content".to_string())?;
+        validate_against_schema(structured, schema, schema, "$")
+    }
 
     /// Test that Content::text helper creates valid content.
     #[test]
@@ -89,6 +166,146 @@ mod tests {
         assert!(validate_content(&content).is_ok());
     }
 
+    fn image_output(images: Vec<adk_rust_mcp_image::ImageArtifact>) -> adk_rust_mcp_image::ImageGenerateOutput {
+        adk_rust_mcp_image::ImageGenerateOutput {
+            images,
+            model: "imagen-4.0-generate-preview-06-06".to_string(),
+            prompt: "A red circle".to_string(),
+            failures: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    fn artifact(data: Option<&str>, local_path: Option<&str>, gcs_uri: Option<&str>) -> adk_rust_mcp_image::ImageArtifact {
+        adk_rust_mcp_image::ImageArtifact {
+            data: data.map(str::to_string),
+            local_path: local_path.map(str::to_string),
+            gcs_uri: gcs_uri.map(str::to_string),
+            mime_type: "image/png".to_string(),
+            width: Some(1024),
+            height: Some(1024),
+            size_bytes: Some(2048),
+            enhanced_prompt: None,
+        }
+    }
+
+    /// Test that image tool results match the published output schema in every output mode.
+    #[test]
+    fn test_image_output_matches_published_schema() {
+        use adk_rust_mcp_image::ImageServer;
+
+        let schema = Value::Object((*ImageServer::generate_output_schema()).clone());
+        let outputs = [
+            image_output(vec![artifact(Some("aW1hZ2U="), None, None), artifact(Some("aW1hZ2U="), None, None)]),
+            image_output(vec![artifact(None, Some("/tmp/circle.png"), None)]),
+            image_output(vec![artifact(None, None, Some("gs://bucket/circle.png"))]),
+        ];
+
+        for output in outputs {
+            let result = ImageServer::generate_output_result(output, "Images").unwrap();
+            assert!(validate_tool_result(&result).is_ok());
+            if let Err(e) = validate_structured_output(&result, &schema) {
+                panic!("Structured output does not match schema: {}", e);
+            }
+
+            let images = result.structured_content.as_ref().unwrap()["images"].as_array().unwrap();
+            for image in images {
+                let set = ["data", "local_path", "gcs_uri"]
+                    .iter()
+                    .filter(|field| image.get(**field).is_some())
+                    .count();
+                assert_eq!(set, 1, "Exactly one of data/local_path/gcs_uri should be set: {}", image);
+            }
+        }
+    }
+
+    /// Test that the schema check rejects outputs that drift from the published schema.
+    #[test]
+    fn test_image_output_schema_rejects_mismatches() {
+        let schema = Value::Object((*adk_rust_mcp_image::ImageServer::generate_output_schema()).clone());
+
+        let missing_model = serde_json::json!({ "images": [], "prompt": "A cat" });
+        assert!(validate_against_schema(&missing_model, &schema, &schema, "$").is_err());
+
+        let bad_width = serde_json::json!({
+            "images": [{ "data": "aW1n", "mime_type": "image/png", "width": "wide" }],
+            "model": "imagen-3.0-generate-002",
+            "prompt": "A cat"
+        });
+        assert!(validate_against_schema(&bad_width, &schema, &schema, "$").is_err());
+
+        let unknown_field = serde_json::json!({
+            "images": [{ "location": "gs://bucket/a.png", "mime_type": "image/png" }],
+            "model": "imagen-3.0-generate-002",
+            "prompt": "A cat"
+        });
+        assert!(validate_against_schema(&unknown_field, &schema, &schema, "$").is_err());
+    }
+
+    /// Test that image tool results list written files as text and inline data as images.
+    #[test]
+    fn test_image_output_content_by_mode() {
+        use adk_rust_mcp_image::ImageServer;
+
+        let inline = ImageServer::generate_output_result(
+            image_output(vec![artifact(Some("aW1hZ2U="), None, None)]),
+            "Images",
+        )
+        .unwrap();
+        assert!(matches!(&inline.content[0].raw, RawContent::Image(img) if img.mime_type == "image/png"));
+
+        let uploaded = ImageServer::generate_output_result(
+            image_output(vec![artifact(None, None, Some("gs://bucket/a.png")), artifact(None, None, Some("gs://bucket/b.png"))]),
+            "Edited images",
+        )
+        .unwrap();
+        assert_eq!(uploaded.content.len(), 1);
+        assert!(matches!(
+            &uploaded.content[0].raw,
+            RawContent::Text(t) if t.text == "Edited images uploaded to: gs://bucket/a.png, gs://bucket/b.png"
+        ));
+    }
+
+    /// Test that images that could not be output are reported alongside the written ones.
+    #[test]
+    fn test_image_output_reports_partial_failures() {
+        use adk_rust_mcp_image::{ImageFailure, ImageServer};
+
+        let schema = Value::Object((*ImageServer::generate_output_schema()).clone());
+        let mut output = image_output(vec![artifact(None, None, Some("gs://bucket/a_0.png"))]);
+        output.failures = vec![ImageFailure {
+            index: 1,
+            error: "Validation error: Invalid base64 data".to_string(),
+        }];
+
+        let result = ImageServer::generate_output_result(output, "Images").unwrap();
+        assert!(validate_structured_output(&result, &schema).is_ok());
+        assert_eq!(result.structured_content.as_ref().unwrap()["failures"][0]["index"], 1);
+        assert_eq!(result.content.len(), 2);
+        assert!(matches!(
+            &result.content[1].raw,
+            RawContent::Text(t) if t.text == "Image 1 could not be output: Validation error: Invalid base64 data"
+        ));
+    }
+
+    /// Test that advisory warnings are reported as text without failing the call.
+    #[test]
+    fn test_image_output_reports_warnings() {
+        use adk_rust_mcp_image::ImageServer;
+
+        let schema = Value::Object((*ImageServer::generate_output_schema()).clone());
+        let mut output = image_output(vec![artifact(None, None, Some("gs://bucket/a_0.png"))]);
+        output.warnings = vec!["The prompt appears to be in a language (ru) that imagen-4 does not support".to_string()];
+
+        let result = ImageServer::generate_output_result(output, "Images").unwrap();
+        assert!(validate_structured_output(&result, &schema).is_ok());
+        assert_eq!(result.is_error, Some(false));
+        assert!(matches!(
+            &result.content[1].raw,
+            RawContent::Text(t) if t.text.starts_with("Warning: The prompt appears to be in a language (ru)")
+        ));
+    }
+
     /// Test that Content::text with empty string fails validation.
     #[test]
     fn test_content_text_empty_fails() {
@@ -201,16 +418,6 @@ mod property_tests {
         ]
     }
 
-    /// Strategy to generate valid audio MIME types
-    fn valid_audio_mime_strategy() -> impl Strategy<Value = String> {
-        prop_oneof![
-            Just("audio/wav".to_string()),
-            Just("audio/mp3".to_string()),
-            Just("audio/mpeg".to_string()),
-            Just("audio/ogg".to_string()),
-        ]
-    }
-
     /// Strategy to generate invalid MIME types for images
     fn invalid_image_mime_strategy() -> impl Strategy<Value = String> {
         prop_oneof![
@@ -3,16 +3,15 @@
 //! Tests that each MCP server can be instantiated and provides correct server info.
 //! These tests verify Requirements 3.7, 3.8 - tool and resource registration.
 
+#[cfg(test)]
 use adk_rust_mcp_common::Config;
-use rmcp::ServerHandler;
 
 /// Test configuration for integration tests.
+#[cfg(test)]
 fn test_config() -> Config {
     Config {
         project_id: "test-project".to_string(),
-        location: "us-central1".to_string(),
-        gcs_bucket: None,
-        port: 8080,
+        ..Default::default()
     }
 }
 
@@ -25,6 +24,7 @@ mod tests {
     use adk_rust_mcp_music::MusicServer;
     use adk_rust_mcp_speech::SpeechServer;
     use adk_rust_mcp_video::VideoServer;
+    use rmcp::ServerHandler;
 
     /// Test that ImageServer can be created and provides server info.
     /// **Validates: Requirements 3.7, 3.8**